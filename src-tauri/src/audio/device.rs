@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 pub struct AudioDeviceInfo {
     pub name: String,
     pub is_default: bool,
+    /// Total channel count of the device - what the engine opens it with
+    /// (None if it couldn't be queried)
+    pub channels: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +19,20 @@ pub struct AudioConfig {
     pub buffer_size: u32,
 }
 
+/// Buffer sizes (in frames) an output device accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportedBufferSizes {
+    /// Smallest supported buffer size (None if the backend doesn't report a range)
+    pub min: Option<u32>,
+    /// Largest supported buffer size (None if the backend doesn't report a range)
+    pub max: Option<u32>,
+    /// Common power-of-two sizes within the supported range, for a picker
+    pub options: Vec<u32>,
+}
+
+/// Common buffer sizes offered in the UI (filtered by what the device supports)
+const COMMON_BUFFER_SIZES: [u32; 8] = [32, 64, 128, 256, 512, 1024, 2048, 4096];
+
 impl Default for AudioConfig {
     fn default() -> Self {
         Self {
@@ -42,6 +59,7 @@ pub fn list_output_devices() -> Result<Vec<AudioDeviceInfo>, String> {
             result.push(AudioDeviceInfo {
                 is_default: Some(&name) == default_name.as_ref(),
                 name,
+                channels: output_channel_count(&device),
             });
         }
     }
@@ -49,6 +67,11 @@ pub fn list_output_devices() -> Result<Vec<AudioDeviceInfo>, String> {
     Ok(result)
 }
 
+/// Total number of output channels on a device (from its default config)
+pub fn output_channel_count(device: &cpal::Device) -> Option<u16> {
+    device.default_output_config().ok().map(|config| config.channels())
+}
+
 /// Get the default output device
 pub fn get_default_output_device() -> Result<cpal::Device, String> {
     let host = cpal::default_host();
@@ -88,11 +111,69 @@ pub fn get_default_sample_rate() -> Result<u32, String> {
     Ok(config.sample_rate().0)
 }
 
-/// Get supported config for a device
+/// Get the buffer sizes supported by an output device (default device if name is None)
+pub fn get_supported_buffer_sizes(device_name: Option<&str>) -> Result<SupportedBufferSizes, String> {
+    let device = get_output_device(device_name)?;
+    let supported_configs = device
+        .supported_output_configs()
+        .map_err(|e| format!("Failed to get supported configs: {}", e))?;
+
+    // Union of the ranges across all supported configs
+    let mut range: Option<(u32, u32)> = None;
+    for config in supported_configs {
+        if let cpal::SupportedBufferSize::Range { min, max } = *config.buffer_size() {
+            range = Some(match range {
+                Some((lo, hi)) => (lo.min(min), hi.max(max)),
+                None => (min, max),
+            });
+        }
+    }
+
+    let options = COMMON_BUFFER_SIZES
+        .iter()
+        .copied()
+        .filter(|size| match range {
+            Some((min, max)) => *size >= min && *size <= max,
+            None => true,
+        })
+        .collect();
+
+    Ok(SupportedBufferSizes {
+        min: range.map(|(min, _)| min),
+        max: range.map(|(_, max)| max),
+        options,
+    })
+}
+
+/// Check that a buffer size is usable with an output device before building a stream
+pub fn validate_buffer_size(device_name: Option<&str>, buffer_size: u32) -> Result<(), String> {
+    if buffer_size == 0 {
+        return Err("Buffer size must be greater than 0".to_string());
+    }
+
+    let supported = get_supported_buffer_sizes(device_name)?;
+    if let (Some(min), Some(max)) = (supported.min, supported.max) {
+        if buffer_size < min || buffer_size > max {
+            return Err(format!(
+                "Buffer size {} is not supported by this device (supported: {}-{} frames)",
+                buffer_size, min, max
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Get supported config for a device. The stream is opened with all of the device's
+/// channels so the stereo output can be routed to any pair on multi-output interfaces.
 pub fn get_supported_config(
     device: &cpal::Device,
     preferred: &AudioConfig,
 ) -> Result<cpal::StreamConfig, String> {
+    let device_channels = output_channel_count(device)
+        .unwrap_or(preferred.channels)
+        .max(preferred.channels);
+
     let supported_configs = device
         .supported_output_configs()
         .map_err(|e| format!("Failed to get supported configs: {}", e))?;
@@ -107,7 +188,7 @@ pub fn get_supported_config(
             && config.channels() >= preferred.channels
         {
             return Ok(cpal::StreamConfig {
-                channels: preferred.channels,
+                channels: device_channels.min(config.channels()),
                 sample_rate: cpal::SampleRate(preferred.sample_rate),
                 buffer_size: cpal::BufferSize::Fixed(preferred.buffer_size),
             });
@@ -120,7 +201,7 @@ pub fn get_supported_config(
         .map_err(|e| format!("Failed to get default config: {}", e))?;
 
     Ok(cpal::StreamConfig {
-        channels: default_config.channels().min(2),
+        channels: default_config.channels(),
         sample_rate: default_config.sample_rate(),
         buffer_size: cpal::BufferSize::Default,
     })
@@ -146,6 +227,7 @@ pub fn list_input_devices() -> Result<Vec<AudioDeviceInfo>, String> {
             result.push(AudioDeviceInfo {
                 is_default: Some(&name) == default_name.as_ref(),
                 name,
+                channels: device.default_input_config().ok().map(|config| config.channels()),
             });
         }
     }
@@ -194,14 +276,15 @@ pub fn get_input_device_sample_rate(device_name: Option<&str>) -> Result<u32, St
 }
 
 /// Get input config using the device's native sample rate
-/// This avoids CoreAudio conflicts by never forcing a non-native sample rate
+/// This avoids CoreAudio conflicts by never forcing a non-native sample rate.
+/// All of the device's channels are opened; the capture picks which ones feed L/R.
 pub fn get_native_input_config(device: &cpal::Device) -> Result<cpal::StreamConfig, String> {
     let default_config = device
         .default_input_config()
         .map_err(|e| format!("Failed to get default input config: {}", e))?;
 
     Ok(cpal::StreamConfig {
-        channels: default_config.channels().min(2),
+        channels: default_config.channels(),
         sample_rate: default_config.sample_rate(),
         buffer_size: cpal::BufferSize::Default,
     })
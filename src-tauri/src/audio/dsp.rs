@@ -0,0 +1,263 @@
+//! Small output-stage processors for the monitor chain
+//!
+//! These run on the audio thread after the plugin, so they must not allocate.
+
+use serde::{Deserialize, Serialize};
+
+/// Pole radius of the DC blocker at 44.1kHz (cutoff around 35Hz)
+const DC_BLOCK_R_44K: f32 = 0.995;
+
+/// First-order DC blocking high-pass: y[n] = x[n] - x[n-1] + R * y[n-1]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DcBlocker {
+    /// Previous input sample
+    pub x1: f32,
+    /// Previous output sample
+    pub y1: f32,
+}
+
+impl DcBlocker {
+    /// Pole radius for a sample rate, keeping the cutoff frequency constant
+    pub fn coefficient(sample_rate: u32) -> f32 {
+        DC_BLOCK_R_44K.powf(44100.0 / sample_rate.max(1) as f32)
+    }
+
+    /// Filter one sample
+    #[inline]
+    pub fn process(&mut self, x: f32, r: f32) -> f32 {
+        let y = x - self.x1 + r * self.y1;
+        self.x1 = x;
+        // Flush denormals so the feedback path doesn't get slow on silence
+        self.y1 = if y.abs() < 1e-20 { 0.0 } else { y };
+        self.y1
+    }
+
+    /// Clear the filter history
+    pub fn reset(&mut self) {
+        self.x1 = 0.0;
+        self.y1 = 0.0;
+    }
+}
+
+/// Longest delay a DelayLine created with the default size can apply (frames)
+pub const MAX_DELAY_FRAMES: usize = 1 << 16;
+
+/// Stereo delay line for lining the dry signal up with a plugin's latency
+///
+/// The buffer is allocated once up front; processing never allocates.
+pub struct DelayLine {
+    buffer: Vec<[f32; 2]>,
+    write_pos: usize,
+}
+
+impl DelayLine {
+    pub fn new(max_frames: usize) -> Self {
+        Self {
+            buffer: vec![[0.0; 2]; max_frames.max(1) + 1],
+            write_pos: 0,
+        }
+    }
+
+    /// Write one frame and return the frame written `delay` frames ago
+    /// (delays beyond the buffer length are clamped)
+    #[inline]
+    pub fn process(&mut self, frame: [f32; 2], delay: usize) -> [f32; 2] {
+        let len = self.buffer.len();
+        self.buffer[self.write_pos] = frame;
+        let delay = delay.min(len - 1);
+        let delayed = self.buffer[(self.write_pos + len - delay) % len];
+        self.write_pos = (self.write_pos + 1) % len;
+        delayed
+    }
+
+    /// Clear the buffered audio
+    pub fn reset(&mut self) {
+        self.buffer.fill([0.0; 2]);
+        self.write_pos = 0;
+    }
+}
+
+/// Curve the safety limiter uses to keep output under its ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimiterMode {
+    /// Clamp at the ceiling (default, most protective)
+    #[default]
+    Hard,
+    /// tanh saturation that approaches the ceiling smoothly
+    Soft,
+}
+
+impl LimiterMode {
+    /// Limit one (finite) sample to +/- ceiling
+    #[inline]
+    pub fn apply(self, x: f32, ceiling: f32) -> f32 {
+        match self {
+            LimiterMode::Hard => x.clamp(-ceiling, ceiling),
+            LimiterMode::Soft => ceiling * (x / ceiling).tanh(),
+        }
+    }
+}
+
+/// Fast xorshift32 RNG for noise generation on the audio thread
+#[derive(Debug, Clone, Copy)]
+pub struct XorShift32 {
+    state: u32,
+}
+
+impl XorShift32 {
+    pub fn new(seed: u32) -> Self {
+        // Zero is a fixed point of xorshift
+        Self { state: if seed == 0 { 0x9E37_79B9 } else { seed } }
+    }
+
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Uniform value in [0, 1)
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+/// TPDF dither + quantizer with optional first-order noise shaping
+///
+/// Quantizes to `bits` of resolution (full scale = ±1.0). Shaping feeds the previous
+/// quantization error back so the noise is pushed toward high frequencies.
+#[derive(Debug, Clone, Copy)]
+pub struct Dither {
+    rng: XorShift32,
+    /// Previous quantization error per channel (for noise shaping)
+    error: [f32; 2],
+}
+
+impl Dither {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            rng: XorShift32::new(seed),
+            error: [0.0; 2],
+        }
+    }
+
+    /// Dither and quantize one sample on a channel (0 = left, 1 = right)
+    #[inline]
+    pub fn process(&mut self, x: f32, channel: usize, bits: u8, shaped: bool) -> f32 {
+        let levels = (1u32 << (bits.clamp(2, 24) - 1)) as f32;
+        let step = 1.0 / levels;
+        let channel = channel.min(1);
+
+        let target = if shaped { x - self.error[channel] } else { x };
+        // TPDF: sum of two uniform values, ±1 LSB peak
+        let noise = (self.rng.next_f32() - self.rng.next_f32()) * step;
+        let quantized = ((target + noise) * levels).round() / levels;
+        self.error[channel] = quantized - target;
+        quantized
+    }
+
+    /// Clear the noise shaping history
+    pub fn reset(&mut self) {
+        self.error = [0.0; 2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dc_blocker_removes_offset() {
+        let r = DcBlocker::coefficient(48000);
+        let mut blocker = DcBlocker::default();
+
+        // 0.5 DC offset plus a 1kHz tone
+        let input: Vec<f32> = (0..48000)
+            .map(|i| 0.5 + 0.25 * (i as f32 * 2.0 * std::f32::consts::PI * 1000.0 / 48000.0).sin())
+            .collect();
+        let output: Vec<f32> = input.iter().map(|&x| blocker.process(x, r)).collect();
+
+        // Offset is passed through at first...
+        assert!(output[0] > 0.4);
+
+        // ...then decays: the mean of the last 100ms is ~0
+        let tail = &output[output.len() - 4800..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 0.001, "residual DC {}", mean);
+
+        // The tone itself survives
+        let peak = tail.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak > 0.2);
+    }
+
+    #[test]
+    fn test_delay_line_alignment() {
+        let mut delay = DelayLine::new(8);
+        let outputs: Vec<f32> = (1..=6).map(|i| delay.process([i as f32, -(i as f32)], 3)[0]).collect();
+        assert_eq!(outputs, vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0]);
+
+        // Zero delay passes straight through, oversized delays clamp to the buffer
+        assert_eq!(delay.process([7.0, -7.0], 0), [7.0, -7.0]);
+        delay.reset();
+        assert_eq!(delay.process([1.0, 1.0], 100), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_soft_limiter_stays_under_ceiling() {
+        let ceiling = 10.0f32.powf(3.0 / 20.0); // +3 dB
+        let mut previous = f32::NEG_INFINITY;
+        for i in -400..=400 {
+            let x = i as f32 / 50.0; // -8.0 to 8.0
+            let y = LimiterMode::Soft.apply(x, ceiling);
+            assert!(y.abs() < ceiling);
+            assert!(y > previous, "not monotonic at {}", x);
+            previous = y;
+        }
+        // Near-linear for quiet signals
+        assert!((LimiterMode::Soft.apply(0.01, ceiling) - 0.01).abs() < 1e-5);
+        assert_eq!(LimiterMode::Hard.apply(2.0, ceiling), ceiling);
+    }
+
+    #[test]
+    fn test_dither_error_is_decorrelated() {
+        let bits = 16;
+        let lsb = 1.0 / (1u32 << (bits - 1)) as f32;
+
+        // Slow ramp across a few LSBs: without dither the error follows the signal
+        let ramp: Vec<f32> = (0..100_000).map(|i| (i as f32 / 100_000.0) * 8.0 * lsb).collect();
+
+        let mut dither = Dither::new(12345);
+        let errors: Vec<f32> = ramp
+            .iter()
+            .map(|&x| dither.process(x, 0, bits, false) - x)
+            .collect();
+
+        // Mean error ~0 (no signal-dependent bias)...
+        let mean = errors.iter().sum::<f32>() / errors.len() as f32;
+        assert!(mean.abs() < 0.02 * lsb, "mean error {} LSB", mean / lsb);
+
+        // ...and uncorrelated with the ramp
+        let ramp_mean = ramp.iter().sum::<f32>() / ramp.len() as f32;
+        let covariance: f32 = ramp
+            .iter()
+            .zip(&errors)
+            .map(|(x, e)| (x - ramp_mean) * (e - mean))
+            .sum::<f32>()
+            / ramp.len() as f32;
+        let ramp_std = (ramp.iter().map(|x| (x - ramp_mean).powi(2)).sum::<f32>() / ramp.len() as f32).sqrt();
+        let error_std = (errors.iter().map(|e| (e - mean).powi(2)).sum::<f32>() / errors.len() as f32).sqrt();
+        let correlation = covariance / (ramp_std * error_std);
+        assert!(correlation.abs() < 0.05, "correlation {}", correlation);
+
+        // Output lands on the 16-bit grid
+        let sample = dither.process(0.123, 0, bits, false);
+        let steps = sample / lsb;
+        assert!((steps - steps.round()).abs() < 1e-3);
+    }
+}
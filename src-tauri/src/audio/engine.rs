@@ -1,6 +1,5 @@
 //! Main audio engine using cpal for real-time audio output
 
-use cpal::traits::{DeviceTrait, StreamTrait};
 use parking_lot::{Mutex, RwLock};
 use rubato::{FftFixedInOut, Resampler};
 use serde::{Deserialize, Serialize};
@@ -11,13 +10,27 @@ use std::sync::Arc;
 
 use super::buffer::StereoSample;
 use super::device::{get_output_device, get_supported_config, AudioConfig};
+use super::dsp::{DcBlocker, DelayLine, Dither, LimiterMode, MAX_DELAY_FRAMES};
+use super::gain_match::GainMatcher;
 use super::input::{get_input_handle, start_input_capture, stop_input_capture};
-use super::midi::MidiEventQueue;
-use super::plugin::{PluginInstance, PluginState};
-use super::samples::{AudioSample, SamplePlayer};
-use super::signals::{GatePattern, SignalConfig, SignalGenerator};
-use super::spectrum::{SpectrumAnalyzer, NUM_BANDS};
-use super::stereo::{StereoAnalyzer, STEREO_HISTORY_SIZE};
+use super::loudness::{LoudnessMeter, LoudnessReading};
+use super::midi::{activity, MidiEventQueue, NoteSource};
+use super::output::{null_stream_config, route_channels, ChannelRouting, OutputStream, OutputTarget};
+use super::plugin::automation::{ParamAutomation, ParamChange, ParamValueQueue};
+use super::plugin::clap_host::{AudioPortInfo, InputMapping, PluginParam};
+use super::plugin::{HostedPlugin, PluginState};
+use super::render::{render_blocks, write_wav, RenderProgress, RenderResult};
+use super::response::{FrequencyResponse, Sweep, SweepCapture};
+use super::samples::{AudioSample, SampleInfo, SamplePlayer, SampleSource};
+use super::signals::{GatePattern, SignalConfig, SignalGenerator, SignalType};
+use super::spectrum::{SpectrumAnalyzer, FFT_SIZE, MAX_FFT_SIZE, MIN_FFT_SIZE, NUM_BANDS};
+use super::ghost_kick::{GhostKick, GhostKickPattern};
+use super::stereo::{
+    apply_stereo_width, StereoAnalyzer, DEFAULT_CORRELATION_SMOOTHING_MS, MAX_CORRELATION_SMOOTHING_MS,
+    MAX_GONIOMETER_GAIN, MAX_STEREO_WIDTH, MIN_CORRELATION_SMOOTHING_MS, MIN_GONIOMETER_GAIN, MIN_STEREO_WIDTH,
+    STEREO_HISTORY_SIZE,
+};
+use super::weighting::{MeteringWeighting, WeightingFilter};
 
 /// Current state of the audio engine
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -28,6 +41,54 @@ pub enum EngineState {
     Paused,
 }
 
+/// Health of the global audio engine, as reported to the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineStatus {
+    /// Engine initialized and the output stream is alive
+    Running,
+    /// Output device disappeared (unplugged, driver reset) - stream is dead
+    DeviceLost,
+    /// No output device could be opened - running on the silent null output
+    NoDevice,
+    /// Engine not initialized or shut down
+    Stopped,
+}
+
+/// What the engine sends to the output when an effect plugin is loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorMode {
+    /// Regular monitoring path
+    Normal,
+    /// Plugin output only
+    Wet,
+    /// Dry input, delayed by the plugin latency (bypass)
+    Dry,
+    /// Plugin output minus the latency-aligned dry input, per channel (null test residual)
+    Difference,
+}
+
+impl MonitorMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            MonitorMode::Normal => MONITOR_NORMAL,
+            MonitorMode::Wet => MONITOR_WET,
+            MonitorMode::Dry => MONITOR_DRY,
+            MonitorMode::Difference => MONITOR_DIFFERENCE,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            MONITOR_WET => MonitorMode::Wet,
+            MONITOR_DRY => MonitorMode::Dry,
+            MONITOR_DIFFERENCE => MonitorMode::Difference,
+            _ => MonitorMode::Normal,
+        }
+    }
+}
+
 /// Input source for the audio engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -38,6 +99,20 @@ pub enum InputSource {
     None,
 }
 
+/// A loaded plugin captured from one engine so it can be restored into another
+/// (used when the engine is rebuilt for a new device or sample rate)
+#[derive(Debug, Clone)]
+pub struct PluginSnapshot {
+    /// Path to the .clap bundle
+    pub path: String,
+    /// Saved plugin state (None if the plugin doesn't support the state extension)
+    pub state: Option<Vec<u8>>,
+    /// Whether the plugin was flagged as an instrument
+    pub is_instrument: bool,
+    /// Manual effect/instrument override in effect (None = detected type)
+    pub instrument_override: Option<bool>,
+}
+
 /// Commands that can be sent to the audio engine
 #[derive(Debug, Clone)]
 pub enum EngineCommand {
@@ -51,6 +126,32 @@ pub enum EngineCommand {
     LoadSample(String),
 }
 
+/// Active input source kind, published to the audio thread as an AtomicU8.
+/// The full InputSource (paths, configs) stays behind a lock on the control side;
+/// the callback only needs to know which generator to pull from.
+const SOURCE_NONE: u8 = 0;
+const SOURCE_SIGNAL: u8 = 1;
+const SOURCE_SAMPLE: u8 = 2;
+const SOURCE_LIVE: u8 = 3;
+
+impl InputSource {
+    /// Lock-free tag for this source (see SOURCE_* constants)
+    fn kind(&self) -> u8 {
+        match self {
+            InputSource::Signal { .. } => SOURCE_SIGNAL,
+            InputSource::Sample { .. } => SOURCE_SAMPLE,
+            InputSource::Live { .. } => SOURCE_LIVE,
+            InputSource::None => SOURCE_NONE,
+        }
+    }
+}
+
+/// Monitor modes as stored in SharedState::monitor_mode
+const MONITOR_NORMAL: u8 = 0;
+const MONITOR_WET: u8 = 1;
+const MONITOR_DRY: u8 = 2;
+const MONITOR_DIFFERENCE: u8 = 3;
+
 /// Crossfade state for hot reload
 const CROSSFADE_NONE: u8 = 0;
 const CROSSFADE_OUT: u8 = 1;
@@ -59,13 +160,50 @@ const CROSSFADE_IN: u8 = 2;
 /// Crossfade duration in samples (at 44.1kHz: 4410 = 100ms)
 const CROSSFADE_SAMPLES: u32 = 4410;
 
+/// Fade-in gain `position` samples into a crossfade (0.0 -> 1.0). Shared by the hot
+/// reload fade and the bypass fade so both sound the same.
+#[inline]
+fn crossfade_gain(position: u32) -> f32 {
+    (position as f32 / CROSSFADE_SAMPLES as f32).clamp(0.0, 1.0)
+}
+
+/// Allowed range for the safety limiter ceiling (dBFS)
+const MIN_LIMITER_CEILING_DB: f32 = -24.0;
+const MAX_LIMITER_CEILING_DB: f32 = 6.0;
+
+/// Where the editor is opened for thumbnail capture (far off any display)
+const THUMBNAIL_EDITOR_POSITION: (f64, f64) = (-20000.0, -20000.0);
+
 /// Number of samples in waveform display buffer (per channel)
 /// 4096 samples = ~85ms at 48kHz, allows for various zoom levels
 const WAVEFORM_SAMPLES: usize = 4096;
 
+/// Device name reported by an offline engine (no output stream)
+const OFFLINE_OUTPUT_NAME: &str = "Offline";
+
+/// Level meter smoothing factor (applied once per block)
+const LEVEL_SMOOTHING: f32 = 0.1;
+
+/// Minimum number of frames the plugin and callback buffers are sized for.
+/// Larger fixed buffer sizes raise this so `HostedPlugin::process` never truncates.
+const DEFAULT_MAX_FRAMES: usize = 4096;
+
 /// Maximum output buffer size to prevent unbounded growth (about 1 second at 48kHz)
 const MAX_OUTPUT_BUFFER_SIZE: usize = 48000;
 
+/// Default auto level time constant in seconds - slow enough to ignore transients
+const AUTO_LEVEL_DEFAULT_TIME_CONSTANT: f32 = 3.0;
+
+/// Allowed auto level time constant range in seconds
+const AUTO_LEVEL_MIN_TIME_CONSTANT: f32 = 0.5;
+const AUTO_LEVEL_MAX_TIME_CONSTANT: f32 = 10.0;
+
+/// Default live input resampler chunk size - a good balance of latency vs efficiency
+const DEFAULT_RESAMPLER_CHUNK_SIZE: usize = 256;
+
+/// Largest live input resampler chunk size accepted (~85ms at 48kHz)
+const MAX_RESAMPLER_CHUNK_SIZE: usize = 4096;
+
 /// Live input resampler for handling sample rate mismatch
 struct LiveInputResampler {
     resampler: FftFixedInOut<f32>,
@@ -83,6 +221,8 @@ struct LiveInputResampler {
     /// Accumulated output samples ready to consume (VecDeque for O(1) front removal)
     output_ready_left: VecDeque<f32>,
     output_ready_right: VecDeque<f32>,
+    /// Latency added by chunking plus the resampler's own delay, in ms
+    added_latency_ms: f32,
 }
 
 impl LiveInputResampler {
@@ -98,6 +238,10 @@ impl LiveInputResampler {
 
         let input_frames_needed = resampler.input_frames_next();
         let output_frames = resampler.output_frames_next();
+        // A whole input chunk has to arrive before anything comes out, then the filter delay
+        let added_latency_ms = (input_frames_needed as f32 / input_rate as f32
+            + resampler.output_delay() as f32 / output_rate as f32)
+            * 1000.0;
 
         log::info!(
             "Created resampler: {} Hz -> {} Hz, input frames: {}, output frames: {}",
@@ -116,9 +260,19 @@ impl LiveInputResampler {
             accum_right: Vec::with_capacity(input_frames_needed * 2),
             output_ready_left: VecDeque::with_capacity(output_frames * 2),
             output_ready_right: VecDeque::with_capacity(output_frames * 2),
+            added_latency_ms,
         })
     }
 
+    /// Carry over another resampler's buffered input and output, so swapping
+    /// resamplers mid-stream doesn't drop the audio already in flight
+    fn take_pending_from(&mut self, old: &mut LiveInputResampler) {
+        self.accum_left.extend_from_slice(&old.accum_left);
+        self.accum_right.extend_from_slice(&old.accum_right);
+        std::mem::swap(&mut self.output_ready_left, &mut old.output_ready_left);
+        std::mem::swap(&mut self.output_ready_right, &mut old.output_ready_right);
+    }
+
     /// Add input samples to the accumulator
     fn push_input(&mut self, left: f32, right: f32) {
         self.accum_left.push(left);
@@ -185,9 +339,22 @@ impl LiveInputResampler {
     }
 }
 
+/// Output and input analyzers built off the audio thread for an FFT size change.
+/// While `fresh` the callback swaps them in, which leaves its old pair in the slot
+/// for the next resize to drop on the command thread.
+#[derive(Default)]
+struct SpectrumResize {
+    analyzers: Option<(SpectrumAnalyzer, SpectrumAnalyzer)>,
+    fresh: bool,
+}
+
 /// Shared state between engine and audio thread
 struct SharedState {
+    // Full source description - control side only, never touched by the audio callback
     input_source: RwLock<InputSource>,
+    // Kind of the active source, read lock-free by the callback (SOURCE_* constants).
+    // Swapped only after the new source is fully set up, so changes land on a buffer boundary.
+    input_source_kind: AtomicU8,
     signal_generator: RwLock<SignalGenerator>,
     sample_player: RwLock<SamplePlayer>,
     is_playing: AtomicBool,
@@ -202,16 +369,38 @@ struct SharedState {
     input_level_right: AtomicU32,
     // Live input paused state
     live_paused: AtomicBool,
+    // Whether captured live input reaches the output/plugin (metering runs either way)
+    input_monitor: AtomicBool,
     // Live input resampler (for sample rate conversion)
     live_resampler: Mutex<Option<LiveInputResampler>>,
     // Clipping indicators (set when limiter engages, cleared after being read)
     clipping_left: AtomicBool,
     clipping_right: AtomicBool,
+    // Frequency weighting for the output level meter, and the weighted level of the
+    // mono output mix (f32 bits, same smoothing as the raw levels)
+    metering_weighting: AtomicU8,
+    weighted_output_level: AtomicU32,
+    // BS.1770 loudness of the output in LUFS (f32 bits, NaN while there's no reading), and
+    // a flag asking the callback to restart integration
+    loudness_momentary: AtomicU32,
+    loudness_short_term: AtomicU32,
+    loudness_integrated: AtomicU32,
+    loudness_reset_pending: AtomicBool,
+    // Safety limiter: ceiling as linear gain (f32 bits) and curve (soft = tanh)
+    limiter_ceiling: AtomicU32,
+    limiter_soft: AtomicBool,
+    // Safety limiter activity since the last reset: samples clamped (including NaN/Inf)
+    // and the largest absolute sample value seen (f32 bits)
+    limiter_clamped_samples: AtomicU64,
+    limiter_max_abs: AtomicU32,
     // Spectrum analyzer data - stored as AtomicU32 array for lock-free access
     // Output spectrum (post-FX)
     spectrum_bands: [AtomicU32; NUM_BANDS],
     // Input spectrum (pre-FX) for comparison overlay
     spectrum_bands_input: [AtomicU32; NUM_BANDS],
+    // Frozen output spectrum for before/after comparison (survives play/stop)
+    spectrum_snapshot: [AtomicU32; NUM_BANDS],
+    has_spectrum_snapshot: AtomicBool,
     // Waveform display buffers (separate L/R for stereo visualization)
     waveform_buffer_left: [AtomicU32; WAVEFORM_SAMPLES],
     waveform_buffer_right: [AtomicU32; WAVEFORM_SAMPLES],
@@ -232,20 +421,102 @@ struct SharedState {
     // INPUT stereo (pre-FX for comparison)
     stereo_positions_input: [AtomicU32; STEREO_HISTORY_SIZE * 2],
     stereo_correlation_input: AtomicU32,
+    // Goniometer display: how many of the newest positions are shown, and radius gain (f32 bits)
+    goniometer_history: AtomicU32,
+    goniometer_gain: AtomicU32,
+    // Correlation meter smoothing time constant in ms (f32 bits)
+    correlation_smoothing_ms: AtomicU32,
+    // Set by reset_analysis: the callback resets its analyzers, whose smoothing would
+    // otherwise carry the old readings over
+    analysis_reset_pending: AtomicBool,
+    // Spectrum analyzer FFT size, and the analyzers built for it on the command thread.
+    // The callback only try_locks the slot, so a resize never allocates on the audio thread
+    spectrum_fft_size: AtomicU32,
+    spectrum_resize: Mutex<SpectrumResize>,
     // Plugin hosting
-    plugin_instance: RwLock<Option<PluginInstance>>,
+    plugin_instance: RwLock<Option<HostedPlugin>>,
     plugin_state: RwLock<PluginState>,
+    // Set by the audio thread when the plugin returns CLAP_PROCESS_ERROR (it's bypassed
+    // from then on); cleared whenever a plugin is loaded or unloaded
+    plugin_process_error: AtomicBool,
     // MIDI queue reference (separate from plugin lock for lock-free MIDI access)
     // Updated when plugin is loaded/unloaded
     midi_queue: RwLock<Option<Arc<MidiEventQueue>>>,
     // Whether the loaded plugin is an instrument (needs MIDI processing even when not "playing")
     is_instrument_plugin: AtomicBool,
+    // Manual effect/instrument choice, applied over the detected type on every load and
+    // hot reload until cleared (None = use detection)
+    instrument_override: RwLock<Option<bool>>,
+    // Staged by release_plugin_voices: the callback runs one silent process cycle so the
+    // plugin sees queued note-offs, then clears the flag
+    voice_release_pending: AtomicBool,
     // Crossfade for hot reload
     crossfade_state: AtomicU8,
     crossfade_position: AtomicU32,
     // Plugin editor window position (persists across plugin reload for hot reload)
     // This is stored at engine level so it survives plugin unload/reload cycles
     last_editor_position: RwLock<Option<(f64, f64)>>,
+    // Set by the stream error callback when the output device goes away
+    device_lost: AtomicBool,
+    // Most recent error reported by the output stream's error callback
+    last_stream_error: Mutex<Option<String>>,
+    // Loudness matching of plugin output against the dry input (for fair bypass comparisons)
+    gain_match_enabled: AtomicBool,
+    // Output monitoring mode (MONITOR_* constants)
+    monitor_mode: AtomicU8,
+    // Plugin bypass: the callback fades to the unprocessed input, then stops calling the plugin
+    plugin_bypass: AtomicBool,
+    // Wet/dry mix of the plugin output against the dry input (f32 bits, 1.0 = fully wet)
+    plugin_mix: AtomicU32,
+    // Monitor stereo width applied before the limiter (f32 bits, 1.0 = unchanged)
+    monitor_width: AtomicU32,
+    // How the stereo bus feeds the plugin's main input, kept across plugin loads
+    input_mapping: Mutex<InputMapping>,
+    // Latency reported by the loaded plugin (samples), used to align the dry path
+    plugin_latency: AtomicU32,
+    // Compensation currently applied to the plugin output in dB (f32 bits)
+    gain_match_db: AtomicU32,
+    // Auto level: slow makeup gain holding the plugin output at the input level
+    auto_level_enabled: AtomicBool,
+    // Auto level measurement time constant in seconds (f32 bits)
+    auto_level_time_constant: AtomicU32,
+    // Makeup gain currently applied by auto level in dB (f32 bits)
+    auto_level_db: AtomicU32,
+    // Ghost kick: synthetic kick fed to the plugin's sidechain input
+    ghost_kick_enabled: AtomicBool,
+    // Ghost kick rhythm (GhostKickPattern as u8)
+    ghost_kick_pattern: AtomicU8,
+    // Ghost kick tempo in BPM (f32 bits)
+    ghost_kick_bpm: AtomicU32,
+    // Transport tempo in BPM (f32 bits), follows the pattern player
+    transport_bpm: AtomicU32,
+    // Set to restart the ghost kick at beat 0 on the next callback
+    ghost_kick_resync: AtomicBool,
+    // DC blocker on the final output (off by default)
+    dc_block_enabled: AtomicBool,
+    // DC blocker history per channel: [x1_left, y1_left, x1_right, y1_right] (f32 bits)
+    dc_block_state: [AtomicU32; 4],
+    // Output dither (TPDF, optionally noise-shaped) to a target bit depth
+    dither_enabled: AtomicBool,
+    dither_bits: AtomicU8,
+    dither_shaped: AtomicBool,
+    // Frames rendered since the stream started (advanced at the start of each callback)
+    sample_clock: AtomicU64,
+    // Frames rendered while playing - the transport position (held by pause, reset by stop)
+    play_clock: AtomicU64,
+    // Parameter changes waiting for the next plugin process call
+    param_queue: ParamValueQueue,
+    // Automation being recorded: (sample clock at start, recorded lanes)
+    param_recording: Mutex<Option<(u64, ParamAutomation)>>,
+    // Automation available for playback (read by the audio thread with try_read)
+    param_automation: RwLock<Option<ParamAutomation>>,
+    automation_playing: AtomicBool,
+    // Sample clock value where automation playback started
+    automation_start: AtomicU64,
+    // Frequency response measurement: the capture is owned by the audio thread while
+    // response_measuring is set, and the audio thread clears the flag when it's done
+    response_capture: Mutex<Option<SweepCapture>>,
+    response_measuring: AtomicBool,
     // Performance monitoring (disabled by default for zero overhead)
     // When enabled, clap_host measures plugin.process() call duration
     perf_monitoring_enabled: AtomicBool,
@@ -255,6 +526,117 @@ struct SharedState {
     perf_samples_processed: AtomicU32,
 }
 
+impl SharedState {
+    fn new(sample_rate: u32, last_stream_error: Option<String>) -> Self {
+        // Initialize spectrum bands, waveform, and stereo arrays with zeros
+        const INIT_BAND: AtomicU32 = AtomicU32::new(0);
+        const INIT_WAVEFORM: AtomicU32 = AtomicU32::new(0);
+        const INIT_STEREO: AtomicU32 = AtomicU32::new(0);
+        const INIT_PEAK: AtomicU32 = AtomicU32::new(0);
+        Self {
+            input_source: RwLock::new(InputSource::None),
+            input_source_kind: AtomicU8::new(SOURCE_NONE),
+            signal_generator: RwLock::new(SignalGenerator::new(sample_rate)),
+            sample_player: RwLock::new(SamplePlayer::new()),
+            is_playing: AtomicBool::new(false),
+            is_looping: AtomicBool::new(true),
+            master_volume: AtomicU32::new(f32_to_u32(0.75)), // Default 75% volume
+            output_level_left: AtomicU32::new(f32_to_u32(0.0)),
+            output_level_right: AtomicU32::new(f32_to_u32(0.0)),
+            input_level_left: AtomicU32::new(f32_to_u32(0.0)),
+            input_level_right: AtomicU32::new(f32_to_u32(0.0)),
+            live_paused: AtomicBool::new(false),
+            input_monitor: AtomicBool::new(true),
+            live_resampler: Mutex::new(None),
+            clipping_left: AtomicBool::new(false),
+            clipping_right: AtomicBool::new(false),
+            metering_weighting: AtomicU8::new(MeteringWeighting::None.to_u8()),
+            weighted_output_level: AtomicU32::new(f32_to_u32(0.0)),
+            loudness_momentary: AtomicU32::new(f32_to_u32(f32::NAN)),
+            loudness_short_term: AtomicU32::new(f32_to_u32(f32::NAN)),
+            loudness_integrated: AtomicU32::new(f32_to_u32(f32::NAN)),
+            loudness_reset_pending: AtomicBool::new(false),
+            limiter_ceiling: AtomicU32::new(f32_to_u32(1.0)),
+            limiter_soft: AtomicBool::new(false),
+            limiter_clamped_samples: AtomicU64::new(0),
+            limiter_max_abs: AtomicU32::new(f32_to_u32(0.0)),
+            spectrum_bands: [INIT_BAND; NUM_BANDS],
+            spectrum_bands_input: [INIT_BAND; NUM_BANDS],
+            spectrum_snapshot: [INIT_BAND; NUM_BANDS],
+            has_spectrum_snapshot: AtomicBool::new(false),
+            waveform_buffer_left: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
+            waveform_buffer_right: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
+            waveform_write_pos: AtomicU32::new(0),
+            waveform_peak_left: INIT_PEAK,
+            waveform_peak_right: INIT_PEAK,
+            // Input waveform (pre-FX) for comparison
+            waveform_buffer_input_left: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
+            waveform_buffer_input_right: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
+            waveform_input_write_pos: AtomicU32::new(0),
+            waveform_input_peak_left: INIT_PEAK,
+            waveform_input_peak_right: INIT_PEAK,
+            // Output stereo (post-FX)
+            stereo_positions: [INIT_STEREO; STEREO_HISTORY_SIZE * 2],
+            stereo_correlation: AtomicU32::new(f32_to_u32(1.0)), // Start at mono
+            // Input stereo (pre-FX) for comparison
+            stereo_positions_input: [INIT_STEREO; STEREO_HISTORY_SIZE * 2],
+            stereo_correlation_input: AtomicU32::new(f32_to_u32(1.0)), // Start at mono
+            goniometer_history: AtomicU32::new(STEREO_HISTORY_SIZE as u32),
+            goniometer_gain: AtomicU32::new(f32_to_u32(1.0)),
+            correlation_smoothing_ms: AtomicU32::new(f32_to_u32(DEFAULT_CORRELATION_SMOOTHING_MS)),
+            analysis_reset_pending: AtomicBool::new(false),
+            spectrum_fft_size: AtomicU32::new(FFT_SIZE as u32),
+            spectrum_resize: Mutex::new(SpectrumResize::default()),
+            plugin_instance: RwLock::new(None),
+            plugin_state: RwLock::new(PluginState::Unloaded),
+            plugin_process_error: AtomicBool::new(false),
+            midi_queue: RwLock::new(None),
+            is_instrument_plugin: AtomicBool::new(false),
+            instrument_override: RwLock::new(None),
+            voice_release_pending: AtomicBool::new(false),
+            crossfade_state: AtomicU8::new(CROSSFADE_NONE),
+            crossfade_position: AtomicU32::new(0),
+            last_editor_position: RwLock::new(None),
+            device_lost: AtomicBool::new(false),
+            last_stream_error: Mutex::new(last_stream_error),
+            gain_match_enabled: AtomicBool::new(false),
+            gain_match_db: AtomicU32::new(f32_to_u32(0.0)),
+            auto_level_enabled: AtomicBool::new(false),
+            auto_level_time_constant: AtomicU32::new(f32_to_u32(AUTO_LEVEL_DEFAULT_TIME_CONSTANT)),
+            auto_level_db: AtomicU32::new(f32_to_u32(0.0)),
+            ghost_kick_enabled: AtomicBool::new(false),
+            ghost_kick_pattern: AtomicU8::new(GhostKickPattern::Quarter.to_u8()),
+            ghost_kick_bpm: AtomicU32::new(f32_to_u32(120.0)),
+            transport_bpm: AtomicU32::new(f32_to_u32(120.0)),
+            ghost_kick_resync: AtomicBool::new(false),
+            monitor_mode: AtomicU8::new(MONITOR_NORMAL),
+            plugin_bypass: AtomicBool::new(false),
+            plugin_mix: AtomicU32::new(f32_to_u32(1.0)),
+            monitor_width: AtomicU32::new(f32_to_u32(1.0)),
+            input_mapping: Mutex::new(InputMapping::default()),
+            plugin_latency: AtomicU32::new(0),
+            dc_block_enabled: AtomicBool::new(false),
+            dc_block_state: [INIT_PEAK; 4],
+            dither_enabled: AtomicBool::new(false),
+            dither_bits: AtomicU8::new(16),
+            dither_shaped: AtomicBool::new(false),
+            sample_clock: AtomicU64::new(0),
+            play_clock: AtomicU64::new(0),
+            param_queue: ParamValueQueue::new(1024),
+            param_recording: Mutex::new(None),
+            param_automation: RwLock::new(None),
+            automation_playing: AtomicBool::new(false),
+            automation_start: AtomicU64::new(0),
+            response_capture: Mutex::new(None),
+            response_measuring: AtomicBool::new(false),
+            // Performance monitoring disabled by default (zero overhead when off)
+            perf_monitoring_enabled: AtomicBool::new(false),
+            perf_plugin_process_ns: AtomicU64::new(0),
+            perf_samples_processed: AtomicU32::new(0),
+        }
+    }
+}
+
 /// Helper to store f32 in AtomicU32
 #[inline]
 fn f32_to_u32(f: f32) -> u32 {
@@ -272,6 +654,15 @@ fn u32_to_f32(u: u32) -> f32 {
 pub struct AudioEngineHandle {
     shared: Arc<SharedState>,
     sample_rate: u32,
+    // Name of the output device the stream was opened on
+    device_name: String,
+    config: AudioConfig,
+    // Max frames per callback - shared by the callback buffers and plugin activation
+    max_frames: u32,
+    // Render thread flag when running on the null output (no device available)
+    null_output: Option<Arc<AtomicBool>>,
+    // Physical output channels the stereo signal is written to
+    output_routing: Arc<ChannelRouting>,
 }
 
 impl AudioEngineHandle {
@@ -286,9 +677,52 @@ impl AudioEngineHandle {
     }
 
     pub fn stop(&self) {
+        self.release_plugin_voices();
         self.shared.is_playing.store(false, Ordering::SeqCst);
+        self.shared.play_clock.store(0, Ordering::Relaxed);
         self.shared.sample_player.write().stop();
         self.shared.signal_generator.write().reset();
+        self.reset_dc_block();
+    }
+
+    /// Enable/disable the DC blocker on the final output
+    pub fn set_dc_block(&self, enabled: bool) {
+        if enabled && !self.shared.dc_block_enabled.load(Ordering::Relaxed) {
+            self.reset_dc_block();
+        }
+        self.shared.dc_block_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether the DC blocker is enabled
+    pub fn is_dc_block_enabled(&self) -> bool {
+        self.shared.dc_block_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Configure output dither: quantize to `bits` (16 or 24) with TPDF noise,
+    /// optionally noise-shaped
+    pub fn set_dither(&self, enabled: bool, bits: u8, shaped: bool) -> Result<(), String> {
+        if bits != 16 && bits != 24 {
+            return Err(format!("Unsupported dither bit depth: {} (expected 16 or 24)", bits));
+        }
+        self.shared.dither_bits.store(bits, Ordering::Relaxed);
+        self.shared.dither_shaped.store(shaped, Ordering::Relaxed);
+        self.shared.dither_enabled.store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Current dither settings: (enabled, bits, shaped)
+    pub fn get_dither(&self) -> (bool, u8, bool) {
+        (
+            self.shared.dither_enabled.load(Ordering::Relaxed),
+            self.shared.dither_bits.load(Ordering::Relaxed),
+            self.shared.dither_shaped.load(Ordering::Relaxed),
+        )
+    }
+
+    fn reset_dc_block(&self) {
+        for state in &self.shared.dc_block_state {
+            state.store(f32_to_u32(0.0), Ordering::Relaxed);
+        }
     }
 
     pub fn pause(&self) {
@@ -346,10 +780,10 @@ impl AudioEngineHandle {
                         log::info!("AudioEngine: Input device native sample rate: {} Hz, output: {} Hz", input_rate, self.sample_rate);
 
                         if input_rate != self.sample_rate {
-                            // Use provided chunk size or default to 256 (good balance of latency vs efficiency)
+                            // Use provided chunk size or the default
                             // Smaller values (64, 128) = lower latency but more CPU
                             // Larger values (512, 1024) = higher latency but more efficient
-                            let resampler_chunk_size = chunk_size.unwrap_or(256);
+                            let resampler_chunk_size = chunk_size.unwrap_or(DEFAULT_RESAMPLER_CHUNK_SIZE);
                             log::info!(
                                 "AudioEngine: Sample rate mismatch detected. Input: {} Hz, Output: {} Hz. Creating resampler with chunk size {}.",
                                 input_rate, self.sample_rate, resampler_chunk_size
@@ -383,21 +817,135 @@ impl AudioEngineHandle {
                 self.shared.sample_player.write().unload();
             }
         }
+        // Publish to the audio thread only once the new source is ready
+        let kind = source.kind();
         *self.shared.input_source.write() = source;
+        self.shared.input_source_kind.store(kind, Ordering::Release);
+        self.reset_analysis();
+    }
+
+    /// Change the live input resampler chunk size without restarting capture
+    ///
+    /// The new resampler is built here, off the audio thread, and swapped in while the
+    /// callback isn't holding the resampler lock, so it takes effect at a buffer boundary.
+    /// Audio already buffered in the old resampler carries over.
+    pub fn set_input_latency(&self, chunk_size: usize) -> Result<LiveInputLatency, String> {
+        if chunk_size == 0 || chunk_size > MAX_RESAMPLER_CHUNK_SIZE {
+            return Err(format!(
+                "Chunk size must be between 1 and {} samples",
+                MAX_RESAMPLER_CHUNK_SIZE
+            ));
+        }
+
+        // Remember it so a capture restart (e.g. device change) keeps the setting
+        match &mut *self.shared.input_source.write() {
+            InputSource::Live { chunk_size: current, .. } => *current = Some(chunk_size),
+            _ => return Err("Live input is not active".to_string()),
+        }
+
+        let input_handle = get_input_handle().ok_or_else(|| "Live input is not active".to_string())?;
+        let input_rate = input_handle.sample_rate();
+        if input_rate != self.sample_rate {
+            let mut resampler = LiveInputResampler::new(input_rate, self.sample_rate, chunk_size)?;
+            let old = {
+                let mut guard = self.shared.live_resampler.lock();
+                if let Some(old) = guard.as_mut() {
+                    resampler.take_pending_from(old);
+                }
+                guard.replace(resampler)
+            };
+            // Free the old resampler outside the lock the audio thread waits on
+            drop(old);
+            log::info!("AudioEngine: Live input resampler chunk size set to {}", chunk_size);
+        }
+
+        Ok(self.get_input_latency())
+    }
+
+    /// Report whether live input is being resampled and the latency that adds
+    pub fn get_input_latency(&self) -> LiveInputLatency {
+        let chunk_size = match &*self.shared.input_source.read() {
+            InputSource::Live { chunk_size, .. } => chunk_size.unwrap_or(DEFAULT_RESAMPLER_CHUNK_SIZE),
+            _ => DEFAULT_RESAMPLER_CHUNK_SIZE,
+        };
+        let input_sample_rate = get_input_handle().map(|handle| handle.sample_rate());
+        let added_latency_ms = self
+            .shared
+            .live_resampler
+            .lock()
+            .as_ref()
+            .map(|resampler| resampler.added_latency_ms);
+
+        LiveInputLatency {
+            resampling: added_latency_ms.is_some(),
+            chunk_size,
+            added_latency_ms: added_latency_ms.unwrap_or(0.0),
+            input_sample_rate,
+            output_sample_rate: self.sample_rate,
+        }
+    }
+
+    /// Get the current input source description
+    pub fn get_input_source(&self) -> InputSource {
+        self.shared.input_source.read().clone()
+    }
+
+    /// Get the signal generator configuration (kept even while another source is active)
+    pub fn get_signal_config(&self) -> SignalConfig {
+        self.shared.signal_generator.read().config().clone()
+    }
+
+    /// Replace the signal generator configuration without switching the input source
+    pub fn set_signal_config(&self, config: SignalConfig) {
+        self.shared.signal_generator.write().set_config(config);
     }
 
     pub fn set_frequency(&self, frequency: f32) {
         self.shared.signal_generator.write().set_frequency(frequency);
     }
 
+    /// Get the signal generator frequency (Hz)
+    pub fn get_frequency(&self) -> f32 {
+        self.shared.signal_generator.read().config().frequency
+    }
+
+    /// Get the signal generator amplitude (0.0 - 1.0)
+    pub fn get_amplitude(&self) -> f32 {
+        self.shared.signal_generator.read().config().amplitude
+    }
+
     pub fn set_amplitude(&self, amplitude: f32) {
         self.shared.signal_generator.write().set_amplitude(amplitude);
     }
 
+    /// Fire the one-shot burst of the signal generator (restarts one that's still playing)
+    pub fn trigger_burst(&self) -> Result<(), String> {
+        let mut generator = self.shared.signal_generator.write();
+        if generator.config().signal_type != SignalType::Burst {
+            return Err("The test signal isn't set to burst".to_string());
+        }
+        generator.trigger_burst();
+        Ok(())
+    }
+
     pub fn set_gate_pattern(&self, pattern: GatePattern) {
         self.shared.signal_generator.write().set_gate_pattern(pattern);
     }
 
+    /// Sync the gate to the transport tempo on a musical division, or free-run with None
+    pub fn set_gate_sync(&self, division: Option<GatePattern>) {
+        self.shared.signal_generator.write().set_gate_sync(division);
+    }
+
+    /// Set the transport tempo (BPM) that synced gates follow
+    pub fn set_transport_tempo(&self, bpm: f32) {
+        self.shared.transport_bpm.store(f32_to_u32(bpm), Ordering::Relaxed);
+    }
+
+    pub fn get_transport_tempo(&self) -> f32 {
+        u32_to_f32(self.shared.transport_bpm.load(Ordering::Relaxed))
+    }
+
     pub fn set_gate_rate(&self, rate: f32) {
         self.shared.signal_generator.write().set_gate_rate(rate);
     }
@@ -411,22 +959,28 @@ impl AudioEngineHandle {
         self.shared.sample_player.write().set_looping(looping);
     }
 
+    pub fn is_looping(&self) -> bool {
+        self.shared.is_looping.load(Ordering::SeqCst)
+    }
+
     pub fn load_sample<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
         let path_ref = path.as_ref();
         log::info!("Loading sample from: {:?}", path_ref);
 
-        let sample = AudioSample::load(path_ref)?;
+        let sample = SampleSource::open(path_ref, self.is_looping())?;
+        let info = sample.info();
         log::info!(
-            "Sample loaded: {} samples, {} Hz, {} channels, {:.2}s",
-            sample.info.num_samples,
-            sample.info.sample_rate,
-            sample.info.channels,
-            sample.info.duration_secs
+            "Sample loaded: {} samples, {} Hz, {} channels, {:.2}s{}",
+            info.num_samples,
+            info.sample_rate,
+            info.channels,
+            info.duration_secs,
+            if info.streaming { " (streaming)" } else { "" }
         );
 
         // Calculate speed ratio for resampling if needed
-        let speed_ratio = sample.info.sample_rate as f32 / self.sample_rate as f32;
-        log::info!("Speed ratio: {} (sample {}Hz -> engine {}Hz)", speed_ratio, sample.info.sample_rate, self.sample_rate);
+        let speed_ratio = info.sample_rate as f32 / self.sample_rate as f32;
+        log::info!("Speed ratio: {} (sample {}Hz -> engine {}Hz)", speed_ratio, info.sample_rate, self.sample_rate);
 
         let mut player = self.shared.sample_player.write();
         player.load_sample(sample);
@@ -435,12 +989,81 @@ impl AudioEngineHandle {
         Ok(())
     }
 
+    /// Loop only the part of the loaded sample between two times (in seconds) while looping
+    /// is on. The region is clamped to the sample's length.
+    pub fn set_loop_region(&self, start_secs: f32, end_secs: f32) -> Result<(), String> {
+        if !start_secs.is_finite() || !end_secs.is_finite() {
+            return Err("Loop region times must be finite".to_string());
+        }
+        let mut player = self.shared.sample_player.write();
+        let sample_rate = player.info().map(|info| info.sample_rate).ok_or_else(|| "No sample loaded".to_string())?;
+        let to_frames = |secs: f32| (secs.max(0.0) as f64 * sample_rate as f64).round() as usize;
+        player.set_loop_region(to_frames(start_secs), to_frames(end_secs))
+    }
+
+    /// Loop the whole sample again
+    pub fn clear_loop_region(&self) {
+        self.shared.sample_player.write().clear_loop_region();
+    }
+
+    /// Varispeed for sample playback (0.25 - 4.0, applied on top of sample rate matching)
+    pub fn set_playback_speed(&self, speed: f32) {
+        self.shared.sample_player.write().set_playback_speed(speed);
+    }
+
+    pub fn get_playback_speed(&self) -> f32 {
+        self.shared.sample_player.read().playback_speed()
+    }
+
+    /// Info about the loaded sample (whether it's streamed, length, format)
+    pub fn sample_info(&self) -> Option<SampleInfo> {
+        self.shared.sample_player.read().info().cloned()
+    }
+
     pub fn get_output_levels(&self) -> (f32, f32) {
         let left = u32_to_f32(self.shared.output_level_left.load(Ordering::Relaxed));
         let right = u32_to_f32(self.shared.output_level_right.load(Ordering::Relaxed));
         (left, right)
     }
 
+    /// Choose the frequency weighting for the weighted output level
+    pub fn set_metering_weighting(&self, weighting: MeteringWeighting) {
+        self.shared.metering_weighting.store(weighting.to_u8(), Ordering::Relaxed);
+        self.shared.weighted_output_level.store(f32_to_u32(0.0), Ordering::Relaxed);
+    }
+
+    pub fn get_metering_weighting(&self) -> MeteringWeighting {
+        MeteringWeighting::from_u8(self.shared.metering_weighting.load(Ordering::Relaxed))
+    }
+
+    /// Level (0.0 - 1.0, smoothed peak) of the mono output mix after the metering weighting.
+    /// Unweighted, it follows the louder of the two channel levels for correlated material.
+    pub fn get_weighted_output_level(&self) -> f32 {
+        u32_to_f32(self.shared.weighted_output_level.load(Ordering::Relaxed))
+    }
+
+    /// Momentary, short-term and integrated loudness of the output (pre-limiter)
+    pub fn get_loudness(&self) -> LoudnessReading {
+        let lufs = |value: &AtomicU32| Some(u32_to_f32(value.load(Ordering::Relaxed))).filter(|l| !l.is_nan());
+        LoudnessReading {
+            momentary: lufs(&self.shared.loudness_momentary),
+            short_term: lufs(&self.shared.loudness_short_term),
+            integrated: lufs(&self.shared.loudness_integrated),
+        }
+    }
+
+    /// Restart loudness integration (the readings clear immediately)
+    pub fn reset_loudness(&self) {
+        for value in [
+            &self.shared.loudness_momentary,
+            &self.shared.loudness_short_term,
+            &self.shared.loudness_integrated,
+        ] {
+            value.store(f32_to_u32(f32::NAN), Ordering::Relaxed);
+        }
+        self.shared.loudness_reset_pending.store(true, Ordering::Release);
+    }
+
     /// Get input levels (for live input metering)
     pub fn get_input_levels(&self) -> (f32, f32) {
         let left = u32_to_f32(self.shared.input_level_left.load(Ordering::Relaxed));
@@ -468,6 +1091,17 @@ impl AudioEngineHandle {
         self.shared.live_paused.load(Ordering::SeqCst)
     }
 
+    /// Set whether live input is passed on to the output and plugin
+    /// Unlike pausing, capture and input metering keep running while monitoring is off.
+    pub fn set_input_monitor(&self, enabled: bool) {
+        self.shared.input_monitor.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Check if live input is passed on to the output and plugin
+    pub fn is_input_monitor_enabled(&self) -> bool {
+        self.shared.input_monitor.load(Ordering::SeqCst)
+    }
+
     /// Set master volume (0.0 - 1.0)
     pub fn set_master_volume(&self, volume: f32) {
         let clamped = volume.clamp(0.0, 1.0);
@@ -497,6 +1131,80 @@ impl AudioEngineHandle {
         bands
     }
 
+    /// Center frequency of each spectrum band at the engine's sample rate
+    pub fn get_spectrum_band_frequencies(&self) -> [f32; NUM_BANDS] {
+        SpectrumAnalyzer::band_frequencies(self.sample_rate)
+    }
+
+    /// Frequency range (low, high) of each spectrum band at the engine's sample rate
+    pub fn get_spectrum_band_edges(&self) -> [(f32, f32); NUM_BANDS] {
+        SpectrumAnalyzer::band_edges(self.sample_rate)
+    }
+
+    /// Change the spectrum analyzers' FFT size. The analyzers are built here and the audio
+    /// callback swaps them in on its next spectrum update; until then the bands read as
+    /// silence rather than stale values.
+    pub fn set_spectrum_fft_size(&self, fft_size: usize) -> Result<(), String> {
+        if !SpectrumAnalyzer::is_supported_fft_size(fft_size) {
+            return Err(format!(
+                "Unsupported FFT size {} (must be a power of 2 from {} to {})",
+                fft_size,
+                MIN_FFT_SIZE,
+                MAX_FFT_SIZE
+            ));
+        }
+        let analyzers = (
+            SpectrumAnalyzer::with_fft_size(self.sample_rate, fft_size),
+            SpectrumAnalyzer::with_fft_size(self.sample_rate, fft_size),
+        );
+        // Replacing the slot drops whichever pair it held (the callback's retired analyzers,
+        // or a pair it never picked up) here rather than on the audio thread
+        let retired = {
+            let mut resize = self.shared.spectrum_resize.lock();
+            resize.fresh = true;
+            resize.analyzers.replace(analyzers)
+        };
+        drop(retired);
+        self.shared.spectrum_fft_size.store(fft_size as u32, Ordering::Relaxed);
+        for band in self.shared.spectrum_bands.iter().chain(&self.shared.spectrum_bands_input) {
+            band.store(f32_to_u32(0.0), Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// FFT size the spectrum analyzers use (or are about to be rebuilt with)
+    pub fn get_spectrum_fft_size(&self) -> usize {
+        self.shared.spectrum_fft_size.load(Ordering::Relaxed) as usize
+    }
+
+    /// Freeze the current output spectrum into the snapshot slot for A/B comparison
+    pub fn capture_spectrum_snapshot(&self) {
+        for (snapshot, band) in self.shared.spectrum_snapshot.iter().zip(self.shared.spectrum_bands.iter()) {
+            snapshot.store(band.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.shared.has_spectrum_snapshot.store(true, Ordering::Release);
+    }
+
+    /// Get the frozen spectrum snapshot (0.0 - 1.0), or None if nothing has been captured
+    pub fn get_spectrum_snapshot(&self) -> Option<[f32; NUM_BANDS]> {
+        if !self.shared.has_spectrum_snapshot.load(Ordering::Acquire) {
+            return None;
+        }
+        let mut bands = [0.0f32; NUM_BANDS];
+        for (i, band) in self.shared.spectrum_snapshot.iter().enumerate() {
+            bands[i] = u32_to_f32(band.load(Ordering::Relaxed));
+        }
+        Some(bands)
+    }
+
+    /// Discard the frozen spectrum snapshot
+    pub fn clear_spectrum_snapshot(&self) {
+        self.shared.has_spectrum_snapshot.store(false, Ordering::Release);
+        for band in self.shared.spectrum_snapshot.iter() {
+            band.store(0, Ordering::Relaxed);
+        }
+    }
+
     /// Get and clear clipping indicators (returns true if clipping occurred since last check)
     pub fn get_clipping(&self) -> (bool, bool) {
         let left = self.shared.clipping_left.swap(false, Ordering::Relaxed);
@@ -504,6 +1212,98 @@ impl AudioEngineHandle {
         (left, right)
     }
 
+    /// Configure the safety limiter: ceiling in dBFS (-24 to +6) and curve
+    /// NaN/Inf replacement stays on regardless of the settings
+    pub fn set_limiter(&self, ceiling_db: f32, mode: LimiterMode) -> Result<(), String> {
+        if !ceiling_db.is_finite() || !(MIN_LIMITER_CEILING_DB..=MAX_LIMITER_CEILING_DB).contains(&ceiling_db) {
+            return Err(format!(
+                "Limiter ceiling must be between {} and {} dB",
+                MIN_LIMITER_CEILING_DB, MAX_LIMITER_CEILING_DB
+            ));
+        }
+        let ceiling = 10.0f32.powf(ceiling_db / 20.0);
+        self.shared.limiter_ceiling.store(f32_to_u32(ceiling), Ordering::Relaxed);
+        self.shared.limiter_soft.store(mode == LimiterMode::Soft, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Current safety limiter settings: (ceiling in dBFS, curve)
+    pub fn get_limiter(&self) -> (f32, LimiterMode) {
+        let ceiling = u32_to_f32(self.shared.limiter_ceiling.load(Ordering::Relaxed));
+        let mode = if self.shared.limiter_soft.load(Ordering::Relaxed) {
+            LimiterMode::Soft
+        } else {
+            LimiterMode::Hard
+        };
+        (20.0 * ceiling.log10(), mode)
+    }
+
+    /// Safety limiter activity since the last reset:
+    /// (samples clamped, maximum absolute sample value seen before limiting)
+    pub fn get_headroom_stats(&self) -> (u64, f32) {
+        (
+            self.shared.limiter_clamped_samples.load(Ordering::Relaxed),
+            u32_to_f32(self.shared.limiter_max_abs.load(Ordering::Relaxed)),
+        )
+    }
+
+    /// Clear the safety limiter counters
+    pub fn reset_headroom_stats(&self) {
+        self.shared.limiter_clamped_samples.store(0, Ordering::Relaxed);
+        self.shared.limiter_max_abs.store(f32_to_u32(0.0), Ordering::Relaxed);
+    }
+
+    /// Clear every analysis readout (levels, clip indicators, spectrum, waveform, goniometer,
+    /// correlation, peak holds, headroom stats) so nothing from the previous plugin or
+    /// source lingers. The spectrum snapshot is kept - it's a deliberate comparison.
+    pub fn reset_analysis(&self) {
+        let shared = &self.shared;
+        let zero = f32_to_u32(0.0);
+
+        for level in [
+            &shared.output_level_left,
+            &shared.output_level_right,
+            &shared.weighted_output_level,
+            &shared.input_level_left,
+            &shared.input_level_right,
+            &shared.waveform_peak_left,
+            &shared.waveform_peak_right,
+            &shared.waveform_input_peak_left,
+            &shared.waveform_input_peak_right,
+        ] {
+            level.store(zero, Ordering::Relaxed);
+        }
+        shared.clipping_left.store(false, Ordering::Relaxed);
+        shared.clipping_right.store(false, Ordering::Relaxed);
+
+        for band in shared.spectrum_bands.iter().chain(&shared.spectrum_bands_input) {
+            band.store(zero, Ordering::Relaxed);
+        }
+        for sample in shared
+            .waveform_buffer_left
+            .iter()
+            .chain(&shared.waveform_buffer_right)
+            .chain(&shared.waveform_buffer_input_left)
+            .chain(&shared.waveform_buffer_input_right)
+        {
+            sample.store(zero, Ordering::Relaxed);
+        }
+
+        // Goniometer points back to the center with zero radius (invisible), correlation to mono
+        let center_angle = f32_to_u32(std::f32::consts::FRAC_PI_2);
+        for positions in [&shared.stereo_positions, &shared.stereo_positions_input] {
+            for point in positions.chunks(2) {
+                point[0].store(center_angle, Ordering::Relaxed);
+                point[1].store(zero, Ordering::Relaxed);
+            }
+        }
+        shared.stereo_correlation.store(f32_to_u32(1.0), Ordering::Relaxed);
+        shared.stereo_correlation_input.store(f32_to_u32(1.0), Ordering::Relaxed);
+
+        self.reset_headroom_stats();
+        shared.analysis_reset_pending.store(true, Ordering::Release);
+    }
+
     /// Get stereo waveform display buffers (circular buffer of recent samples)
     /// Returns (left_channel, right_channel) vectors
     pub fn get_waveform_data(&self) -> (Vec<f32>, Vec<f32>) {
@@ -529,17 +1329,14 @@ impl AudioEngineHandle {
     }
 
     /// Get stereo imaging positions for visualization
-    /// Returns Vec of (angle, radius) pairs where:
+    /// Returns Vec of (angle, radius, recent) where:
     /// - angle: 0 = full left, PI/2 = center, PI = full right
     /// - radius: 0-1 based on amplitude
-    pub fn get_stereo_positions(&self) -> Vec<(f32, f32)> {
-        let mut positions = Vec::with_capacity(STEREO_HISTORY_SIZE);
-        for i in 0..STEREO_HISTORY_SIZE {
-            let angle = u32_to_f32(self.shared.stereo_positions[i * 2].load(Ordering::Relaxed));
-            let radius = u32_to_f32(self.shared.stereo_positions[i * 2 + 1].load(Ordering::Relaxed));
-            positions.push((angle, radius));
-        }
-        positions
+    /// - recent: true for the newest points (drawn brighter)
+    ///
+    /// Only the newest `history_length` points (see set_goniometer_scale) are returned
+    pub fn get_stereo_positions(&self) -> Vec<(f32, f32, bool)> {
+        self.read_stereo_positions(&self.shared.stereo_positions)
     }
 
     /// Get stereo correlation coefficient
@@ -572,14 +1369,56 @@ impl AudioEngineHandle {
     }
 
     /// Get INPUT (pre-FX) stereo imaging positions for visualization
-    pub fn get_stereo_positions_input(&self) -> Vec<(f32, f32)> {
-        let mut positions = Vec::with_capacity(STEREO_HISTORY_SIZE);
-        for i in 0..STEREO_HISTORY_SIZE {
-            let angle = u32_to_f32(self.shared.stereo_positions_input[i * 2].load(Ordering::Relaxed));
-            let radius = u32_to_f32(self.shared.stereo_positions_input[i * 2 + 1].load(Ordering::Relaxed));
-            positions.push((angle, radius));
+    pub fn get_stereo_positions_input(&self) -> Vec<(f32, f32, bool)> {
+        self.read_stereo_positions(&self.shared.stereo_positions_input)
+    }
+
+    /// Read the visible tail of a stereo position array (stored oldest to newest)
+    fn read_stereo_positions(&self, positions: &[AtomicU32]) -> Vec<(f32, f32, bool)> {
+        let length = (self.shared.goniometer_history.load(Ordering::Relaxed) as usize)
+            .clamp(1, STEREO_HISTORY_SIZE);
+        let first = STEREO_HISTORY_SIZE - length;
+        let recent_from = STEREO_HISTORY_SIZE - StereoAnalyzer::recent_count(length);
+        (first..STEREO_HISTORY_SIZE)
+            .map(|i| {
+                let angle = u32_to_f32(positions[i * 2].load(Ordering::Relaxed));
+                let radius = u32_to_f32(positions[i * 2 + 1].load(Ordering::Relaxed));
+                (angle, radius, i >= recent_from)
+            })
+            .collect()
+    }
+
+    /// Configure the goniometer display: number of history points shown
+    /// (1..=STEREO_HISTORY_SIZE) and gain applied to point radii
+    pub fn set_goniometer_scale(&self, history_length: usize, gain: f32) -> Result<(), String> {
+        if history_length == 0 || history_length > STEREO_HISTORY_SIZE {
+            return Err(format!(
+                "Goniometer history length must be between 1 and {}",
+                STEREO_HISTORY_SIZE
+            ));
+        }
+        if !gain.is_finite() || !(MIN_GONIOMETER_GAIN..=MAX_GONIOMETER_GAIN).contains(&gain) {
+            return Err(format!(
+                "Goniometer gain must be between {} and {}",
+                MIN_GONIOMETER_GAIN, MAX_GONIOMETER_GAIN
+            ));
+        }
+        self.shared.goniometer_history.store(history_length as u32, Ordering::Relaxed);
+        self.shared.goniometer_gain.store(f32_to_u32(gain), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Set the correlation meter smoothing time constant in milliseconds
+    /// (MIN_CORRELATION_SMOOTHING_MS..=MAX_CORRELATION_SMOOTHING_MS, 0 = no smoothing)
+    pub fn set_correlation_smoothing(&self, ms: f32) -> Result<(), String> {
+        if !ms.is_finite() || !(MIN_CORRELATION_SMOOTHING_MS..=MAX_CORRELATION_SMOOTHING_MS).contains(&ms) {
+            return Err(format!(
+                "Correlation smoothing must be between {} and {} ms",
+                MIN_CORRELATION_SMOOTHING_MS, MAX_CORRELATION_SMOOTHING_MS
+            ));
         }
-        positions
+        self.shared.correlation_smoothing_ms.store(f32_to_u32(ms), Ordering::Relaxed);
+        Ok(())
     }
 
     /// Get INPUT (pre-FX) stereo correlation coefficient
@@ -587,54 +1426,381 @@ impl AudioEngineHandle {
         u32_to_f32(self.shared.stereo_correlation_input.load(Ordering::Relaxed))
     }
 
-    pub fn get_state(&self) -> EngineState {
-        if self.shared.is_playing.load(Ordering::SeqCst) {
-            EngineState::Playing
-        } else {
-            EngineState::Stopped
-        }
+    /// Choose what's sent to the output when an effect plugin is loaded
+    pub fn set_monitor_mode(&self, mode: MonitorMode) {
+        self.shared.monitor_mode.store(mode.to_u8(), Ordering::Relaxed);
     }
 
-    // Plugin methods
+    pub fn get_monitor_mode(&self) -> MonitorMode {
+        MonitorMode::from_u8(self.shared.monitor_mode.load(Ordering::Relaxed))
+    }
 
-    /// Load a CLAP plugin from a .clap bundle path
-    pub fn load_plugin(&self, path: &Path) -> Result<(), String> {
-        log::info!("Loading plugin from: {:?}", path);
+    /// Set the monitor stereo width (0 = mono, 1 = unchanged, up to 2 = wider), applied
+    /// after the plugin and before the limiter. Returns the width after clamping.
+    pub fn set_monitor_width(&self, width: f32) -> Result<f32, String> {
+        if !width.is_finite() {
+            return Err(format!("Invalid stereo width: {}", width));
+        }
+        let width = width.clamp(MIN_STEREO_WIDTH, MAX_STEREO_WIDTH);
+        self.shared.monitor_width.store(f32_to_u32(width), Ordering::Relaxed);
+        Ok(width)
+    }
 
-        // Update state to loading
-        *self.shared.plugin_state.write() = PluginState::Loading {
-            path: path.display().to_string(),
-        };
+    pub fn get_monitor_width(&self) -> f32 {
+        u32_to_f32(self.shared.monitor_width.load(Ordering::Relaxed))
+    }
 
-        // Unload existing plugin first
-        self.unload_plugin();
+    /// Enable/disable loudness matching of the plugin output against the dry input, so
+    /// toggling bypass compares wet and dry at the same level
+    pub fn set_gain_match(&self, enabled: bool) {
+        self.shared.gain_match_enabled.store(enabled, Ordering::Relaxed);
+    }
 
-        // Load new plugin with sample rate and reasonable max frames
-        let max_frames = 4096u32;
-        match PluginInstance::load(path, self.sample_rate as f64, max_frames) {
-            Ok(mut plugin) => {
-                // Start processing
-                if let Err(e) = plugin.start_processing() {
-                    log::warn!("Plugin start_processing failed: {}", e);
+    /// Whether gain matching is enabled
+    pub fn is_gain_match_enabled(&self) -> bool {
+        self.shared.gain_match_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Compensation currently applied to the plugin output in dB (0.0 when disabled)
+    pub fn get_gain_match_db(&self) -> f32 {
+        u32_to_f32(self.shared.gain_match_db.load(Ordering::Relaxed))
+    }
+
+    /// Enable/disable auto level: a slow makeup gain that keeps the plugin output at the
+    /// input's level on continuous material, so switching the effect doesn't change loudness.
+    /// Unlike gain match (a fast match for comparisons) it only follows sustained differences.
+    /// Gain match is skipped while auto level is on, as both level against the same input.
+    pub fn set_auto_level(&self, enabled: bool) {
+        self.shared.auto_level_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether auto level is enabled
+    pub fn is_auto_level_enabled(&self) -> bool {
+        self.shared.auto_level_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Set the auto level measurement time constant in seconds (clamped to the allowed range)
+    pub fn set_auto_level_time_constant(&self, seconds: f32) {
+        let seconds = if seconds.is_finite() { seconds } else { AUTO_LEVEL_DEFAULT_TIME_CONSTANT };
+        let seconds = seconds.clamp(AUTO_LEVEL_MIN_TIME_CONSTANT, AUTO_LEVEL_MAX_TIME_CONSTANT);
+        self.shared.auto_level_time_constant.store(f32_to_u32(seconds), Ordering::Relaxed);
+    }
+
+    /// Auto level measurement time constant in seconds
+    pub fn get_auto_level_time_constant(&self) -> f32 {
+        u32_to_f32(self.shared.auto_level_time_constant.load(Ordering::Relaxed))
+    }
+
+    /// Makeup gain currently applied by auto level in dB (0.0 when disabled)
+    pub fn get_auto_level_db(&self) -> f32 {
+        u32_to_f32(self.shared.auto_level_db.load(Ordering::Relaxed))
+    }
+
+    /// Enable/disable the ghost kick: a synthetic kick drum on `pattern` at `bpm`, fed to
+    /// the loaded effect plugin's sidechain input so ducking can be auditioned.
+    /// Restarts the rhythm at beat 0.
+    pub fn set_ghost_kick(&self, enabled: bool, pattern: GhostKickPattern, bpm: f32) {
+        self.shared.ghost_kick_pattern.store(pattern.to_u8(), Ordering::Relaxed);
+        self.shared.ghost_kick_bpm.store(f32_to_u32(bpm), Ordering::Relaxed);
+        self.shared.ghost_kick_resync.store(true, Ordering::Relaxed);
+        self.shared.ghost_kick_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Follow the pattern player's tempo; `restart` puts the kick back on beat 0
+    /// (used when a pattern starts so both share a downbeat)
+    pub fn sync_ghost_kick(&self, bpm: f32, restart: bool) {
+        self.shared.ghost_kick_bpm.store(f32_to_u32(bpm), Ordering::Relaxed);
+        if restart {
+            self.shared.ghost_kick_resync.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether the loaded plugin has a sidechain input (false if no plugin is loaded)
+    pub fn plugin_has_sidechain(&self) -> bool {
+        self.shared
+            .plugin_instance
+            .read()
+            .as_ref()
+            .map(|plugin| plugin.has_sidechain_input())
+            .unwrap_or(false)
+    }
+
+    /// Frames rendered since the output stream started
+    pub fn sample_clock(&self) -> u64 {
+        self.shared.sample_clock.load(Ordering::Relaxed)
+    }
+
+    /// Frames rendered while playing since the last stop (pause holds the position)
+    pub fn play_clock(&self) -> u64 {
+        self.shared.play_clock.load(Ordering::Relaxed)
+    }
+
+    /// Output sample rate the engine runs at
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Transport position in seconds at the engine's sample rate
+    pub fn get_play_position_secs(&self) -> f64 {
+        self.play_clock() as f64 / self.sample_rate.max(1) as f64
+    }
+
+    /// Set a plugin parameter (plain value), recording it if automation recording is active
+    pub fn set_parameter_value(&self, param_id: u32, value: f64) -> Result<(), String> {
+        if !self.has_plugin() {
+            return Err("No plugin loaded".to_string());
+        }
+        if !self.shared.param_queue.push(ParamChange { param_id, value }) {
+            return Err("Parameter queue full".to_string());
+        }
+
+        if let Some((start, ref mut automation)) = *self.shared.param_recording.lock() {
+            automation.record(param_id, self.sample_clock().saturating_sub(start), value);
+        }
+        Ok(())
+    }
+
+    /// Start recording parameter changes (discards any recording in progress)
+    pub fn start_param_recording(&self) {
+        *self.shared.param_recording.lock() =
+            Some((self.sample_clock(), ParamAutomation::new(self.sample_rate)));
+        log::info!("Parameter automation recording started");
+    }
+
+    /// Stop recording and keep the result for playback
+    pub fn stop_param_recording(&self) -> Option<ParamAutomation> {
+        let (_, automation) = self.shared.param_recording.lock().take()?;
+        log::info!(
+            "Parameter automation recording stopped: {} lane(s), {} samples",
+            automation.lanes.len(),
+            automation.length()
+        );
+        self.set_param_automation(Some(automation.clone()));
+        Some(automation)
+    }
+
+    /// Whether parameter changes are currently being recorded
+    pub fn is_param_recording(&self) -> bool {
+        self.shared.param_recording.lock().is_some()
+    }
+
+    /// Replace the automation used for playback (None clears it)
+    pub fn set_param_automation(&self, automation: Option<ParamAutomation>) {
+        self.shared.automation_playing.store(false, Ordering::Relaxed);
+        *self.shared.param_automation.write() = automation;
+    }
+
+    /// Automation available for playback
+    pub fn get_param_automation(&self) -> Option<ParamAutomation> {
+        self.shared.param_automation.read().clone()
+    }
+
+    /// Play the recorded automation from the beginning
+    pub fn play_param_automation(&self) -> Result<(), String> {
+        {
+            let automation = self.shared.param_automation.read();
+            match automation.as_ref() {
+                Some(automation) if !automation.is_empty() => {
+                    if automation.sample_rate != self.sample_rate {
+                        log::warn!(
+                            "Automation recorded at {} Hz, playing at {} Hz",
+                            automation.sample_rate,
+                            self.sample_rate
+                        );
+                    }
                 }
+                _ => return Err("No parameter automation recorded".to_string()),
+            }
+        }
+        self.shared.automation_start.store(self.sample_clock(), Ordering::Relaxed);
+        self.shared.automation_playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
 
-                let name = plugin.name.clone();
-                let has_editor = plugin.has_gui();
-                let path_str = path.display().to_string();
+    /// Stop automation playback
+    pub fn stop_param_automation(&self) {
+        self.shared.automation_playing.store(false, Ordering::Relaxed);
+    }
 
-                // Get MIDI queue reference before storing plugin
-                let midi_queue = plugin.midi_queue();
+    /// Whether automation is currently playing back
+    pub fn is_param_automation_playing(&self) -> bool {
+        self.shared.automation_playing.load(Ordering::Relaxed)
+    }
 
-                *self.shared.plugin_instance.write() = Some(plugin);
-                // Store MIDI queue reference separately for lock-free access
-                *self.shared.midi_queue.write() = Some(midi_queue);
-                *self.shared.plugin_state.write() = PluginState::Active {
-                    name: name.clone(),
-                    path: path_str,
-                    has_editor,
-                };
+    /// Measure the loaded plugin's magnitude and phase response with an exponential sweep
+    ///
+    /// Blocks while the audio thread plays the sweep through the plugin (the device
+    /// output is muted meanwhile), then deconvolves the recording. The plugin's reported
+    /// latency is compensated when aligning the impulse response.
+    pub fn run_frequency_response(&self) -> Result<FrequencyResponse, String> {
+        let latency = match self.shared.plugin_instance.read().as_ref() {
+            Some(plugin) => plugin.latency(),
+            None => return Err("No plugin loaded".to_string()),
+        };
+        if self.is_instrument() {
+            return Err("Frequency response can only be measured on effect plugins".to_string());
+        }
+
+        let sweep = Sweep::new(self.sample_rate);
+        let length = sweep.capture_length(latency);
+        {
+            let mut capture = self.shared.response_capture.lock();
+            if capture.is_some() {
+                return Err("A measurement is already running".to_string());
+            }
+            *capture = Some(SweepCapture::new(sweep.signal.clone(), length));
+        }
+        self.shared.response_measuring.store(true, Ordering::Release);
+
+        // Capture time plus a few seconds of slack for a stalled stream
+        let deadline = std::time::Instant::now()
+            + std::time::Duration::from_secs_f64(length as f64 / self.sample_rate as f64 + 5.0);
+        while self.shared.response_measuring.load(Ordering::Acquire) {
+            if std::time::Instant::now() > deadline {
+                self.shared.response_measuring.store(false, Ordering::Release);
+                self.shared.response_capture.lock().take();
+                return Err("Frequency response measurement timed out".to_string());
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        let capture = self
+            .shared
+            .response_capture
+            .lock()
+            .take()
+            .ok_or("Measurement was cancelled")?;
+        let recording = capture.into_recording()?;
+        log::info!(
+            "Measured frequency response: {} frames, plugin latency {} samples",
+            recording.len(),
+            latency
+        );
+        Ok(sweep.analyze(&recording, latency))
+    }
+
+    /// Render the loaded sample through the loaded plugin to a WAV file, faster than realtime
+    /// A second instance of the plugin (same bundle, same state) does the processing on the
+    /// calling thread, so playback isn't interrupted. The render runs at the sample's own rate.
+    pub fn render_sample_through_plugin<F>(&self, output_path: &Path, on_progress: F) -> Result<RenderResult, String>
+    where
+        F: FnMut(RenderProgress),
+    {
+        let sample_path = match &*self.shared.input_source.read() {
+            InputSource::Sample { path } => path.clone(),
+            _ => return Err("No sample loaded - select a sample as the input source".to_string()),
+        };
+        let snapshot = self.snapshot_plugin().ok_or("No plugin loaded")?;
+        if snapshot.is_instrument {
+            return Err("Samples can only be rendered through effect plugins".to_string());
+        }
+
+        let sample = AudioSample::load(&sample_path)?;
+        let sample_rate = sample.info.sample_rate;
+        let mut plugin = HostedPlugin::load(Path::new(&snapshot.path), sample_rate as f64, self.max_frames)?;
+        plugin.set_input_mapping(self.get_input_mapping());
+        if let Some(ref state) = snapshot.state {
+            plugin
+                .load_state(state)
+                .map_err(|e| format!("Failed to copy the plugin's state for rendering: {}", e))?;
+        }
+        let latency = plugin.latency();
+
+        log::info!(
+            "Rendering '{}' through {} ({} frames at {} Hz, latency {})",
+            sample.info.name,
+            plugin.name(),
+            sample.data.len(),
+            sample_rate,
+            latency
+        );
+        let output = render_blocks(
+            &sample.data,
+            latency as usize,
+            self.max_frames as usize,
+            |input, output| plugin.process(input, output),
+            on_progress,
+        )?;
+        write_wav(output_path, &output, sample_rate, 2)?;
+
+        let frames = output.len() / 2;
+        Ok(RenderResult {
+            output_path: output_path.display().to_string(),
+            sample_rate,
+            frames,
+            duration_secs: frames as f64 / sample_rate as f64,
+            latency_samples: latency,
+        })
+    }
+
+    /// Check if the output device was lost (stream error callback reported it gone)
+    pub fn is_device_lost(&self) -> bool {
+        self.shared.device_lost.load(Ordering::SeqCst)
+    }
+
+    /// Most recent error reported by the output stream, if any
+    pub fn last_stream_error(&self) -> Option<String> {
+        self.shared.last_stream_error.lock().clone()
+    }
+
+    /// Check if the engine is running on the silent null output (no device could be opened)
+    pub fn is_null_output(&self) -> bool {
+        self.null_output.is_some()
+    }
+
+    /// Route the stereo output to a pair of the device's channels (0-based indices)
+    pub fn set_output_channels(&self, left: u16, right: u16) -> Result<(), String> {
+        self.output_routing.set(left, right)?;
+        log::info!("Output routed to channels {}/{}", left + 1, right + 1);
+        Ok(())
+    }
+
+    /// Device channels the stereo output is written to (left, right), 0-based
+    pub fn get_output_channels(&self) -> (u16, u16) {
+        self.output_routing.get()
+    }
+
+    /// Total channels the output stream was opened with
+    pub fn output_channel_count(&self) -> u16 {
+        self.output_routing.device_channels()
+    }
+
+    /// Name of the output device the engine is running on
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Get the engine's output configuration
+    pub fn config(&self) -> &AudioConfig {
+        &self.config
+    }
+
+    pub fn get_state(&self) -> EngineState {
+        if self.shared.is_playing.load(Ordering::SeqCst) {
+            EngineState::Playing
+        } else {
+            EngineState::Stopped
+        }
+    }
+
+    // Plugin methods
+
+    /// Load a plugin from a .clap or .vst3 bundle path
+    pub fn load_plugin(&self, path: &Path) -> Result<(), String> {
+        log::info!("Loading plugin from: {:?}", path);
+
+        // Update state to loading
+        *self.shared.plugin_state.write() = PluginState::Loading {
+            path: path.display().to_string(),
+        };
+
+        // Unload existing plugin first
+        self.unload_plugin();
 
-                log::info!("Plugin loaded: {}", name);
+        // Load new plugin with the same max frames the audio callback was sized for
+        match HostedPlugin::load(path, self.sample_rate as f64, self.max_frames) {
+            Ok(plugin) => {
+                self.install_plugin(plugin, path);
                 Ok(())
             }
             Err(e) => {
@@ -646,8 +1812,66 @@ impl AudioEngineHandle {
         }
     }
 
+    /// Start a freshly loaded plugin and make it the active one
+    fn install_plugin(&self, mut plugin: HostedPlugin, path: &Path) {
+        plugin.set_input_mapping(self.get_input_mapping());
+
+        // Start processing
+        if let Err(e) = plugin.start_processing() {
+            log::warn!("Plugin start_processing failed: {}", e);
+        }
+
+        let name = plugin.name().to_string();
+        let has_editor = plugin.has_gui();
+        let detected = plugin.is_instrument();
+        let path_str = path.display().to_string();
+
+        // Instruments must be processed outside playback or they stay silent, so the
+        // type is applied here: the manual override if there is one, else the detected type
+        let is_instrument = self.shared.instrument_override.read().unwrap_or(detected);
+        self.apply_is_instrument(is_instrument);
+
+        // Get MIDI queue reference before storing plugin
+        let midi_queue = plugin.midi_queue();
+        self.shared.plugin_latency.store(plugin.latency(), Ordering::Relaxed);
+
+        *self.shared.plugin_instance.write() = Some(plugin);
+        self.shared.plugin_process_error.store(false, Ordering::SeqCst);
+        // Store MIDI queue reference separately for lock-free access
+        *self.shared.midi_queue.write() = Some(midi_queue);
+        *self.shared.plugin_state.write() = PluginState::Active {
+            name: name.clone(),
+            path: path_str,
+            has_editor,
+            is_instrument,
+        };
+
+        self.reset_analysis();
+        log::info!("Plugin loaded: {}", name);
+    }
+
+    /// Send all-notes-off to the plugin and wait for the audio thread to run one silent
+    /// process cycle, so held voices are released before the plugin is stopped or torn down
+    fn release_plugin_voices(&self) {
+        let Some(queue) = self.shared.midi_queue.read().clone() else {
+            return;
+        };
+        queue.all_notes_off();
+
+        self.shared.voice_release_pending.store(true, Ordering::SeqCst);
+        // Bounded wait - the stream may be stalled or the device gone
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(100);
+        while self.shared.voice_release_pending.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        self.shared.voice_release_pending.store(false, Ordering::SeqCst);
+    }
+
     /// Unload the current plugin
     pub fn unload_plugin(&self) {
+        // Let the plugin release any held notes before it goes away
+        self.release_plugin_voices();
+
         // Clear MIDI queue reference first (allows immediate MIDI rejection)
         *self.shared.midi_queue.write() = None;
 
@@ -656,16 +1880,177 @@ impl AudioEngineHandle {
         self.shared.perf_samples_processed.store(0, Ordering::Relaxed);
 
         let mut plugin_lock = self.shared.plugin_instance.write();
+        self.shared.plugin_latency.store(0, Ordering::Relaxed);
         if let Some(mut plugin) = plugin_lock.take() {
             plugin.stop_processing();
             log::info!("Plugin unloaded");
         }
+        self.shared.plugin_process_error.store(false, Ordering::SeqCst);
+        // Nothing is sounding anymore - drop the keyboard highlight too
+        activity::clear_active_notes(NoteSource::Command);
         *self.shared.plugin_state.write() = PluginState::Unloaded;
+        drop(plugin_lock);
+        self.reset_analysis();
+    }
+
+    /// Save the loaded plugin's state (CLAP state extension)
+    /// Returns None if no plugin is loaded or the plugin doesn't support state
+    pub fn save_plugin_state(&self) -> Option<Vec<u8>> {
+        let plugin_lock = self.shared.plugin_instance.read();
+        match plugin_lock.as_ref()?.save_state() {
+            Ok(state) => Some(state),
+            Err(e) => {
+                log::warn!("Could not save plugin state: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Restore previously saved state into the loaded plugin
+    pub fn restore_plugin_state(&self, state: &[u8]) -> Result<(), String> {
+        let mut plugin_lock = self.shared.plugin_instance.write();
+        let plugin = plugin_lock.as_mut().ok_or_else(|| "No plugin loaded".to_string())?;
+        plugin.load_state(state)
+    }
+
+    /// Write the loaded plugin's state to a file, creating its parent directory
+    ///
+    /// Returns false without touching the file when no plugin is loaded or the plugin
+    /// has no state to save (e.g. a CLAP plugin without the state extension).
+    pub fn save_plugin_state_to_file(&self, path: &Path) -> Result<bool, String> {
+        let state = {
+            let plugin_lock = self.shared.plugin_instance.read();
+            let Some(plugin) = plugin_lock.as_ref() else {
+                return Ok(false);
+            };
+            match plugin.save_state() {
+                Ok(state) => state,
+                Err(e) => {
+                    log::info!("Not saving state of {}: {}", plugin.name(), e);
+                    return Ok(false);
+                }
+            }
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(path, &state)
+            .map_err(|e| format!("Failed to write plugin state to {}: {}", path.display(), e))?;
+        log::info!("Saved {} bytes of plugin state to {}", state.len(), path.display());
+        Ok(true)
+    }
+
+    /// Restore the loaded plugin's state from a file written by save_plugin_state_to_file
+    pub fn load_plugin_state_from_file(&self, path: &Path) -> Result<(), String> {
+        let state = std::fs::read(path)
+            .map_err(|e| format!("Failed to read plugin state from {}: {}", path.display(), e))?;
+        self.restore_plugin_state(&state)
+    }
+
+    /// Restore state through the plugin's pending-state channel (used after a hot reload)
+    ///
+    /// The state is applied by the audio thread right before its next process call, so
+    /// the first processed block already has it. If the audio thread doesn't pick it up
+    /// shortly (nothing playing), it's applied here instead. Errors if the plugin
+    /// rejected the state, e.g. because its parameter layout changed.
+    pub fn restore_plugin_state_pending(&self, state: Vec<u8>) -> Result<(), String> {
+        let counts = |plugin: &HostedPlugin| {
+            (plugin.get_state_apply_count(), plugin.get_state_failure_count())
+        };
+
+        let before = {
+            let plugin_lock = self.shared.plugin_instance.read();
+            let plugin = plugin_lock.as_ref().ok_or_else(|| "No plugin loaded".to_string())?;
+            let before = counts(plugin);
+            plugin.queue_state(state)?;
+            before
+        };
+
+        let mut after = before;
+        for _ in 0..20 {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            if let Some(plugin) = self.shared.plugin_instance.read().as_ref() {
+                after = counts(plugin);
+            }
+            if after != before {
+                break;
+            }
+        }
+
+        if after == before {
+            let mut plugin_lock = self.shared.plugin_instance.write();
+            let plugin = plugin_lock.as_mut().ok_or_else(|| "No plugin loaded".to_string())?;
+            plugin.apply_pending_state();
+            after = counts(plugin);
+        }
+        if let Some(plugin) = self.shared.plugin_instance.read().as_ref() {
+            plugin.sync_controller_state();
+        }
+
+        if after.1 != before.1 {
+            Err("Plugin rejected the saved state".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Capture the loaded plugin's path and state so it can be restored into another engine
+    /// Returns None if no plugin is active
+    pub fn snapshot_plugin(&self) -> Option<PluginSnapshot> {
+        let path = match &*self.shared.plugin_state.read() {
+            PluginState::Active { path, .. } => path.clone(),
+            _ => return None,
+        };
+        Some(PluginSnapshot {
+            path,
+            state: self.save_plugin_state(),
+            is_instrument: self.is_instrument(),
+            instrument_override: self.instrument_override(),
+        })
+    }
+
+    /// Load a captured plugin into this engine and restore its state.
+    /// The plugin is activated at this engine's sample rate and max frames.
+    pub fn restore_plugin_snapshot(&self, snapshot: &PluginSnapshot) -> Result<(), String> {
+        *self.shared.instrument_override.write() = snapshot.instrument_override;
+        self.load_plugin(Path::new(&snapshot.path))?;
+        if let Some(state) = &snapshot.state {
+            // A stale state blob shouldn't fail the reload - the plugin just starts from defaults
+            if let Err(e) = self.restore_plugin_state(state) {
+                log::warn!("Failed to restore plugin state: {}", e);
+            }
+        }
+        Ok(())
     }
 
     /// Get the current plugin state
+    /// A plugin that failed during processing is reported as Error
     pub fn get_plugin_state(&self) -> PluginState {
-        self.shared.plugin_state.read().clone()
+        let state = self.shared.plugin_state.read().clone();
+        match state {
+            PluginState::Active { name, .. } if self.plugin_process_failed() => PluginState::Error {
+                message: format!(
+                    "Plugin '{}' reported a processing error and has been bypassed. Reload to try again.",
+                    name
+                ),
+            },
+            state => state,
+        }
+    }
+
+    /// Path of the loaded (or reloading) plugin, including one that failed during processing
+    pub fn loaded_plugin_path(&self) -> Option<String> {
+        match &*self.shared.plugin_state.read() {
+            PluginState::Active { path, .. } | PluginState::Reloading { path } => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    /// Check if the loaded plugin returned a processing error and is being bypassed
+    pub fn plugin_process_failed(&self) -> bool {
+        self.shared.plugin_process_error.load(Ordering::SeqCst)
     }
 
     /// Check if a plugin is loaded
@@ -694,6 +2079,70 @@ impl AudioEngineHandle {
             .unwrap_or(false)
     }
 
+    /// Audio ports declared by the loaded plugin (None if no plugin is loaded)
+    pub fn get_plugin_ports(&self) -> Option<Vec<AudioPortInfo>> {
+        self.shared
+            .plugin_instance
+            .read()
+            .as_ref()
+            .map(|p| p.audio_ports().to_vec())
+    }
+
+    /// Set how the stereo bus is mapped onto the plugin's main input. Applies to the
+    /// loaded plugin right away and to plugins loaded later.
+    pub fn set_input_mapping(&self, mapping: InputMapping) {
+        *self.shared.input_mapping.lock() = mapping;
+        if let Some(plugin) = self.shared.plugin_instance.write().as_mut() {
+            plugin.set_input_mapping(mapping);
+        }
+    }
+
+    pub fn get_input_mapping(&self) -> InputMapping {
+        *self.shared.input_mapping.lock()
+    }
+
+    /// Bypass the loaded plugin (and plugins loaded later) so the input passes through
+    /// unprocessed. Toggling crossfades between the plugin output and the input.
+    pub fn set_plugin_bypass(&self, bypass: bool) {
+        self.shared.plugin_bypass.store(bypass, Ordering::Relaxed);
+    }
+
+    pub fn is_plugin_bypassed(&self) -> bool {
+        self.shared.plugin_bypass.load(Ordering::Relaxed)
+    }
+
+    /// Blend the plugin output with the dry input: 0.0 = fully dry, 1.0 = fully wet (default).
+    /// The dry signal is delayed by the plugin latency so the two line up.
+    pub fn set_plugin_mix(&self, mix: f32) {
+        if mix.is_finite() {
+            self.shared.plugin_mix.store(f32_to_u32(mix.clamp(0.0, 1.0)), Ordering::Relaxed);
+        }
+    }
+
+    pub fn get_plugin_mix(&self) -> f32 {
+        u32_to_f32(self.shared.plugin_mix.load(Ordering::Relaxed))
+    }
+
+    /// Parameters of the loaded plugin with their current values (empty if none is loaded)
+    pub fn get_plugin_params(&self) -> Vec<PluginParam> {
+        self.shared
+            .plugin_instance
+            .read()
+            .as_ref()
+            .map(|p| p.get_params())
+            .unwrap_or_default()
+    }
+
+    /// Note names the loaded plugin reports for keyboard labeling, as (key, name)
+    pub fn get_plugin_note_names(&self) -> Vec<(i16, String)> {
+        self.shared
+            .plugin_instance
+            .read()
+            .as_ref()
+            .map(|p| p.get_note_names())
+            .unwrap_or_default()
+    }
+
     /// Open the plugin's editor window
     ///
     /// Uses stored position if available, otherwise centers the window.
@@ -775,6 +2224,40 @@ impl AudioEngineHandle {
         result
     }
 
+    /// Open the plugin's editor off-screen for a thumbnail capture, unless it's already open.
+    /// Returns whether it was opened here (and so has to be closed after the capture).
+    ///
+    /// Call on the main thread: the editor dispatches window work there, which would
+    /// deadlock against plugin_idle if the plugin lock were held from another thread.
+    pub fn open_plugin_editor_for_capture(&self) -> Result<bool, String> {
+        if self.is_plugin_editor_open() {
+            return Ok(false);
+        }
+        let mut plugin_lock = self.shared.plugin_instance.write();
+        let plugin = plugin_lock.as_mut().ok_or_else(|| "No plugin loaded".to_string())?;
+        plugin.open_editor_at(Some(THUMBNAIL_EDITOR_POSITION))?;
+        Ok(true)
+    }
+
+    /// Capture the plugin's editor as PNG data (for project thumbnails), closing it
+    /// afterwards if `close` is set. Call on the main thread, like open_plugin_editor_for_capture.
+    pub fn capture_plugin_editor(&self, close: bool) -> Result<Vec<u8>, String> {
+        let result = self
+            .shared
+            .plugin_instance
+            .read()
+            .as_ref()
+            .ok_or_else(|| "Plugin was unloaded during capture".to_string())?
+            .capture_editor_png();
+        if close {
+            // Closed directly so the off-screen position isn't remembered as the user's
+            if let Some(plugin) = self.shared.plugin_instance.write().as_mut() {
+                plugin.close_editor();
+            }
+        }
+        result
+    }
+
     /// Flush plugin parameters and handle callbacks
     /// This should be called periodically when the editor is open to ensure
     /// GUI parameter changes are processed even when audio isn't playing.
@@ -798,37 +2281,98 @@ impl AudioEngineHandle {
 
     /// Send a MIDI note on event to the loaded plugin
     /// Uses lock-free queue access - never blocks the audio thread
+    /// The note only shows up as active if it was actually queued.
     #[inline]
     pub fn midi_note_on(&self, note: u8, velocity: u8) {
         // Use the separate midi_queue reference to avoid plugin lock
-        if let Some(queue) = self.shared.midi_queue.read().as_ref() {
-            queue.note_on(note, velocity);
+        let queued = self.shared.midi_queue.read().as_ref().is_some_and(|queue| queue.note_on(note, velocity));
+        if queued {
+            activity::record_note_on(note, velocity, NoteSource::Command);
         }
     }
 
-    /// Send a MIDI note off event to the loaded plugin
+    /// Send a MIDI note off event to the loaded plugin (release velocity 0)
     /// Uses lock-free queue access - never blocks the audio thread
     #[inline]
     pub fn midi_note_off(&self, note: u8) {
+        self.midi_note_off_vel(note, 0);
+    }
+
+    /// Send a MIDI note off event with a release velocity (0-127) to the loaded plugin
+    #[inline]
+    pub fn midi_note_off_vel(&self, note: u8, velocity: u8) {
+        let queued = self
+            .shared
+            .midi_queue
+            .read()
+            .as_ref()
+            .is_some_and(|queue| queue.note_off_with_velocity(note, velocity));
+        if queued {
+            activity::record_note_off(note, NoteSource::Command);
+        }
+    }
+
+    /// Send a MIDI control change to the loaded plugin
+    /// Uses lock-free queue access - never blocks the audio thread
+    #[inline]
+    pub fn midi_cc(&self, controller: u8, value: u8, channel: u8) {
         if let Some(queue) = self.shared.midi_queue.read().as_ref() {
-            queue.note_off(note);
+            queue.control_change(controller, value, channel);
         }
     }
 
-    /// Send all notes off to the loaded plugin
+    /// Send a MIDI pitch bend (14-bit, center 8192) to the loaded plugin
+    /// Uses lock-free queue access - never blocks the audio thread
     #[inline]
-    pub fn midi_all_notes_off(&self) {
+    pub fn midi_pitch_bend(&self, value: u16, channel: u8) {
         if let Some(queue) = self.shared.midi_queue.read().as_ref() {
-            queue.all_notes_off();
+            queue.pitch_bend(value, channel);
         }
     }
 
-    /// Set whether the loaded plugin is an instrument (vs effect)
-    /// Instrument plugins are processed even when not "playing" for MIDI input
+    /// Send all notes off to the loaded plugin
+    #[inline]
+    pub fn midi_all_notes_off(&self) {
+        if let Some(queue) = self.shared.midi_queue.read().as_ref() {
+            queue.all_notes_off();
+        }
+        activity::clear_active_notes(NoteSource::Command);
+    }
+
+    /// Set whether the loaded plugin is an instrument (vs effect)
+    /// Instrument plugins are processed even when not "playing" for MIDI input.
+    /// This overrides detection and sticks through hot reloads until cleared.
     pub fn set_is_instrument(&self, is_instrument: bool) {
+        *self.shared.instrument_override.write() = Some(is_instrument);
+        self.apply_is_instrument(is_instrument);
+        if let PluginState::Active { is_instrument: reported, .. } = &mut *self.shared.plugin_state.write() {
+            *reported = is_instrument;
+        }
+    }
+
+    /// Drop the manual effect/instrument override; the next load uses the detected type
+    pub fn clear_instrument_override(&self) {
+        *self.shared.instrument_override.write() = None;
+    }
+
+    /// The manual effect/instrument override, if one is set
+    pub fn instrument_override(&self) -> Option<bool> {
+        *self.shared.instrument_override.read()
+    }
+
+    fn apply_is_instrument(&self, is_instrument: bool) {
+        // No longer processed outside playback - release voices while we still are
+        if !is_instrument && self.is_instrument() {
+            self.release_plugin_voices();
+        }
         self.shared.is_instrument_plugin.store(is_instrument, Ordering::SeqCst);
     }
 
+    /// Check whether the loaded plugin is flagged as an instrument
+    pub fn is_instrument(&self) -> bool {
+        self.shared.is_instrument_plugin.load(Ordering::SeqCst)
+    }
+
     /// Get the current plugin's MIDI queue (for pattern player)
     /// Uses the separate midi_queue reference to avoid plugin lock
     pub fn get_plugin_midi_queue(&self) -> Option<Arc<MidiEventQueue>> {
@@ -922,6 +2466,20 @@ impl AudioEngineHandle {
     }
 }
 
+/// Live input resampling status (see AudioEngineHandle::set_input_latency)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveInputLatency {
+    /// True when the input device's rate differs from the output rate
+    pub resampling: bool,
+    /// Resampler chunk size in samples
+    pub chunk_size: usize,
+    /// Latency the resampler adds in ms (0 when not resampling)
+    pub added_latency_ms: f32,
+    /// Native rate of the capturing input device (None if capture isn't running)
+    pub input_sample_rate: Option<u32>,
+    pub output_sample_rate: u32,
+}
+
 /// Plugin performance metrics (only populated when monitoring is enabled)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginPerformance {
@@ -939,683 +2497,1134 @@ pub struct PluginPerformance {
     pub per_sample_ns: f32,
 }
 
-/// The main audio engine
-pub struct AudioEngine {
-    _stream: cpal::Stream,
-    handle: AudioEngineHandle,
-    config: AudioConfig,
+/// Renders the engine's output one block at a time: source, plugin, monitoring, limiter,
+/// volume and metering. Owned by the output callback (or a test), never shared.
+pub struct AudioProcessor {
+    shared: Arc<SharedState>,
+    // Max frames per block - the buffers below are sized for this many stereo frames
+    max_frames: usize,
+    // Pre-allocated buffers for plugin processing (avoid allocation in audio callback)
+    input_buffer: Vec<f32>,
+    output_buffer: Vec<f32>,
+    // Pre-allocated buffers for metering/analysis (avoid allocation in audio callback)
+    pre_limited_buffer: Vec<f32>,
+    mono_output_buffer: Vec<f32>,
+    mono_input_buffer: Vec<f32>,
+    // Dry input delayed by the plugin latency (for dry/difference monitoring)
+    dry_delay: DelayLine,
+    dry_delay_in_use: bool,
+    aligned_dry_buffer: Vec<f32>,
+    sample_rate: u32,
+    // Frequency weighting for the weighted output level (rebuilt when the mode changes)
+    weighting_filter: WeightingFilter,
+    // BS.1770 loudness of the output
+    loudness_meter: LoudnessMeter,
+    // Spectrum analyzers for visualization (input = pre-FX, output = post-FX)
+    spectrum_analyzer: SpectrumAnalyzer,
+    spectrum_analyzer_input: SpectrumAnalyzer,
+    // Counter for throttling spectrum updates (every N callbacks)
+    spectrum_update_counter: u32,
+    // Stereo analyzers for stereo imaging visualization (input = pre-FX, output = post-FX)
+    stereo_analyzer: StereoAnalyzer,
+    stereo_analyzer_input: StereoAnalyzer,
+    current_correlation_smoothing: f32,
+    // Output dither state (RNG seeded once, no per-sample allocation)
+    dither: Dither,
+    // DC blocker pole radius for this sample rate
+    dc_block_r: f32,
+    // Loudness matcher for plugin output vs dry input
+    gain_matcher: GainMatcher,
+    // Slow makeup gain for auto level (same measurement, longer time constant)
+    auto_leveler: GainMatcher,
+    // Ghost kick for the plugin's sidechain input (rendered into its own buffer)
+    ghost_kick: GhostKick,
+    ghost_kick_buffer: Vec<f32>,
+    // Bypass crossfade position in samples (0 = plugin, CROSSFADE_SAMPLES = fully bypassed)
+    bypass_fade_position: u32,
+    // Wet/dry mix applied in the last block (ramps to the requested mix over one block)
+    current_mix: f32,
 }
 
-impl AudioEngine {
-    /// Create and start a new audio engine
-    pub fn new(device_name: Option<&str>, config: AudioConfig) -> Result<Self, String> {
-        let device = get_output_device(device_name)?;
-        let stream_config = get_supported_config(&device, &config)?;
+impl AudioProcessor {
+    fn new(shared: Arc<SharedState>, sample_rate: u32, max_frames: usize) -> Self {
+        // For stereo interleaved, we need max_frames * 2 samples
+        let max_buffer_size = max_frames * 2; // 8192 at the default size
+        Self {
+            shared,
+            max_frames,
+            input_buffer: vec![0.0f32; max_buffer_size],
+            output_buffer: vec![0.0f32; max_buffer_size],
+            pre_limited_buffer: vec![0.0f32; max_buffer_size],
+            mono_output_buffer: vec![0.0f32; max_frames],
+            mono_input_buffer: vec![0.0f32; max_frames],
+            dry_delay: DelayLine::new(MAX_DELAY_FRAMES),
+            dry_delay_in_use: false,
+            aligned_dry_buffer: vec![0.0f32; max_buffer_size],
+            sample_rate,
+            weighting_filter: WeightingFilter::new(MeteringWeighting::None, sample_rate),
+            loudness_meter: LoudnessMeter::new(sample_rate),
+            spectrum_analyzer: SpectrumAnalyzer::new(sample_rate),
+            spectrum_analyzer_input: SpectrumAnalyzer::new(sample_rate),
+            spectrum_update_counter: 0,
+            stereo_analyzer: StereoAnalyzer::with_sample_rate(sample_rate),
+            stereo_analyzer_input: StereoAnalyzer::with_sample_rate(sample_rate),
+            current_correlation_smoothing: DEFAULT_CORRELATION_SMOOTHING_MS,
+            dither: Dither::new(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.subsec_nanos())
+                    .unwrap_or(1),
+            ),
+            dc_block_r: DcBlocker::coefficient(sample_rate),
+            gain_matcher: GainMatcher::new(sample_rate),
+            auto_leveler: GainMatcher::with_window(sample_rate, AUTO_LEVEL_DEFAULT_TIME_CONSTANT),
+            ghost_kick: GhostKick::new(sample_rate),
+            ghost_kick_buffer: vec![0.0f32; max_buffer_size],
+            bypass_fade_position: 0,
+            current_mix: 1.0,
+        }
+    }
 
-        let sample_rate = stream_config.sample_rate.0;
-        let channels = stream_config.channels as usize;
+    /// Blend the plugin output in `data` with the latency-aligned dry input, ramping from
+    /// the previous block's mix to `mix`. Non-finite plugin samples are zeroed first so a
+    /// misbehaving plugin can't poison the dry signal.
+    fn apply_mix(&mut self, data: &mut [f32], channels: usize, mix: f32) {
+        let start = self.current_mix;
+        let frames = (data.len() / channels).max(1) as f32;
+        for (i, (frame, dry)) in data
+            .chunks_mut(channels)
+            .zip(self.aligned_dry_buffer.chunks(channels))
+            .enumerate()
+        {
+            let wet_gain = start + (mix - start) * ((i + 1) as f32 / frames);
+            for (sample, &dry) in frame.iter_mut().zip(dry) {
+                let wet = if sample.is_finite() { *sample } else { 0.0 };
+                *sample = wet * wet_gain + dry * (1.0 - wet_gain);
+            }
+        }
+        self.current_mix = mix;
+    }
 
-        log::info!(
-            "Starting audio engine: {} Hz, {} channels",
-            sample_rate,
-            channels
-        );
+    /// Crossfade the plugin output in `data` with the latency-aligned dry input, moving the
+    /// bypass fade toward fully bypassed (or back toward the plugin) at the hot reload rate
+    fn apply_bypass_fade(&mut self, data: &mut [f32], channels: usize, bypass: bool) {
+        for (frame, dry) in data.chunks_mut(channels).zip(self.aligned_dry_buffer.chunks(channels)) {
+            self.bypass_fade_position = if bypass {
+                (self.bypass_fade_position + channels as u32).min(CROSSFADE_SAMPLES)
+            } else {
+                self.bypass_fade_position.saturating_sub(channels as u32)
+            };
+            let dry_gain = crossfade_gain(self.bypass_fade_position);
+            for (sample, &dry) in frame.iter_mut().zip(dry) {
+                *sample = *sample * (1.0 - dry_gain) + dry * dry_gain;
+            }
+        }
+    }
 
-        // Create shared state
-        // Initialize spectrum bands, waveform, and stereo arrays with zeros
-        const INIT_BAND: AtomicU32 = AtomicU32::new(0);
-        const INIT_WAVEFORM: AtomicU32 = AtomicU32::new(0);
-        const INIT_STEREO: AtomicU32 = AtomicU32::new(0);
-        const INIT_PEAK: AtomicU32 = AtomicU32::new(0);
-        let shared = Arc::new(SharedState {
-            input_source: RwLock::new(InputSource::None),
-            signal_generator: RwLock::new(SignalGenerator::new(sample_rate)),
-            sample_player: RwLock::new(SamplePlayer::new()),
-            is_playing: AtomicBool::new(false),
-            is_looping: AtomicBool::new(true),
-            master_volume: AtomicU32::new(f32_to_u32(0.75)), // Default 75% volume
-            output_level_left: AtomicU32::new(f32_to_u32(0.0)),
-            output_level_right: AtomicU32::new(f32_to_u32(0.0)),
-            input_level_left: AtomicU32::new(f32_to_u32(0.0)),
-            input_level_right: AtomicU32::new(f32_to_u32(0.0)),
-            live_paused: AtomicBool::new(false),
-            live_resampler: Mutex::new(None),
-            clipping_left: AtomicBool::new(false),
-            clipping_right: AtomicBool::new(false),
-            spectrum_bands: [INIT_BAND; NUM_BANDS],
-            spectrum_bands_input: [INIT_BAND; NUM_BANDS],
-            waveform_buffer_left: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
-            waveform_buffer_right: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
-            waveform_write_pos: AtomicU32::new(0),
-            waveform_peak_left: INIT_PEAK,
-            waveform_peak_right: INIT_PEAK,
-            // Input waveform (pre-FX) for comparison
-            waveform_buffer_input_left: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
-            waveform_buffer_input_right: [INIT_WAVEFORM; WAVEFORM_SAMPLES],
-            waveform_input_write_pos: AtomicU32::new(0),
-            waveform_input_peak_left: INIT_PEAK,
-            waveform_input_peak_right: INIT_PEAK,
-            // Output stereo (post-FX)
-            stereo_positions: [INIT_STEREO; STEREO_HISTORY_SIZE * 2],
-            stereo_correlation: AtomicU32::new(f32_to_u32(1.0)), // Start at mono
-            // Input stereo (pre-FX) for comparison
-            stereo_positions_input: [INIT_STEREO; STEREO_HISTORY_SIZE * 2],
-            stereo_correlation_input: AtomicU32::new(f32_to_u32(1.0)), // Start at mono
-            plugin_instance: RwLock::new(None),
-            plugin_state: RwLock::new(PluginState::Unloaded),
-            midi_queue: RwLock::new(None),
-            is_instrument_plugin: AtomicBool::new(false),
-            crossfade_state: AtomicU8::new(CROSSFADE_NONE),
-            crossfade_position: AtomicU32::new(0),
-            last_editor_position: RwLock::new(None),
-            // Performance monitoring disabled by default (zero overhead when off)
-            perf_monitoring_enabled: AtomicBool::new(false),
-            perf_plugin_process_ns: AtomicU64::new(0),
-            perf_samples_processed: AtomicU32::new(0),
-        });
+    /// Render one block of interleaved output (`channels` is 1 or 2) into `data`
+    pub fn process_block(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.clamp(1, 2);
+        let max_buffer_size = self.max_frames * channels;
 
-        let shared_clone = Arc::clone(&shared);
+        let is_playing = self.shared.is_playing.load(Ordering::SeqCst);
+        let block_frames = (data.len() / channels) as u32;
+        let block_start = self.shared.sample_clock.fetch_add(block_frames as u64, Ordering::Relaxed);
+        let play_start = if is_playing {
+            self.shared.play_clock.fetch_add(block_frames as u64, Ordering::Relaxed)
+        } else {
+            self.shared.play_clock.load(Ordering::Relaxed)
+        };
+        // Use try_read to avoid blocking audio thread if main thread holds write lock
+        // during plugin load/unload. If we can't read, assume no plugin.
+        let has_plugin = self.shared.plugin_instance
+            .try_read()
+            .map(|guard| guard.is_some())
+            .unwrap_or(false);
+        let is_instrument = self.shared.is_instrument_plugin.load(Ordering::SeqCst);
+        let measuring = self.shared.response_measuring.load(Ordering::Acquire);
+
+        // Voice release staged by stop/unload: feed the plugin one silent cycle so it
+        // consumes the queued note-offs, and keep the device output silent meanwhile
+        if self.shared.voice_release_pending.load(Ordering::Acquire) && data.len() <= max_buffer_size {
+            if let Some(mut plugin_lock) = self.shared.plugin_instance.try_write() {
+                if let Some(ref mut plugin) = *plugin_lock {
+                    self.input_buffer[..data.len()].fill(0.0);
+                    let _ = plugin.process(&self.input_buffer[..data.len()], &mut self.output_buffer[..data.len()]);
+                }
+                self.shared.voice_release_pending.store(false, Ordering::Release);
+            }
+            data.fill(0.0);
+            self.shared.output_level_left.store(f32_to_u32(0.0), Ordering::Relaxed);
+            self.shared.output_level_right.store(f32_to_u32(0.0), Ordering::Relaxed);
+            self.shared.weighted_output_level.store(f32_to_u32(0.0), Ordering::Relaxed);
+            return;
+        }
 
-        // Level smoothing factor
-        let level_smoothing = 0.1f32;
+        // For instrument plugins, we need to process even when not "playing"
+        // because they generate sound from MIDI input, not audio input.
+        // For effect plugins, respect the is_playing flag normally (a response
+        // measurement runs regardless).
+        if !(is_playing || measuring || (has_plugin && is_instrument)) {
+            // Not playing, and either no plugin or plugin is an effect - output silence
+            for sample in data.iter_mut() {
+                *sample = 0.0;
+            }
+            self.shared.output_level_left.store(f32_to_u32(0.0), Ordering::Relaxed);
+            self.shared.output_level_right.store(f32_to_u32(0.0), Ordering::Relaxed);
+            self.shared.weighted_output_level.store(f32_to_u32(0.0), Ordering::Relaxed);
+            return;
+        }
 
-        // Pre-allocate buffers for plugin processing (avoid allocation in audio callback)
-        // IMPORTANT: This must match the max_frames used in load_plugin (4096)
-        // For stereo interleaved, we need max_frames * 2 samples
-        let max_frames = 4096usize;
-        let max_buffer_size = max_frames * channels; // 8192 for stereo
-        let mut input_buffer = vec![0.0f32; max_buffer_size];
-        let mut output_buffer = vec![0.0f32; max_buffer_size];
-        // Pre-allocate buffers for metering/analysis (avoid allocation in audio callback)
-        let mut pre_limited_buffer = vec![0.0f32; max_buffer_size];
-        let mut mono_output_buffer = vec![0.0f32; max_frames];
-        let mut mono_input_buffer = vec![0.0f32; max_frames];
-
-        // Create spectrum analyzers for visualization (input = pre-FX, output = post-FX)
-        let mut spectrum_analyzer = SpectrumAnalyzer::new(sample_rate);
-        let mut spectrum_analyzer_input = SpectrumAnalyzer::new(sample_rate);
-        // Counter for throttling spectrum updates (every N callbacks)
-        let mut spectrum_update_counter = 0u32;
-
-        // Create stereo analyzers for stereo imaging visualization (input = pre-FX, output = post-FX)
-        let mut stereo_analyzer = StereoAnalyzer::new();
-        let mut stereo_analyzer_input = StereoAnalyzer::new();
+        // Lock-free read of the active source kind - never fails, so a source
+        // change can't drop a buffer to silence mid-stream
+        let input_source = self.shared.input_source_kind.load(Ordering::Acquire);
+
+        // Set when live input was metered here and then gated off by the monitor switch
+        let mut live_input_muted = false;
+
+        // Generate input samples
+        match input_source {
+            SOURCE_SIGNAL => {
+                let mut generator = self.shared.signal_generator.write();
+                let transport_bpm = u32_to_f32(self.shared.transport_bpm.load(Ordering::Relaxed));
+                generator.sync_to_transport(transport_bpm, play_start);
+                for chunk in data.chunks_mut(channels) {
+                    let sample = generator.next_sample();
+                    chunk[0] = sample.left;
+                    if channels > 1 {
+                        chunk[1] = sample.right;
+                    }
+                }
+            }
+            SOURCE_SAMPLE => {
+                let mut player = self.shared.sample_player.write();
+                for chunk in data.chunks_mut(channels) {
+                    let sample = player.next_sample();
+                    chunk[0] = sample.left;
+                    if channels > 1 {
+                        chunk[1] = sample.right;
+                    }
+                }
+            }
+            SOURCE_LIVE => {
+                // Check if paused - if so, output silence
+                let is_paused = self.shared.live_paused.load(Ordering::SeqCst);
+                if is_paused {
+                    for sample in data.iter_mut() {
+                        *sample = 0.0;
+                    }
+                } else if let Some(input_handle) = crate::audio::input::get_input_handle() {
+                    let mut peak_left = 0.0f32;
+                    let mut peak_right = 0.0f32;
 
-        // Build the output stream
-        let stream = device
-            .build_output_stream(
-                &stream_config,
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    let is_playing = shared_clone.is_playing.load(Ordering::SeqCst);
-                    // Use try_read to avoid blocking audio thread if main thread holds write lock
-                    // during plugin load/unload. If we can't read, assume no plugin.
-                    let has_plugin = shared_clone.plugin_instance
-                        .try_read()
-                        .map(|guard| guard.is_some())
-                        .unwrap_or(false);
-                    let is_instrument = shared_clone.is_instrument_plugin.load(Ordering::SeqCst);
-
-                    // For instrument plugins, we need to process even when not "playing"
-                    // because they generate sound from MIDI input, not audio input.
-                    // For effect plugins, respect the is_playing flag normally.
-                    if !is_playing && !(has_plugin && is_instrument) {
-                        // Not playing, and either no plugin or plugin is an effect - output silence
-                        for sample in data.iter_mut() {
-                            *sample = 0.0;
+                    // Check if we need to resample
+                    let mut resampler_guard = self.shared.live_resampler.lock();
+
+                    if let Some(ref mut resampler) = *resampler_guard {
+                        // Resampling mode: read input samples, resample, then output
+                        let frames_needed = data.len() / channels;
+
+                        // Read enough input samples and feed to resampler
+                        // We may need to read more samples than output frames due to rate difference
+                        let available = input_handle.available_samples();
+                        for _ in 0..available.min(frames_needed * 2) {
+                            let sample = input_handle.read_sample();
+                            resampler.push_input(sample.left, sample.right);
+                            // Track input levels from raw input
+                            peak_left = peak_left.max(sample.left.abs());
+                            peak_right = peak_right.max(sample.right.abs());
                         }
-                        shared_clone.output_level_left.store(f32_to_u32(0.0), Ordering::Relaxed);
-                        shared_clone.output_level_right.store(f32_to_u32(0.0), Ordering::Relaxed);
-                        return;
-                    }
 
-                    // Use try_read for input_source to avoid blocking during source changes
-                    // If we can't read, use None which outputs silence for this callback
-                    let input_source = shared_clone.input_source
-                        .try_read()
-                        .map(|guard| guard.clone())
-                        .unwrap_or(InputSource::None);
-
-                    // Generate input samples
-                    match input_source {
-                        InputSource::Signal { .. } => {
-                            let mut generator = shared_clone.signal_generator.write();
-                            for chunk in data.chunks_mut(channels) {
-                                let sample = generator.next_sample();
-                                chunk[0] = sample.left;
-                                if channels > 1 {
-                                    chunk[1] = sample.right;
-                                }
+                        // Process resampler to generate output
+                        while resampler.available_output() < frames_needed {
+                            if !resampler.process() {
+                                break; // Not enough input yet
                             }
                         }
-                        InputSource::Sample { .. } => {
-                            let mut player = shared_clone.sample_player.write();
-                            for chunk in data.chunks_mut(channels) {
-                                let sample = player.next_sample();
+
+                        // Read resampled output
+                        for chunk in data.chunks_mut(channels) {
+                            if let Some(sample) = resampler.pop_output() {
                                 chunk[0] = sample.left;
                                 if channels > 1 {
                                     chunk[1] = sample.right;
                                 }
-                            }
-                        }
-                        InputSource::Live { .. } => {
-                            // Check if paused - if so, output silence
-                            let is_paused = shared_clone.live_paused.load(Ordering::SeqCst);
-                            if is_paused {
-                                for sample in data.iter_mut() {
-                                    *sample = 0.0;
-                                }
-                            } else if let Some(input_handle) = crate::audio::input::get_input_handle() {
-                                let mut peak_left = 0.0f32;
-                                let mut peak_right = 0.0f32;
-
-                                // Check if we need to resample
-                                let mut resampler_guard = shared_clone.live_resampler.lock();
-
-                                if let Some(ref mut resampler) = *resampler_guard {
-                                    // Resampling mode: read input samples, resample, then output
-                                    let frames_needed = data.len() / channels;
-
-                                    // Read enough input samples and feed to resampler
-                                    // We may need to read more samples than output frames due to rate difference
-                                    let available = input_handle.available_samples();
-                                    for _ in 0..available.min(frames_needed * 2) {
-                                        let sample = input_handle.read_sample();
-                                        resampler.push_input(sample.left, sample.right);
-                                        // Track input levels from raw input
-                                        peak_left = peak_left.max(sample.left.abs());
-                                        peak_right = peak_right.max(sample.right.abs());
-                                    }
-
-                                    // Process resampler to generate output
-                                    while resampler.available_output() < frames_needed {
-                                        if !resampler.process() {
-                                            break; // Not enough input yet
-                                        }
-                                    }
-
-                                    // Read resampled output
-                                    for chunk in data.chunks_mut(channels) {
-                                        if let Some(sample) = resampler.pop_output() {
-                                            chunk[0] = sample.left;
-                                            if channels > 1 {
-                                                chunk[1] = sample.right;
-                                            }
-                                        } else {
-                                            // No resampled data available yet, output silence
-                                            chunk[0] = 0.0;
-                                            if channels > 1 {
-                                                chunk[1] = 0.0;
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    // No resampling needed - direct passthrough
-                                    for chunk in data.chunks_mut(channels) {
-                                        let sample = input_handle.read_sample();
-                                        chunk[0] = sample.left;
-                                        if channels > 1 {
-                                            chunk[1] = sample.right;
-                                        }
-                                        // Track input levels
-                                        peak_left = peak_left.max(sample.left.abs());
-                                        peak_right = peak_right.max(sample.right.abs());
-                                    }
-                                }
-
-                                drop(resampler_guard); // Release lock
-                                // Input levels are captured universally after input_buffer copy
                             } else {
-                                // No input handle available, output silence
-                                for sample in data.iter_mut() {
-                                    *sample = 0.0;
+                                // No resampled data available yet, output silence
+                                chunk[0] = 0.0;
+                                if channels > 1 {
+                                    chunk[1] = 0.0;
                                 }
                             }
                         }
-                        InputSource::None => {
-                            for sample in data.iter_mut() {
-                                *sample = 0.0;
+                    } else {
+                        // No resampling needed - direct passthrough
+                        for chunk in data.chunks_mut(channels) {
+                            let sample = input_handle.read_sample();
+                            chunk[0] = sample.left;
+                            if channels > 1 {
+                                chunk[1] = sample.right;
                             }
+                            // Track input levels
+                            peak_left = peak_left.max(sample.left.abs());
+                            peak_right = peak_right.max(sample.right.abs());
                         }
                     }
 
-                    // Process through plugin if loaded
-                    // Debug: log periodically to check plugin routing
-                    static ENGINE_CALL_COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
-                    let engine_count = ENGINE_CALL_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    if engine_count % 1000 == 0 {
-                        log::info!(
-                            "Engine callback #{}: has_plugin={}, data.len()={}, max_buffer_size={}",
-                            engine_count, has_plugin, data.len(), max_buffer_size
-                        );
+                    drop(resampler_guard); // Release lock
+
+                    // Input levels are captured universally after input_buffer copy,
+                    // unless monitoring is off: meter the captured input here, then
+                    // keep it from reaching the plugin and output
+                    if !self.shared.input_monitor.load(Ordering::Relaxed) {
+                        let current = u32_to_f32(self.shared.input_level_left.load(Ordering::Relaxed));
+                        let new_level = current * (1.0 - LEVEL_SMOOTHING) + peak_left * LEVEL_SMOOTHING;
+                        self.shared.input_level_left.store(f32_to_u32(new_level), Ordering::Relaxed);
+                        let current = u32_to_f32(self.shared.input_level_right.load(Ordering::Relaxed));
+                        let new_level = current * (1.0 - LEVEL_SMOOTHING) + peak_right * LEVEL_SMOOTHING;
+                        self.shared.input_level_right.store(f32_to_u32(new_level), Ordering::Relaxed);
+
+                        data.fill(0.0);
+                        live_input_muted = true;
                     }
+                } else {
+                    // No input handle available, output silence
+                    for sample in data.iter_mut() {
+                        *sample = 0.0;
+                    }
+                }
+            }
+            _ => {
+                // SOURCE_NONE - silence
+                for sample in data.iter_mut() {
+                    *sample = 0.0;
+                }
+            }
+        }
 
-                    // ALWAYS apply pending state when we have a plugin, regardless of buffer size
-                    // This is critical for syncing parameter changes from the editor
-                    // Use try_write to avoid blocking audio thread if main thread holds the lock
-                    if has_plugin {
-                        if let Some(mut plugin_lock) = shared_clone.plugin_instance.try_write() {
-                            if let Some(ref mut plugin) = *plugin_lock {
-                                plugin.apply_pending_state();
-                            }
-                        }
-                        // If we can't get the lock, skip this cycle - parameter sync can wait
+        // Frequency response measurement: the sweep replaces the selected source
+        if measuring {
+            if let Some(capture) = self.shared.response_capture.try_lock() {
+                if let Some(ref capture) = *capture {
+                    capture.fill_input(data, channels);
+                }
+            }
+        }
+
+        // Process through plugin if loaded
+        // Debug: log periodically to check plugin routing (debug builds at debug level only)
+        static ENGINE_CALL_COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let engine_count = ENGINE_CALL_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let trace_callback = engine_count % 1000 == 0
+            && cfg!(debug_assertions)
+            && log::log_enabled!(log::Level::Debug);
+        if trace_callback {
+            log::debug!(
+                "Engine callback #{}: has_plugin={}, data.len()={}, max_buffer_size={}",
+                engine_count, has_plugin, data.len(), max_buffer_size
+            );
+        }
+
+        // ALWAYS apply pending state when we have a plugin, regardless of buffer size
+        // This is critical for syncing parameter changes from the editor
+        // Use try_write to avoid blocking audio thread if main thread holds the lock
+        if has_plugin {
+            if let Some(mut plugin_lock) = self.shared.plugin_instance.try_write() {
+                if let Some(ref mut plugin) = *plugin_lock {
+                    plugin.apply_pending_state();
+                }
+            }
+            // If we can't get the lock, skip this cycle - parameter sync can wait
+        }
+
+        // ALWAYS copy input to buffer for pre-FX spectrum analysis
+        // This must happen before plugin processing so we capture the original input
+        if data.len() <= max_buffer_size {
+            self.input_buffer[..data.len()].copy_from_slice(data);
+
+            // ========================================
+            // CAPTURE INPUT (PRE-FX) LEVELS
+            // ========================================
+            // Calculate input peak levels for pre/post comparison
+            let mut input_peak_left = 0.0f32;
+            let mut input_peak_right = 0.0f32;
+
+            for (i, &sample) in data.iter().enumerate() {
+                if !sample.is_finite() {
+                    continue;
+                }
+                let abs_sample = sample.abs();
+                if channels > 1 {
+                    if i % 2 == 0 {
+                        input_peak_left = input_peak_left.max(abs_sample);
+                    } else {
+                        input_peak_right = input_peak_right.max(abs_sample);
                     }
+                } else {
+                    input_peak_left = input_peak_left.max(abs_sample);
+                    input_peak_right = input_peak_left;
+                }
+            }
 
-                    // ALWAYS copy input to buffer for pre-FX spectrum analysis
-                    // This must happen before plugin processing so we capture the original input
-                    if data.len() <= max_buffer_size {
-                        input_buffer[..data.len()].copy_from_slice(data);
-
-                        // ========================================
-                        // CAPTURE INPUT (PRE-FX) LEVELS
-                        // ========================================
-                        // Calculate input peak levels for pre/post comparison
-                        let mut input_peak_left = 0.0f32;
-                        let mut input_peak_right = 0.0f32;
-
-                        for (i, &sample) in data.iter().enumerate() {
-                            if !sample.is_finite() {
-                                continue;
-                            }
-                            let abs_sample = sample.abs();
-                            if channels > 1 {
-                                if i % 2 == 0 {
-                                    input_peak_left = input_peak_left.max(abs_sample);
-                                } else {
-                                    input_peak_right = input_peak_right.max(abs_sample);
+            // Update input levels with smoothing (lock-free using atomics)
+            // (already done for muted live input, which is silent by now)
+            if !live_input_muted {
+                let current = u32_to_f32(self.shared.input_level_left.load(Ordering::Relaxed));
+                let new_level = current * (1.0 - LEVEL_SMOOTHING) + input_peak_left * LEVEL_SMOOTHING;
+                self.shared.input_level_left.store(f32_to_u32(new_level), Ordering::Relaxed);
+            }
+            if !live_input_muted {
+                let current = u32_to_f32(self.shared.input_level_right.load(Ordering::Relaxed));
+                let new_level = current * (1.0 - LEVEL_SMOOTHING) + input_peak_right * LEVEL_SMOOTHING;
+                self.shared.input_level_right.store(f32_to_u32(new_level), Ordering::Relaxed);
+            }
+        }
+
+        let auto_level = self.shared.auto_level_enabled.load(Ordering::Relaxed);
+        if !auto_level || !has_plugin {
+            self.auto_leveler.reset();
+            self.shared.auto_level_db.store(f32_to_u32(0.0), Ordering::Relaxed);
+        }
+
+        // Auto level already holds the output at the input level - gain match would
+        // only measure its own result, so it sits out while auto level is on
+        let gain_match = self.shared.gain_match_enabled.load(Ordering::Relaxed) && !auto_level;
+        if !gain_match || !has_plugin {
+            self.gain_matcher.reset();
+            self.shared.gain_match_db.store(f32_to_u32(0.0), Ordering::Relaxed);
+        }
+
+        // Ghost kick settings (tempo follows the pattern player)
+        let ghost_kick_on = self.shared.ghost_kick_enabled.load(Ordering::Relaxed);
+        if ghost_kick_on {
+            self.ghost_kick.set_bpm(u32_to_f32(self.shared.ghost_kick_bpm.load(Ordering::Relaxed)));
+            let pattern = GhostKickPattern::from_u8(self.shared.ghost_kick_pattern.load(Ordering::Relaxed));
+            if self.shared.ghost_kick_resync.swap(false, Ordering::Relaxed) {
+                self.ghost_kick.set_pattern(pattern);
+            }
+        }
+
+        // Bypass: once the fade to the input has finished, the plugin isn't called at all
+        let bypass = self.shared.plugin_bypass.load(Ordering::Relaxed);
+        let fully_bypassed = bypass && self.bypass_fade_position >= CROSSFADE_SAMPLES;
+
+        if has_plugin && data.len() <= max_buffer_size {
+            // Try to process through plugin using try_write to avoid blocking
+            // If main thread holds the lock (during reload/param update), pass through input unchanged
+            let plugin_processed = if fully_bypassed {
+                // data already holds the input, which passes through
+                false
+            } else if let Some(mut plugin_lock) = self.shared.plugin_instance.try_write() {
+                if let Some(ref mut plugin) = *plugin_lock {
+                    // Performance monitoring: time only the plugin.process() call
+                    // Check flag first to avoid Instant::now() overhead when disabled
+                    let perf_enabled = self.shared.perf_monitoring_enabled.load(Ordering::Relaxed);
+                    let start_time = if perf_enabled {
+                        Some(std::time::Instant::now())
+                    } else {
+                        None
+                    };
+
+                    // Parameter changes from commands land at the start of the block
+                    self.shared.param_queue.drain(|change| {
+                        plugin.queue_param_value(change.param_id, change.value, 0);
+                    });
+
+                    // Automation playback: recorded points at their sample offsets
+                    if self.shared.automation_playing.load(Ordering::Relaxed) {
+                        if let Some(automation) = self.shared.param_automation.try_read() {
+                            if let Some(ref automation) = *automation {
+                                let position = block_start
+                                    .saturating_sub(self.shared.automation_start.load(Ordering::Relaxed));
+                                automation.for_each_in_block(position, block_frames, |param_id, offset, value| {
+                                    plugin.queue_param_value(param_id, value, offset);
+                                });
+                                if position + block_frames as u64 > automation.length() {
+                                    self.shared.automation_playing.store(false, Ordering::Relaxed);
                                 }
-                            } else {
-                                input_peak_left = input_peak_left.max(abs_sample);
-                                input_peak_right = input_peak_left;
                             }
                         }
+                    }
 
-                        // Update input levels with smoothing (lock-free using atomics)
-                        {
-                            let current = u32_to_f32(shared_clone.input_level_left.load(Ordering::Relaxed));
-                            let new_level = current * (1.0 - level_smoothing) + input_peak_left * level_smoothing;
-                            shared_clone.input_level_left.store(f32_to_u32(new_level), Ordering::Relaxed);
-                        }
-                        {
-                            let current = u32_to_f32(shared_clone.input_level_right.load(Ordering::Relaxed));
-                            let new_level = current * (1.0 - level_smoothing) + input_peak_right * level_smoothing;
-                            shared_clone.input_level_right.store(f32_to_u32(new_level), Ordering::Relaxed);
-                        }
+                    // Ghost kick into the sidechain (effects only - instruments have no sidechain use here)
+                    let sidechain = if ghost_kick_on && !is_instrument && plugin.has_sidechain_input() {
+                        self.ghost_kick.fill_stereo(&mut self.ghost_kick_buffer[..data.len()]);
+                        Some(&self.ghost_kick_buffer[..data.len()])
+                    } else {
+                        None
+                    };
+
+                    // A plugin that reported a processing error is skipped, so the
+                    // dry input passes through until it's reloaded
+                    let result = !plugin.has_process_error()
+                        && plugin
+                            .process_with_sidechain(
+                                &self.input_buffer[..data.len()],
+                                sidechain,
+                                &mut self.output_buffer[..data.len()],
+                            )
+                            .is_ok();
+                    if plugin.has_process_error() {
+                        self.shared.plugin_process_error.store(true, Ordering::Relaxed);
                     }
 
-                    if has_plugin && data.len() <= max_buffer_size {
-                        // Try to process through plugin using try_write to avoid blocking
-                        // If main thread holds the lock (during reload/param update), pass through input unchanged
-                        let plugin_processed = if let Some(mut plugin_lock) = shared_clone.plugin_instance.try_write() {
-                            if let Some(ref mut plugin) = *plugin_lock {
-                                // Performance monitoring: time only the plugin.process() call
-                                // Check flag first to avoid Instant::now() overhead when disabled
-                                let perf_enabled = shared_clone.perf_monitoring_enabled.load(Ordering::Relaxed);
-                                let start_time = if perf_enabled {
-                                    Some(std::time::Instant::now())
-                                } else {
-                                    None
-                                };
-
-                                let result = plugin
-                                    .process(&input_buffer[..data.len()], &mut output_buffer[..data.len()])
-                                    .is_ok();
-
-                                // Store timing if monitoring was enabled
-                                if let Some(start) = start_time {
-                                    let elapsed_ns = start.elapsed().as_nanos() as u64;
-                                    shared_clone.perf_plugin_process_ns.store(elapsed_ns, Ordering::Relaxed);
-                                    shared_clone.perf_samples_processed.store((data.len() / channels) as u32, Ordering::Relaxed);
-                                }
+                    // Store timing if monitoring was enabled
+                    if let Some(start) = start_time {
+                        let elapsed_ns = start.elapsed().as_nanos() as u64;
+                        self.shared.perf_plugin_process_ns.store(elapsed_ns, Ordering::Relaxed);
+                        self.shared.perf_samples_processed.store((data.len() / channels) as u32, Ordering::Relaxed);
+                    }
 
-                                result
-                            } else {
-                                false
-                            }
-                        } else {
-                            // Couldn't get lock - main thread is busy with plugin
-                            // For effects: pass through input unchanged (no glitch)
-                            // For instruments: the input_buffer already has generated audio
-                            false
-                        };
-
-                        if plugin_processed {
-                                // Apply crossfade if reloading
-                                let crossfade_state =
-                                    shared_clone.crossfade_state.load(Ordering::SeqCst);
-
-                                if crossfade_state == CROSSFADE_NONE {
-                                    // No crossfade, just copy output
-                                    data.copy_from_slice(&output_buffer[..data.len()]);
-
-                                    // Debug: verify output buffer has plugin output
-                                    if engine_count % 1000 == 0 {
-                                        let out_max = output_buffer.iter().take(data.len()).map(|s| s.abs()).fold(0.0f32, f32::max);
-                                        log::info!("Engine: copied plugin output to device, out_max={:.4}", out_max);
-                                    }
-                                } else {
-                                    // Apply crossfade
-                                    let mut position = shared_clone
-                                        .crossfade_position
-                                        .load(Ordering::SeqCst);
-                                    let samples_per_frame = channels as u32;
-
-                                    for (i, chunk) in data.chunks_mut(channels).enumerate() {
-                                        let fade = if crossfade_state == CROSSFADE_OUT {
-                                            // Fading out: 1.0 -> 0.0
-                                            1.0 - (position as f32 / CROSSFADE_SAMPLES as f32)
-                                        } else {
-                                            // Fading in: 0.0 -> 1.0
-                                            position as f32 / CROSSFADE_SAMPLES as f32
-                                        };
-                                        let fade = fade.clamp(0.0, 1.0);
-
-                                        // Apply fade to output
-                                        let idx = i * channels;
-                                        chunk[0] = output_buffer[idx] * fade;
-                                        if channels > 1 {
-                                            chunk[1] = output_buffer[idx + 1] * fade;
-                                        }
-
-                                        position = position.saturating_add(samples_per_frame);
-                                    }
-
-                                    // Update position and check if complete
-                                    if position >= CROSSFADE_SAMPLES {
-                                        shared_clone
-                                            .crossfade_state
-                                            .store(CROSSFADE_NONE, Ordering::SeqCst);
-                                        shared_clone.crossfade_position.store(0, Ordering::SeqCst);
-                                    } else {
-                                        shared_clone
-                                            .crossfade_position
-                                            .store(position, Ordering::SeqCst);
-                                    }
-                                }
+                    result
+                } else {
+                    false
+                }
+            } else {
+                // Couldn't get lock - main thread is busy with plugin
+                // For effects: pass through input unchanged (no glitch)
+                // For instruments: the input_buffer already has generated audio
+                false
+            };
+
+            if measuring {
+                if let Some(mut capture) = self.shared.response_capture.try_lock() {
+                    if let Some(ref mut capture) = *capture {
+                        if capture.record(&self.output_buffer[..data.len()], channels, plugin_processed) {
+                            self.shared.response_measuring.store(false, Ordering::Release);
                         }
-                        // If plugin_processed is false (couldn't get lock), data already has input audio
-                        // which passes through unchanged - this avoids audio glitches during hot reload
                     }
+                }
+            }
 
-                    // ========================================
-                    // CAPTURE TRUE PLUGIN OUTPUT FOR ANALYSIS
-                    // ========================================
-                    // Metering happens BEFORE volume control so meters show
-                    // true plugin output regardless of listening volume
-                    // Calculate TRUE peak levels BEFORE safety limiting
-                    // This shows what the plugin actually outputs (can be >0dB)
-                    let mut peak_left = 0.0f32;
-                    let mut peak_right = 0.0f32;
-                    let mut clipped_left = false;
-                    let mut clipped_right = false;
+            // Dry input lined up with the plugin latency, for the mix, the bypass fade and
+            // the monitor modes. Always fed so switching modes is seamless.
+            let len = data.len();
+            if is_instrument {
+                // No dry path: bypassing an instrument fades it to silence
+                self.aligned_dry_buffer[..len].fill(0.0);
+            } else {
+                let latency = self.shared.plugin_latency.load(Ordering::Relaxed) as usize;
+                for (dry, aligned) in self.input_buffer[..len]
+                    .chunks(channels)
+                    .zip(self.aligned_dry_buffer[..len].chunks_mut(channels))
+                {
+                    let right = if channels > 1 { dry[1] } else { dry[0] };
+                    let delayed = self.dry_delay.process([dry[0], right], latency);
+                    for (sample, &value) in aligned.iter_mut().zip(delayed.iter()) {
+                        *sample = value;
+                    }
+                }
+                self.dry_delay_in_use = true;
+            }
 
-                    for (i, &sample) in data.iter().enumerate() {
-                        // Skip NaN/Inf for peak calculation
-                        if !sample.is_finite() {
-                            if channels > 1 {
-                                if i % 2 == 0 { clipped_left = true; } else { clipped_right = true; }
-                            } else {
-                                clipped_left = true;
-                                clipped_right = true;
-                            }
-                            continue;
-                        }
+            // Fully bypassed: the aligned input, so toggling bypass doesn't shift the audio
+            // by the plugin latency
+            if fully_bypassed {
+                data.copy_from_slice(&self.aligned_dry_buffer[..len]);
+            }
 
-                        let abs_sample = sample.abs();
-                        if channels > 1 {
-                            if i % 2 == 0 {
-                                peak_left = peak_left.max(abs_sample);
-                                if abs_sample > 1.0 { clipped_left = true; }
+            // Wet and Difference monitor the plugin output itself, so nothing after the
+            // reload crossfade (levelling, the dry mix, the bypass fade) is applied to it
+            let monitor_mode = if is_instrument {
+                MONITOR_NORMAL
+            } else {
+                self.shared.monitor_mode.load(Ordering::Relaxed)
+            };
+            let plugin_output_only = matches!(monitor_mode, MONITOR_WET | MONITOR_DIFFERENCE);
+
+            if plugin_processed {
+                    // Apply crossfade if reloading
+                    let crossfade_state =
+                        self.shared.crossfade_state.load(Ordering::SeqCst);
+
+                    if crossfade_state == CROSSFADE_NONE {
+                        // No crossfade, just copy output
+                        data.copy_from_slice(&self.output_buffer[..data.len()]);
+
+                        // Debug: verify output buffer has plugin output
+                        if trace_callback {
+                            let out_max = self.output_buffer.iter().take(data.len()).map(|s| s.abs()).fold(0.0f32, f32::max);
+                            log::debug!("Engine: copied plugin output to device, out_max={:.4}", out_max);
+                        }
+                    } else {
+                        // Apply crossfade
+                        let mut position = self.shared
+                            .crossfade_position
+                            .load(Ordering::SeqCst);
+                        let samples_per_frame = channels as u32;
+
+                        for (i, chunk) in data.chunks_mut(channels).enumerate() {
+                            let fade = if crossfade_state == CROSSFADE_OUT {
+                                // Fading out: 1.0 -> 0.0
+                                1.0 - crossfade_gain(position)
                             } else {
-                                peak_right = peak_right.max(abs_sample);
-                                if abs_sample > 1.0 { clipped_right = true; }
-                            }
-                        } else {
-                            peak_left = peak_left.max(abs_sample);
-                            peak_right = peak_left;
-                            if abs_sample > 1.0 {
-                                clipped_left = true;
-                                clipped_right = true;
+                                // Fading in: 0.0 -> 1.0
+                                crossfade_gain(position)
+                            };
+
+                            // Apply fade to output
+                            let idx = i * channels;
+                            chunk[0] = self.output_buffer[idx] * fade;
+                            if channels > 1 {
+                                chunk[1] = self.output_buffer[idx + 1] * fade;
                             }
+
+                            position = position.saturating_add(samples_per_frame);
                         }
-                    }
 
-                    // Copy pre-limited data for spectrum/waveform analysis
-                    // Uses pre-allocated buffer to avoid heap allocation in audio callback
-                    let data_len = data.len();
-                    pre_limited_buffer[..data_len].copy_from_slice(data);
-                    let pre_limited_data = &pre_limited_buffer[..data_len];
-
-                    // ========================================
-                    // SAFETY LIMITER (for speaker protection)
-                    // ========================================
-                    // Clamp all output to prevent speaker/ear damage
-                    // This protects against poorly written plugins that output >0dB
-                    for sample in data.iter_mut() {
-                        if !sample.is_finite() {
-                            *sample = 0.0;
+                        // Update position and check if complete
+                        if position >= CROSSFADE_SAMPLES {
+                            self.shared
+                                .crossfade_state
+                                .store(CROSSFADE_NONE, Ordering::SeqCst);
+                            self.shared.crossfade_position.store(0, Ordering::SeqCst);
                         } else {
-                            *sample = sample.clamp(-1.0, 1.0);
+                            self.shared
+                                .crossfade_position
+                                .store(position, Ordering::SeqCst);
                         }
                     }
 
-                    // ========================================
-                    // OUTPUT VOLUME (listening level control)
-                    // ========================================
-                    // Applied AFTER safety limiter so it only affects speaker output,
-                    // not metering. User can listen quietly while seeing true levels.
-                    let output_vol = u32_to_f32(shared_clone.master_volume.load(Ordering::Relaxed));
-                    for sample in data.iter_mut() {
-                        *sample *= output_vol;
+                    // Auto level: slow makeup gain toward the input level, so turning
+                    // the effect on/off doesn't change loudness on continuous material
+                    if auto_level && !is_instrument && !plugin_output_only {
+                        self.auto_leveler.set_window(u32_to_f32(
+                            self.shared.auto_level_time_constant.load(Ordering::Relaxed),
+                        ));
+                        self.auto_leveler.measure(&self.input_buffer[..data.len()], &self.output_buffer[..data.len()], channels);
+                        self.auto_leveler.apply(data);
+                        self.shared.auto_level_db.store(f32_to_u32(self.auto_leveler.applied_db()), Ordering::Relaxed);
                     }
 
-                    // Set clipping flags (will stay true until read and cleared)
-                    if clipped_left {
-                        shared_clone.clipping_left.store(true, Ordering::Relaxed);
-                    }
-                    if clipped_right {
-                        shared_clone.clipping_right.store(true, Ordering::Relaxed);
+                    // Gain match: level the plugin output to the dry input so a wet/dry
+                    // comparison isn't biased by loudness. Instruments have no dry path.
+                    if gain_match && !is_instrument && !plugin_output_only {
+                        self.gain_matcher.measure(&self.input_buffer[..data.len()], &self.output_buffer[..data.len()], channels);
+                        self.gain_matcher.apply(data);
+                        self.shared.gain_match_db.store(f32_to_u32(self.gain_matcher.applied_db()), Ordering::Relaxed);
                     }
 
-                    // Update output levels with smoothing (lock-free using atomics)
-                    {
-                        let current = u32_to_f32(shared_clone.output_level_left.load(Ordering::Relaxed));
-                        let new_level = current * (1.0 - level_smoothing) + peak_left * level_smoothing;
-                        shared_clone.output_level_left.store(f32_to_u32(new_level), Ordering::Relaxed);
-                    }
-                    {
-                        let current = u32_to_f32(shared_clone.output_level_right.load(Ordering::Relaxed));
-                        let new_level = current * (1.0 - level_smoothing) + peak_right * level_smoothing;
-                        shared_clone.output_level_right.store(f32_to_u32(new_level), Ordering::Relaxed);
-                    }
+                    if !plugin_output_only {
+                        // Wet/dry mix against the latency-aligned input (instruments have no dry path)
+                        let mix = u32_to_f32(self.shared.plugin_mix.load(Ordering::Relaxed));
+                        if !is_instrument && (mix < 1.0 || self.current_mix < 1.0) {
+                            self.apply_mix(data, channels, mix);
+                        }
 
-                    // Update waveform display buffer (downsample to fit display)
-                    // Store L and R separately for stereo visualization
-                    // Uses PRE-LIMITED data to show true plugin output (not affected by volume)
-                    let frames = pre_limited_data.len() / channels;
-                    let downsample_factor = (frames / 16).max(1); // Capture ~16 samples per callback for more detail
-                    let mut write_pos = shared_clone.waveform_write_pos.load(Ordering::Relaxed) as usize;
-
-                    // Track peak values for peak hold display
-                    let mut waveform_peak_l = 0.0f32;
-                    let mut waveform_peak_r = 0.0f32;
-
-                    for (i, chunk) in pre_limited_data.chunks(channels).enumerate() {
-                        let left_sample = chunk[0];
-                        let right_sample = if channels > 1 { chunk[1] } else { chunk[0] };
-
-                        // Validate samples (plugin could output NaN/Inf)
-                        let left_valid = if left_sample.is_finite() { left_sample } else { 0.0 };
-                        let right_valid = if right_sample.is_finite() { right_sample } else { 0.0 };
-
-                        // Track peaks (using validated samples)
-                        waveform_peak_l = waveform_peak_l.max(left_valid.abs());
-                        waveform_peak_r = waveform_peak_r.max(right_valid.abs());
-
-                        if i % downsample_factor == 0 {
-                            // Store L and R separately
-                            shared_clone.waveform_buffer_left[write_pos].store(f32_to_u32(left_valid), Ordering::Relaxed);
-                            shared_clone.waveform_buffer_right[write_pos].store(f32_to_u32(right_valid), Ordering::Relaxed);
-                            write_pos = (write_pos + 1) % WAVEFORM_SAMPLES;
+                        // Bypass toggled: fade between the plugin output and the input
+                        if bypass || self.bypass_fade_position > 0 {
+                            self.apply_bypass_fade(data, channels, bypass);
                         }
                     }
-                    shared_clone.waveform_write_pos.store(write_pos as u32, Ordering::Relaxed);
-
-                    // Update peak hold values (keep max of current and new)
-                    let current_peak_l = u32_to_f32(shared_clone.waveform_peak_left.load(Ordering::Relaxed));
-                    let current_peak_r = u32_to_f32(shared_clone.waveform_peak_right.load(Ordering::Relaxed));
-                    shared_clone.waveform_peak_left.store(f32_to_u32(current_peak_l.max(waveform_peak_l)), Ordering::Relaxed);
-                    shared_clone.waveform_peak_right.store(f32_to_u32(current_peak_r.max(waveform_peak_r)), Ordering::Relaxed);
-
-                    // Update INPUT waveform display buffer (pre-FX for comparison)
-                    // Uses input_buffer which contains the signal before plugin processing
-                    let input_data = &input_buffer[..data.len()];
-                    let mut input_write_pos = shared_clone.waveform_input_write_pos.load(Ordering::Relaxed) as usize;
-                    let mut waveform_input_peak_l = 0.0f32;
-                    let mut waveform_input_peak_r = 0.0f32;
-
-                    for (i, chunk) in input_data.chunks(channels).enumerate() {
-                        let left_sample = chunk[0];
-                        let right_sample = if channels > 1 { chunk[1] } else { chunk[0] };
-
-                        // Skip NaN/Inf for input waveform (could come from corrupted samples)
-                        let left_valid = if left_sample.is_finite() { left_sample } else { 0.0 };
-                        let right_valid = if right_sample.is_finite() { right_sample } else { 0.0 };
-
-                        // Track peaks (using validated samples)
-                        waveform_input_peak_l = waveform_input_peak_l.max(left_valid.abs());
-                        waveform_input_peak_r = waveform_input_peak_r.max(right_valid.abs());
-
-                        if i % downsample_factor == 0 {
-                            shared_clone.waveform_buffer_input_left[input_write_pos].store(f32_to_u32(left_valid), Ordering::Relaxed);
-                            shared_clone.waveform_buffer_input_right[input_write_pos].store(f32_to_u32(right_valid), Ordering::Relaxed);
-                            input_write_pos = (input_write_pos + 1) % WAVEFORM_SAMPLES;
-                        }
+            }
+            // If plugin_processed is false (couldn't get lock), data already has input audio
+            // which passes through unchanged - this avoids audio glitches during hot reload
+
+            // Monitor modes (always Normal for instruments, which have no dry path)
+            match monitor_mode {
+                MONITOR_DRY => data.copy_from_slice(&self.aligned_dry_buffer[..len]),
+                // No plugin output this block (lock busy, or fully bypassed): data holds the
+                // unaligned input, which isn't plugin output and would leave a false residual
+                MONITOR_WET | MONITOR_DIFFERENCE if !plugin_processed => data.fill(0.0),
+                // data already holds the plugin output as it left the plugin
+                MONITOR_WET => {}
+                MONITOR_DIFFERENCE => {
+                    for (sample, &dry) in data.iter_mut().zip(&self.aligned_dry_buffer[..len]) {
+                        *sample -= dry;
                     }
-                    shared_clone.waveform_input_write_pos.store(input_write_pos as u32, Ordering::Relaxed);
-
-                    // Update input peak hold values
-                    let current_input_peak_l = u32_to_f32(shared_clone.waveform_input_peak_left.load(Ordering::Relaxed));
-                    let current_input_peak_r = u32_to_f32(shared_clone.waveform_input_peak_right.load(Ordering::Relaxed));
-                    shared_clone.waveform_input_peak_left.store(f32_to_u32(current_input_peak_l.max(waveform_input_peak_l)), Ordering::Relaxed);
-                    shared_clone.waveform_input_peak_right.store(f32_to_u32(current_input_peak_r.max(waveform_input_peak_r)), Ordering::Relaxed);
-
-                    // Update spectrum analyzer (mono mix of L/R for analysis)
-                    // Update every 2 callbacks for smoother visuals (~6ms at 44.1kHz/512)
-                    spectrum_update_counter += 1;
-                    if spectrum_update_counter >= 2 {
-                        spectrum_update_counter = 0;
-
-                        // Create mono mix for output (post-FX) analysis
-                        // Uses pre-allocated buffer to avoid heap allocation in audio callback
-                        let mono_frames = data_len / channels;
-                        if channels > 1 {
-                            for (i, chunk) in pre_limited_data.chunks(2).enumerate() {
-                                mono_output_buffer[i] = (chunk[0] + chunk[1]) * 0.5;
-                            }
-                        } else {
-                            mono_output_buffer[..mono_frames].copy_from_slice(pre_limited_data);
-                        }
+                }
+                _ => {}
+            }
+        } else if self.dry_delay_in_use {
+            // Plugin gone - don't replay stale dry audio when the next one loads
+            self.dry_delay.reset();
+            self.dry_delay_in_use = false;
+        }
 
-                        // Create mono mix for input (pre-FX) analysis
-                        // Uses pre-allocated buffer to avoid heap allocation in audio callback
-                        if channels > 1 {
-                            for (i, chunk) in input_buffer[..data_len].chunks(2).enumerate() {
-                                mono_input_buffer[i] = (chunk[0] + chunk[1]) * 0.5;
-                            }
-                        } else {
-                            mono_input_buffer[..mono_frames].copy_from_slice(&input_buffer[..data_len]);
-                        }
+        // Keep the measurement sweep off the speakers
+        if measuring {
+            data.fill(0.0);
+        }
 
-                        // Push samples and compute FFT for output (post-FX)
-                        spectrum_analyzer.push_samples(&mono_output_buffer[..mono_frames]);
-                        spectrum_analyzer.analyze();
+        // ========================================
+        // CAPTURE TRUE PLUGIN OUTPUT FOR ANALYSIS
+        // ========================================
+        // Metering happens BEFORE volume control so meters show
+        // true plugin output regardless of listening volume
+        // Calculate TRUE peak levels BEFORE safety limiting
+        // This shows what the plugin actually outputs (can be >0dB)
+        let mut peak_left = 0.0f32;
+        let mut peak_right = 0.0f32;
+        let mut clipped_left = false;
+        let mut clipped_right = false;
+        // Clip indicators fire when the limiter ceiling is exceeded
+        let ceiling = u32_to_f32(self.shared.limiter_ceiling.load(Ordering::Relaxed));
+
+        for (i, &sample) in data.iter().enumerate() {
+            // Skip NaN/Inf for peak calculation
+            if !sample.is_finite() {
+                if channels > 1 {
+                    if i % 2 == 0 { clipped_left = true; } else { clipped_right = true; }
+                } else {
+                    clipped_left = true;
+                    clipped_right = true;
+                }
+                continue;
+            }
 
-                        // Push samples and compute FFT for input (pre-FX)
-                        spectrum_analyzer_input.push_samples(&mono_input_buffer[..mono_frames]);
-                        spectrum_analyzer_input.analyze();
+            let abs_sample = sample.abs();
+            if channels > 1 {
+                if i % 2 == 0 {
+                    peak_left = peak_left.max(abs_sample);
+                    if abs_sample > ceiling { clipped_left = true; }
+                } else {
+                    peak_right = peak_right.max(abs_sample);
+                    if abs_sample > ceiling { clipped_right = true; }
+                }
+            } else {
+                peak_left = peak_left.max(abs_sample);
+                peak_right = peak_left;
+                if abs_sample > ceiling {
+                    clipped_left = true;
+                    clipped_right = true;
+                }
+            }
+        }
 
-                        // Store output spectrum data to shared state (lock-free)
-                        let magnitudes = spectrum_analyzer.get_magnitudes();
-                        for (i, &mag) in magnitudes.iter().enumerate() {
-                            shared_clone.spectrum_bands[i].store(f32_to_u32(mag), Ordering::Relaxed);
-                        }
+        // Copy pre-limited data for spectrum/waveform analysis
+        // Uses pre-allocated buffer to avoid heap allocation in audio callback
+        let data_len = data.len();
+        self.pre_limited_buffer[..data_len].copy_from_slice(data);
+        let pre_limited_data = &self.pre_limited_buffer[..data_len];
+
+        // ========================================
+        // DC BLOCKER (optional, per channel)
+        // ========================================
+        if self.shared.dc_block_enabled.load(Ordering::Relaxed) {
+            let state = &self.shared.dc_block_state;
+            let mut blockers = [
+                DcBlocker {
+                    x1: u32_to_f32(state[0].load(Ordering::Relaxed)),
+                    y1: u32_to_f32(state[1].load(Ordering::Relaxed)),
+                },
+                DcBlocker {
+                    x1: u32_to_f32(state[2].load(Ordering::Relaxed)),
+                    y1: u32_to_f32(state[3].load(Ordering::Relaxed)),
+                },
+            ];
+            for chunk in data.chunks_mut(channels) {
+                for (sample, blocker) in chunk.iter_mut().zip(blockers.iter_mut()) {
+                    if sample.is_finite() {
+                        *sample = blocker.process(*sample, self.dc_block_r);
+                    }
+                }
+            }
+            for (i, blocker) in blockers.iter().enumerate() {
+                state[i * 2].store(f32_to_u32(blocker.x1), Ordering::Relaxed);
+                state[i * 2 + 1].store(f32_to_u32(blocker.y1), Ordering::Relaxed);
+            }
+        }
 
-                        // Store input spectrum data to shared state (lock-free)
-                        let magnitudes_input = spectrum_analyzer_input.get_magnitudes();
-                        for (i, &mag) in magnitudes_input.iter().enumerate() {
-                            shared_clone.spectrum_bands_input[i].store(f32_to_u32(mag), Ordering::Relaxed);
-                        }
+        // ========================================
+        // MONITOR STEREO WIDTH (listening aid, after the plugin)
+        // ========================================
+        let monitor_width = u32_to_f32(self.shared.monitor_width.load(Ordering::Relaxed));
+        if channels > 1 && monitor_width != 1.0 {
+            for chunk in data.chunks_mut(channels) {
+                (chunk[0], chunk[1]) = apply_stereo_width(chunk[0], chunk[1], monitor_width);
+            }
+        }
 
-                        // Stereo analysis - push stereo samples (not mono)
-                        // OUTPUT stereo: Uses PRE-LIMITED data to show true stereo field
-                        // INPUT stereo: Uses input_buffer for pre-FX comparison
-                        if channels > 1 {
-                            // Output stereo analysis (post-FX)
-                            stereo_analyzer.push_samples(&pre_limited_data);
-
-                            // Store output stereo positions to shared state (lock-free)
-                            let positions = stereo_analyzer.get_positions();
-                            for (i, &(angle, radius)) in positions.iter().enumerate() {
-                                shared_clone.stereo_positions[i * 2].store(f32_to_u32(angle), Ordering::Relaxed);
-                                shared_clone.stereo_positions[i * 2 + 1].store(f32_to_u32(radius), Ordering::Relaxed);
-                            }
+        // ========================================
+        // SAFETY LIMITER (for speaker protection)
+        // ========================================
+        // Clamp all output to prevent speaker/ear damage
+        // This protects against poorly written plugins that output >0dB
+        // Ceiling defaults to 0dB with a hard clamp; soft mode shapes every sample
+        let limiter_mode = if self.shared.limiter_soft.load(Ordering::Relaxed) {
+            LimiterMode::Soft
+        } else {
+            LimiterMode::Hard
+        };
+        let mut clamped_samples = 0u64;
+        let mut max_abs = 0.0f32;
+        for sample in data.iter_mut() {
+            if !sample.is_finite() {
+                *sample = 0.0;
+                clamped_samples += 1;
+            } else {
+                let abs_sample = sample.abs();
+                max_abs = max_abs.max(abs_sample);
+                if abs_sample > ceiling {
+                    clamped_samples += 1;
+                }
+                if limiter_mode == LimiterMode::Soft || abs_sample > ceiling {
+                    *sample = limiter_mode.apply(*sample, ceiling);
+                }
+            }
+        }
+        if clamped_samples > 0 {
+            self.shared.limiter_clamped_samples.fetch_add(clamped_samples, Ordering::Relaxed);
+        }
+        // Bit patterns of non-negative floats order the same as their values
+        self.shared.limiter_max_abs.fetch_max(f32_to_u32(max_abs), Ordering::Relaxed);
+
+        // ========================================
+        // OUTPUT VOLUME (listening level control)
+        // ========================================
+        // Applied AFTER safety limiter so it only affects speaker output,
+        // not metering. User can listen quietly while seeing true levels.
+        let output_vol = u32_to_f32(self.shared.master_volume.load(Ordering::Relaxed));
+        for sample in data.iter_mut() {
+            *sample *= output_vol;
+        }
 
-                            // Store output correlation
-                            let correlation = stereo_analyzer.get_correlation();
-                            shared_clone.stereo_correlation.store(f32_to_u32(correlation), Ordering::Relaxed);
+        // ========================================
+        // DITHER (last step before the device buffer)
+        // ========================================
+        if self.shared.dither_enabled.load(Ordering::Relaxed) {
+            let bits = self.shared.dither_bits.load(Ordering::Relaxed);
+            let shaped = self.shared.dither_shaped.load(Ordering::Relaxed);
+            for chunk in data.chunks_mut(channels) {
+                for (channel, sample) in chunk.iter_mut().enumerate() {
+                    *sample = self.dither.process(*sample, channel, bits, shaped);
+                }
+            }
+        }
 
-                            // Input stereo analysis (pre-FX)
-                            stereo_analyzer_input.push_samples(&input_buffer[..data.len()]);
+        // Set clipping flags (will stay true until read and cleared)
+        if clipped_left {
+            self.shared.clipping_left.store(true, Ordering::Relaxed);
+        }
+        if clipped_right {
+            self.shared.clipping_right.store(true, Ordering::Relaxed);
+        }
 
-                            // Store input stereo positions to shared state (lock-free)
-                            let positions_input = stereo_analyzer_input.get_positions();
-                            for (i, &(angle, radius)) in positions_input.iter().enumerate() {
-                                shared_clone.stereo_positions_input[i * 2].store(f32_to_u32(angle), Ordering::Relaxed);
-                                shared_clone.stereo_positions_input[i * 2 + 1].store(f32_to_u32(radius), Ordering::Relaxed);
-                            }
+        // Update output levels with smoothing (lock-free using atomics)
+        {
+            let current = u32_to_f32(self.shared.output_level_left.load(Ordering::Relaxed));
+            let new_level = current * (1.0 - LEVEL_SMOOTHING) + peak_left * LEVEL_SMOOTHING;
+            self.shared.output_level_left.store(f32_to_u32(new_level), Ordering::Relaxed);
+        }
+        {
+            let current = u32_to_f32(self.shared.output_level_right.load(Ordering::Relaxed));
+            let new_level = current * (1.0 - LEVEL_SMOOTHING) + peak_right * LEVEL_SMOOTHING;
+            self.shared.output_level_right.store(f32_to_u32(new_level), Ordering::Relaxed);
+        }
 
-                            // Store input correlation
-                            let correlation_input = stereo_analyzer_input.get_correlation();
-                            shared_clone.stereo_correlation_input.store(f32_to_u32(correlation_input), Ordering::Relaxed);
-                        } else {
-                            // Mono audio: reset stereo analyzers and clear positions to center
-                            // This prevents stale stereo particles from displaying when switching from stereo to mono
-                            stereo_analyzer.reset();
-                            stereo_analyzer_input.reset();
-
-                            // Set all positions to center (PI/2) with zero radius (invisible)
-                            let center_angle = std::f32::consts::FRAC_PI_2;
-                            for i in 0..STEREO_HISTORY_SIZE {
-                                // Output positions
-                                shared_clone.stereo_positions[i * 2].store(f32_to_u32(center_angle), Ordering::Relaxed);
-                                shared_clone.stereo_positions[i * 2 + 1].store(f32_to_u32(0.0), Ordering::Relaxed);
-                                // Input positions
-                                shared_clone.stereo_positions_input[i * 2].store(f32_to_u32(center_angle), Ordering::Relaxed);
-                                shared_clone.stereo_positions_input[i * 2 + 1].store(f32_to_u32(0.0), Ordering::Relaxed);
-                            }
+        // Mono mix of the output (post-FX) - used for the weighted level and the spectrum
+        // Uses pre-allocated buffer to avoid heap allocation in audio callback
+        let mono_frames = pre_limited_data.len() / channels;
+        if channels > 1 {
+            for (i, chunk) in pre_limited_data.chunks(2).enumerate() {
+                self.mono_output_buffer[i] = (chunk[0] + chunk[1]) * 0.5;
+            }
+        } else {
+            self.mono_output_buffer[..mono_frames].copy_from_slice(pre_limited_data);
+        }
 
-                            // Correlation is 1.0 for mono (perfect correlation)
-                            shared_clone.stereo_correlation.store(f32_to_u32(1.0), Ordering::Relaxed);
-                            shared_clone.stereo_correlation_input.store(f32_to_u32(1.0), Ordering::Relaxed);
-                        }
+        // Weighted output level (filter rebuilt here when the weighting changes - no allocation)
+        let weighting = MeteringWeighting::from_u8(self.shared.metering_weighting.load(Ordering::Relaxed));
+        if weighting != self.weighting_filter.weighting() {
+            self.weighting_filter = WeightingFilter::new(weighting, self.sample_rate);
+        }
+        let mut weighted_peak = 0.0f32;
+        for &sample in &self.mono_output_buffer[..mono_frames] {
+            let weighted = self.weighting_filter.process(if sample.is_finite() { sample } else { 0.0 });
+            weighted_peak = weighted_peak.max(weighted.abs());
+        }
+        {
+            let current = u32_to_f32(self.shared.weighted_output_level.load(Ordering::Relaxed));
+            let new_level = current * (1.0 - LEVEL_SMOOTHING) + weighted_peak * LEVEL_SMOOTHING;
+            self.shared.weighted_output_level.store(f32_to_u32(new_level), Ordering::Relaxed);
+        }
+
+        // Loudness (same pre-limiter signal as the meters)
+        if self.shared.loudness_reset_pending.swap(false, Ordering::AcqRel) {
+            self.loudness_meter.reset();
+        }
+        self.loudness_meter.process(pre_limited_data, channels);
+        let loudness = self.loudness_meter.reading();
+        for (value, reading) in [
+            (&self.shared.loudness_momentary, loudness.momentary),
+            (&self.shared.loudness_short_term, loudness.short_term),
+            (&self.shared.loudness_integrated, loudness.integrated),
+        ] {
+            value.store(f32_to_u32(reading.unwrap_or(f32::NAN)), Ordering::Relaxed);
+        }
+
+        // Update waveform display buffer (downsample to fit display)
+        // Store L and R separately for stereo visualization
+        // Uses PRE-LIMITED data to show true plugin output (not affected by volume)
+        let frames = pre_limited_data.len() / channels;
+        let downsample_factor = (frames / 16).max(1); // Capture ~16 samples per callback for more detail
+        let mut write_pos = self.shared.waveform_write_pos.load(Ordering::Relaxed) as usize;
+
+        // Track peak values for peak hold display
+        let mut waveform_peak_l = 0.0f32;
+        let mut waveform_peak_r = 0.0f32;
+
+        for (i, chunk) in pre_limited_data.chunks(channels).enumerate() {
+            let left_sample = chunk[0];
+            let right_sample = if channels > 1 { chunk[1] } else { chunk[0] };
+
+            // Validate samples (plugin could output NaN/Inf)
+            let left_valid = if left_sample.is_finite() { left_sample } else { 0.0 };
+            let right_valid = if right_sample.is_finite() { right_sample } else { 0.0 };
+
+            // Track peaks (using validated samples)
+            waveform_peak_l = waveform_peak_l.max(left_valid.abs());
+            waveform_peak_r = waveform_peak_r.max(right_valid.abs());
+
+            if i % downsample_factor == 0 {
+                // Store L and R separately
+                self.shared.waveform_buffer_left[write_pos].store(f32_to_u32(left_valid), Ordering::Relaxed);
+                self.shared.waveform_buffer_right[write_pos].store(f32_to_u32(right_valid), Ordering::Relaxed);
+                write_pos = (write_pos + 1) % WAVEFORM_SAMPLES;
+            }
+        }
+        self.shared.waveform_write_pos.store(write_pos as u32, Ordering::Relaxed);
+
+        // Update peak hold values (keep max of current and new)
+        let current_peak_l = u32_to_f32(self.shared.waveform_peak_left.load(Ordering::Relaxed));
+        let current_peak_r = u32_to_f32(self.shared.waveform_peak_right.load(Ordering::Relaxed));
+        self.shared.waveform_peak_left.store(f32_to_u32(current_peak_l.max(waveform_peak_l)), Ordering::Relaxed);
+        self.shared.waveform_peak_right.store(f32_to_u32(current_peak_r.max(waveform_peak_r)), Ordering::Relaxed);
+
+        // Update INPUT waveform display buffer (pre-FX for comparison)
+        // Uses input_buffer which contains the signal before plugin processing
+        let input_data = &self.input_buffer[..data.len()];
+        let mut input_write_pos = self.shared.waveform_input_write_pos.load(Ordering::Relaxed) as usize;
+        let mut waveform_input_peak_l = 0.0f32;
+        let mut waveform_input_peak_r = 0.0f32;
+
+        for (i, chunk) in input_data.chunks(channels).enumerate() {
+            let left_sample = chunk[0];
+            let right_sample = if channels > 1 { chunk[1] } else { chunk[0] };
+
+            // Skip NaN/Inf for input waveform (could come from corrupted samples)
+            let left_valid = if left_sample.is_finite() { left_sample } else { 0.0 };
+            let right_valid = if right_sample.is_finite() { right_sample } else { 0.0 };
+
+            // Track peaks (using validated samples)
+            waveform_input_peak_l = waveform_input_peak_l.max(left_valid.abs());
+            waveform_input_peak_r = waveform_input_peak_r.max(right_valid.abs());
+
+            if i % downsample_factor == 0 {
+                self.shared.waveform_buffer_input_left[input_write_pos].store(f32_to_u32(left_valid), Ordering::Relaxed);
+                self.shared.waveform_buffer_input_right[input_write_pos].store(f32_to_u32(right_valid), Ordering::Relaxed);
+                input_write_pos = (input_write_pos + 1) % WAVEFORM_SAMPLES;
+            }
+        }
+        self.shared.waveform_input_write_pos.store(input_write_pos as u32, Ordering::Relaxed);
+
+        // Update input peak hold values
+        let current_input_peak_l = u32_to_f32(self.shared.waveform_input_peak_left.load(Ordering::Relaxed));
+        let current_input_peak_r = u32_to_f32(self.shared.waveform_input_peak_right.load(Ordering::Relaxed));
+        self.shared.waveform_input_peak_left.store(f32_to_u32(current_input_peak_l.max(waveform_input_peak_l)), Ordering::Relaxed);
+        self.shared.waveform_input_peak_right.store(f32_to_u32(current_input_peak_r.max(waveform_input_peak_r)), Ordering::Relaxed);
+
+        // Update spectrum analyzer (mono mix of L/R for analysis)
+        // Update every 2 callbacks for smoother visuals (~6ms at 44.1kHz/512)
+        self.spectrum_update_counter += 1;
+        if self.spectrum_update_counter >= 2 {
+            self.spectrum_update_counter = 0;
+
+            if self.shared.analysis_reset_pending.swap(false, Ordering::AcqRel) {
+                self.spectrum_analyzer.reset();
+                self.spectrum_analyzer_input.reset();
+                self.stereo_analyzer.reset();
+                self.stereo_analyzer_input.reset();
+            }
+            // A busy slot means a resize is being queued; pick it up on a later update
+            if let Some(mut resize) = self.shared.spectrum_resize.try_lock() {
+                if resize.fresh {
+                    resize.fresh = false;
+                    // Swapping leaves the old analyzers in the slot, so none are freed here, and
+                    // the fresh ones start from silence so nothing measured at the old size lingers
+                    if let Some((output, input)) = resize.analyzers.as_mut() {
+                        std::mem::swap(&mut self.spectrum_analyzer, output);
+                        std::mem::swap(&mut self.spectrum_analyzer_input, input);
                     }
-                },
+                }
+            }
+            // Create mono mix for input (pre-FX) analysis
+            // Uses pre-allocated buffer to avoid heap allocation in audio callback
+            if channels > 1 {
+                for (i, chunk) in self.input_buffer[..data_len].chunks(2).enumerate() {
+                    self.mono_input_buffer[i] = (chunk[0] + chunk[1]) * 0.5;
+                }
+            } else {
+                self.mono_input_buffer[..mono_frames].copy_from_slice(&self.input_buffer[..data_len]);
+            }
+
+            // Push samples and compute FFT for output (post-FX)
+            self.spectrum_analyzer.push_samples(&self.mono_output_buffer[..mono_frames]);
+            self.spectrum_analyzer.analyze();
+
+            // Push samples and compute FFT for input (pre-FX)
+            self.spectrum_analyzer_input.push_samples(&self.mono_input_buffer[..mono_frames]);
+            self.spectrum_analyzer_input.analyze();
+
+            // Store output spectrum data to shared state (lock-free)
+            let magnitudes = self.spectrum_analyzer.get_magnitudes();
+            for (i, &mag) in magnitudes.iter().enumerate() {
+                self.shared.spectrum_bands[i].store(f32_to_u32(mag), Ordering::Relaxed);
+            }
+
+            // Store input spectrum data to shared state (lock-free)
+            let magnitudes_input = self.spectrum_analyzer_input.get_magnitudes();
+            for (i, &mag) in magnitudes_input.iter().enumerate() {
+                self.shared.spectrum_bands_input[i].store(f32_to_u32(mag), Ordering::Relaxed);
+            }
+
+            // Stereo analysis - push stereo samples (not mono)
+            // OUTPUT stereo: Uses PRE-LIMITED data to show true stereo field
+            // INPUT stereo: Uses input_buffer for pre-FX comparison
+            if channels > 1 {
+                // Goniometer display settings (gain applies to newly pushed points)
+                let history = self.shared.goniometer_history.load(Ordering::Relaxed) as usize;
+                let gain = u32_to_f32(self.shared.goniometer_gain.load(Ordering::Relaxed));
+                self.stereo_analyzer.set_history_length(history);
+                self.stereo_analyzer.set_gain(gain);
+                self.stereo_analyzer_input.set_history_length(history);
+                self.stereo_analyzer_input.set_gain(gain);
+
+                // Correlation smoothing (only recompute the coefficient when it changes)
+                let smoothing_ms = u32_to_f32(self.shared.correlation_smoothing_ms.load(Ordering::Relaxed));
+                if smoothing_ms != self.current_correlation_smoothing {
+                    self.current_correlation_smoothing = smoothing_ms;
+                    self.stereo_analyzer.set_correlation_smoothing(smoothing_ms);
+                    self.stereo_analyzer_input.set_correlation_smoothing(smoothing_ms);
+                }
+
+                // Output stereo analysis (post-FX)
+                self.stereo_analyzer.push_samples(&pre_limited_data);
+
+                // Store output stereo positions to shared state (lock-free)
+                let positions = self.stereo_analyzer.get_positions();
+                for (i, &(angle, radius)) in positions.iter().enumerate() {
+                    self.shared.stereo_positions[i * 2].store(f32_to_u32(angle), Ordering::Relaxed);
+                    self.shared.stereo_positions[i * 2 + 1].store(f32_to_u32(radius), Ordering::Relaxed);
+                }
+
+                // Store output correlation
+                let correlation = self.stereo_analyzer.get_correlation();
+                self.shared.stereo_correlation.store(f32_to_u32(correlation), Ordering::Relaxed);
+
+                // Input stereo analysis (pre-FX)
+                self.stereo_analyzer_input.push_samples(&self.input_buffer[..data.len()]);
+
+                // Store input stereo positions to shared state (lock-free)
+                let positions_input = self.stereo_analyzer_input.get_positions();
+                for (i, &(angle, radius)) in positions_input.iter().enumerate() {
+                    self.shared.stereo_positions_input[i * 2].store(f32_to_u32(angle), Ordering::Relaxed);
+                    self.shared.stereo_positions_input[i * 2 + 1].store(f32_to_u32(radius), Ordering::Relaxed);
+                }
+
+                // Store input correlation
+                let correlation_input = self.stereo_analyzer_input.get_correlation();
+                self.shared.stereo_correlation_input.store(f32_to_u32(correlation_input), Ordering::Relaxed);
+            } else {
+                // Mono audio: reset stereo analyzers and clear positions to center
+                // This prevents stale stereo particles from displaying when switching from stereo to mono
+                self.stereo_analyzer.reset();
+                self.stereo_analyzer_input.reset();
+
+                // Set all positions to center (PI/2) with zero radius (invisible)
+                let center_angle = std::f32::consts::FRAC_PI_2;
+                for i in 0..STEREO_HISTORY_SIZE {
+                    // Output positions
+                    self.shared.stereo_positions[i * 2].store(f32_to_u32(center_angle), Ordering::Relaxed);
+                    self.shared.stereo_positions[i * 2 + 1].store(f32_to_u32(0.0), Ordering::Relaxed);
+                    // Input positions
+                    self.shared.stereo_positions_input[i * 2].store(f32_to_u32(center_angle), Ordering::Relaxed);
+                    self.shared.stereo_positions_input[i * 2 + 1].store(f32_to_u32(0.0), Ordering::Relaxed);
+                }
+
+                // Correlation is 1.0 for mono (perfect correlation)
+                self.shared.stereo_correlation.store(f32_to_u32(1.0), Ordering::Relaxed);
+                self.shared.stereo_correlation_input.store(f32_to_u32(1.0), Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// The main audio engine
+pub struct AudioEngine {
+    _stream: OutputStream,
+    handle: AudioEngineHandle,
+    config: AudioConfig,
+}
+
+impl AudioEngine {
+    /// Create and start a new audio engine
+    pub fn new(device_name: Option<&str>, config: AudioConfig) -> Result<Self, String> {
+        // Without a usable device, keep the engine running on the null output so
+        // plugins, metering and MIDI still work - just without sound
+        let (output, stream_config, output_error) = match get_output_device(device_name)
+            .and_then(|device| get_supported_config(&device, &config).map(|stream_config| (device, stream_config)))
+        {
+            Ok((device, stream_config)) => (OutputTarget::Device(device), stream_config, None),
+            Err(e) => {
+                log::warn!("No usable audio output device ({}) - falling back to silent output", e);
+                (OutputTarget::Null, null_stream_config(&config), Some(e))
+            }
+        };
+
+        let sample_rate = stream_config.sample_rate.0;
+        // The engine renders (at most) stereo - route_channels places it on the device's channels
+        let device_channels = stream_config.channels;
+        let channels = (device_channels as usize).min(2);
+        let output_routing = Arc::new(ChannelRouting::new(device_channels));
+
+        log::info!(
+            "Starting audio engine: {} Hz, {} channels",
+            sample_rate,
+            device_channels
+        );
+
+        let shared = Arc::new(SharedState::new(sample_rate, output_error));
+        let shared_error = Arc::clone(&shared);
+
+        // IMPORTANT: load_plugin activates plugins with this same max_frames (stored on the handle)
+        let max_frames = match stream_config.buffer_size {
+            cpal::BufferSize::Fixed(frames) => (frames as usize).max(DEFAULT_MAX_FRAMES),
+            cpal::BufferSize::Default => DEFAULT_MAX_FRAMES,
+        };
+        let mut processor = AudioProcessor::new(Arc::clone(&shared), sample_rate, max_frames);
+
+        // Build the output stream
+        let stream = output
+            .build_stream(
+                &stream_config,
+                route_channels(Arc::clone(&output_routing), channels, max_frames, move |data: &mut [f32]| {
+                    processor.process_block(data, channels)
+                }),
                 move |err| {
                     log::error!("Audio stream error: {}", err);
+                    *shared_error.last_stream_error.lock() = Some(err.to_string());
+                    // The stream won't recover on its own once the device is gone -
+                    // flag it so the preview monitor can rebuild the engine
+                    if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                        shared_error.device_lost.store(true, Ordering::SeqCst);
+                    }
                 },
-                None, // No timeout
-            )
-            .map_err(|e| format!("Failed to build output stream: {}", e))?;
+            )?;
 
-        // Start the stream
-        stream
-            .play()
-            .map_err(|e| format!("Failed to start stream: {}", e))?;
+        let null_output = match &stream {
+            OutputStream::Null(null) => Some(null.running_flag()),
+            OutputStream::Device(_) => None,
+        };
 
         let handle = AudioEngineHandle {
             shared,
             sample_rate,
+            device_name: output.name(),
+            config: config.clone(),
+            max_frames: max_frames as u32,
+            null_output,
+            output_routing,
         };
 
         Ok(Self {
@@ -1639,6 +3648,26 @@ impl AudioEngine {
     pub fn sample_rate(&self) -> u32 {
         self.handle.sample_rate
     }
+
+    /// Engine state without an output stream: the caller renders blocks by calling
+    /// `process_block` on the returned processor (offline rendering, tests)
+    pub fn offline(config: AudioConfig) -> (AudioEngineHandle, AudioProcessor) {
+        let sample_rate = config.sample_rate;
+        let max_frames = (config.buffer_size as usize).max(DEFAULT_MAX_FRAMES);
+        let shared = Arc::new(SharedState::new(sample_rate, None));
+        let processor = AudioProcessor::new(Arc::clone(&shared), sample_rate, max_frames);
+
+        let handle = AudioEngineHandle {
+            shared,
+            sample_rate,
+            device_name: OFFLINE_OUTPUT_NAME.to_string(),
+            output_routing: Arc::new(ChannelRouting::new(config.channels)),
+            config,
+            max_frames: max_frames as u32,
+            null_output: None,
+        };
+        (handle, processor)
+    }
 }
 
 // Global engine handle (cpal::Stream isn't Send/Sync, so we store just the handle)
@@ -1685,7 +3714,10 @@ pub fn get_engine_handle() -> Option<AudioEngineHandle> {
 /// Shutdown the global engine
 pub fn shutdown_engine() {
     if let Some(cell) = ENGINE_HANDLE.get() {
-        *cell.write() = None;
+        // Device streams are leaked, but the null output's render thread can be stopped
+        if let Some(running) = cell.write().take().and_then(|handle| handle.null_output) {
+            running.store(false, Ordering::SeqCst);
+        }
     }
     // Note: The stream is leaked and will be cleaned up when the process exits
     // The audio callback will produce silence when the handle is None
@@ -1725,7 +3757,533 @@ pub fn reinit_engine(device_name: Option<&str>, config: AudioConfig) -> Result<(
     Ok(())
 }
 
+/// Reinitialize the audio engine, carrying the loaded plugin (and its state) over
+/// to the new engine so a device or sample rate change doesn't end the preview session.
+/// Master volume is carried over too, and the output channel pair if the device stays the same.
+pub fn reinit_engine_preserving_plugin(device_name: Option<&str>, config: AudioConfig) -> Result<(), String> {
+    let old_handle = get_engine_handle();
+    let snapshot = old_handle.as_ref().and_then(|h| h.snapshot_plugin());
+    let master_volume = old_handle.as_ref().map(|h| h.get_master_volume());
+    let output_channels = old_handle
+        .as_ref()
+        .map(|h| (h.device_name().to_string(), h.get_output_channels()));
+    let automation = old_handle.as_ref().and_then(|h| h.get_param_automation());
+
+    // The old stream is leaked rather than dropped, so release the plugin explicitly -
+    // otherwise the old instance lingers and the bundle is loaded twice
+    if let Some(handle) = &old_handle {
+        handle.close_plugin_editor();
+        handle.unload_plugin();
+    }
+
+    reinit_engine(device_name, config)?;
+
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    if let Some(volume) = master_volume {
+        handle.set_master_volume(volume);
+    }
+    if let Some((old_device, (left, right))) = output_channels {
+        if old_device == handle.device_name() {
+            if let Err(e) = handle.set_output_channels(left, right) {
+                log::warn!("Could not restore output channels: {}", e);
+            }
+        }
+    }
+    handle.set_param_automation(automation);
+    if let Some(snapshot) = snapshot {
+        log::info!("Restoring plugin after engine reinit: {}", snapshot.path);
+        handle
+            .restore_plugin_snapshot(&snapshot)
+            .map_err(|e| format!("Audio engine reinitialized but plugin could not be reloaded: {}", e))?;
+    }
+
+    Ok(())
+}
+
 /// Get the current audio engine sample rate
 pub fn get_engine_sample_rate() -> Option<u32> {
     get_engine_handle().map(|h| h.sample_rate)
 }
+
+/// Get the current engine status (running, device lost, no device, or stopped)
+pub fn get_engine_status() -> EngineStatus {
+    match get_engine_handle() {
+        Some(handle) if handle.is_device_lost() => EngineStatus::DeviceLost,
+        Some(handle) if handle.is_null_output() => EngineStatus::NoDevice,
+        Some(_) => EngineStatus::Running,
+        None => EngineStatus::Stopped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK_FRAMES: usize = 256;
+
+    /// Offline stereo engine playing a square wave at `amplitude` (peaks at 0.8 * amplitude),
+    /// with full listening volume so the output shows the limiter directly
+    fn square_engine(amplitude: f32) -> (AudioEngineHandle, AudioProcessor) {
+        let (handle, processor) = AudioEngine::offline(AudioConfig {
+            sample_rate: 48000,
+            channels: 2,
+            buffer_size: BLOCK_FRAMES as u32,
+        });
+        handle.set_input_source(InputSource::Signal {
+            config: SignalConfig {
+                signal_type: SignalType::Square,
+                amplitude,
+                ..SignalConfig::default()
+            },
+        });
+        handle.set_master_volume(1.0);
+        handle.play();
+        (handle, processor)
+    }
+
+    fn render(processor: &mut AudioProcessor) -> Vec<f32> {
+        let mut data = vec![0.0f32; BLOCK_FRAMES * 2];
+        processor.process_block(&mut data, 2);
+        data
+    }
+
+    #[test]
+    fn test_offline_engine_is_silent_until_played() {
+        let (handle, mut processor) = square_engine(1.0);
+        handle.stop();
+        assert!(render(&mut processor).iter().all(|&s| s == 0.0));
+
+        handle.play();
+        assert!(render(&mut processor).iter().any(|&s| s != 0.0));
+        assert_eq!(handle.play_clock(), BLOCK_FRAMES as u64);
+    }
+
+    #[test]
+    fn test_hard_limiter_clamps_to_ceiling() {
+        // Peaks at 2.0 against a -6 dB ceiling
+        let (handle, mut processor) = square_engine(2.5);
+        handle.set_limiter(-6.0, LimiterMode::Hard).unwrap();
+        let ceiling = 10.0f32.powf(-6.0 / 20.0);
+
+        let output = render(&mut processor);
+        assert!(output.iter().all(|s| (s.abs() - ceiling).abs() < 1e-6));
+
+        let (clamped, max_abs) = handle.get_headroom_stats();
+        assert_eq!(clamped, output.len() as u64);
+        assert!((max_abs - 2.0).abs() < 1e-6);
+        assert_eq!(handle.get_clipping(), (true, true));
+        // Meters show the plugin's true output, before the limiter
+        assert!(handle.get_output_levels().0 > ceiling * LEVEL_SMOOTHING);
+    }
+
+    #[test]
+    fn test_soft_limiter_and_volume_stay_under_ceiling() {
+        let (handle, mut processor) = square_engine(1.0);
+        handle.set_limiter(0.0, LimiterMode::Soft).unwrap();
+        handle.set_master_volume(0.5);
+
+        // 0.8 through tanh, then half volume
+        let expected = 0.8f32.tanh() * 0.5;
+        let output = render(&mut processor);
+        assert!(output.iter().all(|s| (s.abs() - expected).abs() < 1e-6));
+        // Soft mode shapes the signal but nothing went over the ceiling
+        assert_eq!(handle.get_headroom_stats().0, 0);
+        assert_eq!(handle.get_clipping(), (false, false));
+    }
+
+    #[test]
+    fn test_triggered_impulse_reaches_output_at_full_scale() {
+        let (handle, mut processor) = square_engine(0.5);
+        assert!(handle.trigger_burst().is_err());
+        handle.set_signal_config(SignalConfig {
+            signal_type: SignalType::Burst,
+            ..SignalConfig::default()
+        });
+        assert!(render(&mut processor).iter().all(|&s| s == 0.0));
+
+        handle.trigger_burst().unwrap();
+        let output = render(&mut processor);
+        assert_eq!(&output[..2], &[1.0, 1.0]);
+        assert!(output[2..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_note_without_plugin_is_not_reported_active() {
+        let (handle, _processor) = square_engine(0.5);
+        // No plugin means no MIDI queue, so nothing is played
+        handle.midi_note_on(5, 100);
+        assert!(!activity::active_notes().contains(&5));
+    }
+
+    #[test]
+    fn test_spectrum_resize_during_playback() {
+        let (handle, mut processor) = square_engine(0.5);
+        for _ in 0..4 {
+            render(&mut processor);
+        }
+        assert!(handle.get_spectrum_data().iter().any(|&m| m > 0.0));
+
+        assert!(handle.set_spectrum_fft_size(3000).is_err());
+        assert!(handle.set_spectrum_fft_size(1 << 20).is_err());
+        handle.set_spectrum_fft_size(4096).unwrap();
+        assert_eq!(handle.get_spectrum_fft_size(), 4096);
+        // Nothing measured at the old size is shown while the rebuild is pending
+        assert!(handle.get_spectrum_data().iter().all(|&m| m == 0.0));
+
+        for _ in 0..4 {
+            render(&mut processor);
+        }
+        assert_eq!(processor.spectrum_analyzer.fft_size(), 4096);
+        assert_eq!(processor.spectrum_analyzer_input.fft_size(), 4096);
+        let bands = handle.get_spectrum_data();
+        assert!(bands.iter().all(|m| m.is_finite()));
+        assert!(bands.iter().any(|&m| m > 0.0));
+        // The callback handed its old analyzers back instead of dropping them
+        {
+            let resize = handle.shared.spectrum_resize.lock();
+            assert!(!resize.fresh);
+            let (output, input) = resize.analyzers.as_ref().unwrap();
+            assert_eq!(output.fft_size(), FFT_SIZE);
+            assert_eq!(input.fft_size(), FFT_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_a_weighted_1khz_matches_unweighted_level() {
+        let (handle, mut processor) = square_engine(0.5);
+        handle.set_signal_config(SignalConfig {
+            signal_type: SignalType::Sine,
+            frequency: 1000.0,
+            amplitude: 0.5,
+            ..SignalConfig::default()
+        });
+        assert_eq!(handle.get_metering_weighting(), MeteringWeighting::None);
+
+        let settle = |processor: &mut AudioProcessor| {
+            for _ in 0..200 {
+                render(processor);
+            }
+        };
+        settle(&mut processor);
+        let reference = handle.get_output_levels().0;
+        let unweighted = handle.get_weighted_output_level();
+        assert!((unweighted - reference).abs() < 1e-3);
+
+        handle.set_metering_weighting(MeteringWeighting::A);
+        settle(&mut processor);
+        let weighted = handle.get_weighted_output_level();
+        let difference_db = 20.0 * (weighted / reference).log10();
+        assert!(difference_db.abs() < 0.2, "A-weighted 1 kHz read {} dB", difference_db);
+        // The raw channel levels are untouched by the weighting
+        assert!((handle.get_output_levels().0 - reference).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_loudness_is_measured_and_reset() {
+        let (handle, mut processor) = square_engine(0.1);
+        assert_eq!(handle.get_loudness(), LoudnessReading::default());
+
+        // Half a second of audio gives a momentary and integrated reading, not yet short-term
+        for _ in 0..100 {
+            render(&mut processor);
+        }
+        let loudness = handle.get_loudness();
+        assert!(loudness.momentary.is_some_and(|l| l < -10.0 && l > -30.0));
+        assert!(loudness.integrated.is_some());
+        assert!(loudness.short_term.is_none());
+
+        handle.reset_loudness();
+        assert_eq!(handle.get_loudness(), LoudnessReading::default());
+        render(&mut processor);
+        assert_eq!(handle.get_loudness(), LoudnessReading::default());
+    }
+
+    #[test]
+    fn test_vst3_plugin_becomes_active() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let (handle, mut processor) = square_engine(0.5);
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+
+        assert_eq!(
+            handle.get_plugin_state(),
+            PluginState::Active {
+                name: mock::MOCK_NAME.to_string(),
+                path: "/plugins/Mock Gain.vst3".to_string(),
+                has_editor: false,
+                is_instrument: false,
+            }
+        );
+        assert!(handle.save_plugin_state().is_some());
+
+        // The mock halves the input
+        let output = render(&mut processor);
+        assert!(output.iter().any(|&s| s != 0.0));
+        assert!(output
+            .iter()
+            .zip(&processor.input_buffer[..output.len()])
+            .all(|(out, input)| *out == input * 0.5));
+
+        handle.unload_plugin();
+        assert_eq!(handle.get_plugin_state(), PluginState::Unloaded);
+
+        // .vst3 paths go to the VST3 loader
+        let error = handle.load_plugin(Path::new("/nonexistent/Missing.vst3")).unwrap_err();
+        assert!(error.contains("VST3"), "{}", error);
+    }
+
+    #[test]
+    fn test_plugin_state_file_round_trip() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let dir = std::env::temp_dir().join(format!("freqlab-plugin-state-{}", std::process::id()));
+        let path = dir.join(".vstworkshop").join("plugin-state.bin");
+        let (handle, mut processor) = square_engine(0.5);
+
+        // Nothing loaded: nothing written
+        assert!(!handle.save_plugin_state_to_file(&path).unwrap());
+        assert!(!path.exists());
+
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+        let mut state = handle.save_plugin_state().unwrap();
+        state[8..12].copy_from_slice(&0.25f32.to_le_bytes());
+        handle.restore_plugin_state(&state).unwrap();
+        assert!(handle.save_plugin_state_to_file(&path).unwrap());
+        handle.unload_plugin();
+
+        // A fresh instance starts at its default gain until the file is loaded
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+        assert_ne!(handle.save_plugin_state().unwrap(), state);
+        handle.load_plugin_state_from_file(&path).unwrap();
+        assert_eq!(handle.save_plugin_state().unwrap(), state);
+
+        let output = render(&mut processor);
+        assert!(output
+            .iter()
+            .zip(&processor.input_buffer[..output.len()])
+            .all(|(out, input)| *out == input * 0.25));
+
+        assert!(handle.load_plugin_state_from_file(&dir.join("missing.bin")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_instrument_override_survives_reload() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let (handle, _processor) = square_engine(0.5);
+        let path = Path::new("/plugins/Mock Gain.vst3");
+        let reload = || {
+            handle.unload_plugin();
+            let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+            handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), path);
+        };
+        let reported = || match handle.get_plugin_state() {
+            PluginState::Active { is_instrument, .. } => is_instrument,
+            state => panic!("plugin not active: {:?}", state),
+        };
+
+        reload();
+        assert!(!handle.is_instrument());
+
+        // The manual choice wins over detection, now and after a hot reload
+        handle.set_is_instrument(true);
+        assert!(reported());
+        reload();
+        assert!(handle.is_instrument());
+        assert!(reported());
+        assert_eq!(handle.snapshot_plugin().unwrap().instrument_override, Some(true));
+
+        handle.clear_instrument_override();
+        reload();
+        assert!(!handle.is_instrument());
+        assert!(!reported());
+    }
+
+    #[test]
+    fn test_bypass_passes_input_through() {
+        let (handle, mut processor) = square_engine(0.5);
+        handle.set_plugin_bypass(true);
+        assert!(handle.is_plugin_bypassed());
+
+        let output = render(&mut processor);
+        assert_eq!(&output[..], &processor.input_buffer[..output.len()]);
+        assert!(output.iter().all(|&s| s != 0.0));
+    }
+
+    #[test]
+    fn test_bypass_keeps_latency_alignment() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let (handle, mut processor) = square_engine(0.5);
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+        handle.shared.plugin_latency.store(32, Ordering::Relaxed);
+        handle.set_plugin_bypass(true);
+
+        let mut output = render(&mut processor);
+        for _ in 0..(CROSSFADE_SAMPLES as usize / (BLOCK_FRAMES * 2) + 2) {
+            output = render(&mut processor);
+        }
+        // Fully bypassed: the input delayed like the plugin path, so nothing jumps
+        assert_eq!(processor.bypass_fade_position, CROSSFADE_SAMPLES);
+        assert_eq!(&output[..], &processor.aligned_dry_buffer[..output.len()]);
+        assert_ne!(&output[..], &processor.input_buffer[..output.len()]);
+    }
+
+    #[test]
+    fn test_bypassed_instrument_fades_to_silence() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let (handle, mut processor) = square_engine(0.5);
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+        handle.set_is_instrument(true);
+        // Left over from an effect loaded before
+        processor.aligned_dry_buffer.fill(1.0);
+        handle.set_plugin_bypass(true);
+
+        let mut output = render(&mut processor);
+        assert!(output.iter().all(|&s| s.abs() < 1.0));
+        for _ in 0..(CROSSFADE_SAMPLES as usize / (BLOCK_FRAMES * 2) + 2) {
+            output = render(&mut processor);
+        }
+        assert_eq!(processor.bypass_fade_position, CROSSFADE_SAMPLES);
+        assert!(output.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_bypass_fades_between_plugin_and_input() {
+        let (_handle, mut processor) = square_engine(0.5);
+        let len = BLOCK_FRAMES * 2;
+        // The fade targets the latency-aligned input, not the raw one
+        processor.input_buffer[..len].fill(-1.0);
+        processor.aligned_dry_buffer[..len].fill(1.0);
+
+        // Plugin output of 0.0 against an input of 1.0: the mix ramps up at the hot reload rate
+        let step = crossfade_gain(2);
+        let mut outputs = Vec::new();
+        while outputs.len() < CROSSFADE_SAMPLES as usize * 3 {
+            let mut block = vec![0.0f32; len];
+            processor.apply_bypass_fade(&mut block, 2, true);
+            outputs.extend(block.chunks(2).map(|frame| frame[0]));
+        }
+        assert!(outputs[0] > 0.0 && outputs[0] < 0.01);
+        assert!(outputs.windows(2).all(|pair| pair[1] >= pair[0] && pair[1] - pair[0] <= step * 1.01));
+        assert_eq!(processor.bypass_fade_position, CROSSFADE_SAMPLES);
+        assert_eq!(*outputs.last().unwrap(), 1.0);
+
+        // And back toward the plugin when bypass is switched off
+        let mut block = vec![0.0f32; len];
+        processor.apply_bypass_fade(&mut block, 2, false);
+        assert!(block[0] < 1.0 && block[len - 2] < block[0]);
+    }
+
+    #[test]
+    fn test_dry_mix_yields_untouched_input() {
+        let (handle, mut processor) = square_engine(0.5);
+        handle.set_plugin_mix(0.0);
+        assert_eq!(handle.get_plugin_mix(), 0.0);
+        handle.set_plugin_mix(f32::NAN);
+        assert_eq!(handle.get_plugin_mix(), 0.0);
+
+        // Without a plugin the input is untouched whatever the mix
+        let output = render(&mut processor);
+        assert_eq!(&output[..], &processor.input_buffer[..output.len()]);
+
+        // Plugin output full of garbage: fully dry leaves exactly the (aligned) input
+        let len = BLOCK_FRAMES * 2;
+        let dry: Vec<f32> = (0..len).map(|i| (i as f32 * 0.01).sin()).collect();
+        processor.aligned_dry_buffer[..len].copy_from_slice(&dry);
+        let mut block = vec![f32::NAN; len];
+        block[1] = f32::INFINITY;
+        // The first block ramps from fully wet
+        processor.apply_mix(&mut block, 2, 0.0);
+        assert!(block.iter().all(|s| s.is_finite()));
+        assert_eq!(&block[len - 2..], &dry[len - 2..]);
+
+        let mut block = vec![f32::NAN; len];
+        processor.apply_mix(&mut block, 2, 0.0);
+        assert_eq!(block, dry);
+
+        // Half way: an even blend
+        let mut block = vec![1.0f32; len];
+        processor.current_mix = 0.5;
+        processor.apply_mix(&mut block, 2, 0.5);
+        assert!(block.iter().zip(&dry).all(|(out, d)| (out - (0.5 + d * 0.5)).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_wet_and_difference_ignore_the_mix() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let (handle, mut processor) = square_engine(0.5);
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+        handle.set_plugin_mix(0.5);
+        render(&mut processor);
+
+        // The mock halves its input: Wet is that alone, not blended with the dry half
+        handle.set_monitor_mode(MonitorMode::Wet);
+        let output = render(&mut processor);
+        assert!(output
+            .iter()
+            .zip(&processor.input_buffer[..output.len()])
+            .all(|(out, input)| *out == input * 0.5));
+
+        // Difference is the plugin's change to the input, whatever the mix
+        handle.set_monitor_mode(MonitorMode::Difference);
+        let output = render(&mut processor);
+        assert!(output
+            .iter()
+            .zip(&processor.input_buffer[..output.len()])
+            .all(|(out, input)| *out == input * 0.5 - input));
+    }
+
+    #[test]
+    fn test_difference_is_silent_when_the_plugin_is_skipped() {
+        use crate::audio::plugin::vst3_host::mock;
+
+        let (handle, mut processor) = square_engine(0.5);
+        let plugin = mock::gain_plugin(48000.0, BLOCK_FRAMES as u32).unwrap();
+        handle.install_plugin(HostedPlugin::Vst3(Box::new(plugin)), Path::new("/plugins/Mock Gain.vst3"));
+        handle.shared.plugin_latency.store(32, Ordering::Relaxed);
+        handle.set_gain_match(true);
+        handle.set_monitor_mode(MonitorMode::Difference);
+        render(&mut processor);
+
+        // A reader holding the plugin lock keeps the callback from processing the block
+        for mode in [MonitorMode::Difference, MonitorMode::Wet] {
+            handle.set_monitor_mode(mode);
+            let _plugin_lock = handle.shared.plugin_instance.read();
+            assert!(render(&mut processor).iter().all(|&s| s == 0.0));
+        }
+
+        // Wet is the plugin output untouched by gain match
+        handle.set_monitor_mode(MonitorMode::Wet);
+        for _ in 0..8 {
+            render(&mut processor);
+        }
+        assert_eq!(handle.get_gain_match_db(), 0.0);
+        assert!(render(&mut processor).iter().any(|&s| s != 0.0));
+    }
+
+    #[test]
+    fn test_nan_output_is_silenced() {
+        let (handle, mut processor) = square_engine(f32::NAN);
+
+        let output = render(&mut processor);
+        assert!(output.iter().all(|&s| s == 0.0));
+        assert_eq!(handle.get_headroom_stats().0, output.len() as u64);
+        assert_eq!(handle.get_clipping(), (true, true));
+
+        // NaN never reaches the meters or the waveform
+        assert_eq!(handle.get_output_levels(), (0.0, 0.0));
+        assert_eq!(handle.get_waveform_peaks(), (0.0, 0.0));
+        let (left, right) = handle.get_waveform_data();
+        assert!(left.iter().chain(right.iter()).all(|s| s.is_finite()));
+    }
+}
@@ -0,0 +1,170 @@
+//! Loudness matching of the plugin output (wet) against the dry input
+//!
+//! When toggling bypass to compare the plugin output with the dry input, the louder
+//! one almost always sounds "better". The matcher tracks the short-term RMS level of
+//! both paths and produces a gain for the wet path that brings it to the same level
+//! as the dry one.
+
+/// Short-term measurement window in seconds (similar to the 400ms LUFS momentary window)
+const WINDOW_SECONDS: f32 = 0.4;
+
+/// Mean-square level below which a path is treated as silent (~-70 dBFS RMS)
+const SILENCE_THRESHOLD: f32 = 1e-7;
+
+/// Maximum compensation applied in either direction
+pub const MAX_COMPENSATION_DB: f32 = 24.0;
+
+/// Per-block smoothing for the applied gain (avoids zipper noise on changes)
+const GAIN_SMOOTHING: f32 = 0.05;
+
+/// Tracks the level of the dry (reference) and wet (processed) paths and computes a
+/// matching gain for the wet one
+pub struct GainMatcher {
+    /// Smoothed mean-square of the reference (dry) path
+    reference_ms: f32,
+    /// Smoothed mean-square of the processed (wet) path
+    processed_ms: f32,
+    /// Current target compensation in dB (held while either path is silent)
+    target_db: f32,
+    /// Gain currently applied (linear), ramps toward the target
+    current_gain: f32,
+    sample_rate: f32,
+    /// Time constant of the level measurement in seconds
+    window_seconds: f32,
+}
+
+impl GainMatcher {
+    pub fn new(sample_rate: u32) -> Self {
+        Self::with_window(sample_rate, WINDOW_SECONDS)
+    }
+
+    /// Create a matcher with a custom measurement time constant
+    /// Longer windows react slowly and only follow sustained level differences.
+    pub fn with_window(sample_rate: u32, window_seconds: f32) -> Self {
+        Self {
+            reference_ms: 0.0,
+            processed_ms: 0.0,
+            target_db: 0.0,
+            current_gain: 1.0,
+            sample_rate: sample_rate as f32,
+            window_seconds,
+        }
+    }
+
+    /// Change the measurement time constant (keeps the current measurement)
+    pub fn set_window(&mut self, window_seconds: f32) {
+        self.window_seconds = window_seconds;
+    }
+
+    /// Clear the measurement and return to unity gain
+    pub fn reset(&mut self) {
+        self.reference_ms = 0.0;
+        self.processed_ms = 0.0;
+        self.target_db = 0.0;
+        self.current_gain = 1.0;
+    }
+
+    /// Measure one block of both paths (interleaved, same length) and update the target gain
+    pub fn measure(&mut self, reference: &[f32], processed: &[f32], channels: usize) {
+        let len = reference.len().min(processed.len());
+        if len == 0 {
+            return;
+        }
+
+        let block_ms = |samples: &[f32]| {
+            let sum: f32 = samples
+                .iter()
+                .filter(|s| s.is_finite())
+                .map(|s| s * s)
+                .sum();
+            sum / samples.len() as f32
+        };
+        let reference_block = block_ms(&reference[..len]);
+        let processed_block = block_ms(&processed[..len]);
+
+        // One-pole averaging with a time constant of window_seconds
+        let frames = (len / channels.max(1)) as f32;
+        let coeff = 1.0 - (-frames / (self.window_seconds * self.sample_rate)).exp();
+        self.reference_ms += (reference_block - self.reference_ms) * coeff;
+        self.processed_ms += (processed_block - self.processed_ms) * coeff;
+
+        // Hold the last offset through silence (e.g. gated signals, instrument plugins)
+        if self.reference_ms > SILENCE_THRESHOLD && self.processed_ms > SILENCE_THRESHOLD {
+            let db = 10.0 * (self.reference_ms / self.processed_ms).log10();
+            self.target_db = db.clamp(-MAX_COMPENSATION_DB, MAX_COMPENSATION_DB);
+        }
+    }
+
+    /// Apply the compensating gain to a block, ramping from the previous gain
+    pub fn apply(&mut self, data: &mut [f32]) {
+        let target_gain = 10.0f32.powf(self.target_db / 20.0);
+        let start_gain = self.current_gain;
+        let end_gain = start_gain + (target_gain - start_gain) * GAIN_SMOOTHING;
+        let len = data.len().max(1) as f32;
+        for (i, sample) in data.iter_mut().enumerate() {
+            *sample *= start_gain + (end_gain - start_gain) * (i as f32 / len);
+        }
+        self.current_gain = end_gain;
+    }
+
+    /// Compensation currently being applied, in dB
+    pub fn applied_db(&self) -> f32 {
+        20.0 * self.current_gain.max(1e-6).log10()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gain_match_converges_to_reference_level() {
+        let mut matcher = GainMatcher::new(48000);
+        let reference: Vec<f32> = (0..1024).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        // Processed path is 6 dB louder
+        let processed: Vec<f32> = reference.iter().map(|s| s * 2.0).collect();
+
+        for _ in 0..500 {
+            matcher.measure(&reference, &processed, 2);
+            let mut block = processed.clone();
+            matcher.apply(&mut block);
+        }
+
+        assert!((matcher.applied_db() + 6.02).abs() < 0.1, "applied {} dB", matcher.applied_db());
+    }
+
+    #[test]
+    fn test_longer_window_reacts_slower() {
+        let mut fast = GainMatcher::new(48000);
+        let mut slow = GainMatcher::with_window(48000, 3.0);
+        let reference = vec![0.25f32; 1024];
+        let processed = vec![0.5f32; 1024];
+
+        // ~0.2s of audio: the default window is well on its way, the slow one barely moved
+        for _ in 0..10 {
+            fast.measure(&reference, &processed, 2);
+            slow.measure(&reference, &processed, 2);
+        }
+        assert!(slow.processed_ms < fast.processed_ms);
+        assert!(slow.reference_ms < fast.reference_ms);
+    }
+
+    #[test]
+    fn test_gain_match_holds_through_silence() {
+        let mut matcher = GainMatcher::new(48000);
+        let reference = vec![0.25f32; 1024];
+        let processed = vec![0.5f32; 1024];
+        for _ in 0..500 {
+            matcher.measure(&reference, &processed, 2);
+            matcher.apply(&mut [0.0; 1024]);
+        }
+        let before = matcher.applied_db();
+
+        let silence = vec![0.0f32; 1024];
+        for _ in 0..500 {
+            matcher.measure(&silence, &silence, 2);
+            matcher.apply(&mut [0.0; 1024]);
+        }
+        assert!((matcher.applied_db() - before).abs() < 0.01);
+    }
+}
@@ -0,0 +1,210 @@
+//! Synthetic kick drum for driving an effect plugin's sidechain input
+//!
+//! Generates a short decaying sine "ghost kick" on a tempo-synced rhythm so
+//! sidechain ducking can be auditioned without loading a drum instrument.
+
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Kick pitch sweeps from START to END Hz
+const KICK_START_FREQ: f32 = 150.0;
+const KICK_END_FREQ: f32 = 50.0;
+/// Pitch sweep time constant (seconds)
+const KICK_PITCH_DECAY: f32 = 0.03;
+/// Amplitude envelope time constant (seconds)
+const KICK_AMP_DECAY: f32 = 0.06;
+/// The kick is cut off after this long (envelope is below -40dB by then)
+const KICK_LENGTH: f32 = 0.3;
+/// Peak level of the kick
+const KICK_AMPLITUDE: f32 = 0.8;
+
+/// Tempo range, matching the pattern player
+pub const MIN_GHOST_KICK_BPM: f32 = 20.0;
+pub const MAX_GHOST_KICK_BPM: f32 = 400.0;
+
+const PATTERN_QUARTER: u8 = 0;
+const PATTERN_HALF: u8 = 1;
+const PATTERN_EIGHTH: u8 = 2;
+const PATTERN_OFFBEAT: u8 = 3;
+
+/// Rhythm the ghost kick is triggered on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GhostKickPattern {
+    /// Every beat (four on the floor)
+    Quarter,
+    /// Every other beat (beats 1 and 3)
+    Half,
+    /// Every eighth note
+    Eighth,
+    /// Between the beats (the "and")
+    Offbeat,
+}
+
+impl GhostKickPattern {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            GhostKickPattern::Quarter => PATTERN_QUARTER,
+            GhostKickPattern::Half => PATTERN_HALF,
+            GhostKickPattern::Eighth => PATTERN_EIGHTH,
+            GhostKickPattern::Offbeat => PATTERN_OFFBEAT,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            PATTERN_HALF => GhostKickPattern::Half,
+            PATTERN_EIGHTH => GhostKickPattern::Eighth,
+            PATTERN_OFFBEAT => GhostKickPattern::Offbeat,
+            _ => GhostKickPattern::Quarter,
+        }
+    }
+
+    /// (interval between hits, offset of the first hit) in beats
+    fn timing(self) -> (f64, f64) {
+        match self {
+            GhostKickPattern::Quarter => (1.0, 0.0),
+            GhostKickPattern::Half => (2.0, 0.0),
+            GhostKickPattern::Eighth => (0.5, 0.0),
+            GhostKickPattern::Offbeat => (1.0, 0.5),
+        }
+    }
+}
+
+/// Tempo-synced kick drum generator
+pub struct GhostKick {
+    sample_rate: f32,
+    bpm: f32,
+    pattern: GhostKickPattern,
+    /// Beats elapsed since the last reset
+    position: f64,
+    /// Index of the most recent hit slot (triggers when it changes)
+    last_hit: i64,
+    /// Time since the current kick started (seconds), None when silent
+    kick_time: Option<f32>,
+    /// Oscillator phase (radians)
+    phase: f32,
+}
+
+impl GhostKick {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1) as f32,
+            bpm: 120.0,
+            pattern: GhostKickPattern::Quarter,
+            position: 0.0,
+            last_hit: i64::MIN,
+            kick_time: None,
+            phase: 0.0,
+        }
+    }
+
+    /// Set the tempo (clamped to MIN/MAX_GHOST_KICK_BPM, non-finite values are ignored)
+    pub fn set_bpm(&mut self, bpm: f32) {
+        if bpm.is_finite() {
+            self.bpm = bpm.clamp(MIN_GHOST_KICK_BPM, MAX_GHOST_KICK_BPM);
+        }
+    }
+
+    /// Set the rhythm; restarts on the next downbeat
+    pub fn set_pattern(&mut self, pattern: GhostKickPattern) {
+        self.pattern = pattern;
+        self.reset();
+    }
+
+    /// Restart at beat 0 (the first hit of an on-beat pattern plays immediately)
+    pub fn reset(&mut self) {
+        self.position = 0.0;
+        self.last_hit = i64::MIN;
+        self.kick_time = None;
+        self.phase = 0.0;
+    }
+
+    /// Generate the next sample
+    pub fn next_sample(&mut self) -> f32 {
+        let (interval, offset) = self.pattern.timing();
+        let hit = ((self.position - offset) / interval).floor() as i64;
+        if hit != self.last_hit {
+            self.last_hit = hit;
+            if hit >= 0 {
+                self.kick_time = Some(0.0);
+                self.phase = 0.0;
+            }
+        }
+        self.position += self.bpm as f64 / 60.0 / self.sample_rate as f64;
+
+        let Some(t) = self.kick_time else {
+            return 0.0;
+        };
+        if t >= KICK_LENGTH {
+            self.kick_time = None;
+            return 0.0;
+        }
+
+        let freq = KICK_END_FREQ + (KICK_START_FREQ - KICK_END_FREQ) * (-t / KICK_PITCH_DECAY).exp();
+        let sample = self.phase.sin() * (-t / KICK_AMP_DECAY).exp() * KICK_AMPLITUDE;
+
+        self.phase += 2.0 * PI * freq / self.sample_rate;
+        if self.phase >= 2.0 * PI {
+            self.phase -= 2.0 * PI;
+        }
+        self.kick_time = Some(t + 1.0 / self.sample_rate);
+
+        sample
+    }
+
+    /// Fill an interleaved stereo buffer with the kick (same signal on both channels)
+    pub fn fill_stereo(&mut self, buffer: &mut [f32]) {
+        for frame in buffer.chunks_mut(2) {
+            let sample = self.next_sample();
+            frame.fill(sample);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Peak level in a range of a mono render
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |acc, s| acc.max(s.abs()))
+    }
+
+    #[test]
+    fn test_kicks_land_on_pattern_hits() {
+        // 120 BPM at 48kHz = 24000 samples per beat
+        let mut kick = GhostKick::new(48000);
+        kick.set_bpm(120.0);
+        let quarter: Vec<f32> = (0..48000).map(|_| kick.next_sample()).collect();
+
+        // A kick right at each beat, silence before the next one
+        assert!(peak(&quarter[0..480]) > 0.3);
+        assert!(peak(&quarter[18000..24000]) < 1e-3);
+        assert!(peak(&quarter[24000..24480]) > 0.3);
+
+        // Offbeat: quiet on the beat, kick half a beat later
+        kick.set_pattern(GhostKickPattern::Offbeat);
+        let offbeat: Vec<f32> = (0..24000).map(|_| kick.next_sample()).collect();
+        assert!(peak(&offbeat[0..11000]) < 1e-3);
+        assert!(peak(&offbeat[12000..12480]) > 0.3);
+
+        // Half: nothing on beat 2
+        kick.set_pattern(GhostKickPattern::Half);
+        let half: Vec<f32> = (0..48000).map(|_| kick.next_sample()).collect();
+        assert!(peak(&half[0..480]) > 0.3);
+        assert!(peak(&half[24000..48000]) < 1e-3);
+    }
+
+    #[test]
+    fn test_kick_decays() {
+        let mut kick = GhostKick::new(48000);
+        let samples: Vec<f32> = (0..24000).map(|_| kick.next_sample()).collect();
+
+        // Energy is concentrated at the start and fades out
+        let early = peak(&samples[0..2400]);
+        let late = peak(&samples[9600..12000]);
+        assert!(early > 0.5, "early peak {}", early);
+        assert!(late < early * 0.1, "late peak {}", late);
+    }
+}
@@ -6,7 +6,7 @@
 use cpal::traits::{DeviceTrait, StreamTrait};
 use parking_lot::{Mutex, RwLock};
 use ringbuf::{traits::*, HeapRb};
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, Ordering};
 use std::sync::Arc;
 
 use super::buffer::StereoSample;
@@ -24,6 +24,31 @@ fn u32_to_f32(u: u32) -> f32 {
     f32::from_bits(u)
 }
 
+/// Physical input channels routed to L/R, kept across capture restarts
+static SELECTED_LEFT_CHANNEL: AtomicU16 = AtomicU16::new(0);
+static SELECTED_RIGHT_CHANNEL: AtomicU16 = AtomicU16::new(1);
+
+/// Pick the L/R samples from one interleaved input frame
+/// A channel index past the end of the frame falls back to the first channel,
+/// so a mono input feeds both sides.
+#[inline]
+fn select_stereo(frame: &[f32], left: usize, right: usize) -> (f32, f32) {
+    let first = frame[0];
+    (
+        frame.get(left).copied().unwrap_or(first),
+        frame.get(right).copied().unwrap_or(first),
+    )
+}
+
+/// The saved L/R selection if the device has those channels, otherwise its first two
+fn initial_channel_selection(channels: u16, left: u16, right: u16) -> (u16, u16) {
+    if left < channels && right < channels {
+        (left, right)
+    } else {
+        (0, 1.min(channels.saturating_sub(1)))
+    }
+}
+
 /// Shared state between input stream and readers
 struct InputSharedState {
     /// Ring buffer consumer (read side) - wrapped in Mutex for thread-safe access
@@ -36,8 +61,11 @@ struct InputSharedState {
     is_active: AtomicBool,
     /// Error state
     error: RwLock<Option<String>>,
-    /// Number of input channels (1 = mono, 2 = stereo)
+    /// Number of channels the input device provides (1 = mono, 2 = stereo, more for interfaces)
     input_channels: u16,
+    /// Physical channels that feed the left/right inputs
+    left_channel: AtomicU16,
+    right_channel: AtomicU16,
     /// Actual sample rate of input device
     actual_sample_rate: u32,
 }
@@ -94,6 +122,34 @@ impl InputCaptureHandle {
         self.shared.actual_sample_rate
     }
 
+    /// Get the number of channels the input device provides
+    pub fn channel_count(&self) -> u16 {
+        self.shared.input_channels
+    }
+
+    /// Get the physical channels feeding the left/right inputs
+    pub fn input_channels(&self) -> (u16, u16) {
+        (
+            self.shared.left_channel.load(Ordering::Relaxed),
+            self.shared.right_channel.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Choose which physical channels (0-based) feed the left/right inputs
+    /// Pass the same channel twice to use a single input on both sides.
+    pub fn set_input_channels(&self, left: u16, right: u16) -> Result<(), String> {
+        let channels = self.shared.input_channels;
+        if left >= channels || right >= channels {
+            return Err(format!(
+                "Input channels {}/{} out of range - the device has {} channel(s)",
+                left, right, channels
+            ));
+        }
+        self.shared.left_channel.store(left, Ordering::Relaxed);
+        self.shared.right_channel.store(right, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Get the number of available samples in the buffer
     pub fn available_samples(&self) -> usize {
         self.shared.consumer.lock().occupied_len()
@@ -143,6 +199,13 @@ impl InputCapture {
         let rb = HeapRb::new(buffer_size_samples);
         let (mut producer, consumer) = rb.split();
 
+        let (left_channel, right_channel) = initial_channel_selection(
+            channels,
+            SELECTED_LEFT_CHANNEL.load(Ordering::Relaxed),
+            SELECTED_RIGHT_CHANNEL.load(Ordering::Relaxed),
+        );
+        log::info!("Input channels: {} -> L, {} -> R", left_channel, right_channel);
+
         // Create shared state
         let shared = Arc::new(InputSharedState {
             consumer: Mutex::new(consumer),
@@ -151,6 +214,8 @@ impl InputCapture {
             is_active: AtomicBool::new(true),
             error: RwLock::new(None),
             input_channels: channels,
+            left_channel: AtomicU16::new(left_channel),
+            right_channel: AtomicU16::new(right_channel),
             actual_sample_rate,
         });
 
@@ -163,19 +228,14 @@ impl InputCapture {
                 &stream_config,
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
                     let channels = shared_clone.input_channels as usize;
+                    let left_channel = shared_clone.left_channel.load(Ordering::Relaxed) as usize;
+                    let right_channel = shared_clone.right_channel.load(Ordering::Relaxed) as usize;
                     let mut peak_left = 0.0f32;
                     let mut peak_right = 0.0f32;
 
-                    // Process input samples
+                    // Process input samples (mono is duplicated to both channels)
                     for chunk in data.chunks(channels) {
-                        let (left, right) = if channels == 1 {
-                            // Mono input - duplicate to both channels
-                            let mono = chunk[0];
-                            (mono, mono)
-                        } else {
-                            // Stereo input
-                            (chunk[0], chunk.get(1).copied().unwrap_or(chunk[0]))
-                        };
+                        let (left, right) = select_stereo(chunk, left_channel, right_channel);
 
                         // Track peak levels
                         peak_left = peak_left.max(left.abs());
@@ -291,6 +351,18 @@ pub fn stop_input_capture() {
     // This is the same pattern used by the main audio engine
 }
 
+/// Choose which physical input channels feed L/R, for the running capture and later ones
+/// Errors if a capture is running and the device doesn't have those channels.
+pub fn set_input_channels(left: u16, right: u16) -> Result<(), String> {
+    if let Some(handle) = get_input_handle() {
+        handle.set_input_channels(left, right)?;
+    }
+    SELECTED_LEFT_CHANNEL.store(left, Ordering::Relaxed);
+    SELECTED_RIGHT_CHANNEL.store(right, Ordering::Relaxed);
+    log::info!("Input channels set: {} -> L, {} -> R", left, right);
+    Ok(())
+}
+
 /// Get the global input capture handle (if active)
 pub fn get_input_handle() -> Option<InputCaptureHandle> {
     INPUT_HANDLE
@@ -302,3 +374,28 @@ pub fn get_input_handle() -> Option<InputCaptureHandle> {
 pub fn is_input_capture_active() -> bool {
     get_input_handle().map(|h| h.is_active()).unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_stereo() {
+        // Mono feeds both sides
+        assert_eq!(select_stereo(&[0.5], 0, 1), (0.5, 0.5));
+        // Plain stereo
+        assert_eq!(select_stereo(&[0.1, 0.2], 0, 1), (0.1, 0.2));
+        // Picking a pair out of a multi-channel interface
+        assert_eq!(select_stereo(&[0.1, 0.2, 0.3, 0.4], 2, 3), (0.3, 0.4));
+        // One input on both sides
+        assert_eq!(select_stereo(&[0.1, 0.2, 0.3], 1, 1), (0.2, 0.2));
+    }
+
+    #[test]
+    fn test_initial_channel_selection() {
+        assert_eq!(initial_channel_selection(8, 4, 5), (4, 5));
+        // Saved pair doesn't exist on this device
+        assert_eq!(initial_channel_selection(2, 4, 5), (0, 1));
+        assert_eq!(initial_channel_selection(1, 0, 1), (0, 0));
+    }
+}
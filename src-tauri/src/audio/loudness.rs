@@ -0,0 +1,265 @@
+//! Loudness measurement per ITU-R BS.1770-4 / EBU R128
+//!
+//! The signal is K-weighted per channel and its mean square is collected in 100 ms
+//! segments. Four segments make a 400 ms gating block, so consecutive blocks overlap by 75%:
+//! - Momentary loudness: the latest 400 ms block
+//! - Short-term loudness: the latest 3 s (30 segments)
+//! - Integrated loudness: every block since the last reset, gated twice - blocks below
+//!   -70 LUFS are dropped (absolute gate), then blocks more than 10 LU below the loudness
+//!   of what's left are dropped too (relative gate)
+//!
+//! Blocks for the integrated value go into a fixed histogram of 0.01 LU bins that keeps the
+//! block count and summed energy per bin, so measuring for hours never allocates on the audio
+//! thread. Energies are exact; only the relative gate threshold is quantized to a bin edge.
+
+use serde::Serialize;
+
+use super::weighting::{MeteringWeighting, WeightingFilter};
+
+/// Offset in the BS.1770 loudness formula (makes a 1 kHz sine read its RMS level in dBFS)
+const LOUDNESS_OFFSET: f64 = -0.691;
+
+/// Blocks quieter than this never count toward integrated loudness
+pub const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Blocks this far below the absolute-gated loudness are dropped by the relative gate
+const RELATIVE_GATE_LU: f64 = 10.0;
+
+/// Segments per gating block (400 ms of 100 ms segments = 75% overlap)
+const BLOCK_SEGMENTS: usize = 4;
+
+/// Segments in the short-term window (3 s)
+const SHORT_TERM_SEGMENTS: usize = 30;
+
+/// Integrated loudness histogram: bin width and the loudest block it distinguishes
+const HISTOGRAM_RESOLUTION_LU: f64 = 0.01;
+const HISTOGRAM_MAX_LUFS: f64 = 10.0;
+
+/// Loudness readings in LUFS (None until enough audio has been measured)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct LoudnessReading {
+    /// Latest 400 ms
+    pub momentary: Option<f32>,
+    /// Latest 3 s
+    pub short_term: Option<f32>,
+    /// Gated loudness since the last reset
+    pub integrated: Option<f32>,
+}
+
+fn energy_to_lufs(energy: f64) -> f64 {
+    LOUDNESS_OFFSET + 10.0 * energy.log10()
+}
+
+/// Measures loudness of an interleaved stereo (or mono) stream
+pub struct LoudnessMeter {
+    filters: [WeightingFilter; 2],
+    /// Frames per 100 ms segment
+    segment_frames: usize,
+    /// Weighted energy (summed over channels) and frame count of the segment in progress
+    segment_energy: f64,
+    segment_progress: usize,
+    /// Mean square of the most recent completed segments (ring buffer)
+    segments: [f64; SHORT_TERM_SEGMENTS],
+    /// Completed segments since the last reset
+    segment_count: usize,
+    /// Gating blocks per histogram bin, and their summed mean square
+    block_counts: Vec<u64>,
+    block_energy: Vec<f64>,
+    momentary: Option<f64>,
+    short_term: Option<f64>,
+    integrated: Option<f64>,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        let bins = ((HISTOGRAM_MAX_LUFS - ABSOLUTE_GATE_LUFS) / HISTOGRAM_RESOLUTION_LU).round() as usize;
+        Self {
+            filters: [WeightingFilter::new(MeteringWeighting::K, sample_rate); 2],
+            segment_frames: (sample_rate as usize / 10).max(1),
+            segment_energy: 0.0,
+            segment_progress: 0,
+            segments: [0.0; SHORT_TERM_SEGMENTS],
+            segment_count: 0,
+            block_counts: vec![0; bins],
+            block_energy: vec![0.0; bins],
+            momentary: None,
+            short_term: None,
+            integrated: None,
+        }
+    }
+
+    /// Measure a block of interleaved samples (only the first two channels are used)
+    pub fn process(&mut self, data: &[f32], channels: usize) {
+        let channels = channels.max(1);
+        let measured = channels.min(2);
+        for frame in data.chunks_exact(channels) {
+            for (channel, &sample) in frame[..measured].iter().enumerate() {
+                let sample = if sample.is_finite() { sample } else { 0.0 };
+                let weighted = self.filters[channel].process(sample) as f64;
+                self.segment_energy += weighted * weighted;
+            }
+            self.segment_progress += 1;
+            if self.segment_progress == self.segment_frames {
+                self.finish_segment();
+            }
+        }
+    }
+
+    fn finish_segment(&mut self) {
+        let mean_square = self.segment_energy / self.segment_frames as f64;
+        self.segments[self.segment_count % SHORT_TERM_SEGMENTS] = mean_square;
+        self.segment_count += 1;
+        self.segment_energy = 0.0;
+        self.segment_progress = 0;
+
+        if self.segment_count >= SHORT_TERM_SEGMENTS {
+            let energy = self.segments.iter().sum::<f64>() / SHORT_TERM_SEGMENTS as f64;
+            self.short_term = Some(energy_to_lufs(energy));
+        }
+
+        if self.segment_count >= BLOCK_SEGMENTS {
+            let block_energy = (1..=BLOCK_SEGMENTS)
+                .map(|back| self.segments[(self.segment_count - back) % SHORT_TERM_SEGMENTS])
+                .sum::<f64>()
+                / BLOCK_SEGMENTS as f64;
+            let block_lufs = energy_to_lufs(block_energy);
+            self.momentary = Some(block_lufs);
+
+            if block_lufs > ABSOLUTE_GATE_LUFS {
+                let bin = self.bin(block_lufs);
+                self.block_counts[bin] += 1;
+                self.block_energy[bin] += block_energy;
+                self.integrated = self.gated_loudness();
+            }
+        }
+    }
+
+    /// Histogram bin for a block loudness (anything louder than the top lands in the last bin)
+    fn bin(&self, lufs: f64) -> usize {
+        let bin = ((lufs - ABSOLUTE_GATE_LUFS) / HISTOGRAM_RESOLUTION_LU).floor().max(0.0) as usize;
+        bin.min(self.block_counts.len() - 1)
+    }
+
+    /// Integrated loudness of the blocks that pass both gates
+    fn gated_loudness(&self) -> Option<f64> {
+        let mean_from = |first_bin: usize| {
+            let count: u64 = self.block_counts[first_bin..].iter().sum();
+            let energy: f64 = self.block_energy[first_bin..].iter().sum();
+            (count > 0).then(|| energy / count as f64)
+        };
+
+        // Everything in the histogram already passed the absolute gate
+        let relative_gate = energy_to_lufs(mean_from(0)?) - RELATIVE_GATE_LU;
+        let first_bin = if relative_gate > ABSOLUTE_GATE_LUFS { self.bin(relative_gate) } else { 0 };
+        mean_from(first_bin).map(energy_to_lufs)
+    }
+
+    /// Current readings (digital silence has no loudness and reads as None)
+    pub fn reading(&self) -> LoudnessReading {
+        let lufs = |value: Option<f64>| value.filter(|l| l.is_finite()).map(|l| l as f32);
+        LoudnessReading {
+            momentary: lufs(self.momentary),
+            short_term: lufs(self.short_term),
+            integrated: lufs(self.integrated),
+        }
+    }
+
+    /// Start measuring from scratch
+    pub fn reset(&mut self) {
+        for filter in &mut self.filters {
+            filter.reset();
+        }
+        self.segment_energy = 0.0;
+        self.segment_progress = 0;
+        self.segments = [0.0; SHORT_TERM_SEGMENTS];
+        self.segment_count = 0;
+        self.block_counts.fill(0);
+        self.block_energy.fill(0.0);
+        self.momentary = None;
+        self.short_term = None;
+        self.integrated = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::samples::AudioSample;
+    use std::path::Path;
+
+    /// 10 cycles of a 1 kHz stereo sine at -23 dBFS (48 kHz, 16-bit) - loops seamlessly
+    /// and reads -23 LUFS, like the EBU Tech 3341 reference signal
+    fn reference_sine() -> (Vec<f32>, u32) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/audio/test_fixtures/sine_1k_minus23.wav");
+        let sample = AudioSample::load(&path).unwrap();
+        let interleaved = sample.data.iter().flat_map(|s| [s.left, s.right]).collect();
+        (interleaved, sample.info.sample_rate)
+    }
+
+    /// Feed `seconds` of the looped reference, scaled by `gain_db`
+    fn feed(meter: &mut LoudnessMeter, reference: &[f32], sample_rate: u32, seconds: f32, gain_db: f32) {
+        let gain = 10.0f32.powf(gain_db / 20.0);
+        let scaled: Vec<f32> = reference.iter().map(|s| s * gain).collect();
+        let loops = (seconds * sample_rate as f32) as usize / (reference.len() / 2);
+        for _ in 0..loops {
+            meter.process(&scaled, 2);
+        }
+    }
+
+    fn assert_lufs(value: Option<f32>, expected: f32, what: &str) {
+        let value = value.unwrap_or_else(|| panic!("no {} reading", what));
+        assert!((value - expected).abs() < 0.1, "{} read {} LUFS, expected {}", what, value, expected);
+    }
+
+    #[test]
+    fn test_reference_sine_reads_minus_23_lufs() {
+        let (reference, sample_rate) = reference_sine();
+        assert_eq!(sample_rate, 48000);
+        let mut meter = LoudnessMeter::new(sample_rate);
+        assert_eq!(meter.reading(), LoudnessReading::default());
+
+        // Momentary needs 400 ms, short-term 3 s
+        feed(&mut meter, &reference, sample_rate, 1.0, 0.0);
+        assert!(meter.reading().momentary.is_some());
+        assert!(meter.reading().short_term.is_none());
+
+        feed(&mut meter, &reference, sample_rate, 19.0, 0.0);
+        let reading = meter.reading();
+        assert_lufs(reading.momentary, -23.0, "momentary");
+        assert_lufs(reading.short_term, -23.0, "short-term");
+        assert_lufs(reading.integrated, -23.0, "integrated");
+
+        meter.reset();
+        assert_eq!(meter.reading(), LoudnessReading::default());
+    }
+
+    #[test]
+    fn test_integrated_loudness_gating() {
+        let (reference, sample_rate) = reference_sine();
+        let mut meter = LoudnessMeter::new(sample_rate);
+
+        // Near-silence is below the absolute gate and doesn't drag the reading down...
+        feed(&mut meter, &reference, sample_rate, 10.0, 0.0);
+        feed(&mut meter, &reference, sample_rate, 10.0, -60.0);
+        let reading = meter.reading();
+        assert_lufs(reading.integrated, -23.0, "integrated");
+        assert!(reading.momentary.unwrap() < -80.0);
+
+        // ...a passage 15 LU down falls under the relative gate (ungated mean -25.9, gate -35.9)...
+        feed(&mut meter, &reference, sample_rate, 10.0, -15.0);
+        assert_lufs(meter.reading().integrated, -23.0, "integrated");
+        assert_lufs(meter.reading().short_term, -38.0, "short-term");
+
+        // ...but one 7 LU down passes it and counts: equal time at -23 and -30 averages the energy
+        meter.reset();
+        feed(&mut meter, &reference, sample_rate, 10.0, 0.0);
+        feed(&mut meter, &reference, sample_rate, 10.0, -7.0);
+        let expected = 10.0 * ((1.0 + 10.0f32.powf(-0.7)) / 2.0).log10() - 23.0;
+        assert_lufs(meter.reading().integrated, expected, "integrated");
+
+        // Nothing above the absolute gate: no integrated reading at all
+        meter.reset();
+        feed(&mut meter, &reference, sample_rate, 2.0, -60.0);
+        assert!(meter.reading().integrated.is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! Held-note tracking for UI feedback
+//!
+//! Notes can reach the plugin from the on-screen keyboard (commands) or from a hardware
+//! device. Both paths report here so the frontend keyboard can light up the same way
+//! regardless of where a note came from.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::RwLock;
+
+/// Where a note event originated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteSource {
+    /// On-screen keyboard / Tauri MIDI commands
+    Command,
+    /// Live MIDI input device
+    Device,
+}
+
+/// A note on/off reported to the frontend (velocity 0 = note off)
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct NoteActivity {
+    /// MIDI note number (0-127)
+    pub note: u8,
+    /// Velocity (0-127), 0 when the note was released
+    pub velocity: u8,
+    /// Where the note came from
+    pub source: NoteSource,
+}
+
+/// Lock-free set of held notes (128-bit bitset, channel-agnostic)
+pub struct ActiveNotes {
+    bits: [AtomicU64; 2],
+}
+
+impl ActiveNotes {
+    /// Create an empty set
+    pub const fn new() -> Self {
+        Self {
+            bits: [AtomicU64::new(0), AtomicU64::new(0)],
+        }
+    }
+
+    /// Mark a note as held
+    #[inline]
+    pub fn insert(&self, note: u8) {
+        let note = note & 0x7F;
+        self.bits[(note / 64) as usize].fetch_or(1u64 << (note % 64), Ordering::Relaxed);
+    }
+
+    /// Mark a note as released
+    #[inline]
+    pub fn remove(&self, note: u8) {
+        let note = note & 0x7F;
+        self.bits[(note / 64) as usize].fetch_and(!(1u64 << (note % 64)), Ordering::Relaxed);
+    }
+
+    /// Release every note
+    pub fn clear(&self) {
+        for word in &self.bits {
+            word.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Held notes in ascending order
+    pub fn to_sorted_vec(&self) -> Vec<u8> {
+        let mut notes = Vec::new();
+        for (index, word) in self.bits.iter().enumerate() {
+            let bits = word.load(Ordering::Relaxed);
+            for bit in 0..64u8 {
+                if bits & (1u64 << bit) != 0 {
+                    notes.push(index as u8 * 64 + bit);
+                }
+            }
+        }
+        notes
+    }
+}
+
+impl Default for ActiveNotes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Callback invoked for every note on/off (used to emit the `midi-note` event)
+type NoteListener = Box<dyn Fn(NoteActivity) + Send + Sync>;
+
+/// Notes currently held across all sources
+static ACTIVE_NOTES: ActiveNotes = ActiveNotes::new();
+
+/// Listener for note activity (set once the app handle is available)
+static NOTE_LISTENER: RwLock<Option<NoteListener>> = RwLock::new(None);
+
+/// Register the listener notified on every note on/off
+pub fn set_note_listener(listener: impl Fn(NoteActivity) + Send + Sync + 'static) {
+    *NOTE_LISTENER.write() = Some(Box::new(listener));
+}
+
+fn notify(activity: NoteActivity) {
+    if let Some(listener) = NOTE_LISTENER.read().as_ref() {
+        listener(activity);
+    }
+}
+
+/// Record a note on (velocity 0 is treated as a note off, per MIDI convention)
+pub fn record_note_on(note: u8, velocity: u8, source: NoteSource) {
+    if velocity == 0 {
+        record_note_off(note, source);
+        return;
+    }
+    ACTIVE_NOTES.insert(note);
+    notify(NoteActivity { note, velocity, source });
+}
+
+/// Record a note off
+pub fn record_note_off(note: u8, source: NoteSource) {
+    ACTIVE_NOTES.remove(note);
+    notify(NoteActivity { note, velocity: 0, source });
+}
+
+/// Release all held notes (all-notes-off, plugin unload), reporting a note off for each
+pub fn clear_active_notes(source: NoteSource) {
+    let held = ACTIVE_NOTES.to_sorted_vec();
+    ACTIVE_NOTES.clear();
+    for note in held {
+        notify(NoteActivity { note, velocity: 0, source });
+    }
+}
+
+/// Currently held notes, sorted ascending
+pub fn active_notes() -> Vec<u8> {
+    ACTIVE_NOTES.to_sorted_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_notes_set() {
+        let notes = ActiveNotes::new();
+        notes.insert(72);
+        notes.insert(60);
+        notes.insert(127);
+        notes.insert(60);
+        assert_eq!(notes.to_sorted_vec(), vec![60, 72, 127]);
+
+        notes.remove(72);
+        assert_eq!(notes.to_sorted_vec(), vec![60, 127]);
+
+        notes.clear();
+        assert!(notes.to_sorted_vec().is_empty());
+    }
+}
@@ -3,10 +3,12 @@
 //! Provides enumeration and connection to MIDI input devices (hardware and virtual).
 //! Received MIDI messages are forwarded to the plugin's MIDI event queue.
 
+use std::sync::atomic::{AtomicU16, AtomicU8, Ordering};
 use std::sync::Arc;
 use parking_lot::Mutex;
 use midir::{MidiInput, MidiInputConnection};
 
+use super::activity::{self, NoteSource};
 use super::events::{MidiEvent, MidiEventQueue};
 
 /// Information about a MIDI input device
@@ -18,6 +20,51 @@ pub struct MidiDeviceInfo {
     pub name: String,
 }
 
+/// Channel mask that lets every channel through
+const ALL_CHANNELS: u16 = 0xFFFF;
+
+/// A note held on a device: (note, incoming channel, channel sent to the plugin)
+type HeldNote = (u8, u8, u8);
+
+/// Per-device input state shared between the manager and the device's callback
+struct DeviceInput {
+    /// Incoming channels to accept, one bit per channel (bit 0 = channel 1)
+    channel_mask: AtomicU16,
+    /// Output channel for each incoming channel (identity by default)
+    channel_remap: [AtomicU8; 16],
+    /// Notes held on this device, so just its notes can be released on disconnect
+    held_notes: Mutex<Vec<HeldNote>>,
+}
+
+impl DeviceInput {
+    fn new() -> Self {
+        Self {
+            channel_mask: AtomicU16::new(ALL_CHANNELS),
+            channel_remap: std::array::from_fn(|ch| AtomicU8::new(ch as u8)),
+            held_notes: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Map an incoming channel to the channel sent to the plugin,
+    /// or None if the channel is filtered out
+    fn route(&self, channel: u8) -> Option<u8> {
+        let channel = channel & 0x0F;
+        if self.channel_mask.load(Ordering::Relaxed) & (1 << channel) == 0 {
+            return None;
+        }
+        Some(self.channel_remap[channel as usize].load(Ordering::Relaxed))
+    }
+
+    /// Forget a held note, returning the channel its note-on was sent on.
+    /// Note-offs follow the note-on rather than the current routing, so changing
+    /// the filter or remap while a key is down can't leave a note stuck.
+    fn release_note(&self, note: u8, channel: u8) -> Option<u8> {
+        let mut held = self.held_notes.lock();
+        let index = held.iter().position(|&(n, ch, _)| n == note && ch == channel)?;
+        Some(held.remove(index).2)
+    }
+}
+
 /// Active MIDI input connection
 struct ActiveConnection {
     /// The midir connection (must be kept alive)
@@ -25,29 +72,35 @@ struct ActiveConnection {
     connection: MidiInputConnection<()>,
     /// Name of connected device
     device_name: String,
-    /// Last received note (shared with callback)
-    last_note: Arc<Mutex<Option<u8>>>,
+    /// Routing and held notes (shared with callback)
+    input: Arc<DeviceInput>,
 }
 
 /// Global MIDI input manager
+///
+/// Any number of devices can be connected at once; their events are merged into
+/// the same plugin queue.
 pub struct MidiInputManager {
-    /// Active connection (if any)
-    connection: Mutex<Option<ActiveConnection>>,
-    /// MIDI event queue to forward events to (shared with callback)
+    /// Active connections, in connection order
+    connections: Mutex<Vec<ActiveConnection>>,
+    /// MIDI event queue to forward events to (shared with callbacks)
     queue: Arc<Mutex<Option<Arc<MidiEventQueue>>>>,
+    /// Last received note on any device (shared with callbacks)
+    last_note: Arc<Mutex<Option<u8>>>,
 }
 
 impl MidiInputManager {
     /// Create a new MIDI input manager
     pub fn new() -> Self {
         Self {
-            connection: Mutex::new(None),
+            connections: Mutex::new(Vec::new()),
             queue: Arc::new(Mutex::new(None)),
+            last_note: Arc::new(Mutex::new(None)),
         }
     }
 
     /// Set the MIDI event queue (called when plugin is loaded/reloaded)
-    /// This updates the shared reference that the callback reads from
+    /// This updates the shared reference that the callbacks read from
     pub fn set_queue(&self, queue: Option<Arc<MidiEventQueue>>) {
         *self.queue.lock() = queue;
     }
@@ -70,11 +123,9 @@ impl MidiInputManager {
         Ok(devices)
     }
 
-    /// Connect to a MIDI input device by index
+    /// Connect to a MIDI input device by index (other connected devices stay connected)
+    /// Connecting a device that's already connected is a no-op.
     pub fn connect(&self, device_index: usize, queue: Arc<MidiEventQueue>) -> Result<String, String> {
-        // Disconnect any existing connection first
-        self.disconnect();
-
         // Store the queue reference in the shared Arc
         *self.queue.lock() = Some(queue);
 
@@ -91,45 +142,83 @@ impl MidiInputManager {
             .port_name(port)
             .unwrap_or_else(|_| format!("Device {}", device_index));
 
+        if self.connections.lock().iter().any(|c| c.device_name == device_name) {
+            return Ok(device_name);
+        }
+
         log::info!("Connecting to MIDI device: {}", device_name);
 
         // Create callback that reads from the shared queue reference
         // This allows the queue to be updated via set_queue() without reconnecting
         let shared_queue = self.queue.clone();
-        let last_note = Arc::new(Mutex::new(None::<u8>));
-        let last_note_clone = last_note.clone();
+        let last_note = self.last_note.clone();
+        let input = Arc::new(DeviceInput::new());
+        let input_clone = input.clone();
 
         let connection = midi_in
             .connect(
                 port,
                 "freqlab-midi-in",
                 move |_timestamp, message, _| {
-                    Self::handle_midi_message_shared(message, &shared_queue, &last_note_clone);
+                    Self::handle_midi_message_shared(message, &shared_queue, &last_note, &input_clone);
                 },
                 (),
             )
             .map_err(|e| format!("Failed to connect to MIDI device: {}", e))?;
 
-        // Store the connection with the last_note Arc for activity tracking
-        *self.connection.lock() = Some(ActiveConnection {
+        self.connections.lock().push(ActiveConnection {
             connection,
             device_name: device_name.clone(),
-            last_note,
+            input,
         });
 
         log::info!("Successfully connected to MIDI device: {}", device_name);
         Ok(device_name)
     }
 
-    /// Disconnect from the current MIDI device
+    /// Disconnect one device by name
+    pub fn disconnect_device(&self, device_name: &str) -> Result<(), String> {
+        let active = {
+            let mut connections = self.connections.lock();
+            let index = connections
+                .iter()
+                .position(|c| c.device_name == device_name)
+                .ok_or_else(|| format!("MIDI device not connected: {}", device_name))?;
+            connections.remove(index)
+        };
+        self.release(active);
+        Ok(())
+    }
+
+    /// Disconnect every device
     pub fn disconnect(&self) {
-        let mut conn = self.connection.lock();
-        if let Some(active) = conn.take() {
-            log::info!("Disconnecting from MIDI device: {}", active.device_name);
-            // Connection is dropped here, which closes the port
+        let connections = std::mem::take(&mut *self.connections.lock());
+        for active in connections {
+            self.release(active);
+        }
+    }
+
+    /// Close a connection and reset what the device may have left hanging
+    fn release(&self, active: ActiveConnection) {
+        log::info!("Disconnecting from MIDI device: {}", active.device_name);
+        let held: Vec<HeldNote> = std::mem::take(&mut *active.input.held_notes.lock());
+        // Connection is dropped here, which closes the port
+        drop(active);
+
+        let others_connected = !self.connections.lock().is_empty();
+        if let Some(queue) = self.queue.lock().as_ref() {
+            // Release just this device's notes so other devices keep playing
+            for (note, _, channel) in held {
+                queue.push(MidiEvent::NoteOff {
+                    note,
+                    velocity: 0,
+                    channel,
+                });
+                activity::record_note_off(note, NoteSource::Device);
+            }
 
-            // Reset controller state and send all notes off when disconnecting
-            if let Some(queue) = self.queue.lock().as_ref() {
+            // Controllers are shared, so only reset them once the last device is gone
+            if !others_connected {
                 // Reset sustain pedal (CC 64) to off
                 queue.push(MidiEvent::ControlChange {
                     controller: 64,
@@ -151,27 +240,84 @@ impl MidiInputManager {
                 queue.all_notes_off();
             }
         }
+        if !others_connected {
+            activity::clear_active_notes(NoteSource::Device);
+            *self.last_note.lock() = None;
+        }
+    }
+
+    /// Only accept incoming channels whose bit is set in `mask` (bit 0 = channel 1)
+    pub fn set_channel_filter(&self, device_name: &str, mask: u16) -> Result<(), String> {
+        self.with_input(device_name, |input| {
+            input.channel_mask.store(mask, Ordering::Relaxed);
+        })?;
+        log::info!("MIDI device '{}' channel filter set to {:#06x}", device_name, mask);
+        Ok(())
+    }
+
+    /// Send events arriving on channel `from` to channel `to` (both 0-15).
+    /// Remapping a channel to itself restores pass-through.
+    pub fn set_channel_remap(&self, device_name: &str, from: u8, to: u8) -> Result<(), String> {
+        if from > 15 || to > 15 {
+            return Err(format!("MIDI channel out of range (0-15): {} -> {}", from, to));
+        }
+        self.with_input(device_name, |input| {
+            input.channel_remap[from as usize].store(to, Ordering::Relaxed);
+        })?;
+        log::info!("MIDI device '{}' channel {} remapped to {}", device_name, from + 1, to + 1);
+        Ok(())
+    }
+
+    /// Run `f` on a connected device's input state
+    fn with_input(&self, device_name: &str, f: impl FnOnce(&DeviceInput)) -> Result<(), String> {
+        let connections = self.connections.lock();
+        let active = connections
+            .iter()
+            .find(|c| c.device_name == device_name)
+            .ok_or_else(|| format!("MIDI device not connected: {}", device_name))?;
+        f(&active.input);
+        Ok(())
+    }
+
+    /// Drop connections whose device is no longer present (e.g. unplugged)
+    /// Returns the names of the devices that were removed.
+    pub fn remove_missing_devices(&self) -> Vec<String> {
+        if self.connections.lock().is_empty() {
+            return Vec::new();
+        }
+        let Ok(available) = self.list_devices() else {
+            return Vec::new();
+        };
+
+        let missing: Vec<String> = self
+            .connected_device_names()
+            .into_iter()
+            .filter(|name| !available.iter().any(|d| &d.name == name))
+            .collect();
+        for name in &missing {
+            log::warn!("MIDI device disappeared: {}", name);
+            let _ = self.disconnect_device(name);
+        }
+        missing
     }
 
-    /// Check if connected to a device
+    /// Check if any device is connected
     pub fn is_connected(&self) -> bool {
-        self.connection.lock().is_some()
+        !self.connections.lock().is_empty()
     }
 
-    /// Get the name of the connected device (if any)
-    pub fn connected_device_name(&self) -> Option<String> {
-        self.connection
+    /// Get the names of the connected devices, in connection order
+    pub fn connected_device_names(&self) -> Vec<String> {
+        self.connections
             .lock()
-            .as_ref()
+            .iter()
             .map(|c| c.device_name.clone())
+            .collect()
     }
 
-    /// Get the last received note (for activity indicator)
+    /// Get the last received note on any device (for activity indicator)
     pub fn get_last_note(&self) -> Option<u8> {
-        self.connection
-            .lock()
-            .as_ref()
-            .and_then(|c| *c.last_note.lock())
+        *self.last_note.lock()
     }
 
     /// Handle incoming MIDI message using shared queue reference
@@ -180,6 +326,7 @@ impl MidiInputManager {
         message: &[u8],
         shared_queue: &Arc<Mutex<Option<Arc<MidiEventQueue>>>>,
         last_note: &Arc<Mutex<Option<u8>>>,
+        input: &DeviceInput,
     ) {
         if message.is_empty() {
             return;
@@ -204,11 +351,7 @@ impl MidiInputManager {
                 if message.len() >= 3 {
                     let note = message[1] & 0x7F;
                     let velocity = message[2] & 0x7F;
-                    queue.push(MidiEvent::NoteOff {
-                        note,
-                        velocity,
-                        channel,
-                    });
+                    Self::note_off(&queue, input, note, velocity, channel);
                     log::trace!("MIDI Note Off: note={}, vel={}, ch={}", note, velocity, channel);
                 }
             }
@@ -220,17 +363,15 @@ impl MidiInputManager {
 
                     // Note On with velocity 0 is actually Note Off
                     if velocity == 0 {
-                        queue.push(MidiEvent::NoteOff {
-                            note,
-                            velocity: 0,
-                            channel,
-                        });
-                    } else {
+                        Self::note_off(&queue, input, note, 0, channel);
+                    } else if let Some(out_channel) = input.route(channel) {
                         queue.push(MidiEvent::NoteOn {
                             note,
                             velocity,
-                            channel,
+                            channel: out_channel,
                         });
+                        input.held_notes.lock().push((note, channel, out_channel));
+                        activity::record_note_on(note, velocity, NoteSource::Device);
                         // Update last note for activity indicator
                         *last_note.lock() = Some(note);
                     }
@@ -240,19 +381,24 @@ impl MidiInputManager {
             // Control Change (CC)
             0xB0 => {
                 if message.len() >= 3 {
+                    let Some(out_channel) = input.route(channel) else {
+                        return;
+                    };
                     let cc = message[1] & 0x7F;
                     let value = message[2] & 0x7F;
 
                     // Handle All Notes Off CC (123) specially
                     if cc == 123 {
                         queue.push(MidiEvent::AllNotesOff);
+                        input.held_notes.lock().clear();
+                        activity::clear_active_notes(NoteSource::Device);
                         log::debug!("MIDI All Notes Off CC received");
                     } else {
                         // Forward other CC messages (sustain=64, mod wheel=1, etc.)
                         queue.push(MidiEvent::ControlChange {
                             controller: cc,
                             value,
-                            channel,
+                            channel: out_channel,
                         });
                         log::trace!("MIDI CC: cc={}, value={}, ch={}", cc, value, channel);
                     }
@@ -261,10 +407,16 @@ impl MidiInputManager {
             // Pitch Bend
             0xE0 => {
                 if message.len() >= 3 {
+                    let Some(out_channel) = input.route(channel) else {
+                        return;
+                    };
                     let lsb = message[1] & 0x7F;
                     let msb = message[2] & 0x7F;
                     let value = ((msb as u16) << 7) | (lsb as u16);
-                    queue.push(MidiEvent::PitchBend { value, channel });
+                    queue.push(MidiEvent::PitchBend {
+                        value,
+                        channel: out_channel,
+                    });
                     log::trace!("MIDI Pitch Bend: value={}, ch={}", value, channel);
                 }
             }
@@ -274,6 +426,20 @@ impl MidiInputManager {
             }
         }
     }
+
+    /// Forward a note-off to wherever its note-on went (or through the current
+    /// routing if the note wasn't held)
+    fn note_off(queue: &MidiEventQueue, input: &DeviceInput, note: u8, velocity: u8, channel: u8) {
+        let Some(out_channel) = input.release_note(note, channel).or_else(|| input.route(channel)) else {
+            return;
+        };
+        queue.push(MidiEvent::NoteOff {
+            note,
+            velocity,
+            channel: out_channel,
+        });
+        activity::record_note_off(note, NoteSource::Device);
+    }
 }
 
 impl Default for MidiInputManager {
@@ -296,7 +462,31 @@ mod tests {
     fn test_manager_creation() {
         let manager = MidiInputManager::new();
         assert!(!manager.is_connected());
-        assert!(manager.connected_device_name().is_none());
+        assert!(manager.connected_device_names().is_empty());
+        assert!(manager.disconnect_device("Nope").is_err());
+    }
+
+    #[test]
+    fn test_channel_routing() {
+        let input = DeviceInput::new();
+        // Pass-through by default
+        assert_eq!(input.route(0), Some(0));
+        assert_eq!(input.route(15), Some(15));
+
+        // Only channels 1 and 3
+        input.channel_mask.store(0b101, Ordering::Relaxed);
+        assert_eq!(input.route(0), Some(0));
+        assert_eq!(input.route(1), None);
+        assert_eq!(input.route(2), Some(2));
+
+        input.channel_remap[2].store(9, Ordering::Relaxed);
+        assert_eq!(input.route(2), Some(9));
+
+        // A held note is released on the channel it started on, even after re-routing
+        input.held_notes.lock().push((60, 2, 9));
+        input.channel_mask.store(0, Ordering::Relaxed);
+        assert_eq!(input.release_note(60, 2), Some(9));
+        assert_eq!(input.release_note(60, 2), None);
     }
 
     #[test]
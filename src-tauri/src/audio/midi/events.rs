@@ -6,6 +6,14 @@
 
 use ringbuf::{traits::*, HeapRb};
 use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of 64-bit words needed to track 128 notes on 16 channels
+const ACTIVE_NOTE_WORDS: usize = 16 * 2;
+
+/// Share of the queue kept free of CC and pitch bend, so a controller sweep can't
+/// fill it and drop the notes behind it
+const CONTROLLER_RESERVE_DIVISOR: usize = 4;
 
 /// MIDI event types that can be sent to plugins
 #[derive(Debug, Clone, Copy)]
@@ -59,12 +67,18 @@ impl MidiEvent {
         }
     }
 
-    /// Create a note off event on channel 0
+    /// Create a note off event on channel 0 with a release velocity of 0
     #[inline]
     pub fn note_off(note: u8) -> Self {
+        Self::note_off_with_velocity(note, 0)
+    }
+
+    /// Create a note off event on channel 0 with a release velocity
+    #[inline]
+    pub fn note_off_with_velocity(note: u8, velocity: u8) -> Self {
         Self::NoteOff {
             note,
-            velocity: 0,
+            velocity,
             channel: 0,
         }
     }
@@ -84,6 +98,12 @@ impl MidiEvent {
     pub fn pitch_bend(value: u16, channel: u8) -> Self {
         Self::PitchBend { value, channel }
     }
+
+    /// CC and pitch bend, which have to leave the queue's note reserve alone
+    #[inline]
+    fn is_controller(&self) -> bool {
+        matches!(self, Self::ControlChange { .. } | Self::PitchBend { .. })
+    }
 }
 
 /// Thread-safe MIDI event queue using lock-free ring buffer
@@ -97,6 +117,8 @@ pub struct MidiEventQueue {
     consumer: Mutex<ringbuf::HeapCons<MidiEvent>>,
     /// Capacity for logging overflow warnings
     capacity: usize,
+    /// Bitset of held notes (2 words per channel) so all_notes_off can release them explicitly
+    active_notes: [AtomicU64; ACTIVE_NOTE_WORDS],
 }
 
 impl MidiEventQueue {
@@ -108,18 +130,71 @@ impl MidiEventQueue {
             producer: Mutex::new(producer),
             consumer: Mutex::new(consumer),
             capacity,
+            active_notes: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    /// Word index and bit mask for a (channel, note) pair in the active-notes bitset
+    #[inline]
+    fn note_slot(channel: u8, note: u8) -> (usize, u64) {
+        let channel = (channel & 0x0F) as usize;
+        let note = note & 0x7F;
+        (channel * 2 + (note / 64) as usize, 1u64 << (note % 64))
+    }
+
+    /// Keep the active-notes bitset in sync with an event that made it into the queue
+    #[inline]
+    fn track(&self, event: &MidiEvent) {
+        match *event {
+            MidiEvent::NoteOn { note, velocity, channel } if velocity > 0 => {
+                let (word, bit) = Self::note_slot(channel, note);
+                self.active_notes[word].fetch_or(bit, Ordering::Relaxed);
+            }
+            // Note on with velocity 0 is a note off by MIDI convention
+            MidiEvent::NoteOn { note, channel, .. } | MidiEvent::NoteOff { note, channel, .. } => {
+                let (word, bit) = Self::note_slot(channel, note);
+                self.active_notes[word].fetch_and(!bit, Ordering::Relaxed);
+            }
+            MidiEvent::AllNotesOff => {
+                for word in self.active_notes.iter() {
+                    word.store(0, Ordering::Relaxed);
+                }
+            }
+            _ => {}
         }
     }
 
+    /// Notes currently held through this queue, as (channel, note) pairs
+    pub fn active_notes(&self) -> Vec<(u8, u8)> {
+        let mut notes = Vec::new();
+        for (index, word) in self.active_notes.iter().enumerate() {
+            let bits = word.load(Ordering::Relaxed);
+            for bit in 0..64u8 {
+                if bits & (1u64 << bit) != 0 {
+                    notes.push(((index / 2) as u8, (index % 2) as u8 * 64 + bit));
+                }
+            }
+        }
+        notes
+    }
+
     /// Push an event to the queue (called from command handlers)
     /// Returns true if successful, false if queue is full
+    ///
+    /// CC and pitch bend are refused once the queue is down to its reserve, which is
+    /// left for note events.
     #[inline]
     pub fn push(&self, event: MidiEvent) -> bool {
         // Use try_lock to avoid blocking if consumer is draining
         // If we can't get the lock immediately, the event is dropped
         // This is acceptable for real-time audio - better to drop than block
         if let Some(mut producer) = self.producer.try_lock() {
+            if event.is_controller() && producer.vacant_len() <= self.capacity / CONTROLLER_RESERVE_DIVISOR {
+                log::debug!("MIDI queue nearly full, controller event dropped");
+                return false;
+            }
             if producer.try_push(event).is_ok() {
+                self.track(&event);
                 return true;
             }
             // Queue full - log at debug level to avoid spam
@@ -128,21 +203,77 @@ impl MidiEventQueue {
         false
     }
 
+    /// Push several events at once (e.g. the note-ons of a chord)
+    /// Returns true if all of them were queued, false (queuing none) otherwise
+    ///
+    /// The batch is published with a single write index update, so the audio thread
+    /// drains either all of it or none of it in a given callback and the events reach
+    /// the plugin in the same block.
+    pub fn push_batch(&self, events: &[MidiEvent]) -> bool {
+        if events.is_empty() {
+            return true;
+        }
+        if let Some(mut producer) = self.producer.try_lock() {
+            let reserve = if events.iter().any(MidiEvent::is_controller) {
+                self.capacity / CONTROLLER_RESERVE_DIVISOR
+            } else {
+                0
+            };
+            if producer.vacant_len() < events.len() + reserve {
+                log::debug!("MIDI queue full (capacity: {}), {} events dropped", self.capacity, events.len());
+                return false;
+            }
+            producer.push_slice(events);
+            for event in events {
+                self.track(event);
+            }
+            return true;
+        }
+        false
+    }
+
     /// Push a note on event
     #[inline]
     pub fn note_on(&self, note: u8, velocity: u8) -> bool {
         self.push(MidiEvent::note_on(note, velocity))
     }
 
-    /// Push a note off event
+    /// Push a note off event (release velocity 0)
     #[inline]
     pub fn note_off(&self, note: u8) -> bool {
         self.push(MidiEvent::note_off(note))
     }
 
-    /// Send all notes off
+    /// Push a note off event with a release velocity (0-127)
+    #[inline]
+    pub fn note_off_with_velocity(&self, note: u8, velocity: u8) -> bool {
+        self.push(MidiEvent::note_off_with_velocity(note, velocity))
+    }
+
+    /// Push a control change event
     #[inline]
+    pub fn control_change(&self, controller: u8, value: u8, channel: u8) -> bool {
+        self.push(MidiEvent::control_change(controller, value, channel))
+    }
+
+    /// Push a pitch bend event (14-bit, center 8192)
+    #[inline]
+    pub fn pitch_bend(&self, value: u16, channel: u8) -> bool {
+        self.push(MidiEvent::pitch_bend(value, channel))
+    }
+
+    /// Send all notes off
+    ///
+    /// Every held note gets an explicit note off on its own channel (plugins that ignore
+    /// the channel-0 catch-all would otherwise keep ringing), followed by AllNotesOff.
     pub fn all_notes_off(&self) -> bool {
+        for (channel, note) in self.active_notes() {
+            self.push(MidiEvent::NoteOff {
+                note,
+                velocity: 0,
+                channel,
+            });
+        }
         self.push(MidiEvent::AllNotesOff)
     }
 
@@ -220,6 +351,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_note_off_release_velocity() {
+        let queue = MidiEventQueue::new(16);
+
+        assert!(queue.note_off(60));
+        assert!(queue.note_off_with_velocity(62, 90));
+
+        let mut buffer = Vec::with_capacity(16);
+        queue.drain_into(&mut buffer);
+        let releases: Vec<(u8, u8)> = buffer
+            .iter()
+            .filter_map(|event| match *event {
+                MidiEvent::NoteOff { note, velocity, .. } => Some((note, velocity)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(releases, vec![(60, 0), (62, 90)]);
+    }
+
     #[test]
     fn test_queue_overflow() {
         let queue = MidiEventQueue::new(4);
@@ -238,4 +388,74 @@ mod tests {
         let count = queue.drain_into(&mut buffer);
         assert_eq!(count, 4);
     }
+
+    #[test]
+    fn test_all_notes_off_releases_active_notes() {
+        let queue = MidiEventQueue::new(32);
+
+        queue.note_on(60, 100);
+        queue.note_on(64, 100);
+        queue.push(MidiEvent::NoteOn { note: 36, velocity: 90, channel: 9 });
+        queue.note_off(64);
+        // Velocity 0 note on counts as a release
+        queue.note_on(67, 0);
+        assert_eq!(queue.active_notes(), vec![(0, 60), (9, 36)]);
+
+        let mut buffer = Vec::with_capacity(32);
+        queue.drain_into(&mut buffer);
+
+        assert!(queue.all_notes_off());
+        queue.drain_into(&mut buffer);
+
+        let offs: Vec<(u8, u8)> = buffer
+            .iter()
+            .filter_map(|event| match *event {
+                MidiEvent::NoteOff { note, channel, .. } => Some((channel, note)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(offs, vec![(0, 60), (9, 36)]);
+        assert!(matches!(buffer.last(), Some(MidiEvent::AllNotesOff)));
+        assert!(queue.active_notes().is_empty());
+    }
+
+    #[test]
+    fn test_controller_burst_leaves_room_for_notes() {
+        let queue = MidiEventQueue::new(16);
+
+        // A mod wheel sweep stops at the reserve...
+        let accepted = (0..40u8).filter(|value| queue.control_change(1, *value, 0)).count();
+        assert_eq!(accepted, 12);
+        assert!(!queue.pitch_bend(8192, 0));
+
+        // ...which notes can still use
+        for note in 60..64 {
+            assert!(queue.note_on(note, 100));
+        }
+        assert!(!queue.note_on(64, 100));
+
+        let mut buffer = Vec::with_capacity(16);
+        assert_eq!(queue.drain_into(&mut buffer), 16);
+        assert!(queue.control_change(64, 127, 0));
+    }
+
+    #[test]
+    fn test_batch_is_queued_whole_or_not_at_all() {
+        let queue = MidiEventQueue::new(4);
+        let chord = [
+            MidiEvent::note_on(60, 100),
+            MidiEvent::note_on(64, 100),
+            MidiEvent::note_on(67, 100),
+        ];
+        assert!(queue.push_batch(&chord));
+        assert_eq!(queue.active_notes(), vec![(0, 60), (0, 64), (0, 67)]);
+
+        // Only one slot left: the second chord is refused rather than split
+        assert!(!queue.push_batch(&chord));
+        assert_eq!(queue.len(), 3);
+
+        let mut buffer = Vec::with_capacity(4);
+        assert_eq!(queue.drain_into(&mut buffer), 3);
+        assert!(queue.push_batch(&chord));
+    }
 }
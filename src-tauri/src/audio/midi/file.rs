@@ -30,6 +30,50 @@ pub struct TempoEvent {
     pub bpm: f32,
 }
 
+/// Default tempo for a MIDI file without tempo events (per the spec)
+const DEFAULT_FILE_BPM: f32 = 120.0;
+
+/// Walk the constant-tempo segments of a tempo map as (start beat, end beat, bpm)
+/// The last segment runs to infinity; tempo before the first event uses the first event's BPM.
+fn tempo_segments(tempo_map: &[TempoEvent]) -> impl Iterator<Item = (f32, f32, f32)> + '_ {
+    let first_bpm = tempo_map.first().map_or(DEFAULT_FILE_BPM, |e| e.bpm);
+    let starts = std::iter::once((0.0, first_bpm))
+        .chain(tempo_map.iter().filter(|e| e.beat > 0.0).map(|e| (e.beat, e.bpm)));
+    let ends = tempo_map
+        .iter()
+        .filter(|e| e.beat > 0.0)
+        .map(|e| e.beat)
+        .chain(std::iter::once(f32::INFINITY));
+    starts
+        .zip(ends)
+        .map(|((start, bpm), end)| (start, end, bpm.max(1.0)))
+}
+
+/// Time in seconds at a beat position, following the tempo map
+pub fn beats_to_seconds(tempo_map: &[TempoEvent], beat: f32) -> f32 {
+    let mut seconds = 0.0;
+    for (start, end, bpm) in tempo_segments(tempo_map) {
+        if beat <= end {
+            return seconds + (beat - start).max(0.0) * 60.0 / bpm;
+        }
+        seconds += (end - start) * 60.0 / bpm;
+    }
+    seconds
+}
+
+/// Beat position at a time in seconds, following the tempo map
+pub fn seconds_to_beats(tempo_map: &[TempoEvent], seconds: f32) -> f32 {
+    let mut elapsed = 0.0;
+    for (start, end, bpm) in tempo_segments(tempo_map) {
+        let segment_seconds = (end - start) * 60.0 / bpm;
+        if seconds <= elapsed + segment_seconds {
+            return start + (seconds - elapsed).max(0.0) * bpm / 60.0;
+        }
+        elapsed += segment_seconds;
+    }
+    0.0
+}
+
 /// A note extracted from a MIDI file
 #[derive(Debug, Clone, Copy)]
 pub struct MidiFileNote {
@@ -43,6 +87,8 @@ pub struct MidiFileNote {
     pub duration: f32,
     /// MIDI channel (0-15)
     pub channel: u8,
+    /// Index into ParsedMidiFile::tracks (the track this note was split into)
+    pub track: usize,
 }
 
 /// A parsed MIDI file with track information
@@ -68,6 +114,18 @@ impl ParsedMidiFile {
         self.track_notes.get(track_index).map(|v| v.as_slice())
     }
 
+    /// Notes of every track merged in start order, for playing the whole file
+    pub fn all_notes(&self) -> Vec<MidiFileNote> {
+        let mut notes: Vec<MidiFileNote> = self.track_notes.iter().flatten().copied().collect();
+        notes.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+        notes
+    }
+
+    /// Length of the longest track in beats
+    pub fn duration_beats(&self) -> f32 {
+        self.tracks.iter().map(|track| track.duration_beats).fold(0.0, f32::max)
+    }
+
     /// Check if the file has tempo automation (more than one tempo event)
     pub fn has_tempo_automation(&self) -> bool {
         self.tempo_map.len() > 1
@@ -131,7 +189,7 @@ pub fn parse_midi_file(path: &Path) -> Result<ParsedMidiFile, String> {
 
     // Ensure there's always a tempo at beat 0 (MIDI spec default is 120 BPM)
     if tempo_map.is_empty() || tempo_map[0].beat > 0.0 {
-        tempo_map.insert(0, TempoEvent { beat: 0.0, bpm: 120.0 });
+        tempo_map.insert(0, TempoEvent { beat: 0.0, bpm: DEFAULT_FILE_BPM });
     }
 
     // Initial BPM is the tempo at beat 0 (after sorting and adding default)
@@ -175,6 +233,7 @@ pub fn parse_midi_file(path: &Path) -> Result<ParsedMidiFile, String> {
                                         velocity: vel,
                                         duration,
                                         channel: ch,
+                                        track: 0,
                                     });
                                 }
                             }
@@ -189,6 +248,7 @@ pub fn parse_midi_file(path: &Path) -> Result<ParsedMidiFile, String> {
                                     velocity: vel,
                                     duration,
                                     channel: ch,
+                                    track: 0,
                                 });
                             }
                         }
@@ -209,6 +269,7 @@ pub fn parse_midi_file(path: &Path) -> Result<ParsedMidiFile, String> {
                 velocity: vel,
                 duration,
                 channel: ch,
+                track: 0,
             });
         }
 
@@ -281,7 +342,12 @@ pub fn parse_midi_file(path: &Path) -> Result<ParsedMidiFile, String> {
     // Sort tracks by channel for consistent ordering
     let mut combined: Vec<_> = tracks.into_iter().zip(track_notes.into_iter()).collect();
     combined.sort_by_key(|(info, _)| info.channel.unwrap_or(255));
-    let (tracks, track_notes): (Vec<_>, Vec<_>) = combined.into_iter().unzip();
+    let (tracks, mut track_notes): (Vec<_>, Vec<_>) = combined.into_iter().unzip();
+    for (index, notes) in track_notes.iter_mut().enumerate() {
+        for note in notes.iter_mut() {
+            note.track = index;
+        }
+    }
 
     // If no tracks with notes found, return error
     if tracks.is_empty() {
@@ -335,4 +401,58 @@ mod tests {
         assert!((bpm - 100.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_two_tempo_regions() {
+        use midly::num::{u15, u24, u28, u4, u7};
+        use midly::{Format, Header, Timing, TrackEvent};
+
+        let tpb = 480u32;
+        let event = |delta: u32, kind| TrackEvent { delta: u28::new(delta), kind };
+        let note_on = |key: u8| TrackEventKind::Midi {
+            channel: u4::new(0),
+            message: MidiMessage::NoteOn { key: u7::new(key), vel: u7::new(100) },
+        };
+        let note_off = |key: u8| TrackEventKind::Midi {
+            channel: u4::new(0),
+            message: MidiMessage::NoteOff { key: u7::new(key), vel: u7::new(0) },
+        };
+
+        // 120 BPM for the first 4 beats, then 60 BPM; notes at beats 0, 2, 4 and 6
+        let track = vec![
+            event(0, TrackEventKind::Meta(MetaMessage::Tempo(u24::new(500_000)))),
+            event(0, note_on(60)),
+            event(tpb, note_off(60)),
+            event(tpb, note_on(62)),
+            event(tpb, note_off(62)),
+            event(tpb, TrackEventKind::Meta(MetaMessage::Tempo(u24::new(1_000_000)))),
+            event(0, note_on(64)),
+            event(tpb, note_off(64)),
+            event(tpb, note_on(65)),
+            event(tpb, note_off(65)),
+            event(0, TrackEventKind::Meta(MetaMessage::EndOfTrack)),
+        ];
+        let smf = Smf {
+            header: Header::new(Format::SingleTrack, Timing::Metrical(u15::new(tpb as u16))),
+            tracks: vec![track],
+        };
+
+        let path = std::env::temp_dir().join(format!("freqlab-tempo-{}.mid", std::process::id()));
+        smf.save(&path).unwrap();
+        let parsed = parse_midi_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(parsed.has_tempo_automation());
+        let mut notes = parsed.track_notes[0].clone();
+        notes.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+        let seconds: Vec<f32> = notes.iter().map(|n| beats_to_seconds(&parsed.tempo_map, n.beat)).collect();
+        let expected = [0.0, 1.0, 2.0, 4.0];
+        for (actual, expected) in seconds.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+        }
+
+        // And back again, including mid-segment positions
+        for (beat, time) in [(1.0, 0.5), (4.0, 2.0), (5.5, 3.5), (6.0, 4.0)] {
+            assert!((seconds_to_beats(&parsed.tempo_map, time) - beat).abs() < 1e-4);
+        }
+    }
 }
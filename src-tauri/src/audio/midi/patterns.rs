@@ -1,4 +1,15 @@
 //! MIDI pattern presets for instrument plugins
+//!
+//! Built-in patterns are compiled in; user patterns are JSON files in
+//! `~/.config/freqlab/patterns/` and play exactly like the built-ins.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix for user pattern IDs (keeps them from shadowing built-ins)
+const USER_PATTERN_PREFIX: &str = "user_";
 
 /// A single note in a pattern
 #[derive(Debug, Clone, Copy)]
@@ -31,15 +42,15 @@ pub enum PatternCategory {
 #[derive(Debug, Clone)]
 pub struct Pattern {
     /// Unique identifier
-    pub id: &'static str,
+    pub id: Cow<'static, str>,
     /// Display name
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// Category
     pub category: PatternCategory,
     /// Pattern length in beats
     pub length_beats: f32,
     /// Notes in the pattern
-    pub notes: &'static [PatternNote],
+    pub notes: Cow<'static, [PatternNote]>,
 }
 
 /// Pattern info for frontend (without note data)
@@ -49,6 +60,8 @@ pub struct PatternInfo {
     pub name: String,
     pub category: PatternCategory,
     pub length_beats: f32,
+    /// Whether this is a user-defined pattern
+    pub is_user: bool,
 }
 
 impl From<&Pattern> for PatternInfo {
@@ -58,10 +71,39 @@ impl From<&Pattern> for PatternInfo {
             name: p.name.to_string(),
             category: p.category,
             length_beats: p.length_beats,
+            is_user: p.id.starts_with(USER_PATTERN_PREFIX),
         }
     }
 }
 
+/// A note in a user pattern definition (times in beats)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PatternNoteDef {
+    pub start: f32,
+    pub duration: f32,
+    /// MIDI note number (0-127)
+    pub note: u8,
+    /// Velocity (0-127)
+    pub velocity: u8,
+}
+
+/// Note data for a user pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternDef {
+    pub notes: Vec<PatternNoteDef>,
+    /// Loop length in beats (defaults to the end of the last note, rounded up to a whole beat)
+    #[serde(default)]
+    pub length_beats: Option<f32>,
+}
+
+/// On-disk format of a user pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UserPatternFile {
+    name: String,
+    category: PatternCategory,
+    def: PatternDef,
+}
+
 // =============================================================================
 // MELODIC PATTERNS
 // =============================================================================
@@ -79,11 +121,11 @@ const ARPEGGIO_UP_NOTES: &[PatternNote] = &[
 ];
 
 const ARPEGGIO_UP: Pattern = Pattern {
-    id: "arpeggio_up",
-    name: "Arpeggio Up",
+    id: Cow::Borrowed("arpeggio_up"),
+    name: Cow::Borrowed("Arpeggio Up"),
     category: PatternCategory::Melodic,
     length_beats: 4.0,
-    notes: ARPEGGIO_UP_NOTES,
+    notes: Cow::Borrowed(ARPEGGIO_UP_NOTES),
 };
 
 /// Arpeggio Down - C major triad descending
@@ -99,11 +141,11 @@ const ARPEGGIO_DOWN_NOTES: &[PatternNote] = &[
 ];
 
 const ARPEGGIO_DOWN: Pattern = Pattern {
-    id: "arpeggio_down",
-    name: "Arpeggio Down",
+    id: Cow::Borrowed("arpeggio_down"),
+    name: Cow::Borrowed("Arpeggio Down"),
     category: PatternCategory::Melodic,
     length_beats: 4.0,
-    notes: ARPEGGIO_DOWN_NOTES,
+    notes: Cow::Borrowed(ARPEGGIO_DOWN_NOTES),
 };
 
 /// Scale Run - C major scale ascending and descending
@@ -127,11 +169,11 @@ const SCALE_RUN_NOTES: &[PatternNote] = &[
 ];
 
 const SCALE_RUN: Pattern = Pattern {
-    id: "scale_run",
-    name: "Scale Run",
+    id: Cow::Borrowed("scale_run"),
+    name: Cow::Borrowed("Scale Run"),
     category: PatternCategory::Melodic,
     length_beats: 4.0,
-    notes: SCALE_RUN_NOTES,
+    notes: Cow::Borrowed(SCALE_RUN_NOTES),
 };
 
 /// Chord Stabs - C major triads on beats
@@ -155,11 +197,71 @@ const CHORD_STABS_NOTES: &[PatternNote] = &[
 ];
 
 const CHORD_STABS: Pattern = Pattern {
-    id: "chord_stabs",
-    name: "Chord Stabs",
+    id: Cow::Borrowed("chord_stabs"),
+    name: Cow::Borrowed("Chord Stabs"),
     category: PatternCategory::Melodic,
     length_beats: 4.0,
-    notes: CHORD_STABS_NOTES,
+    notes: Cow::Borrowed(CHORD_STABS_NOTES),
+};
+
+/// Progression Chords - I-V-vi-IV block chords, one per beat (C, G/B, Am, F)
+const PROGRESSION_CHORDS_NOTES: &[PatternNote] = &[
+    // C major
+    PatternNote::new(0.0, 60, 100, 0.9),  // C4
+    PatternNote::new(0.0, 64, 100, 0.9),  // E4
+    PatternNote::new(0.0, 67, 100, 0.9),  // G4
+    // G major over B
+    PatternNote::new(1.0, 59, 95, 0.9),   // B3
+    PatternNote::new(1.0, 62, 95, 0.9),   // D4
+    PatternNote::new(1.0, 67, 95, 0.9),   // G4
+    // A minor
+    PatternNote::new(2.0, 60, 100, 0.9),  // C4
+    PatternNote::new(2.0, 64, 100, 0.9),  // E4
+    PatternNote::new(2.0, 69, 100, 0.9),  // A4
+    // F major
+    PatternNote::new(3.0, 60, 95, 0.9),   // C4
+    PatternNote::new(3.0, 65, 95, 0.9),   // F4
+    PatternNote::new(3.0, 69, 95, 0.9),   // A4
+];
+
+const PROGRESSION_CHORDS: Pattern = Pattern {
+    id: Cow::Borrowed("progression_chords"),
+    name: Cow::Borrowed("Progression Chords"),
+    category: PatternCategory::Melodic,
+    length_beats: 4.0,
+    notes: Cow::Borrowed(PROGRESSION_CHORDS_NOTES),
+};
+
+/// Broken Chords - the same progression arpeggiated in 16ths (low, mid, high, mid)
+const BROKEN_CHORDS_NOTES: &[PatternNote] = &[
+    // C major
+    PatternNote::new(0.0, 60, 100, 0.25),
+    PatternNote::new(0.25, 64, 85, 0.25),
+    PatternNote::new(0.5, 67, 90, 0.25),
+    PatternNote::new(0.75, 64, 85, 0.25),
+    // G major over B
+    PatternNote::new(1.0, 59, 100, 0.25),
+    PatternNote::new(1.25, 62, 85, 0.25),
+    PatternNote::new(1.5, 67, 90, 0.25),
+    PatternNote::new(1.75, 62, 85, 0.25),
+    // A minor
+    PatternNote::new(2.0, 60, 100, 0.25),
+    PatternNote::new(2.25, 64, 85, 0.25),
+    PatternNote::new(2.5, 69, 90, 0.25),
+    PatternNote::new(2.75, 64, 85, 0.25),
+    // F major
+    PatternNote::new(3.0, 60, 100, 0.25),
+    PatternNote::new(3.25, 65, 85, 0.25),
+    PatternNote::new(3.5, 69, 90, 0.25),
+    PatternNote::new(3.75, 65, 85, 0.25),
+];
+
+const BROKEN_CHORDS: Pattern = Pattern {
+    id: Cow::Borrowed("broken_chords"),
+    name: Cow::Borrowed("Broken Chords"),
+    category: PatternCategory::Melodic,
+    length_beats: 4.0,
+    notes: Cow::Borrowed(BROKEN_CHORDS_NOTES),
 };
 
 /// Simple Lead Line
@@ -172,11 +274,11 @@ const LEAD_LINE_NOTES: &[PatternNote] = &[
 ];
 
 const LEAD_LINE: Pattern = Pattern {
-    id: "lead_line",
-    name: "Lead Line",
+    id: Cow::Borrowed("lead_line"),
+    name: Cow::Borrowed("Lead Line"),
     category: PatternCategory::Melodic,
     length_beats: 4.0,
-    notes: LEAD_LINE_NOTES,
+    notes: Cow::Borrowed(LEAD_LINE_NOTES),
 };
 
 // =============================================================================
@@ -192,11 +294,11 @@ const ROOT_PULSE_NOTES: &[PatternNote] = &[
 ];
 
 const ROOT_PULSE: Pattern = Pattern {
-    id: "root_pulse",
-    name: "Root Pulse",
+    id: Cow::Borrowed("root_pulse"),
+    name: Cow::Borrowed("Root Pulse"),
     category: PatternCategory::Bass,
     length_beats: 4.0,
-    notes: ROOT_PULSE_NOTES,
+    notes: Cow::Borrowed(ROOT_PULSE_NOTES),
 };
 
 /// Octave Bounce - root and octave alternating
@@ -212,11 +314,11 @@ const OCTAVE_BOUNCE_NOTES: &[PatternNote] = &[
 ];
 
 const OCTAVE_BOUNCE: Pattern = Pattern {
-    id: "octave_bounce",
-    name: "Octave Bounce",
+    id: Cow::Borrowed("octave_bounce"),
+    name: Cow::Borrowed("Octave Bounce"),
     category: PatternCategory::Bass,
     length_beats: 4.0,
-    notes: OCTAVE_BOUNCE_NOTES,
+    notes: Cow::Borrowed(OCTAVE_BOUNCE_NOTES),
 };
 
 /// Walking Bass - simple jazz walk
@@ -228,11 +330,11 @@ const WALKING_BASS_NOTES: &[PatternNote] = &[
 ];
 
 const WALKING_BASS: Pattern = Pattern {
-    id: "walking_bass",
-    name: "Walking Bass",
+    id: Cow::Borrowed("walking_bass"),
+    name: Cow::Borrowed("Walking Bass"),
     category: PatternCategory::Bass,
     length_beats: 4.0,
-    notes: WALKING_BASS_NOTES,
+    notes: Cow::Borrowed(WALKING_BASS_NOTES),
 };
 
 /// Synth Bass - syncopated EDM-style
@@ -247,11 +349,11 @@ const SYNTH_BASS_NOTES: &[PatternNote] = &[
 ];
 
 const SYNTH_BASS: Pattern = Pattern {
-    id: "synth_bass",
-    name: "Synth Bass",
+    id: Cow::Borrowed("synth_bass"),
+    name: Cow::Borrowed("Synth Bass"),
     category: PatternCategory::Bass,
     length_beats: 4.0,
-    notes: SYNTH_BASS_NOTES,
+    notes: Cow::Borrowed(SYNTH_BASS_NOTES),
 };
 
 // =============================================================================
@@ -268,11 +370,11 @@ const FOUR_ON_FLOOR_NOTES: &[PatternNote] = &[
 ];
 
 const FOUR_ON_FLOOR: Pattern = Pattern {
-    id: "four_on_floor",
-    name: "Four on Floor",
+    id: Cow::Borrowed("four_on_floor"),
+    name: Cow::Borrowed("Four on Floor"),
     category: PatternCategory::Drums,
     length_beats: 4.0,
-    notes: FOUR_ON_FLOOR_NOTES,
+    notes: Cow::Borrowed(FOUR_ON_FLOOR_NOTES),
 };
 
 /// Basic Beat - kick, snare, hi-hat
@@ -295,11 +397,11 @@ const BASIC_BEAT_NOTES: &[PatternNote] = &[
 ];
 
 const BASIC_BEAT: Pattern = Pattern {
-    id: "basic_beat",
-    name: "Basic Beat",
+    id: Cow::Borrowed("basic_beat"),
+    name: Cow::Borrowed("Basic Beat"),
     category: PatternCategory::Drums,
     length_beats: 4.0,
-    notes: BASIC_BEAT_NOTES,
+    notes: Cow::Borrowed(BASIC_BEAT_NOTES),
 };
 
 /// Breakbeat - syncopated
@@ -325,11 +427,11 @@ const BREAKBEAT_NOTES: &[PatternNote] = &[
 ];
 
 const BREAKBEAT: Pattern = Pattern {
-    id: "breakbeat",
-    name: "Breakbeat",
+    id: Cow::Borrowed("breakbeat"),
+    name: Cow::Borrowed("Breakbeat"),
     category: PatternCategory::Drums,
     length_beats: 4.0,
-    notes: BREAKBEAT_NOTES,
+    notes: Cow::Borrowed(BREAKBEAT_NOTES),
 };
 
 /// Disco Beat
@@ -350,11 +452,11 @@ const DISCO_BEAT_NOTES: &[PatternNote] = &[
 ];
 
 const DISCO_BEAT: Pattern = Pattern {
-    id: "disco_beat",
-    name: "Disco Beat",
+    id: Cow::Borrowed("disco_beat"),
+    name: Cow::Borrowed("Disco Beat"),
     category: PatternCategory::Drums,
     length_beats: 4.0,
-    notes: DISCO_BEAT_NOTES,
+    notes: Cow::Borrowed(DISCO_BEAT_NOTES),
 };
 
 // =============================================================================
@@ -368,6 +470,8 @@ pub const ALL_PATTERNS: &[Pattern] = &[
     ARPEGGIO_DOWN,
     SCALE_RUN,
     CHORD_STABS,
+    PROGRESSION_CHORDS,
+    BROKEN_CHORDS,
     LEAD_LINE,
     // Bass
     ROOT_PULSE,
@@ -381,32 +485,189 @@ pub const ALL_PATTERNS: &[Pattern] = &[
     DISCO_BEAT,
 ];
 
-/// Get a pattern by ID
-pub fn get_pattern(id: &str) -> Option<&'static Pattern> {
-    ALL_PATTERNS.iter().find(|p| p.id == id)
+/// Get a pattern by ID (built-in or user)
+pub fn get_pattern(id: &str) -> Option<Pattern> {
+    if let Some(pattern) = ALL_PATTERNS.iter().find(|p| p.id == id) {
+        return Some(pattern.clone());
+    }
+    if id.starts_with(USER_PATTERN_PREFIX) {
+        return load_user_patterns().into_iter().find(|p| p.id == id);
+    }
+    None
 }
 
-/// Get all pattern info for frontend
+/// Get all pattern info for frontend (built-ins first, then user patterns)
 pub fn list_patterns() -> Vec<PatternInfo> {
-    ALL_PATTERNS.iter().map(PatternInfo::from).collect()
+    ALL_PATTERNS
+        .iter()
+        .chain(load_user_patterns().iter())
+        .map(PatternInfo::from)
+        .collect()
 }
 
 /// Get patterns by category
 pub fn get_patterns_by_category(category: PatternCategory) -> Vec<PatternInfo> {
     ALL_PATTERNS
         .iter()
+        .chain(load_user_patterns().iter())
         .filter(|p| p.category == category)
         .map(PatternInfo::from)
         .collect()
 }
 
+// =============================================================================
+// USER PATTERNS
+// =============================================================================
+
+/// Folder holding user pattern files
+fn user_patterns_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home)
+        .join(".config")
+        .join("freqlab")
+        .join("patterns")
+}
+
+/// File-safe slug for a pattern name ("My Groove!" -> "my_groove")
+fn pattern_slug(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    slug.split('_').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("_")
+}
+
+impl PatternDef {
+    /// Check the definition and convert it to a playable pattern
+    fn to_pattern(&self, name: &str, category: PatternCategory) -> Result<Pattern, String> {
+        let slug = pattern_slug(name);
+        if slug.is_empty() {
+            return Err("Pattern name must contain letters or numbers".to_string());
+        }
+        if self.notes.is_empty() {
+            return Err("Pattern has no notes".to_string());
+        }
+
+        let mut notes = Vec::with_capacity(self.notes.len());
+        let mut end = 0.0f32;
+        for (i, note) in self.notes.iter().enumerate() {
+            if !(note.duration.is_finite() && note.duration > 0.0) {
+                return Err(format!("Note {} has a non-positive duration: {}", i + 1, note.duration));
+            }
+            if !(note.start.is_finite() && note.start >= 0.0) {
+                return Err(format!("Note {} starts before the pattern: {}", i + 1, note.start));
+            }
+            end = end.max(note.start + note.duration);
+            notes.push(PatternNote::new(note.start, note.note, note.velocity, note.duration));
+        }
+        notes.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+
+        let length_beats = match self.length_beats {
+            Some(length) if length.is_finite() && length > 0.0 => length,
+            Some(length) => return Err(format!("Pattern length must be positive: {}", length)),
+            None => end.ceil().max(1.0),
+        };
+
+        Ok(Pattern {
+            id: Cow::Owned(format!("{}{}", USER_PATTERN_PREFIX, slug)),
+            name: Cow::Owned(name.trim().to_string()),
+            category,
+            length_beats,
+            notes: Cow::Owned(notes),
+        })
+    }
+}
+
+/// Load every valid user pattern in a folder, sorted by name (invalid files are skipped)
+fn load_user_patterns_from(dir: &Path) -> Vec<Pattern> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut patterns: Vec<Pattern> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let file: UserPatternFile = match serde_json::from_str(&content) {
+                Ok(file) => file,
+                Err(e) => {
+                    log::warn!("Skipping invalid pattern file {:?}: {}", path, e);
+                    return None;
+                }
+            };
+            match file.def.to_pattern(&file.name, file.category) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    log::warn!("Skipping invalid pattern file {:?}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    patterns.sort_by_key(|p| p.name.to_lowercase());
+    patterns
+}
+
+fn save_user_pattern_to(
+    dir: &Path,
+    name: &str,
+    category: PatternCategory,
+    def: PatternDef,
+) -> Result<PatternInfo, String> {
+    let pattern = def.to_pattern(name, category)?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create patterns folder: {}", e))?;
+
+    let file = UserPatternFile {
+        name: pattern.name.to_string(),
+        category,
+        def,
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize pattern: {}", e))?;
+    let path = dir.join(format!("{}.json", pattern_slug(name)));
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write pattern: {}", e))?;
+
+    Ok(PatternInfo::from(&pattern))
+}
+
+fn delete_user_pattern_from(dir: &Path, name: &str) -> Result<(), String> {
+    // Accept either the display name or the pattern ID
+    let slug = pattern_slug(name.strip_prefix(USER_PATTERN_PREFIX).unwrap_or(name));
+    let path = dir.join(format!("{}.json", slug));
+    if !path.exists() {
+        return Err(format!("User pattern not found: {}", name));
+    }
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete pattern: {}", e))
+}
+
+/// Load all user patterns
+pub fn load_user_patterns() -> Vec<Pattern> {
+    load_user_patterns_from(&user_patterns_dir())
+}
+
+/// Save (or overwrite) a user pattern
+pub fn save_user_pattern(name: &str, category: PatternCategory, def: PatternDef) -> Result<PatternInfo, String> {
+    save_user_pattern_to(&user_patterns_dir(), name, category, def)
+}
+
+/// Delete a user pattern by name or ID
+pub fn delete_user_pattern(name: &str) -> Result<(), String> {
+    delete_user_pattern_from(&user_patterns_dir(), name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_pattern_count() {
-        assert_eq!(ALL_PATTERNS.len(), 13);
+        assert_eq!(ALL_PATTERNS.len(), 15);
     }
 
     #[test]
@@ -419,7 +680,7 @@ mod tests {
     #[test]
     fn test_categories() {
         let melodic = get_patterns_by_category(PatternCategory::Melodic);
-        assert_eq!(melodic.len(), 5);
+        assert_eq!(melodic.len(), 7);
 
         let bass = get_patterns_by_category(PatternCategory::Bass);
         assert_eq!(bass.len(), 4);
@@ -427,4 +688,38 @@ mod tests {
         let drums = get_patterns_by_category(PatternCategory::Drums);
         assert_eq!(drums.len(), 4);
     }
+
+    #[test]
+    fn test_user_pattern_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("freqlab-patterns-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let def = PatternDef {
+            notes: vec![
+                PatternNoteDef { start: 1.0, duration: 0.5, note: 64, velocity: 90 },
+                PatternNoteDef { start: 0.0, duration: 2.25, note: 60, velocity: 100 },
+            ],
+            length_beats: None,
+        };
+        let info = save_user_pattern_to(&dir, "My Groove!", PatternCategory::Bass, def.clone()).unwrap();
+        assert_eq!(info.id, "user_my_groove");
+        assert!(info.is_user);
+        assert_eq!(info.length_beats, 3.0);
+
+        let loaded = load_user_patterns_from(&dir);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "My Groove!");
+        // Notes are sorted by start for the player
+        assert_eq!(loaded[0].notes[0].note, 60);
+
+        let mut bad = def;
+        bad.notes[0].duration = 0.0;
+        assert!(save_user_pattern_to(&dir, "Bad", PatternCategory::Bass, bad).is_err());
+
+        delete_user_pattern_from(&dir, "user_my_groove").unwrap();
+        assert!(load_user_patterns_from(&dir).is_empty());
+        assert!(delete_user_pattern_from(&dir, "My Groove!").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
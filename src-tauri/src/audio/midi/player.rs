@@ -1,19 +1,47 @@
 //! MIDI pattern and file player with BPM-based scheduling
 
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 use parking_lot::Mutex;
 
-use super::events::MidiEventQueue;
-use super::file::{MidiFileNote, TempoEvent};
-use super::patterns::get_pattern;
+use super::events::{MidiEvent, MidiEventQueue};
+use super::file::{beats_to_seconds, seconds_to_beats, MidiFileNote, TempoEvent};
+use super::patterns::{get_pattern, PatternNote};
+
+/// Swing works on 16th-note pairs: the second 16th of each pair is delayed
+const SWING_STEP_BEATS: f32 = 0.25;
+
+/// Number of MIDI file tracks that can be muted or soloed (one bit each)
+const MAX_MIXED_TRACKS: usize = 64;
 
 /// Active notes tracker for sending note-offs
 struct ActiveNote {
     note: u8,
     end_beat: f32,
+    /// MIDI file track the note came from (0 for patterns)
+    track: usize,
+}
+
+/// Muted and soloed MIDI file tracks, one bit per track
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct TrackMix {
+    muted: u64,
+    soloed: u64,
+}
+
+impl TrackMix {
+    /// Whether a track is heard: any solo silences every track that isn't soloed,
+    /// otherwise only muted tracks are silent
+    fn is_audible(&self, track: usize) -> bool {
+        let bit = if track < MAX_MIXED_TRACKS { 1u64 << track } else { 0 };
+        if self.soloed != 0 {
+            self.soloed & bit != 0
+        } else {
+            self.muted & bit == 0
+        }
+    }
 }
 
 /// Playback source type
@@ -34,18 +62,30 @@ struct PlayerSharedState {
     octave_shift: AtomicU32,
     /// Whether to loop
     is_looping: AtomicBool,
+    /// Pattern swing amount (0.0-1.0, stored as f32 bits in u32)
+    swing_bits: AtomicU32,
     /// Signal to stop the thread
     should_stop: AtomicBool,
     /// Playback source type (0 = Pattern, 1 = MidiFile)
     source_type: AtomicU8,
-    /// Whether to use tempo automation for MIDI files
+    /// Whether MIDI files follow their embedded tempo map (otherwise the fixed BPM)
     use_tempo_automation: AtomicBool,
+    /// Bitmask of muted MIDI file tracks
+    muted_tracks: AtomicU64,
+    /// Bitmask of soloed MIDI file tracks
+    soloed_tracks: AtomicU64,
     /// Current playback position in beats (stored as f32 bits in u32)
     playback_position_bits: AtomicU32,
     /// Current duration in beats (stored as f32 bits in u32)
     duration_beats_bits: AtomicU32,
     /// Seek request position in beats (f32::MAX bits = no seek pending)
     seek_request_bits: AtomicU32,
+    /// Whether MIDI file playback wraps within the loop region
+    loop_enabled: AtomicBool,
+    /// Loop region start in beats (stored as f32 bits in u32)
+    loop_start_bits: AtomicU32,
+    /// Loop region end in beats (stored as f32 bits in u32)
+    loop_end_bits: AtomicU32,
     /// Version counter for MIDI file data (incremented on each track change)
     midi_data_version: AtomicU32,
     /// Version counter for MIDI queue (incremented when queue changes, e.g., hot reload)
@@ -58,6 +98,26 @@ struct PlayerSharedState {
     midi_queue: Mutex<Option<Arc<MidiEventQueue>>>,
 }
 
+impl PlayerSharedState {
+    fn track_mix(&self) -> TrackMix {
+        TrackMix {
+            muted: self.muted_tracks.load(Ordering::SeqCst),
+            soloed: self.soloed_tracks.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Active loop region in beats, if enabled
+    fn loop_region(&self) -> Option<(f32, f32)> {
+        if !self.loop_enabled.load(Ordering::SeqCst) {
+            return None;
+        }
+        Some((
+            f32::from_bits(self.loop_start_bits.load(Ordering::SeqCst)),
+            f32::from_bits(self.loop_end_bits.load(Ordering::SeqCst)),
+        ))
+    }
+}
+
 /// Data for MIDI file playback
 #[derive(Clone)]
 struct MidiFileData {
@@ -80,12 +140,18 @@ impl MidiPlayer {
             bpm: AtomicU32::new(120),
             octave_shift: AtomicU32::new(0), // 0 = no shift
             is_looping: AtomicBool::new(true),
+            swing_bits: AtomicU32::new(0.0_f32.to_bits()),
             should_stop: AtomicBool::new(false),
             source_type: AtomicU8::new(PlaybackSource::Pattern as u8),
-            use_tempo_automation: AtomicBool::new(false),
+            use_tempo_automation: AtomicBool::new(true),
+            muted_tracks: AtomicU64::new(0),
+            soloed_tracks: AtomicU64::new(0),
             playback_position_bits: AtomicU32::new(0.0_f32.to_bits()),
             duration_beats_bits: AtomicU32::new(0.0_f32.to_bits()),
             seek_request_bits: AtomicU32::new(f32::MAX.to_bits()), // No seek pending
+            loop_enabled: AtomicBool::new(false),
+            loop_start_bits: AtomicU32::new(0.0_f32.to_bits()),
+            loop_end_bits: AtomicU32::new(0.0_f32.to_bits()),
             midi_data_version: AtomicU32::new(0),
             midi_queue_version: AtomicU32::new(0),
             current_pattern: Mutex::new(None),
@@ -202,6 +268,35 @@ impl MidiPlayer {
         self.shared.use_tempo_automation.store(enabled, Ordering::SeqCst);
     }
 
+    /// Mute or unmute a MIDI file track (index into the loaded file's tracks)
+    /// Notes it's holding are released on the next tick.
+    pub fn set_track_mute(&self, track: usize, muted: bool) -> Result<(), String> {
+        Self::set_track_bit(&self.shared.muted_tracks, track, muted)
+    }
+
+    /// Solo or unsolo a MIDI file track. While any track is soloed, only soloed tracks play.
+    pub fn set_track_solo(&self, track: usize, solo: bool) -> Result<(), String> {
+        Self::set_track_bit(&self.shared.soloed_tracks, track, solo)
+    }
+
+    /// Clear all track mutes and solos (e.g. when another file is loaded)
+    pub fn reset_track_mix(&self) {
+        self.shared.muted_tracks.store(0, Ordering::SeqCst);
+        self.shared.soloed_tracks.store(0, Ordering::SeqCst);
+    }
+
+    fn set_track_bit(bits: &AtomicU64, track: usize, set: bool) -> Result<(), String> {
+        if track >= MAX_MIXED_TRACKS {
+            return Err(format!("Only the first {} tracks can be muted or soloed", MAX_MIXED_TRACKS));
+        }
+        if set {
+            bits.fetch_or(1u64 << track, Ordering::SeqCst);
+        } else {
+            bits.fetch_and(!(1u64 << track), Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
     /// Stop playback
     pub fn stop(&self) {
         self.shared.is_playing.store(false, Ordering::SeqCst);
@@ -221,6 +316,18 @@ impl MidiPlayer {
         self.shared.octave_shift.store(shift as i8 as u8 as u32, Ordering::SeqCst);
     }
 
+    /// Set pattern swing in percent (0-100, clamped)
+    /// Takes effect at the next 16th-note pair, so no note is skipped or played twice.
+    pub fn set_swing(&self, percent: f32) {
+        let amount = if percent.is_finite() { (percent / 100.0).clamp(0.0, 1.0) } else { 0.0 };
+        self.shared.swing_bits.store(amount.to_bits(), Ordering::SeqCst);
+    }
+
+    /// Get pattern swing in percent
+    pub fn get_swing(&self) -> f32 {
+        f32::from_bits(self.shared.swing_bits.load(Ordering::SeqCst)) * 100.0
+    }
+
     /// Set looping
     pub fn set_looping(&self, looping: bool) {
         self.shared.is_looping.store(looping, Ordering::SeqCst);
@@ -254,9 +361,49 @@ impl MidiPlayer {
         f32::from_bits(self.shared.duration_beats_bits.load(Ordering::SeqCst))
     }
 
+    /// Convert a beat position to seconds at the current tempo settings
+    /// (the file's tempo map when following embedded tempo, otherwise the fixed BPM)
+    pub fn beats_to_seconds(&self, beats: f32) -> f32 {
+        let bpm = self.shared.bpm.load(Ordering::SeqCst).max(1) as f32;
+        let is_file = self.shared.source_type.load(Ordering::SeqCst) == PlaybackSource::MidiFile as u8;
+        if is_file && self.shared.use_tempo_automation.load(Ordering::SeqCst) {
+            if let Some(ref data) = *self.shared.midi_file_notes.lock() {
+                if !data.tempo_map.is_empty() {
+                    return beats_to_seconds(&data.tempo_map, beats);
+                }
+            }
+        }
+        beats * 60.0 / bpm
+    }
+
     /// Seek to a position in beats (will be processed on next tick)
+    /// Clamped into the loop region while it's enabled.
     pub fn seek(&self, position_beats: f32) {
-        self.shared.seek_request_bits.store(position_beats.to_bits(), Ordering::SeqCst);
+        let position = match self.shared.loop_region() {
+            Some((start, end)) if end > start => position_beats.clamp(start, end),
+            _ => position_beats,
+        };
+        self.shared.seek_request_bits.store(position.to_bits(), Ordering::SeqCst);
+    }
+
+    /// Set the MIDI file loop region in beats (takes effect immediately)
+    /// Playback wraps from `end_beats` back to `start_beats` while enabled.
+    pub fn set_loop_region(&self, start_beats: f32, end_beats: f32, enabled: bool) -> Result<(), String> {
+        if !start_beats.is_finite() || !end_beats.is_finite() || start_beats < 0.0 {
+            return Err(format!("Invalid loop region: {} - {}", start_beats, end_beats));
+        }
+        if enabled && end_beats <= start_beats {
+            return Err(format!("Loop end ({}) must be after loop start ({})", end_beats, start_beats));
+        }
+        self.shared.loop_start_bits.store(start_beats.to_bits(), Ordering::SeqCst);
+        self.shared.loop_end_bits.store(end_beats.to_bits(), Ordering::SeqCst);
+        self.shared.loop_enabled.store(enabled, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Get the loop region (start, end) in beats, if enabled
+    pub fn get_loop_region(&self) -> Option<(f32, f32)> {
+        self.shared.loop_region()
     }
 }
 
@@ -273,11 +420,129 @@ impl Drop for MidiPlayer {
     }
 }
 
+/// Send note-ons for file notes starting in `[from, to)` as one batch and track them
+/// for note-offs (notes on tracks silenced by `mix` are skipped)
+fn start_file_notes(
+    notes: &[MidiFileNote],
+    from: f32,
+    to: f32,
+    octave_shift: i8,
+    mix: TrackMix,
+    midi_queue: &MidiEventQueue,
+    active_notes: &mut Vec<ActiveNote>,
+) {
+    let mut note_ons = Vec::new();
+    let mut started = Vec::new();
+    for file_note in notes {
+        if file_note.beat >= from && file_note.beat < to && mix.is_audible(file_note.track) {
+            // Apply octave shift
+            let shifted_note = (file_note.note as i16 + (octave_shift as i16 * 12))
+                .clamp(0, 127) as u8;
+
+            note_ons.push(MidiEvent::note_on(shifted_note, file_note.velocity));
+            started.push(ActiveNote {
+                note: shifted_note,
+                end_beat: file_note.beat + file_note.duration,
+                track: file_note.track,
+            });
+        }
+    }
+    if midi_queue.push_batch(&note_ons) {
+        active_notes.extend(started);
+    }
+}
+
+/// Release held notes on tracks that `mix` silences, so muting a track mid-note doesn't leave it stuck
+fn release_silenced_notes(mix: TrackMix, midi_queue: &MidiEventQueue, active_notes: &mut Vec<ActiveNote>) {
+    active_notes.retain(|active| {
+        if mix.is_audible(active.track) {
+            true
+        } else {
+            midi_queue.note_off(active.note);
+            false
+        }
+    });
+}
+
+/// Where a note at `beat` plays with swing applied (0.0-1.0)
+///
+/// Each 16th-note pair is warped so its second 16th lands up to half a step later.
+/// The pair's boundaries stay put, so the bar length and note order don't change.
+fn swung_beat(beat: f32, swing: f32) -> f32 {
+    if swing <= 0.0 {
+        return beat;
+    }
+    let pair = SWING_STEP_BEATS * 2.0;
+    let pair_start = (beat / pair).floor() * pair;
+    let offset = beat - pair_start;
+    let split = SWING_STEP_BEATS * (1.0 + swing * 0.5);
+    let swung = if offset < SWING_STEP_BEATS {
+        offset * split / SWING_STEP_BEATS
+    } else {
+        split + (offset - SWING_STEP_BEATS) * (pair - split) / SWING_STEP_BEATS
+    };
+    pair_start + swung
+}
+
+/// Send note-ons for pattern notes starting in `[from, to)` and track them for note-offs
+///
+/// Notes sharing a beat (chords) always land in the same window, and the window's
+/// note-ons are queued as one batch, so a chord reaches the plugin in a single audio block.
+fn start_pattern_notes(
+    notes: &[PatternNote],
+    from: f32,
+    to: f32,
+    swing: f32,
+    octave_shift: i8,
+    midi_queue: &MidiEventQueue,
+    active_notes: &mut Vec<ActiveNote>,
+) {
+    let mut note_ons = Vec::new();
+    let mut started = Vec::new();
+    for pattern_note in notes {
+        let start = swung_beat(pattern_note.beat, swing);
+        if start >= from && start < to {
+            let shifted_note = (pattern_note.note as i16 + (octave_shift as i16 * 12))
+                .clamp(0, 127) as u8;
+
+            note_ons.push(MidiEvent::note_on(shifted_note, pattern_note.velocity));
+            started.push(ActiveNote {
+                note: shifted_note,
+                end_beat: start + pattern_note.duration,
+                track: 0,
+            });
+        }
+    }
+    if midi_queue.push_batch(&note_ons) {
+        active_notes.extend(started);
+    }
+}
+
+/// Split the window `[from, to)` at its first 16th-note pair boundary, switching `swing`
+/// to `requested_swing` there. Returns each part with the swing to play it with
+/// (the second part is empty when no boundary is crossed).
+///
+/// Swing only moves notes within their own pair, so as long as a pair is played with
+/// one amount every note in it starts exactly once.
+fn swing_windows(from: f32, to: f32, swing: &mut f32, requested_swing: f32) -> [(f32, f32, f32); 2] {
+    let pair = SWING_STEP_BEATS * 2.0;
+    let boundary = (to / pair).floor() * pair;
+    if boundary > from {
+        let before = (from, boundary, *swing);
+        *swing = requested_swing;
+        [before, (boundary, to, *swing)]
+    } else {
+        [(from, to, *swing), (to, to, *swing)]
+    }
+}
+
 /// Player thread function
 fn player_thread(shared: Arc<PlayerSharedState>) {
     let mut playback_position: f32 = 0.0;
     let mut last_tick = Instant::now();
     let mut active_notes: Vec<ActiveNote> = Vec::new();
+    // Swing the pattern is currently played with (follows the requested amount at pair boundaries)
+    let mut swing = 0.0f32;
 
     // Tick intervals
     let active_tick = Duration::from_millis(1);  // 1ms when playing for tight timing
@@ -287,10 +552,11 @@ fn player_thread(shared: Arc<PlayerSharedState>) {
     let mut cached_queue: Option<Arc<MidiEventQueue>> = None;
     let mut cached_queue_version: u32 = 0;
     let mut cached_pattern_id: Option<String> = None;
-    let mut cached_pattern: Option<&'static super::patterns::Pattern> = None;
+    let mut cached_pattern: Option<super::patterns::Pattern> = None;
     let mut cached_source_type: u8 = PlaybackSource::Pattern as u8;
     let mut cached_midi_file: Option<MidiFileData> = None;
     let mut cached_midi_version: u32 = 0;
+    let mut cached_track_mix = TrackMix::default();
 
     loop {
         // Check if we should exit
@@ -304,6 +570,7 @@ fn player_thread(shared: Arc<PlayerSharedState>) {
             playback_position = 0.0;
             shared.playback_position_bits.store(0.0_f32.to_bits(), Ordering::SeqCst);
             active_notes.clear();
+            swing = f32::from_bits(shared.swing_bits.load(Ordering::SeqCst));
             cached_pattern_id = None;
             cached_pattern = None;
             cached_midi_file = None;
@@ -398,34 +665,38 @@ fn player_thread(shared: Arc<PlayerSharedState>) {
                     shared.duration_beats_bits.store(duration.to_bits(), Ordering::SeqCst);
                     let use_tempo_auto = shared.use_tempo_automation.load(Ordering::SeqCst);
 
+                    // Tracks that were just muted (or left out of a solo) let go of their notes
+                    let track_mix = shared.track_mix();
+                    if track_mix != cached_track_mix {
+                        release_silenced_notes(track_mix, &midi_queue, &mut active_notes);
+                        cached_track_mix = track_mix;
+                    }
+
                     // Calculate time delta
                     let now = Instant::now();
                     let dt = now.duration_since(last_tick).as_secs_f32();
                     last_tick = now;
 
-                    // Get current BPM - either from tempo map or fixed BPM
-                    let current_bpm = if use_tempo_auto && !data.tempo_map.is_empty() {
-                        // Find the tempo event that applies at current position
-                        // (last tempo event with beat <= playback_position)
-                        let mut tempo_bpm = data.tempo_map[0].bpm;
-                        for event in &data.tempo_map {
-                            if event.beat <= playback_position {
-                                tempo_bpm = event.bpm;
-                            } else {
-                                break;
-                            }
+                    // Loop region (clamped to the file) - keep playback inside it
+                    let loop_region = shared
+                        .loop_region()
+                        .map(|(start, end)| (start, end.min(duration)))
+                        .filter(|(start, end)| end > start);
+                    if let Some((start, _)) = loop_region {
+                        if playback_position < start {
+                            playback_position = start;
                         }
-                        tempo_bpm
-                    } else {
-                        bpm
-                    };
+                    }
 
-                    let beats_per_second = current_bpm / 60.0;
-                    let beat_delta = dt * beats_per_second;
+                    // Advance either along the file's tempo map (exact across tempo changes
+                    // within a tick) or at the fixed BPM
                     let old_position = playback_position;
-                    playback_position += beat_delta;
-                    // Update position atomic
-                    shared.playback_position_bits.store(playback_position.to_bits(), Ordering::SeqCst);
+                    playback_position = if use_tempo_auto && !data.tempo_map.is_empty() {
+                        let seconds = beats_to_seconds(&data.tempo_map, playback_position);
+                        seconds_to_beats(&data.tempo_map, seconds + dt)
+                    } else {
+                        playback_position + dt * bpm / 60.0
+                    };
 
                     // Check for note-offs (notes that have ended)
                     active_notes.retain(|active| {
@@ -437,25 +708,29 @@ fn player_thread(shared: Arc<PlayerSharedState>) {
                         }
                     });
 
-                    // Check for note-ons (notes that should start)
-                    for file_note in &data.notes {
-                        let note_start = file_note.beat;
-                        let note_end = file_note.beat + file_note.duration;
-
-                        if note_start >= old_position && note_start < playback_position {
-                            // Apply octave shift
-                            let shifted_note = (file_note.note as i16 + (octave_shift as i16 * 12))
-                                .clamp(0, 127) as u8;
-
-                            midi_queue.note_on(shifted_note, file_note.velocity);
+                    if let Some((loop_start, loop_end)) = loop_region {
+                        if playback_position >= loop_end {
+                            // Play up to the loop end, then wrap. Notes spanning the boundary
+                            // would hang, so everything is released before restarting.
+                            start_file_notes(&data.notes, old_position, loop_end, octave_shift, track_mix, &midi_queue, &mut active_notes);
+                            for active in active_notes.drain(..) {
+                                midi_queue.note_off(active.note);
+                            }
+                            midi_queue.all_notes_off();
 
-                            active_notes.push(ActiveNote {
-                                note: shifted_note,
-                                end_beat: note_end,
-                            });
+                            playback_position = loop_start + (playback_position - loop_end) % (loop_end - loop_start);
+                            start_file_notes(&data.notes, loop_start, playback_position, octave_shift, track_mix, &midi_queue, &mut active_notes);
+                            shared.playback_position_bits.store(playback_position.to_bits(), Ordering::SeqCst);
+                            continue;
                         }
                     }
 
+                    // Update position atomic
+                    shared.playback_position_bits.store(playback_position.to_bits(), Ordering::SeqCst);
+
+                    // Check for note-ons (notes that should start)
+                    start_file_notes(&data.notes, old_position, playback_position, octave_shift, track_mix, &midi_queue, &mut active_notes);
+
                     // Check for loop or end
                     if playback_position >= duration {
                         for active in &active_notes {
@@ -498,8 +773,8 @@ fn player_thread(shared: Arc<PlayerSharedState>) {
                 }
             }
 
-            match cached_pattern {
-                Some(p) => (p.notes, p.length_beats),
+            match &cached_pattern {
+                Some(p) => (&p.notes[..], p.length_beats),
                 None => {
                     shared.is_playing.store(false, Ordering::SeqCst);
                     continue;
@@ -528,21 +803,9 @@ fn player_thread(shared: Arc<PlayerSharedState>) {
         });
 
         // Check for note-ons (notes that should start) - Pattern mode only (MIDI file handled above)
-        for pattern_note in notes_slice {
-            let note_start = pattern_note.beat;
-            let note_end = pattern_note.beat + pattern_note.duration;
-
-            if note_start >= old_position && note_start < playback_position {
-                let shifted_note = (pattern_note.note as i16 + (octave_shift as i16 * 12))
-                    .clamp(0, 127) as u8;
-
-                midi_queue.note_on(shifted_note, pattern_note.velocity);
-
-                active_notes.push(ActiveNote {
-                    note: shifted_note,
-                    end_beat: note_end,
-                });
-            }
+        let requested_swing = f32::from_bits(shared.swing_bits.load(Ordering::SeqCst));
+        for (from, to, swing) in swing_windows(old_position, playback_position, &mut swing, requested_swing) {
+            start_pattern_notes(notes_slice, from, to, swing, octave_shift, &midi_queue, &mut active_notes);
         }
 
         // Check for loop or end
@@ -588,4 +851,159 @@ mod tests {
         player.set_bpm(500); // Above max
         assert_eq!(player.get_bpm(), 400);
     }
+
+    #[test]
+    fn test_loop_region_clamps_seek() {
+        let player = MidiPlayer::new();
+        assert!(player.set_loop_region(8.0, 4.0, true).is_err());
+        assert!(player.get_loop_region().is_none());
+
+        player.set_loop_region(4.0, 8.0, true).unwrap();
+        assert_eq!(player.get_loop_region(), Some((4.0, 8.0)));
+
+        player.seek(1.0);
+        assert_eq!(f32::from_bits(player.shared.seek_request_bits.load(Ordering::SeqCst)), 4.0);
+        player.seek(12.0);
+        assert_eq!(f32::from_bits(player.shared.seek_request_bits.load(Ordering::SeqCst)), 8.0);
+
+        // Disabled: seeks are free again
+        player.set_loop_region(4.0, 8.0, false).unwrap();
+        player.seek(1.0);
+        assert_eq!(f32::from_bits(player.shared.seek_request_bits.load(Ordering::SeqCst)), 1.0);
+    }
+
+    #[test]
+    fn test_block_chord_notes_start_in_the_same_tick() {
+        let pattern = get_pattern("progression_chords").unwrap();
+        let queue = MidiEventQueue::new(64);
+        let mut active_notes = Vec::new();
+
+        // One 1ms tick at 120 BPM straddling the first chord
+        start_pattern_notes(&pattern.notes, 0.0, 0.002, 0.0, 0, &queue, &mut active_notes);
+
+        let mut events = Vec::with_capacity(64);
+        queue.drain_into(&mut events);
+        let notes: Vec<(u8, u8)> = events
+            .iter()
+            .filter_map(|event| match *event {
+                MidiEvent::NoteOn { note, velocity, .. } => Some((note, velocity)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(notes, vec![(60, 100), (64, 100), (67, 100)]);
+        assert_eq!(active_notes.len(), 3);
+        assert!(active_notes.iter().all(|active| active.end_beat == 0.9));
+
+        // The next chord waits for its beat
+        start_pattern_notes(&pattern.notes, 0.002, 0.999, 0.0, 0, &queue, &mut active_notes);
+        assert!(queue.is_empty());
+    }
+
+    /// Beats at which each pattern note starts when stepping through one bar in 1ms
+    /// ticks at 120 BPM, with `swing_at` giving the requested swing for each tick
+    fn play_bar(pattern_id: &str, swing_at: impl Fn(usize) -> f32) -> Vec<(u8, f32)> {
+        let pattern = get_pattern(pattern_id).unwrap();
+        let queue = MidiEventQueue::new(1024);
+        let mut active_notes = Vec::new();
+        let mut starts = Vec::new();
+        let mut swing = swing_at(0);
+        let beat_per_tick = 0.002;
+
+        for tick in 0..(pattern.length_beats / beat_per_tick).ceil() as usize {
+            let from = tick as f32 * beat_per_tick;
+            let to = ((tick + 1) as f32 * beat_per_tick).min(pattern.length_beats);
+            for (from, to, swing) in swing_windows(from, to, &mut swing, swing_at(tick)) {
+                start_pattern_notes(&pattern.notes, from, to, swing, 0, &queue, &mut active_notes);
+            }
+            starts.extend(active_notes.drain(..).map(|active| (active.note, from)));
+        }
+        starts
+    }
+
+    #[test]
+    fn test_swing_delays_off_beats() {
+        let straight = play_bar("scale_run", |_| 0.0);
+        let swung = play_bar("scale_run", |_| 0.6);
+        assert_eq!(straight.len(), 16);
+        assert_eq!(swung.len(), 16);
+
+        for (index, ((note, straight_beat), (swung_note, swung_beat))) in straight.iter().zip(&swung).enumerate() {
+            assert_eq!(note, swung_note);
+            if index % 2 == 0 {
+                // On-beats don't move
+                assert!((straight_beat - swung_beat).abs() < 0.003, "{} vs {}", straight_beat, swung_beat);
+            } else {
+                // Off-beats land 60% of half a 16th (0.075 beats) late
+                let delay = swung_beat - straight_beat;
+                assert!((delay - 0.075).abs() < 0.003, "off-beat {} delayed by {}", index, delay);
+            }
+        }
+        assert_eq!(swung_beat(4.0, 1.0), 4.0);
+        assert_eq!(swung_beat(0.25, 1.0), 0.375);
+    }
+
+    #[test]
+    fn test_swing_change_mid_bar_plays_every_note_once() {
+        // Swing jumps up and down at ticks that fall inside 16th pairs
+        let starts = play_bar("scale_run", |tick| match tick {
+            0..=180 => 0.0,
+            181..=1190 => 1.0,
+            _ => 0.2,
+        });
+        let notes: Vec<u8> = starts.iter().map(|(note, _)| *note).collect();
+        let expected: Vec<u8> = get_pattern("scale_run").unwrap().notes.iter().map(|n| n.note).collect();
+        assert_eq!(notes, expected);
+        assert!(starts.windows(2).all(|pair| pair[0].1 < pair[1].1));
+    }
+
+    fn file_note(beat: f32, note: u8, track: usize) -> MidiFileNote {
+        MidiFileNote { beat, note, velocity: 100, duration: 1.0, channel: track as u8, track }
+    }
+
+    /// Note numbers of the note-ons and note-offs drained from the queue
+    fn drain_notes(queue: &MidiEventQueue) -> (Vec<u8>, Vec<u8>) {
+        use crate::audio::midi::MidiEvent;
+
+        let mut events = Vec::with_capacity(64);
+        queue.drain_into(&mut events);
+        let ons = events.iter().filter_map(|e| match *e { MidiEvent::NoteOn { note, .. } => Some(note), _ => None }).collect();
+        let offs = events.iter().filter_map(|e| match *e { MidiEvent::NoteOff { note, .. } => Some(note), _ => None }).collect();
+        (ons, offs)
+    }
+
+    #[test]
+    fn test_muted_track_is_silent() {
+        let notes = [file_note(0.0, 36, 0), file_note(0.0, 60, 1), file_note(0.5, 38, 0), file_note(0.5, 64, 2)];
+        let queue = MidiEventQueue::new(64);
+        let mut active_notes = Vec::new();
+
+        let muted = TrackMix { muted: 0b001, soloed: 0 };
+        start_file_notes(&notes, 0.0, 1.0, 0, muted, &queue, &mut active_notes);
+        assert_eq!(drain_notes(&queue).0, vec![60, 64]);
+
+        // Solo overrides mutes: a soloed muted track plays, everything else is silent
+        let soloed = TrackMix { muted: 0b001, soloed: 0b101 };
+        active_notes.clear();
+        start_file_notes(&notes, 0.0, 1.0, 0, soloed, &queue, &mut active_notes);
+        assert_eq!(drain_notes(&queue).0, vec![36, 38, 64]);
+
+        // Muting track 2 mid-note releases only its note
+        release_silenced_notes(TrackMix { muted: 0b100, soloed: 0 }, &queue, &mut active_notes);
+        assert_eq!(drain_notes(&queue).1, vec![64]);
+        assert_eq!(active_notes.iter().map(|active| active.note).collect::<Vec<_>>(), vec![36, 38]);
+    }
+
+    #[test]
+    fn test_track_mix_bits() {
+        let player = MidiPlayer::new();
+        player.set_track_mute(0, true).unwrap();
+        player.set_track_mute(3, true).unwrap();
+        player.set_track_solo(2, true).unwrap();
+        player.set_track_mute(3, false).unwrap();
+        assert_eq!(player.shared.track_mix(), TrackMix { muted: 0b0001, soloed: 0b0100 });
+        assert!(player.set_track_solo(MAX_MIXED_TRACKS, true).is_err());
+
+        player.reset_track_mix();
+        assert_eq!(player.shared.track_mix(), TrackMix::default());
+    }
 }
@@ -9,11 +9,20 @@
 
 pub mod buffer;
 pub mod device;
+pub mod dsp;
 pub mod engine;
+pub mod gain_match;
+pub mod ghost_kick;
 pub mod input;
+pub mod loudness;
 pub mod midi;
+pub mod output;
 pub mod plugin;
+pub mod render;
+pub mod response;
+pub mod sample_stream;
 pub mod samples;
 pub mod signals;
 pub mod spectrum;
 pub mod stereo;
+pub mod weighting;
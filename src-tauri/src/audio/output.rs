@@ -0,0 +1,307 @@
+//! Output backends for the audio engine
+//!
+//! The engine normally renders into a cpal output stream. When no output device
+//! can be opened (headless machines, every device busy) it falls back to a null
+//! output: a thread that runs the same render callback in real time and throws
+//! the audio away, so plugins, metering and MIDI keep working without sound.
+//!
+//! The engine renders stereo; `route_channels` places it on any channel pair of
+//! a wider device (multi-output interfaces).
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::device::AudioConfig;
+
+/// Device name reported while the engine runs on the null output
+pub const NULL_OUTPUT_NAME: &str = "No audio device";
+
+/// If the null output falls further behind than this, it stops catching up
+/// and restarts its schedule from now (e.g. after the machine was suspended)
+const NULL_OUTPUT_MAX_LAG: Duration = Duration::from_millis(250);
+
+/// Where the engine sends its output
+pub enum OutputTarget {
+    /// A real output device
+    Device(cpal::Device),
+    /// Nowhere - audio is rendered and discarded
+    Null,
+}
+
+/// A running output. Must be kept alive for audio to keep flowing.
+pub enum OutputStream {
+    Device(cpal::Stream),
+    Null(NullOutput),
+}
+
+/// Real-time render thread without a device behind it
+pub struct NullOutput {
+    running: Arc<AtomicBool>,
+}
+
+impl NullOutput {
+    /// Flag that keeps the render thread alive - clear it to stop the thread
+    pub fn running_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.running)
+    }
+}
+
+/// Which physical output channels the engine's stereo signal is written to
+pub struct ChannelRouting {
+    /// Channels the output stream was opened with
+    device_channels: u16,
+    left: AtomicU16,
+    right: AtomicU16,
+}
+
+impl ChannelRouting {
+    /// Route to the first pair (or channel 0 on a mono device)
+    pub fn new(device_channels: u16) -> Self {
+        let device_channels = device_channels.max(1);
+        Self {
+            device_channels,
+            left: AtomicU16::new(0),
+            right: AtomicU16::new(1.min(device_channels - 1)),
+        }
+    }
+
+    /// Total channels of the output
+    pub fn device_channels(&self) -> u16 {
+        self.device_channels
+    }
+
+    /// Current (left, right) channel indices (0-based)
+    pub fn get(&self) -> (u16, u16) {
+        (self.left.load(Ordering::Relaxed), self.right.load(Ordering::Relaxed))
+    }
+
+    /// Select the (left, right) channel indices (0-based) - must exist on the device and differ
+    pub fn set(&self, left: u16, right: u16) -> Result<(), String> {
+        if left >= self.device_channels || right >= self.device_channels {
+            return Err(format!(
+                "Output channels {}/{} are out of range - the device has {} channel{}",
+                left + 1,
+                right + 1,
+                self.device_channels,
+                if self.device_channels == 1 { "" } else { "s" }
+            ));
+        }
+        if left == right && self.device_channels > 1 {
+            return Err("Left and right output channels must be different".to_string());
+        }
+        self.left.store(left, Ordering::Relaxed);
+        self.right.store(right, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// True when the output is the engine's own layout (first pair of a mono/stereo device)
+    fn is_passthrough(&self, render_channels: usize) -> bool {
+        let (left, right) = self.get();
+        self.device_channels as usize == render_channels && left == 0 && right == self.device_channels - 1
+    }
+}
+
+/// Wrap a render callback producing `render_channels` (1 or 2) interleaved channels so it
+/// writes into the selected slots of the device's buffer. Other channels get silence.
+/// Blocks are rendered at most `max_frames` at a time.
+pub fn route_channels<R>(
+    routing: Arc<ChannelRouting>,
+    render_channels: usize,
+    max_frames: usize,
+    mut render: R,
+) -> impl FnMut(&mut [f32]) + Send + 'static
+where
+    R: FnMut(&mut [f32]) + Send + 'static,
+{
+    let device_channels = routing.device_channels() as usize;
+    let render_channels = render_channels.clamp(1, 2);
+    let max_frames = max_frames.max(1);
+    let mut scratch = vec![0.0f32; max_frames * render_channels];
+
+    move |data: &mut [f32]| {
+        if routing.is_passthrough(render_channels) {
+            render(data);
+            return;
+        }
+
+        let (left, right) = routing.get();
+        for block in data.chunks_mut(max_frames * device_channels) {
+            let frames = block.len() / device_channels;
+            let rendered = &mut scratch[..frames * render_channels];
+            rendered.fill(0.0);
+            render(rendered);
+
+            for (out, frame) in block.chunks_mut(device_channels).zip(rendered.chunks(render_channels)) {
+                out.fill(0.0);
+                out[left as usize] = frame[0];
+                out[right as usize] = frame[render_channels - 1];
+            }
+        }
+    }
+}
+
+/// Stream config for the null output, taken straight from the requested settings
+pub fn null_stream_config(config: &AudioConfig) -> cpal::StreamConfig {
+    cpal::StreamConfig {
+        channels: config.channels.max(1),
+        sample_rate: cpal::SampleRate(config.sample_rate),
+        buffer_size: cpal::BufferSize::Fixed(config.buffer_size.max(1)),
+    }
+}
+
+impl OutputTarget {
+    /// Name to report for this output
+    pub fn name(&self) -> String {
+        match self {
+            OutputTarget::Device(device) => device.name().unwrap_or_else(|_| "Unknown device".to_string()),
+            OutputTarget::Null => NULL_OUTPUT_NAME.to_string(),
+        }
+    }
+
+    /// Build and start an output that calls `render` for every block of interleaved samples.
+    /// `on_error` only fires for device streams - the null output can't fail once started.
+    pub fn build_stream<R, E>(
+        &self,
+        stream_config: &cpal::StreamConfig,
+        mut render: R,
+        on_error: E,
+    ) -> Result<OutputStream, String>
+    where
+        R: FnMut(&mut [f32]) + Send + 'static,
+        E: FnMut(cpal::StreamError) + Send + 'static,
+    {
+        match self {
+            OutputTarget::Device(device) => {
+                let stream = device
+                    .build_output_stream(
+                        stream_config,
+                        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| render(data),
+                        on_error,
+                        None, // No timeout
+                    )
+                    .map_err(|e| format!("Failed to build output stream: {}", e))?;
+                stream
+                    .play()
+                    .map_err(|e| format!("Failed to start stream: {}", e))?;
+                Ok(OutputStream::Device(stream))
+            }
+            OutputTarget::Null => spawn_null_output(stream_config, render).map(OutputStream::Null),
+        }
+    }
+}
+
+/// Start a thread that renders one block per buffer period and discards it
+fn spawn_null_output<R>(stream_config: &cpal::StreamConfig, mut render: R) -> Result<NullOutput, String>
+where
+    R: FnMut(&mut [f32]) + Send + 'static,
+{
+    let frames = match stream_config.buffer_size {
+        cpal::BufferSize::Fixed(frames) => frames.max(1) as usize,
+        cpal::BufferSize::Default => 512,
+    };
+    let channels = stream_config.channels.max(1) as usize;
+    let period = Duration::from_secs_f64(frames as f64 / stream_config.sample_rate.0.max(1) as f64);
+
+    let running = Arc::new(AtomicBool::new(true));
+    let thread_running = Arc::clone(&running);
+
+    std::thread::Builder::new()
+        .name("null-audio-output".to_string())
+        .spawn(move || {
+            log::info!("Null audio output started ({} frames per block)", frames);
+            let mut buffer = vec![0.0f32; frames * channels];
+            let mut deadline = Instant::now();
+
+            while thread_running.load(Ordering::SeqCst) {
+                buffer.fill(0.0);
+                render(&mut buffer);
+
+                deadline += period;
+                let now = Instant::now();
+                if deadline > now {
+                    std::thread::sleep(deadline - now);
+                } else if now - deadline > NULL_OUTPUT_MAX_LAG {
+                    deadline = now;
+                }
+            }
+
+            log::info!("Null audio output stopped");
+        })
+        .map_err(|e| format!("Failed to start null audio output: {}", e))?;
+
+    Ok(NullOutput { running })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_channel_routing_validation() {
+        let routing = ChannelRouting::new(8);
+        assert_eq!(routing.get(), (0, 1));
+        assert!(routing.set(2, 3).is_ok());
+        assert_eq!(routing.get(), (2, 3));
+        assert!(routing.set(6, 8).is_err());
+        assert!(routing.set(4, 4).is_err());
+        assert_eq!(routing.get(), (2, 3));
+
+        let mono = ChannelRouting::new(1);
+        assert_eq!(mono.get(), (0, 0));
+        assert!(mono.set(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_route_channels_writes_selected_pair() {
+        let routing = Arc::new(ChannelRouting::new(4));
+        let mut output = route_channels(Arc::clone(&routing), 2, 3, |data: &mut [f32]| {
+            for frame in data.chunks_mut(2) {
+                frame[0] = 0.5;
+                frame[1] = -0.5;
+            }
+        });
+
+        // 5 frames, more than max_frames, so the render runs in two blocks
+        let mut data = vec![9.0f32; 5 * 4];
+        routing.set(3, 2).unwrap();
+        output(&mut data);
+        for frame in data.chunks(4) {
+            assert_eq!(frame, &[0.0, 0.0, -0.5, 0.5]);
+        }
+    }
+
+    #[test]
+    fn test_null_output_renders_in_real_time() {
+        let config = AudioConfig {
+            sample_rate: 48000,
+            channels: 2,
+            buffer_size: 480, // 10ms blocks
+        };
+        let frames = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&frames);
+
+        let stream = OutputTarget::Null
+            .build_stream(
+                &null_stream_config(&config),
+                move |data: &mut [f32]| {
+                    counted.fetch_add(data.len() / 2, Ordering::SeqCst);
+                },
+                |_| {},
+            )
+            .unwrap();
+        let OutputStream::Null(null) = stream else {
+            panic!("expected a null output");
+        };
+
+        std::thread::sleep(Duration::from_millis(200));
+        null.running_flag().store(false, Ordering::SeqCst);
+
+        // ~9600 frames in 200ms - allow for scheduling jitter, but not free-running
+        let rendered = frames.load(Ordering::SeqCst);
+        assert!(rendered >= 4800, "rendered {} frames", rendered);
+        assert!(rendered <= 14400, "rendered {} frames", rendered);
+    }
+}
@@ -0,0 +1,150 @@
+//! Parameter automation recording and playback
+//!
+//! Parameter changes made through the engine are timestamped against the engine's
+//! sample clock and stored as one lane per parameter. On playback the audio thread
+//! turns lane points into CLAP param value events at the matching sample offsets.
+
+use parking_lot::Mutex;
+use ringbuf::{traits::*, HeapRb};
+use serde::{Deserialize, Serialize};
+
+/// A parameter value change to deliver to the plugin on the next process call
+#[derive(Debug, Clone, Copy)]
+pub struct ParamChange {
+    /// CLAP parameter id
+    pub param_id: u32,
+    /// Plain parameter value
+    pub value: f64,
+}
+
+/// One recorded parameter value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AutomationPoint {
+    /// Sample position relative to the start of the recording
+    pub sample: u64,
+    /// Plain parameter value
+    pub value: f64,
+}
+
+/// Recorded values for a single parameter, sorted by sample position
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutomationLane {
+    pub param_id: u32,
+    pub points: Vec<AutomationPoint>,
+}
+
+/// A set of automation lanes captured in one recording pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParamAutomation {
+    /// Sample rate the positions were recorded at
+    pub sample_rate: u32,
+    pub lanes: Vec<AutomationLane>,
+}
+
+impl ParamAutomation {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            lanes: Vec::new(),
+        }
+    }
+
+    /// Record a value at a sample position (positions must be non-decreasing per lane)
+    pub fn record(&mut self, param_id: u32, sample: u64, value: f64) {
+        let lane = match self.lanes.iter_mut().position(|l| l.param_id == param_id) {
+            Some(index) => &mut self.lanes[index],
+            None => {
+                self.lanes.push(AutomationLane {
+                    param_id,
+                    points: Vec::new(),
+                });
+                self.lanes.last_mut().unwrap()
+            }
+        };
+        lane.points.push(AutomationPoint { sample, value });
+    }
+
+    /// Position of the last recorded point across all lanes
+    pub fn length(&self) -> u64 {
+        self.lanes
+            .iter()
+            .filter_map(|lane| lane.points.last())
+            .map(|point| point.sample)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lanes.iter().all(|lane| lane.points.is_empty())
+    }
+
+    /// Visit every point in `[start, start + frames)`, passing the offset within the block
+    /// Lanes are sorted, so each lookup is a binary search (no allocation - audio thread safe)
+    pub fn for_each_in_block(&self, start: u64, frames: u32, mut f: impl FnMut(u32, u32, f64)) {
+        let end = start + frames as u64;
+        for lane in &self.lanes {
+            let first = lane.points.partition_point(|p| p.sample < start);
+            for point in lane.points[first..].iter().take_while(|p| p.sample < end) {
+                f(lane.param_id, (point.sample - start) as u32, point.value);
+            }
+        }
+    }
+}
+
+/// Queue of parameter changes from command handlers to the audio thread
+///
+/// Same layout as the MIDI queue: producers share a Mutex, the audio thread
+/// only ever uses try_lock on the consumer side.
+pub struct ParamValueQueue {
+    producer: Mutex<ringbuf::HeapProd<ParamChange>>,
+    consumer: Mutex<ringbuf::HeapCons<ParamChange>>,
+}
+
+impl ParamValueQueue {
+    pub fn new(capacity: usize) -> Self {
+        let rb = HeapRb::new(capacity);
+        let (producer, consumer) = rb.split();
+        Self {
+            producer: Mutex::new(producer),
+            consumer: Mutex::new(consumer),
+        }
+    }
+
+    /// Queue a change (returns false if the queue is full)
+    pub fn push(&self, change: ParamChange) -> bool {
+        self.producer.lock().try_push(change).is_ok()
+    }
+
+    /// Pop every pending change (called from the audio thread, never blocks)
+    #[inline]
+    pub fn drain(&self, mut f: impl FnMut(ParamChange)) {
+        if let Some(mut consumer) = self.consumer.try_lock() {
+            while let Some(change) = consumer.try_pop() {
+                f(change);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_automation_block_lookup() {
+        let mut automation = ParamAutomation::new(48000);
+        automation.record(1, 0, 0.0);
+        automation.record(1, 100, 0.5);
+        automation.record(2, 150, 1.0);
+        automation.record(1, 600, 1.0);
+        assert_eq!(automation.length(), 600);
+
+        let mut events = Vec::new();
+        automation.for_each_in_block(64, 128, |id, offset, value| events.push((id, offset, value)));
+        assert_eq!(events, vec![(1, 36, 0.5), (2, 86, 1.0)]);
+
+        events.clear();
+        automation.for_each_in_block(192, 128, |id, offset, value| events.push((id, offset, value)));
+        assert!(events.is_empty());
+    }
+}
@@ -7,6 +7,7 @@ use super::clap_sys::*;
 use super::editor;
 use crate::audio::midi::{MidiEvent, MidiEventQueue};
 use libloading::{Library, Symbol};
+use serde::{Deserialize, Serialize};
 use std::ffi::{CStr, CString};
 use std::path::{Path, PathBuf};
 use std::process::Child;
@@ -24,6 +25,91 @@ pub fn take_callback_request() -> bool {
     CALLBACK_REQUESTED.swap(false, Ordering::SeqCst)
 }
 
+/// Bumped whenever a plugin reports that its note names changed
+/// Instances compare against this to know when their cached names are stale
+static NOTE_NAMES_GENERATION: AtomicU32 = AtomicU32::new(0);
+
+/// Current note name generation (changes each time a plugin signals new note names)
+pub fn note_names_generation() -> u32 {
+    NOTE_NAMES_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Note names as (key, name), sorted by key
+pub type NoteNames = Vec<(i16, String)>;
+
+/// Names reported for the CLAP_PARAM_* flags in PluginParam::flags
+const PARAM_FLAG_NAMES: [(u32, &str); 9] = [
+    (CLAP_PARAM_IS_STEPPED, "stepped"),
+    (CLAP_PARAM_IS_PERIODIC, "periodic"),
+    (CLAP_PARAM_IS_HIDDEN, "hidden"),
+    (CLAP_PARAM_IS_READONLY, "readonly"),
+    (CLAP_PARAM_IS_BYPASS, "bypass"),
+    (CLAP_PARAM_IS_AUTOMATABLE, "automatable"),
+    (CLAP_PARAM_IS_MODULATABLE, "modulatable"),
+    (CLAP_PARAM_REQUIRES_PROCESS, "requires_process"),
+    (CLAP_PARAM_IS_ENUM, "enum"),
+];
+
+/// A parameter of the loaded plugin, as reported by the clap.params extension
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginParam {
+    pub id: u32,
+    pub name: String,
+    /// Group path the plugin files the parameter under (e.g. "Filter/Envelope"), may be empty
+    pub module: String,
+    pub min_value: f64,
+    pub max_value: f64,
+    pub default_value: f64,
+    /// Current plain value (None if the plugin couldn't report it)
+    pub value: Option<f64>,
+    /// Current value as formatted by the plugin (e.g. "-6.0 dB")
+    pub display_value: Option<String>,
+    /// Set CLAP_PARAM_* flags by name (e.g. "stepped", "automatable")
+    pub flags: Vec<String>,
+}
+
+/// An audio port as declared by the plugin through clap.audio-ports
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioPortInfo {
+    pub name: String,
+    pub is_input: bool,
+    /// The main port in its direction (the one the engine's stereo bus is connected to)
+    pub is_main: bool,
+    pub channel_count: u32,
+}
+
+/// How the engine's stereo bus is fed into the plugin's main input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputMapping {
+    /// Left and right go to the first two channels (mono inputs get the left channel)
+    #[default]
+    StereoPassthrough,
+    /// (L + R) / 2 on every channel
+    MonoSum,
+    /// The left channel on every channel
+    LeftOnly,
+}
+
+/// Names of the known flags set in a CLAP parameter's flags
+fn param_flag_names(flags: u32) -> Vec<String> {
+    PARAM_FLAG_NAMES
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Read a NUL-terminated string out of a fixed-size CLAP buffer, even if the plugin filled it completely
+pub(super) fn fixed_c_string(buffer: &mut [std::os::raw::c_char]) -> String {
+    if let Some(last) = buffer.last_mut() {
+        *last = 0;
+    }
+    unsafe { CStr::from_ptr(buffer.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
 /// Host name and version info
 const HOST_NAME: &str = "freqlab";
 const HOST_VENDOR: &str = "freqlab";
@@ -56,6 +142,8 @@ pub struct PluginInstance {
     pub vendor: String,
     pub version: String,
     pub plugin_id: String,
+    /// Detected at load from the descriptor features and audio ports (see detect_instrument)
+    pub is_instrument: bool,
 
     // Audio state
     sample_rate: f64,
@@ -63,11 +151,14 @@ pub struct PluginInstance {
     is_active: bool,
     is_processing: bool,
 
-    // Audio buffers (pre-allocated)
-    input_buffer_ptrs: Vec<*mut f32>,
-    output_buffer_ptrs: Vec<*mut f32>,
-    input_data: Vec<Vec<f32>>,
-    output_data: Vec<Vec<f32>>,
+    // Audio buffers (pre-allocated, f64 if the plugin prefers 64-bit processing)
+    // The main input has as many channels as the plugin declares on it
+    input_buffers: PortBuffers,
+    input_mapping: InputMapping,
+    audio_ports: Vec<AudioPortInfo>,
+    output_buffers: PortBuffers,
+    // Sidechain (second input port) buffers, no channels if the plugin has no sidechain input
+    sidechain_buffers: PortBuffers,
 
     // Plugin path (kept for potential editor host use)
     _plugin_path: PathBuf,
@@ -80,8 +171,12 @@ pub struct PluginInstance {
     editor_open: bool,
     // Channel for receiving state updates from editor process
     state_receiver: Option<std::sync::mpsc::Receiver<Vec<u8>>>,
+    // Sending side of the state channel (used to queue state from the main thread)
+    state_sender: Option<std::sync::mpsc::Sender<Vec<u8>>>,
     // Counter for how many states have been applied (for debugging)
     state_apply_count: AtomicU32,
+    // Counter for queued states the plugin rejected
+    state_failure_count: AtomicU32,
 
     // Direct editor window state (for in-process hosting by editor_host binary)
     #[cfg(target_os = "macos")]
@@ -94,10 +189,17 @@ pub struct PluginInstance {
     midi_context: MidiEventContext,
     /// Pre-allocated buffer for draining MIDI events (avoids allocation in audio thread)
     midi_drain_buffer: Vec<MidiEvent>,
+    /// Parameter changes for the next process call: (sample offset, param id, value)
+    pending_param_values: Vec<(u32, u32, f64)>,
+
+    /// Note names from the clap.note-name extension, with the generation they were read at
+    note_names: Mutex<Option<(u32, NoteNames)>>,
 
     // Safety
     /// Set to true if the plugin panics during process - we'll output silence instead of crashing
     crashed: bool,
+    /// Set when process() returns CLAP_PROCESS_ERROR - the plugin is bypassed until reloaded
+    process_failed: bool,
 }
 
 // Host callback structure (renamed to avoid conflict with ClapHost struct)
@@ -141,7 +243,7 @@ pub fn cleanup_temp_bundles() {
     if let Ok(entries) = std::fs::read_dir(&temp_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.extension().map(|e| e == "clap").unwrap_or(false) {
+            if path.extension().map(|e| e == "clap" || e == "vst3").unwrap_or(false) {
                 log::info!("Removing stale temp bundle: {:?}", path);
                 if let Err(e) = std::fs::remove_dir_all(&path) {
                     log::warn!("Failed to remove temp bundle {:?}: {}", path, e);
@@ -286,12 +388,15 @@ impl PluginInstance {
             }
         };
 
+        let features = unsafe { read_features(desc_ref.features) };
+
         log::info!(
-            "Loading plugin: {} by {} (id: {}, version: {})",
+            "Loading plugin: {} by {} (id: {}, version: {}, features: {:?})",
             name,
             vendor,
             plugin_id,
-            version
+            version,
+            features
         );
 
         // Create host callbacks structure
@@ -350,14 +455,28 @@ impl PluginInstance {
             return Err("Plugin init() failed".to_string());
         }
 
-        // Pre-allocate audio buffers (stereo)
-        let channels = 2usize;
-        let mut input_data: Vec<Vec<f32>> = Vec::with_capacity(channels);
-        let mut output_data: Vec<Vec<f32>> = Vec::with_capacity(channels);
-        for _ in 0..channels {
-            input_data.push(vec![0.0f32; max_frames as usize]);
-            output_data.push(vec![0.0f32; max_frames as usize]);
+        // Ports must be queried while the plugin is inactive
+        let ports = Self::query_audio_ports(plugin);
+        if ports.sidechain_channels > 0 {
+            log::info!("Plugin has a {}-channel sidechain input", ports.sidechain_channels);
+        }
+        if ports.use_f64 {
+            log::info!("Plugin prefers 64-bit processing, using f64 buffers");
         }
+        let is_instrument = detect_instrument(&features, ports.input_count);
+        log::info!("Plugin detected as {}", if is_instrument { "instrument" } else { "effect" });
+
+        // Pre-allocate audio buffers (stereo out, input as declared - stereo if unknown)
+        let max = max_frames as usize;
+        let input_channels = ports.main_input_channels.unwrap_or(2) as usize;
+        if input_channels != 2 {
+            log::info!("Plugin main input has {} channel(s)", input_channels);
+        }
+        let input_buffers = PortBuffers::new(input_channels, max, ports.use_f64);
+        let output_buffers = PortBuffers::new(2, max, ports.use_f64);
+        let sidechain_buffers = PortBuffers::new(ports.sidechain_channels as usize, max, ports.use_f64);
+
+        let (state_sender, state_receiver) = std::sync::mpsc::channel();
 
         let mut host_instance = Self {
             _library: Some(library),
@@ -373,27 +492,34 @@ impl PluginInstance {
             vendor,
             version,
             plugin_id,
+            is_instrument,
             sample_rate,
             max_frames,
             is_active: false,
             is_processing: false,
-            input_buffer_ptrs: Vec::new(),
-            output_buffer_ptrs: Vec::new(),
-            input_data,
-            output_data,
+            input_buffers,
+            input_mapping: InputMapping::default(),
+            audio_ports: ports.ports,
+            output_buffers,
+            sidechain_buffers,
             _plugin_path: bundle_path.to_path_buf(),
             temp_bundle_path,
             _editor_process: None,
             editor_open: false,
-            state_receiver: None,
+            state_receiver: Some(state_receiver),
+            state_sender: Some(state_sender),
             state_apply_count: AtomicU32::new(0),
+            state_failure_count: AtomicU32::new(0),
             #[cfg(target_os = "macos")]
             editor_window: None,
             midi_queue: Arc::new(MidiEventQueue::new(1024)),
             midi_context: MidiEventContext::new(),
+            pending_param_values: Vec::with_capacity(256),
+            note_names: Mutex::new(None),
             // Pre-allocate buffer for 256 events (covers typical usage without reallocation)
             midi_drain_buffer: Vec::with_capacity(256),
             crashed: false,
+            process_failed: false,
         };
 
         // Activate the plugin
@@ -404,9 +530,9 @@ impl PluginInstance {
         Ok(host_instance)
     }
 
-    /// Copy the .clap bundle to a temp location with a unique suffix
+    /// Copy the plugin bundle (.clap or .vst3) to a temp location with a unique suffix
     /// This bypasses macOS's dylib caching which can cause hot reload to show old versions
-    fn copy_to_temp(bundle_path: &Path) -> Result<(PathBuf, Option<PathBuf>), String> {
+    pub(super) fn copy_to_temp(bundle_path: &Path) -> Result<(PathBuf, Option<PathBuf>), String> {
         use std::time::{SystemTime, UNIX_EPOCH};
 
         // Generate a unique suffix using timestamp
@@ -419,6 +545,10 @@ impl PluginInstance {
             .file_stem()
             .ok_or("Invalid bundle path")?
             .to_string_lossy();
+        let extension = bundle_path
+            .extension()
+            .map(|e| e.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "clap".to_string());
 
         // Create temp directory for plugin bundles
         let temp_dir = std::env::temp_dir().join("freqlab-plugins");
@@ -430,7 +560,7 @@ impl PluginInstance {
             let prefix = format!("{}_", bundle_name);
             for entry in entries.flatten() {
                 let name = entry.file_name().to_string_lossy().to_string();
-                if name.starts_with(&prefix) && name.ends_with(".clap") {
+                if name.starts_with(&prefix) && name.ends_with(&format!(".{}", extension)) {
                     log::info!("Cleaning up old temp bundle: {:?}", entry.path());
                     let _ = std::fs::remove_dir_all(entry.path());
                 }
@@ -438,7 +568,7 @@ impl PluginInstance {
         }
 
         // Create unique temp bundle name
-        let temp_bundle_name = format!("{}_{}.clap", bundle_name, timestamp);
+        let temp_bundle_name = format!("{}_{}.{}", bundle_name, timestamp, extension);
         let temp_bundle_path = temp_dir.join(&temp_bundle_name);
 
         log::info!(
@@ -590,17 +720,127 @@ impl PluginInstance {
         log::info!("Plugin processing stopped");
     }
 
+    /// Read the plugin's port layout from the clap.audio-ports extension
+    ///
+    /// The sidechain is the second input port. 64-bit processing is used only when the
+    /// main ports both support and prefer it, so f32 stays the default.
+    fn query_audio_ports(plugin: *const ClapPlugin) -> AudioPortLayout {
+        let mut layout = AudioPortLayout::default();
+        let plugin_ref = unsafe { &*plugin };
+        let Some(get_ext) = plugin_ref.get_extension else {
+            return layout;
+        };
+        let ext = unsafe { get_ext(plugin, CLAP_EXT_AUDIO_PORTS.as_ptr() as *const _) };
+        if ext.is_null() {
+            return layout;
+        }
+        let ports_ext = unsafe { &*(ext as *const ClapPluginAudioPorts) };
+        let (Some(count), Some(get)) = (ports_ext.count, ports_ext.get) else {
+            return layout;
+        };
+        layout.input_count = Some(unsafe { count(plugin, true) });
+
+        let port_info = |index: u32, is_input: bool| -> Option<ClapAudioPortInfo> {
+            if index >= unsafe { count(plugin, is_input) } {
+                return None;
+            }
+            let mut info = ClapAudioPortInfo {
+                id: 0,
+                name: [0; 256],
+                flags: 0,
+                channel_count: 0,
+                port_type: ptr::null(),
+                in_place_pair: u32::MAX,
+            };
+            unsafe { get(plugin, index, is_input, &mut info) }.then_some(info)
+        };
+        let port_flags =
+            |index: u32, is_input: bool| port_info(index, is_input).map(|info| (info.flags, info.channel_count));
+
+        for is_input in [true, false] {
+            for index in 0..unsafe { count(plugin, is_input) } {
+                if let Some(mut info) = port_info(index, is_input) {
+                    layout.ports.push(AudioPortInfo {
+                        name: fixed_c_string(&mut info.name),
+                        is_input,
+                        is_main: info.flags & CLAP_AUDIO_PORT_IS_MAIN != 0,
+                        channel_count: info.channel_count,
+                    });
+                }
+            }
+        }
+
+        // The bus is fed to the first input port if it's the main one
+        if let Some((flags, channels)) = port_flags(0, true) {
+            if flags & CLAP_AUDIO_PORT_IS_MAIN != 0 && channels > 0 {
+                layout.main_input_channels = Some(channels);
+            }
+        }
+
+        // Only mono and stereo sidechains are fed
+        if let Some((flags, channels)) = port_flags(1, true) {
+            if flags & CLAP_AUDIO_PORT_IS_MAIN == 0 {
+                layout.sidechain_channels = channels.min(2);
+            }
+        }
+
+        let wants_f64 = |flags: u32| {
+            flags & CLAP_AUDIO_PORT_SUPPORTS_64BITS != 0 && flags & CLAP_AUDIO_PORT_PREFERS_64BITS != 0
+        };
+        let main_ports = [port_flags(0, true), port_flags(0, false)];
+        layout.use_f64 = main_ports.iter().any(|port| port.is_some())
+            && main_ports.iter().flatten().all(|&(flags, _)| wants_f64(flags));
+
+        layout
+    }
+
+    /// Audio ports the plugin declared at load (empty without the audio-ports extension)
+    pub fn audio_ports(&self) -> &[AudioPortInfo] {
+        &self.audio_ports
+    }
+
+    /// How the stereo bus is mapped onto the plugin's main input
+    pub fn set_input_mapping(&mut self, mapping: InputMapping) {
+        self.input_mapping = mapping;
+    }
+
+    pub fn input_mapping(&self) -> InputMapping {
+        self.input_mapping
+    }
+
+    /// Whether the plugin has a sidechain input port
+    pub fn has_sidechain_input(&self) -> bool {
+        self.sidechain_buffers.channels() > 0
+    }
+
     /// Process audio through the plugin
     ///
     /// Takes stereo input samples and returns stereo output samples.
     /// Input/output are interleaved: [L, R, L, R, ...]
     pub fn process(&mut self, input: &[f32], output: &mut [f32]) -> Result<(), String> {
+        self.process_with_sidechain(input, None, output)
+    }
+
+    /// Process audio through the plugin with an optional sidechain signal
+    ///
+    /// `sidechain` is interleaved stereo like `input`. Plugins with a sidechain port get
+    /// silence there when it's None; plugins without one ignore it.
+    pub fn process_with_sidechain(
+        &mut self,
+        input: &[f32],
+        sidechain: Option<&[f32]>,
+        output: &mut [f32],
+    ) -> Result<(), String> {
         // If plugin has crashed, output silence to prevent repeated crashes
         if self.crashed {
             output.fill(0.0);
             return Ok(());
         }
 
+        if self.process_failed {
+            return Err("Plugin reported a processing error".to_string());
+        }
+
         if !self.is_active {
             return Err("Plugin not active".to_string());
         }
@@ -619,76 +859,34 @@ impl PluginInstance {
         if frames > self.max_frames as usize {
             // Silently truncate rather than error - audio callbacks must not fail
             log::warn!("Buffer size {} exceeds max_frames {}, truncating", frames, self.max_frames);
-            return self.process(&input[..self.max_frames as usize * 2], &mut output[..self.max_frames as usize * 2]);
+            let len = self.max_frames as usize * 2;
+            return self.process_with_sidechain(&input[..len], sidechain.map(|sc| &sc[..len.min(sc.len())]), &mut output[..len]);
         }
 
-        // Deinterleave input into channel buffers
-        for i in 0..frames {
-            self.input_data[0][i] = input[i * 2];     // Left
-            self.input_data[1][i] = input[i * 2 + 1]; // Right
-        }
+        // Deinterleave input and sidechain (silence if none) into channel buffers
+        self.input_buffers.deinterleave_mapped(Some(input), frames, self.input_mapping);
+        self.sidechain_buffers.deinterleave(sidechain, frames);
+        self.output_buffers.deinterleave(None, frames);
 
-        // Clear output buffers
-        for ch in &mut self.output_data {
-            for sample in ch.iter_mut().take(frames) {
-                *sample = 0.0;
-            }
-        }
+        // Create audio buffers (main input, then the sidechain port if the plugin has one)
+        let input_buffers = [self.input_buffers.clap_buffer(), self.sidechain_buffers.clap_buffer()];
+        let input_count = if self.has_sidechain_input() { 2 } else { 1 };
 
-        // Set up buffer pointers
-        self.input_buffer_ptrs.clear();
-        self.output_buffer_ptrs.clear();
-        for ch in &mut self.input_data {
-            self.input_buffer_ptrs.push(ch.as_mut_ptr());
-        }
-        for ch in &mut self.output_data {
-            self.output_buffer_ptrs.push(ch.as_mut_ptr());
-        }
-
-        // Create audio buffers
-        let input_buffer = ClapAudioBuffer {
-            data32: self.input_buffer_ptrs.as_mut_ptr(),
-            data64: ptr::null_mut(),
-            channel_count: 2,
-            latency: 0,
-            constant_mask: 0,
-        };
-
-        let mut output_buffer = ClapAudioBuffer {
-            data32: self.output_buffer_ptrs.as_mut_ptr(),
-            data64: ptr::null_mut(),
-            channel_count: 2,
-            latency: 0,
-            constant_mask: 0,
-        };
+        let mut output_buffer = self.output_buffers.clap_buffer();
 
         // Drain MIDI queue into pre-allocated buffer (avoids allocation in audio thread)
         self.midi_queue.drain_into(&mut self.midi_drain_buffer);
 
         // Convert MIDI events to CLAP format
         self.midi_context.clear();
-        for event in self.midi_drain_buffer.iter() {
-            match event {
-                MidiEvent::NoteOn { note, velocity, channel } => {
-                    self.midi_context.add_note_on(*note, *velocity, *channel, 0);
-                }
-                MidiEvent::NoteOff { note, velocity, channel } => {
-                    self.midi_context.add_note_off(*note, *velocity, *channel, 0);
-                }
-                MidiEvent::ControlChange { controller, value, channel } => {
-                    self.midi_context.add_control_change(*controller, *value, *channel, 0);
-                }
-                MidiEvent::PitchBend { value, channel } => {
-                    self.midi_context.add_pitch_bend(*value, *channel, 0);
-                }
-                MidiEvent::AllNotesOff => {
-                    // Send note off for all 128 notes
-                    for note in 0..128u8 {
-                        self.midi_context.add_note_off(note, 0, 0, 0);
-                    }
-                }
-            }
+        fill_midi_context(&mut self.midi_context, &self.midi_drain_buffer);
+
+        // Parameter changes go after the time-0 MIDI events, in time order
+        self.pending_param_values.sort_unstable_by_key(|&(time, _, _)| time);
+        for &(time, param_id, value) in self.pending_param_values.iter() {
+            self.midi_context.add_param_value(param_id, value, time.min(frames as u32 - 1));
         }
+        self.pending_param_values.clear();
 
         // Create input events with MIDI context
         let input_events = ClapInputEvents {
@@ -707,9 +905,9 @@ impl PluginInstance {
             steady_time: -1, // Unknown
             frames_count: frames as u32,
             transport: ptr::null(),
-            audio_inputs: &input_buffer,
+            audio_inputs: input_buffers.as_ptr(),
             audio_outputs: &mut output_buffer,
-            audio_inputs_count: 1,
+            audio_inputs_count: input_count,
             audio_outputs_count: 1,
             in_events: &input_events,
             out_events: &output_events,
@@ -752,26 +950,34 @@ impl PluginInstance {
             }
         };
 
-        // Log process result periodically (every ~1000 calls to avoid spam)
+        if result == CLAP_PROCESS_ERROR {
+            // Output buffers are undefined after an error - let the caller use the dry signal
+            self.process_failed = true;
+            log::error!(
+                "Plugin '{}' returned CLAP_PROCESS_ERROR. Plugin has been bypassed - reload to retry.",
+                self.name
+            );
+            return Err("Plugin reported a processing error".to_string());
+        }
+
+        // Log process result periodically (every ~1000 calls to avoid spam, debug builds
+        // at debug level only)
         static CALL_COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
         let count = CALL_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        if count % 1000 == 0 {
+        if count % 1000 == 0 && cfg!(debug_assertions) && log::log_enabled!(log::Level::Debug) {
             // Check if input had signal
             let input_max = input.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
             // Check if output has signal
-            let output_max_l = self.output_data[0].iter().take(frames).map(|s| s.abs()).fold(0.0f32, f32::max);
-            let output_max_r = self.output_data[1].iter().take(frames).map(|s| s.abs()).fold(0.0f32, f32::max);
-            log::info!(
+            let output_max_l = self.output_buffers.peak(0, frames);
+            let output_max_r = self.output_buffers.peak(1, frames);
+            log::debug!(
                 "Plugin process #{}: frames={}, result={}, input_max={:.4}, output_max_l={:.4}, output_max_r={:.4}",
                 count, frames, result, input_max, output_max_l, output_max_r
             );
         }
 
         // Interleave output from channel buffers
-        for i in 0..frames {
-            output[i * 2] = self.output_data[0][i];     // Left
-            output[i * 2 + 1] = self.output_data[1][i]; // Right
-        }
+        self.output_buffers.interleave_into(output, frames);
 
         Ok(())
     }
@@ -782,6 +988,12 @@ impl PluginInstance {
         self.crashed
     }
 
+    /// Check if the plugin returned CLAP_PROCESS_ERROR
+    /// If true, process() fails without calling the plugin until it's reloaded
+    pub fn has_process_error(&self) -> bool {
+        self.process_failed
+    }
+
     /// Check if the plugin has a GUI
     pub fn has_gui(&self) -> bool {
         let plugin_ref = unsafe { &*self.plugin };
@@ -793,6 +1005,16 @@ impl PluginInstance {
         false
     }
 
+    /// Queue a parameter value change for the next process call at a sample offset
+    /// (called from the audio thread just before process)
+    #[inline]
+    pub fn queue_param_value(&mut self, param_id: u32, value: f64, sample_offset: u32) {
+        // Never grow past the pre-allocated capacity on the audio thread
+        if self.pending_param_values.len() < self.pending_param_values.capacity() {
+            self.pending_param_values.push((sample_offset, param_id, value));
+        }
+    }
+
     /// Get a reference to the MIDI event queue for sending events
     pub fn midi_queue(&self) -> Arc<MidiEventQueue> {
         Arc::clone(&self.midi_queue)
@@ -803,7 +1025,7 @@ impl PluginInstance {
         self.midi_queue.note_on(note, velocity);
     }
 
-    /// Send a note off event to the plugin
+    /// Send a note off event to the plugin (release velocity 0)
     pub fn send_note_off(&self, note: u8) {
         self.midi_queue.note_off(note);
     }
@@ -833,6 +1055,142 @@ impl PluginInstance {
         false
     }
 
+    /// All parameters with their ranges, current values and flags (main thread only).
+    /// Empty if the plugin doesn't support the params extension.
+    pub fn get_params(&self) -> Vec<PluginParam> {
+        Self::query_params(self.plugin)
+    }
+
+    fn query_params(plugin: *const ClapPlugin) -> Vec<PluginParam> {
+        let plugin_ref = unsafe { &*plugin };
+        let Some(get_ext) = plugin_ref.get_extension else {
+            return Vec::new();
+        };
+        let ext = unsafe { get_ext(plugin, CLAP_EXT_PARAMS.as_ptr() as *const _) };
+        if ext.is_null() {
+            return Vec::new();
+        }
+        let params_ext = unsafe { &*(ext as *const ClapPluginParams) };
+        let (Some(count), Some(get_info)) = (params_ext.count, params_ext.get_info) else {
+            return Vec::new();
+        };
+
+        let mut params = Vec::new();
+        for index in 0..unsafe { count(plugin) } {
+            let mut info = ClapParamInfo {
+                id: 0,
+                flags: 0,
+                cookie: ptr::null_mut(),
+                name: [0; 256],
+                module: [0; 1024],
+                min_value: 0.0,
+                max_value: 0.0,
+                default_value: 0.0,
+            };
+            if !unsafe { get_info(plugin, index, &mut info) } {
+                continue;
+            }
+
+            let value = params_ext.get_value.and_then(|get_value| {
+                let mut value = 0.0f64;
+                unsafe { get_value(plugin, info.id, &mut value) }.then_some(value)
+            });
+            let display_value = match (params_ext.value_to_text, value) {
+                (Some(value_to_text), Some(value)) => {
+                    let mut text = [0 as std::os::raw::c_char; 256];
+                    unsafe { value_to_text(plugin, info.id, value, text.as_mut_ptr(), text.len() as u32) }
+                        .then(|| fixed_c_string(&mut text))
+                }
+                _ => None,
+            };
+
+            params.push(PluginParam {
+                id: info.id,
+                name: fixed_c_string(&mut info.name),
+                module: fixed_c_string(&mut info.module),
+                min_value: info.min_value,
+                max_value: info.max_value,
+                default_value: info.default_value,
+                value,
+                display_value,
+                flags: param_flag_names(info.flags),
+            });
+        }
+        params
+    }
+
+    /// Note names for keyboard labeling as (key, name), sorted by key
+    /// Read from the clap.note-name extension and cached until the plugin signals a change.
+    /// Names for "any key" are skipped since they can't label a specific key.
+    pub fn get_note_names(&self) -> NoteNames {
+        let generation = note_names_generation();
+        let mut cache = self.note_names.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_generation, names)) = cache.as_ref() {
+            if *cached_generation == generation {
+                return names.clone();
+            }
+        }
+
+        let names = self.read_note_names();
+        *cache = Some((generation, names.clone()));
+        names
+    }
+
+    fn read_note_names(&self) -> NoteNames {
+        let plugin_ref = unsafe { &*self.plugin };
+        let Some(get_ext) = plugin_ref.get_extension else {
+            return Vec::new();
+        };
+        let ext = unsafe { get_ext(self.plugin, CLAP_EXT_NOTE_NAME.as_ptr() as *const _) };
+        if ext.is_null() {
+            return Vec::new();
+        }
+        let note_name_ext = unsafe { &*(ext as *const ClapPluginNoteName) };
+        let (Some(count), Some(get)) = (note_name_ext.count, note_name_ext.get) else {
+            return Vec::new();
+        };
+
+        let mut names = Vec::new();
+        for index in 0..unsafe { count(self.plugin) } {
+            let mut note_name = ClapNoteName {
+                name: [0; 256],
+                port: -1,
+                key: -1,
+                channel: -1,
+            };
+            if !unsafe { get(self.plugin, index, &mut note_name) } || !(0..=127).contains(&note_name.key) {
+                continue;
+            }
+            // Force termination in case the plugin filled the whole buffer
+            note_name.name[255] = 0;
+            let name = unsafe { CStr::from_ptr(note_name.name.as_ptr()) }
+                .to_string_lossy()
+                .into_owned();
+            if !name.is_empty() && !names.iter().any(|(key, _)| *key == note_name.key) {
+                names.push((note_name.key, name));
+            }
+        }
+        names.sort_by_key(|(key, _)| *key);
+        names
+    }
+
+    /// Latency reported by the plugin in samples (0 if it doesn't support the latency extension)
+    pub fn latency(&self) -> u32 {
+        let plugin_ref = unsafe { &*self.plugin };
+        let Some(get_ext) = plugin_ref.get_extension else {
+            return 0;
+        };
+        let ext = unsafe { get_ext(self.plugin, CLAP_EXT_LATENCY.as_ptr() as *const _) };
+        if ext.is_null() {
+            return 0;
+        }
+        let latency_ext = unsafe { &*(ext as *const ClapPluginLatency) };
+        match latency_ext.get {
+            Some(get) => unsafe { get(self.plugin) },
+            None => 0,
+        }
+    }
+
     /// Flush parameter changes without processing audio
     /// This is needed for the editor host where we don't call process()
     /// When the plugin's GUI changes a parameter, it calls host->request_flush()
@@ -1033,16 +1391,34 @@ impl PluginInstance {
                 // In a production DAW, you'd queue these for the main thread to log
                 if self.load_state(&state).is_ok() {
                     self.state_apply_count.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    self.state_failure_count.fetch_add(1, Ordering::Relaxed);
                 }
             }
         }
     }
 
+    /// Queue a state to be applied by the next apply_pending_state call
+    pub fn queue_state(&self, state: Vec<u8>) -> Result<(), String> {
+        let sender = self
+            .state_sender
+            .as_ref()
+            .ok_or_else(|| "Plugin has no state channel".to_string())?;
+        sender
+            .send(state)
+            .map_err(|_| "Plugin state channel is closed".to_string())
+    }
+
     /// Get the number of state updates applied from the editor
     pub fn get_state_apply_count(&self) -> u32 {
         self.state_apply_count.load(Ordering::Relaxed)
     }
 
+    /// Get the number of queued states that failed to load
+    pub fn get_state_failure_count(&self) -> u32 {
+        self.state_failure_count.load(Ordering::Relaxed)
+    }
+
     /// Check if the editor window is currently open
     pub fn is_editor_open(&self) -> bool {
         self.editor_open
@@ -1085,7 +1461,11 @@ impl PluginInstance {
         // Open the editor window in-process (same plugin instance as audio)
         // This means GUI parameter changes directly affect audio via shared atomics
         log::info!("open_editor_at: Opening editor window in-process at position {:?}", position);
-        self.open_editor_window_at(position)?;
+        if let Err(e) = self.open_editor_window_at(position) {
+            // Audio keeps running - only the editor failed
+            self.editor_open = false;
+            return Err(e);
+        }
 
         self.editor_open = true;
         log::info!("open_editor_at: Editor opened successfully (in-process, shared atomics)");
@@ -1117,6 +1497,21 @@ impl PluginInstance {
         self.get_editor_window_position()
     }
 
+    /// Capture the open editor window's contents as PNG data
+    #[cfg(target_os = "macos")]
+    pub fn capture_editor_png(&self) -> Result<Vec<u8>, String> {
+        let window = self
+            .editor_window
+            .ok_or_else(|| "Editor window is not open".to_string())?;
+        editor::capture_window_png(window)
+    }
+
+    /// Capture the editor window (stub for non-macOS)
+    #[cfg(not(target_os = "macos"))]
+    pub fn capture_editor_png(&self) -> Result<Vec<u8>, String> {
+        Err("Plugin editor not supported on this platform".to_string())
+    }
+
     /// Close the plugin's editor window (IN-PROCESS)
     /// Note: Position is NOT saved here - caller (AudioEngineHandle) should save it
     #[cfg(target_os = "macos")]
@@ -1171,8 +1566,24 @@ impl PluginInstance {
         }
 
         // Create the editor window directly using the editor module
-        let (window, _content_view) = unsafe {
-            editor::create_editor_window_at(self.plugin, &self.name, position)?
+        // Plugin GUI code can panic, and unwinding across the ObjC boundary is undefined -
+        // stop it here and report an error instead of taking down the whole app
+        let created = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            editor::create_editor_window_at(self.plugin, &self.name, position)
+        }));
+        let (window, _content_view) = match created {
+            Ok(result) => result?,
+            Err(payload) => {
+                let message = editor::panic_message(&*payload);
+                log::error!(
+                    "open_editor_window_at: Plugin '{}' panicked while opening its editor: {}",
+                    self.name,
+                    message
+                );
+                self.editor_window = None;
+                self.editor_open = false;
+                return Err(format!("Plugin editor panicked: {}", message));
+            }
         };
 
         self.editor_window = Some(window);
@@ -1242,6 +1653,177 @@ impl PluginInstance {
     }
 }
 
+/// Read a descriptor's null-terminated feature list
+unsafe fn read_features(features: *const *const std::os::raw::c_char) -> Vec<String> {
+    let mut result = Vec::new();
+    if features.is_null() {
+        return result;
+    }
+    let mut i = 0;
+    loop {
+        let feature = *features.add(i);
+        if feature.is_null() {
+            break;
+        }
+        result.push(CStr::from_ptr(feature).to_string_lossy().into_owned());
+        i += 1;
+    }
+    result
+}
+
+/// Whether a plugin is an instrument: it declares the "instrument" or "synthesizer"
+/// feature, or (without either) has no audio inputs at all
+fn detect_instrument(features: &[String], input_count: Option<u32>) -> bool {
+    if features.iter().any(|f| f == "instrument" || f == "synthesizer") {
+        return true;
+    }
+    input_count == Some(0)
+}
+
+/// Port layout read from the clap.audio-ports extension
+#[derive(Debug, Default, Clone, PartialEq)]
+struct AudioPortLayout {
+    /// Number of audio input ports (None if the plugin has no audio-ports extension)
+    input_count: Option<u32>,
+    /// Channels on the main input port (None if there is no main input)
+    main_input_channels: Option<u32>,
+    /// Channels on the sidechain (second) input port, 0 if there is none
+    sidechain_channels: u32,
+    /// Exchange audio as f64 instead of f32
+    use_f64: bool,
+    /// Every declared port, inputs first
+    ports: Vec<AudioPortInfo>,
+}
+
+/// Pre-allocated channel buffers for one audio port, in f32 or f64
+///
+/// Only the precision in use is allocated; the CLAP buffer has the other pointer set null.
+/// The VST3 host uses these too, always in f32.
+pub(super) struct PortBuffers {
+    data32: Vec<Vec<f32>>,
+    data64: Vec<Vec<f64>>,
+    ptrs32: Vec<*mut f32>,
+    ptrs64: Vec<*mut f64>,
+    use_f64: bool,
+}
+
+impl PortBuffers {
+    pub(super) fn new(channels: usize, max_frames: usize, use_f64: bool) -> Self {
+        let (data32, data64) = if use_f64 {
+            (Vec::new(), vec![vec![0.0f64; max_frames]; channels])
+        } else {
+            (vec![vec![0.0f32; max_frames]; channels], Vec::new())
+        };
+        Self {
+            data32,
+            data64,
+            ptrs32: Vec::with_capacity(channels),
+            ptrs64: Vec::with_capacity(channels),
+            use_f64,
+        }
+    }
+
+    pub(super) fn channels(&self) -> usize {
+        if self.use_f64 {
+            self.data64.len()
+        } else {
+            self.data32.len()
+        }
+    }
+
+    /// Fill the first `frames` samples of each channel from interleaved stereo
+    /// (mono ports take the left channel), or with silence if `source` is None
+    pub(super) fn deinterleave(&mut self, source: Option<&[f32]>, frames: usize) {
+        self.deinterleave_mapped(source, frames, InputMapping::StereoPassthrough);
+    }
+
+    /// Like deinterleave, with the stereo source mapped onto the channels by `mapping`
+    /// (channels past the second get the right channel when passing stereo through)
+    pub(super) fn deinterleave_mapped(&mut self, source: Option<&[f32]>, frames: usize, mapping: InputMapping) {
+        let sample_at = |i: usize, channel: usize| -> f32 {
+            let Some(frame) = source.and_then(|s| s.get(i * 2..i * 2 + 2)) else {
+                return 0.0;
+            };
+            match mapping {
+                InputMapping::StereoPassthrough => frame[channel.min(1)],
+                InputMapping::MonoSum => (frame[0] + frame[1]) * 0.5,
+                InputMapping::LeftOnly => frame[0],
+            }
+        };
+        if self.use_f64 {
+            for (channel, data) in self.data64.iter_mut().enumerate() {
+                for (i, sample) in data.iter_mut().take(frames).enumerate() {
+                    *sample = sample_at(i, channel) as f64;
+                }
+            }
+        } else {
+            for (channel, data) in self.data32.iter_mut().enumerate() {
+                for (i, sample) in data.iter_mut().take(frames).enumerate() {
+                    *sample = sample_at(i, channel);
+                }
+            }
+        }
+    }
+
+    /// Write the first two channels into an interleaved stereo buffer
+    pub(super) fn interleave_into(&self, output: &mut [f32], frames: usize) {
+        for (channel, offset) in [(0usize, 0usize), (1, 1)] {
+            for i in 0..frames {
+                output[i * 2 + offset] = if self.use_f64 {
+                    self.data64.get(channel).map_or(0.0, |data| data[i] as f32)
+                } else {
+                    self.data32.get(channel).map_or(0.0, |data| data[i])
+                };
+            }
+        }
+    }
+
+    /// Peak level of a channel over the first `frames` samples
+    fn peak(&self, channel: usize, frames: usize) -> f32 {
+        if self.use_f64 {
+            self.data64.get(channel).map_or(0.0, |data| {
+                data.iter().take(frames).map(|s| s.abs() as f32).fold(0.0f32, f32::max)
+            })
+        } else {
+            self.data32.get(channel).map_or(0.0, |data| {
+                data.iter().take(frames).map(|s| s.abs()).fold(0.0f32, f32::max)
+            })
+        }
+    }
+
+    /// Refresh the f32 channel pointers and return them (null if the buffers are f64)
+    pub(super) fn channel_ptrs32(&mut self) -> *mut *mut f32 {
+        self.ptrs32.clear();
+        for ch in &mut self.data32 {
+            self.ptrs32.push(ch.as_mut_ptr());
+        }
+        if self.use_f64 {
+            ptr::null_mut()
+        } else {
+            self.ptrs32.as_mut_ptr()
+        }
+    }
+
+    /// Refresh the channel pointers and describe the buffers to the plugin
+    fn clap_buffer(&mut self) -> ClapAudioBuffer {
+        self.ptrs32.clear();
+        self.ptrs64.clear();
+        for ch in &mut self.data32 {
+            self.ptrs32.push(ch.as_mut_ptr());
+        }
+        for ch in &mut self.data64 {
+            self.ptrs64.push(ch.as_mut_ptr());
+        }
+        ClapAudioBuffer {
+            data32: if self.use_f64 { ptr::null_mut() } else { self.ptrs32.as_mut_ptr() },
+            data64: if self.use_f64 { self.ptrs64.as_mut_ptr() } else { ptr::null_mut() },
+            channel_count: self.channels() as u32,
+            latency: 0,
+            constant_mask: 0,
+        }
+    }
+}
+
 impl Drop for PluginInstance {
     fn drop(&mut self) {
         log::info!("Unloading plugin: {}", self.name);
@@ -1317,6 +1899,11 @@ unsafe extern "C" fn host_get_extension(
         return &HOST_PARAMS as *const ClapHostParams as *const std::ffi::c_void;
     }
 
+    // Note name extension so drum maps / key switches can tell us when their names change
+    if ext_id.to_bytes_with_nul() == CLAP_EXT_NOTE_NAME {
+        return &HOST_NOTE_NAME as *const ClapHostNoteName as *const std::ffi::c_void;
+    }
+
     ptr::null()
 }
 
@@ -1345,6 +1932,16 @@ unsafe extern "C" fn host_params_request_flush(_host: *const ClapHost) {
     // The parameters will be picked up on the next process() call
 }
 
+// Static host note name extension instance
+static HOST_NOTE_NAME: ClapHostNoteName = ClapHostNoteName {
+    changed: Some(host_note_name_changed),
+};
+
+unsafe extern "C" fn host_note_name_changed(_host: *const ClapHost) {
+    log::debug!("Plugin note names changed");
+    NOTE_NAMES_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
 unsafe extern "C" fn host_request_restart(_host: *const ClapHost_) {
     log::debug!("Plugin requested restart");
     // TODO: Handle restart request
@@ -1360,3 +1957,465 @@ unsafe extern "C" fn host_request_callback(_host: *const ClapHost_) {
     CALLBACK_REQUESTED.store(true, Ordering::SeqCst);
 }
 
+/// Convert queued MIDI events to CLAP events (all at time 0)
+fn fill_midi_context(context: &mut MidiEventContext, events: &[MidiEvent]) {
+    for event in events {
+        match *event {
+            MidiEvent::NoteOn { note, velocity, channel } => {
+                context.add_note_on(note, velocity, channel, 0);
+            }
+            MidiEvent::NoteOff { note, velocity, channel } => {
+                context.add_note_off(note, velocity, channel, 0);
+            }
+            MidiEvent::ControlChange { controller, value, channel } => {
+                context.add_control_change(controller, value, channel, 0);
+            }
+            MidiEvent::PitchBend { value, channel } => {
+                context.add_pitch_bend(value, channel, 0);
+            }
+            MidiEvent::AllNotesOff => {
+                // Send note off for all 128 notes
+                for note in 0..128u8 {
+                    context.add_note_off(note, 0, 0, 0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::c_void;
+    use std::os::raw::c_char;
+
+    /// Port description served by the mock plugin through plugin_data
+    struct MockPorts {
+        main_flags: u32,
+        input_count: u32,
+        /// Channels on the main input port
+        main_channels: u32,
+    }
+
+    unsafe fn mock_ports(plugin: *const ClapPlugin) -> &'static MockPorts {
+        &*((*plugin).plugin_data as *const MockPorts)
+    }
+
+    unsafe extern "C" fn mock_port_count(plugin: *const ClapPlugin, is_input: bool) -> u32 {
+        if is_input {
+            mock_ports(plugin).input_count
+        } else {
+            1
+        }
+    }
+
+    unsafe extern "C" fn mock_port_get(
+        plugin: *const ClapPlugin,
+        index: u32,
+        is_input: bool,
+        info: *mut ClapAudioPortInfo,
+    ) -> bool {
+        let ports = mock_ports(plugin);
+        (*info).flags = if index == 0 { ports.main_flags | CLAP_AUDIO_PORT_IS_MAIN } else { 0 };
+        (*info).channel_count = if index == 0 && is_input { ports.main_channels } else { 2 };
+        let name = match (index, is_input) {
+            (0, true) => "Main In",
+            (0, false) => "Main Out",
+            _ => "Sidechain",
+        };
+        write_c_string(name, (*info).name.as_mut_ptr(), (*info).name.len());
+        true
+    }
+
+    static MOCK_AUDIO_PORTS: ClapPluginAudioPorts = ClapPluginAudioPorts {
+        count: Some(mock_port_count),
+        get: Some(mock_port_get),
+    };
+
+    /// Copy a string into a fixed-size CLAP buffer (NUL-terminated)
+    unsafe fn write_c_string(text: &str, out: *mut c_char, capacity: usize) {
+        let len = text.len().min(capacity - 1);
+        ptr::copy_nonoverlapping(text.as_ptr() as *const c_char, out, len);
+        *out.add(len) = 0;
+    }
+
+    /// Two parameters: "Gain" (id 10, value -6) and "Mode" (id 11, value unavailable)
+    unsafe extern "C" fn mock_param_count(_plugin: *const ClapPlugin) -> u32 {
+        2
+    }
+
+    unsafe extern "C" fn mock_param_get_info(_plugin: *const ClapPlugin, index: u32, info: *mut ClapParamInfo) -> bool {
+        let info = &mut *info;
+        match index {
+            0 => {
+                info.id = 10;
+                info.flags = CLAP_PARAM_IS_AUTOMATABLE;
+                write_c_string("Gain", info.name.as_mut_ptr(), info.name.len());
+                write_c_string("Output", info.module.as_mut_ptr(), info.module.len());
+                (info.min_value, info.max_value, info.default_value) = (-24.0, 24.0, 0.0);
+            }
+            1 => {
+                info.id = 11;
+                info.flags = CLAP_PARAM_IS_STEPPED | CLAP_PARAM_IS_ENUM;
+                write_c_string("Mode", info.name.as_mut_ptr(), info.name.len());
+                (info.min_value, info.max_value, info.default_value) = (0.0, 3.0, 1.0);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    unsafe extern "C" fn mock_param_get_value(_plugin: *const ClapPlugin, param_id: u32, out: *mut f64) -> bool {
+        if param_id != 10 {
+            return false;
+        }
+        *out = -6.0;
+        true
+    }
+
+    unsafe extern "C" fn mock_param_value_to_text(
+        _plugin: *const ClapPlugin,
+        _param_id: u32,
+        value: f64,
+        out: *mut c_char,
+        capacity: u32,
+    ) -> bool {
+        write_c_string(&format!("{:.1} dB", value), out, capacity as usize);
+        true
+    }
+
+    static MOCK_PARAMS: ClapPluginParams = ClapPluginParams {
+        count: Some(mock_param_count),
+        get_info: Some(mock_param_get_info),
+        get_value: Some(mock_param_get_value),
+        value_to_text: Some(mock_param_value_to_text),
+        text_to_value: None,
+        flush: None,
+    };
+
+    unsafe extern "C" fn mock_get_extension(_plugin: *const ClapPlugin, id: *const c_char) -> *const c_void {
+        let id = CStr::from_ptr(id).to_bytes_with_nul();
+        if id == CLAP_EXT_AUDIO_PORTS {
+            &MOCK_AUDIO_PORTS as *const ClapPluginAudioPorts as *const c_void
+        } else if id == CLAP_EXT_PARAMS {
+            &MOCK_PARAMS as *const ClapPluginParams as *const c_void
+        } else {
+            ptr::null()
+        }
+    }
+
+    fn mock_plugin(ports: &MockPorts) -> ClapPlugin {
+        ClapPlugin {
+            desc: ptr::null(),
+            plugin_data: ports as *const MockPorts as *mut c_void,
+            init: None,
+            destroy: None,
+            activate: None,
+            deactivate: None,
+            start_processing: None,
+            stop_processing: None,
+            reset: None,
+            process: None,
+            get_extension: Some(mock_get_extension),
+            on_main_thread: None,
+        }
+    }
+
+    /// Layout the host reads from a mock plugin declaring `main_flags` on its main ports
+    fn layout_for(main_flags: u32, input_count: u32) -> AudioPortLayout {
+        let ports = MockPorts { main_flags, input_count, main_channels: 2 };
+        let plugin = mock_plugin(&ports);
+        PluginInstance::query_audio_ports(&plugin)
+    }
+
+    #[test]
+    fn test_port_layout_from_flags() {
+        let layout = layout_for(0, 1);
+        assert_eq!(
+            (layout.input_count, layout.main_input_channels, layout.sidechain_channels, layout.use_f64),
+            (Some(1), Some(2), 0, false)
+        );
+        // Supporting 64-bit isn't enough - f32 stays the default unless it's preferred
+        assert!(!layout_for(CLAP_AUDIO_PORT_SUPPORTS_64BITS, 1).use_f64);
+        assert!(layout_for(CLAP_AUDIO_PORT_SUPPORTS_64BITS | CLAP_AUDIO_PORT_PREFERS_64BITS, 1).use_f64);
+        assert_eq!(layout_for(0, 2).sidechain_channels, 2);
+    }
+
+    #[test]
+    fn test_port_list_and_mono_main_input() {
+        let ports = MockPorts { main_flags: 0, input_count: 2, main_channels: 1 };
+        let plugin = mock_plugin(&ports);
+        let layout = PluginInstance::query_audio_ports(&plugin);
+
+        assert_eq!(layout.main_input_channels, Some(1));
+        let described: Vec<(&str, bool, bool, u32)> = layout
+            .ports
+            .iter()
+            .map(|port| (port.name.as_str(), port.is_input, port.is_main, port.channel_count))
+            .collect();
+        assert_eq!(
+            described,
+            vec![("Main In", true, true, 1), ("Sidechain", true, false, 2), ("Main Out", false, true, 2)]
+        );
+    }
+
+    #[test]
+    fn test_input_mapping() {
+        let input = [0.5f32, -0.25, 1.0, 0.0];
+        let channel = |buffers: &PortBuffers, index: usize| buffers.data32[index][..2].to_vec();
+
+        let mut stereo = PortBuffers::new(2, 4, false);
+        stereo.deinterleave_mapped(Some(&input), 2, InputMapping::StereoPassthrough);
+        assert_eq!((channel(&stereo, 0), channel(&stereo, 1)), (vec![0.5, 1.0], vec![-0.25, 0.0]));
+        stereo.deinterleave_mapped(Some(&input), 2, InputMapping::MonoSum);
+        assert_eq!((channel(&stereo, 0), channel(&stereo, 1)), (vec![0.125, 0.5], vec![0.125, 0.5]));
+
+        // A mono main input gets the left channel unless the mapping says otherwise
+        let mut mono = PortBuffers::new(1, 4, false);
+        mono.deinterleave_mapped(Some(&input), 2, InputMapping::StereoPassthrough);
+        assert_eq!(channel(&mono, 0), vec![0.5, 1.0]);
+        mono.deinterleave_mapped(Some(&input), 2, InputMapping::MonoSum);
+        assert_eq!(channel(&mono, 0), vec![0.125, 0.5]);
+        mono.deinterleave_mapped(Some(&input), 2, InputMapping::LeftOnly);
+        assert_eq!(channel(&mono, 0), vec![0.5, 1.0]);
+        mono.deinterleave_mapped(None, 2, InputMapping::MonoSum);
+        assert_eq!(channel(&mono, 0), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_buffers_follow_declared_precision() {
+        let input = [0.25f32, -0.5, 0.75, -1.0];
+
+        for flags in [0, CLAP_AUDIO_PORT_SUPPORTS_64BITS | CLAP_AUDIO_PORT_PREFERS_64BITS] {
+            let use_f64 = layout_for(flags, 1).use_f64;
+            let mut buffers = PortBuffers::new(2, 4, use_f64);
+            buffers.deinterleave(Some(&input), 2);
+            let clap_buffer = buffers.clap_buffer();
+            assert_eq!(clap_buffer.channel_count, 2);
+
+            // Only the pointer set for the declared precision is populated, and it holds the input
+            if use_f64 {
+                assert!(clap_buffer.data32.is_null());
+                assert!(!clap_buffer.data64.is_null());
+                let right = unsafe { *clap_buffer.data64.add(1) };
+                assert_eq!(unsafe { *right.add(1) }, -1.0f64);
+                // Simulate the plugin writing its output
+                unsafe { *right = 0.125 };
+            } else {
+                assert!(clap_buffer.data64.is_null());
+                assert!(!clap_buffer.data32.is_null());
+                let right = unsafe { *clap_buffer.data32.add(1) };
+                assert_eq!(unsafe { *right.add(1) }, -1.0f32);
+                unsafe { *right = 0.125 };
+            }
+
+            // Output converts back to interleaved f32
+            let mut output = [0.0f32; 4];
+            buffers.interleave_into(&mut output, 2);
+            assert_eq!(output, [0.25, 0.125, 0.75, -1.0]);
+        }
+    }
+
+    #[test]
+    fn test_query_params() {
+        let ports = MockPorts { main_flags: 0, input_count: 1, main_channels: 2 };
+        let plugin = mock_plugin(&ports);
+        let params = PluginInstance::query_params(&plugin);
+
+        assert_eq!(params.len(), 2);
+        assert_eq!(
+            params[0],
+            PluginParam {
+                id: 10,
+                name: "Gain".to_string(),
+                module: "Output".to_string(),
+                min_value: -24.0,
+                max_value: 24.0,
+                default_value: 0.0,
+                value: Some(-6.0),
+                display_value: Some("-6.0 dB".to_string()),
+                flags: vec!["automatable".to_string()],
+            }
+        );
+        // A value the plugin can't report is left empty rather than made up
+        assert_eq!(params[1].name, "Mode");
+        assert_eq!(params[1].value, None);
+        assert_eq!(params[1].display_value, None);
+        assert_eq!(params[1].flags, vec!["stepped".to_string(), "enum".to_string()]);
+    }
+
+    #[test]
+    fn test_detect_instrument() {
+        let features = |list: &[&str]| list.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        assert!(detect_instrument(&features(&["instrument", "stereo"]), Some(1)));
+        assert!(detect_instrument(&features(&["synthesizer"]), None));
+        assert!(!detect_instrument(&features(&["audio-effect", "stereo"]), Some(1)));
+        // No inputs means nothing to process but MIDI
+        assert!(detect_instrument(&features(&[]), Some(0)));
+        // Unknown ports and no instrument feature: assume an effect
+        assert!(!detect_instrument(&features(&[]), None));
+
+        let list = [c"instrument".as_ptr(), c"stereo".as_ptr(), ptr::null()];
+        assert_eq!(unsafe { read_features(list.as_ptr()) }, features(&["instrument", "stereo"]));
+        assert!(unsafe { read_features(ptr::null()) }.is_empty());
+    }
+
+    #[test]
+    fn test_note_events_share_note_id() {
+        let mut context = MidiEventContext::new();
+        context.add_note_on(60, 100, 2, 0);
+        context.add_note_on(60, 100, 3, 0);
+        context.add_note_off(60, 64, 2, 0);
+
+        let [on, other, off] = &context.note_events[..] else {
+            panic!("expected three note events");
+        };
+        assert_eq!(on.note_id, off.note_id);
+        assert_ne!(on.note_id, other.note_id);
+        assert!(on.note_id >= 0);
+        assert!((off.velocity - 64.0 / 127.0).abs() < 1e-9);
+    }
+
+    /// Identifying fields of a note event as seen by the mock plugin: (type, note id, port, channel, key)
+    type ReceivedNote = (u16, i32, i16, i16, i16);
+
+    thread_local! {
+        static RECEIVED_NOTES: std::cell::RefCell<Vec<ReceivedNote>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// Mock process that records every note event it is handed, reading them the way a plugin does
+    unsafe extern "C" fn mock_record_notes(_plugin: *const ClapPlugin, process: *const ClapProcess) -> i32 {
+        let events = &*(*process).in_events;
+        let count = events.size.unwrap()(events);
+        for index in 0..count {
+            let header = events.get.unwrap()(events, index);
+            let type_ = (*header).type_;
+            if type_ == CLAP_EVENT_NOTE_ON || type_ == CLAP_EVENT_NOTE_OFF {
+                let note = &*(header as *const ClapEventNote);
+                RECEIVED_NOTES.with(|received| {
+                    received.borrow_mut().push((type_, note.note_id, note.port_index, note.channel, note.key))
+                });
+            }
+        }
+        CLAP_PROCESS_CONTINUE
+    }
+
+    #[test]
+    fn test_every_note_on_is_released_by_matching_note_off() {
+        let events = [
+            MidiEvent::note_on(60, 100),
+            MidiEvent::NoteOn { note: 36, velocity: 90, channel: 9 },
+            MidiEvent::note_on(64, 100),
+            MidiEvent::note_off_with_velocity(60, 80),
+            // Out-of-range channel/key must be clamped the same way on and off
+            MidiEvent::NoteOn { note: 200, velocity: 100, channel: 17 },
+            MidiEvent::NoteOff { note: 200, velocity: 0, channel: 17 },
+            MidiEvent::NoteOff { note: 36, velocity: 0, channel: 9 },
+            // Releases the still-held 64
+            MidiEvent::AllNotesOff,
+        ];
+        let mut context = MidiEventContext::new();
+        fill_midi_context(&mut context, &events);
+
+        let input_events = ClapInputEvents {
+            ctx: &context as *const MidiEventContext as *mut c_void,
+            size: Some(midi_input_events_size),
+            get: Some(midi_input_events_get),
+        };
+        let output_events = ClapOutputEvents {
+            ctx: ptr::null_mut(),
+            try_push: Some(empty_output_events_push),
+        };
+        let process = ClapProcess {
+            steady_time: -1,
+            frames_count: 0,
+            transport: ptr::null(),
+            audio_inputs: ptr::null(),
+            audio_outputs: ptr::null_mut(),
+            audio_inputs_count: 0,
+            audio_outputs_count: 0,
+            in_events: &input_events,
+            out_events: &output_events,
+        };
+        let ports = MockPorts { main_flags: 0, input_count: 0, main_channels: 2 };
+        let plugin = ClapPlugin { process: Some(mock_record_notes), ..mock_plugin(&ports) };
+        unsafe { plugin.process.unwrap()(&plugin, &process) };
+
+        let received = RECEIVED_NOTES.with(|received| received.take());
+        let identity = |note: &ReceivedNote| (note.1, note.2, note.3, note.4);
+        let ons: Vec<(usize, &ReceivedNote)> =
+            received.iter().enumerate().filter(|(_, note)| note.0 == CLAP_EVENT_NOTE_ON).collect();
+        assert_eq!(ons.len(), 4);
+        assert!(ons.iter().any(|(_, on)| on.3 == 1 && on.4 == 72));
+
+        for (position, on) in ons {
+            assert!(on.1 >= 0, "note on {:?} has no note id", on);
+            let released = received[position + 1..]
+                .iter()
+                .any(|off| off.0 == CLAP_EVENT_NOTE_OFF && identity(off) == identity(on));
+            assert!(released, "note on {:?} has no matching note off", on);
+        }
+    }
+
+    thread_local! {
+        static RECEIVED_MIDI: std::cell::RefCell<Vec<[u8; 3]>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// Mock process that records the bytes of every raw MIDI event it is handed
+    unsafe extern "C" fn mock_record_midi(_plugin: *const ClapPlugin, process: *const ClapProcess) -> i32 {
+        let events = &*(*process).in_events;
+        let count = events.size.unwrap()(events);
+        for index in 0..count {
+            let header = events.get.unwrap()(events, index);
+            if (*header).type_ == CLAP_EVENT_MIDI {
+                let midi = &*(header as *const ClapEventMidi);
+                RECEIVED_MIDI.with(|received| received.borrow_mut().push(midi.data));
+            }
+        }
+        CLAP_PROCESS_CONTINUE
+    }
+
+    #[test]
+    fn test_sustain_cc_reaches_process_context() {
+        let queue = MidiEventQueue::new(64);
+        assert!(queue.note_on(60, 100));
+        assert!(queue.control_change(64, 127, 0));
+        assert!(queue.pitch_bend(12000, 3));
+        assert!(queue.control_change(64, 0, 0));
+
+        let mut drained = Vec::with_capacity(64);
+        queue.drain_into(&mut drained);
+        let mut context = MidiEventContext::new();
+        fill_midi_context(&mut context, &drained);
+
+        let input_events = ClapInputEvents {
+            ctx: &context as *const MidiEventContext as *mut c_void,
+            size: Some(midi_input_events_size),
+            get: Some(midi_input_events_get),
+        };
+        let output_events = ClapOutputEvents {
+            ctx: ptr::null_mut(),
+            try_push: Some(empty_output_events_push),
+        };
+        let process = ClapProcess {
+            steady_time: -1,
+            frames_count: 0,
+            transport: ptr::null(),
+            audio_inputs: ptr::null(),
+            audio_outputs: ptr::null_mut(),
+            audio_inputs_count: 0,
+            audio_outputs_count: 0,
+            in_events: &input_events,
+            out_events: &output_events,
+        };
+        let ports = MockPorts { main_flags: 0, input_count: 0, main_channels: 2 };
+        let plugin = ClapPlugin { process: Some(mock_record_midi), ..mock_plugin(&ports) };
+        unsafe { plugin.process.unwrap()(&plugin, &process) };
+
+        // Sustain down, bend on channel 3 (12000 = LSB 0x60, MSB 0x5D), sustain up
+        let received = RECEIVED_MIDI.with(|received| received.take());
+        assert_eq!(received, vec![[0xB0, 64, 127], [0xE3, 0x60, 0x5D], [0xB0, 64, 0]]);
+        assert_eq!(context.note_events.len(), 1);
+    }
+}
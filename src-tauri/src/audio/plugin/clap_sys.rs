@@ -257,6 +257,18 @@ pub struct ClapEventMidi {
     pub data: [u8; 3],
 }
 
+#[repr(C)]
+pub struct ClapEventParamValue {
+    pub header: ClapEventHeader,
+    pub param_id: u32,
+    pub cookie: *mut c_void,
+    pub note_id: i32,
+    pub port_index: i16,
+    pub channel: i16,
+    pub key: i16,
+    pub value: f64,
+}
+
 // =============================================================================
 // Null implementations for input/output events (empty event lists)
 // =============================================================================
@@ -286,6 +298,13 @@ pub unsafe extern "C" fn empty_output_events_push(
 // MIDI Event Context for Input Events
 // =============================================================================
 
+/// Note id for a channel/key pair. Note on and note off derive it the same way,
+/// so plugins that track voices by note id release the voice the note on started.
+#[inline]
+pub fn note_id(channel: u8, key: u8) -> i32 {
+    (channel & 0x0F) as i32 * 128 + (key & 0x7F) as i32
+}
+
 /// Context structure for passing MIDI events to the plugin
 /// This is stored and passed via the ClapInputEvents ctx field
 pub struct MidiEventContext {
@@ -293,13 +312,16 @@ pub struct MidiEventContext {
     pub note_events: Vec<ClapEventNote>,
     /// Pre-allocated storage for raw MIDI events (CC, pitch bend, etc.)
     pub midi_events: Vec<ClapEventMidi>,
+    /// Pre-allocated storage for parameter value events (sorted by time)
+    pub param_events: Vec<ClapEventParamValue>,
 }
 
 impl MidiEventContext {
     pub fn new() -> Self {
         Self {
             note_events: Vec::with_capacity(64), // Pre-allocate for typical use
-            midi_events: Vec::with_capacity(32), // CC and pitch bend
+            midi_events: Vec::with_capacity(256), // CC and pitch bend arrive in bursts (wheel sweeps)
+            param_events: Vec::with_capacity(64), // Automation and parameter changes
         }
     }
 
@@ -307,46 +329,42 @@ impl MidiEventContext {
     pub fn clear(&mut self) {
         self.note_events.clear();
         self.midi_events.clear();
+        self.param_events.clear();
     }
 
     /// Get total event count (for callback)
     pub fn len(&self) -> usize {
-        self.note_events.len() + self.midi_events.len()
+        self.note_events.len() + self.midi_events.len() + self.param_events.len()
     }
 
     /// Add a note on event
     pub fn add_note_on(&mut self, note: u8, velocity: u8, channel: u8, time: u32) {
-        self.note_events.push(ClapEventNote {
-            header: ClapEventHeader {
-                size: std::mem::size_of::<ClapEventNote>() as u32,
-                time,
-                space_id: 0, // CLAP_CORE_EVENT_SPACE_ID
-                type_: CLAP_EVENT_NOTE_ON,
-                flags: 0,
-            },
-            note_id: -1, // No specific note ID
-            port_index: 0,
-            channel: channel as i16,
-            key: note as i16,
-            velocity: velocity as f64 / 127.0, // CLAP uses 0.0-1.0
-        });
+        self.add_note(CLAP_EVENT_NOTE_ON, note, velocity, channel, time);
     }
 
     /// Add a note off event
     pub fn add_note_off(&mut self, note: u8, velocity: u8, channel: u8, time: u32) {
+        self.add_note(CLAP_EVENT_NOTE_OFF, note, velocity, channel, time);
+    }
+
+    /// Note on and note off share this so their identifying fields (port, channel, key,
+    /// note id) are always built the same way - a mismatch leaves the voice stuck
+    fn add_note(&mut self, type_: u16, note: u8, velocity: u8, channel: u8, time: u32) {
+        let channel = channel & 0x0F;
+        let key = note & 0x7F;
         self.note_events.push(ClapEventNote {
             header: ClapEventHeader {
                 size: std::mem::size_of::<ClapEventNote>() as u32,
                 time,
-                space_id: 0,
-                type_: CLAP_EVENT_NOTE_OFF,
+                space_id: 0, // CLAP_CORE_EVENT_SPACE_ID
+                type_,
                 flags: 0,
             },
-            note_id: -1,
+            note_id: note_id(channel, key),
             port_index: 0,
             channel: channel as i16,
-            key: note as i16,
-            velocity: velocity as f64 / 127.0,
+            key: key as i16,
+            velocity: (velocity & 0x7F) as f64 / 127.0, // CLAP uses 0.0-1.0
         });
     }
 
@@ -383,6 +401,27 @@ impl MidiEventContext {
             data: [0xE0 | (channel & 0x0F), lsb, msb],
         });
     }
+
+    /// Add a parameter value event (must be added in time order)
+    pub fn add_param_value(&mut self, param_id: u32, value: f64, time: u32) {
+        self.param_events.push(ClapEventParamValue {
+            header: ClapEventHeader {
+                size: std::mem::size_of::<ClapEventParamValue>() as u32,
+                time,
+                space_id: 0,
+                type_: CLAP_EVENT_PARAM_VALUE,
+                flags: 0,
+            },
+            param_id,
+            cookie: std::ptr::null_mut(),
+            // -1 = applies to all notes/ports/channels (not polyphonic)
+            note_id: -1,
+            port_index: -1,
+            channel: -1,
+            key: -1,
+            value,
+        });
+    }
 }
 
 /// Callback: return number of events in the context
@@ -395,7 +434,8 @@ pub unsafe extern "C" fn midi_input_events_size(list: *const ClapInputEvents) ->
 }
 
 /// Callback: return event at index from the context
-/// Events are indexed: note_events first, then midi_events
+/// Events are indexed: note_events first, then midi_events, then param_events
+/// (note and MIDI events are all at time 0, param events are time-sorted)
 pub unsafe extern "C" fn midi_input_events_get(
     list: *const ClapInputEvents,
     index: u32,
@@ -406,13 +446,17 @@ pub unsafe extern "C" fn midi_input_events_get(
     }
     let idx = index as usize;
     let note_count = (*ctx).note_events.len();
+    let midi_count = (*ctx).midi_events.len();
 
     if idx < note_count {
         // Return note event
         &(&(*ctx).note_events)[idx].header as *const ClapEventHeader
-    } else if idx < note_count + (*ctx).midi_events.len() {
+    } else if idx < note_count + midi_count {
         // Return MIDI event (CC, pitch bend)
         &(&(*ctx).midi_events)[idx - note_count].header as *const ClapEventHeader
+    } else if idx < note_count + midi_count + (*ctx).param_events.len() {
+        // Return parameter value event
+        &(&(*ctx).param_events)[idx - note_count - midi_count].header as *const ClapEventHeader
     } else {
         std::ptr::null()
     }
@@ -680,3 +724,99 @@ pub struct ClapParamInfo {
     pub max_value: f64,
     pub default_value: f64,
 }
+
+/// Parameter flags (ClapParamInfo::flags)
+pub const CLAP_PARAM_IS_STEPPED: u32 = 1 << 0;
+pub const CLAP_PARAM_IS_PERIODIC: u32 = 1 << 1;
+pub const CLAP_PARAM_IS_HIDDEN: u32 = 1 << 2;
+pub const CLAP_PARAM_IS_READONLY: u32 = 1 << 3;
+pub const CLAP_PARAM_IS_BYPASS: u32 = 1 << 4;
+pub const CLAP_PARAM_IS_AUTOMATABLE: u32 = 1 << 5;
+pub const CLAP_PARAM_IS_MODULATABLE: u32 = 1 << 10;
+pub const CLAP_PARAM_REQUIRES_PROCESS: u32 = 1 << 15;
+pub const CLAP_PARAM_IS_ENUM: u32 = 1 << 16;
+
+// =============================================================================
+// Latency Extension
+// =============================================================================
+
+pub const CLAP_EXT_LATENCY: &[u8] = b"clap.latency\0";
+
+/// Plugin-side latency extension
+#[repr(C)]
+pub struct ClapPluginLatency {
+    /// Returns the plugin latency in samples (main thread, plugin must be activated)
+    pub get: Option<unsafe extern "C" fn(plugin: *const ClapPlugin) -> u32>,
+}
+
+// =============================================================================
+// Note Name Extension
+// =============================================================================
+
+pub const CLAP_EXT_NOTE_NAME: &[u8] = b"clap.note-name\0";
+
+/// A named note (-1 in port/key/channel means "any")
+#[repr(C)]
+pub struct ClapNoteName {
+    pub name: [c_char; 256],
+    pub port: i16,
+    pub key: i16,
+    pub channel: i16,
+}
+
+/// Plugin-side note name extension
+#[repr(C)]
+pub struct ClapPluginNoteName {
+    /// Returns the number of note names
+    pub count: Option<unsafe extern "C" fn(plugin: *const ClapPlugin) -> u32>,
+    /// Gets a note name by index
+    pub get: Option<
+        unsafe extern "C" fn(plugin: *const ClapPlugin, index: u32, note_name: *mut ClapNoteName) -> bool,
+    >,
+}
+
+/// Host-side note name extension - called by the plugin when its note names change
+#[repr(C)]
+pub struct ClapHostNoteName {
+    pub changed: Option<unsafe extern "C" fn(host: *const ClapHost)>,
+}
+
+// =============================================================================
+// Audio Ports Extension
+// =============================================================================
+
+pub const CLAP_EXT_AUDIO_PORTS: &[u8] = b"clap.audio-ports\0";
+
+/// The port is the main audio input or output
+pub const CLAP_AUDIO_PORT_IS_MAIN: u32 = 1 << 0;
+/// The port can process 64-bit audio
+pub const CLAP_AUDIO_PORT_SUPPORTS_64BITS: u32 = 1 << 1;
+/// The port prefers 64-bit audio (only meaningful with SUPPORTS_64BITS)
+pub const CLAP_AUDIO_PORT_PREFERS_64BITS: u32 = 1 << 2;
+
+/// Description of one audio input or output port
+#[repr(C)]
+pub struct ClapAudioPortInfo {
+    pub id: u32,
+    pub name: [c_char; 256],
+    pub flags: u32,
+    pub channel_count: u32,
+    pub port_type: *const c_char,
+    pub in_place_pair: u32,
+}
+
+/// Plugin-side audio ports extension (main thread, plugin must be deactivated)
+#[repr(C)]
+pub struct ClapPluginAudioPorts {
+    /// Returns the number of input or output ports
+    pub count: Option<unsafe extern "C" fn(plugin: *const ClapPlugin, is_input: bool) -> u32>,
+    /// Gets a port's info by index
+    pub get: Option<
+        unsafe extern "C" fn(
+            plugin: *const ClapPlugin,
+            index: u32,
+            is_input: bool,
+            info: *mut ClapAudioPortInfo,
+        ) -> bool,
+    >,
+}
@@ -65,13 +65,14 @@ pub unsafe fn get_gui_size(plugin: *const ClapPlugin) -> Option<(u32, u32)> {
 #[cfg(target_os = "macos")]
 mod macos {
     use super::*;
+    use objc2::encode::{Encoding, RefEncode};
     use objc2::rc::{autoreleasepool, Retained};
-    use objc2::{MainThreadMarker, MainThreadOnly};
+    use objc2::{msg_send, AnyThread, MainThreadMarker, MainThreadOnly};
     use objc2_app_kit::{
-        NSApplication, NSApplicationActivationPolicy, NSBackingStoreType, NSColor, NSWindow,
-        NSWindowStyleMask,
+        NSApplication, NSApplicationActivationPolicy, NSBackingStoreType, NSBitmapImageFileType,
+        NSBitmapImageRep, NSColor, NSWindow, NSWindowStyleMask,
     };
-    use objc2_foundation::{NSPoint, NSRect, NSSize, NSThread};
+    use objc2_foundation::{NSDictionary, NSPoint, NSRect, NSSize, NSThread};
 
     // FFI bindings for Grand Central Dispatch
     #[repr(C)]
@@ -90,6 +91,36 @@ mod macos {
         );
     }
 
+    // FFI bindings for CoreGraphics window capture
+    #[repr(C)]
+    struct CGImage {
+        _private: [u8; 0],
+    }
+
+    // Lets a CGImageRef be passed through msg_send! with the encoding AppKit expects
+    unsafe impl RefEncode for CGImage {
+        const ENCODING_REF: Encoding = Encoding::Pointer(&Encoding::Struct("CGImage", &[]));
+    }
+
+    /// kCGWindowListOptionIncludingWindow
+    const CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
+    /// kCGWindowImageBoundsIgnoreFraming (leave out the window shadow)
+    const CG_WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING: u32 = 1 << 0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGWindowListCreateImage(
+            screen_bounds: NSRect,
+            list_option: u32,
+            window_id: u32,
+            image_option: u32,
+        ) -> *mut CGImage;
+        fn CGImageCreateWithImageInRect(image: *mut CGImage, rect: NSRect) -> *mut CGImage;
+        fn CGImageGetWidth(image: *mut CGImage) -> usize;
+        fn CGImageGetHeight(image: *mut CGImage) -> usize;
+        fn CGImageRelease(image: *mut CGImage);
+    }
+
     /// Get the main dispatch queue
     fn main_queue() -> DispatchQueueT {
         unsafe { &_dispatch_main_q as *const _ as DispatchQueueT }
@@ -112,6 +143,15 @@ mod macos {
         create_editor_window_at(plugin, title, None)
     }
 
+    /// Describe a caught panic payload for logging
+    pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string())
+    }
+
     /// Context for main thread dispatch
     struct EditorWindowContext {
         plugin: *const ClapPlugin,
@@ -131,7 +171,13 @@ mod macos {
 
             log::info!("create_editor_window_on_main: Running on main thread");
 
-            ctx.result = Some(unsafe { create_editor_window_inner(ctx.plugin, &ctx.title, ctx.position) });
+            // A panic must not unwind out of this extern "C" callback into GCD
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+                create_editor_window_inner(ctx.plugin, &ctx.title, ctx.position)
+            }));
+            ctx.result = Some(result.unwrap_or_else(|payload| {
+                Err(format!("Plugin editor panicked: {}", panic_message(&*payload)))
+            }));
 
             log::info!(
                 "create_editor_window_on_main: Complete (success={})",
@@ -527,6 +573,101 @@ mod macos {
         }
     }
 
+    /// Context for main thread window capture
+    struct CaptureContext {
+        window: *mut c_void,
+        result: Option<Result<Vec<u8>, String>>,
+    }
+
+    /// Callback for capturing a window on the main thread
+    extern "C" fn capture_window_on_main(ctx: *mut std::ffi::c_void) {
+        autoreleasepool(|_pool| {
+            let ctx = unsafe { &mut *(ctx as *mut CaptureContext) };
+            ctx.result = Some(unsafe { capture_window_inner(ctx.window) });
+        });
+    }
+
+    /// Capture a window's content area (without the title bar) as PNG data
+    /// This function dispatches to the main thread if needed.
+    pub fn capture_window_png(window: *mut c_void) -> Result<Vec<u8>, String> {
+        if window.is_null() {
+            return Err("Editor window is not open".to_string());
+        }
+
+        if is_main_thread() {
+            autoreleasepool(|_pool| unsafe { capture_window_inner(window) })
+        } else {
+            let mut ctx = CaptureContext {
+                window,
+                result: None,
+            };
+
+            unsafe {
+                dispatch_sync_f(
+                    main_queue(),
+                    &mut ctx as *mut CaptureContext as *mut std::ffi::c_void,
+                    capture_window_on_main,
+                );
+            }
+
+            ctx.result.unwrap_or_else(|| Err("Main thread dispatch failed".to_string()))
+        }
+    }
+
+    /// Inner implementation of capture_window_png (called on main thread)
+    ///
+    /// Uses the window server's copy of the window, so layer-backed content such as
+    /// a WKWebView is captured too (drawing the view hierarchy would leave it blank).
+    unsafe fn capture_window_inner(window: *mut c_void) -> Result<Vec<u8>, String> {
+        let window_ref = &*(window as *const NSWindow);
+        let window_id = window_ref.windowNumber();
+        if window_id <= 0 {
+            return Err("Editor window has no window server ID".to_string());
+        }
+
+        // CGRectNull: use the window's own bounds
+        let null_rect = NSRect::new(
+            NSPoint::new(f64::INFINITY, f64::INFINITY),
+            NSSize::new(0.0, 0.0),
+        );
+        let full = CGWindowListCreateImage(
+            null_rect,
+            CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW,
+            window_id as u32,
+            CG_WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING,
+        );
+        if full.is_null() {
+            return Err("Failed to capture editor window".to_string());
+        }
+
+        // Crop off the title bar so only the plugin's UI is kept (image origin is top-left)
+        let frame = window_ref.frame();
+        let content = window_ref.contentRectForFrameRect(frame);
+        let title_bar = ((frame.size.height - content.size.height) * window_ref.backingScaleFactor()).round();
+        let width = CGImageGetWidth(full) as f64;
+        let height = CGImageGetHeight(full) as f64;
+        let crop = NSRect::new(
+            NSPoint::new(0.0, title_bar),
+            NSSize::new(width, (height - title_bar).max(1.0)),
+        );
+        let image = CGImageCreateWithImageInRect(full, crop);
+        CGImageRelease(full);
+        if image.is_null() {
+            return Err("Failed to crop editor capture".to_string());
+        }
+
+        // The bitmap rep keeps its own reference to the image
+        let rep: Option<Retained<NSBitmapImageRep>> =
+            msg_send![NSBitmapImageRep::alloc(), initWithCGImage: image];
+        CGImageRelease(image);
+        let rep = rep.ok_or_else(|| "Failed to read editor capture".to_string())?;
+
+        let png = rep
+            .representationUsingType_properties(NSBitmapImageFileType::PNG, &NSDictionary::new())
+            .ok_or_else(|| "Failed to encode editor capture as PNG".to_string())?;
+        Ok(png.to_vec())
+    }
+
     /// Context for deminiaturizing a window
     struct DeminiaturizeContext {
         window: *mut c_void,
@@ -613,3 +754,8 @@ pub fn is_window_visible(_window: *mut c_void) -> bool {
 
 #[cfg(not(target_os = "macos"))]
 pub fn restore_window(_window: *mut c_void) {}
+
+#[cfg(not(target_os = "macos"))]
+pub fn capture_window_png(_window: *mut c_void) -> Result<Vec<u8>, String> {
+    Err("GUI not implemented for this platform".to_string())
+}
@@ -0,0 +1,265 @@
+//! A loaded plugin of either format
+//!
+//! The engine holds a HostedPlugin and doesn't care which format is behind it. Features a
+//! format doesn't support yet (the VST3 editor, parameters and note names) report as absent.
+
+use super::clap_host::{AudioPortInfo, InputMapping, NoteNames, PluginInstance, PluginParam};
+use super::vst3_host::Vst3Instance;
+use crate::audio::midi::MidiEventQueue;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Instances are boxed: both are large and only one is loaded at a time
+pub enum HostedPlugin {
+    Clap(Box<PluginInstance>),
+    Vst3(Box<Vst3Instance>),
+}
+
+impl HostedPlugin {
+    /// Load a plugin bundle: .vst3 bundles as VST3, anything else as CLAP
+    pub fn load(path: &Path, sample_rate: f64, max_frames: u32) -> Result<Self, String> {
+        let is_vst3 = path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("vst3"))
+            .unwrap_or(false);
+        if is_vst3 {
+            Vst3Instance::load(path, sample_rate, max_frames).map(|plugin| Self::Vst3(Box::new(plugin)))
+        } else {
+            PluginInstance::load(path, sample_rate, max_frames).map(|plugin| Self::Clap(Box::new(plugin)))
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Clap(plugin) => &plugin.name,
+            Self::Vst3(plugin) => &plugin.name,
+        }
+    }
+
+    pub fn is_instrument(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.is_instrument,
+            Self::Vst3(plugin) => plugin.is_instrument,
+        }
+    }
+
+    pub fn start_processing(&mut self) -> Result<(), String> {
+        match self {
+            Self::Clap(plugin) => plugin.start_processing(),
+            Self::Vst3(plugin) => plugin.start_processing(),
+        }
+    }
+
+    pub fn stop_processing(&mut self) {
+        match self {
+            Self::Clap(plugin) => plugin.stop_processing(),
+            Self::Vst3(plugin) => plugin.stop_processing(),
+        }
+    }
+
+    pub fn process(&mut self, input: &[f32], output: &mut [f32]) -> Result<(), String> {
+        match self {
+            Self::Clap(plugin) => plugin.process(input, output),
+            Self::Vst3(plugin) => plugin.process(input, output),
+        }
+    }
+
+    /// Process with a sidechain signal (ignored by plugins without a sidechain input)
+    pub fn process_with_sidechain(
+        &mut self,
+        input: &[f32],
+        sidechain: Option<&[f32]>,
+        output: &mut [f32],
+    ) -> Result<(), String> {
+        match self {
+            Self::Clap(plugin) => plugin.process_with_sidechain(input, sidechain, output),
+            Self::Vst3(plugin) => plugin.process(input, output),
+        }
+    }
+
+    pub fn has_sidechain_input(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.has_sidechain_input(),
+            Self::Vst3(plugin) => plugin.has_sidechain_input(),
+        }
+    }
+
+    pub fn audio_ports(&self) -> &[AudioPortInfo] {
+        match self {
+            Self::Clap(plugin) => plugin.audio_ports(),
+            Self::Vst3(plugin) => plugin.audio_ports(),
+        }
+    }
+
+    pub fn set_input_mapping(&mut self, mapping: InputMapping) {
+        match self {
+            Self::Clap(plugin) => plugin.set_input_mapping(mapping),
+            Self::Vst3(plugin) => plugin.set_input_mapping(mapping),
+        }
+    }
+
+    pub fn latency(&self) -> u32 {
+        match self {
+            Self::Clap(plugin) => plugin.latency(),
+            Self::Vst3(plugin) => plugin.latency(),
+        }
+    }
+
+    pub fn midi_queue(&self) -> Arc<MidiEventQueue> {
+        match self {
+            Self::Clap(plugin) => plugin.midi_queue(),
+            Self::Vst3(plugin) => plugin.midi_queue(),
+        }
+    }
+
+    pub fn has_crashed(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.has_crashed(),
+            Self::Vst3(plugin) => plugin.has_crashed(),
+        }
+    }
+
+    pub fn has_process_error(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.has_process_error(),
+            Self::Vst3(plugin) => plugin.has_process_error(),
+        }
+    }
+
+    // State
+
+    pub fn save_state(&self) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Clap(plugin) => plugin.save_state(),
+            Self::Vst3(plugin) => plugin.save_state(),
+        }
+    }
+
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        match self {
+            Self::Clap(plugin) => plugin.load_state(data),
+            Self::Vst3(plugin) => plugin.load_state(data),
+        }
+    }
+
+    pub fn queue_state(&self, state: Vec<u8>) -> Result<(), String> {
+        match self {
+            Self::Clap(plugin) => plugin.queue_state(state),
+            Self::Vst3(plugin) => plugin.queue_state(state),
+        }
+    }
+
+    pub fn apply_pending_state(&mut self) {
+        match self {
+            Self::Clap(plugin) => plugin.apply_pending_state(),
+            Self::Vst3(plugin) => plugin.apply_pending_state(),
+        }
+    }
+
+    /// Update the plugin's editor side after apply_pending_state (main thread; only VST3
+    /// splits its state between the audio and main threads)
+    pub fn sync_controller_state(&self) {
+        match self {
+            Self::Clap(_) => {}
+            Self::Vst3(plugin) => plugin.sync_controller_state(),
+        }
+    }
+
+    pub fn get_state_apply_count(&self) -> u32 {
+        match self {
+            Self::Clap(plugin) => plugin.get_state_apply_count(),
+            Self::Vst3(plugin) => plugin.get_state_apply_count(),
+        }
+    }
+
+    pub fn get_state_failure_count(&self) -> u32 {
+        match self {
+            Self::Clap(plugin) => plugin.get_state_failure_count(),
+            Self::Vst3(plugin) => plugin.get_state_failure_count(),
+        }
+    }
+
+    // Parameters (CLAP only for now)
+
+    pub fn get_params(&self) -> Vec<PluginParam> {
+        match self {
+            Self::Clap(plugin) => plugin.get_params(),
+            Self::Vst3(_) => Vec::new(),
+        }
+    }
+
+    pub fn queue_param_value(&mut self, param_id: u32, value: f64, sample_offset: u32) {
+        if let Self::Clap(plugin) = self {
+            plugin.queue_param_value(param_id, value, sample_offset);
+        }
+    }
+
+    pub fn flush_params(&self) {
+        if let Self::Clap(plugin) = self {
+            plugin.flush_params();
+        }
+    }
+
+    pub fn call_on_main_thread(&self) {
+        if let Self::Clap(plugin) = self {
+            plugin.call_on_main_thread();
+        }
+    }
+
+    pub fn get_note_names(&self) -> NoteNames {
+        match self {
+            Self::Clap(plugin) => plugin.get_note_names(),
+            Self::Vst3(_) => Vec::new(),
+        }
+    }
+
+    // Editor (CLAP only for now)
+
+    pub fn has_gui(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.has_gui(),
+            Self::Vst3(_) => false,
+        }
+    }
+
+    pub fn is_editor_open(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.is_editor_open(),
+            Self::Vst3(_) => false,
+        }
+    }
+
+    pub fn is_editor_window_visible(&self) -> bool {
+        match self {
+            Self::Clap(plugin) => plugin.is_editor_window_visible(),
+            Self::Vst3(_) => false,
+        }
+    }
+
+    pub fn get_editor_position(&self) -> Option<(f64, f64)> {
+        match self {
+            Self::Clap(plugin) => plugin.get_editor_position(),
+            Self::Vst3(_) => None,
+        }
+    }
+
+    pub fn open_editor_at(&mut self, position: Option<(f64, f64)>) -> Result<(), String> {
+        match self {
+            Self::Clap(plugin) => plugin.open_editor_at(position),
+            Self::Vst3(_) => Err("Editors of VST3 plugins aren't supported yet".to_string()),
+        }
+    }
+
+    pub fn close_editor(&mut self) {
+        if let Self::Clap(plugin) = self {
+            plugin.close_editor();
+        }
+    }
+
+    pub fn capture_editor_png(&self) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Clap(plugin) => plugin.capture_editor_png(),
+            Self::Vst3(_) => Err("Editors of VST3 plugins aren't supported yet".to_string()),
+        }
+    }
+}
@@ -1,15 +1,19 @@
-//! CLAP Plugin Hosting
+//! CLAP and VST3 Plugin Hosting
 //!
-//! Provides hot-reloadable CLAP plugin hosting for the preview system.
-//! - Load .clap bundles and process audio through them
+//! Provides hot-reloadable plugin hosting for the preview system.
+//! - Load .clap and .vst3 bundles and process audio through them
 //! - Open plugin's native GUI in a standalone window
 //! - Watch for file changes and reload with crossfade
 
+pub mod automation;
 pub mod clap_host;
 pub mod clap_sys;
 pub mod crash_guard;
 pub mod editor;
 pub mod file_watcher;
+pub mod hosted;
+pub mod vst3_host;
+pub mod vst3_sys;
 
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -18,6 +22,8 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 pub use clap_host::{cleanup_temp_bundles, PluginInstance};
+pub use hosted::HostedPlugin;
+pub use vst3_host::Vst3Instance;
 
 /// Plugin type determines audio routing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,6 +54,8 @@ pub enum PluginState {
         name: String,
         path: String,
         has_editor: bool,
+        /// Detected from the plugin's features and audio ports at load time
+        is_instrument: bool,
     },
     /// Plugin failed to load
     Error { message: String },
@@ -0,0 +1,1542 @@
+//! VST3 Plugin Hosting
+//!
+//! Loads a .vst3 bundle and runs its audio processor on the engine's stereo bus, the same
+//! way PluginInstance does for CLAP. Supports stereo processing and state save/restore;
+//! editors, parameters and note input are CLAP-only for now.
+
+use super::clap_host::{fixed_c_string, AudioPortInfo, InputMapping, PluginInstance, PortBuffers};
+use super::vst3_sys::*;
+use crate::audio::midi::{MidiEvent, MidiEventQueue};
+use libloading::{Library, Symbol};
+use parking_lot::Mutex;
+use ringbuf::{traits::*, HeapRb};
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Controller updates the audio thread can hand back before the main thread syncs them
+/// (only the latest one is applied)
+const CONTROLLER_STATE_SLOTS: usize = 4;
+
+/// Saved state starts with this, then the component state length (u32 LE), the component
+/// state and the edit controller state
+const STATE_MAGIC: &[u8; 4] = b"VST3";
+
+const HOST_NAME: &str = "freqlab";
+
+/// Module exit function (Linux ModuleExit, macOS bundleExit, Windows ExitDll)
+type ModuleExitFn = unsafe extern "system" fn() -> bool;
+
+/// Interface vtable of a COM object
+unsafe fn vtbl<'a, T>(object: *mut c_void) -> &'a T {
+    &**(object as *const *const T)
+}
+
+/// Query an interface, returning a new reference to it
+unsafe fn query_interface(object: *mut c_void, iid: &Tuid) -> Option<*mut c_void> {
+    let mut result = ptr::null_mut();
+    let status = (vtbl::<FUnknownVtbl>(object).query_interface)(object, iid, &mut result);
+    (status == K_RESULT_OK && !result.is_null()).then_some(result)
+}
+
+unsafe fn release(object: *mut c_void) {
+    (vtbl::<FUnknownVtbl>(object).release)(object);
+}
+
+/// Decode a NUL-terminated UTF-16 VST3 string
+fn string128(buffer: &String128) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// A loaded VST3 binary. Dropping it exits the module, unloads the library and removes the
+/// temp bundle, so it has to outlive every object the plugin created.
+struct Vst3Module {
+    library: Option<Library>,
+    exit: Option<ModuleExitFn>,
+    /// CFBundleRef passed to bundleEntry
+    #[cfg(target_os = "macos")]
+    cf_bundle: *const c_void,
+    temp_bundle_path: Option<PathBuf>,
+}
+
+impl Vst3Module {
+    /// Load the binary and call the platform's module entry function
+    fn load(binary_path: &Path, bundle_path: &Path, temp_bundle_path: Option<PathBuf>) -> Result<Self, String> {
+        let library = unsafe { Library::new(binary_path).map_err(|e| format!("Failed to load library: {}", e))? };
+        let mut module = Self {
+            library: None,
+            exit: None,
+            #[cfg(target_os = "macos")]
+            cf_bundle: ptr::null(),
+            temp_bundle_path,
+        };
+
+        let entered = unsafe { module.enter(&library, binary_path, bundle_path) };
+        module.library = Some(library);
+        if !entered {
+            return Err("Plugin module entry function returned false".to_string());
+        }
+        Ok(module)
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn enter(&mut self, library: &Library, binary_path: &Path, _bundle_path: &Path) -> bool {
+        type ModuleEntryFn = unsafe extern "system" fn(handle: *mut c_void) -> bool;
+        let Ok(entry) = library.get::<ModuleEntryFn>(b"ModuleEntry\0") else {
+            return true;
+        };
+        self.exit = library.get::<ModuleExitFn>(b"ModuleExit\0").ok().map(|f| *f);
+        // ModuleEntry wants the dlopen handle - this returns the one already open
+        let Ok(path) = std::ffi::CString::new(binary_path.to_string_lossy().as_bytes()) else {
+            return false;
+        };
+        let handle = libc::dlopen(path.as_ptr(), libc::RTLD_LAZY | libc::RTLD_NOLOAD);
+        let entered = entry(handle);
+        if !handle.is_null() {
+            libc::dlclose(handle);
+        }
+        entered
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn enter(&mut self, library: &Library, _binary_path: &Path, bundle_path: &Path) -> bool {
+        type BundleEntryFn = unsafe extern "system" fn(bundle: *const c_void) -> bool;
+        let Ok(entry) = library.get::<BundleEntryFn>(b"bundleEntry\0") else {
+            return true;
+        };
+        self.exit = library.get::<ModuleExitFn>(b"bundleExit\0").ok().map(|f| *f);
+        self.cf_bundle = core_foundation::create_bundle(bundle_path);
+        entry(self.cf_bundle)
+    }
+
+    #[cfg(target_os = "windows")]
+    unsafe fn enter(&mut self, library: &Library, _binary_path: &Path, _bundle_path: &Path) -> bool {
+        let Ok(entry) = library.get::<unsafe extern "system" fn() -> bool>(b"InitDll\0") else {
+            return true;
+        };
+        self.exit = library.get::<ModuleExitFn>(b"ExitDll\0").ok().map(|f| *f);
+        entry()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    unsafe fn enter(&mut self, _library: &Library, _binary_path: &Path, _bundle_path: &Path) -> bool {
+        true
+    }
+
+    fn get_factory(&self) -> Result<*mut c_void, String> {
+        let library = self.library.as_ref().ok_or("Plugin library is not loaded")?;
+        let get_factory: Symbol<GetPluginFactoryFn> = unsafe {
+            library
+                .get(b"GetPluginFactory\0")
+                .map_err(|e| format!("No GetPluginFactory symbol found: {}", e))?
+        };
+        let factory = unsafe { get_factory() };
+        if factory.is_null() {
+            return Err("GetPluginFactory returned null".to_string());
+        }
+        Ok(factory)
+    }
+}
+
+impl Drop for Vst3Module {
+    fn drop(&mut self) {
+        if let Some(exit) = self.exit.take() {
+            unsafe { exit() };
+        }
+        #[cfg(target_os = "macos")]
+        if !self.cf_bundle.is_null() {
+            unsafe { core_foundation::release(self.cf_bundle) };
+        }
+
+        // Drop the library before deleting the temp bundle (see PluginInstance's Drop)
+        if let Some(library) = self.library.take() {
+            drop(library);
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        if let Some(temp_path) = self.temp_bundle_path.take() {
+            log::info!("Deleting temp bundle: {:?}", temp_path);
+            if let Err(e) = std::fs::remove_dir_all(&temp_path) {
+                log::warn!("Failed to delete temp bundle {:?}: {}", temp_path, e);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod core_foundation {
+    use std::ffi::c_void;
+    use std::path::Path;
+    use std::ptr;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFURLCreateFromFileSystemRepresentation(
+            allocator: *const c_void,
+            buffer: *const u8,
+            length: isize,
+            is_directory: u8,
+        ) -> *const c_void;
+        fn CFBundleCreate(allocator: *const c_void, url: *const c_void) -> *const c_void;
+        fn CFRelease(object: *const c_void);
+    }
+
+    /// CFBundleRef for a bundle directory (null on failure)
+    pub unsafe fn create_bundle(path: &Path) -> *const c_void {
+        let path = path.to_string_lossy();
+        let url = CFURLCreateFromFileSystemRepresentation(ptr::null(), path.as_ptr(), path.len() as isize, 1);
+        if url.is_null() {
+            return ptr::null();
+        }
+        let bundle = CFBundleCreate(ptr::null(), url);
+        CFRelease(url);
+        bundle
+    }
+
+    pub unsafe fn release(object: *const c_void) {
+        CFRelease(object);
+    }
+}
+
+/// A loaded VST3 plugin instance
+pub struct Vst3Instance {
+    /// IPluginFactory
+    factory: *mut c_void,
+    /// IComponent (initialized)
+    component: *mut c_void,
+    /// IAudioProcessor of the component
+    processor: *mut c_void,
+    /// IEditController (null if the plugin has none)
+    controller: *mut c_void,
+    /// Whether the controller is its own object (initialized and terminated separately)
+    separate_controller: bool,
+    /// Host context passed to initialize (must outlive the plugin objects)
+    host: Box<HostApplication>,
+
+    // Plugin info
+    pub name: String,
+    pub vendor: String,
+    pub version: String,
+    /// Detected from the "Instrument" subcategory or a missing audio input
+    pub is_instrument: bool,
+
+    // Audio state
+    max_frames: u32,
+    is_active: bool,
+    is_processing: bool,
+
+    // One buffer set per audio bus, the main bus first. Only the main buses carry audio.
+    input_buffers: Vec<PortBuffers>,
+    output_buffers: Vec<PortBuffers>,
+    input_bus_buffers: Vec<AudioBusBuffers>,
+    output_bus_buffers: Vec<AudioBusBuffers>,
+    input_mapping: InputMapping,
+    audio_ports: Vec<AudioPortInfo>,
+
+    // State queued from the main thread for the audio thread (see PluginInstance)
+    state_receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+    state_sender: std::sync::mpsc::Sender<Vec<u8>>,
+    state_apply_count: AtomicU32,
+    state_failure_count: AtomicU32,
+    // States whose component half the audio thread applied, for sync_controller_state to
+    // pass on to the edit controller (which must only be called from the main thread)
+    controller_state_producer: ringbuf::HeapProd<Vec<u8>>,
+    controller_state_consumer: Mutex<ringbuf::HeapCons<Vec<u8>>>,
+
+    /// Notes aren't delivered to VST3 plugins yet - the queue is drained so it doesn't fill up
+    midi_queue: Arc<MidiEventQueue>,
+    midi_drain_buffer: Vec<MidiEvent>,
+
+    // Safety (see PluginInstance)
+    crashed: bool,
+    process_failed: bool,
+
+    /// The binary the plugin lives in (dropped last)
+    module: Option<Vst3Module>,
+}
+
+// Safety: as for PluginInstance, the instance is only reached through the engine's RwLock
+unsafe impl Send for Vst3Instance {}
+unsafe impl Sync for Vst3Instance {}
+
+impl Vst3Instance {
+    /// Load the first audio processor class in a .vst3 bundle
+    pub fn load(bundle_path: &Path, sample_rate: f64, max_frames: u32) -> Result<Self, String> {
+        log::info!("Loading VST3 plugin from: {:?}", bundle_path);
+
+        // Bundles are copied to temp like CLAP bundles (single-file plugins are loaded in place)
+        let (actual_bundle_path, temp_bundle_path) = if bundle_path.is_dir() {
+            PluginInstance::copy_to_temp(bundle_path)?
+        } else {
+            (bundle_path.to_path_buf(), None)
+        };
+        let binary_path = Self::resolve_binary_path(&actual_bundle_path)?;
+        log::info!("Resolved VST3 binary path: {:?}", binary_path);
+
+        let module = Vst3Module::load(&binary_path, &actual_bundle_path, temp_bundle_path)?;
+        let factory = module.get_factory()?;
+        let mut instance = Self::from_factory(factory, sample_rate, max_frames)?;
+        instance.module = Some(module);
+
+        log::info!("VST3 plugin loaded and activated successfully");
+        Ok(instance)
+    }
+
+    /// Create, set up and activate the first audio processor class of a factory.
+    /// Takes over the factory reference (released on error too).
+    fn from_factory(factory: *mut c_void, sample_rate: f64, max_frames: u32) -> Result<Self, String> {
+        let (state_sender, state_receiver) = std::sync::mpsc::channel();
+        let (controller_state_producer, controller_state_consumer) = HeapRb::new(CONTROLLER_STATE_SLOTS).split();
+        let mut instance = Self {
+            factory,
+            component: ptr::null_mut(),
+            processor: ptr::null_mut(),
+            controller: ptr::null_mut(),
+            separate_controller: false,
+            host: Box::new(HostApplication { vtbl: &HOST_APPLICATION_VTBL }),
+            name: String::new(),
+            vendor: String::new(),
+            version: String::new(),
+            is_instrument: false,
+            max_frames,
+            is_active: false,
+            is_processing: false,
+            input_buffers: Vec::new(),
+            output_buffers: Vec::new(),
+            input_bus_buffers: Vec::new(),
+            output_bus_buffers: Vec::new(),
+            input_mapping: InputMapping::default(),
+            audio_ports: Vec::new(),
+            state_receiver,
+            state_sender,
+            state_apply_count: AtomicU32::new(0),
+            state_failure_count: AtomicU32::new(0),
+            controller_state_producer,
+            controller_state_consumer: Mutex::new(controller_state_consumer),
+            midi_queue: Arc::new(MidiEventQueue::new(1024)),
+            midi_drain_buffer: Vec::with_capacity(256),
+            crashed: false,
+            process_failed: false,
+            module: None,
+        };
+
+        let sub_categories = unsafe { instance.create_component()? };
+        unsafe { instance.create_controller() };
+        let input_bus_count = unsafe { instance.setup_buses() };
+
+        instance.is_instrument = sub_categories.split('|').any(|c| c == "Instrument") || input_bus_count == 0;
+        log::info!(
+            "Loading VST3 plugin: {} by {} (version: {}, categories: {}) as {}",
+            instance.name,
+            instance.vendor,
+            instance.version,
+            sub_categories,
+            if instance.is_instrument { "instrument" } else { "effect" }
+        );
+
+        unsafe { instance.activate(sample_rate)? };
+        Ok(instance)
+    }
+
+    /// Find the first audio processor class, create its component and initialize it.
+    /// Returns the class subcategories.
+    unsafe fn create_component(&mut self) -> Result<String, String> {
+        let factory = vtbl::<IPluginFactoryVtbl>(self.factory);
+
+        let mut factory_info: PFactoryInfo = std::mem::zeroed();
+        let factory_vendor = if (factory.get_factory_info)(self.factory, &mut factory_info) == K_RESULT_OK {
+            fixed_c_string(&mut factory_info.vendor)
+        } else {
+            String::new()
+        };
+
+        let class_count = (factory.count_classes)(self.factory);
+        let (index, mut class_info) = (0..class_count)
+            .find_map(|index| {
+                let mut info: PClassInfo = std::mem::zeroed();
+                let found = (factory.get_class_info)(self.factory, index, &mut info) == K_RESULT_OK
+                    && fixed_c_string(&mut info.category).as_bytes() == K_VST_AUDIO_EFFECT_CLASS;
+                found.then_some((index, info))
+            })
+            .ok_or("No audio processor class in this bundle")?;
+        log::info!("Found {} class(es) in bundle, using class {}", class_count, index);
+
+        self.name = fixed_c_string(&mut class_info.name);
+        let mut sub_categories = String::new();
+        if let Some(factory2) = query_interface(self.factory, &IPLUGIN_FACTORY2_IID) {
+            let mut info2: PClassInfo2 = std::mem::zeroed();
+            if (vtbl::<IPluginFactory2Vtbl>(factory2).get_class_info2)(factory2, index, &mut info2) == K_RESULT_OK {
+                sub_categories = fixed_c_string(&mut info2.sub_categories);
+                self.vendor = fixed_c_string(&mut info2.vendor);
+                self.version = fixed_c_string(&mut info2.version);
+            }
+            release(factory2);
+        }
+        if self.name.is_empty() {
+            self.name = "Unknown Plugin".to_string();
+        }
+        if self.vendor.is_empty() {
+            self.vendor = if factory_vendor.is_empty() { "Unknown".to_string() } else { factory_vendor };
+        }
+        if self.version.is_empty() {
+            self.version = "0.0.0".to_string();
+        }
+
+        let mut component = ptr::null_mut();
+        let status = (factory.create_instance)(
+            self.factory,
+            class_info.cid.as_ptr() as *const _,
+            ICOMPONENT_IID.as_ptr() as *const _,
+            &mut component,
+        );
+        if status != K_RESULT_OK || component.is_null() {
+            return Err("Failed to create plugin component".to_string());
+        }
+        if (vtbl::<IPluginBaseVtbl>(component).initialize)(component, self.host_context()) != K_RESULT_OK {
+            release(component);
+            return Err("Plugin component initialize() failed".to_string());
+        }
+        self.component = component;
+
+        self.processor =
+            query_interface(component, &IAUDIO_PROCESSOR_IID).ok_or("Plugin has no audio processor")?;
+        Ok(sub_categories)
+    }
+
+    /// Find the edit controller: the component itself, or a separate class it names.
+    /// A plugin without one still processes audio; only its controller state is lost.
+    unsafe fn create_controller(&mut self) {
+        if let Some(controller) = query_interface(self.component, &IEDIT_CONTROLLER_IID) {
+            self.controller = controller;
+            return;
+        }
+
+        let mut class_id: Tuid = [0; 16];
+        let component = vtbl::<IComponentVtbl>(self.component);
+        if (component.get_controller_class_id)(self.component, &mut class_id) != K_RESULT_OK || class_id == [0; 16] {
+            return;
+        }
+        let mut controller = ptr::null_mut();
+        let status = (vtbl::<IPluginFactoryVtbl>(self.factory).create_instance)(
+            self.factory,
+            class_id.as_ptr() as *const _,
+            IEDIT_CONTROLLER_IID.as_ptr() as *const _,
+            &mut controller,
+        );
+        if status != K_RESULT_OK || controller.is_null() {
+            log::warn!("Could not create the plugin's edit controller");
+            return;
+        }
+        if (vtbl::<IPluginBaseVtbl>(controller).initialize)(controller, self.host_context()) != K_RESULT_OK {
+            log::warn!("Plugin edit controller initialize() failed");
+            release(controller);
+            return;
+        }
+        self.controller = controller;
+        self.separate_controller = true;
+
+        // A separate controller learns the component's state through setComponentState
+        if let Ok((component_state, _)) = self.read_state() {
+            let mut stream = MemoryStream::new(component_state);
+            (vtbl::<IEditControllerVtbl>(controller).set_component_state)(controller, stream.as_ptr());
+        }
+    }
+
+    /// Ask for stereo on the main buses, allocate buffers for every audio bus and
+    /// activate the main ones. Returns the number of audio input buses.
+    unsafe fn setup_buses(&mut self) -> usize {
+        let component = vtbl::<IComponentVtbl>(self.component);
+        let processor = vtbl::<IAudioProcessorVtbl>(self.processor);
+        let bus_count = |dir: i32| (component.get_bus_count)(self.component, K_AUDIO, dir).max(0);
+
+        let mut arrangements = [Vec::new(), Vec::new()];
+        for (dir, arrangement) in [K_INPUT, K_OUTPUT].into_iter().zip(arrangements.iter_mut()) {
+            for index in 0..bus_count(dir) {
+                let mut info: BusInfo = std::mem::zeroed();
+                if (component.get_bus_info)(self.component, K_AUDIO, dir, index, &mut info) != K_RESULT_OK {
+                    info.channel_count = 0;
+                }
+                self.audio_ports.push(AudioPortInfo {
+                    name: string128(&info.name),
+                    is_input: dir == K_INPUT,
+                    is_main: info.bus_type == K_MAIN,
+                    channel_count: info.channel_count.max(0) as u32,
+                });
+                let mut current: SpeakerArrangement = 0;
+                (processor.get_bus_arrangement)(self.processor, dir, index, &mut current);
+                arrangement.push(if index == 0 { K_STEREO } else { current });
+            }
+        }
+        let [mut inputs, mut outputs] = arrangements;
+        let status = (processor.set_bus_arrangements)(
+            self.processor,
+            inputs.as_mut_ptr(),
+            inputs.len() as i32,
+            outputs.as_mut_ptr(),
+            outputs.len() as i32,
+        );
+        if status != K_RESULT_OK {
+            log::info!("Plugin rejected stereo main buses, keeping its own layout");
+        }
+
+        // Whatever was agreed on decides the channel counts
+        let max = self.max_frames as usize;
+        for (dir, count) in [(K_INPUT, inputs.len()), (K_OUTPUT, outputs.len())] {
+            for index in 0..count {
+                let mut arrangement: SpeakerArrangement = 0;
+                let channels = if (processor.get_bus_arrangement)(self.processor, dir, index as i32, &mut arrangement)
+                    == K_RESULT_OK
+                {
+                    arrangement.count_ones() as usize
+                } else {
+                    2
+                };
+                let buffers = PortBuffers::new(channels, max, false);
+                if dir == K_INPUT {
+                    self.input_buffers.push(buffers);
+                } else {
+                    self.output_buffers.push(buffers);
+                }
+            }
+            if count > 0 {
+                (component.activate_bus)(self.component, K_AUDIO, dir, 0, 1);
+            }
+        }
+        self.input_bus_buffers.reserve(inputs.len());
+        self.output_bus_buffers.reserve(outputs.len());
+        inputs.len()
+    }
+
+    unsafe fn activate(&mut self, sample_rate: f64) -> Result<(), String> {
+        let mut setup = ProcessSetup {
+            process_mode: K_REALTIME,
+            symbolic_sample_size: K_SAMPLE32,
+            max_samples_per_block: self.max_frames as i32,
+            sample_rate,
+        };
+        if (vtbl::<IAudioProcessorVtbl>(self.processor).setup_processing)(self.processor, &mut setup) != K_RESULT_OK {
+            return Err("Plugin setupProcessing() failed".to_string());
+        }
+        if (vtbl::<IComponentVtbl>(self.component).set_active)(self.component, 1) != K_RESULT_OK {
+            return Err("Plugin setActive() failed".to_string());
+        }
+        self.is_active = true;
+        log::info!("Plugin activated: {}Hz, max {} frames", sample_rate, self.max_frames);
+        Ok(())
+    }
+
+    fn host_context(&self) -> *mut c_void {
+        &*self.host as *const HostApplication as *mut c_void
+    }
+
+    /// Start audio processing
+    pub fn start_processing(&mut self) -> Result<(), String> {
+        if !self.is_active {
+            return Err("Plugin not active".to_string());
+        }
+        if self.is_processing {
+            return Ok(());
+        }
+        // Plugins that don't need the call may answer kNotImplemented
+        let status = unsafe { (vtbl::<IAudioProcessorVtbl>(self.processor).set_processing)(self.processor, 1) };
+        if status != K_RESULT_OK && status != K_NOT_IMPLEMENTED {
+            return Err("Plugin setProcessing() failed".to_string());
+        }
+        self.is_processing = true;
+        log::info!("Plugin processing started");
+        Ok(())
+    }
+
+    /// Stop audio processing
+    pub fn stop_processing(&mut self) {
+        if !self.is_processing {
+            return;
+        }
+        unsafe { (vtbl::<IAudioProcessorVtbl>(self.processor).set_processing)(self.processor, 0) };
+        self.is_processing = false;
+        log::info!("Plugin processing stopped");
+    }
+
+    /// Audio buses the plugin declared at load, inputs first
+    pub fn audio_ports(&self) -> &[AudioPortInfo] {
+        &self.audio_ports
+    }
+
+    /// How the stereo bus is mapped onto the plugin's main input
+    pub fn set_input_mapping(&mut self, mapping: InputMapping) {
+        self.input_mapping = mapping;
+    }
+
+    /// Aux input buses get silence - the sidechain isn't fed to VST3 plugins yet
+    pub fn has_sidechain_input(&self) -> bool {
+        false
+    }
+
+    /// Process interleaved stereo through the plugin (see PluginInstance::process)
+    pub fn process(&mut self, input: &[f32], output: &mut [f32]) -> Result<(), String> {
+        if self.crashed {
+            output.fill(0.0);
+            return Ok(());
+        }
+        if self.process_failed {
+            return Err("Plugin reported a processing error".to_string());
+        }
+        if !self.is_active {
+            return Err("Plugin not active".to_string());
+        }
+        if !self.is_processing {
+            self.start_processing()?;
+        }
+
+        let frames = input.len() / 2;
+        if frames == 0 {
+            return Ok(());
+        }
+        if frames > self.max_frames as usize {
+            log::warn!("Buffer size {} exceeds max_frames {}, truncating", frames, self.max_frames);
+            let len = self.max_frames as usize * 2;
+            return self.process(&input[..len], &mut output[..len]);
+        }
+
+        self.midi_queue.drain_into(&mut self.midi_drain_buffer);
+
+        // The main input gets the bus, aux inputs silence
+        self.input_bus_buffers.clear();
+        for (index, buffers) in self.input_buffers.iter_mut().enumerate() {
+            let source = (index == 0).then_some(input);
+            buffers.deinterleave_mapped(source, frames, self.input_mapping);
+            self.input_bus_buffers.push(AudioBusBuffers {
+                num_channels: buffers.channels() as i32,
+                silence_flags: 0,
+                channel_buffers32: buffers.channel_ptrs32(),
+            });
+        }
+        self.output_bus_buffers.clear();
+        for buffers in self.output_buffers.iter_mut() {
+            buffers.deinterleave(None, frames);
+            self.output_bus_buffers.push(AudioBusBuffers {
+                num_channels: buffers.channels() as i32,
+                silence_flags: 0,
+                channel_buffers32: buffers.channel_ptrs32(),
+            });
+        }
+
+        let mut data = ProcessData {
+            process_mode: K_REALTIME,
+            symbolic_sample_size: K_SAMPLE32,
+            num_samples: frames as i32,
+            num_inputs: self.input_bus_buffers.len() as i32,
+            num_outputs: self.output_bus_buffers.len() as i32,
+            inputs: self.input_bus_buffers.as_mut_ptr(),
+            outputs: self.output_bus_buffers.as_mut_ptr(),
+            input_parameter_changes: ptr::null_mut(),
+            output_parameter_changes: ptr::null_mut(),
+            input_events: ptr::null_mut(),
+            output_events: ptr::null_mut(),
+            process_context: ptr::null_mut(),
+        };
+
+        let processor = self.processor;
+        let process_fn = unsafe { vtbl::<IAudioProcessorVtbl>(processor).process };
+        let data_ptr = &mut data as *mut ProcessData;
+        let guard_result = super::crash_guard::with_crash_guard(|| unsafe { process_fn(processor, data_ptr) });
+
+        let result = match guard_result {
+            super::crash_guard::CrashGuardResult::Ok(r) => r,
+            super::crash_guard::CrashGuardResult::Crashed(signal) => {
+                self.crashed = true;
+                log::error!(
+                    "Plugin '{}' crashed during process! Signal: {}. Plugin has been disabled - reload to retry.",
+                    self.name,
+                    signal
+                );
+                output.fill(0.0);
+                return Ok(());
+            }
+        };
+
+        if result != K_RESULT_OK {
+            self.process_failed = true;
+            log::error!(
+                "Plugin '{}' process() returned {}. Plugin has been bypassed - reload to retry.",
+                self.name,
+                result
+            );
+            return Err("Plugin reported a processing error".to_string());
+        }
+
+        match self.output_buffers.first() {
+            Some(buffers) => buffers.interleave_into(output, frames),
+            None => output[..frames * 2].fill(0.0),
+        }
+        Ok(())
+    }
+
+    /// Check if the plugin has crashed during processing
+    pub fn has_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    /// Check if the plugin's process() returned an error
+    pub fn has_process_error(&self) -> bool {
+        self.process_failed
+    }
+
+    pub fn midi_queue(&self) -> Arc<MidiEventQueue> {
+        self.midi_queue.clone()
+    }
+
+    /// Latency reported by the audio processor in samples
+    pub fn latency(&self) -> u32 {
+        unsafe { (vtbl::<IAudioProcessorVtbl>(self.processor).get_latency_samples)(self.processor) }
+    }
+
+    /// Component state and controller state (empty if there's no controller or it has none)
+    fn read_state(&self) -> Result<(Vec<u8>, Vec<u8>), String> {
+        let mut component_stream = MemoryStream::new(Vec::new());
+        let status =
+            unsafe { (vtbl::<IComponentVtbl>(self.component).get_state)(self.component, component_stream.as_ptr()) };
+        if status != K_RESULT_OK {
+            return Err("Plugin state save failed".to_string());
+        }
+
+        let mut controller_stream = MemoryStream::new(Vec::new());
+        if !self.controller.is_null() {
+            let status = unsafe {
+                (vtbl::<IEditControllerVtbl>(self.controller).get_state)(self.controller, controller_stream.as_ptr())
+            };
+            if status != K_RESULT_OK {
+                controller_stream.data.clear();
+            }
+        }
+        Ok((component_stream.data, controller_stream.data))
+    }
+
+    /// Save the component and controller state to a byte vector
+    pub fn save_state(&self) -> Result<Vec<u8>, String> {
+        let (component, controller) = self.read_state()?;
+        let mut state = Vec::with_capacity(8 + component.len() + controller.len());
+        state.extend_from_slice(STATE_MAGIC);
+        state.extend_from_slice(&(component.len() as u32).to_le_bytes());
+        state.extend_from_slice(&component);
+        state.extend_from_slice(&controller);
+        Ok(state)
+    }
+
+    /// Load state written by save_state (main thread)
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let (component, controller) = split_state(data)?;
+        self.load_component_state(component)?;
+        self.load_controller_state(component, controller);
+        log::trace!("Loaded plugin state: {} bytes", data.len());
+        Ok(())
+    }
+
+    /// Hand the component (processor) half of a state to the plugin
+    fn load_component_state(&mut self, component: &[u8]) -> Result<(), String> {
+        let mut stream = MemoryStream::new(component.to_vec());
+        let status = unsafe { (vtbl::<IComponentVtbl>(self.component).set_state)(self.component, stream.as_ptr()) };
+        if status != K_RESULT_OK {
+            return Err("Plugin state load failed".to_string());
+        }
+        Ok(())
+    }
+
+    /// Bring the edit controller in line with a loaded state (main thread only)
+    fn load_controller_state(&self, component: &[u8], controller: &[u8]) {
+        if self.controller.is_null() {
+            return;
+        }
+        let controller_vtbl = unsafe { vtbl::<IEditControllerVtbl>(self.controller) };
+        let mut stream = MemoryStream::new(component.to_vec());
+        unsafe { (controller_vtbl.set_component_state)(self.controller, stream.as_ptr()) };
+        if !controller.is_empty() {
+            let mut stream = MemoryStream::new(controller.to_vec());
+            unsafe { (controller_vtbl.set_state)(self.controller, stream.as_ptr()) };
+        }
+    }
+
+    /// Apply the latest queued state (called from the audio thread, see PluginInstance)
+    ///
+    /// Only the component state is loaded here; the state is then handed back for
+    /// sync_controller_state to update the edit controller on the main thread.
+    pub fn apply_pending_state(&mut self) {
+        let mut latest_state: Option<Vec<u8>> = None;
+        while let Ok(state) = self.state_receiver.try_recv() {
+            latest_state = Some(state);
+        }
+        if let Some(state) = latest_state {
+            let loaded = split_state(&state).and_then(|(component, _)| self.load_component_state(component));
+            if loaded.is_ok() {
+                self.state_apply_count.fetch_add(1, Ordering::Relaxed);
+                // If the main thread is that far behind, the newer states it has queued win anyway
+                let _ = self.controller_state_producer.try_push(state);
+            } else {
+                self.state_failure_count.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Pass the latest state applied by apply_pending_state on to the edit controller
+    /// (call from the main thread)
+    pub fn sync_controller_state(&self) {
+        let latest_state = {
+            let mut consumer = self.controller_state_consumer.lock();
+            let mut latest_state = None;
+            while let Some(state) = consumer.try_pop() {
+                latest_state = Some(state);
+            }
+            latest_state
+        };
+        if let Some(state) = latest_state {
+            if let Ok((component, controller)) = split_state(&state) {
+                self.load_controller_state(component, controller);
+            }
+        }
+    }
+
+    /// Queue a state to be applied by the next apply_pending_state call
+    pub fn queue_state(&self, state: Vec<u8>) -> Result<(), String> {
+        self.state_sender
+            .send(state)
+            .map_err(|_| "Plugin state channel is closed".to_string())
+    }
+
+    pub fn get_state_apply_count(&self) -> u32 {
+        self.state_apply_count.load(Ordering::Relaxed)
+    }
+
+    pub fn get_state_failure_count(&self) -> u32 {
+        self.state_failure_count.load(Ordering::Relaxed)
+    }
+
+    /// Locate the binary inside a .vst3 bundle:
+    /// Contents/MacOS/<name>, Contents/<arch>-linux/<name>.so or Contents/<arch>-win/<name>.vst3
+    fn resolve_binary_path(bundle_path: &Path) -> Result<PathBuf, String> {
+        // Single-file plugins (older Windows layout)
+        if bundle_path.is_file() {
+            return Ok(bundle_path.to_path_buf());
+        }
+
+        let arch = std::env::consts::ARCH;
+        let (binary_dir, extension) = if cfg!(target_os = "macos") {
+            ("MacOS".to_string(), None)
+        } else if cfg!(target_os = "windows") {
+            (format!("{}-win", arch), Some("vst3"))
+        } else {
+            (format!("{}-linux", arch), Some("so"))
+        };
+        let binary_dir = bundle_path.join("Contents").join(binary_dir);
+
+        let bundle_name = bundle_path.file_stem().ok_or("Invalid bundle path")?;
+        let mut binary_path = binary_dir.join(bundle_name);
+        if let Some(extension) = extension {
+            binary_path.set_extension(extension);
+        }
+        if binary_path.is_file() {
+            return Ok(binary_path);
+        }
+
+        // Temp bundles are renamed, so the binary name doesn't match - take the one that's there
+        std::fs::read_dir(&binary_dir)
+            .ok()
+            .and_then(|entries| {
+                entries.flatten().map(|entry| entry.path()).find(|path| {
+                    path.is_file() && path.extension().and_then(|e| e.to_str()) == extension
+                })
+            })
+            .ok_or_else(|| format!("Could not find VST3 binary in bundle: {:?}", bundle_path))
+    }
+}
+
+/// Split saved state into component and controller state
+fn split_state(data: &[u8]) -> Result<(&[u8], &[u8]), String> {
+    let invalid = || "Not a VST3 plugin state".to_string();
+    let rest = data.strip_prefix(STATE_MAGIC.as_slice()).ok_or_else(invalid)?;
+    let (length, rest) = rest.split_first_chunk::<4>().ok_or_else(invalid)?;
+    let length = u32::from_le_bytes(*length) as usize;
+    if length > rest.len() {
+        return Err(invalid());
+    }
+    Ok(rest.split_at(length))
+}
+
+impl Drop for Vst3Instance {
+    fn drop(&mut self) {
+        log::info!("Unloading plugin: {}", self.name);
+        unsafe {
+            if !self.processor.is_null() {
+                self.stop_processing();
+                if self.is_active {
+                    (vtbl::<IComponentVtbl>(self.component).set_active)(self.component, 0);
+                    self.is_active = false;
+                }
+                release(self.processor);
+            }
+            if !self.controller.is_null() {
+                if self.separate_controller {
+                    (vtbl::<IPluginBaseVtbl>(self.controller).terminate)(self.controller);
+                }
+                release(self.controller);
+            }
+            if !self.component.is_null() {
+                (vtbl::<IPluginBaseVtbl>(self.component).terminate)(self.component);
+                release(self.component);
+            }
+            release(self.factory);
+        }
+        // The module field unloads the library after this
+    }
+}
+
+// =============================================================================
+// Host Objects
+// =============================================================================
+
+/// IBStream over a byte vector. Lives on the host's stack for the duration of a state call,
+/// so reference counting is a no-op.
+#[repr(C)]
+struct MemoryStream {
+    vtbl: *const IBStreamVtbl,
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl MemoryStream {
+    fn new(data: Vec<u8>) -> Self {
+        Self {
+            vtbl: &MEMORY_STREAM_VTBL,
+            data,
+            position: 0,
+        }
+    }
+
+    fn as_ptr(&mut self) -> *mut c_void {
+        self as *mut Self as *mut c_void
+    }
+}
+
+static MEMORY_STREAM_VTBL: IBStreamVtbl = IBStreamVtbl {
+    unknown: FUnknownVtbl {
+        query_interface: stream_query_interface,
+        add_ref: no_ref_count,
+        release: no_ref_count,
+    },
+    read: stream_read,
+    write: stream_write,
+    seek: stream_seek,
+    tell: stream_tell,
+};
+
+unsafe extern "system" fn no_ref_count(_this: *mut c_void) -> u32 {
+    1
+}
+
+unsafe extern "system" fn stream_query_interface(
+    this: *mut c_void,
+    iid: *const Tuid,
+    obj: *mut *mut c_void,
+) -> TResult {
+    if obj.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+    if !iid.is_null() && (*iid == FUNKNOWN_IID || *iid == IBSTREAM_IID) {
+        *obj = this;
+        K_RESULT_OK
+    } else {
+        *obj = ptr::null_mut();
+        K_NO_INTERFACE
+    }
+}
+
+unsafe extern "system" fn stream_read(
+    this: *mut c_void,
+    buffer: *mut c_void,
+    num_bytes: i32,
+    num_bytes_read: *mut i32,
+) -> TResult {
+    let stream = &mut *(this as *mut MemoryStream);
+    if buffer.is_null() || num_bytes < 0 {
+        return K_INVALID_ARGUMENT;
+    }
+    let available = stream.data.len().saturating_sub(stream.position);
+    let count = (num_bytes as usize).min(available);
+    ptr::copy_nonoverlapping(stream.data.as_ptr().add(stream.position), buffer as *mut u8, count);
+    stream.position += count;
+    if !num_bytes_read.is_null() {
+        *num_bytes_read = count as i32;
+    }
+    K_RESULT_OK
+}
+
+unsafe extern "system" fn stream_write(
+    this: *mut c_void,
+    buffer: *mut c_void,
+    num_bytes: i32,
+    num_bytes_written: *mut i32,
+) -> TResult {
+    let stream = &mut *(this as *mut MemoryStream);
+    if buffer.is_null() || num_bytes < 0 {
+        return K_INVALID_ARGUMENT;
+    }
+    let count = num_bytes as usize;
+    let end = stream.position + count;
+    if stream.data.len() < end {
+        stream.data.resize(end, 0);
+    }
+    ptr::copy_nonoverlapping(buffer as *const u8, stream.data.as_mut_ptr().add(stream.position), count);
+    stream.position = end;
+    if !num_bytes_written.is_null() {
+        *num_bytes_written = num_bytes;
+    }
+    K_RESULT_OK
+}
+
+unsafe extern "system" fn stream_seek(this: *mut c_void, pos: i64, mode: i32, result: *mut i64) -> TResult {
+    let stream = &mut *(this as *mut MemoryStream);
+    let base = match mode {
+        K_IB_SEEK_SET => 0,
+        K_IB_SEEK_CUR => stream.position as i64,
+        K_IB_SEEK_END => stream.data.len() as i64,
+        _ => return K_INVALID_ARGUMENT,
+    };
+    let position = base + pos;
+    if position < 0 {
+        return K_INVALID_ARGUMENT;
+    }
+    stream.position = position as usize;
+    if !result.is_null() {
+        *result = position;
+    }
+    K_RESULT_OK
+}
+
+unsafe extern "system" fn stream_tell(this: *mut c_void, pos: *mut i64) -> TResult {
+    let stream = &*(this as *const MemoryStream);
+    if pos.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+    *pos = stream.position as i64;
+    K_RESULT_OK
+}
+
+/// IHostApplication passed to initialize(). Owned by the instance, so reference counting
+/// is a no-op here too.
+#[repr(C)]
+struct HostApplication {
+    vtbl: *const IHostApplicationVtbl,
+}
+
+static HOST_APPLICATION_VTBL: IHostApplicationVtbl = IHostApplicationVtbl {
+    unknown: FUnknownVtbl {
+        query_interface: host_query_interface,
+        add_ref: no_ref_count,
+        release: no_ref_count,
+    },
+    get_name: host_get_name,
+    create_instance: host_create_instance,
+};
+
+unsafe extern "system" fn host_query_interface(this: *mut c_void, iid: *const Tuid, obj: *mut *mut c_void) -> TResult {
+    if obj.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+    if !iid.is_null() && (*iid == FUNKNOWN_IID || *iid == IHOST_APPLICATION_IID) {
+        *obj = this;
+        K_RESULT_OK
+    } else {
+        *obj = ptr::null_mut();
+        K_NO_INTERFACE
+    }
+}
+
+unsafe extern "system" fn host_get_name(_this: *mut c_void, name: *mut String128) -> TResult {
+    if name.is_null() {
+        return K_INVALID_ARGUMENT;
+    }
+    let name = &mut *name;
+    name.fill(0);
+    for (slot, unit) in name.iter_mut().zip(HOST_NAME.encode_utf16()) {
+        *slot = unit;
+    }
+    K_RESULT_OK
+}
+
+/// Host-side messages and attribute lists aren't provided
+unsafe extern "system" fn host_create_instance(
+    _this: *mut c_void,
+    _cid: *mut Tuid,
+    _iid: *mut Tuid,
+    obj: *mut *mut c_void,
+) -> TResult {
+    if !obj.is_null() {
+        *obj = ptr::null_mut();
+    }
+    K_NOT_IMPLEMENTED
+}
+
+/// A minimal in-process VST3 plugin for tests: one stereo effect that applies a gain,
+/// stored as its state
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use std::cell::Cell;
+
+    pub const MOCK_NAME: &str = "Mock Gain";
+    const MOCK_CID: Tuid = *b"freqlab-mockgain";
+
+    thread_local! {
+        /// Live mock objects (factories and plugins) created on this thread, to check
+        /// everything is released
+        pub static LIVE_OBJECTS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn count_object(delta: isize) {
+        LIVE_OBJECTS.with(|live| live.set(live.get().wrapping_add_signed(delta)));
+    }
+
+    fn write_c_string(text: &str, out: &mut [std::os::raw::c_char]) {
+        for (slot, byte) in out.iter_mut().zip(text.bytes().chain(std::iter::once(0))) {
+            *slot = byte as std::os::raw::c_char;
+        }
+    }
+
+    #[repr(C)]
+    struct MockFactory {
+        vtbl: *const IPluginFactoryVtbl,
+        refs: AtomicU32,
+    }
+
+    static FACTORY_VTBL: IPluginFactoryVtbl = IPluginFactoryVtbl {
+        unknown: FUnknownVtbl {
+            query_interface: factory_query_interface,
+            add_ref: factory_add_ref,
+            release: factory_release,
+        },
+        get_factory_info: factory_get_info,
+        count_classes: factory_count_classes,
+        get_class_info: factory_get_class_info,
+        create_instance: factory_create_instance,
+    };
+
+    unsafe extern "system" fn factory_query_interface(
+        this: *mut c_void,
+        iid: *const Tuid,
+        obj: *mut *mut c_void,
+    ) -> TResult {
+        if *iid == FUNKNOWN_IID || *iid == IPLUGIN_FACTORY_IID {
+            factory_add_ref(this);
+            *obj = this;
+            K_RESULT_OK
+        } else {
+            *obj = ptr::null_mut();
+            K_NO_INTERFACE
+        }
+    }
+
+    unsafe extern "system" fn factory_add_ref(this: *mut c_void) -> u32 {
+        (*(this as *const MockFactory)).refs.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    unsafe extern "system" fn factory_release(this: *mut c_void) -> u32 {
+        let refs = (*(this as *const MockFactory)).refs.fetch_sub(1, Ordering::SeqCst) - 1;
+        if refs == 0 {
+            drop(Box::from_raw(this as *mut MockFactory));
+            count_object(-1);
+        }
+        refs
+    }
+
+    unsafe extern "system" fn factory_get_info(_this: *mut c_void, info: *mut PFactoryInfo) -> TResult {
+        write_c_string("freqlab tests", &mut (*info).vendor);
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn factory_count_classes(_this: *mut c_void) -> i32 {
+        2
+    }
+
+    /// A controller class first, so the host has to look for the audio processor
+    unsafe extern "system" fn factory_get_class_info(_this: *mut c_void, index: i32, info: *mut PClassInfo) -> TResult {
+        let info = &mut *info;
+        info.cid = MOCK_CID;
+        match index {
+            0 => write_c_string("Component Controller Class", &mut info.category),
+            1 => write_c_string("Audio Module Class", &mut info.category),
+            _ => return K_INVALID_ARGUMENT,
+        }
+        write_c_string(MOCK_NAME, &mut info.name);
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn factory_create_instance(
+        _this: *mut c_void,
+        cid: *const std::os::raw::c_char,
+        iid: *const std::os::raw::c_char,
+        obj: *mut *mut c_void,
+    ) -> TResult {
+        *obj = ptr::null_mut();
+        if *(cid as *const Tuid) != MOCK_CID || *(iid as *const Tuid) != ICOMPONENT_IID {
+            return K_NO_INTERFACE;
+        }
+        let plugin = Box::new(MockPlugin {
+            component: &COMPONENT_VTBL,
+            processor: &PROCESSOR_VTBL,
+            refs: AtomicU32::new(1),
+            gain: 0.5,
+            active: false,
+        });
+        count_object(1);
+        *obj = Box::into_raw(plugin) as *mut c_void;
+        K_RESULT_OK
+    }
+
+    /// IComponent and IAudioProcessor in one object (the processor interface is the second pointer)
+    #[repr(C)]
+    struct MockPlugin {
+        component: *const IComponentVtbl,
+        processor: *const IAudioProcessorVtbl,
+        refs: AtomicU32,
+        gain: f32,
+        active: bool,
+    }
+
+    unsafe fn plugin<'a>(this: *mut c_void) -> &'a mut MockPlugin {
+        &mut *(this as *mut MockPlugin)
+    }
+
+    unsafe fn from_processor<'a>(this: *mut c_void) -> &'a mut MockPlugin {
+        plugin((this as *mut *const c_void).sub(1) as *mut c_void)
+    }
+
+    unsafe fn plugin_query_interface(plugin: &mut MockPlugin, iid: *const Tuid, obj: *mut *mut c_void) -> TResult {
+        let base = plugin as *mut MockPlugin;
+        *obj = if *iid == FUNKNOWN_IID || *iid == IPLUGIN_BASE_IID || *iid == ICOMPONENT_IID {
+            base as *mut c_void
+        } else if *iid == IAUDIO_PROCESSOR_IID {
+            &mut (*base).processor as *mut _ as *mut c_void
+        } else {
+            return K_NO_INTERFACE;
+        };
+        plugin.refs.fetch_add(1, Ordering::SeqCst);
+        K_RESULT_OK
+    }
+
+    unsafe fn plugin_release(plugin: &mut MockPlugin) -> u32 {
+        let refs = plugin.refs.fetch_sub(1, Ordering::SeqCst) - 1;
+        if refs == 0 {
+            drop(Box::from_raw(plugin as *mut MockPlugin));
+            count_object(-1);
+        }
+        refs
+    }
+
+    static COMPONENT_VTBL: IComponentVtbl = IComponentVtbl {
+        base: IPluginBaseVtbl {
+            unknown: FUnknownVtbl {
+                query_interface: component_query_interface,
+                add_ref: component_add_ref,
+                release: component_release,
+            },
+            initialize: component_initialize,
+            terminate: component_terminate,
+        },
+        get_controller_class_id: component_get_controller_class_id,
+        set_io_mode: component_set_io_mode,
+        get_bus_count: component_get_bus_count,
+        get_bus_info: component_get_bus_info,
+        get_routing_info: component_get_routing_info,
+        activate_bus: component_activate_bus,
+        set_active: component_set_active,
+        set_state: component_set_state,
+        get_state: component_get_state,
+    };
+
+    unsafe extern "system" fn component_query_interface(
+        this: *mut c_void,
+        iid: *const Tuid,
+        obj: *mut *mut c_void,
+    ) -> TResult {
+        plugin_query_interface(plugin(this), iid, obj)
+    }
+
+    unsafe extern "system" fn component_add_ref(this: *mut c_void) -> u32 {
+        plugin(this).refs.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    unsafe extern "system" fn component_release(this: *mut c_void) -> u32 {
+        plugin_release(plugin(this))
+    }
+
+    unsafe extern "system" fn component_initialize(_this: *mut c_void, context: *mut c_void) -> TResult {
+        // The host has to say who it is
+        let mut name: String128 = [0; 128];
+        (vtbl::<IHostApplicationVtbl>(context).get_name)(context, &mut name);
+        if string128(&name) == HOST_NAME {
+            K_RESULT_OK
+        } else {
+            K_RESULT_FALSE
+        }
+    }
+
+    unsafe extern "system" fn component_terminate(_this: *mut c_void) -> TResult {
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn component_get_controller_class_id(_this: *mut c_void, class_id: *mut Tuid) -> TResult {
+        *class_id = [0; 16];
+        K_RESULT_FALSE
+    }
+
+    unsafe extern "system" fn component_set_io_mode(_this: *mut c_void, _mode: i32) -> TResult {
+        K_NOT_IMPLEMENTED
+    }
+
+    unsafe extern "system" fn component_get_bus_count(_this: *mut c_void, media_type: i32, _dir: i32) -> i32 {
+        (media_type == K_AUDIO) as i32
+    }
+
+    unsafe extern "system" fn component_get_bus_info(
+        _this: *mut c_void,
+        _media_type: i32,
+        dir: i32,
+        _index: i32,
+        bus: *mut BusInfo,
+    ) -> TResult {
+        let bus = &mut *bus;
+        bus.media_type = K_AUDIO;
+        bus.direction = dir;
+        bus.channel_count = 2;
+        bus.bus_type = K_MAIN;
+        for (slot, unit) in bus.name.iter_mut().zip("Main".encode_utf16()) {
+            *slot = unit;
+        }
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn component_get_routing_info(
+        _this: *mut c_void,
+        _in_info: *mut c_void,
+        _out_info: *mut c_void,
+    ) -> TResult {
+        K_NOT_IMPLEMENTED
+    }
+
+    unsafe extern "system" fn component_activate_bus(
+        _this: *mut c_void,
+        _media_type: i32,
+        _dir: i32,
+        _index: i32,
+        _state: TBool,
+    ) -> TResult {
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn component_set_active(this: *mut c_void, state: TBool) -> TResult {
+        plugin(this).active = state != 0;
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn component_set_state(this: *mut c_void, stream: *mut c_void) -> TResult {
+        let mut bytes = [0u8; 4];
+        let mut read = 0;
+        (vtbl::<IBStreamVtbl>(stream).read)(stream, bytes.as_mut_ptr() as *mut c_void, 4, &mut read);
+        if read != 4 {
+            return K_RESULT_FALSE;
+        }
+        plugin(this).gain = f32::from_le_bytes(bytes);
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn component_get_state(this: *mut c_void, stream: *mut c_void) -> TResult {
+        let mut bytes = plugin(this).gain.to_le_bytes();
+        (vtbl::<IBStreamVtbl>(stream).write)(stream, bytes.as_mut_ptr() as *mut c_void, 4, ptr::null_mut())
+    }
+
+    static PROCESSOR_VTBL: IAudioProcessorVtbl = IAudioProcessorVtbl {
+        unknown: FUnknownVtbl {
+            query_interface: processor_query_interface,
+            add_ref: processor_add_ref,
+            release: processor_release,
+        },
+        set_bus_arrangements: processor_set_bus_arrangements,
+        get_bus_arrangement: processor_get_bus_arrangement,
+        can_process_sample_size: processor_can_process_sample_size,
+        get_latency_samples: processor_get_latency_samples,
+        setup_processing: processor_setup_processing,
+        set_processing: processor_set_processing,
+        process: processor_process,
+        get_tail_samples: processor_get_tail_samples,
+    };
+
+    unsafe extern "system" fn processor_query_interface(
+        this: *mut c_void,
+        iid: *const Tuid,
+        obj: *mut *mut c_void,
+    ) -> TResult {
+        plugin_query_interface(from_processor(this), iid, obj)
+    }
+
+    unsafe extern "system" fn processor_add_ref(this: *mut c_void) -> u32 {
+        from_processor(this).refs.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    unsafe extern "system" fn processor_release(this: *mut c_void) -> u32 {
+        plugin_release(from_processor(this))
+    }
+
+    unsafe extern "system" fn processor_set_bus_arrangements(
+        _this: *mut c_void,
+        inputs: *mut SpeakerArrangement,
+        num_ins: i32,
+        outputs: *mut SpeakerArrangement,
+        num_outs: i32,
+    ) -> TResult {
+        let stereo = num_ins == 1 && num_outs == 1 && *inputs == K_STEREO && *outputs == K_STEREO;
+        if stereo {
+            K_RESULT_OK
+        } else {
+            K_RESULT_FALSE
+        }
+    }
+
+    unsafe extern "system" fn processor_get_bus_arrangement(
+        _this: *mut c_void,
+        _dir: i32,
+        _index: i32,
+        arrangement: *mut SpeakerArrangement,
+    ) -> TResult {
+        *arrangement = K_STEREO;
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn processor_can_process_sample_size(_this: *mut c_void, size: i32) -> TResult {
+        if size == K_SAMPLE32 {
+            K_RESULT_OK
+        } else {
+            K_RESULT_FALSE
+        }
+    }
+
+    unsafe extern "system" fn processor_get_latency_samples(_this: *mut c_void) -> u32 {
+        0
+    }
+
+    unsafe extern "system" fn processor_setup_processing(_this: *mut c_void, setup: *mut ProcessSetup) -> TResult {
+        if (*setup).symbolic_sample_size == K_SAMPLE32 {
+            K_RESULT_OK
+        } else {
+            K_RESULT_FALSE
+        }
+    }
+
+    unsafe extern "system" fn processor_set_processing(_this: *mut c_void, _state: TBool) -> TResult {
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn processor_process(this: *mut c_void, data: *mut ProcessData) -> TResult {
+        let plugin = from_processor(this);
+        let data = &*data;
+        if !plugin.active || data.num_inputs != 1 || data.num_outputs != 1 {
+            return K_RESULT_FALSE;
+        }
+        let (input, output) = (&*data.inputs, &*data.outputs);
+        let frames = data.num_samples as usize;
+        for channel in 0..output.num_channels as usize {
+            let source = std::slice::from_raw_parts(*input.channel_buffers32.add(channel), frames);
+            let target = std::slice::from_raw_parts_mut(*output.channel_buffers32.add(channel), frames);
+            for (out, &sample) in target.iter_mut().zip(source) {
+                *out = sample * plugin.gain;
+            }
+        }
+        K_RESULT_OK
+    }
+
+    unsafe extern "system" fn processor_get_tail_samples(_this: *mut c_void) -> u32 {
+        0
+    }
+
+    /// Load the mock through its factory, as Vst3Instance::load does after opening a bundle
+    pub fn gain_plugin(sample_rate: f64, max_frames: u32) -> Result<Vst3Instance, String> {
+        let factory = Box::new(MockFactory {
+            vtbl: &FACTORY_VTBL,
+            refs: AtomicU32::new(1),
+        });
+        count_object(1);
+        Vst3Instance::from_factory(Box::into_raw(factory) as *mut c_void, sample_rate, max_frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::*;
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_mock_plugin_loads_and_processes() {
+        let mut plugin = gain_plugin(48000.0, 256).unwrap();
+        assert_eq!(plugin.name, MOCK_NAME);
+        assert_eq!(plugin.vendor, "freqlab tests");
+        assert!(!plugin.is_instrument);
+        assert_eq!(plugin.audio_ports().len(), 2);
+        assert!(plugin.audio_ports().iter().all(|port| port.is_main && port.channel_count == 2));
+
+        let input: Vec<f32> = (0..512).map(|i| (i as f32 * 0.01).sin()).collect();
+        let mut output = vec![0.0f32; 512];
+        plugin.process(&input, &mut output).unwrap();
+        assert!(output.iter().zip(&input).all(|(out, inp)| *out == inp * 0.5));
+        drop(plugin);
+        assert_eq!(LIVE_OBJECTS.with(Cell::get), 0);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let mut plugin = gain_plugin(48000.0, 256).unwrap();
+        let state = plugin.save_state().unwrap();
+        assert_eq!(&state[..4], STATE_MAGIC);
+
+        let mut louder = state.clone();
+        louder[8..12].copy_from_slice(&2.0f32.to_le_bytes());
+        plugin.load_state(&louder).unwrap();
+        let mut output = [0.0f32; 2];
+        plugin.process(&[0.25, -0.25], &mut output).unwrap();
+        assert_eq!(output, [0.5, -0.5]);
+
+        // Queued the way hot reload restores state
+        plugin.queue_state(state).unwrap();
+        plugin.apply_pending_state();
+        assert_eq!(plugin.get_state_apply_count(), 1);
+        plugin.process(&[0.25, -0.25], &mut output).unwrap();
+        assert_eq!(output, [0.125, -0.125]);
+        // The controller half waits for the main thread
+        assert_eq!(plugin.controller_state_consumer.lock().occupied_len(), 1);
+        plugin.sync_controller_state();
+        assert!(plugin.controller_state_consumer.lock().is_empty());
+
+        assert!(plugin.load_state(b"CLAPstate").is_err());
+        assert!(plugin.load_state(b"VST3\xff\0\0\0").is_err());
+    }
+
+    #[test]
+    fn test_memory_stream_seek_and_overwrite() {
+        let mut stream = MemoryStream::new(b"abcdef".to_vec());
+        let this = stream.as_ptr();
+        let mut position = 0i64;
+        let mut buffer = *b"XY";
+        unsafe {
+            assert_eq!(stream_seek(this, -2, K_IB_SEEK_END, &mut position), K_RESULT_OK);
+            assert_eq!(position, 4);
+            stream_write(this, buffer.as_mut_ptr() as *mut c_void, 2, ptr::null_mut());
+            stream_write(this, buffer.as_mut_ptr() as *mut c_void, 2, ptr::null_mut());
+            assert_eq!(stream_seek(this, -9, K_IB_SEEK_CUR, ptr::null_mut()), K_INVALID_ARGUMENT);
+            stream_seek(this, 1, K_IB_SEEK_SET, ptr::null_mut());
+            let mut read = 0;
+            let mut out = [0u8; 16];
+            stream_read(this, out.as_mut_ptr() as *mut c_void, 16, &mut read);
+            assert_eq!(&out[..read as usize], b"bcdXYXY");
+        }
+    }
+}
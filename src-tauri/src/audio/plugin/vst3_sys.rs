@@ -0,0 +1,323 @@
+//! VST3 FFI structures
+//!
+//! The subset of the VST3 COM interfaces the host needs to load a plugin, run its audio
+//! processor and save/restore its state. Interfaces are vtable pointers; methods use the
+//! platform's COM calling convention (`extern "system"`).
+//! Reference: https://github.com/steinbergmedia/vst3_pluginterfaces
+
+use std::ffi::c_void;
+use std::os::raw::c_char;
+
+// =============================================================================
+// Basic Types
+// =============================================================================
+
+pub type TResult = i32;
+pub type TBool = u8;
+/// Interface / class identifier
+pub type Tuid = [u8; 16];
+pub type String128 = [u16; 128];
+/// Bitmask of speakers on a bus
+pub type SpeakerArrangement = u64;
+
+#[cfg(target_os = "windows")]
+mod results {
+    pub const K_NO_INTERFACE: i32 = 0x80004002u32 as i32;
+    pub const K_NOT_IMPLEMENTED: i32 = 0x80004001u32 as i32;
+    pub const K_INVALID_ARGUMENT: i32 = 0x80070057u32 as i32;
+}
+
+#[cfg(not(target_os = "windows"))]
+mod results {
+    pub const K_NO_INTERFACE: i32 = -1;
+    pub const K_NOT_IMPLEMENTED: i32 = 3;
+    pub const K_INVALID_ARGUMENT: i32 = 2;
+}
+
+pub const K_RESULT_OK: TResult = 0;
+pub const K_RESULT_FALSE: TResult = 1;
+pub const K_NO_INTERFACE: TResult = results::K_NO_INTERFACE;
+pub const K_NOT_IMPLEMENTED: TResult = results::K_NOT_IMPLEMENTED;
+pub const K_INVALID_ARGUMENT: TResult = results::K_INVALID_ARGUMENT;
+
+/// Build an interface ID the way the SDK's INLINE_UID does (COM byte order on Windows)
+pub const fn uid(l1: u32, l2: u32, l3: u32, l4: u32) -> Tuid {
+    let (a, b, c, d) = (l1.to_be_bytes(), l2.to_be_bytes(), l3.to_be_bytes(), l4.to_be_bytes());
+    if cfg!(target_os = "windows") {
+        [
+            a[3], a[2], a[1], a[0], b[1], b[0], b[3], b[2], c[0], c[1], c[2], c[3], d[0], d[1], d[2], d[3],
+        ]
+    } else {
+        [
+            a[0], a[1], a[2], a[3], b[0], b[1], b[2], b[3], c[0], c[1], c[2], c[3], d[0], d[1], d[2], d[3],
+        ]
+    }
+}
+
+pub const FUNKNOWN_IID: Tuid = uid(0x00000000, 0x00000000, 0xC0000000, 0x00000046);
+pub const IPLUGIN_FACTORY_IID: Tuid = uid(0x7A4D811C, 0x52114A1F, 0xAED9D2EE, 0x0B43BF9F);
+pub const IPLUGIN_FACTORY2_IID: Tuid = uid(0x0007B650, 0xF24B4C0B, 0xA464EDB9, 0xF00B2ABB);
+pub const IPLUGIN_BASE_IID: Tuid = uid(0x22888DDB, 0x156E45AE, 0x8358B348, 0x08190625);
+pub const ICOMPONENT_IID: Tuid = uid(0xE831FF31, 0xF2D54301, 0x928EBBEE, 0x25697802);
+pub const IAUDIO_PROCESSOR_IID: Tuid = uid(0x42043F99, 0xB7DA453C, 0xA569E79D, 0x9AAEC33D);
+pub const IEDIT_CONTROLLER_IID: Tuid = uid(0xDCD7BBE3, 0x7742448D, 0xA874AACC, 0x979C759E);
+pub const IBSTREAM_IID: Tuid = uid(0xC3BF6EA2, 0x30994752, 0x9B6BF990, 0x1EE33E9B);
+pub const IHOST_APPLICATION_IID: Tuid = uid(0x58E595CC, 0xDB2D4969, 0x8B6AAF8C, 0x36A664E5);
+
+/// Class category of audio processors in the factory
+pub const K_VST_AUDIO_EFFECT_CLASS: &[u8] = b"Audio Module Class";
+
+// Media types
+pub const K_AUDIO: i32 = 0;
+pub const K_EVENT: i32 = 1;
+
+// Bus directions
+pub const K_INPUT: i32 = 0;
+pub const K_OUTPUT: i32 = 1;
+
+// Bus types
+pub const K_MAIN: i32 = 0;
+pub const K_AUX: i32 = 1;
+
+// Process modes and sample sizes
+pub const K_REALTIME: i32 = 0;
+pub const K_OFFLINE: i32 = 2;
+pub const K_SAMPLE32: i32 = 0;
+
+// Speaker arrangements
+pub const K_SPEAKER_L: SpeakerArrangement = 1 << 0;
+pub const K_SPEAKER_R: SpeakerArrangement = 1 << 1;
+pub const K_SPEAKER_M: SpeakerArrangement = 1 << 19;
+pub const K_STEREO: SpeakerArrangement = K_SPEAKER_L | K_SPEAKER_R;
+pub const K_MONO: SpeakerArrangement = K_SPEAKER_M;
+
+// IBStream seek modes
+pub const K_IB_SEEK_SET: i32 = 0;
+pub const K_IB_SEEK_CUR: i32 = 1;
+pub const K_IB_SEEK_END: i32 = 2;
+
+// =============================================================================
+// FUnknown
+// =============================================================================
+
+#[repr(C)]
+pub struct FUnknownVtbl {
+    pub query_interface:
+        unsafe extern "system" fn(this: *mut c_void, iid: *const Tuid, obj: *mut *mut c_void) -> TResult,
+    pub add_ref: unsafe extern "system" fn(this: *mut c_void) -> u32,
+    pub release: unsafe extern "system" fn(this: *mut c_void) -> u32,
+}
+
+// =============================================================================
+// Plugin Factory
+// =============================================================================
+
+#[repr(C)]
+pub struct PFactoryInfo {
+    pub vendor: [c_char; 64],
+    pub url: [c_char; 256],
+    pub email: [c_char; 128],
+    pub flags: i32,
+}
+
+#[repr(C)]
+pub struct PClassInfo {
+    pub cid: Tuid,
+    pub cardinality: i32,
+    pub category: [c_char; 32],
+    pub name: [c_char; 64],
+}
+
+#[repr(C)]
+pub struct PClassInfo2 {
+    pub cid: Tuid,
+    pub cardinality: i32,
+    pub category: [c_char; 32],
+    pub name: [c_char; 64],
+    pub class_flags: u32,
+    /// Pipe-separated, e.g. "Fx|Dynamics" or "Instrument|Synth"
+    pub sub_categories: [c_char; 128],
+    pub vendor: [c_char; 64],
+    pub version: [c_char; 64],
+    pub sdk_version: [c_char; 64],
+}
+
+#[repr(C)]
+pub struct IPluginFactoryVtbl {
+    pub unknown: FUnknownVtbl,
+    pub get_factory_info: unsafe extern "system" fn(this: *mut c_void, info: *mut PFactoryInfo) -> TResult,
+    pub count_classes: unsafe extern "system" fn(this: *mut c_void) -> i32,
+    pub get_class_info: unsafe extern "system" fn(this: *mut c_void, index: i32, info: *mut PClassInfo) -> TResult,
+    pub create_instance: unsafe extern "system" fn(
+        this: *mut c_void,
+        cid: *const c_char,
+        iid: *const c_char,
+        obj: *mut *mut c_void,
+    ) -> TResult,
+}
+
+#[repr(C)]
+pub struct IPluginFactory2Vtbl {
+    pub factory: IPluginFactoryVtbl,
+    pub get_class_info2:
+        unsafe extern "system" fn(this: *mut c_void, index: i32, info: *mut PClassInfo2) -> TResult,
+}
+
+// =============================================================================
+// Component
+// =============================================================================
+
+#[repr(C)]
+pub struct IPluginBaseVtbl {
+    pub unknown: FUnknownVtbl,
+    pub initialize: unsafe extern "system" fn(this: *mut c_void, context: *mut c_void) -> TResult,
+    pub terminate: unsafe extern "system" fn(this: *mut c_void) -> TResult,
+}
+
+#[repr(C)]
+pub struct BusInfo {
+    pub media_type: i32,
+    pub direction: i32,
+    pub channel_count: i32,
+    pub name: String128,
+    pub bus_type: i32,
+    pub flags: u32,
+}
+
+#[repr(C)]
+pub struct IComponentVtbl {
+    pub base: IPluginBaseVtbl,
+    pub get_controller_class_id: unsafe extern "system" fn(this: *mut c_void, class_id: *mut Tuid) -> TResult,
+    pub set_io_mode: unsafe extern "system" fn(this: *mut c_void, mode: i32) -> TResult,
+    pub get_bus_count: unsafe extern "system" fn(this: *mut c_void, media_type: i32, dir: i32) -> i32,
+    pub get_bus_info: unsafe extern "system" fn(
+        this: *mut c_void,
+        media_type: i32,
+        dir: i32,
+        index: i32,
+        bus: *mut BusInfo,
+    ) -> TResult,
+    pub get_routing_info:
+        unsafe extern "system" fn(this: *mut c_void, in_info: *mut c_void, out_info: *mut c_void) -> TResult,
+    pub activate_bus: unsafe extern "system" fn(
+        this: *mut c_void,
+        media_type: i32,
+        dir: i32,
+        index: i32,
+        state: TBool,
+    ) -> TResult,
+    pub set_active: unsafe extern "system" fn(this: *mut c_void, state: TBool) -> TResult,
+    pub set_state: unsafe extern "system" fn(this: *mut c_void, state: *mut c_void) -> TResult,
+    pub get_state: unsafe extern "system" fn(this: *mut c_void, state: *mut c_void) -> TResult,
+}
+
+// =============================================================================
+// Audio Processor
+// =============================================================================
+
+#[repr(C)]
+pub struct ProcessSetup {
+    pub process_mode: i32,
+    pub symbolic_sample_size: i32,
+    pub max_samples_per_block: i32,
+    pub sample_rate: f64,
+}
+
+#[repr(C)]
+pub struct AudioBusBuffers {
+    pub num_channels: i32,
+    pub silence_flags: u64,
+    /// Union with the 64-bit channel pointers (only 32-bit processing is used)
+    pub channel_buffers32: *mut *mut f32,
+}
+
+#[repr(C)]
+pub struct ProcessData {
+    pub process_mode: i32,
+    pub symbolic_sample_size: i32,
+    pub num_samples: i32,
+    pub num_inputs: i32,
+    pub num_outputs: i32,
+    pub inputs: *mut AudioBusBuffers,
+    pub outputs: *mut AudioBusBuffers,
+    pub input_parameter_changes: *mut c_void,
+    pub output_parameter_changes: *mut c_void,
+    pub input_events: *mut c_void,
+    pub output_events: *mut c_void,
+    pub process_context: *mut c_void,
+}
+
+#[repr(C)]
+pub struct IAudioProcessorVtbl {
+    pub unknown: FUnknownVtbl,
+    pub set_bus_arrangements: unsafe extern "system" fn(
+        this: *mut c_void,
+        inputs: *mut SpeakerArrangement,
+        num_ins: i32,
+        outputs: *mut SpeakerArrangement,
+        num_outs: i32,
+    ) -> TResult,
+    pub get_bus_arrangement: unsafe extern "system" fn(
+        this: *mut c_void,
+        dir: i32,
+        index: i32,
+        arrangement: *mut SpeakerArrangement,
+    ) -> TResult,
+    pub can_process_sample_size: unsafe extern "system" fn(this: *mut c_void, symbolic_sample_size: i32) -> TResult,
+    pub get_latency_samples: unsafe extern "system" fn(this: *mut c_void) -> u32,
+    pub setup_processing: unsafe extern "system" fn(this: *mut c_void, setup: *mut ProcessSetup) -> TResult,
+    pub set_processing: unsafe extern "system" fn(this: *mut c_void, state: TBool) -> TResult,
+    pub process: unsafe extern "system" fn(this: *mut c_void, data: *mut ProcessData) -> TResult,
+    pub get_tail_samples: unsafe extern "system" fn(this: *mut c_void) -> u32,
+}
+
+// =============================================================================
+// Edit Controller
+// =============================================================================
+
+/// Leading methods of IEditController (parameter and view methods follow, unused here)
+#[repr(C)]
+pub struct IEditControllerVtbl {
+    pub base: IPluginBaseVtbl,
+    pub set_component_state: unsafe extern "system" fn(this: *mut c_void, state: *mut c_void) -> TResult,
+    pub set_state: unsafe extern "system" fn(this: *mut c_void, state: *mut c_void) -> TResult,
+    pub get_state: unsafe extern "system" fn(this: *mut c_void, state: *mut c_void) -> TResult,
+}
+
+// =============================================================================
+// Host Interfaces
+// =============================================================================
+
+#[repr(C)]
+pub struct IBStreamVtbl {
+    pub unknown: FUnknownVtbl,
+    pub read: unsafe extern "system" fn(
+        this: *mut c_void,
+        buffer: *mut c_void,
+        num_bytes: i32,
+        num_bytes_read: *mut i32,
+    ) -> TResult,
+    pub write: unsafe extern "system" fn(
+        this: *mut c_void,
+        buffer: *mut c_void,
+        num_bytes: i32,
+        num_bytes_written: *mut i32,
+    ) -> TResult,
+    pub seek: unsafe extern "system" fn(this: *mut c_void, pos: i64, mode: i32, result: *mut i64) -> TResult,
+    pub tell: unsafe extern "system" fn(this: *mut c_void, pos: *mut i64) -> TResult,
+}
+
+#[repr(C)]
+pub struct IHostApplicationVtbl {
+    pub unknown: FUnknownVtbl,
+    pub get_name: unsafe extern "system" fn(this: *mut c_void, name: *mut String128) -> TResult,
+    pub create_instance: unsafe extern "system" fn(
+        this: *mut c_void,
+        cid: *mut Tuid,
+        iid: *mut Tuid,
+        obj: *mut *mut c_void,
+    ) -> TResult,
+}
+
+/// Entry point every VST3 binary exports
+pub type GetPluginFactoryFn = unsafe extern "system" fn() -> *mut c_void;
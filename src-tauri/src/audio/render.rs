@@ -0,0 +1,211 @@
+//! Offline rendering of a sample through a plugin
+//!
+//! The sample is fed through the plugin in blocks as fast as the plugin can process
+//! them, on whatever thread calls in - never the audio device. The plugin's reported
+//! latency is compensated by rendering that many extra frames of silence and dropping
+//! the same number from the start, so the result lines up with the original sample.
+//! Output is written as 32-bit float WAV so anything above 0 dBFS survives for comparison.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::buffer::StereoSample;
+
+/// WAVE_FORMAT_IEEE_FLOAT
+const WAV_FORMAT_FLOAT: u16 = 3;
+
+/// Result of an offline render
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderResult {
+    pub output_path: String,
+    pub sample_rate: u32,
+    /// Length in frames (the same as the source sample)
+    pub frames: usize,
+    pub duration_secs: f64,
+    /// Plugin latency that was compensated, in samples
+    pub latency_samples: u32,
+}
+
+/// Progress of an offline render, reported once per percent
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RenderProgress {
+    pub frames_done: usize,
+    /// Source frames plus the latency tail
+    pub total_frames: usize,
+    /// 0.0 - 1.0
+    pub progress: f32,
+}
+
+/// Run `input` through `process` in blocks of up to `max_frames`, followed by `latency`
+/// frames of silence, and return the interleaved stereo output with the first `latency`
+/// frames dropped (so it's exactly as long as the input)
+pub fn render_blocks<P, F>(
+    input: &[StereoSample],
+    latency: usize,
+    max_frames: usize,
+    mut process: P,
+    mut on_progress: F,
+) -> Result<Vec<f32>, String>
+where
+    P: FnMut(&[f32], &mut [f32]) -> Result<(), String>,
+    F: FnMut(RenderProgress),
+{
+    if input.is_empty() {
+        return Err("The sample is empty".to_string());
+    }
+
+    let max_frames = max_frames.max(1);
+    let total_frames = input.len() + latency;
+    let mut input_block = vec![0.0f32; max_frames * 2];
+    let mut output_block = vec![0.0f32; max_frames * 2];
+    let mut output = Vec::with_capacity(input.len() * 2);
+
+    let mut frames_done = 0;
+    let mut latency_left = latency;
+    let mut last_percent = None;
+    while frames_done < total_frames {
+        let frames = (total_frames - frames_done).min(max_frames);
+        for (i, frame) in input_block[..frames * 2].chunks_mut(2).enumerate() {
+            // Past the end of the sample: silence for the latency tail
+            let sample = input.get(frames_done + i).copied().unwrap_or_default();
+            frame[0] = sample.left;
+            frame[1] = sample.right;
+        }
+        output_block[..frames * 2].fill(0.0);
+        process(&input_block[..frames * 2], &mut output_block[..frames * 2])?;
+
+        let skipped = latency_left.min(frames);
+        latency_left -= skipped;
+        output.extend_from_slice(&output_block[skipped * 2..frames * 2]);
+        frames_done += frames;
+
+        let percent = frames_done * 100 / total_frames;
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            on_progress(RenderProgress {
+                frames_done,
+                total_frames,
+                progress: frames_done as f32 / total_frames as f32,
+            });
+        }
+    }
+
+    Ok(output)
+}
+
+/// Write interleaved samples as a 32-bit float WAV file
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: u32, channels: u16) -> Result<(), String> {
+    let channels = channels.max(1);
+    let block_align = channels as u32 * 4;
+    let frames = samples.len() as u64 / channels as u64;
+    let data_size = frames * block_align as u64;
+    // RIFF size: "WAVE" + fmt chunk (8 + 18) + fact chunk (8 + 4) + data chunk header (8) + data
+    let riff_size = 4 + 26 + 12 + 8 + data_size;
+    if riff_size > u32::MAX as u64 {
+        return Err("Render is too long for a WAV file (over 4 GB)".to_string());
+    }
+
+    let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    let mut write = |bytes: &[u8]| {
+        writer
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    };
+
+    write(b"RIFF")?;
+    write(&(riff_size as u32).to_le_bytes())?;
+    write(b"WAVE")?;
+
+    write(b"fmt ")?;
+    write(&18u32.to_le_bytes())?;
+    write(&WAV_FORMAT_FLOAT.to_le_bytes())?;
+    write(&channels.to_le_bytes())?;
+    write(&sample_rate.to_le_bytes())?;
+    write(&(sample_rate * block_align).to_le_bytes())?;
+    write(&(block_align as u16).to_le_bytes())?;
+    write(&32u16.to_le_bytes())?;
+    write(&0u16.to_le_bytes())?; // no extension
+
+    // Non-PCM formats carry the frame count in a fact chunk
+    write(b"fact")?;
+    write(&4u32.to_le_bytes())?;
+    write(&(frames as u32).to_le_bytes())?;
+
+    write(b"data")?;
+    write(&(data_size as u32).to_le_bytes())?;
+    for sample in &samples[..(frames * channels as u64) as usize] {
+        write(&sample.to_le_bytes())?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::samples::AudioSample;
+
+    fn ramp(frames: usize) -> Vec<StereoSample> {
+        (0..frames)
+            .map(|i| StereoSample {
+                left: i as f32,
+                right: -(i as f32),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_blocks_compensates_latency() {
+        // A plugin that delays by 5 frames and halves the level
+        let latency = 5;
+        let mut delay_line = vec![0.0f32; latency * 2];
+        let delay = |input: &[f32], output: &mut [f32]| {
+            for (sample, out) in input.iter().zip(output.iter_mut()) {
+                delay_line.push(*sample);
+                *out = delay_line.remove(0) * 0.5;
+            }
+            Ok(())
+        };
+
+        let mut progress = Vec::new();
+        let input = ramp(23);
+        let output = render_blocks(&input, latency, 8, delay, |p| progress.push(p)).unwrap();
+
+        assert_eq!(output.len(), 23 * 2);
+        for (frame, source) in output.chunks(2).zip(&input) {
+            assert_eq!(frame, &[source.left * 0.5, source.right * 0.5]);
+        }
+        // 28 frames in blocks of 8: one report per block, ending at 100%
+        assert_eq!(progress.len(), 4);
+        let last = progress.last().unwrap();
+        assert_eq!((last.frames_done, last.total_frames, last.progress), (28, 28, 1.0));
+    }
+
+    #[test]
+    fn test_render_blocks_stops_on_plugin_error() {
+        let failing = |_: &[f32], _: &mut [f32]| Err("Plugin reported a processing error".to_string());
+        assert!(render_blocks(&ramp(4), 0, 8, failing, |_| {}).is_err());
+        assert!(render_blocks(&[], 0, 8, |_, _| Ok(()), |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_write_wav_round_trips() {
+        let path = std::env::temp_dir().join(format!("freqlab_render_test_{}.wav", std::process::id()));
+        let samples = [0.25, -0.5, 1.5, -1.25, 0.0, 0.125];
+        write_wav(&path, &samples, 44100, 2).unwrap();
+
+        let loaded = AudioSample::load(&path);
+        std::fs::remove_file(&path).ok();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.info.sample_rate, 44100);
+        assert_eq!(loaded.info.channels, 2);
+        let frames: Vec<(f32, f32)> = loaded.data.iter().map(|s| (s.left, s.right)).collect();
+        // Above 0 dBFS is kept as is
+        assert_eq!(frames, vec![(0.25, -0.5), (1.5, -1.25), (0.0, 0.125)]);
+    }
+}
@@ -0,0 +1,307 @@
+//! Frequency response measurement with an exponential sine sweep
+//!
+//! The sweep is played through the plugin and the recording is convolved with an
+//! inverse sweep (time-reversed, with a -6dB/octave envelope that undoes the sweep's
+//! pink energy distribution). The result is the plugin's impulse response. Harmonic
+//! distortion products land *before* the linear response, so a window starting at the
+//! aligned impulse keeps them out of the measurement.
+
+use realfft::num_complex::Complex;
+use realfft::RealFftPlanner;
+use serde::Serialize;
+
+/// Sweep range (the end is capped below Nyquist at low sample rates)
+const SWEEP_START_HZ: f64 = 20.0;
+const SWEEP_END_HZ: f64 = 20000.0;
+const SWEEP_SECONDS: f64 = 2.0;
+/// Sweep level (-6 dBFS leaves headroom for plugins that boost)
+const SWEEP_AMPLITUDE: f64 = 0.5;
+/// Fade in/out on the sweep to avoid clicks at the edges
+const FADE_SECONDS: f64 = 0.01;
+
+/// Impulse response window length in samples (sets the frequency resolution)
+const IR_LENGTH: usize = 16384;
+/// Samples kept before the aligned impulse (pre-ringing of linear-phase filters)
+const IR_PRE_ROLL: usize = 256;
+/// Number of log-spaced points in the returned response
+const RESPONSE_POINTS: usize = 512;
+
+/// Measured magnitude and phase response of a plugin
+#[derive(Debug, Clone, Serialize)]
+pub struct FrequencyResponse {
+    pub sample_rate: u32,
+    /// Plugin latency that was compensated when aligning the impulse response
+    pub latency_samples: u32,
+    /// Log-spaced frequencies in Hz
+    pub frequencies: Vec<f32>,
+    /// Magnitude at each frequency in dB
+    pub magnitude_db: Vec<f32>,
+    /// Phase at each frequency in degrees (-180 to 180)
+    pub phase_degrees: Vec<f32>,
+}
+
+/// Exponential sweep and its inverse filter
+pub struct Sweep {
+    pub sample_rate: u32,
+    start_hz: f64,
+    end_hz: f64,
+    /// The sweep as played into the plugin
+    pub signal: Vec<f32>,
+    /// Time-reversed, amplitude-compensated sweep used for deconvolution
+    inverse: Vec<f32>,
+}
+
+impl Sweep {
+    pub fn new(sample_rate: u32) -> Self {
+        let sr = sample_rate as f64;
+        let start_hz = SWEEP_START_HZ;
+        let end_hz = SWEEP_END_HZ.min(sr * 0.45);
+        let length = (SWEEP_SECONDS * sr) as usize;
+        let rate = (end_hz / start_hz).ln();
+        let fade = ((FADE_SECONDS * sr) as usize).max(1);
+
+        let signal: Vec<f32> = (0..length)
+            .map(|n| {
+                let t = n as f64 / sr;
+                let phase = 2.0 * std::f64::consts::PI * start_hz * SWEEP_SECONDS / rate
+                    * ((t * rate / SWEEP_SECONDS).exp() - 1.0);
+                let envelope = (n.min(length - 1 - n) as f64 / fade as f64).min(1.0);
+                (SWEEP_AMPLITUDE * envelope * phase.sin()) as f32
+            })
+            .collect();
+
+        // Low frequencies last longer in the sweep - scale them down by 6dB/octave
+        let inverse: Vec<f32> = (0..length)
+            .map(|n| {
+                let k = length - 1 - n;
+                let t = k as f64 / sr;
+                signal[k] * (-t * rate / SWEEP_SECONDS).exp() as f32
+            })
+            .collect();
+
+        Self {
+            sample_rate,
+            start_hz,
+            end_hz,
+            signal,
+            inverse,
+        }
+    }
+
+    /// Number of output frames to record for a plugin with the given latency
+    pub fn capture_length(&self, latency: u32) -> usize {
+        self.signal.len() + latency as usize + IR_LENGTH
+    }
+
+    /// Compute the response from a recording of the plugin output
+    ///
+    /// `latency` shifts the analysis window so the reported phase excludes the
+    /// plugin's own delay.
+    pub fn analyze(&self, recording: &[f32], latency: u32) -> FrequencyResponse {
+        let ir = deconvolve(recording, &self.inverse);
+        // Deconvolving the sweep itself gives the band-limited reference impulse
+        // the plugin response is calibrated against
+        let reference = deconvolve(&self.signal, &self.inverse);
+
+        // A pass-through system peaks at the end of the inverse filter
+        let peak = self.inverse.len() - 1;
+        let ir_spectrum = windowed_spectrum(&ir, peak + latency as usize);
+        let reference_spectrum = windowed_spectrum(&reference, peak);
+
+        let bin_hz = self.sample_rate as f64 / IR_LENGTH as f64;
+        let log_start = self.start_hz.ln();
+        let log_end = self.end_hz.ln();
+
+        let mut frequencies = Vec::with_capacity(RESPONSE_POINTS);
+        let mut magnitude_db = Vec::with_capacity(RESPONSE_POINTS);
+        let mut phase_degrees = Vec::with_capacity(RESPONSE_POINTS);
+
+        for i in 0..RESPONSE_POINTS {
+            let t = i as f64 / (RESPONSE_POINTS - 1) as f64;
+            let freq = (log_start + t * (log_end - log_start)).exp();
+
+            // Linear interpolation between the neighbouring bins
+            let position = freq / bin_hz;
+            let bin = (position.floor() as usize).min(ir_spectrum.len() - 2);
+            let frac = position - bin as f64;
+            let transfer = |k: usize| {
+                let r = reference_spectrum[k];
+                if r.norm() > 1e-12 {
+                    ir_spectrum[k] / r
+                } else {
+                    Complex::new(0.0, 0.0)
+                }
+            };
+            let h = transfer(bin) * (1.0 - frac) + transfer(bin + 1) * frac;
+
+            frequencies.push(freq as f32);
+            magnitude_db.push((20.0 * h.norm().max(1e-9).log10()) as f32);
+            phase_degrees.push(h.arg().to_degrees() as f32);
+        }
+
+        FrequencyResponse {
+            sample_rate: self.sample_rate,
+            latency_samples: latency,
+            frequencies,
+            magnitude_db,
+            phase_degrees,
+        }
+    }
+}
+
+/// Audio-thread side of a measurement: feeds the sweep and records the plugin output
+///
+/// The recording is pre-allocated, so nothing here allocates on the audio thread.
+pub struct SweepCapture {
+    sweep: Vec<f32>,
+    recorded: Vec<f32>,
+    length: usize,
+    position: usize,
+    failed: bool,
+}
+
+impl SweepCapture {
+    pub fn new(sweep: Vec<f32>, length: usize) -> Self {
+        Self {
+            sweep,
+            recorded: Vec::with_capacity(length),
+            length,
+            position: 0,
+            failed: false,
+        }
+    }
+
+    /// Write the next block of the sweep to every channel (silence once the sweep ends)
+    pub fn fill_input(&self, data: &mut [f32], channels: usize) {
+        for (i, frame) in data.chunks_mut(channels).enumerate() {
+            let sample = self.sweep.get(self.position + i).copied().unwrap_or(0.0);
+            frame.fill(sample);
+        }
+    }
+
+    /// Record a block of plugin output (channels averaged to mono)
+    /// Returns true once the capture is complete (or has failed because the block
+    /// wasn't processed by the plugin)
+    pub fn record(&mut self, output: &[f32], channels: usize, processed: bool) -> bool {
+        if !processed {
+            self.failed = true;
+            return true;
+        }
+        for frame in output.chunks(channels) {
+            if self.recorded.len() >= self.length {
+                break;
+            }
+            let sum: f32 = frame.iter().sum();
+            self.recorded.push(sum / frame.len() as f32);
+        }
+        self.position += output.len() / channels.max(1);
+        self.recorded.len() >= self.length
+    }
+
+    /// Take the finished recording
+    pub fn into_recording(self) -> Result<Vec<f32>, String> {
+        if self.failed {
+            return Err("Plugin was busy during the measurement - try again".to_string());
+        }
+        if self.recorded.len() < self.length {
+            return Err("Measurement did not complete".to_string());
+        }
+        Ok(self.recorded)
+    }
+}
+
+/// Linear convolution of a recording with the inverse sweep via FFT
+fn deconvolve(signal: &[f32], inverse: &[f32]) -> Vec<f64> {
+    let n = (signal.len() + inverse.len()).next_power_of_two();
+    let mut planner = RealFftPlanner::<f64>::new();
+    let forward = planner.plan_fft_forward(n);
+    let backward = planner.plan_fft_inverse(n);
+
+    let spectrum = |samples: &[f32]| {
+        let mut buffer = vec![0.0f64; n];
+        for (dst, &src) in buffer.iter_mut().zip(samples) {
+            *dst = src as f64;
+        }
+        let mut output = forward.make_output_vec();
+        // Lengths match the plan, so this can't fail
+        let _ = forward.process(&mut buffer, &mut output);
+        output
+    };
+
+    let mut product = spectrum(signal);
+    for (a, b) in product.iter_mut().zip(spectrum(inverse)) {
+        *a *= b;
+    }
+
+    let mut result = backward.make_output_vec();
+    let _ = backward.process(&mut product, &mut result);
+    let scale = 1.0 / n as f64;
+    for sample in result.iter_mut() {
+        *sample *= scale;
+    }
+    result
+}
+
+/// Spectrum of an IR_LENGTH window of the impulse response starting at `start`
+///
+/// The pre-roll is wrapped to the end of the window so `start` stays at time zero,
+/// and both edges are faded to limit truncation ripple.
+fn windowed_spectrum(ir: &[f64], start: usize) -> Vec<Complex<f64>> {
+    let mut frame = vec![0.0f64; IR_LENGTH];
+    let causal = IR_LENGTH - IR_PRE_ROLL;
+    let fade_start = causal * 3 / 4;
+    let fade_length = (causal - fade_start) as f64;
+
+    for (i, value) in frame.iter_mut().take(causal).enumerate() {
+        let sample = ir.get(start + i).copied().unwrap_or(0.0);
+        let window = if i < fade_start {
+            1.0
+        } else {
+            0.5 * (1.0 + (std::f64::consts::PI * (i - fade_start) as f64 / fade_length).cos())
+        };
+        *value = sample * window;
+    }
+
+    let pre_roll = IR_PRE_ROLL.min(start);
+    for j in 0..pre_roll {
+        let sample = ir.get(start - pre_roll + j).copied().unwrap_or(0.0);
+        let window = 0.5 * (1.0 - (std::f64::consts::PI * j as f64 / pre_roll as f64).cos());
+        frame[IR_LENGTH - pre_roll + j] = sample * window;
+    }
+
+    let mut planner = RealFftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(IR_LENGTH);
+    let mut output = fft.make_output_vec();
+    let _ = fft.process(&mut frame, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_of_delayed_gain() {
+        let sweep = Sweep::new(48000);
+        let latency = 64;
+
+        // "Plugin" = -6dB gain with 64 samples of latency
+        let mut recording = vec![0.0f32; sweep.capture_length(latency)];
+        for (i, &s) in sweep.signal.iter().enumerate() {
+            recording[i + latency as usize] = s * 0.5;
+        }
+
+        let response = sweep.analyze(&recording, latency);
+        assert_eq!(response.frequencies.len(), RESPONSE_POINTS);
+        for i in (0..RESPONSE_POINTS).step_by(32) {
+            assert!(
+                (response.magnitude_db[i] + 6.02).abs() < 0.1,
+                "{} Hz: {} dB",
+                response.frequencies[i],
+                response.magnitude_db[i]
+            );
+            // Latency is compensated, so no linear phase
+            assert!(response.phase_degrees[i].abs() < 1.0, "{} Hz: {} deg", response.frequencies[i], response.phase_degrees[i]);
+        }
+    }
+}
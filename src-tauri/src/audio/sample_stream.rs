@@ -0,0 +1,281 @@
+//! Streaming sample playback for long files
+//!
+//! Instead of decoding the whole file up front, a background thread decodes a couple of
+//! seconds ahead into a ring buffer that the audio thread reads from. Loading returns as
+//! soon as the file is probed and memory stays bounded regardless of the file's length.
+//!
+//! Restarting (stop) bumps a generation counter: the decoder thread seeks back to the start
+//! and tags the frames it decodes afterwards with the new generation, so the reader can drop
+//! anything decoded before the restart. Looping is done by the decoder thread seeking back
+//! to the start when it reaches the end.
+
+use parking_lot::Mutex;
+use ringbuf::{traits::*, HeapRb};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::buffer::StereoSample;
+use super::samples::{OpenedTrack, SampleInfo};
+
+/// How far ahead the decoder runs
+const STREAM_BUFFER_SECS: usize = 2;
+
+/// How long the decoder thread waits when the buffer is full (or playback has ended)
+const STREAM_IDLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// finished_generation value while the decoder hasn't reached the end
+const NOT_FINISHED: u64 = u64::MAX;
+
+/// State shared between the player and the decoder thread
+struct StreamControl {
+    /// Bumped by the player to restart from the beginning
+    generation: AtomicU64,
+    looping: AtomicBool,
+    /// Generation whose audio has been decoded completely (not looping)
+    finished_generation: AtomicU64,
+    shutdown: AtomicBool,
+}
+
+/// What the stream did when asked for the next frame
+pub enum StreamStatus {
+    /// Moved to the next frame
+    Playing,
+    /// The decoder hasn't caught up yet
+    Buffering,
+    /// Past the last frame of the file
+    Ended,
+}
+
+/// Read side of a streamed sample, owned by the sample player
+pub struct SampleStream {
+    pub info: SampleInfo,
+    /// Only touched through &mut self (get_mut, no locking) - the Mutex makes the player Sync
+    consumer: Mutex<ringbuf::HeapCons<(u64, StereoSample)>>,
+    control: Arc<StreamControl>,
+    generation: u64,
+    /// Frame at the play position (None until the first frame after start/restart)
+    current: Option<StereoSample>,
+    /// Frame after it, if it has been decoded yet
+    next: Option<StereoSample>,
+}
+
+impl SampleStream {
+    /// Start decoding `track` on a background thread
+    pub fn start(track: OpenedTrack, looping: bool) -> Result<Self, String> {
+        let estimated_frames = track.n_frames.unwrap_or(0) as usize;
+        let info = track.info(estimated_frames, true);
+        let capacity = track.sample_rate.max(1) as usize * STREAM_BUFFER_SECS;
+        let (producer, consumer) = HeapRb::new(capacity).split();
+
+        let control = Arc::new(StreamControl {
+            generation: AtomicU64::new(0),
+            looping: AtomicBool::new(looping),
+            finished_generation: AtomicU64::new(NOT_FINISHED),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let thread_control = Arc::clone(&control);
+        let name = info.name.clone();
+        std::thread::Builder::new()
+            .name("sample-stream".to_string())
+            .spawn(move || {
+                run_decoder(track, producer, &thread_control);
+                log::debug!("Sample stream for '{}' stopped", name);
+            })
+            .map_err(|e| format!("Failed to start sample stream: {}", e))?;
+
+        log::info!(
+            "Streaming sample '{}' ({} Hz, {} channels, ~{:.1}s)",
+            info.name,
+            info.sample_rate,
+            info.channels,
+            info.duration_secs
+        );
+
+        Ok(Self {
+            info,
+            consumer: Mutex::new(consumer),
+            control,
+            generation: 0,
+            current: None,
+            next: None,
+        })
+    }
+
+    pub fn set_looping(&self, looping: bool) {
+        self.control.looping.store(looping, Ordering::Release);
+    }
+
+    /// Go back to the start of the file, dropping everything decoded ahead
+    pub fn restart(&mut self) {
+        self.generation = self.control.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        self.consumer.get_mut().clear();
+        self.current = None;
+        self.next = None;
+    }
+
+    /// The frame at the play position and the one after it (the same frame again if the
+    /// next one isn't decoded yet). None before the first frame after start/restart.
+    pub fn frames(&self) -> Option<(StereoSample, StereoSample)> {
+        self.current.map(|current| (current, self.next.unwrap_or(current)))
+    }
+
+    /// Move the play position forward one frame
+    pub fn advance(&mut self) -> StreamStatus {
+        // Read the flag before popping: once it's set, every frame has already been pushed
+        let finished = self.control.finished_generation.load(Ordering::Acquire) == self.generation;
+
+        match self.next.take().or_else(|| self.pop_frame()) {
+            Some(frame) => {
+                self.current = Some(frame);
+                self.next = self.pop_frame();
+                StreamStatus::Playing
+            }
+            None if finished => StreamStatus::Ended,
+            None => StreamStatus::Buffering,
+        }
+    }
+
+    /// Next frame of the current generation, skipping frames decoded before a restart
+    fn pop_frame(&mut self) -> Option<StereoSample> {
+        while let Some((generation, frame)) = self.consumer.get_mut().try_pop() {
+            if generation == self.generation {
+                return Some(frame);
+            }
+        }
+        None
+    }
+}
+
+impl Drop for SampleStream {
+    fn drop(&mut self) {
+        // The decoder thread exits on its next pass
+        self.control.shutdown.store(true, Ordering::Release);
+    }
+}
+
+/// Decoder thread: keep the ring buffer topped up until the stream is dropped
+fn run_decoder(
+    mut track: OpenedTrack,
+    mut producer: ringbuf::HeapProd<(u64, StereoSample)>,
+    control: &StreamControl,
+) {
+    let mut generation = control.generation.load(Ordering::Acquire);
+    let mut decoded: Vec<StereoSample> = Vec::new();
+    let mut pushed = 0;
+
+    while !control.shutdown.load(Ordering::Acquire) {
+        // Restart requested by the player
+        let requested = control.generation.load(Ordering::Acquire);
+        if requested != generation {
+            generation = requested;
+            decoded.clear();
+            pushed = 0;
+            if let Err(e) = track.rewind() {
+                log::error!("Sample stream failed to restart: {}", e);
+                control.finished_generation.store(generation, Ordering::Release);
+            }
+        }
+
+        // Hand over what's already decoded before decoding more
+        while pushed < decoded.len() && producer.try_push((generation, decoded[pushed])).is_ok() {
+            pushed += 1;
+        }
+        if pushed < decoded.len() {
+            std::thread::sleep(STREAM_IDLE_INTERVAL);
+            continue;
+        }
+
+        if control.finished_generation.load(Ordering::Acquire) == generation {
+            // Looping switched on after the end was reached
+            if control.looping.load(Ordering::Acquire) && track.rewind().is_ok() {
+                control.finished_generation.store(NOT_FINISHED, Ordering::Release);
+            } else {
+                std::thread::sleep(STREAM_IDLE_INTERVAL);
+            }
+            continue;
+        }
+
+        decoded.clear();
+        pushed = 0;
+        match track.decode_next(&mut decoded) {
+            Ok(true) => {}
+            Ok(false) => {
+                let rewound = control.looping.load(Ordering::Acquire) && track.rewind().is_ok();
+                if !rewound {
+                    control.finished_generation.store(generation, Ordering::Release);
+                }
+            }
+            Err(e) => {
+                log::error!("Sample stream decode failed: {}", e);
+                control.finished_generation.store(generation, Ordering::Release);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::samples::{SamplePlayer, SampleSource};
+    use std::path::Path;
+
+    fn stream_fixture(name: &str, looping: bool) -> SampleStream {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/audio/test_fixtures").join(name);
+        SampleStream::start(OpenedTrack::open(&path).unwrap(), looping).unwrap()
+    }
+
+    /// Pull frames from the player until it stops (or `limit` frames), waiting out buffering
+    fn play_frames(player: &mut SamplePlayer, limit: usize) -> Vec<StereoSample> {
+        let mut frames = Vec::new();
+        let mut idle_polls = 0;
+        while player.is_playing() && frames.len() < limit && idle_polls < 1000 {
+            let frame = player.next_sample();
+            if frame.left == 0.0 && frame.right == 0.0 {
+                // Buffering - give the decoder thread a moment
+                idle_polls += 1;
+                std::thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn test_stream_plays_file_once_then_restarts() {
+        let stream = stream_fixture("stereo_s16.aiff", false);
+        assert!(stream.info.streaming);
+        // Length comes from the container's estimate (AIFF counts the SSND header)
+        assert!(stream.info.num_samples >= 64);
+
+        let mut player = SamplePlayer::new();
+        player.set_looping(false);
+        player.load_sample(SampleSource::Stream(stream));
+        player.play();
+
+        let frames = play_frames(&mut player, 1000);
+        assert!(!player.is_playing());
+        assert_eq!(frames.len(), 64);
+        assert!(frames.iter().all(|f| f.left == 0.5 && f.right == -0.25));
+
+        // Stop rewinds the decoder
+        player.stop();
+        player.play();
+        assert_eq!(play_frames(&mut player, 1000).len(), 64);
+    }
+
+    #[test]
+    fn test_stream_loops_in_decoder() {
+        let mut player = SamplePlayer::new();
+        player.load_sample(SampleSource::Stream(stream_fixture("mono_s24.caf", true)));
+        player.play();
+
+        // 48 frame file, looped well past its length
+        let frames = play_frames(&mut player, 500);
+        assert!(player.is_playing());
+        assert_eq!(frames.len(), 500);
+        assert!(frames.iter().all(|f| f.left == -0.5));
+    }
+}
@@ -1,16 +1,33 @@
 //! Audio sample loading and playback using Symphonia
+//!
+//! Supported containers: WAV, AIFF/AIFF-C, CAF and MP3/AAC streams. Every format is
+//! converted to interleaved f32 stereo.
 
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use symphonia::core::audio::{AudioBufferRef, Signal};
-use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::FormatOptions;
+use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 
 use super::buffer::StereoSample;
+use super::sample_stream::{SampleStream, StreamStatus};
+
+/// Files longer than this are streamed from disk instead of decoded into memory
+const STREAMING_MIN_SECS: u64 = 60;
+
+/// Size used instead when the container doesn't report its length
+const STREAMING_MIN_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Length of the crossfade that smooths the wrap at the end of a loop region (frames)
+const LOOP_CROSSFADE_FRAMES: usize = 64;
+
+/// Range of the user-facing playback speed (1.0 = original speed and pitch)
+pub const MIN_PLAYBACK_SPEED: f32 = 0.25;
+pub const MAX_PLAYBACK_SPEED: f32 = 4.0;
 
 /// Information about a loaded sample
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,7 +37,10 @@ pub struct SampleInfo {
     pub sample_rate: u32,
     pub channels: u32,
     pub duration_secs: f32,
+    /// Length in frames (estimated from the container while streaming)
     pub num_samples: usize,
+    /// Decoded on the fly while playing instead of loaded into memory
+    pub streaming: bool,
 }
 
 /// A loaded audio sample ready for playback
@@ -31,9 +51,83 @@ pub struct AudioSample {
 }
 
 impl AudioSample {
-    /// Load an audio file from disk
+    /// Load an audio file from disk, decoding it completely
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        Self::decode(OpenedTrack::open(path.as_ref())?)
+    }
+
+    /// Decode an opened track completely
+    fn decode(mut track: OpenedTrack) -> Result<Self, String> {
+        // Decode all samples
+        let mut samples: Vec<StereoSample> = Vec::new();
+        while track.decode_next(&mut samples)? {}
+
+        Ok(Self {
+            info: track.info(samples.len(), false),
+            data: samples,
+        })
+    }
+
+    /// Get a sample at a given position (with optional interpolation)
+    pub fn get_sample(&self, position: usize) -> StereoSample {
+        if position < self.data.len() {
+            self.data[position]
+        } else {
+            StereoSample::silence()
+        }
+    }
+}
+
+/// Audio behind the sample player: decoded up front, or streamed for long files
+pub enum SampleSource {
+    Memory(AudioSample),
+    Stream(SampleStream),
+}
+
+impl SampleSource {
+    /// Open an audio file. Files longer than STREAMING_MIN_SECS are streamed so loading
+    /// doesn't stall and memory stays bounded; shorter ones are decoded into memory.
+    pub fn open<P: AsRef<Path>>(path: P, looping: bool) -> Result<Self, String> {
         let path = path.as_ref();
+        let track = OpenedTrack::open(path)?;
+        let is_long = match track.n_frames {
+            Some(frames) => frames > STREAMING_MIN_SECS * track.sample_rate as u64,
+            None => std::fs::metadata(path)
+                .map(|meta| meta.len() > STREAMING_MIN_BYTES)
+                .unwrap_or(false),
+        };
+
+        if is_long {
+            SampleStream::start(track, looping).map(Self::Stream)
+        } else {
+            AudioSample::decode(track).map(Self::Memory)
+        }
+    }
+
+    pub fn info(&self) -> &SampleInfo {
+        match self {
+            SampleSource::Memory(sample) => &sample.info,
+            SampleSource::Stream(stream) => &stream.info,
+        }
+    }
+}
+
+/// An audio file opened at its default track, ready to decode packet by packet
+pub struct OpenedTrack {
+    path: PathBuf,
+    name: String,
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    pub sample_rate: u32,
+    pub channels: u32,
+    /// Total frames, if the container reports it
+    pub n_frames: Option<u64>,
+}
+
+impl OpenedTrack {
+    /// Open and probe an audio file
+    pub fn open(path: &Path) -> Result<Self, String> {
         let name = path
             .file_stem()
             .and_then(|s| s.to_str())
@@ -46,7 +140,8 @@ impl AudioSample {
 
         // Create a hint to help the format registry
         let mut hint = Hint::new();
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        let extension = path.extension().and_then(|e| e.to_str());
+        if let Some(ext) = extension {
             hint.with_extension(ext);
         }
 
@@ -58,9 +153,12 @@ impl AudioSample {
                 &FormatOptions::default(),
                 &MetadataOptions::default(),
             )
-            .map_err(|e| format!("Failed to probe format: {}", e))?;
+            .map_err(|e| match extension {
+                Some(ext) => format!("Unsupported audio container (.{}): {}", ext, e),
+                None => format!("Unsupported audio container: {}", e),
+            })?;
 
-        let mut format = probed.format;
+        let format = probed.format;
 
         // Get the default track
         let track = format
@@ -77,25 +175,47 @@ impl AudioSample {
             .channels
             .map(|c| c.count() as u32)
             .unwrap_or(2);
+        let n_frames = track.codec_params.n_frames;
 
         // Create decoder
-        let mut decoder = symphonia::default::get_codecs()
+        let codecs = symphonia::default::get_codecs();
+        let decoder = codecs
             .make(&track.codec_params, &DecoderOptions::default())
-            .map_err(|e| format!("Failed to create decoder: {}", e))?;
+            .map_err(|e| {
+                let codec = codecs
+                    .get_codec(track.codec_params.codec)
+                    .map(|descriptor| descriptor.long_name.to_string())
+                    .unwrap_or_else(|| format!("codec id {}", track.codec_params.codec));
+                let container = extension.unwrap_or("unknown").to_uppercase();
+                format!("Unsupported codec in {} file ({}): {}", container, codec, e)
+            })?;
 
-        // Decode all samples
-        let mut samples: Vec<StereoSample> = Vec::new();
+        Ok(Self {
+            path: path.to_path_buf(),
+            name,
+            format,
+            decoder,
+            track_id,
+            sample_rate,
+            channels,
+            n_frames,
+        })
+    }
 
+    /// Decode the next packet of the track and append it to `output` as stereo.
+    /// Returns Ok(false) at the end of the stream.
+    pub fn decode_next(&mut self, output: &mut Vec<StereoSample>) -> Result<bool, String> {
         loop {
-            match format.next_packet() {
+            match self.format.next_packet() {
                 Ok(packet) => {
-                    if packet.track_id() != track_id {
+                    if packet.track_id() != self.track_id {
                         continue;
                     }
 
-                    match decoder.decode(&packet) {
+                    match self.decoder.decode(&packet) {
                         Ok(audio_buf) => {
-                            append_audio_buffer(&audio_buf, &mut samples, channels);
+                            append_audio_buffer(&audio_buf, output, self.channels);
+                            return Ok(true);
                         }
                         Err(symphonia::core::errors::Error::DecodeError(_)) => {
                             // Skip decode errors
@@ -110,35 +230,44 @@ impl AudioSample {
                     if e.kind() == std::io::ErrorKind::UnexpectedEof =>
                 {
                     // End of stream
-                    break;
+                    return Ok(false);
                 }
                 Err(e) => {
                     return Err(format!("Format error: {}", e));
                 }
             }
         }
+    }
 
-        let duration_secs = samples.len() as f32 / sample_rate as f32;
-
-        Ok(Self {
-            info: SampleInfo {
-                name,
-                path: path.to_string_lossy().to_string(),
-                sample_rate,
-                channels,
-                duration_secs,
-                num_samples: samples.len(),
-            },
-            data: samples,
-        })
+    /// Go back to the start of the track. Reopens the file if the format can't seek.
+    pub fn rewind(&mut self) -> Result<(), String> {
+        let seek = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::TimeStamp { ts: 0, track_id: self.track_id },
+        );
+        match seek {
+            Ok(_) => {
+                self.decoder.reset();
+                Ok(())
+            }
+            Err(e) => {
+                log::debug!("Seek failed ({}), reopening {:?}", e, self.path);
+                *self = Self::open(&self.path)?;
+                Ok(())
+            }
+        }
     }
 
-    /// Get a sample at a given position (with optional interpolation)
-    pub fn get_sample(&self, position: usize) -> StereoSample {
-        if position < self.data.len() {
-            self.data[position]
-        } else {
-            StereoSample::silence()
+    /// Sample info for this track holding `num_samples` frames
+    pub fn info(&self, num_samples: usize, streaming: bool) -> SampleInfo {
+        SampleInfo {
+            name: self.name.clone(),
+            path: self.path.to_string_lossy().to_string(),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            duration_secs: num_samples as f32 / self.sample_rate as f32,
+            num_samples,
+            streaming,
         }
     }
 }
@@ -283,15 +412,79 @@ fn append_audio_buffer(buf: &AudioBufferRef, output: &mut Vec<StereoSample>, cha
     }
 }
 
+/// Part of an in-memory sample that repeats while looping, in frames (end exclusive)
+///
+/// The wrap is crossfaded over `fade` frames so the jump doesn't click. Material from just
+/// before the region is faded in over its last frames when there is some; otherwise (a region
+/// at the start of the file) the audio just past the region end is faded out over its first
+/// frames after each wrap. Either way the loop stays exactly `end - start` frames long.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LoopRegion {
+    start: usize,
+    end: usize,
+    fade: usize,
+    /// Fade in the pre-roll before `end` (true) or fade out the tail after `start` (false)
+    fade_before_end: bool,
+}
+
+impl LoopRegion {
+    /// Region clamped to a sample of `total` frames (None if nothing is left of it)
+    fn new(start: usize, end: usize, total: usize) -> Option<Self> {
+        let (start, end) = (start.min(end).min(total), start.max(end).min(total));
+        if start == end {
+            return None;
+        }
+        let max_fade = LOOP_CROSSFADE_FRAMES.min((end - start) / 2);
+        let pre_roll = start.min(max_fade);
+        let tail = (total - end).min(max_fade);
+        Some(Self {
+            start,
+            end,
+            fade: pre_roll.max(tail),
+            fade_before_end: pre_roll >= tail,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Frame to blend with the one at `position`, and its weight (0.0 - 1.0)
+    fn crossfade(&self, position: usize, frac: f32, wrapped: bool) -> Option<(usize, f32)> {
+        if self.fade == 0 {
+            return None;
+        }
+        let fade = self.fade as f32;
+        if self.fade_before_end {
+            let fade_start = self.end - self.fade;
+            (position >= fade_start && position < self.end).then(|| {
+                let k = position - fade_start;
+                (self.start - self.fade + k, (k as f32 + frac) / fade)
+            })
+        } else {
+            (wrapped && position >= self.start && position < self.start + self.fade).then(|| {
+                let k = position - self.start;
+                (self.end + k, 1.0 - (k as f32 + frac) / fade)
+            })
+        }
+    }
+}
+
 /// Sample player that handles playback position and looping
 pub struct SamplePlayer {
-    sample: Option<AudioSample>,
+    sample: Option<SampleSource>,
     position: usize,
     is_playing: bool,
     is_looping: bool,
     /// Playback speed ratio (for resampling)
     speed_ratio: f32,
+    /// Varispeed set by the user, on top of speed_ratio (changes pitch and tempo together)
+    playback_speed: f32,
     fractional_position: f32,
+    /// Repeated part of an in-memory sample while looping (the whole file if None)
+    loop_region: Option<LoopRegion>,
+    /// Whether playback has wrapped at the region end since it started
+    loop_wrapped: bool,
 }
 
 impl SamplePlayer {
@@ -302,20 +495,68 @@ impl SamplePlayer {
             is_playing: false,
             is_looping: true,
             speed_ratio: 1.0,
+            playback_speed: 1.0,
             fractional_position: 0.0,
+            loop_region: None,
+            loop_wrapped: false,
         }
     }
 
-    pub fn load_sample(&mut self, sample: AudioSample) {
+    pub fn load_sample(&mut self, sample: SampleSource) {
+        if let SampleSource::Stream(stream) = &sample {
+            stream.set_looping(self.is_looping);
+        }
         self.sample = Some(sample);
         self.position = 0;
         self.fractional_position = 0.0;
+        self.loop_region = None;
+        self.loop_wrapped = false;
     }
 
     pub fn unload(&mut self) {
         self.sample = None;
         self.position = 0;
         self.is_playing = false;
+        self.loop_region = None;
+        self.loop_wrapped = false;
+    }
+
+    /// Loop only frames `start_sample..end_sample` (in the sample's own frames) while looping
+    /// is on. The region is clamped to the sample; one with nothing left in it is cleared.
+    /// Streamed samples always loop whole - their decoder can't jump back cheaply.
+    pub fn set_loop_region(&mut self, start_sample: usize, end_sample: usize) -> Result<(), String> {
+        let total = match &self.sample {
+            Some(SampleSource::Memory(sample)) => sample.data.len(),
+            Some(SampleSource::Stream(_)) => {
+                return Err("Loop regions aren't supported for streamed (long) samples".to_string())
+            }
+            None => return Err("No sample loaded".to_string()),
+        };
+        self.loop_region = LoopRegion::new(start_sample, end_sample, total);
+        self.loop_wrapped = false;
+        // Start auditioning the region right away
+        if let Some(region) = self.loop_region {
+            if self.is_looping && !(region.start..region.end).contains(&self.position) {
+                self.position = region.start;
+                self.fractional_position = 0.0;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear_loop_region(&mut self) {
+        self.loop_region = None;
+        self.loop_wrapped = false;
+    }
+
+    /// Current loop region as (start, end) frames
+    pub fn loop_region(&self) -> Option<(usize, usize)> {
+        self.loop_region.map(|region| (region.start, region.end))
+    }
+
+    /// Play position in the sample's frames
+    pub fn position(&self) -> usize {
+        self.position
     }
 
     pub fn play(&mut self) {
@@ -324,8 +565,15 @@ impl SamplePlayer {
 
     pub fn stop(&mut self) {
         self.is_playing = false;
-        self.position = 0;
+        self.position = match self.loop_region {
+            Some(region) if self.is_looping => region.start,
+            _ => 0,
+        };
         self.fractional_position = 0.0;
+        self.loop_wrapped = false;
+        if let Some(SampleSource::Stream(stream)) = &mut self.sample {
+            stream.restart();
+        }
     }
 
     pub fn pause(&mut self) {
@@ -334,12 +582,32 @@ impl SamplePlayer {
 
     pub fn set_looping(&mut self, looping: bool) {
         self.is_looping = looping;
+        if let Some(SampleSource::Stream(stream)) = &self.sample {
+            stream.set_looping(looping);
+        }
     }
 
     pub fn set_speed_ratio(&mut self, ratio: f32) {
         self.speed_ratio = ratio.max(0.1).min(4.0);
     }
 
+    /// Speed playback up or slow it down (clamped to MIN/MAX_PLAYBACK_SPEED). Kept across
+    /// sample loads; the play position carries on from where it is.
+    pub fn set_playback_speed(&mut self, speed: f32) {
+        if speed.is_finite() {
+            self.playback_speed = speed.clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED);
+        }
+    }
+
+    pub fn playback_speed(&self) -> f32 {
+        self.playback_speed
+    }
+
+    /// Source frames to advance per output frame
+    fn step(&self) -> f32 {
+        self.speed_ratio * self.playback_speed
+    }
+
     pub fn is_playing(&self) -> bool {
         self.is_playing
     }
@@ -348,39 +616,66 @@ impl SamplePlayer {
         self.sample.is_some()
     }
 
+    /// Info about the loaded sample
+    pub fn info(&self) -> Option<&SampleInfo> {
+        self.sample.as_ref().map(|sample| sample.info())
+    }
+
     /// Get the next sample (with resampling if needed)
     pub fn next_sample(&mut self) -> StereoSample {
         if !self.is_playing {
             return StereoSample::silence();
         }
 
-        let sample = match &self.sample {
-            Some(s) => s,
-            None => return StereoSample::silence(),
+        match &self.sample {
+            Some(SampleSource::Memory(_)) => self.next_memory_sample(),
+            Some(SampleSource::Stream(_)) => self.next_streamed_sample(),
+            None => StereoSample::silence(),
+        }
+    }
+
+    fn next_memory_sample(&mut self) -> StereoSample {
+        let Some(SampleSource::Memory(sample)) = &self.sample else {
+            return StereoSample::silence();
         };
 
-        if self.position >= sample.data.len() {
-            if self.is_looping {
-                self.position = 0;
-                self.fractional_position = 0.0;
-            } else {
-                self.is_playing = false;
-                return StereoSample::silence();
+        let region = self.loop_region.filter(|_| self.is_looping);
+        match region {
+            Some(region) if self.position >= region.end => {
+                self.position = region.start + (self.position - region.end) % region.len();
+                self.loop_wrapped = true;
             }
+            None if self.position >= sample.data.len() => {
+                if self.is_looping {
+                    self.position = 0;
+                    self.fractional_position = 0.0;
+                } else {
+                    self.is_playing = false;
+                    return StereoSample::silence();
+                }
+            }
+            _ => {}
         }
 
         // Linear interpolation for resampling
-        let current = sample.get_sample(self.position);
-        let next = sample.get_sample(self.position + 1);
-        let frac = self.fractional_position;
-
-        let interpolated = StereoSample::new(
-            current.left * (1.0 - frac) + next.left * frac,
-            current.right * (1.0 - frac) + next.right * frac,
-        );
+        let frame_at = |position: usize, frac: f32| {
+            interpolate(sample.get_sample(position), sample.get_sample(position + 1), frac)
+        };
+        let mut interpolated = frame_at(self.position, self.fractional_position);
+
+        // Smooth the jump at the loop region boundary
+        if let Some((other, weight)) =
+            region.and_then(|r| r.crossfade(self.position, self.fractional_position, self.loop_wrapped))
+        {
+            let blended = frame_at(other, self.fractional_position);
+            interpolated = StereoSample::new(
+                interpolated.left * (1.0 - weight) + blended.left * weight,
+                interpolated.right * (1.0 - weight) + blended.right * weight,
+            );
+        }
 
         // Advance position
-        self.fractional_position += self.speed_ratio;
+        self.fractional_position += self.step();
         while self.fractional_position >= 1.0 {
             self.fractional_position -= 1.0;
             self.position += 1;
@@ -389,6 +684,48 @@ impl SamplePlayer {
         interpolated
     }
 
+    /// Streaming counterpart of next_memory_sample - looping happens in the decoder thread
+    fn next_streamed_sample(&mut self) -> StereoSample {
+        let step = self.step();
+        let Some(SampleSource::Stream(stream)) = &mut self.sample else {
+            return StereoSample::silence();
+        };
+
+        // First frame after load/restart
+        if stream.frames().is_none() {
+            match stream.advance() {
+                StreamStatus::Playing => {}
+                StreamStatus::Buffering => return StereoSample::silence(),
+                StreamStatus::Ended => {
+                    self.is_playing = false;
+                    return StereoSample::silence();
+                }
+            }
+        }
+
+        let (current, next) = stream.frames().unwrap_or_default();
+        let interpolated = interpolate(current, next, self.fractional_position);
+
+        self.fractional_position += step;
+        while self.fractional_position >= 1.0 {
+            self.fractional_position -= 1.0;
+            match stream.advance() {
+                StreamStatus::Playing => {}
+                StreamStatus::Buffering => {
+                    // Decoder fell behind - hold this frame until it catches up
+                    self.fractional_position = 0.0;
+                    break;
+                }
+                StreamStatus::Ended => {
+                    self.is_playing = false;
+                    break;
+                }
+            }
+        }
+
+        interpolated
+    }
+
     /// Fill a buffer with samples
     pub fn fill_buffer(&mut self, buffer: &mut [StereoSample]) {
         for sample in buffer.iter_mut() {
@@ -397,8 +734,176 @@ impl SamplePlayer {
     }
 }
 
+/// Linear interpolation between two frames (frac 0.0 - 1.0)
+fn interpolate(current: StereoSample, next: StereoSample, frac: f32) -> StereoSample {
+    StereoSample::new(
+        current.left * (1.0 - frac) + next.left * frac,
+        current.right * (1.0 - frac) + next.right * frac,
+    )
+}
+
 impl Default for SamplePlayer {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_fixture(name: &str) -> AudioSample {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/audio/test_fixtures").join(name);
+        AudioSample::load(&path).unwrap_or_else(|e| panic!("{}: {}", name, e))
+    }
+
+    #[test]
+    fn test_load_aiff_big_endian_pcm() {
+        let sample = load_fixture("stereo_s16.aiff");
+        assert_eq!(sample.info.sample_rate, 44100);
+        assert_eq!(sample.info.channels, 2);
+        assert_eq!(sample.info.num_samples, 64);
+        // Big-endian 16384 / -8192 on left / right
+        assert!(sample.data.iter().all(|s| s.left == 0.5 && s.right == -0.25));
+    }
+
+    #[test]
+    fn test_load_aifc_ulaw() {
+        let sample = load_fixture("mono_ulaw.aifc");
+        assert_eq!(sample.info.sample_rate, 22050);
+        assert_eq!(sample.info.channels, 1);
+        assert_eq!(sample.info.num_samples, 32);
+    }
+
+    #[test]
+    fn test_load_caf_pcm() {
+        let sample = load_fixture("mono_s24.caf");
+        assert_eq!(sample.info.sample_rate, 48000);
+        assert_eq!(sample.info.channels, 1);
+        assert_eq!(sample.info.num_samples, 48);
+        // Mono is copied to both sides
+        assert!(sample.data.iter().all(|s| s.left == -0.5 && s.right == -0.5));
+    }
+
+    /// Player with a rising ramp (0.0 up to just under 1.0) loaded in memory
+    fn ramp_player(frames: usize) -> SamplePlayer {
+        let data: Vec<StereoSample> = (0..frames)
+            .map(|i| StereoSample::new(i as f32 / frames as f32, -(i as f32) / frames as f32))
+            .collect();
+        let info = SampleInfo {
+            name: "ramp".to_string(),
+            path: String::new(),
+            sample_rate: 48000,
+            channels: 2,
+            duration_secs: frames as f32 / 48000.0,
+            num_samples: frames,
+            streaming: false,
+        };
+        let mut player = SamplePlayer::new();
+        player.load_sample(SampleSource::Memory(AudioSample { info, data }));
+        player
+    }
+
+    /// Largest jump between consecutive output frames
+    fn largest_step(player: &mut SamplePlayer, frames: usize) -> f32 {
+        let output: Vec<StereoSample> = (0..frames).map(|_| player.next_sample()).collect();
+        output
+            .windows(2)
+            .map(|pair| (pair[1].left - pair[0].left).abs().max((pair[1].right - pair[0].right).abs()))
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_loop_region_wraps_with_crossfade() {
+        let mut player = ramp_player(1000);
+        player.set_loop_region(200, 400).unwrap();
+        assert_eq!(player.loop_region(), Some((200, 400)));
+        // Playback jumps into the region
+        assert_eq!(player.position(), 200);
+        player.play();
+
+        for _ in 0..200 {
+            player.next_sample();
+        }
+        assert_eq!(player.position(), 400);
+        player.next_sample();
+        assert_eq!(player.position(), 201);
+
+        // The ramp drops by 0.2 at the loop point; crossfaded it never steps more than a
+        // fraction of that (the ramp itself rises 0.001 per frame)
+        assert!(largest_step(&mut player, 1000) < 0.01);
+
+        // A region at the start of the file fades out the audio after its end instead
+        player.set_loop_region(0, 300).unwrap();
+        player.stop();
+        player.play();
+        assert!(largest_step(&mut player, 1000) < 0.01);
+        assert_eq!(player.loop_region(), Some((0, 300)));
+
+        // Not looping: plays through the region to the end of the file
+        player.set_looping(false);
+        let mut frames = 0;
+        while player.is_playing() && frames < 2000 {
+            player.next_sample();
+            frames += 1;
+        }
+        assert!(!player.is_playing());
+        assert!(frames > 300);
+    }
+
+    #[test]
+    fn test_playback_speed_keeps_position_and_stays_smooth() {
+        let mut player = ramp_player(1000);
+        player.set_looping(false);
+        player.play();
+        assert!(largest_step(&mut player, 100) < 0.0011);
+        assert_eq!(player.position(), 100);
+
+        // Twice as fast from where it was: 100 output frames cover 200 source frames
+        player.set_playback_speed(2.0);
+        assert_eq!(player.position(), 100);
+        let step = largest_step(&mut player, 100);
+        assert_eq!(player.position(), 300);
+        assert!(step < 0.0021, "step {}", step);
+
+        // Rate matching and speed multiply; half speed on a 2x rate ratio is original speed
+        player.set_speed_ratio(2.0);
+        player.set_playback_speed(0.5);
+        for _ in 0..10 {
+            player.next_sample();
+        }
+        assert_eq!(player.position(), 310);
+
+        player.set_playback_speed(10.0);
+        assert_eq!(player.playback_speed(), MAX_PLAYBACK_SPEED);
+        player.set_playback_speed(f32::NAN);
+        assert_eq!(player.playback_speed(), MAX_PLAYBACK_SPEED);
+        player.set_playback_speed(0.0);
+        assert_eq!(player.playback_speed(), MIN_PLAYBACK_SPEED);
+    }
+
+    #[test]
+    fn test_loop_region_is_clamped_and_cleared() {
+        let mut player = ramp_player(1000);
+        player.set_loop_region(900, 5000).unwrap();
+        assert_eq!(player.loop_region(), Some((900, 1000)));
+        player.set_loop_region(500, 100).unwrap();
+        assert_eq!(player.loop_region(), Some((100, 500)));
+        player.set_loop_region(2000, 3000).unwrap();
+        assert_eq!(player.loop_region(), None);
+
+        player.set_loop_region(100, 500).unwrap();
+        player.unload();
+        assert_eq!(player.loop_region(), None);
+        assert!(player.set_loop_region(100, 500).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_container_is_named() {
+        let path = std::env::temp_dir().join(format!("freqlab_not_audio_{}.xyz", std::process::id()));
+        std::fs::write(&path, b"definitely not audio").unwrap();
+        let error = AudioSample::load(&path).err().unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(error.contains("Unsupported audio container (.xyz)"), "{}", error);
+    }
+}
@@ -14,8 +14,23 @@ pub enum SignalType {
     Square,
     WhiteNoise,
     PinkNoise,
+    /// Brownian (red) noise, -6 dB/octave
+    BrownNoise,
     Impulse,
     Sweep,
+    /// One-shot burst (see BurstShape), silent until triggered
+    Burst,
+}
+
+/// What a burst plays each time it's triggered
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum BurstShape {
+    /// A single full-scale (1.0) sample - a Dirac impulse, regardless of amplitude
+    #[default]
+    Impulse,
+    /// `cycles` cycles of a sine at the signal frequency, starting at a zero crossing
+    ToneBurst { cycles: u32 },
 }
 
 /// Gate/pulse pattern for test signals
@@ -50,6 +65,10 @@ pub struct SignalConfig {
     pub gate_pattern: GatePattern,
     pub gate_rate: f32,        // Hz for Pulse mode, BPM for musical divisions
     pub gate_duty: f32,        // 0.0 - 1.0, portion of cycle that's "on"
+    #[serde(default)]
+    pub gate_sync: bool,       // musical divisions follow the transport tempo and position
+    #[serde(default)]
+    pub burst: BurstShape,     // for burst
 }
 
 impl Default for SignalConfig {
@@ -64,6 +83,8 @@ impl Default for SignalConfig {
             gate_pattern: GatePattern::Continuous,
             gate_rate: 2.0,    // 2 Hz default for pulse mode
             gate_duty: 0.5,    // 50% duty cycle
+            gate_sync: false,
+            burst: BurstShape::Impulse,
         }
     }
 }
@@ -79,6 +100,12 @@ pub struct SignalGenerator {
     pink_rows: [f32; 16],
     pink_running_sum: f32,
     pink_index: usize,
+    // Brown noise integrator state
+    brown_last: f32,
+    // Samples played of the current burst (None while idle)
+    burst_position: Option<u32>,
+    // Transport tempo used by a synced gate
+    transport_bpm: f32,
 }
 
 impl SignalGenerator {
@@ -92,22 +119,66 @@ impl SignalGenerator {
             pink_rows: [0.0; 16],
             pink_running_sum: 0.0,
             pink_index: 0,
+            brown_last: 0.0,
+            burst_position: None,
+            transport_bpm: 120.0,
         }
     }
 
+    pub fn config(&self) -> &SignalConfig {
+        &self.config
+    }
+
     pub fn set_config(&mut self, config: SignalConfig) {
         self.config = config;
         // Reset state for new signal
         self.phase = 0.0;
         self.sweep_phase = 0.0;
         self.gate_phase = 0.0;
+        self.burst_position = None;
+    }
+
+    /// Start the burst from the beginning (also while one is still playing)
+    pub fn trigger_burst(&mut self) {
+        self.burst_position = Some(0);
+    }
+
+    /// Whether a triggered burst hasn't finished yet
+    pub fn is_burst_active(&self) -> bool {
+        self.burst_position.is_some()
     }
 
+    /// Free-running gate: musical divisions use gate_rate as their BPM
     pub fn set_gate_pattern(&mut self, pattern: GatePattern) {
         self.config.gate_pattern = pattern;
+        self.config.gate_sync = false;
+        self.gate_phase = 0.0;
+    }
+
+    /// Sync the gate to the transport on a musical division (quarter, eighth or sixteenth),
+    /// or go back to the free-running gate with None
+    pub fn set_gate_sync(&mut self, division: Option<GatePattern>) {
+        match division {
+            Some(pattern) => {
+                self.config.gate_pattern = pattern;
+                self.config.gate_sync = true;
+            }
+            None => self.config.gate_sync = false,
+        }
         self.gate_phase = 0.0;
     }
 
+    /// Follow the transport: `position` is the play position in frames at the start of
+    /// the next block. A synced gate jumps to the matching point of its cycle.
+    pub fn sync_to_transport(&mut self, bpm: f32, position: u64) {
+        self.transport_bpm = bpm;
+        if self.config.gate_sync {
+            if let Some(cycle_samples) = self.musical_cycle_samples() {
+                self.gate_phase = (position as f64 % cycle_samples as f64) as f32;
+            }
+        }
+    }
+
     pub fn set_gate_rate(&mut self, rate: f32) {
         self.config.gate_rate = rate.max(0.1);
     }
@@ -132,8 +203,11 @@ impl SignalGenerator {
             SignalType::Square => self.generate_square(),
             SignalType::WhiteNoise => self.generate_white_noise(),
             SignalType::PinkNoise => self.generate_pink_noise(),
+            SignalType::BrownNoise => self.generate_brown_noise(),
             SignalType::Impulse => self.generate_impulse(),
             SignalType::Sweep => self.generate_sweep(),
+            // One-shot, so not gated (the level is applied per shape)
+            SignalType::Burst => return StereoSample::mono(self.generate_burst()),
         };
 
         // Apply gating
@@ -163,20 +237,7 @@ impl SignalGenerator {
                 }
             }
             GatePattern::Quarter | GatePattern::Eighth | GatePattern::Sixteenth => {
-                // gate_rate is BPM
-                let bpm = self.config.gate_rate.max(20.0);
-                let beats_per_second = bpm / 60.0;
-
-                // Subdivide based on pattern
-                let subdivisions = match self.config.gate_pattern {
-                    GatePattern::Quarter => 1.0,
-                    GatePattern::Eighth => 2.0,
-                    GatePattern::Sixteenth => 4.0,
-                    _ => 1.0,
-                };
-
-                let notes_per_second = beats_per_second * subdivisions;
-                let cycle_samples = self.sample_rate / notes_per_second;
+                let cycle_samples = self.musical_cycle_samples().unwrap_or(self.sample_rate);
                 let position_in_cycle = self.gate_phase / cycle_samples;
 
                 self.gate_phase += 1.0;
@@ -193,6 +254,23 @@ impl SignalGenerator {
         }
     }
 
+    /// Length of one note of the musical gate division in samples (None for other patterns)
+    fn musical_cycle_samples(&self) -> Option<f32> {
+        // Subdivide based on pattern
+        let subdivisions = match self.config.gate_pattern {
+            GatePattern::Quarter => 1.0,
+            GatePattern::Eighth => 2.0,
+            GatePattern::Sixteenth => 4.0,
+            GatePattern::Continuous | GatePattern::Pulse => return None,
+        };
+
+        // BPM from the transport when synced, gate_rate otherwise
+        let bpm = if self.config.gate_sync { self.transport_bpm } else { self.config.gate_rate };
+        let beats_per_second = bpm.max(20.0) / 60.0;
+        let notes_per_second = beats_per_second * subdivisions;
+        Some(self.sample_rate / notes_per_second)
+    }
+
     /// Fill a buffer with samples
     pub fn fill_buffer(&mut self, buffer: &mut [StereoSample]) {
         for sample in buffer.iter_mut() {
@@ -244,6 +322,20 @@ impl SignalGenerator {
         (self.pink_running_sum + white) / 5.0
     }
 
+    fn generate_brown_noise(&mut self) -> f32 {
+        // Leaky integrator of white noise: the leak pulls the state back towards zero,
+        // so unlike a pure integrator it can't drift off into DC however long it runs
+        let mut rng = rand::thread_rng();
+        let white: f32 = rng.gen_range(-1.0..1.0);
+        self.brown_last = (self.brown_last + 0.02 * white) / 1.02;
+        if !self.brown_last.is_finite() {
+            self.brown_last = 0.0;
+        }
+
+        // Scale up to roughly the level of the other noise types
+        (self.brown_last * 3.5).clamp(-1.0, 1.0)
+    }
+
     fn generate_impulse(&mut self) -> f32 {
         // Repeating impulse at the configured frequency (impulses per second)
         // e.g., frequency=1 means 1 impulse per second, frequency=4 means 4 per second
@@ -260,6 +352,27 @@ impl SignalGenerator {
         }
     }
 
+    /// Next burst sample with the level applied (0.0 when idle or finished)
+    fn generate_burst(&mut self) -> f32 {
+        let Some(position) = self.burst_position else {
+            return 0.0;
+        };
+
+        let (sample, length) = match self.config.burst {
+            // Exactly full scale, so the output is the plugin's impulse response as is
+            BurstShape::Impulse => (1.0, 1),
+            BurstShape::ToneBurst { cycles } => {
+                let frequency = self.config.frequency.max(1.0);
+                let length = (cycles.max(1) as f32 * self.sample_rate / frequency).round() as u32;
+                let sample = (2.0 * PI * frequency * position as f32 / self.sample_rate).sin();
+                (sample * self.config.amplitude, length.max(1))
+            }
+        };
+
+        self.burst_position = (position + 1 < length).then_some(position + 1);
+        sample
+    }
+
     fn generate_sweep(&mut self) -> f32 {
         // Logarithmic frequency sweep
         let t = self.sweep_phase / self.sample_rate;
@@ -291,6 +404,7 @@ impl SignalGenerator {
         self.phase = 0.0;
         self.sweep_phase = 0.0;
         self.gate_phase = 0.0;
+        self.burst_position = None;
     }
 }
 
@@ -315,6 +429,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_impulse_burst_is_one_full_scale_sample() {
+        let mut gen = SignalGenerator::new(48000);
+        gen.set_config(SignalConfig {
+            signal_type: SignalType::Burst,
+            amplitude: 0.25,
+            ..Default::default()
+        });
+
+        // Silent until triggered
+        assert!((0..100).all(|_| gen.next_sample().left == 0.0));
+
+        for _ in 0..2 {
+            gen.trigger_burst();
+            let samples: Vec<f32> = (0..100).map(|_| gen.next_sample().left).collect();
+            assert_eq!(samples[0], 1.0);
+            assert!(samples[1..].iter().all(|&s| s == 0.0));
+            assert!(!gen.is_burst_active());
+        }
+    }
+
+    #[test]
+    fn test_tone_burst_plays_whole_cycles() {
+        let mut gen = SignalGenerator::new(48000);
+        gen.set_config(SignalConfig {
+            signal_type: SignalType::Burst,
+            frequency: 1000.0,
+            amplitude: 1.0,
+            burst: BurstShape::ToneBurst { cycles: 3 },
+            ..Default::default()
+        });
+        gen.trigger_burst();
+
+        // 3 cycles of 48 samples, then silence
+        let samples: Vec<f32> = (0..200).map(|_| gen.next_sample().left).collect();
+        assert_eq!(samples[0], 0.0);
+        assert!((samples[12] - 1.0).abs() < 1e-4);
+        assert!(samples[..144].iter().any(|&s| s < -0.99));
+        assert!(samples[144..].iter().all(|&s| s == 0.0));
+
+        // Retriggering mid-burst starts over
+        gen.trigger_burst();
+        gen.next_sample();
+        gen.trigger_burst();
+        assert_eq!(gen.next_sample().left, 0.0);
+        assert!(gen.is_burst_active());
+    }
+
+    #[test]
+    fn test_synced_gate_follows_transport() {
+        let mut gen = SignalGenerator::new(48000);
+        gen.set_config(SignalConfig {
+            signal_type: SignalType::Square,
+            frequency: 100.0,
+            amplitude: 1.0,
+            gate_duty: 0.5,
+            ..Default::default()
+        });
+        gen.set_gate_sync(Some(GatePattern::Sixteenth));
+
+        // Sixteenths at 120 BPM are 6000 samples: halfway into one the gate is closed...
+        gen.sync_to_transport(120.0, 6000 * 7 + 3000);
+        assert!((0..3000).all(|_| gen.next_sample().left == 0.0));
+        // ...and it opens on the next sixteenth
+        assert_ne!(gen.next_sample().left, 0.0);
+
+        // At 60 BPM a sixteenth is 12000 samples, so 42000 is halfway into one
+        gen.sync_to_transport(120.0, 42000);
+        assert_ne!(gen.next_sample().left, 0.0);
+        gen.sync_to_transport(60.0, 42000);
+        assert_eq!(gen.next_sample().left, 0.0);
+
+        // Back to free running: gate_rate is the BPM again and the transport is ignored
+        gen.set_gate_pattern(GatePattern::Sixteenth);
+        gen.set_gate_rate(240.0);
+        gen.sync_to_transport(60.0, 3000);
+        assert_ne!(gen.next_sample().left, 0.0);
+        assert!(!gen.config().gate_sync);
+    }
+
+    #[test]
+    fn test_noise_long_term_level() {
+        for signal_type in [SignalType::PinkNoise, SignalType::BrownNoise] {
+            let mut gen = SignalGenerator::new(48000);
+            gen.set_config(SignalConfig {
+                signal_type,
+                amplitude: 1.0,
+                ..Default::default()
+            });
+
+            // Two minutes at 48 kHz, with the level checked one second at a time
+            let mut total = 0.0f64;
+            for _ in 0..120 {
+                let (mut sum_squares, mut sum) = (0.0f64, 0.0f64);
+                for _ in 0..48000 {
+                    let sample = gen.next_sample().left;
+                    assert!(sample.is_finite(), "{:?} produced {}", signal_type, sample);
+                    sum_squares += (sample as f64).powi(2);
+                    sum += sample as f64;
+                }
+                let rms = (sum_squares / 48000.0).sqrt();
+                assert!(rms > 0.05 && rms < 0.8, "{:?} RMS {} out of range", signal_type, rms);
+                total += sum;
+            }
+            // No DC drift over the whole run
+            let mean = total / (120.0 * 48000.0);
+            assert!(mean.abs() < 0.1, "{:?} drifted to {}", signal_type, mean);
+        }
+    }
+
     #[test]
     fn test_white_noise_range() {
         let mut gen = SignalGenerator::new(44100);
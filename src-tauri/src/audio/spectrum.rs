@@ -6,8 +6,20 @@ use std::sync::Arc;
 /// Number of frequency bands for visualization
 pub const NUM_BANDS: usize = 32;
 
-/// FFT size (must be power of 2)
-const FFT_SIZE: usize = 2048;
+/// Default FFT size (must be power of 2)
+pub const FFT_SIZE: usize = 2048;
+
+/// Smallest FFT size the analyzer can be switched to
+pub const MIN_FFT_SIZE: usize = 256;
+
+/// Largest FFT size the analyzer can be switched to
+pub const MAX_FFT_SIZE: usize = 16384;
+
+/// Lowest band frequency (10Hz for sub-bass visibility, can't use 0 on log scale)
+const MIN_BAND_FREQ: f32 = 10.0;
+
+/// Highest band frequency (capped at Nyquist for low sample rates)
+const MAX_BAND_FREQ: f32 = 20000.0;
 
 /// Spectrum analyzer using FFT
 pub struct SpectrumAnalyzer {
@@ -18,46 +30,43 @@ pub struct SpectrumAnalyzer {
     windowed_buffer: Vec<f32>,
     window: Vec<f32>,
     sample_rate: u32,
+    fft_size: usize,
     write_pos: usize,
     /// Band magnitudes (0.0 - 1.0, linear)
     band_magnitudes: [f32; NUM_BANDS],
     /// Band frequencies (center frequency of each band)
     band_frequencies: [f32; NUM_BANDS],
+    /// Band edges (low, high) in Hz - each band's peak is taken over this range
+    band_edges: [(f32, f32); NUM_BANDS],
     /// Smoothing factor for band magnitudes
     smoothing: f32,
 }
 
 impl SpectrumAnalyzer {
     pub fn new(sample_rate: u32) -> Self {
+        Self::with_fft_size(sample_rate, FFT_SIZE)
+    }
+
+    /// Analyzer with a specific FFT size. Larger sizes resolve low frequencies better but
+    /// react more slowly. Sizes that aren't a supported power of 2 are rounded up to one.
+    pub fn with_fft_size(sample_rate: u32, fft_size: usize) -> Self {
+        let fft_size = fft_size.clamp(MIN_FFT_SIZE, MAX_FFT_SIZE).next_power_of_two();
         let mut planner = RealFftPlanner::<f32>::new();
-        let fft = planner.plan_fft_forward(FFT_SIZE);
+        let fft = planner.plan_fft_forward(fft_size);
 
-        let input_buffer = vec![0.0f32; FFT_SIZE];
+        let input_buffer = vec![0.0f32; fft_size];
         let spectrum_buffer = fft.make_output_vec();
         // Pre-allocate scratch buffer for windowed samples
-        let windowed_buffer = vec![0.0f32; FFT_SIZE];
+        let windowed_buffer = vec![0.0f32; fft_size];
 
         // Create Hann window for smooth frequency response
-        let window: Vec<f32> = (0..FFT_SIZE)
+        let window: Vec<f32> = (0..fft_size)
             .map(|i| {
-                let x = i as f32 / FFT_SIZE as f32;
+                let x = i as f32 / fft_size as f32;
                 0.5 * (1.0 - (2.0 * std::f32::consts::PI * x).cos())
             })
             .collect();
 
-        // Calculate logarithmically spaced band frequencies
-        // Range: 10Hz to 20kHz (10Hz for sub-bass visibility, can't use 0 on log scale)
-        let min_freq = 10.0f32;
-        let max_freq = 20000.0f32.min(sample_rate as f32 / 2.0);
-        let log_min = min_freq.ln();
-        let log_max = max_freq.ln();
-
-        let mut band_frequencies = [0.0f32; NUM_BANDS];
-        for i in 0..NUM_BANDS {
-            let t = i as f32 / (NUM_BANDS - 1) as f32;
-            band_frequencies[i] = (log_min + t * (log_max - log_min)).exp();
-        }
-
         Self {
             fft,
             input_buffer,
@@ -65,19 +74,69 @@ impl SpectrumAnalyzer {
             windowed_buffer,
             window,
             sample_rate,
+            fft_size,
             write_pos: 0,
             band_magnitudes: [0.0; NUM_BANDS],
-            band_frequencies,
+            band_frequencies: Self::band_frequencies(sample_rate),
+            band_edges: Self::band_edges(sample_rate),
             smoothing: 0.5, // 0.5 = balanced between responsiveness and smoothness
         }
     }
 
+    /// Whether the analyzer can be switched to this FFT size
+    pub fn is_supported_fft_size(fft_size: usize) -> bool {
+        fft_size.is_power_of_two() && (MIN_FFT_SIZE..=MAX_FFT_SIZE).contains(&fft_size)
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Center frequency of each band at a sample rate (logarithmically spaced, 10Hz to 20kHz)
+    pub fn band_frequencies(sample_rate: u32) -> [f32; NUM_BANDS] {
+        let max_freq = MAX_BAND_FREQ.min(sample_rate as f32 / 2.0);
+        let log_min = MIN_BAND_FREQ.ln();
+        let log_max = max_freq.ln();
+
+        let mut band_frequencies = [0.0f32; NUM_BANDS];
+        for (i, freq) in band_frequencies.iter_mut().enumerate() {
+            let t = i as f32 / (NUM_BANDS - 1) as f32;
+            *freq = (log_min + t * (log_max - log_min)).exp();
+        }
+        band_frequencies
+    }
+
+    /// Frequency range (low, high) each band covers at a sample rate
+    /// Edges sit halfway between neighbouring centers; the outer bands extend to 10Hz and Nyquist.
+    pub fn band_edges(sample_rate: u32) -> [(f32, f32); NUM_BANDS] {
+        let centers = Self::band_frequencies(sample_rate);
+        let mut edges = [(0.0f32, 0.0f32); NUM_BANDS];
+        for (band_idx, edge) in edges.iter_mut().enumerate() {
+            let low = if band_idx == 0 {
+                MIN_BAND_FREQ
+            } else {
+                (centers[band_idx - 1] + centers[band_idx]) / 2.0
+            };
+            let high = if band_idx == NUM_BANDS - 1 {
+                sample_rate as f32 / 2.0
+            } else {
+                (centers[band_idx] + centers[band_idx + 1]) / 2.0
+            };
+            *edge = (low, high);
+        }
+        edges
+    }
+
     /// Push audio samples into the analyzer
     /// Returns true if enough samples have been collected for FFT
     pub fn push_samples(&mut self, samples: &[f32]) -> bool {
         for &sample in samples {
             self.input_buffer[self.write_pos] = sample;
-            self.write_pos = (self.write_pos + 1) % FFT_SIZE;
+            self.write_pos = (self.write_pos + 1) % self.fft_size;
         }
 
         // Return true when we've collected enough samples
@@ -98,28 +157,12 @@ impl SpectrumAnalyzer {
         }
 
         // Calculate magnitude for each band
-        let bin_freq = self.sample_rate as f32 / FFT_SIZE as f32;
+        let bin_freq = self.sample_rate as f32 / self.fft_size as f32;
         let num_bins = self.spectrum_buffer.len();
 
-        // Minimum frequency for first band (matches band_frequencies calculation)
-        let min_freq = 10.0f32;
-
         for band_idx in 0..NUM_BANDS {
             // Get frequency range for this band
-            let center_freq = self.band_frequencies[band_idx];
-            let (low_freq, high_freq) = if band_idx == 0 {
-                (min_freq, (center_freq + self.band_frequencies[1]) / 2.0)
-            } else if band_idx == NUM_BANDS - 1 {
-                (
-                    (self.band_frequencies[band_idx - 1] + center_freq) / 2.0,
-                    self.sample_rate as f32 / 2.0,
-                )
-            } else {
-                (
-                    (self.band_frequencies[band_idx - 1] + center_freq) / 2.0,
-                    (center_freq + self.band_frequencies[band_idx + 1]) / 2.0,
-                )
-            };
+            let (low_freq, high_freq) = self.band_edges[band_idx];
 
             // Find bin range for this frequency band
             let low_bin = ((low_freq / bin_freq) as usize).max(1);
@@ -136,9 +179,9 @@ impl SpectrumAnalyzer {
             }
 
             // Normalize using peak magnitude
-            // Scale factor: FFT_SIZE/2 for Hann window normalization, /2 again for amplitude vs magnitude
+            // Scale factor: fft_size/2 for Hann window normalization, /2 again for amplitude vs magnitude
             // This should give ~1.0 for a full-scale sine wave at any frequency
-            let scale = (FFT_SIZE as f32) / 4.0;
+            let scale = (self.fft_size as f32) / 4.0;
             let normalized = peak_mag / scale;
 
             // Apply smoothing
@@ -174,3 +217,67 @@ impl SpectrumAnalyzer {
         self.band_magnitudes.fill(0.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_edges_cover_range() {
+        let centers = SpectrumAnalyzer::band_frequencies(48000);
+        let edges = SpectrumAnalyzer::band_edges(48000);
+
+        assert!((centers[0] - 10.0).abs() < 0.01);
+        assert!((centers[NUM_BANDS - 1] - 20000.0).abs() < 1.0);
+        assert_eq!(edges[0].0, 10.0);
+        assert_eq!(edges[NUM_BANDS - 1].1, 24000.0);
+        for i in 0..NUM_BANDS {
+            assert!(edges[i].0 <= centers[i] && centers[i] <= edges[i].1);
+            if i > 0 {
+                assert_eq!(edges[i - 1].1, edges[i].0);
+            }
+        }
+
+        // Low sample rates cap the top band at Nyquist
+        let low_rate = SpectrumAnalyzer::band_frequencies(22050);
+        assert!((low_rate[NUM_BANDS - 1] - 11025.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_sine_lands_in_its_band_at_each_fft_size() {
+        let sample_rate = 48000;
+        let edges = SpectrumAnalyzer::band_edges(sample_rate);
+        let expected = edges.iter().position(|&(low, high)| low <= 1000.0 && 1000.0 < high).unwrap();
+
+        for fft_size in [1024, 2048, 4096] {
+            let mut analyzer = SpectrumAnalyzer::with_fft_size(sample_rate, fft_size);
+            assert_eq!(analyzer.fft_size(), fft_size);
+
+            let sine: Vec<f32> = (0..fft_size * 4)
+                .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin())
+                .collect();
+            for block in sine.chunks(512) {
+                analyzer.push_samples(block);
+                analyzer.analyze();
+            }
+
+            let magnitudes = analyzer.get_magnitudes();
+            let loudest = (0..NUM_BANDS)
+                .max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b]))
+                .unwrap();
+            assert_eq!(loudest, expected, "{} point FFT put 1 kHz in the wrong band", fft_size);
+            assert!(
+                magnitudes[expected] > 0.7 && magnitudes[expected] < 1.1,
+                "{} point FFT read {} for a full scale sine",
+                fft_size,
+                magnitudes[expected]
+            );
+        }
+
+        // Unsupported sizes round up to the nearest supported power of 2
+        assert_eq!(SpectrumAnalyzer::with_fft_size(sample_rate, 3000).fft_size(), 4096);
+        assert_eq!(SpectrumAnalyzer::with_fft_size(sample_rate, 0).fft_size(), MIN_FFT_SIZE);
+        assert!(!SpectrumAnalyzer::is_supported_fft_size(3000));
+        assert!(SpectrumAnalyzer::is_supported_fft_size(8192));
+    }
+}
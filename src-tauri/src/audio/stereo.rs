@@ -7,6 +7,48 @@
 /// Larger = denser particle cloud, but more memory/bandwidth
 pub const STEREO_HISTORY_SIZE: usize = 2048;
 
+/// Range for the goniometer display gain applied to point radii
+pub const MIN_GONIOMETER_GAIN: f32 = 0.25;
+pub const MAX_GONIOMETER_GAIN: f32 = 16.0;
+
+/// The newest 1/N of the visible history is flagged as "recent" (drawn brighter)
+const RECENT_FRACTION: usize = 4;
+
+/// Range and default for the correlation smoothing time constant (ms)
+/// The default matches the previous fixed 0.95-per-window smoothing at 44.1kHz
+pub const MIN_CORRELATION_SMOOTHING_MS: f32 = 0.0;
+pub const MAX_CORRELATION_SMOOTHING_MS: f32 = 5000.0;
+pub const DEFAULT_CORRELATION_SMOOTHING_MS: f32 = 1800.0;
+
+/// Range for the monitor stereo width (0 = mono, 1 = unchanged). Capped so the side
+/// boost can't turn a near-mono signal into a large out-of-phase one
+pub const MIN_STEREO_WIDTH: f32 = 0.0;
+pub const MAX_STEREO_WIDTH: f32 = 2.0;
+
+/// Split a stereo sample into mid (L+R) and side (L-R), each halved so
+/// `from_mid_side` is the exact inverse
+#[inline]
+pub fn to_mid_side(left: f32, right: f32) -> (f32, f32) {
+    ((left + right) * 0.5, (left - right) * 0.5)
+}
+
+/// Rebuild left/right from mid and side
+#[inline]
+pub fn from_mid_side(mid: f32, side: f32) -> (f32, f32) {
+    (mid + side, mid - side)
+}
+
+/// Scale the side signal of a stereo sample (0 = mono, 1 = unchanged, >1 = wider)
+#[inline]
+pub fn apply_stereo_width(left: f32, right: f32, width: f32) -> (f32, f32) {
+    let (mid, side) = to_mid_side(left, right);
+    from_mid_side(mid, side * width)
+}
+
+/// Mean power per channel below which a window counts as near-silence (-60 dBFS)
+/// Correlation is held instead of updated so quiet passages don't swing the meter
+const CORRELATION_SILENCE_POWER: f32 = 1e-6;
+
 /// Stereo field analyzer
 ///
 /// Tracks sample positions in polar coordinates and computes
@@ -27,16 +69,25 @@ pub struct StereoAnalyzer {
     /// Smoothed correlation output (-1.0 to +1.0)
     correlation: f32,
 
-    /// Smoothing factor for correlation (higher = smoother)
+    /// Smoothing factor for correlation, applied once per window (higher = smoother)
     smoothing: f32,
 
+    /// Sample rate used to turn the smoothing time constant into a per-window factor
+    sample_rate: u32,
+
     /// Window size for correlation calculation
     correlation_window: usize,
+
+    /// Number of newest positions that are meaningful for display (<= STEREO_HISTORY_SIZE)
+    history_length: usize,
+
+    /// Display gain applied to the radius so quiet signals stay visible
+    gain: f32,
 }
 
 impl StereoAnalyzer {
     pub fn new() -> Self {
-        Self {
+        let mut analyzer = Self {
             positions: [(std::f32::consts::FRAC_PI_2, 0.0); STEREO_HISTORY_SIZE],
             write_pos: 0,
             sum_lr: 0.0,
@@ -44,9 +95,59 @@ impl StereoAnalyzer {
             sum_r2: 0.0,
             sample_count: 0,
             correlation: 1.0, // Start at mono
-            smoothing: 0.95,  // Smooth correlation to prevent jitter
+            smoothing: 0.0,   // Set from DEFAULT_CORRELATION_SMOOTHING_MS below
+            sample_rate: 44100,
             correlation_window: 4096, // About 100ms at 44.1kHz
+            history_length: STEREO_HISTORY_SIZE,
+            gain: 1.0,
+        };
+        analyzer.set_correlation_smoothing(DEFAULT_CORRELATION_SMOOTHING_MS);
+        analyzer
+    }
+
+    /// Create an analyzer whose smoothing time constant is based on `sample_rate`
+    pub fn with_sample_rate(sample_rate: u32) -> Self {
+        let mut analyzer = Self::new();
+        analyzer.sample_rate = sample_rate.max(1);
+        analyzer.set_correlation_smoothing(DEFAULT_CORRELATION_SMOOTHING_MS);
+        analyzer
+    }
+
+    /// Set the correlation smoothing time constant in milliseconds
+    /// (clamped to MIN/MAX_CORRELATION_SMOOTHING_MS; 0 = no smoothing, non-finite values are ignored)
+    pub fn set_correlation_smoothing(&mut self, ms: f32) {
+        if !ms.is_finite() {
+            return;
         }
+        let ms = ms.clamp(MIN_CORRELATION_SMOOTHING_MS, MAX_CORRELATION_SMOOTHING_MS);
+        self.smoothing = if ms <= 0.0 {
+            0.0
+        } else {
+            // One-pole coefficient per correlation window: exp(-window_duration / tau)
+            let window_secs = self.correlation_window as f32 / self.sample_rate as f32;
+            (-window_secs / (ms / 1000.0)).exp()
+        };
+    }
+
+    /// Set how many of the newest positions are shown (clamped to 1..=STEREO_HISTORY_SIZE)
+    pub fn set_history_length(&mut self, length: usize) {
+        self.history_length = length.clamp(1, STEREO_HISTORY_SIZE);
+    }
+
+    pub fn history_length(&self) -> usize {
+        self.history_length
+    }
+
+    /// Set the display gain applied to point radii (non-finite values are ignored)
+    pub fn set_gain(&mut self, gain: f32) {
+        if gain.is_finite() {
+            self.gain = gain.clamp(MIN_GONIOMETER_GAIN, MAX_GONIOMETER_GAIN);
+        }
+    }
+
+    /// How many of the newest `history_length` points count as recent
+    pub fn recent_count(history_length: usize) -> usize {
+        (history_length / RECENT_FRACTION).max(1)
     }
 
     /// Push a stereo sample pair and update position buffer
@@ -61,10 +162,22 @@ impl StereoAnalyzer {
             return;
         }
 
+        // Update correlation sums for every sample so the window has a fixed duration
+        // (quiet windows are detected in compute_correlation and hold the last value)
+        self.sum_lr += left * right;
+        self.sum_l2 += left * left;
+        self.sum_r2 += right * right;
+        self.sample_count += 1;
+
+        // Compute correlation when we have enough samples
+        if self.sample_count >= self.correlation_window {
+            self.compute_correlation();
+        }
+
         // Calculate magnitude (Euclidean distance)
         let magnitude = (left * left + right * right).sqrt();
 
-        // Skip very quiet samples (threshold above denormalized float range)
+        // Skip very quiet samples for display (threshold above denormalized float range)
         if magnitude < 0.005 {
             return;
         }
@@ -76,23 +189,13 @@ impl StereoAnalyzer {
         let abs_r = right.abs();
         let angle = 2.0 * f32::atan2(abs_l, abs_r);
 
-        // Radius from magnitude with sqrt scaling for better visual spread
-        let radius = magnitude.sqrt().min(1.0);
+        // Radius from magnitude with sqrt scaling for better visual spread,
+        // then display gain so quiet material isn't a dot in the middle
+        let radius = (magnitude.sqrt() * self.gain).min(1.0);
 
         // Store position
         self.positions[self.write_pos] = (angle, radius);
         self.write_pos = (self.write_pos + 1) % STEREO_HISTORY_SIZE;
-
-        // Update correlation sums
-        self.sum_lr += left * right;
-        self.sum_l2 += left * left;
-        self.sum_r2 += right * right;
-        self.sample_count += 1;
-
-        // Compute correlation when we have enough samples
-        if self.sample_count >= self.correlation_window {
-            self.compute_correlation();
-        }
     }
 
     /// Push multiple stereo sample pairs (interleaved L/R)
@@ -108,18 +211,17 @@ impl StereoAnalyzer {
     fn compute_correlation(&mut self) {
         // correlation = Σ(L×R) / sqrt(Σ(L²) × Σ(R²))
         let denom = (self.sum_l2 * self.sum_r2).sqrt();
+        let mean_power = (self.sum_l2 + self.sum_r2) / (2 * self.sample_count) as f32;
 
-        // Use threshold above denormalized range to avoid floating point issues
+        // Near-silence (or invalid sums): hold the last value instead of swinging the meter
         // Also check is_finite() to guard against NaN/Inf propagation
-        let raw_correlation = if denom > 1e-10 && denom.is_finite() {
-            (self.sum_lr / denom).clamp(-1.0, 1.0)
-        } else {
-            1.0 // If both channels are silent or invalid, assume mono
-        };
+        if mean_power >= CORRELATION_SILENCE_POWER && denom > 1e-10 && denom.is_finite() {
+            let raw_correlation = (self.sum_lr / denom).clamp(-1.0, 1.0);
 
-        // Apply smoothing
-        self.correlation = self.correlation * self.smoothing
-            + raw_correlation * (1.0 - self.smoothing);
+            // Apply smoothing
+            self.correlation = self.correlation * self.smoothing
+                + raw_correlation * (1.0 - self.smoothing);
+        }
 
         // Reset running sums for next window
         self.sum_lr = 0.0;
@@ -166,3 +268,100 @@ impl Default for StereoAnalyzer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stereo_width() {
+        let close = |(l, r): (f32, f32), (el, er): (f32, f32)| (l - el).abs() < 1e-6 && (r - er).abs() < 1e-6;
+        let (left, right) = (0.8, -0.2);
+        let (mid, side) = to_mid_side(left, right);
+        assert!(close(from_mid_side(mid, side), (left, right)));
+
+        assert!(close(apply_stereo_width(left, right, 1.0), (left, right)));
+        // Mono: both sides carry the mid
+        assert!(close(apply_stereo_width(left, right, 0.0), (0.3, 0.3)));
+        // Double width: side 0.5 -> 1.0
+        assert!(close(apply_stereo_width(left, right, 2.0), (1.3, -0.7)));
+    }
+
+    #[test]
+    fn test_history_length_and_gain() {
+        let mut analyzer = StereoAnalyzer::new();
+        analyzer.set_history_length(8);
+        analyzer.set_gain(4.0);
+
+        // Quiet centered signal: sqrt(0.01 * sqrt(2)) * 4 ~= 0.476
+        for _ in 0..16 {
+            analyzer.push_sample(0.01, 0.01);
+        }
+        analyzer.push_sample(1.0, 0.0);
+
+        // Oldest visible point (8 back from newest) is a quiet one
+        let positions = analyzer.get_positions();
+        let oldest_visible = positions[STEREO_HISTORY_SIZE - 8];
+        assert!((oldest_visible.1 - 0.476).abs() < 0.01, "radius {}", oldest_visible.1);
+
+        // Newest point is last, hard left, clamped to the unit circle
+        let newest = positions[STEREO_HISTORY_SIZE - 1];
+        assert!((newest.0 - std::f32::consts::PI).abs() < 1e-4);
+        assert_eq!(newest.1, 1.0);
+
+        assert_eq!(StereoAnalyzer::recent_count(analyzer.history_length()), 2);
+        assert_eq!(StereoAnalyzer::recent_count(2), 1);
+
+        analyzer.set_history_length(100_000);
+        assert_eq!(analyzer.history_length(), STEREO_HISTORY_SIZE);
+    }
+
+    /// Push one correlation window of a sine, either in phase (L = R) or out of phase (L = -R)
+    fn push_block(analyzer: &mut StereoAnalyzer, in_phase: bool, amplitude: f32) {
+        for i in 0..4096 {
+            let s = amplitude * (i as f32 * 0.05).sin();
+            analyzer.push_sample(s, if in_phase { s } else { -s });
+        }
+    }
+
+    #[test]
+    fn test_correlation_smoothing_transitions_gradually() {
+        let mut analyzer = StereoAnalyzer::with_sample_rate(44100);
+        analyzer.set_correlation_smoothing(500.0);
+
+        // Alternating in/out of phase blocks: the meter must not flip between +1 and -1
+        let mut previous = analyzer.get_correlation();
+        for block in 0..8 {
+            push_block(&mut analyzer, block % 2 == 1, 0.5);
+            let current = analyzer.get_correlation();
+            assert!((current - previous).abs() < 0.5, "jumped from {} to {}", previous, current);
+            assert!(current > -1.0 && current < 1.0);
+            previous = current;
+        }
+
+        // A sustained out-of-phase signal eventually converges toward -1
+        for _ in 0..100 {
+            push_block(&mut analyzer, false, 0.5);
+        }
+        assert!(analyzer.get_correlation() < -0.95, "correlation {}", analyzer.get_correlation());
+
+        // Without smoothing, each window is reported directly
+        analyzer.set_correlation_smoothing(0.0);
+        push_block(&mut analyzer, true, 0.5);
+        assert!((analyzer.get_correlation() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_correlation_holds_during_silence() {
+        let mut analyzer = StereoAnalyzer::with_sample_rate(44100);
+        analyzer.set_correlation_smoothing(0.0);
+        push_block(&mut analyzer, false, 0.5);
+        assert!((analyzer.get_correlation() + 1.0).abs() < 1e-4);
+
+        // Near-silent in-phase material doesn't move the meter
+        for _ in 0..4 {
+            push_block(&mut analyzer, true, 0.0005);
+        }
+        assert!((analyzer.get_correlation() + 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,263 @@
+//! Frequency weighting for the output level meter
+//!
+//! Raw peak levels treat all frequencies alike, but loudness doesn't: a bass-heavy mix reads
+//! hot on a peak meter while sounding no louder. The weighting filters here shape the metered
+//! signal the way the standard curves do:
+//! - A-weighting (IEC 61672), normalized to 0 dB at 1 kHz
+//! - K-weighting (ITU-R BS.1770), the pre-filter used for LUFS - a high shelf of about +4 dB
+//!   plus a high-pass around 38 Hz (reads about +0.7 dB at 1 kHz, as the standard specifies)
+//!
+//! Filters are cascades of biquads designed for the engine's sample rate. They run on the
+//! audio thread, so nothing here allocates. Coefficients and state are f64: the A-weighting
+//! poles around 20 Hz are too close to the unit circle for f32 to stay accurate.
+
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const WEIGHTING_NONE: u8 = 0;
+const WEIGHTING_A: u8 = 1;
+const WEIGHTING_K: u8 = 2;
+
+/// A-weighting analog pole frequencies (Hz)
+const A_POLE_1: f64 = 20.598997;
+const A_POLE_2: f64 = 107.65265;
+const A_POLE_3: f64 = 737.86223;
+const A_POLE_4: f64 = 12194.217;
+
+/// BS.1770 stage 1 high shelf
+const K_SHELF_FREQ: f64 = 1681.974450955533;
+const K_SHELF_GAIN_DB: f64 = 3.999843853973347;
+const K_SHELF_Q: f64 = 0.7071752369554196;
+/// BS.1770 stage 2 high-pass
+const K_HIGHPASS_FREQ: f64 = 38.13547087602444;
+const K_HIGHPASS_Q: f64 = 0.5003270373238773;
+
+/// Most biquads any weighting needs
+const MAX_SECTIONS: usize = 3;
+
+/// Frequency weighting applied to the metered output level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteringWeighting {
+    /// Unweighted (default)
+    #[default]
+    None,
+    /// A-weighting
+    A,
+    /// K-weighting (BS.1770)
+    K,
+}
+
+impl MeteringWeighting {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            MeteringWeighting::None => WEIGHTING_NONE,
+            MeteringWeighting::A => WEIGHTING_A,
+            MeteringWeighting::K => WEIGHTING_K,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            WEIGHTING_A => MeteringWeighting::A,
+            WEIGHTING_K => MeteringWeighting::K,
+            _ => MeteringWeighting::None,
+        }
+    }
+}
+
+/// Transposed direct form II biquad
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    /// a1, a2 (a0 normalized to 1)
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    const IDENTITY: Biquad = Biquad {
+        b: [1.0, 0.0, 0.0],
+        a: [0.0, 0.0],
+        z: [0.0, 0.0],
+    };
+
+    fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+            z: [0.0, 0.0],
+        }
+    }
+
+    /// Bilinear transform of the analog section (b0 s^2 + b1 s + b2) / (a0 s^2 + a1 s + a2)
+    fn bilinear(b: [f64; 3], a: [f64; 3], sample_rate: f64) -> Self {
+        let k = 2.0 * sample_rate;
+        let k2 = k * k;
+        let digital = |c: [f64; 3]| {
+            [
+                c[0] * k2 + c[1] * k + c[2],
+                2.0 * (c[2] - c[0] * k2),
+                c[0] * k2 - c[1] * k + c[2],
+            ]
+        };
+        Self::new(digital(b), digital(a))
+    }
+
+    #[inline]
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+
+    /// Magnitude response at a frequency
+    fn magnitude(&self, freq: f64, sample_rate: f64) -> f64 {
+        let w = 2.0 * PI * freq / sample_rate;
+        // Evaluate numerator and denominator at z^-1 = e^-jw
+        let eval = |c0: f64, c1: f64, c2: f64| {
+            let re = c0 + c1 * w.cos() + c2 * (2.0 * w).cos();
+            let im = -c1 * w.sin() - c2 * (2.0 * w).sin();
+            (re * re + im * im).sqrt()
+        };
+        eval(self.b[0], self.b[1], self.b[2]) / eval(1.0, self.a[0], self.a[1])
+    }
+}
+
+/// Weighting filter for one mono signal
+#[derive(Debug, Clone, Copy)]
+pub struct WeightingFilter {
+    weighting: MeteringWeighting,
+    sections: [Biquad; MAX_SECTIONS],
+    /// Overall gain applied after the sections
+    gain: f64,
+}
+
+impl WeightingFilter {
+    pub fn new(weighting: MeteringWeighting, sample_rate: u32) -> Self {
+        let fs = sample_rate.max(1) as f64;
+        let mut sections = [Biquad::IDENTITY; MAX_SECTIONS];
+        let mut gain = 1.0;
+
+        match weighting {
+            MeteringWeighting::None => {}
+            MeteringWeighting::A => {
+                let w = |f: f64| 2.0 * PI * f;
+                let (w1, w2, w3, w4) = (w(A_POLE_1), w(A_POLE_2), w(A_POLE_3), w(A_POLE_4));
+                // s^4 / ((s + w1)^2 (s + w2) (s + w3) (s + w4)^2), split into three sections
+                sections[0] = Biquad::bilinear([1.0, 0.0, 0.0], [1.0, 2.0 * w1, w1 * w1], fs);
+                sections[1] = Biquad::bilinear([1.0, 0.0, 0.0], [1.0, w2 + w3, w2 * w3], fs);
+                sections[2] = Biquad::bilinear([0.0, 0.0, 1.0], [1.0, 2.0 * w4, w4 * w4], fs);
+                let at_1k: f64 = sections.iter().map(|s| s.magnitude(1000.0, fs)).product();
+                gain = 1.0 / at_1k;
+            }
+            MeteringWeighting::K => {
+                let k = (PI * K_SHELF_FREQ / fs).tan();
+                let vh = 10.0f64.powf(K_SHELF_GAIN_DB / 20.0);
+                let vb = vh.powf(0.4996667741545416);
+                sections[0] = Biquad::new(
+                    [vh + vb * k / K_SHELF_Q + k * k, 2.0 * (k * k - vh), vh - vb * k / K_SHELF_Q + k * k],
+                    [1.0 + k / K_SHELF_Q + k * k, 2.0 * (k * k - 1.0), 1.0 - k / K_SHELF_Q + k * k],
+                );
+
+                let k = (PI * K_HIGHPASS_FREQ / fs).tan();
+                let a0 = 1.0 + k / K_HIGHPASS_Q + k * k;
+                // BS.1770 defines the high-pass with a unity (not normalized) numerator
+                sections[1] = Biquad {
+                    b: [1.0, -2.0, 1.0],
+                    a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / K_HIGHPASS_Q + k * k) / a0],
+                    z: [0.0, 0.0],
+                };
+            }
+        }
+
+        Self {
+            weighting,
+            sections,
+            gain,
+        }
+    }
+
+    pub fn weighting(&self) -> MeteringWeighting {
+        self.weighting
+    }
+
+    /// Filter one sample
+    #[inline]
+    pub fn process(&mut self, x: f32) -> f32 {
+        if self.weighting == MeteringWeighting::None {
+            return x;
+        }
+        let mut y = x as f64;
+        for section in &mut self.sections {
+            y = section.process(y);
+        }
+        let y = (y * self.gain) as f32;
+        // A NaN or Inf would otherwise stick in the feedback path forever
+        if y.is_finite() {
+            y
+        } else {
+            self.reset();
+            0.0
+        }
+    }
+
+    /// Clear the filter history
+    pub fn reset(&mut self) {
+        for section in &mut self.sections {
+            section.z = [0.0, 0.0];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steady-state gain in dB for a sine through the filter
+    fn gain_db(weighting: MeteringWeighting, freq: f32, sample_rate: u32) -> f32 {
+        let mut filter = WeightingFilter::new(weighting, sample_rate);
+        let samples = sample_rate as usize;
+        let mut peak = 0.0f32;
+        for i in 0..samples {
+            let x = (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin();
+            let y = filter.process(x);
+            // Skip the first half while the filter settles
+            if i > samples / 2 {
+                peak = peak.max(y.abs());
+            }
+        }
+        20.0 * peak.log10()
+    }
+
+    #[test]
+    fn test_a_weighting_curve() {
+        for sample_rate in [44100, 48000, 96000] {
+            let at_1k = gain_db(MeteringWeighting::A, 1000.0, sample_rate);
+            assert!(at_1k.abs() < 0.1, "{} Hz: {} dB at 1 kHz", sample_rate, at_1k);
+        }
+        // IEC 61672 table values: -19.1 dB at 100 Hz, -39.4 dB at 31.5 Hz, +1.3 dB at 2.5 kHz
+        let at_100 = gain_db(MeteringWeighting::A, 100.0, 48000);
+        assert!((at_100 + 19.1).abs() < 0.3, "{} dB at 100 Hz", at_100);
+        let at_31 = gain_db(MeteringWeighting::A, 31.5, 48000);
+        assert!((at_31 + 39.4).abs() < 0.5, "{} dB at 31.5 Hz", at_31);
+        let at_2k5 = gain_db(MeteringWeighting::A, 2500.0, 48000);
+        assert!((at_2k5 - 1.3).abs() < 0.2, "{} dB at 2.5 kHz", at_2k5);
+    }
+
+    #[test]
+    fn test_k_weighting_curve() {
+        let at_1k = gain_db(MeteringWeighting::K, 1000.0, 48000);
+        assert!((at_1k - 0.7).abs() < 0.2, "{} dB at 1 kHz", at_1k);
+        let at_10k = gain_db(MeteringWeighting::K, 10000.0, 48000);
+        assert!((at_10k - 4.0).abs() < 0.3, "{} dB at 10 kHz", at_10k);
+        assert!(gain_db(MeteringWeighting::K, 20.0, 48000) < -5.0);
+
+        // Unweighted passes straight through
+        assert!(gain_db(MeteringWeighting::None, 50.0, 48000).abs() < 1e-3);
+        let mut filter = WeightingFilter::new(MeteringWeighting::K, 48000);
+        assert_eq!(filter.process(f32::NAN), 0.0);
+        assert!(filter.process(0.5).is_finite());
+    }
+}
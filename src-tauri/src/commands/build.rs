@@ -1,16 +1,143 @@
 use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::Emitter;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
-use super::projects::{ensure_workspace, get_output_path, get_workspace_path};
+use super::projects::{
+    ensure_workspace, get_output_path, get_projects_path, get_workspace_path, warmup_package_name,
+    WARMUP_FRAMEWORKS,
+};
+
+// Track active cargo builds by project path so they can be cancelled
+static ACTIVE_BUILDS: Mutex<Option<HashMap<String, u32>>> = Mutex::new(None);
+
+fn register_build(project_path: &str, pid: u32) {
+    let mut guard = ACTIVE_BUILDS.lock().unwrap();
+    if guard.is_none() {
+        *guard = Some(HashMap::new());
+    }
+    if let Some(ref mut map) = *guard {
+        map.insert(project_path.to_string(), pid);
+    }
+}
+
+fn unregister_build(project_path: &str) {
+    let mut guard = ACTIVE_BUILDS.lock().unwrap();
+    if let Some(ref mut map) = *guard {
+        map.remove(project_path);
+    }
+}
+
+fn get_build_pid(project_path: &str) -> Option<u32> {
+    let guard = ACTIVE_BUILDS.lock().unwrap();
+    guard.as_ref().and_then(|map| map.get(project_path).copied())
+}
+
+fn any_build_running() -> bool {
+    let guard = ACTIVE_BUILDS.lock().unwrap();
+    guard.as_ref().map(|map| !map.is_empty()).unwrap_or(false)
+}
+
+// Recent error summaries for Claude's context by project path: (checked at, summary)
+type ErrorSummaryCache = HashMap<String, (Instant, Option<String>)>;
+static ERROR_SUMMARY_CACHE: Mutex<Option<ErrorSummaryCache>> = Mutex::new(None);
+
+/// How long an error summary is reused before the project is checked again
+const ERROR_SUMMARY_TTL: Duration = Duration::from_secs(30);
+
+/// Longest a new Claude session waits for the check - the build state is left out after that
+const ERROR_SUMMARY_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn cached_error_summary(project_path: &str, now: Instant) -> Option<Option<String>> {
+    let guard = ERROR_SUMMARY_CACHE.lock().unwrap();
+    let (checked_at, summary) = guard.as_ref()?.get(project_path)?;
+    (now.duration_since(*checked_at) < ERROR_SUMMARY_TTL).then(|| summary.clone())
+}
+
+fn cache_error_summary(project_path: &str, checked_at: Instant, summary: Option<String>) {
+    let mut guard = ERROR_SUMMARY_CACHE.lock().unwrap();
+    guard
+        .get_or_insert_with(HashMap::new)
+        .insert(project_path.to_string(), (checked_at, summary));
+}
+
+/// Builds are refused below this much free space - cargo would fail partway with I/O errors
+const MIN_BUILD_FREE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Below this much free space builds still run, with a warning to clean up
+const LOW_DISK_WARNING_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+// Only one build cache warm-up at a time
+static WARMUP_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Target directory shared by every project in the workspace, so heavy dependencies
+/// (nih-plug, baseview, wry) are compiled once and reused across projects and rebuilds.
+/// Set explicitly so a CARGO_TARGET_DIR inherited from the user's shell can't move it.
+fn build_target_dir() -> PathBuf {
+    get_workspace_path().join("target")
+}
+
+/// Find sccache on the extended PATH
+fn find_sccache() -> Option<PathBuf> {
+    super::get_extended_path()
+        .split(':')
+        .map(|dir| PathBuf::from(dir).join("sccache"))
+        .find(|path| path.is_file())
+}
+
+/// Point a cargo command at the shared target dir, and at sccache when it's installed
+fn configure_build_cache(command: &mut Command) {
+    command.env("CARGO_TARGET_DIR", build_target_dir());
+    if let Some(sccache) = find_sccache() {
+        command.env("RUSTC_WRAPPER", sccache);
+    }
+}
+
+/// How much room is left for builds on the workspace volume
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskSpaceStatus {
+    Ok,
+    /// Under LOW_DISK_WARNING_BYTES: builds run with a warning
+    Low,
+    /// Under MIN_BUILD_FREE_BYTES: builds are refused
+    Critical,
+}
+
+impl DiskSpaceStatus {
+    fn from_free_bytes(free_bytes: u64) -> Self {
+        if free_bytes < MIN_BUILD_FREE_BYTES {
+            Self::Critical
+        } else if free_bytes < LOW_DISK_WARNING_BYTES {
+            Self::Low
+        } else {
+            Self::Ok
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct DiskSpace {
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+    pub status: DiskSpaceStatus,
+    /// Builds are refused below this
+    pub min_build_free_bytes: u64,
+}
 
 #[derive(Serialize, Clone)]
 pub struct BuildResult {
     pub success: bool,
     pub output_path: Option<String>,
     pub error: Option<String>,
+    /// Wall-clock build time (None if the build was cancelled)
+    pub duration_secs: Option<f64>,
 }
 
 #[derive(Serialize, Clone)]
@@ -29,11 +156,140 @@ pub enum BuildStreamEvent {
     Error { message: String },
 }
 
+/// Emitted on "build-cancelled" when a build is stopped by the user
+#[derive(Serialize, Clone)]
+pub struct BuildCancelledEvent {
+    pub project_path: String,
+}
+
+/// Progress of warmup_build_cache, emitted on "warmup-progress"
+#[derive(Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum WarmupEvent {
+    #[serde(rename = "start")]
+    Start { total: usize },
+    /// A new package started compiling (index is 1-based)
+    #[serde(rename = "step")]
+    Step { index: usize, package: String },
+    #[serde(rename = "output")]
+    Output { line: String },
+    #[serde(rename = "done")]
+    Done {
+        success: bool,
+        duration_secs: f64,
+        error: Option<String>,
+    },
+}
+
+/// A compiler error or warning parsed from cargo's `--message-format=json` output
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CompilerDiagnostic {
+    /// "error", "warning", ...
+    pub level: String,
+    pub message: String,
+    /// Error code such as "E0425", if any
+    pub code: Option<String>,
+    /// Primary span location (relative to the package root)
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// Full human-readable rendering, as cargo would print it
+    pub rendered: Option<String>,
+}
+
+/// Result of validate_project
+#[derive(Serialize, Clone)]
+pub struct ValidationResult {
+    pub success: bool,
+    /// Diagnostics for the project's own package (dependencies are left out)
+    pub diagnostics: Vec<CompilerDiagnostic>,
+    pub error: Option<String>,
+}
+
+/// Result of collect_build_errors
+#[derive(Serialize, Clone)]
+pub struct BuildErrorReport {
+    /// Errors located in the project's `src/` directory
+    pub diagnostics: Vec<CompilerDiagnostic>,
+    /// Follow-up message for Claude (None if there were no `src/` errors to report)
+    pub prompt: Option<String>,
+}
+
+/// Most errors included in a build error prompt - the first few usually cause the rest
+const MAX_PROMPT_ERRORS: usize = 10;
+
 /// Convert project name to Cargo package name (snake_case)
-fn to_package_name(name: &str) -> String {
+pub fn to_package_name(name: &str) -> String {
     name.replace('-', "_")
 }
 
+/// Free and total bytes on the volume holding `path` (or its nearest existing ancestor)
+#[cfg(unix)]
+fn volume_space(path: &Path) -> Result<(u64, u64), String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|p| p.exists()).unwrap_or(Path::new("/"));
+    let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes())
+        .map_err(|_| format!("Invalid path: {}", existing.display()))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(format!(
+            "Failed to read free space for {}: {}",
+            existing.display(),
+            std::io::Error::last_os_error()
+        ));
+    }
+    let block_size = stat.f_frsize as u64;
+    // f_bavail: blocks available to unprivileged users (what cargo can actually use)
+    Ok((stat.f_bavail as u64 * block_size, stat.f_blocks as u64 * block_size))
+}
+
+#[cfg(not(unix))]
+fn volume_space(_path: &Path) -> Result<(u64, u64), String> {
+    Err("Checking free disk space isn't supported on this platform".to_string())
+}
+
+fn workspace_disk_space() -> Result<DiskSpace, String> {
+    let (free_bytes, total_bytes) = volume_space(&get_workspace_path())?;
+    Ok(DiskSpace {
+        free_bytes,
+        total_bytes,
+        status: DiskSpaceStatus::from_free_bytes(free_bytes),
+        min_build_free_bytes: MIN_BUILD_FREE_BYTES,
+    })
+}
+
+fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+/// Total size of the files under `path` in bytes (0 if it doesn't exist)
+fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Free space on the volume holding the workspace, and whether it's enough to build
+/// (check_disk_space in prerequisites covers installing the toolchain instead)
+#[tauri::command]
+pub async fn check_build_disk_space() -> Result<DiskSpace, String> {
+    workspace_disk_space()
+}
+
+/// Size of the shared build cache (target dir) in bytes
+#[tauri::command]
+pub async fn get_target_dir_size() -> Result<u64, String> {
+    let target_dir = build_target_dir();
+    tokio::task::spawn_blocking(move || dir_size(&target_dir))
+        .await
+        .map_err(|e| format!("Failed to measure the build cache: {}", e))
+}
+
 /// Build a plugin project using cargo xtask bundle
 #[tauri::command]
 pub async fn build_project(
@@ -44,6 +300,35 @@ pub async fn build_project(
     // Ensure workspace structure exists (creates shared xtask if needed)
     ensure_workspace()?;
 
+    // A full disk fails builds partway with cryptic errors - refuse up front instead
+    let disk_warning = match workspace_disk_space() {
+        Ok(space) if space.status == DiskSpaceStatus::Critical => {
+            let message = format!(
+                "Not enough disk space to build: {} free, at least {} needed. \
+                 Clean the build cache in Settings or free up space and try again.",
+                format_gb(space.free_bytes),
+                format_gb(MIN_BUILD_FREE_BYTES)
+            );
+            let _ = window.emit("build-stream", BuildStreamEvent::Error { message: message.clone() });
+            // No duration, like a cancelled build - this isn't a compile error to fix
+            return Ok(BuildResult {
+                success: false,
+                output_path: None,
+                error: Some(message),
+                duration_secs: None,
+            });
+        }
+        Ok(space) if space.status == DiskSpaceStatus::Low => Some(format!(
+            "warning: disk space is low ({} free) - the build may fail. Consider cleaning the build cache.",
+            format_gb(space.free_bytes)
+        )),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("{}", e);
+            None
+        }
+    };
+
     let workspace_path = get_workspace_path();
     let base_output_path = get_output_path();
 
@@ -57,6 +342,10 @@ pub async fn build_project(
 
     // Emit start event
     let _ = window.emit("build-stream", BuildStreamEvent::Start);
+    if let Some(line) = disk_warning {
+        log::warn!("{}", line);
+        let _ = window.emit("build-stream", BuildStreamEvent::Output { line });
+    }
 
     // Convert project name to Cargo package name (hyphens -> underscores)
     let package_name = to_package_name(&project_name);
@@ -68,16 +357,32 @@ pub async fn build_project(
         .unwrap_or_else(|_| "0".to_string());
 
     // Run cargo xtask bundle from workspace root
-    let mut child = Command::new("cargo")
+    let mut command = Command::new("cargo");
+    command
         .current_dir(&workspace_path)
         .args(["xtask", "bundle", &package_name, "--release"])
         .env("PATH", super::get_extended_path())
         .env("WRY_BUILD_SUFFIX", &build_suffix)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+        .stderr(Stdio::piped());
+    configure_build_cache(&mut command);
+    let using_sccache = find_sccache().is_some();
+    let started = std::time::Instant::now();
+
+    // Own process group so cancel_build can take down rustc/build-script children too
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child = command
         .spawn()
         .map_err(|e| format!("Failed to spawn cargo: {}", e))?;
 
+    // Register build for potential cancellation
+    let project_path = get_projects_path().join(&project_name).to_string_lossy().to_string();
+    if let Some(pid) = child.id() {
+        register_build(&project_path, pid);
+    }
+
     let stdout = child
         .stdout
         .take()
@@ -130,14 +435,35 @@ pub async fn build_project(
     }
 
     // Wait for process to complete
-    let status = child
-        .wait()
-        .await
-        .map_err(|e| format!("Failed to wait for cargo: {}", e))?;
+    let status = child.wait().await;
+
+    // A missing registration means cancel_build already killed it and emitted build-cancelled
+    let was_cancelled = get_build_pid(&project_path).is_none();
+    unregister_build(&project_path);
+
+    let status = status.map_err(|e| format!("Failed to wait for cargo: {}", e))?;
+
+    if was_cancelled && !status.success() {
+        return Ok(BuildResult {
+            success: false,
+            output_path: None,
+            error: Some("Build cancelled".to_string()),
+            duration_secs: None,
+        });
+    }
+
+    let duration_secs = started.elapsed().as_secs_f64();
+    log::info!(
+        "Build of {} {} in {:.1}s{}",
+        package_name,
+        if status.success() { "finished" } else { "failed" },
+        duration_secs,
+        if using_sccache { " (sccache)" } else { "" }
+    );
 
     if status.success() {
         // Copy artifacts to output folder
-        let bundled_path = workspace_path.join("target/bundled");
+        let bundled_path = build_target_dir().join("bundled");
 
         // Look for .vst3 and .clap bundles
         let mut copied_files = Vec::new();
@@ -190,6 +516,7 @@ pub async fn build_project(
             success: true,
             output_path: Some(output_str),
             error: None,
+            duration_secs: Some(duration_secs),
         })
     } else {
         let _ = window.emit("build-stream", BuildStreamEvent::Done {
@@ -201,10 +528,390 @@ pub async fn build_project(
             success: false,
             output_path: None,
             error: Some(error_output),
+            duration_secs: Some(duration_secs),
         })
     }
 }
 
+/// Cancel a running build for a project, killing cargo and its rustc children
+#[tauri::command]
+pub async fn cancel_build(project_path: String, window: tauri::Window) -> Result<(), String> {
+    let Some(pid) = get_build_pid(&project_path) else {
+        return Err("No build running for this project".to_string());
+    };
+    eprintln!("[DEBUG] Cancelling build process {} for {}", pid, project_path);
+
+    // cargo was spawned as a process group leader - signal the whole group
+    #[cfg(unix)]
+    {
+        use std::process::Command as StdCommand;
+        let _ = StdCommand::new("kill")
+            .args(["-TERM", "--", &format!("-{}", pid)])
+            .output();
+    }
+
+    // /T kills the whole process tree
+    #[cfg(windows)]
+    {
+        use std::process::Command as StdCommand;
+        let _ = StdCommand::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output();
+    }
+
+    // Unregister so build_project knows the failure was a cancellation
+    unregister_build(&project_path);
+
+    let _ = window.emit("build-cancelled", BuildCancelledEvent { project_path });
+
+    Ok(())
+}
+
+/// Delete the shared build cache so the next build starts from scratch
+/// Returns the number of bytes freed.
+#[tauri::command]
+pub async fn clean_build_cache() -> Result<u64, String> {
+    if any_build_running() {
+        return Err("Can't clean the build cache while a build is running".to_string());
+    }
+    if WARMUP_RUNNING.load(Ordering::SeqCst) {
+        return Err("Can't clean the build cache while it's being warmed up".to_string());
+    }
+
+    let target_dir = build_target_dir();
+    if !target_dir.exists() {
+        return Ok(0);
+    }
+
+    let freed = dir_size(&target_dir);
+
+    std::fs::remove_dir_all(&target_dir)
+        .map_err(|e| format!("Failed to remove build cache: {}", e))?;
+    log::info!("Cleaned build cache at {} ({} bytes)", target_dir.display(), freed);
+
+    Ok(freed)
+}
+
+/// Precompile the dependencies every project shares (nih-plug, plus nih-plug-webview or
+/// nih-plug-egui) into the shared target dir by building the workspace's warm-up members.
+///
+/// This trades a few minutes of upfront compile time, ideally in the background right
+/// after setup, for a first project build that only compiles the plugin itself. Once the
+/// cache is warm, running it again is close to a no-op.
+///
+/// Safe to run alongside normal use: cargo locks the target dir, so a project build started
+/// meanwhile waits for the current warm-up package and then reuses what it compiled.
+/// Returns the wall-clock time in seconds.
+#[tauri::command]
+pub async fn warmup_build_cache(app_handle: tauri::AppHandle) -> Result<f64, String> {
+    if WARMUP_RUNNING
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err("A build cache warm-up is already running".to_string());
+    }
+
+    let started = std::time::Instant::now();
+    let result = run_warmup(&app_handle).await;
+    WARMUP_RUNNING.store(false, Ordering::SeqCst);
+
+    let duration_secs = started.elapsed().as_secs_f64();
+    match &result {
+        Ok(()) => log::info!("Build cache warm-up finished in {:.1}s", duration_secs),
+        Err(e) => log::warn!("Build cache warm-up failed after {:.1}s: {}", duration_secs, e),
+    }
+    let _ = app_handle.emit("warmup-progress", WarmupEvent::Done {
+        success: result.is_ok(),
+        duration_secs,
+        error: result.clone().err(),
+    });
+
+    result.map(|()| duration_secs)
+}
+
+async fn run_warmup(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    ensure_workspace()?;
+    let workspace_path = get_workspace_path();
+
+    // xtask first: every project build runs it before compiling the plugin
+    let packages: Vec<String> = std::iter::once("xtask".to_string())
+        .chain(WARMUP_FRAMEWORKS.iter().map(|framework| warmup_package_name(framework)))
+        .collect();
+    let _ = app_handle.emit("warmup-progress", WarmupEvent::Start { total: packages.len() });
+
+    for (index, package) in packages.iter().enumerate() {
+        let _ = app_handle.emit("warmup-progress", WarmupEvent::Step {
+            index: index + 1,
+            package: package.clone(),
+        });
+
+        let mut command = Command::new("cargo");
+        command
+            .current_dir(&workspace_path)
+            .args(["build", "--release", "-p", package])
+            .env("PATH", super::get_extended_path())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        configure_build_cache(&mut command);
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to spawn cargo: {}", e))?;
+
+        // cargo build reports progress on stderr
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or("Failed to capture stderr")?;
+        let mut stderr_reader = BufReader::new(stderr).lines();
+        let mut error_output = String::new();
+        while let Ok(Some(line)) = stderr_reader.next_line().await {
+            error_output.push_str(&line);
+            error_output.push('\n');
+            let _ = app_handle.emit("warmup-progress", WarmupEvent::Output { line });
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| format!("Failed to wait for cargo: {}", e))?;
+        if !status.success() {
+            return Err(format!("Failed to compile {}:\n{}", package, error_output));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `compiler-message` lines from cargo JSON output
+/// If `package_name` is given, only messages from that package are kept.
+pub fn parse_cargo_diagnostics(output: &str, package_name: Option<&str>) -> Vec<CompilerDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for line in output.lines() {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if value["reason"] != "compiler-message" {
+            continue;
+        }
+        if let Some(package) = package_name {
+            let target_name = value["target"]["name"].as_str().unwrap_or_default();
+            if target_name != package {
+                continue;
+            }
+        }
+
+        let message = &value["message"];
+        let primary_span = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+
+        diagnostics.push(CompilerDiagnostic {
+            level: message["level"].as_str().unwrap_or("error").to_string(),
+            message: message["message"].as_str().unwrap_or_default().to_string(),
+            code: message["code"]["code"].as_str().map(str::to_string),
+            file: primary_span.and_then(|span| span["file_name"].as_str()).map(str::to_string),
+            line: primary_span.and_then(|span| span["line_start"].as_u64()).map(|l| l as u32),
+            column: primary_span.and_then(|span| span["column_start"].as_u64()).map(|c| c as u32),
+            rendered: message["rendered"].as_str().map(str::to_string),
+        });
+    }
+
+    diagnostics
+}
+
+/// Run `cargo check --message-format=json` on a project
+/// Returns the package name, the raw output and the project's own diagnostics.
+async fn check_project(
+    project_path: &str,
+) -> Result<(String, std::process::Output, Vec<CompilerDiagnostic>), String> {
+    ensure_workspace()?;
+
+    let package_name = to_package_name(&project_folder_name(project_path)?);
+
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(get_workspace_path())
+        .args(["check", "--package", &package_name, "--message-format=json"])
+        .env("PATH", super::get_extended_path())
+        .stdin(Stdio::null());
+    configure_build_cache(&mut command);
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("Failed to spawn cargo: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let diagnostics = parse_cargo_diagnostics(&stdout, Some(&package_name));
+    Ok((package_name, output, diagnostics))
+}
+
+/// Folder name of a plugin project, checking that it has a Cargo.toml
+fn project_folder_name(project_path: &str) -> Result<String, String> {
+    let path = std::path::Path::new(project_path);
+    if !path.join("Cargo.toml").exists() {
+        return Err(format!("Not a plugin project: {}", project_path));
+    }
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid project path: {}", project_path))
+}
+
+/// Path of a diagnostic's file relative to the project, if it's inside the project's `src/`
+/// rustc reports workspace members relative to the workspace root (projects/<name>/src/...).
+fn project_source_file(file: &str, folder_name: &str) -> Option<String> {
+    let file = file.replace('\\', "/");
+    let member_prefix = format!("projects/{}/", folder_name);
+    let relative = match file.find(&member_prefix) {
+        Some(idx) => &file[idx + member_prefix.len()..],
+        None => file.as_str(),
+    };
+    relative.starts_with("src/").then(|| relative.to_string())
+}
+
+/// Keep only errors located in the project's `src/`, with paths made project-relative
+fn source_errors(diagnostics: Vec<CompilerDiagnostic>, folder_name: &str) -> Vec<CompilerDiagnostic> {
+    diagnostics
+        .into_iter()
+        .filter(|d| d.level == "error")
+        .filter_map(|mut d| {
+            d.file = Some(project_source_file(d.file.as_deref()?, folder_name)?);
+            Some(d)
+        })
+        .collect()
+}
+
+/// The first few errors as a code block, noting how many were left out
+fn format_errors(errors: &[CompilerDiagnostic]) -> String {
+    let details: Vec<String> = errors
+        .iter()
+        .take(MAX_PROMPT_ERRORS)
+        .map(|d| match &d.rendered {
+            Some(rendered) => rendered.trim_end().to_string(),
+            None => format!(
+                "error: {} at {}:{}",
+                d.message,
+                d.file.as_deref().unwrap_or("?"),
+                d.line.unwrap_or(0)
+            ),
+        })
+        .collect();
+    let omitted = errors.len().saturating_sub(MAX_PROMPT_ERRORS);
+
+    format!(
+        "```\n{}\n```\n{}",
+        details.join("\n\n"),
+        if omitted > 0 {
+            format!("\n({} more error(s) not shown - they may be caused by the ones above)\n", omitted)
+        } else {
+            String::new()
+        }
+    )
+}
+
+/// Follow-up message asking Claude to fix the given errors
+fn build_errors_prompt(errors: &[CompilerDiagnostic]) -> Option<String> {
+    if errors.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "The build failed with these errors:\n\n{}\nPlease fix these errors.",
+        format_errors(errors)
+    ))
+}
+
+/// The compile errors in a project's `src/`, formatted for a new Claude session's context
+/// None when the project compiles, or when the check fails to run or takes too long.
+/// Results are cached briefly so back-to-back sessions don't each run cargo.
+pub async fn current_error_summary(project_path: &str) -> Option<String> {
+    if let Some(summary) = cached_error_summary(project_path, Instant::now()) {
+        return summary;
+    }
+
+    let folder_name = project_folder_name(project_path).ok()?;
+    let checked_at = Instant::now();
+    let (_, output, diagnostics) = match tokio::time::timeout(ERROR_SUMMARY_TIMEOUT, check_project(project_path)).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            log::warn!("Couldn't check {} for Claude's context: {}", project_path, e);
+            return None;
+        }
+        Err(_) => {
+            log::warn!("cargo check of {} for Claude's context timed out", project_path);
+            return None;
+        }
+    };
+
+    let errors = if output.status.success() {
+        Vec::new()
+    } else {
+        source_errors(diagnostics, &folder_name)
+    };
+    let summary = (!errors.is_empty()).then(|| format_errors(&errors));
+    cache_error_summary(project_path, checked_at, summary.clone());
+    summary
+}
+
+/// Collect the compile errors of a project after a failed build, as a message for Claude
+/// Only errors in the project's own `src/` are included to keep the prompt focused. The
+/// frontend sends the prompt through send_to_claude, which resumes the project's session.
+#[tauri::command]
+pub async fn collect_build_errors(project_path: String) -> Result<BuildErrorReport, String> {
+    let folder_name = project_folder_name(&project_path)?;
+    let (_, _, diagnostics) = check_project(&project_path).await?;
+
+    let diagnostics = source_errors(diagnostics, &folder_name);
+    let prompt = build_errors_prompt(&diagnostics);
+    Ok(BuildErrorReport { diagnostics, prompt })
+}
+
+/// Run `cargo check` on a project and return its diagnostics
+/// Called right after create_project so a template that no longer compiles (e.g. after a
+/// nih-plug bump) is reported up front instead of on the first build.
+#[tauri::command]
+pub async fn validate_project(project_path: String) -> Result<ValidationResult, String> {
+    let (package_name, output, diagnostics) = check_project(&project_path).await?;
+
+    if output.status.success() {
+        return Ok(ValidationResult {
+            success: true,
+            diagnostics,
+            error: None,
+        });
+    }
+
+    let first_error = diagnostics.iter().find(|d| d.level == "error");
+    let error = match first_error {
+        // The generated code itself doesn't compile
+        Some(diagnostic) => format!(
+            "Template out of date: the generated project doesn't compile ({}{})",
+            diagnostic.message,
+            diagnostic
+                .file
+                .as_ref()
+                .map(|f| format!(" at {}:{}", f, diagnostic.line.unwrap_or(0)))
+                .unwrap_or_default()
+        ),
+        // Failed before reaching the project (dependency fetch, toolchain, ...)
+        None => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.lines().rev().take(20).collect();
+            format!(
+                "cargo check failed: {}",
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+            )
+        }
+    };
+    log::warn!("Project validation failed for {}: {}", package_name, error);
+
+    Ok(ValidationResult {
+        success: false,
+        diagnostics,
+        error: Some(error),
+    })
+}
+
 /// Recursively copy a directory
 fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result<()> {
     std::fs::create_dir_all(dst)?;
@@ -234,3 +941,111 @@ pub async fn open_output_folder() -> Result<(), String> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(file: &str) -> CompilerDiagnostic {
+        CompilerDiagnostic {
+            level: "error".to_string(),
+            message: "mismatched types".to_string(),
+            code: Some("E0308".to_string()),
+            file: Some(file.to_string()),
+            line: Some(7),
+            column: Some(5),
+            rendered: None,
+        }
+    }
+
+    #[test]
+    fn test_project_source_file() {
+        assert_eq!(
+            project_source_file("projects/my_synth/src/lib.rs", "my_synth").as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(
+            project_source_file("/Users/me/VSTWorkshop/projects/my_synth/src/dsp/filter.rs", "my_synth").as_deref(),
+            Some("src/dsp/filter.rs")
+        );
+        assert_eq!(project_source_file("src/lib.rs", "my_synth").as_deref(), Some("src/lib.rs"));
+        // Build scripts and dependency sources are left out
+        assert_eq!(project_source_file("projects/my_synth/build.rs", "my_synth"), None);
+        assert_eq!(
+            project_source_file("/Users/me/.cargo/git/checkouts/nih-plug/src/lib.rs", "my_synth"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_build_errors_prompt() {
+        let mut warning = error_at("projects/my_synth/src/lib.rs");
+        warning.level = "warning".to_string();
+        let diagnostics = vec![
+            error_at("projects/my_synth/src/lib.rs"),
+            warning,
+            error_at("/Users/me/.cargo/registry/src/dep/src/lib.rs"),
+        ];
+
+        let errors = source_errors(diagnostics, "my_synth");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file.as_deref(), Some("src/lib.rs"));
+
+        let prompt = build_errors_prompt(&errors).unwrap();
+        assert!(prompt.starts_with("The build failed with these errors:"));
+        assert!(prompt.contains("error: mismatched types at src/lib.rs:7"));
+        assert!(build_errors_prompt(&[]).is_none());
+    }
+
+    #[test]
+    fn test_error_summary_cache_expires() {
+        let checked_at = Instant::now();
+        cache_error_summary("/projects/cached_synth", checked_at, Some("errors".to_string()));
+
+        assert_eq!(
+            cached_error_summary("/projects/cached_synth", checked_at),
+            Some(Some("errors".to_string()))
+        );
+        assert_eq!(cached_error_summary("/projects/cached_synth", checked_at + ERROR_SUMMARY_TTL), None);
+        assert_eq!(cached_error_summary("/projects/other_synth", checked_at), None);
+    }
+
+    #[test]
+    fn test_parse_cargo_diagnostics() {
+        let output = [
+            r#"{"reason":"compiler-artifact","target":{"name":"nih_plug"}}"#,
+            r#"{"reason":"compiler-message","target":{"name":"nih_plug"},"message":{"level":"warning","message":"dependency warning","code":null,"spans":[],"rendered":"warning"}}"#,
+            r#"{"reason":"compiler-message","target":{"name":"my_synth"},"message":{"level":"error","message":"cannot find value `gain` in this scope","code":{"code":"E0425"},"spans":[{"file_name":"src/other.rs","line_start":3,"column_start":1,"is_primary":false},{"file_name":"src/lib.rs","line_start":42,"column_start":9,"is_primary":true}],"rendered":"error[E0425]: cannot find value"}}"#,
+            "not json",
+        ]
+        .join("\n");
+
+        let all = parse_cargo_diagnostics(&output, None);
+        assert_eq!(all.len(), 2);
+
+        let own = parse_cargo_diagnostics(&output, Some("my_synth"));
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].level, "error");
+        assert_eq!(own[0].code.as_deref(), Some("E0425"));
+        assert_eq!(own[0].file.as_deref(), Some("src/lib.rs"));
+        assert_eq!(own[0].line, Some(42));
+        assert_eq!(own[0].column, Some(9));
+    }
+
+    #[test]
+    fn test_disk_space_thresholds() {
+        assert_eq!(DiskSpaceStatus::from_free_bytes(0), DiskSpaceStatus::Critical);
+        assert_eq!(DiskSpaceStatus::from_free_bytes(MIN_BUILD_FREE_BYTES - 1), DiskSpaceStatus::Critical);
+        assert_eq!(DiskSpaceStatus::from_free_bytes(MIN_BUILD_FREE_BYTES), DiskSpaceStatus::Low);
+        assert_eq!(DiskSpaceStatus::from_free_bytes(LOW_DISK_WARNING_BYTES), DiskSpaceStatus::Ok);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_volume_space_of_missing_dir_uses_its_parent() {
+        let missing = std::env::temp_dir().join("freqlab_no_such_dir/target");
+        let (free, total) = volume_space(&missing).unwrap();
+        assert!(total > 0 && free <= total);
+        assert_eq!(dir_size(&missing), 0);
+    }
+}
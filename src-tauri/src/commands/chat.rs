@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 
 /// File attachment stored with a chat message
@@ -28,6 +29,8 @@ pub struct ChatMessage {
     pub reverted: bool, // Computed from activeVersion, kept for backwards compat
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub attachments: Option<Vec<FileAttachment>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interrupted: bool, // Recovered from the stream log after the app exited mid-turn
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -53,6 +56,180 @@ fn get_chat_file_path(project_path: &str) -> PathBuf {
         .join("chat.json")
 }
 
+fn get_chat_stream_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path)
+        .join(".vstworkshop")
+        .join("chat_stream.jsonl")
+}
+
+/// One line of the streaming chat log
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamRecord {
+    /// A Claude turn started
+    Start { timestamp: String },
+    /// A streamed text chunk, in arrival order
+    Text { content: String },
+}
+
+fn append_stream_record(project_path: &str, record: &StreamRecord) -> Result<(), String> {
+    let stream_file = get_chat_stream_path(project_path);
+    if let Some(parent) = stream_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create chat directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(record)
+        .map_err(|e| format!("Failed to serialize stream record: {}", e))?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&stream_file)
+        .map_err(|e| format!("Failed to open chat stream log: {}", e))?;
+    writeln!(file, "{}", line)
+        .map_err(|e| format!("Failed to write chat stream log: {}", e))
+}
+
+/// Start a fresh stream log for a new Claude turn (discards any previous log)
+pub fn begin_chat_stream(project_path: &str) -> Result<(), String> {
+    clear_chat_stream(project_path);
+    append_stream_record(project_path, &StreamRecord::Start {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Append a streamed text chunk to the log as it arrives
+pub fn append_chat_stream(project_path: &str, content: &str) -> Result<(), String> {
+    append_stream_record(project_path, &StreamRecord::Text {
+        content: content.to_string(),
+    })
+}
+
+/// Remove the stream log (the turn was handled by the frontend or folded into history)
+pub fn clear_chat_stream(project_path: &str) {
+    let stream_file = get_chat_stream_path(project_path);
+    if stream_file.exists() {
+        if let Err(e) = fs::remove_file(&stream_file) {
+            eprintln!("[WARN] Failed to remove chat stream log: {}", e);
+        }
+    }
+}
+
+/// Read the streamed content and start time of the logged turn, if any
+/// Unparseable lines (e.g. a partial write at crash time) are skipped
+fn read_chat_stream(project_path: &str) -> Option<(String, String)> {
+    let file = fs::File::open(get_chat_stream_path(project_path)).ok()?;
+
+    let mut started_at = None;
+    let mut chunks = Vec::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        match serde_json::from_str::<StreamRecord>(&line) {
+            Ok(StreamRecord::Start { timestamp }) => started_at = Some(timestamp),
+            Ok(StreamRecord::Text { content }) => chunks.push(content),
+            Err(_) => continue,
+        }
+    }
+
+    // Same layout as the frontend's streaming view (one paragraph per chunk)
+    let content = chunks.join("\n\n").trim().to_string();
+    if content.is_empty() {
+        return None;
+    }
+    Some((content, started_at.unwrap_or_else(|| chrono::Utc::now().to_rfc3339())))
+}
+
+fn read_history(chat_file: &PathBuf) -> Result<ChatHistory, String> {
+    if !chat_file.exists() {
+        return Ok(ChatHistory {
+            messages: Vec::new(),
+            last_updated: chrono::Utc::now().to_rfc3339(),
+            active_version: None,
+        });
+    }
+    let content = fs::read_to_string(chat_file)
+        .map_err(|e| format!("Failed to read chat history: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse chat history: {}", e))
+}
+
+fn write_history(chat_file: &PathBuf, history: &ChatHistory) -> Result<(), String> {
+    if let Some(parent) = chat_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create chat directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(history)
+        .map_err(|e| format!("Failed to serialize chat history: {}", e))?;
+    fs::write(chat_file, json)
+        .map_err(|e| format!("Failed to write chat history: {}", e))
+}
+
+/// Fold the logged turn into the chat history as an assistant message, then truncate the log
+/// Called on a clean Done so the turn is on disk even if the frontend never saves it
+pub fn complete_chat_stream(project_path: &str, commit_hash: Option<String>) -> Result<(), String> {
+    let Some((content, _)) = read_chat_stream(project_path) else {
+        clear_chat_stream(project_path);
+        return Ok(());
+    };
+
+    let chat_file = get_chat_file_path(project_path);
+    let mut history = read_history(&chat_file)?;
+
+    // Same numbering as the frontend: versions count up for each turn that changed files
+    let version = commit_hash
+        .as_ref()
+        .map(|_| history.messages.iter().filter(|m| m.version.is_some()).count() as u32 + 1);
+
+    history.messages.push(ChatMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        role: "assistant".to_string(),
+        content,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        commit_hash,
+        version,
+        reverted: false,
+        attachments: None,
+        interrupted: false,
+    });
+    if version.is_some() {
+        history.active_version = version;
+    }
+    history.last_updated = chrono::Utc::now().to_rfc3339();
+
+    write_history(&chat_file, &history)?;
+    clear_chat_stream(project_path);
+    Ok(())
+}
+
+/// Recover a turn left in the stream log by a crash: append it as an interrupted
+/// assistant message and truncate the log. Skipped while a Claude session is running.
+fn recover_chat_stream(project_path: &str, history: &mut ChatHistory) -> Result<bool, String> {
+    if super::claude::is_session_active(project_path) {
+        return Ok(false);
+    }
+    let Some((content, started_at)) = read_chat_stream(project_path) else {
+        clear_chat_stream(project_path);
+        return Ok(false);
+    };
+
+    history.messages.push(ChatMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        role: "assistant".to_string(),
+        content: format!("{}\n\n(Interrupted - the app closed before this response finished.)", content),
+        timestamp: started_at,
+        commit_hash: None,
+        version: None,
+        reverted: false,
+        attachments: None,
+        interrupted: true,
+    });
+    history.last_updated = chrono::Utc::now().to_rfc3339();
+
+    write_history(&get_chat_file_path(project_path), history)?;
+    clear_chat_stream(project_path);
+    Ok(true)
+}
+
 /// Save chat history with optional explicit activeVersion
 /// If activeVersion is provided, it will be used (even if None to clear it)
 /// If activeVersion is not provided (undefined in JS), preserves existing value from disk
@@ -108,18 +285,14 @@ pub async fn save_chat_history(
 pub async fn load_chat_history(project_path: String) -> Result<ChatState, String> {
     let chat_file = get_chat_file_path(&project_path);
 
-    if !chat_file.exists() {
-        return Ok(ChatState {
-            messages: Vec::new(),
-            active_version: None,
-        });
-    }
-
-    let content = fs::read_to_string(&chat_file)
-        .map_err(|e| format!("Failed to read chat history: {}", e))?;
+    let mut history = read_history(&chat_file)?;
 
-    let history: ChatHistory = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse chat history: {}", e))?;
+    // Reconstruct a turn that was still streaming when the app exited
+    match recover_chat_stream(&project_path, &mut history) {
+        Ok(true) => eprintln!("[DEBUG] Recovered interrupted Claude turn from stream log"),
+        Ok(false) => {}
+        Err(e) => eprintln!("[WARN] Failed to recover chat stream: {}", e),
+    }
 
     Ok(ChatState {
         messages: history.messages,
@@ -10,6 +10,65 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::time::timeout;
 
+/// Default agent turn limit per request
+const DEFAULT_MAX_TURNS: u32 = 50;
+
+/// Upper bound for a caller-supplied turn limit
+const MAX_TURNS_LIMIT: u32 = 500;
+
+/// Longest saved project instructions accepted (characters)
+const MAX_PROJECT_INSTRUCTIONS_CHARS: usize = 4000;
+
+/// Tools allowed by default: file ops, bash for cargo commands, grep/glob for searching, web access, and skills
+const DEFAULT_ALLOWED_TOOLS: &[&str] = &[
+    "Edit", "Write", "Read", "Bash", "Grep", "Glob", "WebSearch", "WebFetch", "Skill",
+];
+
+/// Every tool name a caller may put in the allowlist
+const KNOWN_TOOLS: &[&str] = &[
+    "Edit", "MultiEdit", "Write", "Read", "Bash", "Grep", "Glob", "LS", "WebSearch", "WebFetch",
+    "Skill", "Task", "TodoWrite", "NotebookEdit",
+];
+
+/// Validate a requested turn limit, falling back to the default
+fn resolve_max_turns(max_turns: Option<u32>) -> Result<u32, String> {
+    match max_turns {
+        None => Ok(DEFAULT_MAX_TURNS),
+        Some(turns) if (1..=MAX_TURNS_LIMIT).contains(&turns) => Ok(turns),
+        Some(turns) => Err(format!(
+            "Invalid max turns {} (must be between 1 and {})",
+            turns, MAX_TURNS_LIMIT
+        )),
+    }
+}
+
+/// Validate a requested tool allowlist and join it for --allowedTools
+/// Unknown tool names are rejected rather than passed through silently.
+fn resolve_allowed_tools(allowed_tools: Option<Vec<String>>) -> Result<String, String> {
+    let Some(tools) = allowed_tools else {
+        return Ok(DEFAULT_ALLOWED_TOOLS.join(","));
+    };
+
+    let mut resolved: Vec<&str> = Vec::new();
+    for tool in &tools {
+        let tool = tool.trim();
+        let Some(known) = KNOWN_TOOLS.iter().find(|known| **known == tool) else {
+            return Err(format!(
+                "Unknown tool '{}' (known tools: {})",
+                tool,
+                KNOWN_TOOLS.join(", ")
+            ));
+        };
+        if !resolved.contains(known) {
+            resolved.push(known);
+        }
+    }
+    if resolved.is_empty() {
+        return Err("At least one tool must be allowed".to_string());
+    }
+    Ok(resolved.join(","))
+}
+
 // Track active Claude processes by project path so we can interrupt them
 static ACTIVE_PROCESSES: Mutex<Option<HashMap<String, u32>>> = Mutex::new(None);
 
@@ -35,6 +94,11 @@ fn get_process_pid(project_path: &str) -> Option<u32> {
     guard.as_ref().and_then(|map| map.get(project_path).copied())
 }
 
+/// Whether a Claude session is currently running for this project
+pub fn is_session_active(project_path: &str) -> bool {
+    get_process_pid(project_path).is_some()
+}
+
 #[derive(Serialize, Clone)]
 pub struct ClaudeResponse {
     pub content: String,
@@ -53,6 +117,17 @@ pub enum ClaudeStreamEvent {
     Error { project_path: String, message: String },
     #[serde(rename = "done")]
     Done { project_path: String, content: String },
+    /// Structured tool activity, emitted alongside the human-readable text for the same tool use
+    #[serde(rename = "tool")]
+    Tool {
+        project_path: String,
+        /// Tool name as reported by the CLI (e.g. "Read", "Bash")
+        tool: String,
+        /// Short description of the action (e.g. "Reading file")
+        summary: String,
+        /// The relevant argument (file path, command, pattern) if the tool has one
+        target: Option<String>,
+    },
 }
 
 /// Represents a parsed event from Claude CLI stream-json output
@@ -107,16 +182,218 @@ fn save_session_id(project_path: &str, session_id: &str) -> Result<(), String> {
         .map_err(|e| format!("Failed to save session ID: {}", e))
 }
 
+/// Get the path of the last prompt dump for a project
+fn get_last_prompt_file(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path)
+        .join(".vstworkshop")
+        .join("last_prompt.txt")
+}
+
+/// Get the path of a project's saved custom instructions
+fn get_instructions_file(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path)
+        .join(".vstworkshop")
+        .join("instructions.md")
+}
+
+/// Load a project's saved custom instructions (None if there are none)
+/// A file edited by hand past the length cap is cut at the cap.
+fn load_project_instructions(project_path: &str) -> Option<String> {
+    let content = fs::read_to_string(get_instructions_file(project_path)).ok()?;
+    let instructions = content.trim();
+    if instructions.is_empty() {
+        return None;
+    }
+    Some(instructions.chars().take(MAX_PROJECT_INSTRUCTIONS_CHARS).collect())
+}
+
+/// Save custom instructions that are added to every new Claude session of a project
+/// Empty text removes them.
+#[tauri::command]
+pub async fn set_project_instructions(project_path: String, text: String) -> Result<(), String> {
+    if !project_exists(&project_path) {
+        return Err(format!("Project not found: {}", project_path));
+    }
+
+    let text = text.trim();
+    let length = text.chars().count();
+    if length > MAX_PROJECT_INSTRUCTIONS_CHARS {
+        return Err(format!(
+            "Project instructions are too long ({} characters, max {})",
+            length, MAX_PROJECT_INSTRUCTIONS_CHARS
+        ));
+    }
+
+    let path = get_instructions_file(&project_path);
+    if text.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove project instructions: {}", e))?;
+        }
+        return Ok(());
+    }
+    fs::write(&path, format!("{}\n", text)).map_err(|e| format!("Failed to save project instructions: {}", e))
+}
+
+/// Get a project's saved custom instructions (empty if there are none)
+#[tauri::command]
+pub async fn get_project_instructions(project_path: String) -> Result<String, String> {
+    Ok(load_project_instructions(&project_path).unwrap_or_default())
+}
+
+/// Marks the start of the raw user message (always the last section of the dump)
+const LAST_PROMPT_MESSAGE_HEADER: &str = "=== User message ===";
+
+/// Record the exact CLI args and system prompt of a request in .vstworkshop/last_prompt.txt
+/// Long values (message, system prompt) get their own sections; the resumed session id is redacted.
+fn write_last_prompt(project_path: &str, args: &[String], message: &str) -> Result<(), String> {
+    let mut arg_lines = Vec::new();
+    let mut system_prompt = None;
+    let mut prompt = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-p" | "--append-system-prompt" | "--resume" => {
+                let value = iter.next().map(String::as_str).unwrap_or_default();
+                let shown = match arg.as_str() {
+                    "-p" => {
+                        prompt = Some(value);
+                        "<prompt below>"
+                    }
+                    "--append-system-prompt" => {
+                        system_prompt = Some(value);
+                        "<system prompt below>"
+                    }
+                    _ => "<session id redacted>",
+                };
+                arg_lines.push(format!("{} {}", arg, shown));
+            }
+            _ => arg_lines.push(arg.clone()),
+        }
+    }
+
+    let content = format!(
+        "# Claude request recorded {}\n\n=== Args ===\nclaude\n  {}\n\n=== Prompt ===\n{}\n\n=== System prompt ===\n{}\n\n{}\n{}\n",
+        chrono::Utc::now().to_rfc3339(),
+        arg_lines.join("\n  "),
+        prompt.unwrap_or_default(),
+        system_prompt.unwrap_or("(resumed session - the system prompt was sent with the session's first message)"),
+        LAST_PROMPT_MESSAGE_HEADER,
+        message
+    );
+
+    fs::write(get_last_prompt_file(project_path), content)
+        .map_err(|e| format!("Failed to write last prompt: {}", e))
+}
+
+/// The last recorded request of a project
+#[derive(Serialize, Clone)]
+pub struct LastPromptDump {
+    /// Path of the dump file
+    pub path: String,
+    /// Full dump (args, prompt, system prompt, user message)
+    pub content: String,
+    /// The user's message as sent from the chat, for replaying the request
+    pub message: String,
+}
+
+/// Read the args and system prompt recorded for the last request sent to Claude
+#[tauri::command]
+pub async fn dump_last_prompt(project_path: String) -> Result<LastPromptDump, String> {
+    let path = get_last_prompt_file(&project_path);
+    if !path.exists() {
+        return Err("No prompt has been recorded for this project yet".to_string());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read last prompt: {}", e))?;
+    let message = content
+        .split_once(&format!("{}\n", LAST_PROMPT_MESSAGE_HEADER))
+        .map(|(_, message)| message.trim_end_matches('\n').to_string())
+        .unwrap_or_default();
+
+    Ok(LastPromptDump {
+        path: path.to_string_lossy().to_string(),
+        content,
+        message,
+    })
+}
+
 /// Extract session_id from a JSON event if present
 fn extract_session_id(json_str: &str) -> Option<String> {
     let event: ClaudeJsonEvent = serde_json::from_str(json_str).ok()?;
     event.session_id
 }
 
+/// Structured details of a tool use
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedToolUse {
+    /// Tool name as reported by the CLI
+    tool: String,
+    /// Short description of the action
+    summary: String,
+    /// The relevant argument (file path, command, pattern) if present
+    target: Option<String>,
+}
+
+impl ParsedToolUse {
+    /// Extract the tool name and its relevant argument from a tool_use event
+    fn from_event(tool: &str, input: Option<&serde_json::Value>) -> Self {
+        let (summary, key) = match tool {
+            "Read" => ("Reading file", "file_path"),
+            "Edit" | "MultiEdit" => ("Editing file", "file_path"),
+            "Write" => ("Writing file", "file_path"),
+            "Bash" => ("Running command", "command"),
+            "Grep" | "Glob" => ("Searching", "pattern"),
+            _ => ("Using tool", "file_path"),
+        };
+        let target = input
+            .and_then(|input| input.get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        Self {
+            tool: tool.to_string(),
+            summary: summary.to_string(),
+            target,
+        }
+    }
+
+    /// Human-readable line for the text stream (kept for backward compatibility)
+    fn display_text(&self) -> String {
+        match self.tool.as_str() {
+            "Read" => match &self.target {
+                Some(file) => format!("📖 Reading: {}", file),
+                None => "📖 Reading file...".to_string(),
+            },
+            "Edit" => match &self.target {
+                Some(file) => format!("✏️  Editing: {}", file),
+                None => "✏️  Editing file...".to_string(),
+            },
+            "Write" => match &self.target {
+                Some(file) => format!("📝 Writing: {}", file),
+                None => "📝 Writing file...".to_string(),
+            },
+            "Bash" => match &self.target {
+                Some(cmd) => {
+                    // Truncate long commands (on a char boundary)
+                    let display_cmd = match cmd.char_indices().nth(60) {
+                        Some((idx, _)) => format!("{}...", &cmd[..idx]),
+                        None => cmd.clone(),
+                    };
+                    format!("💻 Running: {}", display_cmd)
+                }
+                None => "💻 Running command...".to_string(),
+            },
+            tool => format!("🔧 Using tool: {}", tool),
+        }
+    }
+}
+
 /// Result of parsing a Claude JSON event
 struct ParsedEvent {
     /// Human-readable text to display during streaming
     display_text: Option<String>,
+    /// If this is a tool use, its structured details
+    tool_use: Option<ParsedToolUse>,
     /// If this is an assistant message, the full text content (for final message extraction)
     assistant_content: Option<String>,
     /// If this is an error event, the error message
@@ -131,6 +408,7 @@ struct ParsedEvent {
 fn parse_claude_event(json_str: &str) -> ParsedEvent {
     let default_event = ParsedEvent {
         display_text: None,
+        tool_use: None,
         assistant_content: None,
         error_content: None,
         is_result_event: false,
@@ -172,6 +450,7 @@ fn parse_claude_event(json_str: &str) -> ParsedEvent {
 
                     return ParsedEvent {
                         display_text: text.clone(),
+                        tool_use: None,
                         assistant_content: text,
                         error_content: None,
                         is_result_event: false,
@@ -183,57 +462,10 @@ fn parse_claude_event(json_str: &str) -> ParsedEvent {
         }
         "tool_use" => {
             let tool = event.tool.as_deref().unwrap_or("unknown");
-            let display = match tool {
-                "Read" => {
-                    if let Some(input) = &event.tool_input {
-                        let file = input.get("file_path")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("file");
-                        format!("📖 Reading: {}", file)
-                    } else {
-                        "📖 Reading file...".to_string()
-                    }
-                }
-                "Edit" => {
-                    if let Some(input) = &event.tool_input {
-                        let file = input.get("file_path")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("file");
-                        format!("✏️  Editing: {}", file)
-                    } else {
-                        "✏️  Editing file...".to_string()
-                    }
-                }
-                "Write" => {
-                    if let Some(input) = &event.tool_input {
-                        let file = input.get("file_path")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("file");
-                        format!("📝 Writing: {}", file)
-                    } else {
-                        "📝 Writing file...".to_string()
-                    }
-                }
-                "Bash" => {
-                    if let Some(input) = &event.tool_input {
-                        let cmd = input.get("command")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("command");
-                        // Truncate long commands
-                        let display_cmd = if cmd.len() > 60 {
-                            format!("{}...", &cmd[..60])
-                        } else {
-                            cmd.to_string()
-                        };
-                        format!("💻 Running: {}", display_cmd)
-                    } else {
-                        "💻 Running command...".to_string()
-                    }
-                }
-                _ => format!("🔧 Using tool: {}", tool),
-            };
+            let tool_use = ParsedToolUse::from_event(tool, event.tool_input.as_ref());
             ParsedEvent {
-                display_text: Some(display),
+                display_text: Some(tool_use.display_text()),
+                tool_use: Some(tool_use),
                 assistant_content: None,
                 error_content: None,
                 is_result_event: false,
@@ -244,6 +476,7 @@ fn parse_claude_event(json_str: &str) -> ParsedEvent {
             // Tool completed - could show result summary
             ParsedEvent {
                 display_text: Some("   ✓ Done".to_string()),
+                tool_use: None,
                 assistant_content: None,
                 error_content: None,
                 is_result_event: false,
@@ -268,6 +501,7 @@ fn parse_claude_event(json_str: &str) -> ParsedEvent {
 
             ParsedEvent {
                 display_text: None,  // Don't display - duplicates assistant message
+                tool_use: None,
                 assistant_content: None,
                 error_content,
                 is_result_event: true,
@@ -284,6 +518,7 @@ fn parse_claude_event(json_str: &str) -> ParsedEvent {
             };
             ParsedEvent {
                 display_text: Some(display),
+                tool_use: None,
                 assistant_content: None,
                 error_content: error_msg,
                 is_result_event: false,
@@ -323,6 +558,7 @@ fn build_context(
     components: Option<&Vec<String>>,
     is_first_message: bool,
     ui_framework: Option<&str>,
+    build_errors: Option<&str>,
 ) -> String {
     // Get path to local nih-plug repo for documentation
     let nih_plug_docs_path = super::projects::get_nih_plug_docs_path();
@@ -368,6 +604,15 @@ fn build_context(
             context.push_str("A plugin with no UI controls is BROKEN. Always update both lib.rs AND ui.html.\n\n");
             context.push_str("---\n\n");
         }
+
+        // Tell the agent up front when it's starting from code that doesn't compile
+        if let Some(errors) = build_errors {
+            context.push_str("## CURRENT STATE: THE PROJECT DOES NOT COMPILE\n\n");
+            context.push_str("`cargo check` reports these errors in the project's current code:\n\n");
+            context.push_str(errors);
+            context.push_str("\nFix these errors first, then implement what the user asked for.\n\n");
+            context.push_str("---\n\n");
+        }
     }
 
     context.push_str(&format!(
@@ -462,6 +707,15 @@ The user will describe what they want. Make the changes directly to the code."#,
     context
 }
 
+/// Whether the project is still on disk (its folder and metadata.json)
+/// Checking the metadata too catches a deleted project whose folder was recreated by a stray write.
+fn project_exists(project_path: &str) -> bool {
+    PathBuf::from(project_path)
+        .join(".vstworkshop")
+        .join("metadata.json")
+        .is_file()
+}
+
 /// Load project metadata to get components and other info
 fn load_project_metadata(project_path: &str) -> Option<super::projects::ProjectMeta> {
     let metadata_path = PathBuf::from(project_path)
@@ -481,20 +735,16 @@ pub async fn send_to_claude(
     model: Option<String>,
     custom_instructions: Option<String>,
     agent_verbosity: Option<String>,
+    max_turns: Option<u32>,
+    allowed_tools: Option<Vec<String>>,
     window: tauri::Window,
 ) -> Result<ClaudeResponse, String> {
-    // Ensure git is initialized for this project (handles existing projects)
-    if !super::git::is_git_repo(&project_path) {
-        super::git::init_repo(&project_path).await?;
-        super::git::create_gitignore(&project_path)?;
-        super::git::commit_changes(&project_path, "Initialize git for version control").await?;
-    }
+    // Validate agent limits up front so a bad request doesn't touch the project
+    let max_turns = resolve_max_turns(max_turns)?;
+    let allowed_tools = resolve_allowed_tools(allowed_tools)?;
 
-    // Ensure .vstworkshop/ is not tracked by git (fixes existing projects)
-    // This prevents chat.json from being reverted when doing git checkout
-    if let Err(e) = super::git::ensure_vstworkshop_ignored(&project_path) {
-        eprintln!("[WARN] Failed to update gitignore: {}", e);
-    }
+    // Ensure git is initialized and .vstworkshop/ untracked (handles existing projects)
+    super::doctor::ensure_git_setup(&project_path).await?;
 
     // Record HEAD commit before Claude runs (to detect if Claude commits changes itself)
     let head_before = super::git::get_head_commit(&project_path).await.ok();
@@ -509,8 +759,23 @@ pub async fn send_to_claude(
     let components = metadata.as_ref().and_then(|m| m.components.as_ref());
     let ui_framework = metadata.as_ref().and_then(|m| m.ui_framework.as_deref());
 
+    // A new session is told when the project is already broken
+    let build_errors = if is_first_message {
+        super::build::current_error_summary(&project_path).await
+    } else {
+        None
+    };
+
     // Build context with components info and project-specific CLAUDE.md
-    let context = build_context(&project_name, &description, &project_path, components, is_first_message, ui_framework);
+    let context = build_context(
+        &project_name,
+        &description,
+        &project_path,
+        components,
+        is_first_message,
+        ui_framework,
+        build_errors.as_deref(),
+    );
 
     // Get verbosity style (default to balanced)
     let verbosity = agent_verbosity.as_deref().unwrap_or("balanced");
@@ -528,12 +793,11 @@ pub async fn send_to_claude(
         styled_message.clone(),
         "--verbose".to_string(),
         "--allowedTools".to_string(),
-        // Allow file ops, bash for cargo commands, grep/glob for searching, web access, and skills
-        "Edit,Write,Read,Bash,Grep,Glob,WebSearch,WebFetch,Skill".to_string(),
+        allowed_tools,
         "--output-format".to_string(),
         "stream-json".to_string(),
         "--max-turns".to_string(),
-        "50".to_string(),
+        max_turns.to_string(),
     ];
 
     // Add model flag if specified
@@ -580,10 +844,12 @@ pub async fn send_to_claude(
         // Build full system prompt with verbosity and custom instructions
         let mut full_context = format!("{}\n{}", context, verbosity_instructions);
 
-        if let Some(ref instructions) = custom_instructions {
-            if !instructions.trim().is_empty() {
-                full_context.push_str(&format!("\n\n--- USER PREFERENCES ---\n{}", instructions.trim()));
-            }
+        // Instructions sent with the request override the ones saved for the project
+        let instructions = custom_instructions
+            .filter(|instructions| !instructions.trim().is_empty())
+            .or_else(|| load_project_instructions(&project_path));
+        if let Some(ref instructions) = instructions {
+            full_context.push_str(&format!("\n\n--- USER PREFERENCES ---\n{}", instructions.trim()));
         }
 
         args.push("--append-system-prompt".to_string());
@@ -599,6 +865,11 @@ pub async fn send_to_claude(
         eprintln!("[DEBUG] Starting new Claude session");
     }
 
+    // Record the exact request so unexpected agent behavior can be traced back to its context
+    if let Err(e) = write_last_prompt(&project_path, &args, &message) {
+        eprintln!("[WARN] {}", e);
+    }
+
     // Spawn Claude CLI process with stream-json for detailed output
     // stdin is set to null to prevent any blocking on input
     let mut child = Command::new("claude")
@@ -616,6 +887,11 @@ pub async fn send_to_claude(
         register_process(&project_path, pid);
     }
 
+    // Start the stream log so streamed content survives a crash mid-turn
+    if let Err(e) = super::chat::begin_chat_stream(&project_path) {
+        eprintln!("[WARN] Failed to start chat stream log: {}", e);
+    }
+
     // Emit start event
     let _ = window.emit("claude-stream", ClaudeStreamEvent::Start {
         project_path: project_path.clone()
@@ -649,6 +925,9 @@ pub async fn send_to_claude(
     // Track if we were interrupted (checked each iteration)
     let mut was_interrupted_during_loop = false;
 
+    // Track if the project was deleted while Claude was running (checked each iteration)
+    let mut project_removed = false;
+
     // Timeout settings for reading:
     // - Short timeout (5s) allows us to check for interrupts frequently
     // - We track total idle time to detect truly stalled processes
@@ -665,6 +944,15 @@ pub async fn send_to_claude(
             break;
         }
 
+        // Stop before writing anything else into a project that no longer exists
+        if !project_exists(&project_path) {
+            eprintln!("[WARN] Project was removed while Claude was running - killing process");
+            project_removed = true;
+            let _ = child.kill().await;
+            unregister_process(&project_path);
+            break;
+        }
+
         let read_result = timeout(read_timeout, async {
             tokio::select! {
                 line = stdout_reader.next_line() => ("stdout", line),
@@ -719,11 +1007,22 @@ pub async fn send_to_claude(
                         if let Some(display_text) = parsed.display_text {
                             full_output.push_str(&display_text);
                             full_output.push('\n');
+                            let _ = super::chat::append_chat_stream(&project_path, &display_text);
                             let _ = window.emit("claude-stream", ClaudeStreamEvent::Text {
                                 project_path: project_path.clone(),
                                 content: display_text,
                             });
                         }
+
+                        // Structured tool activity for the frontend's activity view
+                        if let Some(tool_use) = parsed.tool_use {
+                            let _ = window.emit("claude-stream", ClaudeStreamEvent::Tool {
+                                project_path: project_path.clone(),
+                                tool: tool_use.tool,
+                                summary: tool_use.summary,
+                                target: tool_use.target,
+                            });
+                        }
                     }
                     Ok(None) => {
                         // EOF on stdout - fallback exit condition
@@ -746,9 +1045,11 @@ pub async fn send_to_claude(
                         error_output.push_str(&text);
                         error_output.push('\n');
                         // Also emit stderr as it may contain useful info
+                        let stderr_text = format!("[stderr] {}", text);
+                        let _ = super::chat::append_chat_stream(&project_path, &stderr_text);
                         let _ = window.emit("claude-stream", ClaudeStreamEvent::Text {
                             project_path: project_path.clone(),
-                            content: format!("[stderr] {}", text),
+                            content: stderr_text,
                         });
                     }
                     Ok(None) => {
@@ -799,6 +1100,16 @@ pub async fn send_to_claude(
         }
     }
 
+    // Project deleted mid-turn: the process is already killed, and there's nothing to commit to
+    if project_removed {
+        let _ = timeout(Duration::from_secs(2), child.wait()).await;
+        let _ = window.emit("claude-stream", ClaudeStreamEvent::Error {
+            project_path: project_path.clone(),
+            message: "The project was removed while Claude was working. Session stopped.".to_string(),
+        });
+        return Err("Project removed".to_string());
+    }
+
     // Wait for process to complete with a short timeout
     // Timeout varies based on how we exited the loop:
     // - Interrupt: 2 seconds (user wants it stopped NOW)
@@ -815,6 +1126,7 @@ pub async fn send_to_claude(
     let status = match timeout(wait_timeout, child.wait()).await {
         Ok(Ok(status)) => status,
         Ok(Err(e)) => {
+            super::chat::clear_chat_stream(&project_path);
             return Err(format!("Failed to wait for Claude CLI: {}", e));
         }
         Err(_) => {
@@ -834,6 +1146,9 @@ pub async fn send_to_claude(
 
     // Handle non-success exit
     if !status.success() {
+        // The frontend saves partial/error messages itself - nothing to recover from the log
+        super::chat::clear_chat_stream(&project_path);
+
         // Interrupted sessions take priority - don't report as error
         if was_interrupted {
             // Process was killed by user interrupt - don't emit another error (interrupt_claude already did)
@@ -978,6 +1293,11 @@ pub async fn send_to_claude(
         }
     };
 
+    // Fold the streamed turn into chat history and truncate the stream log
+    if let Err(e) = super::chat::complete_chat_stream(&project_path, commit_hash.clone()) {
+        eprintln!("[WARN] Failed to fold chat stream into history: {}", e);
+    }
+
     Ok(ClaudeResponse {
         content: final_content,
         session_id: captured_session_id,
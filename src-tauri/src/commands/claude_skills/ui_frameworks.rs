@@ -167,6 +167,81 @@ fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Edi
 </html>
 ```
 
+## Throttling Meter Updates
+
+The event loop runs every frame (display refresh rate). Pushing meter data that often can peg
+the CPU in heavy UIs, so send it at a fixed rate instead - but keep draining `ctx.next_event()`
+every frame so input stays responsive:
+
+```rust
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const METER_INTERVAL: Duration = Duration::from_millis(33); // ~30 Hz
+
+let peak_meter = self.peak_meter.clone();
+// The event loop closure is `Fn`, so keep the timestamp behind a Mutex
+let last_meter_update = Mutex::new(Instant::now());
+
+// ... inside .with_event_loop(move |ctx, setter, window| { ... })
+while let Ok(msg) = ctx.next_event() {
+    // handle UI messages as usual (every frame)
+}
+
+let mut last = last_meter_update.lock().unwrap();
+if last.elapsed() >= METER_INTERVAL {
+    *last = Instant::now();
+    let peak = f32::from_bits(peak_meter.load(Ordering::Relaxed));
+    ctx.send_json(json!({ "type": "meter", "peak": peak }));
+}
+```
+
+## Batching Parameter Updates
+
+Sending one `param_change` per dirty parameter floods the IPC channel on preset recall. The
+generated event loop collects every dirty parameter and sends a single message instead:
+
+```rust
+let mut updates = Vec::new();
+if gain_changed.swap(false, Ordering::Relaxed) {
+    updates.push(json!({ "param": "gain", "value": params.gain.unmodulated_normalized_value(), "text": params.gain.to_string() }));
+}
+if cutoff_changed.swap(false, Ordering::Relaxed) {
+    updates.push(json!({ "param": "cutoff", "value": params.cutoff.unmodulated_normalized_value(), "text": params.cutoff.to_string() }));
+}
+if !updates.is_empty() {
+    ctx.send_json(json!({ "type": "param_batch", "updates": updates }));
+}
+```
+
+`ui.html` applies each entry of `msg.updates` with the same code it uses for a single `param_change`.
+When adding a parameter, push it into `updates` rather than calling `ctx.send_json` for it.
+
+## Resizing the Editor from the UI
+
+`Resize` is a reserved message type. The generated `lib.rs` handles it by clamping the size
+to `EDITOR_MIN_SIZE`/`EDITOR_MAX_SIZE` and calling `ctx.resize(window, width, height)`, which
+keeps the editor's stored size and the host window in sync. Don't reuse the name for your own messages.
+
+```javascript
+// Switch to an expanded layout and have the host window follow
+requestResize(800, 500);   // sends { type: 'Resize', width: 800, height: 500 }
+
+window.onPluginMessage = function(msg) {
+    if (msg.type === 'resized') {
+        // msg.width / msg.height are the size actually applied (after clamping)
+    }
+};
+```
+
+## Text Input and Keyboard Focus
+
+Many DAWs keep keystrokes for their own shortcuts, so text fields in the UI never see them.
+`SetKeyboardFocus` is a reserved message type: the generated `ui.html` sends
+`{ type: 'SetKeyboardFocus', focused }` when an `<input>`/`<textarea>` gains or loses focus, and
+`lib.rs` stores it in a `wants_keyboard` AtomicBool that `.with_keyboard_handler()` returns, so
+all keys are captured while typing. Keep both pieces when restructuring the editor code.
+
 ## Common Pitfalls to Avoid
 
 | Pitfall | Problem | Solution |
@@ -0,0 +1,363 @@
+//! Project doctor: checks a plugin project for the breakages users run into (missing files,
+//! workspace drift, corrupt metadata, git setup) and repairs the ones that are safe to fix
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use super::projects::{generate_webview_ui_html, get_workspace_path, to_pascal_case, ProjectMeta};
+
+/// What a detected problem is about
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    MetadataMissing,
+    MetadataInvalid,
+    LibRsMissing,
+    UiHtmlMissing,
+    CargoTomlMissing,
+    /// The workspace Cargo.toml doesn't include the projects folder
+    WorkspaceManifestOutdated,
+    /// The project is outside the projects folder or declares its own [workspace]
+    NotWorkspaceMember,
+    GitMissing,
+    VstworkshopNotIgnored,
+    NihPlugRevMismatch,
+}
+
+impl IssueKind {
+    /// Whether auto_fix may repair it (nothing the user wrote is touched)
+    fn auto_fixable(self) -> bool {
+        matches!(
+            self,
+            IssueKind::UiHtmlMissing
+                | IssueKind::WorkspaceManifestOutdated
+                | IssueKind::GitMissing
+                | IssueKind::VstworkshopNotIgnored
+        )
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// The project won't build or load
+    Error,
+    /// Works for now, but something will go wrong later
+    Warning,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProjectIssue {
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    pub message: String,
+    pub suggestion: String,
+    pub auto_fixable: bool,
+    /// Repaired by this run (auto_fix)
+    pub fixed: bool,
+    /// Why the repair failed, if it was attempted
+    pub fix_error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProjectDiagnosis {
+    pub project_path: String,
+    pub issues: Vec<ProjectIssue>,
+    /// No issues left unfixed
+    pub healthy: bool,
+}
+
+fn issue(kind: IssueKind, severity: IssueSeverity, message: String, suggestion: &str) -> ProjectIssue {
+    ProjectIssue {
+        kind,
+        severity,
+        message,
+        suggestion: suggestion.to_string(),
+        auto_fixable: kind.auto_fixable(),
+        fixed: false,
+        fix_error: None,
+    }
+}
+
+/// The `rev` pinned for dependency `name` in a Cargo.toml (None if it isn't a git dependency)
+fn dependency_rev(cargo_toml: &str, name: &str) -> Option<String> {
+    cargo_toml.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() != name {
+            return None;
+        }
+        let rev = value.split("rev").nth(1)?;
+        let rev = rev.trim_start().strip_prefix('=')?.trim_start().strip_prefix('"')?;
+        Some(rev.split('"').next()?.to_string())
+    })
+}
+
+/// Run every check on the project at `project_path` against the workspace at `workspace`
+fn inspect_project(project_path: &Path, workspace: &Path) -> Vec<ProjectIssue> {
+    let mut issues = Vec::new();
+
+    // Metadata (the UI framework is taken from it when it parses)
+    let metadata_path = project_path.join(".vstworkshop/metadata.json");
+    let metadata = match fs::read_to_string(&metadata_path) {
+        Err(_) => {
+            issues.push(issue(
+                IssueKind::MetadataMissing,
+                IssueSeverity::Warning,
+                "The project metadata (.vstworkshop/metadata.json) is missing".to_string(),
+                "The project won't show up in the project list. Recreate it by importing the project again.",
+            ));
+            None
+        }
+        Ok(content) => match serde_json::from_str::<ProjectMeta>(&content) {
+            Ok(meta) => Some(meta),
+            Err(e) => {
+                issues.push(issue(
+                    IssueKind::MetadataInvalid,
+                    IssueSeverity::Warning,
+                    format!("The project metadata can't be read: {}", e),
+                    "Fix the JSON in .vstworkshop/metadata.json by hand, or remove it and import the project again.",
+                ));
+                None
+            }
+        },
+    };
+
+    if !project_path.join("src/lib.rs").exists() {
+        issues.push(issue(
+            IssueKind::LibRsMissing,
+            IssueSeverity::Error,
+            "src/lib.rs is missing".to_string(),
+            "Restore it from an earlier version in the version history.",
+        ));
+    }
+
+    let cargo_toml = fs::read_to_string(project_path.join("Cargo.toml")).ok();
+    let is_webview = match metadata.as_ref().and_then(|meta| meta.ui_framework.as_deref()) {
+        Some(framework) => framework == "webview",
+        None => cargo_toml.as_deref().is_some_and(|toml| toml.contains("nih_plug_webview")),
+    };
+    if is_webview && !project_path.join("src/ui.html").exists() {
+        issues.push(issue(
+            IssueKind::UiHtmlMissing,
+            IssueSeverity::Error,
+            "This is a WebView project but src/ui.html is missing, so it won't compile".to_string(),
+            "Recreate ui.html from the starter template (auto fix), then re-add your controls.",
+        ));
+    }
+
+    // Workspace membership: the workspace includes projects/*, and the project must not
+    // declare a workspace of its own
+    let workspace_toml = fs::read_to_string(workspace.join("Cargo.toml")).unwrap_or_default();
+    if !workspace_toml.contains("\"projects/*\"") {
+        issues.push(issue(
+            IssueKind::WorkspaceManifestOutdated,
+            IssueSeverity::Error,
+            "The workspace Cargo.toml doesn't include the projects folder".to_string(),
+            "Rewrite the workspace Cargo.toml (auto fix).",
+        ));
+    }
+    match cargo_toml.as_deref() {
+        None => issues.push(issue(
+            IssueKind::CargoTomlMissing,
+            IssueSeverity::Error,
+            "Cargo.toml is missing".to_string(),
+            "Restore it from an earlier version in the version history.",
+        )),
+        Some(toml) => {
+            let in_projects_dir = project_path.parent() == Some(workspace.join("projects").as_path());
+            let own_workspace = toml.lines().any(|line| line.trim() == "[workspace]");
+            if !in_projects_dir || own_workspace {
+                issues.push(issue(
+                    IssueKind::NotWorkspaceMember,
+                    IssueSeverity::Error,
+                    if own_workspace {
+                        "Cargo.toml declares its own [workspace], so the shared build cache isn't used".to_string()
+                    } else {
+                        "The project isn't in the workspace's projects folder".to_string()
+                    },
+                    "Remove the [workspace] section from Cargo.toml and keep the project in the projects folder.",
+                ));
+            }
+
+            let workspace_rev = fs::read_to_string(workspace.join("xtask/Cargo.toml"))
+                .ok()
+                .and_then(|xtask| dependency_rev(&xtask, "nih_plug_xtask"));
+            let project_rev = dependency_rev(toml, "nih_plug");
+            if let (Some(workspace_rev), Some(project_rev)) = (workspace_rev, project_rev) {
+                if workspace_rev != project_rev {
+                    issues.push(issue(
+                        IssueKind::NihPlugRevMismatch,
+                        IssueSeverity::Warning,
+                        format!(
+                            "nih-plug is pinned to {} but the workspace uses {}, so it's compiled twice",
+                            project_rev, workspace_rev
+                        ),
+                        "Change the nih_plug rev in Cargo.toml to match the workspace, then rebuild and fix any API changes.",
+                    ));
+                }
+            }
+        }
+    }
+
+    // Version control: the project's own repo (not one it happens to sit inside)
+    if !project_path.join(".git").exists() {
+        issues.push(issue(
+            IssueKind::GitMissing,
+            IssueSeverity::Warning,
+            "Version control isn't set up, so changes can't be reverted".to_string(),
+            "Initialize git and commit the current state (auto fix).",
+        ));
+    } else {
+        let gitignore = fs::read_to_string(project_path.join(".gitignore")).unwrap_or_default();
+        // Same test ensure_vstworkshop_ignored uses, so a fixed project passes
+        if !gitignore.contains(".vstworkshop/") {
+            issues.push(issue(
+                IssueKind::VstworkshopNotIgnored,
+                IssueSeverity::Warning,
+                ".vstworkshop/ isn't ignored by git, so reverting can roll back chat history".to_string(),
+                "Add .vstworkshop/ to .gitignore and stop tracking it (auto fix).",
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Recreate src/ui.html from the starter template
+fn restore_ui_html(project_path: &Path) -> Result<(), String> {
+    let folder_name = project_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    fs::create_dir_all(project_path.join("src")).map_err(|e| format!("Failed to create src dir: {}", e))?;
+    fs::write(project_path.join("src/ui.html"), generate_webview_ui_html(&to_pascal_case(&folder_name)))
+        .map_err(|e| format!("Failed to write ui.html: {}", e))
+}
+
+/// Make sure the project has its own git repo with app state kept out of it. Run before
+/// anything that relies on version history.
+pub async fn ensure_git_setup(project_path: &str) -> Result<(), String> {
+    if !super::git::is_git_repo(project_path) {
+        super::git::init_repo(project_path).await?;
+        super::git::create_gitignore(project_path)?;
+        super::git::commit_changes(project_path, "Initialize git for version control").await?;
+    }
+
+    // Keeps chat.json from being reverted by a git checkout
+    if let Err(e) = super::git::ensure_vstworkshop_ignored(project_path) {
+        log::warn!("Failed to update gitignore: {}", e);
+    }
+    Ok(())
+}
+
+async fn apply_fix(kind: IssueKind, project_path: &Path) -> Result<(), String> {
+    let path_str = project_path.to_string_lossy().to_string();
+    match kind {
+        IssueKind::UiHtmlMissing => restore_ui_html(project_path),
+        IssueKind::WorkspaceManifestOutdated => super::projects::ensure_workspace(),
+        IssueKind::GitMissing => ensure_git_setup(&path_str).await,
+        IssueKind::VstworkshopNotIgnored => super::git::ensure_vstworkshop_ignored(&path_str),
+        _ => Err("This problem can't be fixed automatically".to_string()),
+    }
+}
+
+/// Check a project for common problems, with a suggested fix for each. With `auto_fix`
+/// the safe ones (missing ui.html, workspace manifest, git setup) are repaired in place.
+#[tauri::command]
+pub async fn diagnose_project(project_path: String, auto_fix: Option<bool>) -> Result<ProjectDiagnosis, String> {
+    let path = Path::new(&project_path);
+    if !path.is_dir() {
+        return Err(format!("Project folder not found: {}", project_path));
+    }
+
+    let mut issues = inspect_project(path, &get_workspace_path());
+    if auto_fix.unwrap_or(false) {
+        for issue in issues.iter_mut().filter(|issue| issue.auto_fixable) {
+            match apply_fix(issue.kind, path).await {
+                Ok(()) => issue.fixed = true,
+                Err(e) => {
+                    log::warn!("Project doctor couldn't fix {:?}: {}", issue.kind, e);
+                    issue.fix_error = Some(e);
+                }
+            }
+        }
+    }
+
+    log::info!(
+        "Diagnosed {}: {} issue(s), {} fixed",
+        project_path,
+        issues.len(),
+        issues.iter().filter(|issue| issue.fixed).count()
+    );
+    Ok(ProjectDiagnosis {
+        project_path,
+        healthy: issues.iter().all(|issue| issue.fixed),
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(issues: &[ProjectIssue]) -> Vec<IssueKind> {
+        issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_dependency_rev() {
+        let toml = r#"[dependencies]
+nih_plug = { git = "https://github.com/robbert-vdh/nih-plug.git", rev = "28b149ec" }
+nih_plug_webview = { git = "https://github.com/jamesontucker/nih-plug-webview" }
+"#;
+        assert_eq!(dependency_rev(toml, "nih_plug").as_deref(), Some("28b149ec"));
+        assert_eq!(dependency_rev(toml, "nih_plug_webview"), None);
+        assert_eq!(dependency_rev(toml, "nih_plug_xtask"), None);
+    }
+
+    #[test]
+    fn test_inspect_broken_webview_project() {
+        let workspace = std::env::temp_dir().join(format!("freqlab_doctor_test_{}", std::process::id()));
+        let project = workspace.join("projects/my_plugin");
+        fs::create_dir_all(project.join(".vstworkshop")).unwrap();
+        fs::create_dir_all(workspace.join("xtask")).unwrap();
+        fs::write(workspace.join("Cargo.toml"), "[workspace]\nmembers = [\"projects/*\", \"xtask\"]\n").unwrap();
+        fs::write(
+            workspace.join("xtask/Cargo.toml"),
+            "nih_plug_xtask = { git = \"https://github.com/robbert-vdh/nih-plug.git\", rev = \"28b149ec\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            "[dependencies]\nnih_plug = { git = \"https://github.com/robbert-vdh/nih-plug.git\", rev = \"0123abcd\" }\n\
+             nih_plug_webview = { git = \"https://github.com/jamesontucker/nih-plug-webview\" }\n",
+        )
+        .unwrap();
+        fs::write(project.join(".vstworkshop/metadata.json"), "{ not json").unwrap();
+
+        let issues = inspect_project(&project, &workspace);
+        let found = kinds(&issues);
+
+        // Only the safe fix is applied; the rest stay for the user
+        restore_ui_html(&project).unwrap();
+        let ui_html = fs::read_to_string(project.join("src/ui.html"));
+        let after_fix = kinds(&inspect_project(&project, &workspace));
+        fs::remove_dir_all(&workspace).unwrap();
+
+        assert_eq!(
+            found,
+            vec![
+                IssueKind::MetadataInvalid,
+                IssueKind::LibRsMissing,
+                IssueKind::UiHtmlMissing,
+                IssueKind::NihPlugRevMismatch,
+                IssueKind::GitMissing,
+            ]
+        );
+        assert!(issues[2].auto_fixable && !issues[1].auto_fixable);
+        assert!(ui_html.unwrap().contains("<title>MyPlugin</title>"));
+        assert!(!after_fix.contains(&IssueKind::UiHtmlMissing));
+        assert!(after_fix.contains(&IssueKind::LibRsMissing));
+    }
+}
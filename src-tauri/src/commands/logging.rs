@@ -1,11 +1,113 @@
 use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 
 static LOG_FILE: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
 
+/// Rotate the active log once it grows past this size
+const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+/// Number of rotated logs kept (freqlab.log.1 is the newest)
+const MAX_LOG_ARCHIVES: u32 = 3;
+/// How many writes between size checks (checking on every write would stat the file each time)
+const ROTATE_CHECK_INTERVAL: u32 = 500;
+
+static WRITES_SINCE_ROTATE_CHECK: AtomicU32 = AtomicU32::new(0);
+
+/// One line of the log file (stored as JSON lines)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    /// ERROR, WARN, INFO, DEBUG or TRACE
+    pub level: String,
+    /// Module path for `log::` records (library modules are recorded under `freqlab::`),
+    /// or the module name passed to log_message
+    pub target: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    fn new(level: &str, target: &str, message: &str) -> Self {
+        Self {
+            timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            level: level.to_uppercase(),
+            target: target.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Parse a JSON line, falling back to the older `[time] [LEVEL] [module] message` format
+    fn parse(line: &str) -> Option<Self> {
+        if let Ok(entry) = serde_json::from_str::<LogEntry>(line) {
+            return Some(entry);
+        }
+
+        let mut rest = line;
+        let mut fields = Vec::with_capacity(3);
+        for _ in 0..3 {
+            let inner = rest.strip_prefix('[')?;
+            let end = inner.find(']')?;
+            fields.push(&inner[..end]);
+            rest = inner[end + 1..].strip_prefix(' ').unwrap_or(&inner[end + 1..]);
+        }
+        Some(Self {
+            timestamp: fields[0].to_string(),
+            level: fields[1].to_string(),
+            target: fields[2].to_string(),
+            message: rest.to_string(),
+        })
+    }
+
+    /// Plain-text rendering used for exports
+    fn to_text(&self) -> String {
+        format!("[{}] [{}] [{}] {}", self.timestamp, self.level, self.target, self.message)
+    }
+
+    /// Whether the entry is at least as severe as `filter` (unparseable levels always pass)
+    fn passes_level(&self, filter: log::LevelFilter) -> bool {
+        match self.level.parse::<log::Level>() {
+            Ok(level) => level <= filter,
+            Err(_) => true,
+        }
+    }
+
+    /// Whether the entry's target is `module` or one of its submodules
+    fn in_module(&self, module: &str) -> bool {
+        self.target == module
+            || self
+                .target
+                .strip_prefix(module)
+                .is_some_and(|rest| rest.starts_with("::"))
+    }
+}
+
+/// Routes `log::` records into the log file alongside log_message entries
+struct FileLogger;
+
+impl log::Log for FileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // The library crate is named freqlab_lib; record it under the app name
+        let target = record.target();
+        let target = match target.strip_prefix("freqlab_lib") {
+            Some(rest) => format!("freqlab{}", rest),
+            None => target.to_string(),
+        };
+        log_message(record.level().as_str(), &target, &record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
 /// Get the log file path (in user's home directory)
 fn get_log_path() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
@@ -15,7 +117,36 @@ fn get_log_path() -> PathBuf {
         .join("freqlab.log")
 }
 
-/// Initialize logging - creates log directory if needed
+/// Path of a rotated log (`freqlab.log.N`)
+fn archive_path(log_path: &Path, index: u32) -> PathBuf {
+    let mut name = log_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", index));
+    log_path.with_file_name(name)
+}
+
+/// Shift `freqlab.log` to `.1`, `.1` to `.2`, ... once it exceeds `max_size`,
+/// dropping the oldest archive beyond `max_archives`
+fn rotate_if_needed(log_path: &Path, max_size: u64, max_archives: u32) {
+    let size = fs::metadata(log_path).map(|m| m.len()).unwrap_or(0);
+    if size <= max_size {
+        return;
+    }
+
+    let _ = fs::remove_file(archive_path(log_path, max_archives));
+    for index in (1..max_archives).rev() {
+        let from = archive_path(log_path, index);
+        if from.exists() {
+            let _ = fs::rename(&from, archive_path(log_path, index + 1));
+        }
+    }
+    if max_archives > 0 {
+        let _ = fs::rename(log_path, archive_path(log_path, 1));
+    } else {
+        let _ = fs::remove_file(log_path);
+    }
+}
+
+/// Initialize logging - creates log directory if needed and installs the `log` backend
 pub fn init_logging() {
     let log_path = get_log_path();
 
@@ -24,11 +155,17 @@ pub fn init_logging() {
         let _ = fs::create_dir_all(parent);
     }
 
+    rotate_if_needed(&log_path, MAX_LOG_SIZE, MAX_LOG_ARCHIVES);
+
     // Store the path
     if let Ok(mut path) = LOG_FILE.lock() {
         *path = Some(log_path.clone());
     }
 
+    if log::set_boxed_logger(Box::new(FileLogger)).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+
     // Write startup marker
     log_message("INFO", "freqlab", "Application started");
 }
@@ -36,9 +173,16 @@ pub fn init_logging() {
 /// Write a log message to the log file
 pub fn log_message(level: &str, module: &str, message: &str) {
     let log_path = get_log_path();
+    let entry = LogEntry::new(level, module, message);
 
     // Also print to stderr for dev mode
-    eprintln!("[{}] [{}] {}", level, module, message);
+    eprintln!("[{}] [{}] {}", entry.level, entry.target, entry.message);
+
+    // Long sessions can log a lot - check the size every so often, not just at startup
+    if WRITES_SINCE_ROTATE_CHECK.fetch_add(1, Ordering::Relaxed) >= ROTATE_CHECK_INTERVAL {
+        WRITES_SINCE_ROTATE_CHECK.store(0, Ordering::Relaxed);
+        rotate_if_needed(&log_path, MAX_LOG_SIZE, MAX_LOG_ARCHIVES);
+    }
 
     // Append to log file
     if let Ok(mut file) = OpenOptions::new()
@@ -46,9 +190,51 @@ pub fn log_message(level: &str, module: &str, message: &str) {
         .append(true)
         .open(&log_path)
     {
-        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-        let _ = writeln!(file, "[{}] [{}] [{}] {}", timestamp, level, module, message);
+        if let Ok(line) = serde_json::to_string(&entry) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Read and parse every entry in the log file
+fn read_entries() -> Result<Vec<LogEntry>, String> {
+    let log_path = get_log_path();
+
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&log_path)
+        .map_err(|e| format!("Failed to read log file: {}", e))?;
+    Ok(content.lines().filter_map(LogEntry::parse).collect())
+}
+
+/// Keep entries at `filter_level` severity or above from `module_filter`, newest `limit` only
+fn filter_entries(
+    entries: Vec<LogEntry>,
+    filter_level: Option<&str>,
+    module_filter: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let level = match filter_level {
+        Some(level) => level
+            .parse::<log::LevelFilter>()
+            .map_err(|_| format!("Unknown log level: {}", level))?,
+        None => log::LevelFilter::Trace,
+    };
+    let module = module_filter.map(str::trim).filter(|m| !m.is_empty());
+
+    let mut entries: Vec<LogEntry> = entries
+        .into_iter()
+        .filter(|entry| entry.passes_level(level))
+        .filter(|entry| module.map_or(true, |m| entry.in_module(m)))
+        .collect();
+
+    if let Some(limit) = limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
     }
+    Ok(entries)
 }
 
 /// Get the log file path as a string
@@ -57,20 +243,45 @@ pub async fn get_log_file_path() -> Result<String, String> {
     Ok(get_log_path().to_string_lossy().to_string())
 }
 
-/// Get the log file contents
+/// Get structured log entries, oldest first
+///
+/// `filter_level` keeps entries at that severity or above (e.g. "warn" = warnings and
+/// errors), `module_filter` keeps a module and its submodules (e.g. "freqlab::audio::plugin"),
+/// and `limit` keeps only the newest entries.
 #[tauri::command]
-pub async fn read_log_file() -> Result<String, String> {
-    let log_path = get_log_path();
-
-    if !log_path.exists() {
-        return Ok("No log file yet.".to_string());
-    }
+pub async fn read_log_file(
+    filter_level: Option<String>,
+    module_filter: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    filter_entries(
+        read_entries()?,
+        filter_level.as_deref(),
+        module_filter.as_deref(),
+        limit,
+    )
+}
 
-    fs::read_to_string(&log_path)
-        .map_err(|e| format!("Failed to read log file: {}", e))
+/// Get the log as plain text (one `[time] [LEVEL] [module] message` line per entry)
+#[tauri::command]
+pub async fn export_log_text(
+    filter_level: Option<String>,
+    module_filter: Option<String>,
+) -> Result<String, String> {
+    let entries = filter_entries(
+        read_entries()?,
+        filter_level.as_deref(),
+        module_filter.as_deref(),
+        None,
+    )?;
+    Ok(entries
+        .iter()
+        .map(LogEntry::to_text)
+        .collect::<Vec<_>>()
+        .join("\n"))
 }
 
-/// Clear the log file
+/// Clear the log file and its rotated archives
 #[tauri::command]
 pub async fn clear_log_file() -> Result<(), String> {
     let log_path = get_log_path();
@@ -80,6 +291,14 @@ pub async fn clear_log_file() -> Result<(), String> {
             .map_err(|e| format!("Failed to clear log file: {}", e))?;
     }
 
+    for index in 1..=MAX_LOG_ARCHIVES {
+        let archive = archive_path(&log_path, index);
+        if archive.exists() {
+            fs::remove_file(&archive)
+                .map_err(|e| format!("Failed to remove {}: {}", archive.display(), e))?;
+        }
+    }
+
     log_message("INFO", "freqlab", "Log file cleared");
     Ok(())
 }
@@ -97,3 +316,80 @@ pub async fn get_log_file_size() -> Result<u64, String> {
         .map(|m| m.len())
         .map_err(|e| format!("Failed to get log file size: {}", e))
 }
+
+/// Change which `log::` records are written ("off", "error", "warn", "info", "debug", "trace")
+///
+/// Per-callback audio diagnostics are only emitted at "debug" or finer in debug builds.
+#[tauri::command]
+pub fn set_log_level(level: String) -> Result<(), String> {
+    let filter = level
+        .parse::<log::LevelFilter>()
+        .map_err(|_| format!("Unknown log level: {}", level))?;
+    log::set_max_level(filter);
+    log_message("INFO", "freqlab", &format!("Log level set to {}", filter));
+    Ok(())
+}
+
+/// Get the current `log::` level filter
+#[tauri::command]
+pub fn get_log_level() -> String {
+    log::max_level().to_string().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_filter_entries() {
+        let json = serde_json::to_string(&LogEntry::new("warn", "freqlab::audio::plugin::clap_host", "busy")).unwrap();
+        let lines = [
+            "[2025-01-01 12:00:00] [INFO] [publish] Starting publish".to_string(),
+            json,
+            serde_json::to_string(&LogEntry::new("ERROR", "freqlab::audio::engine", "stream error")).unwrap(),
+            serde_json::to_string(&LogEntry::new("ERROR", "freqlab::audio::plugin_extra", "not a submodule")).unwrap(),
+            "garbage".to_string(),
+        ];
+        let entries: Vec<LogEntry> = lines.iter().filter_map(|l| LogEntry::parse(l)).collect();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].target, "publish");
+        assert_eq!(entries[0].message, "Starting publish");
+        assert_eq!(entries[1].level, "WARN");
+
+        let warnings = filter_entries(entries.clone(), Some("warn"), None, None).unwrap();
+        assert_eq!(warnings.len(), 3);
+
+        let plugin = filter_entries(entries.clone(), None, Some("freqlab::audio::plugin"), None).unwrap();
+        assert_eq!(plugin.len(), 1);
+        assert_eq!(plugin[0].message, "busy");
+
+        let newest = filter_entries(entries.clone(), Some("error"), None, Some(1)).unwrap();
+        assert_eq!(newest[0].message, "not a submodule");
+
+        assert!(filter_entries(entries, Some("loud"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_bounded_archives() {
+        let dir = std::env::temp_dir().join(format!("freqlab-log-rotate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("freqlab.log");
+
+        for round in 0..4 {
+            fs::write(&log_path, format!("round {}", round)).unwrap();
+            rotate_if_needed(&log_path, 4, 2);
+        }
+        assert!(!log_path.exists());
+        assert_eq!(fs::read_to_string(archive_path(&log_path, 1)).unwrap(), "round 3");
+        assert_eq!(fs::read_to_string(archive_path(&log_path, 2)).unwrap(), "round 2");
+        assert!(!archive_path(&log_path, 3).exists());
+
+        // Under the threshold nothing moves
+        fs::write(&log_path, "ok").unwrap();
+        rotate_if_needed(&log_path, 4, 2);
+        assert!(log_path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,10 +1,13 @@
 pub mod prerequisites;
 pub use prerequisites::cleanup_child_processes;
 pub mod projects;
+pub mod doctor;
+pub mod plugin_template;
 pub mod claude;
 pub mod claude_md;
 pub mod claude_skills;
 pub mod build;
+pub mod standalone;
 pub mod git;
 pub mod chat;
 pub mod publish;
@@ -0,0 +1,628 @@
+//! lib.rs generator for new plugin projects
+//!
+//! Every project starts from the same gain plugin. The variants only differ in
+//! plugin kind (audio IO, MIDI, the process body) and UI framework (imports,
+//! params, editor), so the source is composed from shared fragments driven by
+//! a `TemplateSpec` - a nih-plug change only needs fixing in one place.
+
+/// Effect (processes audio input) or instrument (generates audio from MIDI)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginKind {
+    Effect,
+    Instrument,
+}
+
+/// Editor the plugin is generated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiFramework {
+    /// No custom editor (the host draws generic controls)
+    Native,
+    Egui,
+    Webview,
+}
+
+/// Which template to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateSpec {
+    pub kind: PluginKind,
+    pub ui: UiFramework,
+}
+
+impl TemplateSpec {
+    /// Spec for the project's template ("effect" or "instrument") and UI framework
+    /// ("webview", "egui" or "native"). Unknown templates get a native effect.
+    pub fn from_names(template: &str, ui_framework: &str) -> Self {
+        let kind = match template {
+            "instrument" => PluginKind::Instrument,
+            "effect" => PluginKind::Effect,
+            _ => {
+                return Self {
+                    kind: PluginKind::Effect,
+                    ui: UiFramework::Native,
+                }
+            }
+        };
+        let ui = match ui_framework {
+            "webview" => UiFramework::Webview,
+            "egui" => UiFramework::Egui,
+            _ => UiFramework::Native,
+        };
+        Self { kind, ui }
+    }
+}
+
+/// Names and vendor details substituted into the template
+pub struct PluginIdentity<'a> {
+    pub pascal_name: &'a str,
+    pub snake_name: &'a str,
+    /// Already escaped for use inside a Rust string literal
+    pub description: &'a str,
+    pub vst3_id: &'a str,
+    pub vendor_name: &'a str,
+    pub vendor_id: &'a str,
+    pub vendor_url: &'a str,
+    pub vendor_email: &'a str,
+}
+
+/// Generate the project's src/lib.rs
+pub fn generate_lib_rs(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let mut source = String::new();
+    source.push_str(&imports(spec));
+    source.push_str(&plugin_struct(spec, identity));
+    source.push_str(&params_struct(spec, identity));
+    source.push_str(&plugin_default(spec, identity));
+    source.push_str(&params_default(spec, identity));
+    if spec.kind == PluginKind::Instrument {
+        source.push_str(&note_helpers(spec, identity));
+    }
+    source.push_str(&plugin_impl(spec, identity));
+    source.push_str(&format_impls(spec, identity));
+    source
+}
+
+fn imports(spec: TemplateSpec) -> String {
+    match spec.ui {
+        UiFramework::Native => r#"use nih_plug::prelude::*;
+use std::sync::Arc;
+
+"#
+        .to_string(),
+        UiFramework::Egui => r#"use nih_plug::prelude::*;
+use nih_plug_egui::{create_egui_editor, egui, widgets, EguiState};
+use std::sync::Arc;
+
+"#
+        .to_string(),
+        UiFramework::Webview => r#"use nih_plug::prelude::*;
+use nih_plug_webview::{WebViewEditor, HTMLSource};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Smallest and largest editor size the UI can request (logical pixels)
+const EDITOR_MIN_SIZE: (u32, u32) = (200, 150);
+const EDITOR_MAX_SIZE: (u32, u32) = (1600, 1200);
+
+/// Messages from the WebView UI
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum UIMessage {
+    Init,
+    SetGain { value: f32 },
+    /// Reserved: the UI asks the host window to resize
+    Resize { width: u32, height: u32 },
+    /// Reserved: a text field in the UI gained or lost focus
+    SetKeyboardFocus { focused: bool },
+}
+
+"#
+        .to_string(),
+    }
+}
+
+fn plugin_struct(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let PluginIdentity { pascal_name, description, .. } = identity;
+    // The native template is the annotated reference, the others keep it short
+    let voice_fields = match (spec.kind, spec.ui) {
+        (PluginKind::Effect, _) => "",
+        (PluginKind::Instrument, UiFramework::Native) => {
+            r#"    sample_rate: f32,
+    /// Current phase of the oscillator (0.0 to 1.0)
+    phase: f32,
+    /// Current note frequency (0 if no note playing)
+    note_freq: f32,
+    /// Current note velocity (0.0 to 1.0)
+    velocity: f32,
+"#
+        }
+        (PluginKind::Instrument, _) => {
+            r#"    sample_rate: f32,
+    phase: f32,
+    note_freq: f32,
+    velocity: f32,
+"#
+        }
+    };
+    format!(
+        r#"/// {description}
+struct {pascal_name} {{
+    params: Arc<{pascal_name}Params>,
+{voice_fields}}}
+
+"#
+    )
+}
+
+fn params_struct(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let pascal_name = identity.pascal_name;
+    let editor_state = match spec.ui {
+        UiFramework::Egui => {
+            r#"    #[persist = "editor-state"]
+    editor_state: Arc<EguiState>,
+
+"#
+        }
+        _ => "",
+    };
+    let change_flags = match spec.ui {
+        UiFramework::Webview => {
+            r#"    /// Flag to notify UI when gain changes from host automation
+    #[persist = "gain-dirty"]
+    gain_changed: Arc<AtomicBool>,
+"#
+        }
+        _ => "",
+    };
+    format!(
+        r#"#[derive(Params)]
+struct {pascal_name}Params {{
+{editor_state}    #[id = "gain"]
+    pub gain: FloatParam,
+{change_flags}}}
+
+"#
+    )
+}
+
+fn plugin_default(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let pascal_name = identity.pascal_name;
+    let voice_defaults = match spec.kind {
+        PluginKind::Effect => "",
+        PluginKind::Instrument => {
+            r#"            sample_rate: 44100.0,
+            phase: 0.0,
+            note_freq: 0.0,
+            velocity: 0.0,
+"#
+        }
+    };
+    format!(
+        r#"impl Default for {pascal_name} {{
+    fn default() -> Self {{
+        Self {{
+            params: Arc::new({pascal_name}Params::default()),
+{voice_defaults}        }}
+    }}
+}}
+
+"#
+    )
+}
+
+fn params_default(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let pascal_name = identity.pascal_name;
+    // Instruments start quieter and can't boost as much
+    let (default_db, max_db) = match spec.kind {
+        PluginKind::Effect => ("0.0", "30.0"),
+        PluginKind::Instrument => ("-6.0", "6.0"),
+    };
+    let (flag_setup, editor_state, gain_end) = match spec.ui {
+        UiFramework::Native => ("", "", ",\n"),
+        UiFramework::Egui => ("", "            editor_state: EguiState::from_size(400, 300),\n", ",\n"),
+        UiFramework::Webview => (
+            r#"        let gain_changed = Arc::new(AtomicBool::new(false));
+        let gain_changed_clone = gain_changed.clone();
+
+"#,
+            "",
+            r#"
+            .with_callback(Arc::new(move |_| {
+                gain_changed_clone.store(true, Ordering::Relaxed);
+            })),
+            gain_changed,
+"#,
+        ),
+    };
+    format!(
+        r#"impl Default for {pascal_name}Params {{
+    fn default() -> Self {{
+{flag_setup}        Self {{
+{editor_state}            gain: FloatParam::new(
+                "Gain",
+                util::db_to_gain({default_db}),
+                FloatRange::Skewed {{
+                    min: util::db_to_gain(-30.0),
+                    max: util::db_to_gain({max_db}),
+                    factor: FloatRange::gain_skew_factor(-30.0, {max_db}),
+                }},
+            )
+            .with_smoother(SmoothingStyle::Logarithmic(50.0))
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
+            .with_string_to_value(formatters::s2v_f32_gain_to_db()){gain_end}        }}
+    }}
+}}
+
+"#
+    )
+}
+
+fn note_helpers(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let pascal_name = identity.pascal_name;
+    let doc = match spec.ui {
+        UiFramework::Native => "    /// Convert MIDI note number to frequency in Hz\n",
+        _ => "",
+    };
+    format!(
+        r#"impl {pascal_name} {{
+{doc}    fn midi_note_to_freq(note: u8) -> f32 {{
+        440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
+    }}
+}}
+
+"#
+    )
+}
+
+fn plugin_impl(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let PluginIdentity {
+        pascal_name,
+        vendor_name,
+        vendor_url,
+        vendor_email,
+        ..
+    } = identity;
+    let (layout_comment, main_input, midi_input) = match (spec.kind, spec.ui) {
+        (PluginKind::Effect, _) => ("", "NonZeroU32::new(2)", "None"),
+        (PluginKind::Instrument, UiFramework::Native) => {
+            ("    // Instrument: no audio input, stereo output\n", "None", "Basic")
+        }
+        (PluginKind::Instrument, _) => ("", "None", "Basic"),
+    };
+    let editor = editor_fn(spec, identity);
+    let initialize = match spec.kind {
+        PluginKind::Effect => "",
+        PluginKind::Instrument => {
+            r#"    fn initialize(
+        &mut self,
+        _audio_io_layout: &AudioIOLayout,
+        buffer_config: &BufferConfig,
+        _context: &mut impl InitContext<Self>,
+    ) -> bool {
+        self.sample_rate = buffer_config.sample_rate;
+        true
+    }
+
+"#
+        }
+    };
+    let process = process_fn(spec);
+    format!(
+        r#"impl Plugin for {pascal_name} {{
+    const NAME: &'static str = "{pascal_name}";
+    const VENDOR: &'static str = "{vendor_name}";
+    const URL: &'static str = "{vendor_url}";
+    const EMAIL: &'static str = "{vendor_email}";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+{layout_comment}    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
+        main_input_channels: {main_input},
+        main_output_channels: NonZeroU32::new(2),
+        ..AudioIOLayout::const_default()
+    }}];
+
+    const MIDI_INPUT: MidiConfig = MidiConfig::{midi_input};
+    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
+
+    type SysExMessage = ();
+    type BackgroundTask = ();
+
+    fn params(&self) -> Arc<dyn Params> {{
+        self.params.clone()
+    }}
+
+{editor}{initialize}{process}}}
+
+"#
+    )
+}
+
+/// The `editor()` method (empty for native plugins, which use the host's generic UI)
+fn editor_fn(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let pascal_name = identity.pascal_name;
+    match spec.ui {
+        UiFramework::Native => String::new(),
+        UiFramework::Egui => format!(
+            r#"    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {{
+        let params = self.params.clone();
+        create_egui_editor(
+            self.params.editor_state.clone(),
+            (),
+            |_, _| {{}},
+            move |egui_ctx, setter, _state| {{
+                egui::CentralPanel::default().show(egui_ctx, |ui| {{
+                    ui.heading("{pascal_name}");
+                    ui.add_space(10.0);
+
+                    ui.label("Gain");
+                    ui.add(widgets::ParamSlider::for_param(&params.gain, setter));
+                }});
+            }},
+        )
+    }}
+
+"#
+        ),
+        UiFramework::Webview => r#"    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+        let params = self.params.clone();
+        let gain_changed = self.params.gain_changed.clone();
+        // Set while a text field in the UI has focus, so keys go to the UI instead of the host
+        let wants_keyboard = Arc::new(AtomicBool::new(false));
+        let wants_keyboard_handler = wants_keyboard.clone();
+
+        let editor = WebViewEditor::new(HTMLSource::String(include_str!("ui.html")), (400, 300))
+            .with_background_color((26, 26, 46, 255))
+            .with_developer_mode(true)
+            .with_keyboard_handler(move |_event| wants_keyboard_handler.load(Ordering::Relaxed))
+            .with_event_loop(move |ctx, setter, window| {
+                // Handle messages from WebView
+                while let Ok(msg) = ctx.next_event() {
+                    let ui_msg = match serde_json::from_value::<UIMessage>(msg) {
+                        Ok(ui_msg) => ui_msg,
+                        Err(e) => {
+                            // Unknown type or wrong fields: drop it rather than guess
+                            nih_log!("Ignoring malformed UI message: {}", e);
+                            continue;
+                        }
+                    };
+                    match ui_msg {
+                        UIMessage::Init => {
+                            // Send initial state to UI
+                            ctx.send_json(json!({
+                                "type": "param_change",
+                                "param": "gain",
+                                "value": params.gain.unmodulated_normalized_value(),
+                                "text": params.gain.to_string()
+                            }));
+                        }
+                        UIMessage::SetGain { value } => {
+                            setter.begin_set_parameter(&params.gain);
+                            setter.set_parameter_normalized(&params.gain, value);
+                            setter.end_set_parameter(&params.gain);
+                        }
+                        UIMessage::Resize { width, height } => {
+                            let width = width.clamp(EDITOR_MIN_SIZE.0, EDITOR_MAX_SIZE.0);
+                            let height = height.clamp(EDITOR_MIN_SIZE.1, EDITOR_MAX_SIZE.1);
+                            // Updates the editor's stored size and the host window together
+                            ctx.resize(window, width, height);
+                            // Tell the UI the size it actually got (after clamping)
+                            ctx.send_json(json!({
+                                "type": "resized",
+                                "width": width,
+                                "height": height
+                            }));
+                        }
+                        UIMessage::SetKeyboardFocus { focused } => {
+                            wants_keyboard.store(focused, Ordering::Relaxed);
+                        }
+                    }
+                }
+
+                // Sync UI when parameters change from host automation or preset recall.
+                // Collect every dirty parameter and send them as one message so a preset
+                // change with many parameters doesn't flood the IPC channel.
+                let mut updates = Vec::new();
+                if gain_changed.swap(false, Ordering::Relaxed) {
+                    updates.push(json!({
+                        "param": "gain",
+                        "value": params.gain.unmodulated_normalized_value(),
+                        "text": params.gain.to_string()
+                    }));
+                }
+                if !updates.is_empty() {
+                    ctx.send_json(json!({
+                        "type": "param_batch",
+                        "updates": updates
+                    }));
+                }
+            });
+
+        Some(Box::new(editor))
+    }
+
+"#
+        .to_string(),
+    }
+}
+
+/// The `process()` method: gain on the input for effects, a MIDI sine voice for instruments
+fn process_fn(spec: TemplateSpec) -> String {
+    match spec.kind {
+        PluginKind::Effect => r#"    fn process(
+        &mut self,
+        buffer: &mut Buffer,
+        _aux: &mut AuxiliaryBuffers,
+        _context: &mut impl ProcessContext<Self>,
+    ) -> ProcessStatus {
+        for channel_samples in buffer.iter_samples() {
+            let gain = self.params.gain.smoothed.next();
+            for sample in channel_samples {
+                *sample *= gain;
+                // Protect against NaN/Inf (can crash DAWs)
+                if !sample.is_finite() {
+                    *sample = 0.0;
+                }
+            }
+        }
+        ProcessStatus::Normal
+    }
+"#
+        .to_string(),
+        PluginKind::Instrument => {
+            let (note_off_comment, oscillator_comment) = match spec.ui {
+                UiFramework::Native => (
+                    "                    // Only stop if it's the same note\n",
+                    "            // Simple sine wave oscillator\n",
+                ),
+                _ => ("", ""),
+            };
+            format!(
+                r#"    fn process(
+        &mut self,
+        buffer: &mut Buffer,
+        _aux: &mut AuxiliaryBuffers,
+        context: &mut impl ProcessContext<Self>,
+    ) -> ProcessStatus {{
+        // Process MIDI events
+        while let Some(event) = context.next_event() {{
+            match event {{
+                NoteEvent::NoteOn {{ note, velocity, .. }} => {{
+                    self.note_freq = Self::midi_note_to_freq(note);
+                    self.velocity = velocity;
+                }}
+                NoteEvent::NoteOff {{ note, .. }} => {{
+{note_off_comment}                    if Self::midi_note_to_freq(note) == self.note_freq {{
+                        self.note_freq = 0.0;
+                        self.velocity = 0.0;
+                    }}
+                }}
+                _ => {{}}
+            }}
+        }}
+
+        // Generate audio
+        let gain = self.params.gain.smoothed.next();
+        let phase_delta = self.note_freq / self.sample_rate;
+
+        for channel_samples in buffer.iter_samples() {{
+{oscillator_comment}            let sample = if self.note_freq > 0.0 {{
+                let sine = (self.phase * std::f32::consts::TAU).sin();
+                self.phase = (self.phase + phase_delta) % 1.0;
+                sine * self.velocity * gain
+            }} else {{
+                0.0
+            }};
+
+            for output_sample in channel_samples {{
+                // Protect against NaN/Inf (can crash DAWs)
+                *output_sample = if sample.is_finite() {{ sample }} else {{ 0.0 }};
+            }}
+        }}
+
+        ProcessStatus::Normal
+    }}
+"#
+            )
+        }
+    }
+}
+
+/// CLAP and VST3 metadata plus the export macros
+fn format_impls(spec: TemplateSpec, identity: &PluginIdentity) -> String {
+    let PluginIdentity {
+        pascal_name,
+        snake_name,
+        description,
+        vst3_id,
+        vendor_id,
+        ..
+    } = identity;
+    let (clap_features, vst3_subcategories) = match spec.kind {
+        PluginKind::Effect => ("ClapFeature::AudioEffect, ClapFeature::Stereo", "Vst3SubCategory::Fx"),
+        PluginKind::Instrument => (
+            "ClapFeature::Instrument, ClapFeature::Synthesizer, ClapFeature::Stereo",
+            "Vst3SubCategory::Synth, Vst3SubCategory::Instrument",
+        ),
+    };
+    format!(
+        r#"impl ClapPlugin for {pascal_name} {{
+    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
+    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
+    const CLAP_MANUAL_URL: Option<&'static str> = None;
+    const CLAP_SUPPORT_URL: Option<&'static str> = None;
+    const CLAP_FEATURES: &'static [ClapFeature] = &[{clap_features}];
+}}
+
+impl Vst3Plugin for {pascal_name} {{
+    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
+    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[{vst3_subcategories}];
+}}
+
+nih_export_clap!({pascal_name});
+nih_export_vst3!({pascal_name});
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Identity the snapshots were generated with
+    fn test_identity() -> PluginIdentity<'static> {
+        PluginIdentity {
+            pascal_name: "TestSynth",
+            snake_name: "test_synth",
+            description: "A \\\"warm\\\" test plugin",
+            vst3_id: "TestSynthFreqlab",
+            vendor_name: "Freqlab Tests",
+            vendor_id: "freqlabtests",
+            vendor_url: "https://example.com",
+            vendor_email: "dev@example.com",
+        }
+    }
+
+    /// Output must stay byte-for-byte identical to what existing projects were created with
+    #[test]
+    fn test_templates_match_snapshots() {
+        let snapshots = [
+            ("effect", "native", include_str!("template_snapshots/effect_native.rs.snap")),
+            ("effect", "egui", include_str!("template_snapshots/effect_egui.rs.snap")),
+            ("effect", "webview", include_str!("template_snapshots/effect_webview.rs.snap")),
+            ("instrument", "native", include_str!("template_snapshots/instrument_native.rs.snap")),
+            ("instrument", "egui", include_str!("template_snapshots/instrument_egui.rs.snap")),
+            ("instrument", "webview", include_str!("template_snapshots/instrument_webview.rs.snap")),
+        ];
+        for (template, ui_framework, expected) in snapshots {
+            let spec = TemplateSpec::from_names(template, ui_framework);
+            let generated = generate_lib_rs(spec, &test_identity());
+            if generated != expected {
+                let line = generated
+                    .lines()
+                    .zip(expected.lines())
+                    .position(|(a, b)| a != b)
+                    .map(|i| i + 1)
+                    .unwrap_or_else(|| generated.lines().count().min(expected.lines().count()) + 1);
+                panic!("{} {} template differs from its snapshot at line {}", template, ui_framework, line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spec_from_names() {
+        assert_eq!(
+            TemplateSpec::from_names("instrument", "egui"),
+            TemplateSpec { kind: PluginKind::Instrument, ui: UiFramework::Egui }
+        );
+        assert_eq!(
+            TemplateSpec::from_names("instrument", "something"),
+            TemplateSpec { kind: PluginKind::Instrument, ui: UiFramework::Native }
+        );
+        // Unknown templates fall back to a native effect, whatever the UI
+        assert_eq!(
+            TemplateSpec::from_names("unknown", "webview"),
+            TemplateSpec { kind: PluginKind::Effect, ui: UiFramework::Native }
+        );
+    }
+}
@@ -9,19 +9,27 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 static ACTIVE_CHILD_PIDS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
 
 /// Register a child process PID for tracking
-fn register_child_pid(pid: u32) {
+pub fn register_child_pid(pid: u32) {
     if let Ok(mut pids) = ACTIVE_CHILD_PIDS.lock() {
         pids.push(pid);
     }
 }
 
 /// Unregister a child process PID (called when process completes normally)
-fn unregister_child_pid(pid: u32) {
+pub fn unregister_child_pid(pid: u32) {
     if let Ok(mut pids) = ACTIVE_CHILD_PIDS.lock() {
         pids.retain(|&p| p != pid);
     }
 }
 
+/// Check whether a PID belongs to a tracked child process
+pub fn is_child_pid_tracked(pid: u32) -> bool {
+    ACTIVE_CHILD_PIDS
+        .lock()
+        .map(|pids| pids.contains(&pid))
+        .unwrap_or(false)
+}
+
 /// Kill all tracked child processes - call this on app exit
 pub fn cleanup_child_processes() {
     if let Ok(pids) = ACTIVE_CHILD_PIDS.lock() {
@@ -79,6 +87,32 @@ pub enum CheckStatus {
     NeedsConfig,
 }
 
+// ============================================================================
+// Plugin Folder Types
+// ============================================================================
+
+/// One candidate install folder for a plugin format
+#[derive(Serialize, Clone)]
+pub struct PluginDirStatus {
+    pub path: String,
+    pub exists: bool,
+    /// Whether publishing can write here (for a missing folder: whether it can be created)
+    pub writable: bool,
+    pub message: Option<String>,
+}
+
+/// Install folders for one plugin format, in the order publish_to_daw tries them
+#[derive(Serialize, Clone)]
+pub struct PluginFormatDirs {
+    /// "clap" or "vst3"
+    pub format: String,
+    /// Whether at least one folder can be published to
+    pub usable: bool,
+    pub dirs: Vec<PluginDirStatus>,
+    /// What to do if none of the folders can be used
+    pub fix: Option<String>,
+}
+
 // ============================================================================
 // Disk Space Types
 // ============================================================================
@@ -240,6 +274,137 @@ fn check_claude_auth() -> CheckResult {
     }
 }
 
+/// Whether files can be created in a folder (or, if it doesn't exist yet, in its nearest
+/// existing parent - publishing creates missing plugin folders)
+fn probe_writable(dir: &std::path::Path) -> bool {
+    let Some(existing) = dir.ancestors().find(|d| d.is_dir()) else {
+        return false;
+    };
+    let probe = existing.join(format!(".freqlab-write-test-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn check_plugin_format_dirs(format: &str) -> PluginFormatDirs {
+    let dirs: Vec<PluginDirStatus> = super::publish::system_plugin_dirs(format)
+        .into_iter()
+        .map(|dir| {
+            let exists = dir.is_dir();
+            let writable = probe_writable(&dir);
+            let message = match (exists, writable) {
+                (true, true) => None,
+                (false, true) => Some("Will be created on first publish".to_string()),
+                (true, false) => Some("Not writable by your user".to_string()),
+                (false, false) => Some("Missing and can't be created by your user".to_string()),
+            };
+            PluginDirStatus {
+                path: dir.to_string_lossy().to_string(),
+                exists,
+                writable,
+                message,
+            }
+        })
+        .collect();
+
+    let usable = dirs.iter().any(|d| d.writable);
+    let fix = if usable {
+        None
+    } else {
+        dirs.first().map(|d| {
+            format!(
+                "Give your user access to the folder: sudo mkdir -p \"{0}\" && sudo chown -R $(whoami) \"{0}\"",
+                d.path
+            )
+        })
+    };
+
+    PluginFormatDirs {
+        format: format.to_string(),
+        usable,
+        dirs,
+        fix,
+    }
+}
+
+/// Check the CLAP/VST3 install folders publish_to_daw uses exist (or can be created) and are writable
+#[tauri::command]
+pub async fn check_plugin_dirs() -> Vec<PluginFormatDirs> {
+    tokio::task::spawn_blocking(|| {
+        ["clap", "vst3"]
+            .iter()
+            .map(|format| check_plugin_format_dirs(format))
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Check the Rust toolchain can build for this machine: the host target's standard library
+/// has to be installed through rustup. `version` holds the host target triple.
+fn check_rust_target_blocking() -> CheckResult {
+    let host = run_command_with_timeout("rustc", &["-vV"], 5)
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .find_map(|line| line.strip_prefix("host: "))
+                .map(|host| host.trim().to_string())
+        });
+    let Some(host) = host else {
+        return CheckResult {
+            status: CheckStatus::NotInstalled,
+            version: None,
+            message: Some("Rust isn't installed - install it from https://rustup.rs".to_string()),
+        };
+    };
+
+    let installed = match run_command_with_timeout("rustup", &["target", "list", "--installed"], 10) {
+        Some(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .collect::<Vec<_>>(),
+        // Toolchains not managed by rustup ship their host target built in
+        _ => {
+            return CheckResult {
+                status: CheckStatus::Installed,
+                version: Some(host),
+                message: Some("rustup not found - assuming the toolchain's built-in target".to_string()),
+            }
+        }
+    };
+
+    if installed.contains(&host) {
+        CheckResult {
+            status: CheckStatus::Installed,
+            version: Some(host),
+            message: None,
+        }
+    } else {
+        CheckResult {
+            status: CheckStatus::NeedsConfig,
+            message: Some(format!("Run: rustup target add {}", host)),
+            version: Some(host),
+        }
+    }
+}
+
+/// Check the Rust toolchain has the target plugins are built for installed
+#[tauri::command]
+pub async fn check_rust_target() -> CheckResult {
+    tokio::task::spawn_blocking(check_rust_target_blocking)
+        .await
+        .unwrap_or_else(|_| CheckResult {
+            status: CheckStatus::NotInstalled,
+            version: None,
+            message: Some("Check failed".to_string()),
+        })
+}
+
 #[tauri::command]
 pub async fn check_prerequisites() -> PrerequisiteStatus {
     // Run checks in a blocking thread pool to not freeze the UI
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI8, AtomicU64, AtomicU8, Ordering};
 use tauri::{Emitter, Manager};
 
 /// Global flag to control the level meter thread
@@ -14,6 +14,33 @@ static CRASH_MONITOR_RUNNING: AtomicBool = AtomicBool::new(false);
 /// Tracks whether we've already emitted a plugin-crashed event (to avoid spam)
 static CRASH_EVENT_EMITTED: AtomicBool = AtomicBool::new(false);
 
+/// Tracks whether we've already emitted a plugin-error event for the loaded plugin
+static PROCESS_ERROR_EVENT_EMITTED: AtomicBool = AtomicBool::new(false);
+
+/// Payload of the "plugin-error" event
+const PLUGIN_PROCESS_ERROR_MESSAGE: &str =
+    "Plugin reported a processing error and has been bypassed. Reload to try again.";
+
+/// Sidecar file of the project whose plugin is loaded, where its state is saved on unload and exit
+static PROJECT_PLUGIN_STATE_PATH: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+/// Global flag to control the device monitor thread
+static DEVICE_MONITOR_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Bumped on every start and stop of the device monitor. A thread only runs while the
+/// generation it was started with is current, so one still finishing a sleep or a
+/// recovery when the monitor is restarted exits instead of running alongside the new one.
+static DEVICE_MONITOR_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Tracks whether we've already emitted a device-lost event for the current loss
+static DEVICE_LOST_EMITTED: AtomicBool = AtomicBool::new(false);
+
+/// How long to wait after a device loss before trying to rebuild the engine
+const DEVICE_RECOVERY_DELAY_MS: u64 = 1500;
+
+/// How long an off-screen editor is given to draw before it's captured
+const THUMBNAIL_RENDER_DELAY_MS: u64 = 1000;
+
 /// Start the crash monitor thread - runs independently of metering to detect crashes
 /// even when PreviewPanel is closed
 fn start_crash_monitor(app_handle: tauri::AppHandle) {
@@ -38,6 +65,12 @@ fn start_crash_monitor(app_handle: tauri::AppHandle) {
                         "Plugin crashed during audio processing. Reload to try again.",
                     );
                 }
+
+                // A processing error bypasses the plugin (dry signal) - tell the UI once
+                if handle.plugin_process_failed() && !PROCESS_ERROR_EVENT_EMITTED.swap(true, Ordering::SeqCst) {
+                    log::error!("Crash monitor detected plugin processing error - emitting event");
+                    let _ = app_handle.emit("plugin-error", PLUGIN_PROCESS_ERROR_MESSAGE);
+                }
             }
 
             // Poll every 100ms - fast enough to catch crashes quickly
@@ -53,11 +86,164 @@ fn stop_crash_monitor() {
     CRASH_MONITOR_RUNNING.store(false, Ordering::SeqCst);
 }
 
+/// Start the device monitor thread - watches for the output device disappearing
+/// (interface unplugged, driver reset) and rebuilds the engine on the default device
+fn start_device_monitor(app_handle: tauri::AppHandle) {
+    // Don't start if already running
+    if DEVICE_MONITOR_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let generation = DEVICE_MONITOR_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    std::thread::spawn(move || {
+        log::info!("Device monitor thread started");
+        // Set while a loss is being handled: when it started, and the config to rebuild with
+        let mut lost: Option<(std::time::Instant, AudioConfig)> = None;
+        // Last time we looked for a device while running on the null output
+        let mut last_device_probe = std::time::Instant::now();
+
+        while DEVICE_MONITOR_GENERATION.load(Ordering::SeqCst) == generation {
+            match get_engine_handle() {
+                Some(handle) if handle.is_device_lost() => {
+                    // Emit once per loss so the UI can prompt the user
+                    if !DEVICE_LOST_EMITTED.swap(true, Ordering::SeqCst) {
+                        log::error!("Audio output device lost - emitting device-lost event");
+                        let _ = app_handle.emit("device-lost", "Audio output device disconnected");
+                    }
+                    if lost.is_none() {
+                        lost = Some((std::time::Instant::now(), handle.config().clone()));
+                    }
+                }
+                // A failed rebuild leaves no engine behind - keep retrying with the saved config
+                None if lost.is_some() => {}
+                _ => lost = None,
+            }
+
+            // Give the OS a moment to settle on a new default device before rebuilding
+            if let Some((since, config)) = lost.clone() {
+                if since.elapsed() >= std::time::Duration::from_millis(DEVICE_RECOVERY_DELAY_MS) {
+                    match recover_lost_device(&app_handle, config.clone()) {
+                        Ok(()) => {
+                            log::info!("Audio engine recovered on default output device");
+                            DEVICE_LOST_EMITTED.store(false, Ordering::SeqCst);
+                            lost = None;
+                            let _ = app_handle.emit("device-recovered", ());
+                        }
+                        Err(e) => {
+                            // Try again after another delay (e.g. no device plugged in yet)
+                            log::warn!("Audio device recovery failed: {}", e);
+                            lost = Some((std::time::Instant::now(), config));
+                        }
+                    }
+                }
+            }
+
+            // Running silently because no device was available - move to a real one once it shows up
+            if let Some(handle) = get_engine_handle().filter(|h| h.is_null_output()) {
+                let probe_interval = std::time::Duration::from_millis(DEVICE_RECOVERY_DELAY_MS);
+                if last_device_probe.elapsed() >= probe_interval {
+                    last_device_probe = std::time::Instant::now();
+                    if get_default_output_device().is_ok() {
+                        match recover_lost_device(&app_handle, handle.config().clone()) {
+                            Ok(()) => {
+                                log::info!("Audio output device available - left silent output");
+                                let _ = app_handle.emit("device-recovered", ());
+                            }
+                            Err(e) => log::warn!("Switching to audio output device failed: {}", e),
+                        }
+                    }
+                }
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(250));
+        }
+
+        log::info!("Device monitor thread stopped");
+    });
+}
+
+/// Stop the device monitor thread
+fn stop_device_monitor() {
+    // Not joined: the thread may be waiting on the main thread to finish a recovery
+    DEVICE_MONITOR_GENERATION.fetch_add(1, Ordering::SeqCst);
+    DEVICE_MONITOR_RUNNING.store(false, Ordering::SeqCst);
+}
+
+/// Rebuild the engine on the default output device after a device loss.
+/// Plugin loading has to happen on the main thread, so the work is dispatched there
+/// and this blocks until it finishes.
+fn recover_lost_device(app_handle: &tauri::AppHandle, config: AudioConfig) -> Result<(), String> {
+    // Nothing to rebuild on yet - don't tear down what's left of the old engine
+    get_default_output_device()?;
+
+    let app = app_handle.clone();
+    run_on_main_thread_blocking(app_handle, "device recovery", move || reinit_after_device_loss(&app, config))
+}
+
+/// Run `task` on the main thread and block until it returns its result
+fn run_on_main_thread_blocking<T: Send + 'static>(
+    app_handle: &tauri::AppHandle,
+    what: &str,
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    app_handle
+        .run_on_main_thread(move || {
+            let _ = tx.send(task());
+        })
+        .map_err(|e| format!("Failed to schedule {}: {}", what, e))?;
+    rx.recv()
+        .map_err(|e| format!("Main thread stopped before {} completed: {}", what, e))?
+}
+
+/// Reinitialize the engine on the default device, reloading the active plugin
+/// and restoring its state so the preview session continues where it left off
+fn reinit_after_device_loss(app_handle: &tauri::AppHandle, config: AudioConfig) -> Result<(), String> {
+    // Queues point at the old plugin - detach them before it is torn down
+    clear_midi_player_queue();
+    update_midi_input_queue();
+
+    let result = reinit_engine_preserving_plugin(None, config);
+
+    // No engine means the stream couldn't be rebuilt - let the monitor retry
+    let Some(handle) = get_engine_handle() else {
+        return result;
+    };
+    if handle.has_plugin() {
+        notify_plugin_restored(app_handle);
+    }
+    // The engine is back either way - report a plugin reload failure separately
+    if let Err(e) = result {
+        log::error!("{}", e);
+        let _ = app_handle.emit("plugin-error", &e);
+    }
+    Ok(())
+}
+
+/// Re-wire everything that points at the plugin after it was reloaded into a new engine
+fn notify_plugin_restored(app_handle: &tauri::AppHandle) {
+    let Some(handle) = get_engine_handle() else {
+        return;
+    };
+    CRASH_EVENT_EMITTED.store(false, Ordering::SeqCst);
+    PROCESS_ERROR_EVENT_EMITTED.store(false, Ordering::SeqCst);
+    start_crash_monitor(app_handle.clone());
+    let _ = app_handle.emit("plugin-loaded", &handle.get_plugin_state());
+    update_midi_player_queue();
+    update_midi_input_queue();
+}
+
 use crate::audio::{
-    device::{get_default_sample_rate, list_input_devices, list_output_devices, AudioConfig, AudioDeviceInfo},
-    engine::{get_engine_handle, get_engine_sample_rate, init_engine, reinit_engine, shutdown_engine, EngineState, InputSource, PluginPerformance},
-    plugin::PluginState,
-    signals::{GatePattern, SignalConfig, SignalType},
+    device::{get_default_output_device, get_default_sample_rate, list_input_devices, list_output_devices, validate_buffer_size, AudioConfig, AudioDeviceInfo, SupportedBufferSizes},
+    dsp::LimiterMode,
+    engine::{get_engine_handle, get_engine_sample_rate, get_engine_status, init_engine, reinit_engine, reinit_engine_preserving_plugin, shutdown_engine, EngineState, EngineStatus, InputSource, LiveInputLatency, MonitorMode, PluginPerformance},
+    loudness::LoudnessReading,
+    plugin::{automation::ParamAutomation, clap_host::{note_names_generation, AudioPortInfo, InputMapping, PluginParam}, PluginState},
+    render::RenderResult,
+    response::FrequencyResponse,
+    samples::SampleInfo,
+    signals::{BurstShape, GatePattern, SignalConfig, SignalType},
+    weighting::MeteringWeighting,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -78,6 +264,12 @@ pub struct MeteringData {
     pub left_db: f32,
     /// Right channel output level in dB (-60 to 0)
     pub right_db: f32,
+    /// Frequency weighting applied to the weighted level
+    pub weighting: MeteringWeighting,
+    /// Weighted level of the mono output mix (0.0 - 1.0); unweighted when weighting is none
+    pub weighted_level: f32,
+    /// Weighted level in dB (-60 to 0)
+    pub weighted_db: f32,
     /// Left channel input level (0.0 - 1.0) - for live input metering
     pub input_left: f32,
     /// Right channel input level (0.0 - 1.0) - for live input metering
@@ -110,15 +302,21 @@ pub struct MeteringData {
     pub clipping_left: bool,
     /// Right channel clipping indicator
     pub clipping_right: bool,
+    /// Safety limiter activity since the last reset
+    pub headroom: HeadroomStats,
     /// Stereo imaging positions: Vec of [angle, radius] pairs - post-FX output
     /// angle: 0 = full left, PI/2 = center, PI = full right
     /// radius: 0-1 based on amplitude
     pub stereo_positions: Vec<[f32; 2]>,
+    /// Per-position flag for the newest points (drawn brighter) - post-FX output
+    pub stereo_recent: Vec<bool>,
     /// Stereo correlation coefficient (-1.0 to +1.0) - post-FX output
     /// +1 = mono/in-phase, 0 = uncorrelated, -1 = out of phase
     pub stereo_correlation: f32,
     /// INPUT stereo imaging positions: Vec of [angle, radius] pairs - pre-FX
     pub stereo_positions_input: Vec<[f32; 2]>,
+    /// Per-position recent flag - pre-FX
+    pub stereo_recent_input: Vec<bool>,
     /// INPUT stereo correlation coefficient (-1.0 to +1.0) - pre-FX
     pub stereo_correlation_input: f32,
     /// Plugin performance metrics (only present when monitoring is enabled)
@@ -130,6 +328,26 @@ pub struct MeteringData {
     pub plugin_crashed: bool,
 }
 
+/// Safety limiter activity since the last reset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadroomStats {
+    /// Output samples clamped by the safety limiter (including NaN/Inf)
+    pub clamped_samples: u64,
+    /// Largest absolute sample value seen before limiting
+    pub max_peak: f32,
+    /// max_peak in dB (positive = over 0dBFS)
+    pub max_peak_db: f32,
+}
+
+fn headroom_stats(handle: &crate::audio::engine::AudioEngineHandle) -> HeadroomStats {
+    let (clamped_samples, max_peak) = handle.get_headroom_stats();
+    HeadroomStats {
+        clamped_samples,
+        max_peak,
+        max_peak_db: level_to_db(max_peak),
+    }
+}
+
 /// Convert linear level to dB
 fn level_to_db(level: f32) -> f32 {
     if level <= 0.0 {
@@ -152,6 +370,7 @@ pub fn init_audio_engine(
     device_name: Option<String>,
     sample_rate: Option<u32>,
     buffer_size: Option<u32>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     let config = AudioConfig {
         sample_rate: sample_rate.unwrap_or(48000),
@@ -160,20 +379,139 @@ pub fn init_audio_engine(
     };
     let result = init_engine(device_name.as_deref(), config);
 
-    // Pre-initialize MIDI player to avoid warm-up lag on first use
     if result.is_ok() {
+        // Pre-initialize MIDI player to avoid warm-up lag on first use
         init_midi_player();
+        // Forward note activity from every MIDI source so the on-screen keyboard can light up
+        let note_app_handle = app_handle.clone();
+        crate::audio::midi::activity::set_note_listener(move |activity| {
+            let _ = note_app_handle.emit("midi-note", activity);
+        });
+        // Watch for the output device disappearing so we can recover
+        start_device_monitor(app_handle);
     }
 
     result
 }
 
+/// Result of audio_self_test
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioSelfTestReport {
+    /// Output device the engine is running on
+    pub device_name: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub buffer_size: Option<u32>,
+    /// Whether the engine had to be started by the test
+    pub initialized_engine: bool,
+    /// Whether the test tone showed up on the output meters
+    pub levels_registered: bool,
+    /// Highest output level seen during the test, in dB
+    pub peak_left_db: f32,
+    pub peak_right_db: f32,
+    /// Engine start failure or the last error reported by the output stream
+    pub stream_error: Option<String>,
+    pub status: EngineStatus,
+}
+
+/// Test tone and duration used by audio_self_test
+const SELF_TEST_FREQUENCY: f32 = 1000.0;
+const SELF_TEST_AMPLITUDE: f32 = 0.25;
+const SELF_TEST_DURATION_MS: u64 = 200;
+
+/// Output level the test tone has to reach to count as registered (-40 dB)
+const SELF_TEST_LEVEL_THRESHOLD: f32 = 0.01;
+
+/// Verify the output path end to end: start the engine if needed, play a 1kHz sine for 200ms
+/// and check it reaches the output meters. The previous source and transport are restored.
+#[tauri::command]
+pub async fn audio_self_test(app_handle: tauri::AppHandle) -> Result<AudioSelfTestReport, String> {
+    let mut initialized_engine = false;
+    if get_engine_handle().is_none() {
+        if let Err(e) = init_audio_engine(None, None, None, app_handle) {
+            return Ok(AudioSelfTestReport {
+                device_name: None,
+                sample_rate: None,
+                buffer_size: None,
+                initialized_engine: false,
+                levels_registered: false,
+                peak_left_db: level_to_db(0.0),
+                peak_right_db: level_to_db(0.0),
+                stream_error: Some(e),
+                status: get_engine_status(),
+            });
+        }
+        initialized_engine = true;
+    }
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+
+    // Remember what was playing so the test doesn't disturb the session
+    let previous_source = handle.get_input_source();
+    let previous_monitor_mode = handle.get_monitor_mode();
+    let was_playing = handle.is_playing();
+
+    // Dry monitoring keeps a loaded plugin from coloring the result
+    handle.set_monitor_mode(MonitorMode::Dry);
+    handle.set_input_source(InputSource::Signal {
+        config: SignalConfig {
+            signal_type: SignalType::Sine,
+            frequency: SELF_TEST_FREQUENCY,
+            amplitude: SELF_TEST_AMPLITUDE,
+            ..Default::default()
+        },
+    });
+    handle.play();
+
+    let (mut peak_left, mut peak_right) = (0.0f32, 0.0f32);
+    let polls = SELF_TEST_DURATION_MS / 20;
+    for _ in 0..polls {
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let (left, right) = handle.get_output_levels();
+        peak_left = peak_left.max(left);
+        peak_right = peak_right.max(right);
+    }
+
+    handle.stop();
+    handle.set_input_source(previous_source);
+    handle.set_monitor_mode(previous_monitor_mode);
+    if was_playing {
+        handle.play();
+    }
+
+    let levels_registered = peak_left >= SELF_TEST_LEVEL_THRESHOLD && peak_right >= SELF_TEST_LEVEL_THRESHOLD;
+    log::info!(
+        "Audio self-test on {}: levels {:.1}/{:.1} dB ({})",
+        handle.device_name(),
+        level_to_db(peak_left),
+        level_to_db(peak_right),
+        if levels_registered { "ok" } else { "no signal" }
+    );
+
+    Ok(AudioSelfTestReport {
+        device_name: Some(handle.device_name().to_string()),
+        sample_rate: get_engine_sample_rate(),
+        buffer_size: Some(handle.config().buffer_size),
+        initialized_engine,
+        levels_registered,
+        peak_left_db: level_to_db(peak_left),
+        peak_right_db: level_to_db(peak_right),
+        stream_error: handle.last_stream_error(),
+        status: get_engine_status(),
+    })
+}
+
 /// Shutdown the audio engine
 #[tauri::command]
 pub fn shutdown_audio_engine() {
+    stop_device_monitor();
     shutdown_engine();
 }
 
+/// Get the audio engine status (running, device lost, no device, or stopped)
+#[tauri::command]
+pub fn get_audio_engine_status() -> EngineStatus {
+    get_engine_status()
+}
+
 /// Get list of available audio output devices
 #[tauri::command]
 pub fn get_audio_devices() -> Result<Vec<AudioDeviceInfo>, String> {
@@ -187,6 +525,8 @@ pub fn get_audio_sample_rate() -> Result<u32, String> {
 }
 
 /// Update audio settings and reinitialize the engine
+/// preserve_plugin (default true): reload the active plugin and restore its state
+/// into the new engine instead of unloading it
 /// NOTE: This command is kept for potential future use but is currently not called
 /// from the frontend. Audio settings changes now require an app restart to avoid
 /// ObjC WebView class collision issues with webview-based plugins.
@@ -195,8 +535,14 @@ pub fn set_audio_config(
     device_name: Option<String>,
     sample_rate: u32,
     buffer_size: Option<u32>,
-    _app_handle: tauri::AppHandle,
+    preserve_plugin: Option<bool>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
+    let buffer_size = buffer_size.unwrap_or(512);
+
+    // Reject unsupported buffer sizes up front rather than failing deep in stream creation
+    validate_buffer_size(device_name.as_deref(), buffer_size)?;
+
     // Stop any current playback
     if let Some(handle) = get_engine_handle() {
         handle.stop();
@@ -206,9 +552,28 @@ pub fn set_audio_config(
     let config = AudioConfig {
         sample_rate,
         channels: 2,
-        buffer_size: buffer_size.unwrap_or(512),
+        buffer_size,
     };
-    reinit_engine(device_name.as_deref(), config)
+
+    if !preserve_plugin.unwrap_or(true) {
+        return reinit_engine(device_name.as_deref(), config);
+    }
+
+    // Queues point at the old plugin - detach them before it is torn down
+    clear_midi_player_queue();
+    update_midi_input_queue();
+
+    let result = reinit_engine_preserving_plugin(device_name.as_deref(), config);
+    if get_engine_handle().is_some_and(|h| h.has_plugin()) {
+        notify_plugin_restored(&app_handle);
+    }
+    result
+}
+
+/// Get the buffer sizes supported by an output device (default device if None)
+#[tauri::command]
+pub fn get_supported_buffer_sizes(device_name: Option<String>) -> Result<SupportedBufferSizes, String> {
+    crate::audio::device::get_supported_buffer_sizes(device_name.as_deref())
 }
 
 /// Get the system's default audio sample rate
@@ -242,6 +607,9 @@ pub fn preview_pause() -> Result<(), String> {
 }
 
 /// Set the input source to a test signal
+///
+/// A "burst" is an impulse, or a tone burst of `burst_cycles` cycles when given,
+/// played once per preview_trigger_burst
 #[tauri::command]
 pub fn preview_set_signal(
     signal_type: String,
@@ -250,6 +618,7 @@ pub fn preview_set_signal(
     gate_pattern: Option<String>,
     gate_rate: Option<f32>,
     gate_duty: Option<f32>,
+    burst_cycles: Option<u32>,
 ) -> Result<(), String> {
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
 
@@ -258,8 +627,10 @@ pub fn preview_set_signal(
         "square" => SignalType::Square,
         "white_noise" => SignalType::WhiteNoise,
         "pink_noise" => SignalType::PinkNoise,
+        "brown_noise" => SignalType::BrownNoise,
         "impulse" => SignalType::Impulse,
         "sweep" => SignalType::Sweep,
+        "burst" => SignalType::Burst,
         _ => return Err(format!("Unknown signal type: {}", signal_type)),
     };
 
@@ -278,6 +649,10 @@ pub fn preview_set_signal(
         gate_pattern: gate,
         gate_rate: gate_rate.unwrap_or(2.0),
         gate_duty: gate_duty.unwrap_or(0.5),
+        burst: match burst_cycles {
+            Some(cycles) => BurstShape::ToneBurst { cycles: cycles.max(1) },
+            None => BurstShape::Impulse,
+        },
         ..Default::default()
     };
 
@@ -285,6 +660,13 @@ pub fn preview_set_signal(
     Ok(())
 }
 
+/// Fire the one-shot burst of the test signal (needs the "burst" signal type)
+#[tauri::command]
+pub fn preview_trigger_burst() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.trigger_burst()
+}
+
 /// Set the gate pattern for the current signal
 #[tauri::command]
 pub fn preview_set_gate(
@@ -314,6 +696,28 @@ pub fn preview_set_gate(
     Ok(())
 }
 
+/// Sync the gate to the transport tempo ("quarter", "eighth" or "sixteenth" notes),
+/// or go back to the free-running gate when `division` is None or "off"
+#[tauri::command]
+pub fn preview_set_gate_sync(division: Option<String>, duty: Option<f32>) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+
+    let division = match division.as_deref() {
+        None | Some("off") => None,
+        Some("quarter") => Some(GatePattern::Quarter),
+        Some("eighth") => Some(GatePattern::Eighth),
+        Some("sixteenth") => Some(GatePattern::Sixteenth),
+        Some(other) => return Err(format!("Unknown gate division: {}", other)),
+    };
+
+    handle.set_gate_sync(division);
+    if let Some(d) = duty {
+        handle.set_gate_duty(d);
+    }
+
+    Ok(())
+}
+
 /// Set the signal frequency
 #[tauri::command]
 pub fn preview_set_frequency(frequency: f32) -> Result<(), String> {
@@ -331,11 +735,12 @@ pub fn preview_set_amplitude(amplitude: f32) -> Result<(), String> {
 }
 
 /// Load and set input source to a sample file
+/// Returns the loaded sample's info (long files are streamed rather than loaded into memory)
 #[tauri::command]
-pub fn preview_load_sample(path: String) -> Result<(), String> {
+pub fn preview_load_sample(path: String) -> Result<Option<SampleInfo>, String> {
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
     handle.set_input_source(InputSource::Sample { path });
-    Ok(())
+    Ok(handle.sample_info())
 }
 
 /// Set looping mode
@@ -346,6 +751,56 @@ pub fn preview_set_looping(looping: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Loop only part of the loaded sample (in seconds) while looping is on.
+/// The region is clamped to the sample; the wrap is crossfaded so it doesn't click.
+#[tauri::command]
+pub fn preview_set_loop_region(start_secs: f32, end_secs: f32) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_loop_region(start_secs, end_secs)
+}
+
+/// Loop the whole sample again
+#[tauri::command]
+pub fn preview_clear_loop_region() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.clear_loop_region();
+    Ok(())
+}
+
+/// Speed sample playback up or slow it down (pitch follows, like tape). The ratio is
+/// clamped to 0.25 - 4.0 and playback carries on from its current position.
+/// Returns the speed that was applied.
+#[tauri::command]
+pub fn preview_set_playback_speed(ratio: f32) -> Result<f32, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    if !ratio.is_finite() {
+        return Err("Playback speed must be a finite number".to_string());
+    }
+    handle.set_playback_speed(ratio);
+    Ok(handle.get_playback_speed())
+}
+
+/// Get the signal generator settings (kept even while another input source is active)
+#[tauri::command]
+pub fn preview_get_signal() -> Result<SignalConfig, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_signal_config())
+}
+
+/// Get the active input source
+#[tauri::command]
+pub fn preview_get_input_source() -> Result<InputSource, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_input_source())
+}
+
+/// Get looping mode
+#[tauri::command]
+pub fn preview_get_looping() -> Result<bool, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.is_looping())
+}
+
 /// Get current preview state
 #[tauri::command]
 pub fn preview_get_state() -> Result<PreviewState, String> {
@@ -366,6 +821,76 @@ pub fn preview_get_levels() -> Result<(f32, f32), String> {
     Ok(handle.get_output_levels())
 }
 
+/// Spectrum band magnitudes with the frequencies they belong to
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectrumBands {
+    /// Output band magnitudes (0.0 - 1.0)
+    pub magnitudes: Vec<f32>,
+    /// Input (pre-FX) band magnitudes (0.0 - 1.0)
+    pub input_magnitudes: Vec<f32>,
+    /// Center frequency of each band in Hz (log spaced)
+    pub frequencies: Vec<f32>,
+    /// Frequency range [low, high] each band covers in Hz
+    pub edges: Vec<[f32; 2]>,
+    pub sample_rate: u32,
+    pub fft_size: usize,
+    /// Width of one FFT bin in Hz (the finest resolution any band can have)
+    pub bin_hz: f32,
+}
+
+/// Get the spectrum band magnitudes together with their center frequencies and edges,
+/// so the frequency axis can be drawn from the analyzer's actual band layout
+#[tauri::command]
+pub fn preview_get_spectrum_bands() -> Result<SpectrumBands, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    let sample_rate = get_engine_sample_rate().unwrap_or(48000);
+    let fft_size = handle.get_spectrum_fft_size();
+    Ok(SpectrumBands {
+        magnitudes: handle.get_spectrum_data().to_vec(),
+        input_magnitudes: handle.get_spectrum_input_data().to_vec(),
+        frequencies: handle.get_spectrum_band_frequencies().to_vec(),
+        edges: handle
+            .get_spectrum_band_edges()
+            .iter()
+            .map(|&(low, high)| [low, high])
+            .collect(),
+        sample_rate,
+        fft_size,
+        bin_hz: sample_rate as f32 / fft_size as f32,
+    })
+}
+
+/// Set the spectrum analyzer's FFT size (a power of 2 from 256 to 16384).
+/// Larger sizes resolve bass better but respond more slowly.
+#[tauri::command]
+pub fn preview_set_spectrum_resolution(size: usize) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_spectrum_fft_size(size)
+}
+
+/// Freeze the current output spectrum so it can be overlaid against the live one
+#[tauri::command]
+pub fn preview_capture_spectrum() -> Result<Vec<f32>, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.capture_spectrum_snapshot();
+    Ok(handle.get_spectrum_snapshot().map(|bands| bands.to_vec()).unwrap_or_default())
+}
+
+/// Get the frozen spectrum snapshot (None if nothing has been captured)
+#[tauri::command]
+pub fn preview_get_spectrum_snapshot() -> Result<Option<Vec<f32>>, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_spectrum_snapshot().map(|bands| bands.to_vec()))
+}
+
+/// Discard the frozen spectrum snapshot
+#[tauri::command]
+pub fn preview_clear_spectrum_snapshot() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.clear_spectrum_snapshot();
+    Ok(())
+}
+
 /// Get list of available demo samples
 #[tauri::command]
 pub fn get_demo_samples(app_handle: tauri::AppHandle) -> Result<Vec<DemoSample>, String> {
@@ -442,7 +967,7 @@ fn scan_samples_dir(dir: &PathBuf) -> Result<Vec<DemoSample>, String> {
         let path = entry.path();
         if let Some(ext) = path.extension() {
             let ext = ext.to_string_lossy().to_lowercase();
-            if ext == "wav" || ext == "mp3" || ext == "flac" || ext == "ogg" {
+            if matches!(ext.as_str(), "wav" | "mp3" | "flac" | "ogg" | "aif" | "aiff" | "aifc" | "caf") {
                 let name = path
                     .file_stem()
                     .and_then(|s| s.to_str())
@@ -486,6 +1011,7 @@ pub fn start_level_meter(app_handle: tauri::AppHandle) -> Result<(), String> {
 
     std::thread::spawn(move || {
         log::debug!("Level meter thread started");
+        let mut last_note_names_generation = note_names_generation();
         while LEVEL_METER_RUNNING.load(Ordering::SeqCst) {
             std::thread::sleep(std::time::Duration::from_millis(16)); // ~60fps
 
@@ -496,6 +1022,7 @@ pub fn start_level_meter(app_handle: tauri::AppHandle) -> Result<(), String> {
 
             if let Some(handle) = get_engine_handle() {
                 let (left, right) = handle.get_output_levels();
+                let weighted_level = handle.get_weighted_output_level();
                 let (input_left, input_right) = handle.get_input_levels();
                 let spectrum = handle.get_spectrum_data();
                 let spectrum_input = handle.get_spectrum_input_data();
@@ -506,6 +1033,7 @@ pub fn start_level_meter(app_handle: tauri::AppHandle) -> Result<(), String> {
                 let (waveform_input_left, waveform_input_right) = handle.get_waveform_input_data();
                 let (waveform_input_peak_left, waveform_input_peak_right) = handle.get_waveform_input_peaks();
                 let (clipping_left, clipping_right) = handle.get_clipping();
+                let headroom = headroom_stats(&handle);
                 // Output stereo (post-FX)
                 let stereo_positions_tuples = handle.get_stereo_positions();
                 let stereo_correlation = handle.get_stereo_correlation();
@@ -514,14 +1042,14 @@ pub fn start_level_meter(app_handle: tauri::AppHandle) -> Result<(), String> {
                 let stereo_correlation_input = handle.get_stereo_correlation_input();
 
                 // Convert stereo positions from tuples to arrays for JSON serialization
-                let stereo_positions: Vec<[f32; 2]> = stereo_positions_tuples
+                let (stereo_positions, stereo_recent): (Vec<[f32; 2]>, Vec<bool>) = stereo_positions_tuples
                     .into_iter()
-                    .map(|(angle, radius)| [angle, radius])
-                    .collect();
-                let stereo_positions_input: Vec<[f32; 2]> = stereo_positions_input_tuples
+                    .map(|(angle, radius, recent)| ([angle, radius], recent))
+                    .unzip();
+                let (stereo_positions_input, stereo_recent_input): (Vec<[f32; 2]>, Vec<bool>) = stereo_positions_input_tuples
                     .into_iter()
-                    .map(|(angle, radius)| [angle, radius])
-                    .collect();
+                    .map(|(angle, radius, recent)| ([angle, radius], recent))
+                    .unzip();
 
                 // Get plugin performance metrics (only if monitoring is enabled)
                 let plugin_performance = handle.get_plugin_performance();
@@ -535,12 +1063,22 @@ pub fn start_level_meter(app_handle: tauri::AppHandle) -> Result<(), String> {
                     let _ = app_handle.emit("plugin-crashed", "Plugin crashed during audio processing. Reload to try again.");
                 }
 
+                // Plugin reported new note names - let the keyboard relabel
+                let generation = note_names_generation();
+                if generation != last_note_names_generation {
+                    last_note_names_generation = generation;
+                    let _ = app_handle.emit("plugin-note-names-changed", ());
+                }
+
                 // Send combined metering data with dB values, waveform, and clipping indicators
                 let metering = MeteringData {
                     left,
                     right,
                     left_db: level_to_db(left),
                     right_db: level_to_db(right),
+                    weighting: handle.get_metering_weighting(),
+                    weighted_level,
+                    weighted_db: level_to_db(weighted_level),
                     input_left,
                     input_right,
                     input_left_db: level_to_db(input_left),
@@ -557,9 +1095,12 @@ pub fn start_level_meter(app_handle: tauri::AppHandle) -> Result<(), String> {
                     waveform_input_peak_right,
                     clipping_left,
                     clipping_right,
+                    headroom,
                     stereo_positions,
+                    stereo_recent,
                     stereo_correlation,
                     stereo_positions_input,
+                    stereo_recent_input,
                     stereo_correlation_input,
                     plugin_performance,
                     plugin_crashed,
@@ -607,11 +1148,48 @@ fn prewarm_midi_paths(handle: &crate::audio::engine::AudioEngineHandle) {
     log::debug!("MIDI code paths pre-warmed");
 }
 
-/// Load a CLAP plugin from a .clap bundle path
+/// Where a project's plugin state is kept between sessions
+fn get_plugin_state_sidecar_path(project_name: &str) -> PathBuf {
+    super::projects::get_projects_path()
+        .join(project_name)
+        .join(".vstworkshop")
+        .join("plugin-state.bin")
+}
+
+/// Save the loaded project plugin's state to its project's sidecar file
+///
+/// Does nothing when the loaded plugin doesn't belong to a project. Called before the
+/// plugin is unloaded or replaced, and on app exit.
+pub fn save_project_plugin_state() {
+    let Some(path) = PROJECT_PLUGIN_STATE_PATH.lock().clone() else {
+        return;
+    };
+    let Some(handle) = get_engine_handle() else {
+        return;
+    };
+    if let Err(e) = handle.save_plugin_state_to_file(&path) {
+        log::warn!("Failed to save project plugin state: {}", e);
+    }
+}
+
+/// A manual effect/instrument choice belongs to the plugin it was made for: loading a
+/// different bundle goes back to detection, reloading the same one keeps it
+fn clear_instrument_override_unless_loaded(handle: &crate::audio::engine::AudioEngineHandle, path: &str) {
+    if handle.loaded_plugin_path().as_deref() != Some(path) {
+        handle.clear_instrument_override();
+    }
+}
+
+/// Load a plugin from a .clap or .vst3 bundle path
 #[tauri::command]
 pub fn plugin_load(path: String, app_handle: tauri::AppHandle) -> Result<(), String> {
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
 
+    // The plugin being replaced keeps its project state; this one isn't tied to a project
+    save_project_plugin_state();
+    *PROJECT_PLUGIN_STATE_PATH.lock() = None;
+    clear_instrument_override_unless_loaded(&handle, &path);
+
     // Emit loading event
     let _ = app_handle.emit("plugin-loading", &path);
 
@@ -620,6 +1198,7 @@ pub fn plugin_load(path: String, app_handle: tauri::AppHandle) -> Result<(), Str
             // Reset crash event flag AFTER successful load so we don't get a
             // spurious toast from the old crashed plugin during reload
             CRASH_EVENT_EMITTED.store(false, Ordering::SeqCst);
+            PROCESS_ERROR_EVENT_EMITTED.store(false, Ordering::SeqCst);
 
             // Start crash monitor to detect crashes even when PreviewPanel is closed
             start_crash_monitor(app_handle.clone());
@@ -651,6 +1230,8 @@ pub fn plugin_unload(app_handle: tauri::AppHandle) -> Result<(), String> {
     clear_midi_input_queue();
     // Close editor first to save window position
     handle.close_plugin_editor();
+    save_project_plugin_state();
+    *PROJECT_PLUGIN_STATE_PATH.lock() = None;
     handle.unload_plugin();
     let _ = app_handle.emit("plugin-unloaded", ());
     Ok(())
@@ -677,7 +1258,67 @@ pub fn plugin_has_editor() -> Result<bool, String> {
     Ok(handle.plugin_has_editor())
 }
 
-/// Scan a directory for .clap plugin bundles
+/// Dump the loaded plugin's parameters (id, name, range, current value, flags) so the
+/// agent or UI can check what the built plugin actually exposes.
+/// Returns an empty list when no plugin is loaded.
+#[tauri::command]
+pub fn plugin_dump_params_json() -> Vec<PluginParam> {
+    get_engine_handle()
+        .map(|handle| handle.get_plugin_params())
+        .unwrap_or_default()
+}
+
+/// Get the loaded plugin's note names (drum maps, key switches) as (key, name) pairs
+/// The frontend re-fetches when "plugin-note-names-changed" is emitted
+#[tauri::command]
+pub fn plugin_get_note_names() -> Result<Vec<(i16, String)>, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_plugin_note_names())
+}
+
+/// List the audio ports the loaded plugin declares (main and sidechain inputs, outputs)
+#[tauri::command]
+pub fn plugin_list_ports() -> Result<Vec<AudioPortInfo>, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.get_plugin_ports().ok_or_else(|| "No plugin loaded".to_string())
+}
+
+/// Set how the stereo input is fed to the plugin's main input
+/// ("stereo_passthrough", "mono_sum" or "left_only")
+#[tauri::command]
+pub fn plugin_set_input_mapping(mode: InputMapping) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_input_mapping(mode);
+    Ok(())
+}
+
+/// Bypass the hosted plugin (crossfades to the unprocessed input; kept across plugin loads)
+#[tauri::command]
+pub fn plugin_set_bypass(bypass: bool) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_plugin_bypass(bypass);
+    Ok(())
+}
+
+/// Blend the plugin output with the dry input: 0.0 = fully dry, 1.0 = fully wet
+#[tauri::command]
+pub fn plugin_set_mix(mix: f32) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    if !mix.is_finite() {
+        return Err("Mix must be a finite number".to_string());
+    }
+    handle.set_plugin_mix(mix);
+    Ok(())
+}
+
+/// Get the current input mapping
+#[tauri::command]
+pub fn plugin_get_input_mapping() -> Result<InputMapping, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_input_mapping())
+}
+
+/// Scan a directory for .clap and .vst3 plugin bundles
 #[tauri::command]
 pub fn plugin_scan_directory(path: String) -> Result<Vec<PluginInfo>, String> {
     let dir = std::path::Path::new(&path);
@@ -690,7 +1331,7 @@ pub fn plugin_scan_directory(path: String) -> Result<Vec<PluginInfo>, String> {
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.extension().map(|e| e == "clap").unwrap_or(false) {
+            if path.extension().map(|e| e == "clap" || e == "vst3").unwrap_or(false) {
                 let name = path
                     .file_stem()
                     .and_then(|s| s.to_str())
@@ -759,11 +1400,26 @@ pub fn plugin_load_for_project(
     // Emit loading event
     let _ = app_handle.emit("plugin-loading", &plugin_path);
 
+    save_project_plugin_state();
+    *PROJECT_PLUGIN_STATE_PATH.lock() = None;
+    clear_instrument_override_unless_loaded(&handle, &plugin_path);
+
     match handle.load_plugin(std::path::Path::new(&plugin_path)) {
         Ok(()) => {
             // Reset crash event flag AFTER successful load so we don't get a
             // spurious toast from the old crashed plugin during reload
             CRASH_EVENT_EMITTED.store(false, Ordering::SeqCst);
+            PROCESS_ERROR_EVENT_EMITTED.store(false, Ordering::SeqCst);
+
+            // Pick up where the last session left off
+            let state_path = get_plugin_state_sidecar_path(&project_name);
+            if state_path.exists() {
+                match handle.load_plugin_state_from_file(&state_path) {
+                    Ok(()) => log::info!("Restored plugin state from {}", state_path.display()),
+                    Err(e) => log::warn!("Failed to restore plugin state: {}", e),
+                }
+            }
+            *PROJECT_PLUGIN_STATE_PATH.lock() = Some(state_path);
 
             // Start crash monitor to detect crashes even when PreviewPanel is closed
             start_crash_monitor(app_handle.clone());
@@ -811,6 +1467,35 @@ pub fn plugin_is_editor_open() -> Result<bool, String> {
     Ok(handle.is_plugin_editor_open())
 }
 
+/// Capture the loaded plugin's editor and save it as the project's thumbnail
+///
+/// Opens the editor off-screen if it isn't already open, so this blocks for about a second.
+/// Editor work runs on the main thread; only the wait for the editor to draw happens here.
+#[tauri::command]
+pub async fn plugin_capture_thumbnail(
+    app_handle: tauri::AppHandle,
+    project_path: String,
+) -> Result<super::projects::ProjectMeta, String> {
+    tokio::task::spawn_blocking(move || {
+        let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+        let opened = {
+            let handle = handle.clone();
+            run_on_main_thread_blocking(&app_handle, "editor open", move || handle.open_plugin_editor_for_capture())?
+        };
+        if opened {
+            // Give the UI time to draw (a webview has to load its page first)
+            std::thread::sleep(std::time::Duration::from_millis(THUMBNAIL_RENDER_DELAY_MS));
+        }
+        let png = run_on_main_thread_blocking(&app_handle, "editor capture", move || {
+            handle.capture_plugin_editor(opened)
+        })?;
+        log::info!("Captured plugin editor thumbnail ({} bytes) for {}", png.len(), project_path);
+        super::projects::save_project_thumbnail(&project_path, &png)
+    })
+    .await
+    .map_err(|e| format!("Thumbnail capture task failed: {}", e))?
+}
+
 /// Enable or disable plugin performance monitoring
 /// When enabled, the engine measures plugin.process() call duration
 /// When disabled, no timing overhead is incurred (zero overhead design)
@@ -841,10 +1526,15 @@ pub fn plugin_idle() {
 
 /// Reload the current plugin (for hot reload)
 /// If a project is specified, reload from that project's output folder
+///
+/// The plugin's state is carried over to the new build and the swap is crossfaded.
+/// Pass `preserve_state: false` to start from defaults (e.g. after an intentional
+/// parameter layout change).
 #[tauri::command]
 pub fn plugin_reload(
     project_name: Option<String>,
     version: Option<u32>,
+    preserve_state: Option<bool>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     use tauri::Emitter;
@@ -857,11 +1547,9 @@ pub fn plugin_reload(
             .ok_or_else(|| format!("No .clap plugin found for {} v{}", name, ver))?
     } else {
         // Try to get path from current plugin state
-        match handle.get_plugin_state() {
-            PluginState::Active { path, .. } => path,
-            PluginState::Reloading { path } => path,
-            _ => return Err("No plugin loaded to reload".to_string()),
-        }
+        handle
+            .loaded_plugin_path()
+            .ok_or_else(|| "No plugin loaded to reload".to_string())?
     };
 
     log::info!("Hot reloading plugin: {}", plugin_path);
@@ -869,6 +1557,22 @@ pub fn plugin_reload(
     // Emit reloading event
     let _ = app_handle.emit("plugin-reloading", &plugin_path);
 
+    // Capture the current state before the old build goes away
+    let saved_state = if preserve_state.unwrap_or(true) {
+        handle.save_plugin_state()
+    } else {
+        None
+    };
+
+    // Fade the old build out (bounded wait - the fade only advances while audio is processed)
+    handle.start_crossfade_out();
+    for _ in 0..20 {
+        if handle.is_crossfade_complete() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
     // Close editor if open
     handle.close_plugin_editor();
 
@@ -884,8 +1588,22 @@ pub fn plugin_reload(
     // Reload
     match handle.load_plugin(std::path::Path::new(&plugin_path)) {
         Ok(()) => {
+            // Restore the previous state before fading in; a new build whose parameter
+            // layout changed may reject it, in which case it keeps its defaults
+            if let Some(state) = saved_state {
+                match handle.restore_plugin_state_pending(state) {
+                    Ok(()) => log::info!("Restored plugin state after hot reload"),
+                    Err(e) => {
+                        log::warn!("Could not restore plugin state after hot reload: {}", e);
+                        let _ = app_handle.emit("plugin-state-restore-failed", &e);
+                    }
+                }
+            }
+            handle.start_crossfade_in();
+
             // Reset crash event flag AFTER successful load so we can detect crashes in the reloaded plugin
             CRASH_EVENT_EMITTED.store(false, Ordering::SeqCst);
+            PROCESS_ERROR_EVENT_EMITTED.store(false, Ordering::SeqCst);
 
             // Restart crash monitor for the reloaded plugin
             start_crash_monitor(app_handle.clone());
@@ -908,38 +1626,216 @@ pub fn plugin_reload(
 }
 
 // =============================================================================
-// Live Input Commands
+// Preview Session Commands
 // =============================================================================
 
-/// Get list of available audio input devices
-#[tauri::command]
-pub fn get_input_devices() -> Result<Vec<AudioDeviceInfo>, String> {
-    list_input_devices()
+/// User-facing preview state persisted per project in .vstworkshop/preview_session.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewSession {
+    /// Active input source (signal, sample, live)
+    pub input_source: InputSource,
+    /// Signal generator settings (kept even when a sample is the active source)
+    pub signal: SignalConfig,
+    /// Master volume (0.0 - 1.0)
+    pub master_volume: f32,
+    /// Sample looping
+    pub looping: bool,
+    /// Whether the loaded plugin was flagged as an instrument
+    #[serde(default)]
+    pub is_instrument: bool,
+    /// Path to the loaded .clap bundle
+    #[serde(default)]
+    pub plugin_path: Option<String>,
+    /// Plugin state (preset) as base64
+    #[serde(default)]
+    pub plugin_state: Option<String>,
+    /// Recorded parameter automation lanes
+    #[serde(default)]
+    pub automation: Option<ParamAutomation>,
+    /// When the session was saved (RFC 3339)
+    pub saved_at: String,
 }
 
-/// Set the input source to live audio input
-/// chunk_size: Resampler chunk size (default: 256). Smaller = lower latency, larger = less CPU
-#[tauri::command]
-pub fn preview_set_live_input(device_name: Option<String>, chunk_size: Option<usize>) -> Result<(), String> {
-    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
-    handle.set_input_source(InputSource::Live { device: device_name, chunk_size });
-    Ok(())
+fn get_preview_session_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path)
+        .join(".vstworkshop")
+        .join("preview_session.json")
 }
 
-/// Set the live input paused state
+/// Save the current preview session (source, signal, volume, plugin + state) for a project
 #[tauri::command]
-pub fn preview_set_live_paused(paused: bool) -> Result<(), String> {
+pub fn save_preview_session(project_path: String) -> Result<(), String> {
+    use base64::Engine;
+
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
-    handle.set_live_paused(paused);
+    let snapshot = handle.snapshot_plugin();
+
+    let session = PreviewSession {
+        input_source: handle.get_input_source(),
+        signal: handle.get_signal_config(),
+        master_volume: handle.get_master_volume(),
+        looping: handle.is_looping(),
+        is_instrument: handle.is_instrument(),
+        plugin_path: snapshot.as_ref().map(|s| s.path.clone()),
+        plugin_state: snapshot
+            .and_then(|s| s.state)
+            .map(|state| base64::engine::general_purpose::STANDARD.encode(state)),
+        automation: handle.get_param_automation(),
+        saved_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let session_file = get_preview_session_path(&project_path);
+    if let Some(parent) = session_file.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create session directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&session)
+        .map_err(|e| format!("Failed to serialize preview session: {}", e))?;
+    std::fs::write(&session_file, json)
+        .map_err(|e| format!("Failed to write preview session: {}", e))?;
+
     Ok(())
 }
 
-/// Get live input paused state
+/// Restore a project's saved preview session
+/// Returns None if the project has no saved session. A missing plugin or sample file
+/// is skipped (logged) rather than failing the whole restore.
 #[tauri::command]
-pub fn preview_is_live_paused() -> Result<bool, String> {
-    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
-    Ok(handle.is_live_paused())
-}
+pub fn load_preview_session(
+    project_path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<PreviewSession>, String> {
+    use base64::Engine;
+
+    let session_file = get_preview_session_path(&project_path);
+    if !session_file.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&session_file)
+        .map_err(|e| format!("Failed to read preview session: {}", e))?;
+    let session: PreviewSession = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse preview session: {}", e))?;
+
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+
+    handle.set_master_volume(session.master_volume);
+    handle.set_looping(session.looping);
+    handle.set_signal_config(session.signal.clone());
+
+    match &session.input_source {
+        InputSource::Sample { path } if !std::path::Path::new(path).exists() => {
+            log::warn!("Preview session sample not found, skipping: {}", path);
+        }
+        // Don't open an input device just because a project was opened
+        InputSource::Live { .. } => {
+            log::info!("Preview session used live input - leaving input source unchanged");
+        }
+        source => handle.set_input_source(source.clone()),
+    }
+
+    if let Some(path) = &session.plugin_path {
+        if !std::path::Path::new(path).exists() {
+            log::warn!("Preview session plugin not found, skipping: {}", path);
+        } else if let Err(e) = plugin_load(path.clone(), app_handle) {
+            log::warn!("Failed to restore preview session plugin: {}", e);
+        } else {
+            // Only a type that differs from detection was a manual choice
+            if session.is_instrument != handle.is_instrument() {
+                handle.set_is_instrument(session.is_instrument);
+            }
+            if let Some(encoded) = &session.plugin_state {
+                match base64::engine::general_purpose::STANDARD.decode(encoded) {
+                    Ok(state) => {
+                        if let Err(e) = handle.restore_plugin_state(&state) {
+                            log::warn!("Failed to restore preview session plugin state: {}", e);
+                        }
+                    }
+                    Err(e) => log::warn!("Invalid plugin state in preview session: {}", e),
+                }
+            }
+        }
+    }
+
+    if session.automation.is_some() {
+        handle.set_param_automation(session.automation.clone());
+    }
+
+    Ok(Some(session))
+}
+
+// =============================================================================
+// Live Input Commands
+// =============================================================================
+
+/// Get list of available audio input devices
+#[tauri::command]
+pub fn get_input_devices() -> Result<Vec<AudioDeviceInfo>, String> {
+    list_input_devices()
+}
+
+/// Set the input source to live audio input
+/// chunk_size: Resampler chunk size (default: 256). Smaller = lower latency, larger = less CPU
+#[tauri::command]
+pub fn preview_set_live_input(device_name: Option<String>, chunk_size: Option<usize>) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_input_source(InputSource::Live { device: device_name, chunk_size });
+    Ok(())
+}
+
+/// Set the live input paused state
+#[tauri::command]
+pub fn preview_set_live_paused(paused: bool) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_live_paused(paused);
+    Ok(())
+}
+
+/// Choose which physical input channels (0-based) feed the left/right inputs
+/// Use the same index twice to hear a single mono input on both sides.
+#[tauri::command]
+pub fn preview_set_input_channels(left_idx: u16, right_idx: u16) -> Result<(), String> {
+    crate::audio::input::set_input_channels(left_idx, right_idx)
+}
+
+/// Set whether live input is heard (passed to the plugin and output)
+/// Capture and input metering keep running while monitoring is off.
+#[tauri::command]
+pub fn preview_set_input_monitor(enabled: bool) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_input_monitor(enabled);
+    Ok(())
+}
+
+/// Get whether live input is heard
+#[tauri::command]
+pub fn preview_get_input_monitor() -> Result<bool, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.is_input_monitor_enabled())
+}
+
+/// Change the live input resampler chunk size without restarting capture
+/// Larger chunks stop dropouts on slower machines, smaller ones tighten monitoring.
+#[tauri::command]
+pub fn preview_set_input_latency(chunk_size: usize) -> Result<LiveInputLatency, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_input_latency(chunk_size)
+}
+
+/// Get whether live input is resampled and the latency that adds
+#[tauri::command]
+pub fn preview_get_input_latency() -> Result<LiveInputLatency, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_input_latency())
+}
+
+/// Get live input paused state
+#[tauri::command]
+pub fn preview_is_live_paused() -> Result<bool, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.is_live_paused())
+}
 
 /// Get input levels (for live input metering)
 #[tauri::command]
@@ -963,10 +1859,354 @@ pub fn preview_get_master_volume() -> Result<f32, String> {
     Ok(handle.get_master_volume())
 }
 
+/// Device channels the stereo preview is written to
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputChannels {
+    /// Left channel index (0-based)
+    pub left: u16,
+    /// Right channel index (0-based)
+    pub right: u16,
+    /// Total channels on the output device
+    pub device_channels: u16,
+}
+
+fn output_channels(handle: &crate::audio::engine::AudioEngineHandle) -> OutputChannels {
+    let (left, right) = handle.get_output_channels();
+    OutputChannels {
+        left,
+        right,
+        device_channels: handle.output_channel_count(),
+    }
+}
+
+/// Route the stereo output to a pair of the device's channels (0-based), e.g. 2/3 for outputs 3-4
+#[tauri::command]
+pub fn preview_set_output_channels(left_idx: u16, right_idx: u16) -> Result<OutputChannels, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_output_channels(left_idx, right_idx)?;
+    Ok(output_channels(&handle))
+}
+
+/// Get the device channels the stereo output is written to
+#[tauri::command]
+pub fn preview_get_output_channels() -> Result<OutputChannels, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(output_channels(&handle))
+}
+
+/// Engine clock position, for syncing UI animations to the audio
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaybackPosition {
+    /// Frames rendered since the engine started (free-running)
+    pub sample_clock: u64,
+    /// Frames rendered while playing since the last stop
+    pub play_clock: u64,
+    /// play_clock in seconds
+    pub position_secs: f64,
+    pub sample_rate: u32,
+    pub is_playing: bool,
+}
+
+/// Get the engine's sample clock and transport position
+#[tauri::command]
+pub fn preview_get_position() -> Result<PlaybackPosition, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(PlaybackPosition {
+        sample_clock: handle.sample_clock(),
+        play_clock: handle.play_clock(),
+        position_secs: handle.get_play_position_secs(),
+        sample_rate: handle.sample_rate(),
+        is_playing: handle.is_playing(),
+    })
+}
+
+/// Enable/disable the DC blocker on the final output
+#[tauri::command]
+pub fn preview_set_dc_block(enabled: bool) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_dc_block(enabled);
+    Ok(())
+}
+
+/// Configure output dither (bits: 16 or 24, shaped: first-order noise shaping)
+#[tauri::command]
+pub fn preview_set_dither(enabled: bool, bits: u8, shaped: bool) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_dither(enabled, bits, shaped)
+}
+
+/// Measure the loaded plugin's magnitude and phase response with a sine sweep
+/// Takes a few seconds; the device output is muted while the sweep plays
+#[tauri::command]
+pub async fn preview_measure_response() -> Result<FrequencyResponse, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    tokio::task::spawn_blocking(move || handle.run_frequency_response())
+        .await
+        .map_err(|e| format!("Measurement task failed: {}", e))?
+}
+
+/// Render the loaded sample through the loaded plugin to a WAV file, faster than realtime
+/// Emits "render-progress" events while rendering; playback isn't interrupted
+#[tauri::command]
+pub async fn preview_render_sample(output_path: String, app_handle: tauri::AppHandle) -> Result<RenderResult, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    tokio::task::spawn_blocking(move || {
+        handle.render_sample_through_plugin(std::path::Path::new(&output_path), |progress| {
+            let _ = app_handle.emit("render-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| format!("Render task failed: {}", e))?
+}
+
+/// Configure the goniometer: number of history points shown and display gain for point radii
+#[tauri::command]
+pub fn preview_set_goniometer_scale(history_length: usize, gain: f32) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_goniometer_scale(history_length, gain)
+}
+
+/// Set the correlation meter smoothing time constant in milliseconds (0 = no smoothing)
+#[tauri::command]
+pub fn preview_set_correlation_smoothing(ms: f32) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_correlation_smoothing(ms)
+}
+
+/// Configure the safety limiter: ceiling in dBFS (-24 to +6) and "hard" clamp or "soft" (tanh) curve
+#[tauri::command]
+pub fn preview_set_limiter(ceiling_db: f32, mode: LimiterMode) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_limiter(ceiling_db, mode)
+}
+
+/// Set the frequency weighting of the weighted output level: "none", "a" (A-weighting)
+/// or "k" (K-weighting per BS.1770)
+#[tauri::command]
+pub fn preview_set_metering_weighting(mode: String) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    let weighting = match mode.to_lowercase().as_str() {
+        "none" => MeteringWeighting::None,
+        "a" => MeteringWeighting::A,
+        "k" => MeteringWeighting::K,
+        _ => return Err(format!("Unknown metering weighting: {}", mode)),
+    };
+    handle.set_metering_weighting(weighting);
+    Ok(())
+}
+
+/// Get the output's momentary (400 ms), short-term (3 s) and integrated loudness in LUFS.
+/// Readings are null until enough audio has played (or when it's been silent).
+#[tauri::command]
+pub fn preview_get_loudness() -> Result<LoudnessReading, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_loudness())
+}
+
+/// Restart integrated loudness measurement
+#[tauri::command]
+pub fn preview_reset_loudness() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.reset_loudness();
+    Ok(())
+}
+
+/// Get safety limiter activity since the last reset
+#[tauri::command]
+pub fn preview_get_headroom_stats() -> Result<HeadroomStats, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(headroom_stats(&handle))
+}
+
+/// Reset the safety limiter counters
+#[tauri::command]
+pub fn preview_reset_headroom_stats() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.reset_headroom_stats();
+    Ok(())
+}
+
+/// Clear all meters and analysis displays (done automatically on plugin load/unload
+/// and input source changes)
+#[tauri::command]
+pub fn preview_reset_analysis() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.reset_analysis();
+    Ok(())
+}
+
+/// Choose what's sent to the output with an effect plugin loaded:
+/// normal, wet (plugin only), dry (latency-aligned bypass) or difference (null test residual)
+#[tauri::command]
+pub fn preview_set_monitor_mode(mode: MonitorMode) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_monitor_mode(mode);
+    Ok(())
+}
+
+/// Get the current monitor mode
+#[tauri::command]
+pub fn preview_get_monitor_mode() -> Result<MonitorMode, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_monitor_mode())
+}
+
+/// Set the monitor stereo width (0 = mono, 1 = unchanged, 2 = widest)
+/// Out-of-range values are clamped; returns the width in effect
+#[tauri::command]
+pub fn preview_set_monitor_width(width: f32) -> Result<f32, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_monitor_width(width)
+}
+
+/// Get the monitor stereo width
+#[tauri::command]
+pub fn preview_get_monitor_width() -> Result<f32, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_monitor_width())
+}
+
+/// Gain match status returned to frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GainMatchInfo {
+    /// Whether gain matching is enabled
+    pub enabled: bool,
+    /// Compensation applied to the plugin output in dB (negative = plugin turned down)
+    pub compensation_db: f32,
+}
+
+/// Enable/disable loudness matching of the plugin output against the dry input
+#[tauri::command]
+pub fn preview_set_gain_match(enabled: bool) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_gain_match(enabled);
+    Ok(())
+}
+
+/// Get gain match state and the compensation currently applied
+#[tauri::command]
+pub fn preview_get_gain_match() -> Result<GainMatchInfo, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(GainMatchInfo {
+        enabled: handle.is_gain_match_enabled(),
+        compensation_db: handle.get_gain_match_db(),
+    })
+}
+
+/// Auto level status returned to frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AutoLevelInfo {
+    /// Whether auto level is enabled
+    pub enabled: bool,
+    /// Measurement time constant in seconds
+    pub time_constant_secs: f32,
+    /// Makeup gain applied to the plugin output in dB
+    pub makeup_db: f32,
+}
+
+/// Enable/disable auto level (slow makeup gain matching plugin output to its input)
+/// time_constant_secs optionally changes how slowly it reacts (0.5-10s)
+#[tauri::command]
+pub fn preview_set_auto_level(enabled: bool, time_constant_secs: Option<f32>) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    if let Some(seconds) = time_constant_secs {
+        handle.set_auto_level_time_constant(seconds);
+    }
+    handle.set_auto_level(enabled);
+    Ok(())
+}
+
+/// Get auto level state and the makeup gain currently applied
+#[tauri::command]
+pub fn preview_get_auto_level() -> Result<AutoLevelInfo, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(AutoLevelInfo {
+        enabled: handle.is_auto_level_enabled(),
+        time_constant_secs: handle.get_auto_level_time_constant(),
+        makeup_db: handle.get_auto_level_db(),
+    })
+}
+
+// =============================================================================
+// Parameter Automation Commands
+// =============================================================================
+
+/// Set a plugin parameter by CLAP id (plain value); recorded if recording is active
+#[tauri::command]
+pub fn preview_set_parameter_value(param_id: u32, value: f64) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_parameter_value(param_id, value)
+}
+
+/// Start recording parameter changes against the engine sample clock
+#[tauri::command]
+pub fn start_param_recording() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.start_param_recording();
+    Ok(())
+}
+
+/// Stop recording and return the recorded lanes (also kept for playback)
+#[tauri::command]
+pub fn stop_param_recording() -> Result<Option<ParamAutomation>, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.stop_param_recording())
+}
+
+/// Play back the recorded parameter automation from the start
+#[tauri::command]
+pub fn play_param_automation() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.play_param_automation()
+}
+
+/// Stop parameter automation playback
+#[tauri::command]
+pub fn stop_param_automation() -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.stop_param_automation();
+    Ok(())
+}
+
+/// Get the automation available for playback
+#[tauri::command]
+pub fn get_param_automation() -> Result<Option<ParamAutomation>, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    Ok(handle.get_param_automation())
+}
+
+/// Replace the automation used for playback (None clears it)
+#[tauri::command]
+pub fn set_param_automation(automation: Option<ParamAutomation>) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.set_param_automation(automation);
+    Ok(())
+}
+
 // =============================================================================
 // MIDI Commands (for instrument plugins)
 // =============================================================================
 
+/// Keyboard transpose in semitones, applied to command notes (not the pattern player)
+static MIDI_TRANSPOSE: AtomicI8 = AtomicI8::new(0);
+
+/// Velocity used when a command note doesn't specify one
+static MIDI_DEFAULT_VELOCITY: AtomicU8 = AtomicU8::new(100);
+
+/// Largest transpose allowed in either direction (4 octaves)
+const MAX_MIDI_TRANSPOSE: i8 = 48;
+
+/// Apply the keyboard transpose to a command note
+/// Returns None if the result falls outside 0..=127 (dropped rather than wrapped)
+fn transpose_note(note: u8) -> Option<u8> {
+    let transposed = note as i16 + MIDI_TRANSPOSE.load(Ordering::Relaxed) as i16;
+    (0..=127).contains(&transposed).then_some(transposed as u8)
+}
+
+fn resolve_velocity(velocity: Option<u8>) -> u8 {
+    velocity.unwrap_or_else(|| MIDI_DEFAULT_VELOCITY.load(Ordering::Relaxed))
+}
+
 /// MIDI event for batched processing
 #[derive(serde::Deserialize)]
 pub struct MidiEvent {
@@ -984,11 +2224,15 @@ pub fn midi_batch(events: Vec<MidiEvent>) -> Result<(), String> {
     for event in events {
         match event.event_type.as_str() {
             "on" => {
-                let velocity = event.velocity.unwrap_or(100);
-                handle.midi_note_on(event.note, velocity);
+                if let Some(note) = transpose_note(event.note) {
+                    handle.midi_note_on(note, resolve_velocity(event.velocity));
+                }
             }
             "off" => {
-                handle.midi_note_off(event.note);
+                if let Some(note) = transpose_note(event.note) {
+                    // Velocity on a note off is the release velocity (0 if not given)
+                    handle.midi_note_off_vel(note, event.velocity.unwrap_or(0).min(127));
+                }
             }
             _ => {}
         }
@@ -998,18 +2242,63 @@ pub fn midi_batch(events: Vec<MidiEvent>) -> Result<(), String> {
 }
 
 /// Send a MIDI note on event to the loaded plugin
+/// The keyboard transpose is applied; velocity falls back to the default velocity
 #[tauri::command]
-pub fn midi_note_on(note: u8, velocity: u8) -> Result<(), String> {
+pub fn midi_note_on(note: u8, velocity: Option<u8>) -> Result<(), String> {
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
-    handle.midi_note_on(note, velocity);
+    if let Some(note) = transpose_note(note) {
+        handle.midi_note_on(note, resolve_velocity(velocity));
+    }
     Ok(())
 }
 
-/// Send a MIDI note off event to the loaded plugin
+/// Send a MIDI note off event to the loaded plugin (keyboard transpose applied)
 #[tauri::command]
 pub fn midi_note_off(note: u8) -> Result<(), String> {
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
-    handle.midi_note_off(note);
+    if let Some(note) = transpose_note(note) {
+        handle.midi_note_off(note);
+    }
+    Ok(())
+}
+
+/// Send a MIDI note off event with a release velocity (0-127) to the loaded plugin
+/// (keyboard transpose applied)
+#[tauri::command]
+pub fn midi_note_off_vel(note: u8, velocity: u8) -> Result<(), String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    if let Some(note) = transpose_note(note) {
+        handle.midi_note_off_vel(note, velocity.min(127));
+    }
+    Ok(())
+}
+
+/// Send a MIDI control change (e.g. 1 = mod wheel, 64 = sustain) to the loaded plugin
+/// Channel defaults to 0
+#[tauri::command]
+pub fn midi_cc(controller: u8, value: u8, channel: Option<u8>) -> Result<(), String> {
+    let channel = channel.unwrap_or(0);
+    if controller > 127 || value > 127 || channel > 15 {
+        return Err(format!(
+            "Invalid control change: controller {}, value {}, channel {}",
+            controller, value, channel
+        ));
+    }
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.midi_cc(controller, value, channel);
+    Ok(())
+}
+
+/// Send a 14-bit MIDI pitch bend (0-16383, center 8192) to the loaded plugin
+/// Channel defaults to 0
+#[tauri::command]
+pub fn midi_pitch_bend(value: u16, channel: Option<u8>) -> Result<(), String> {
+    let channel = channel.unwrap_or(0);
+    if value > 16383 || channel > 15 {
+        return Err(format!("Invalid pitch bend: value {}, channel {}", value, channel));
+    }
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.midi_pitch_bend(value, channel);
     Ok(())
 }
 
@@ -1021,6 +2310,56 @@ pub fn midi_all_notes_off() -> Result<(), String> {
     Ok(())
 }
 
+/// Set the keyboard transpose in semitones (-48 to +48)
+/// Held notes are released first so their note-offs aren't sent to the wrong key.
+/// Independent of the pattern player's octave shift.
+#[tauri::command]
+pub fn midi_set_transpose(semitones: i8) -> Result<(), String> {
+    if !(-MAX_MIDI_TRANSPOSE..=MAX_MIDI_TRANSPOSE).contains(&semitones) {
+        return Err(format!(
+            "Transpose must be between -{} and {} semitones",
+            MAX_MIDI_TRANSPOSE, MAX_MIDI_TRANSPOSE
+        ));
+    }
+    if MIDI_TRANSPOSE.swap(semitones, Ordering::Relaxed) != semitones {
+        if let Some(handle) = get_engine_handle() {
+            handle.midi_all_notes_off();
+        }
+    }
+    Ok(())
+}
+
+/// Get the keyboard transpose in semitones
+#[tauri::command]
+pub fn midi_get_transpose() -> i8 {
+    MIDI_TRANSPOSE.load(Ordering::Relaxed)
+}
+
+/// Set the velocity used for note-ons that don't specify one (1-127)
+#[tauri::command]
+pub fn midi_set_default_velocity(velocity: u8) -> Result<(), String> {
+    if !(1..=127).contains(&velocity) {
+        return Err(format!("Velocity must be between 1 and 127, got {}", velocity));
+    }
+    MIDI_DEFAULT_VELOCITY.store(velocity, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Panic: send all notes off and reset the keyboard transpose
+#[tauri::command]
+pub fn midi_panic() -> Result<(), String> {
+    MIDI_TRANSPOSE.store(0, Ordering::Relaxed);
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    handle.midi_all_notes_off();
+    Ok(())
+}
+
+/// Get the notes currently held from any source (on-screen keyboard or MIDI device), sorted
+#[tauri::command]
+pub fn midi_get_active_notes() -> Vec<u8> {
+    crate::audio::midi::activity::active_notes()
+}
+
 /// Set whether the loaded plugin is an instrument (vs effect)
 /// Instrument plugins are processed even when not "playing" for MIDI input
 #[tauri::command]
@@ -1034,7 +2373,8 @@ pub fn set_plugin_is_instrument(is_instrument: bool) -> Result<(), String> {
 // Pattern Playback Commands
 // =============================================================================
 
-use crate::audio::midi::{MidiPlayer, PatternCategory, PatternInfo, list_patterns, get_pattern};
+use crate::audio::ghost_kick::GhostKickPattern;
+use crate::audio::midi::{MidiPlayer, PatternCategory, PatternDef, PatternInfo, list_patterns, get_pattern};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
@@ -1105,6 +2445,20 @@ pub fn pattern_list_by_category(category: PatternCategory) -> Vec<PatternInfo> {
     crate::audio::midi::patterns::get_patterns_by_category(category)
 }
 
+/// Save a user pattern to ~/.config/freqlab/patterns (overwrites one with the same name)
+#[tauri::command]
+pub fn save_user_pattern(name: String, category: PatternCategory, def: PatternDef) -> Result<PatternInfo, String> {
+    let info = crate::audio::midi::patterns::save_user_pattern(&name, category, def)?;
+    log::info!("save_user_pattern: saved {}", info.id);
+    Ok(info)
+}
+
+/// Delete a user pattern by name or ID
+#[tauri::command]
+pub fn delete_user_pattern(name: String) -> Result<(), String> {
+    crate::audio::midi::patterns::delete_user_pattern(&name)
+}
+
 /// Start playing a pattern
 #[tauri::command]
 pub fn pattern_play(
@@ -1137,6 +2491,14 @@ pub fn pattern_play(
 
     let result = player.play(&pattern_id, bpm, octave_shift, looping);
     log::info!("pattern_play: result={:?}", result);
+
+    // Start the ghost kick on the pattern's downbeat
+    if result.is_ok() {
+        if let Some(handle) = get_engine_handle() {
+            handle.set_transport_tempo(player.get_bpm() as f32);
+            handle.sync_ghost_kick(player.get_bpm() as f32, true);
+        }
+    }
     result
 }
 
@@ -1156,6 +2518,10 @@ pub fn pattern_set_bpm(bpm: u32) -> Result<(), String> {
     let player_lock = get_midi_player()?;
     if let Some(player) = player_lock.as_ref() {
         player.set_bpm(bpm);
+        if let Some(handle) = get_engine_handle() {
+            handle.set_transport_tempo(player.get_bpm() as f32);
+            handle.sync_ghost_kick(player.get_bpm() as f32, false);
+        }
     }
     Ok(())
 }
@@ -1170,6 +2536,19 @@ pub fn pattern_set_octave_shift(shift: i8) -> Result<(), String> {
     Ok(())
 }
 
+/// Set pattern swing in percent (0-100): delays every second 16th by up to half a 16th
+#[tauri::command]
+pub fn pattern_set_swing(amount: f32) -> Result<(), String> {
+    if !amount.is_finite() {
+        return Err(format!("Invalid swing amount: {}", amount));
+    }
+    let player_lock = get_midi_player()?;
+    if let Some(player) = player_lock.as_ref() {
+        player.set_swing(amount);
+    }
+    Ok(())
+}
+
 /// Set pattern looping
 #[tauri::command]
 pub fn pattern_set_looping(looping: bool) -> Result<(), String> {
@@ -1190,6 +2569,38 @@ pub fn pattern_is_playing() -> bool {
     }
 }
 
+/// Ghost kick state returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct GhostKickInfo {
+    pub enabled: bool,
+    pub pattern: GhostKickPattern,
+    pub bpm: u32,
+    /// Whether the loaded plugin has a sidechain input to receive the kick
+    pub has_sidechain: bool,
+}
+
+/// Feed a synthetic kick drum into the loaded effect plugin's sidechain input on each
+/// hit of `pattern`, at the pattern player's tempo, to audition sidechain ducking
+#[tauri::command]
+pub fn sidechain_ghost_kick(enabled: bool, pattern: GhostKickPattern) -> Result<GhostKickInfo, String> {
+    let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
+    // Don't create the player just to read its tempo - it defaults to 120 BPM anyway
+    let bpm = MIDI_PLAYER.lock().as_ref().map(|player| player.get_bpm()).unwrap_or(120);
+    handle.set_ghost_kick(enabled, pattern, bpm as f32);
+
+    let has_sidechain = handle.plugin_has_sidechain();
+    if enabled && !has_sidechain {
+        log::warn!("sidechain_ghost_kick: loaded plugin has no sidechain input");
+    }
+
+    Ok(GhostKickInfo {
+        enabled,
+        pattern,
+        bpm,
+        has_sidechain,
+    })
+}
+
 // =============================================================================
 // MIDI File Commands
 // =============================================================================
@@ -1211,6 +2622,11 @@ pub fn midi_file_load(path: String) -> Result<MidiFileInfo, String> {
     // Store the parsed file for playback
     *LOADED_MIDI_FILE.lock() = Some(parsed);
 
+    // Mutes and solos belong to the previous file's tracks
+    if let Some(player) = MIDI_PLAYER.lock().as_ref() {
+        player.reset_track_mix();
+    }
+
     log::info!("midi_file_load: loaded {} tracks", info.tracks.len());
     Ok(info)
 }
@@ -1234,19 +2650,24 @@ pub fn midi_file_unload() {
         if player.get_source() == crate::audio::midi::PlaybackSource::MidiFile {
             player.stop();
         }
+        player.reset_track_mix();
     }
 }
 
-/// Play a track from the loaded MIDI file
+/// Play a track from the loaded MIDI file, or every track when `track_index` is None
+/// Follows the file's embedded tempo map unless `use_tempo_automation` is false,
+/// in which case `bpm` (or the file's initial tempo) is used throughout.
+/// Track mutes and solos apply either way.
 #[tauri::command]
 pub fn midi_file_play(
-    track_index: usize,
+    track_index: Option<usize>,
     bpm: Option<u32>,
     octave_shift: i8,
     looping: bool,
-    use_tempo_automation: bool,
+    use_tempo_automation: Option<bool>,
 ) -> Result<(), String> {
-    log::info!("midi_file_play: track={}, bpm={:?}, octave={}, loop={}, tempo_auto={}",
+    let use_tempo_automation = use_tempo_automation.unwrap_or(true);
+    log::info!("midi_file_play: track={:?}, bpm={:?}, octave={}, loop={}, tempo_auto={}",
         track_index, bpm, octave_shift, looping, use_tempo_automation);
 
     // Get the loaded file
@@ -1254,12 +2675,13 @@ pub fn midi_file_play(
     let parsed = file_lock.as_ref().ok_or("No MIDI file loaded")?;
 
     // Find the track (track_index is the index in our tracks array, not the original MIDI track index)
-    if track_index >= parsed.track_notes.len() {
-        return Err(format!("Track index {} out of range (have {} tracks)", track_index, parsed.track_notes.len()));
-    }
-
-    let notes = parsed.track_notes[track_index].clone();
-    let duration_beats = parsed.tracks[track_index].duration_beats;
+    let (notes, duration_beats) = match track_index {
+        Some(index) if index >= parsed.track_notes.len() => {
+            return Err(format!("Track index {} out of range (have {} tracks)", index, parsed.track_notes.len()));
+        }
+        Some(index) => (parsed.track_notes[index].clone(), parsed.tracks[index].duration_beats),
+        None => (parsed.all_notes(), parsed.duration_beats()),
+    };
     let file_bpm = bpm.unwrap_or(parsed.bpm as u32);
     let tempo_map = parsed.tempo_map.clone();
 
@@ -1284,6 +2706,35 @@ pub fn midi_file_play(
     Ok(())
 }
 
+/// Check a track index against the loaded MIDI file
+fn check_midi_file_track(track: usize) -> Result<(), String> {
+    let file_lock = LOADED_MIDI_FILE.lock();
+    let parsed = file_lock.as_ref().ok_or("No MIDI file loaded")?;
+    if track >= parsed.tracks.len() {
+        return Err(format!("Track index {} out of range (have {} tracks)", track, parsed.tracks.len()));
+    }
+    Ok(())
+}
+
+/// Mute or unmute a track of the loaded MIDI file (takes effect immediately)
+#[tauri::command]
+pub fn midi_file_set_track_mute(track: usize, muted: bool) -> Result<(), String> {
+    check_midi_file_track(track)?;
+    let player_lock = get_midi_player()?;
+    let player = player_lock.as_ref().ok_or("MIDI player not initialized")?;
+    player.set_track_mute(track, muted)
+}
+
+/// Solo or unsolo a track of the loaded MIDI file. While any track is soloed,
+/// only soloed tracks play, muted or not.
+#[tauri::command]
+pub fn midi_file_set_track_solo(track: usize, solo: bool) -> Result<(), String> {
+    check_midi_file_track(track)?;
+    let player_lock = get_midi_player()?;
+    let player = player_lock.as_ref().ok_or("MIDI player not initialized")?;
+    player.set_track_solo(track, solo)
+}
+
 /// Set tempo automation mode for MIDI file playback
 #[tauri::command]
 pub fn midi_file_set_tempo_automation(enabled: bool) -> Result<(), String> {
@@ -1294,6 +2745,12 @@ pub fn midi_file_set_tempo_automation(enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Toggle between the file's embedded tempo map (true) and the manual BPM override (false)
+#[tauri::command]
+pub fn midi_file_use_embedded_tempo(enabled: bool) -> Result<(), String> {
+    midi_file_set_tempo_automation(enabled)
+}
+
 /// Stop MIDI file playback
 #[tauri::command]
 pub fn midi_file_stop() -> Result<(), String> {
@@ -1311,8 +2768,14 @@ pub struct PlaybackPositionInfo {
     pub position: f32,
     /// Total duration in beats
     pub duration: f32,
+    /// Current position in seconds (follows the tempo map when embedded tempo is on)
+    pub position_seconds: f32,
+    /// Total duration in seconds
+    pub duration_seconds: f32,
     /// Whether playback is active
     pub is_playing: bool,
+    /// Active loop region (start, end) in beats
+    pub loop_region: Option<(f32, f32)>,
 }
 
 /// Get current MIDI file playback position
@@ -1321,14 +2784,19 @@ pub fn midi_file_get_position() -> Result<PlaybackPositionInfo, String> {
     let player_lock = get_midi_player()?;
     let player = player_lock.as_ref().ok_or("MIDI player not initialized")?;
 
+    let position = player.get_position();
+    let duration = player.get_duration();
     Ok(PlaybackPositionInfo {
-        position: player.get_position(),
-        duration: player.get_duration(),
+        position,
+        duration,
+        position_seconds: player.beats_to_seconds(position),
+        duration_seconds: player.beats_to_seconds(duration),
         is_playing: player.is_playing(),
+        loop_region: player.get_loop_region(),
     })
 }
 
-/// Seek to a position in the MIDI file
+/// Seek to a position in the MIDI file (clamped into the loop region while it's enabled)
 #[tauri::command]
 pub fn midi_file_seek(position_beats: f32) -> Result<(), String> {
     let player_lock = get_midi_player()?;
@@ -1337,6 +2805,14 @@ pub fn midi_file_seek(position_beats: f32) -> Result<(), String> {
     Ok(())
 }
 
+/// Loop a region of the MIDI file (positions in beats, so tempo changes don't move it)
+#[tauri::command]
+pub fn midi_file_set_loop(start_beats: f32, end_beats: f32, enabled: bool) -> Result<(), String> {
+    let player_lock = get_midi_player()?;
+    let player = player_lock.as_ref().ok_or("MIDI player not initialized")?;
+    player.set_loop_region(start_beats, end_beats, enabled)
+}
+
 // =============================================================================
 // Live MIDI Device Input Commands
 // =============================================================================
@@ -1346,6 +2822,41 @@ use crate::audio::midi::{MidiDeviceInfo, MidiInputManager};
 /// Global MIDI input manager instance
 static MIDI_INPUT_MANAGER: Lazy<MidiInputManager> = Lazy::new(MidiInputManager::new);
 
+/// Global flag to control the MIDI device watcher thread
+static MIDI_DEVICE_WATCHER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Start the MIDI device watcher thread - drops connections to devices that get
+/// unplugged and tells the UI which one went away. Stops once nothing is connected.
+fn start_midi_device_watcher(app_handle: tauri::AppHandle) {
+    // Don't start if already running
+    if MIDI_DEVICE_WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        log::info!("MIDI device watcher thread started");
+
+        loop {
+            while MIDI_INPUT_MANAGER.is_connected() {
+                for device_name in MIDI_INPUT_MANAGER.remove_missing_devices() {
+                    let _ = app_handle.emit("midi-device-disconnected", &device_name);
+                }
+
+                // Enumerating ports is relatively slow, so poll once a second
+                std::thread::sleep(std::time::Duration::from_millis(1000));
+            }
+
+            MIDI_DEVICE_WATCHER_RUNNING.store(false, Ordering::SeqCst);
+            // A device connected while we were stopping won't have started a new watcher
+            if !MIDI_INPUT_MANAGER.is_connected() || MIDI_DEVICE_WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
+                break;
+            }
+        }
+
+        log::info!("MIDI device watcher thread stopped");
+    });
+}
+
 /// Update the MIDI input manager's queue when a plugin is loaded/reloaded
 fn update_midi_input_queue() {
     if MIDI_INPUT_MANAGER.is_connected() {
@@ -1377,33 +2888,53 @@ pub fn midi_device_list() -> Result<Vec<MidiDeviceInfo>, String> {
     MIDI_INPUT_MANAGER.list_devices()
 }
 
-/// Connect to a MIDI input device by index
+/// Connect to a MIDI input device by index (adds to any devices already connected)
 #[tauri::command]
-pub fn midi_device_connect(device_index: usize) -> Result<String, String> {
+pub fn midi_device_connect(app_handle: tauri::AppHandle, device_index: usize) -> Result<String, String> {
     // Get the plugin's MIDI queue
     let handle = get_engine_handle().ok_or_else(|| "Audio engine not initialized".to_string())?;
     let queue = handle.get_plugin_midi_queue()
         .ok_or_else(|| "No plugin loaded - cannot connect MIDI device".to_string())?;
 
-    MIDI_INPUT_MANAGER.connect(device_index, queue)
+    let device_name = MIDI_INPUT_MANAGER.connect(device_index, queue)?;
+    start_midi_device_watcher(app_handle);
+    Ok(device_name)
 }
 
-/// Disconnect from the current MIDI input device
+/// Disconnect one MIDI input device by name, or all of them if no name is given
 #[tauri::command]
-pub fn midi_device_disconnect() {
-    MIDI_INPUT_MANAGER.disconnect();
+pub fn midi_device_disconnect(device_name: Option<String>) -> Result<(), String> {
+    match device_name {
+        Some(name) => MIDI_INPUT_MANAGER.disconnect_device(&name),
+        None => {
+            MIDI_INPUT_MANAGER.disconnect();
+            Ok(())
+        }
+    }
 }
 
-/// Check if connected to a MIDI input device
+/// Check if any MIDI input device is connected
 #[tauri::command]
 pub fn midi_device_is_connected() -> bool {
     MIDI_INPUT_MANAGER.is_connected()
 }
 
-/// Get the name of the connected MIDI device (if any)
+/// Get the names of the connected MIDI devices
+#[tauri::command]
+pub fn midi_device_get_connected() -> Vec<String> {
+    MIDI_INPUT_MANAGER.connected_device_names()
+}
+
+/// Only accept a connected device's channels whose bit is set in `mask` (bit 0 = channel 1)
+#[tauri::command]
+pub fn midi_device_set_channel_filter(device: String, mask: u16) -> Result<(), String> {
+    MIDI_INPUT_MANAGER.set_channel_filter(&device, mask)
+}
+
+/// Remap a connected device's channel `from` to channel `to` (0-15)
 #[tauri::command]
-pub fn midi_device_get_connected() -> Option<String> {
-    MIDI_INPUT_MANAGER.connected_device_name()
+pub fn midi_device_set_channel_remap(device: String, from: u8, to: u8) -> Result<(), String> {
+    MIDI_INPUT_MANAGER.set_channel_remap(&device, from, to)
 }
 
 /// Get the last received MIDI note (for activity indicator)
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::plugin_template::{generate_lib_rs, PluginIdentity, TemplateSpec};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ProjectMeta {
@@ -14,6 +16,20 @@ pub struct ProjectMeta {
     pub created_at: String,
     pub updated_at: String,
     pub path: String,
+    pub thumbnail: Option<String>, // Path to .vstworkshop/thumbnail.png, once captured
+}
+
+/// A deleted project waiting in the workspace trash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrashedProject {
+    pub id: String,           // Folder name inside .trash/
+    pub name: String,         // Project folder name it's restored to
+    #[serde(rename = "displayName")]
+    pub display_name: String, // Name from the project metadata
+    #[serde(rename = "trashedAt")]
+    pub trashed_at: String,
+    #[serde(rename = "hasOutput")]
+    pub has_output: bool,     // The output folder was trashed with it
 }
 
 #[derive(Deserialize)]
@@ -43,10 +59,15 @@ pub fn get_output_path() -> PathBuf {
     get_workspace_path().join("output")
 }
 
-fn get_projects_path() -> PathBuf {
+pub fn get_projects_path() -> PathBuf {
     get_workspace_path().join("projects")
 }
 
+/// Deleted projects are moved here: .trash/{id}/ holds project/, output/ and trashed.json
+pub fn get_trash_path() -> PathBuf {
+    get_workspace_path().join(".trash")
+}
+
 /// Get path to local nih-plug documentation repo
 pub fn get_nih_plug_docs_path() -> PathBuf {
     get_workspace_path().join(".nih-plug-docs")
@@ -100,12 +121,14 @@ pub fn ensure_workspace() -> Result<(), String> {
     // Create or update workspace root Cargo.toml
     let workspace_cargo = workspace.join("Cargo.toml");
     let cargo_content = r#"[workspace]
-members = ["projects/*", "xtask"]
+members = ["projects/*", "xtask", "warmup/*"]
 resolver = "2"
 "#;
     fs::write(&workspace_cargo, cargo_content)
         .map_err(|e| format!("Failed to create workspace Cargo.toml: {}", e))?;
 
+    ensure_warmup_members(&workspace)?;
+
     // Create shared xtask Cargo.toml if it doesn't exist
     let xtask_cargo = workspace.join("xtask/Cargo.toml");
     if !xtask_cargo.exists() {
@@ -162,6 +185,66 @@ xtask = "run --package xtask --release --"
     Ok(())
 }
 
+/// Extra Cargo dependencies a project needs for its UI framework
+/// (shared with the warm-up members so they resolve to the same artifacts)
+fn ui_framework_dependencies(ui_framework: &str) -> &'static str {
+    match ui_framework {
+        "webview" => r#"# Forked nih-plug-webview with Tauri compatibility and hot reload support
+nih_plug_webview = { git = "https://github.com/jamesontucker/nih-plug-webview" }
+serde = { version = "1.0", features = ["derive"] }
+serde_json = "1.0""#,
+        "egui" => r#"nih_plug_egui = { git = "https://github.com/robbert-vdh/nih-plug.git", rev = "28b149ec" }
+egui = "0.24""#,
+        _ => "", // native - no additional deps
+    }
+}
+
+/// UI frameworks that get a warm-up member, in the order they're warmed up
+pub const WARMUP_FRAMEWORKS: [&str; 3] = ["native", "webview", "egui"];
+
+/// Cargo package name of the warm-up member for a UI framework
+pub fn warmup_package_name(ui_framework: &str) -> String {
+    format!("freqlab_warmup_{}", ui_framework)
+}
+
+/// Write the throwaway warm-up members (warmup/<framework>) that pull in the same
+/// dependencies as a new project. Building them fills the shared target dir, so the
+/// first build of a real project only has to compile the plugin itself.
+/// One member per framework, because building them together would unify features
+/// across frameworks and produce artifacts that don't match any single project.
+fn ensure_warmup_members(workspace: &std::path::Path) -> Result<(), String> {
+    for framework in WARMUP_FRAMEWORKS {
+        let member_dir = workspace.join("warmup").join(framework);
+        fs::create_dir_all(member_dir.join("src"))
+            .map_err(|e| format!("Failed to create warm-up dir: {}", e))?;
+
+        // Always rewritten so the dependencies track the project template
+        let cargo_toml = format!(
+            r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+nih_plug = {{ git = "https://github.com/robbert-vdh/nih-plug.git", rev = "28b149ec" }}
+{ui_deps}
+"#,
+            name = warmup_package_name(framework),
+            ui_deps = ui_framework_dependencies(framework)
+        );
+        fs::write(member_dir.join("Cargo.toml"), cargo_toml)
+            .map_err(|e| format!("Failed to write warm-up Cargo.toml: {}", e))?;
+
+        let lib_rs = member_dir.join("src/lib.rs");
+        if !lib_rs.exists() {
+            fs::write(&lib_rs, "// Build cache warm-up only, see freqlab's warmup_build_cache\n")
+                .map_err(|e| format!("Failed to write warm-up lib.rs: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
 /// Validate plugin name (lowercase, no spaces, valid Rust identifier)
 fn validate_name(name: &str) -> Result<(), String> {
     if name.is_empty() {
@@ -179,13 +262,83 @@ fn validate_name(name: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Vendor details checked and escaped for embedding in the generated plugin source
+struct VendorInfo {
+    /// Escaped for use inside a Rust string literal
+    name: String,
+    /// Lowercase ASCII letters and digits of the name, used in the CLAP ID
+    id: String,
+    url: String,
+    email: String,
+}
+
+/// Characters that would break out of (or mangle) a string literal in the generated source
+fn has_unsafe_literal_chars(value: &str) -> bool {
+    value.chars().any(|c| c == '"' || c == '\\' || c.is_control())
+}
+
+/// Validate the vendor name, URL and email (both optional) for a new project
+fn validate_vendor(name: &str, url: &str, email: &str) -> Result<VendorInfo, String> {
+    let name = name.trim();
+    if name.chars().any(|c| c.is_control()) {
+        return Err("Vendor name cannot contain line breaks".to_string());
+    }
+    let id: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    if id.is_empty() {
+        return Err(format!(
+            "Vendor name '{}' needs at least one letter or number (A-Z, 0-9) for the plugin ID",
+            name
+        ));
+    }
+
+    let url = url.trim();
+    if !url.is_empty() {
+        let host = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .map(|rest| rest.split('/').next().unwrap_or(""))
+            .unwrap_or("");
+        if host.is_empty() || url.contains(char::is_whitespace) || has_unsafe_literal_chars(url) {
+            return Err(format!("Invalid vendor URL '{}' (expected e.g. https://example.com)", url));
+        }
+    }
+
+    let email = email.trim();
+    if !email.is_empty() {
+        let valid = match email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+            }
+            None => false,
+        };
+        if !valid || email.contains(char::is_whitespace) || has_unsafe_literal_chars(email) {
+            return Err(format!("Invalid vendor email '{}' (expected e.g. you@example.com)", email));
+        }
+    }
+
+    Ok(VendorInfo {
+        name: name.replace('\\', "\\\\").replace('"', "\\\""),
+        id,
+        url: url.to_string(),
+        email: email.to_string(),
+    })
+}
+
 /// Convert name to valid Rust identifier (snake_case)
 fn to_snake_case(name: &str) -> String {
     name.replace('-', "_")
 }
 
 /// Convert name to PascalCase for struct names
-fn to_pascal_case(name: &str) -> String {
+pub fn to_pascal_case(name: &str) -> String {
     name.split(|c| c == '-' || c == '_')
         .map(|word| {
             let mut chars = word.chars();
@@ -280,6 +433,12 @@ fn generate_project_skills(
 #[tauri::command]
 pub async fn create_project(input: CreateProjectInput) -> Result<ProjectMeta, String> {
     validate_name(&input.name)?;
+    // Check vendor details before anything is written - they end up in the generated source
+    let vendor = validate_vendor(
+        input.vendor_name.as_deref().unwrap_or("freqlab"),
+        input.vendor_url.as_deref().unwrap_or(""),
+        input.vendor_email.as_deref().unwrap_or(""),
+    )?;
     ensure_workspace()?;
 
     let project_path = get_projects_path().join(&input.name);
@@ -287,6 +446,12 @@ pub async fn create_project(input: CreateProjectInput) -> Result<ProjectMeta, St
     if project_path.exists() {
         return Err(format!("Project '{}' already exists", input.name));
     }
+    if read_trash(&get_trash_path()).iter().any(|t| t.name == input.name) {
+        return Err(format!(
+            "A project named '{}' is in the trash - restore it or empty the trash first",
+            input.name
+        ));
+    }
 
     // Create directory structure
     fs::create_dir_all(project_path.join("src"))
@@ -299,15 +464,7 @@ pub async fn create_project(input: CreateProjectInput) -> Result<ProjectMeta, St
     let vst3_id = generate_vst3_id(&input.name);
 
     // Generate dependencies based on UI framework
-    let ui_deps = match input.ui_framework.as_str() {
-        "webview" => r#"# Forked nih-plug-webview with Tauri compatibility and hot reload support
-nih_plug_webview = { git = "https://github.com/jamesontucker/nih-plug-webview" }
-serde = { version = "1.0", features = ["derive"] }
-serde_json = "1.0""#,
-        "egui" => r#"nih_plug_egui = { git = "https://github.com/robbert-vdh/nih-plug.git", rev = "28b149ec" }
-egui = "0.24""#,
-        _ => "", // native - no additional deps
-    };
+    let ui_deps = ui_framework_dependencies(&input.ui_framework);
 
     // Write Cargo.toml (project is a workspace member, no [workspace] section needed)
     let cargo_toml = if ui_deps.is_empty() {
@@ -361,43 +518,22 @@ strip = "symbols"
         .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
 
     // Generate template based on type and UI framework
-    let vendor_name = input.vendor_name.as_deref().unwrap_or("freqlab");
-    let vendor_id: String = vendor_name
-        .to_lowercase()
-        .chars()
-        .filter(|c| c.is_ascii_alphanumeric())
-        .collect();
-    let vendor_url = input.vendor_url.as_deref().unwrap_or("");
-    let vendor_email = input.vendor_email.as_deref().unwrap_or("");
     let description_escaped = input.description.replace('"', "\\\"");
 
     // Select template based on plugin type and UI framework
-    let lib_rs = match (input.template.as_str(), input.ui_framework.as_str()) {
-        ("instrument", "webview") => generate_instrument_webview_template(
-            &pascal_name, &snake_name, &description_escaped, &vst3_id,
-            vendor_name, &vendor_id, vendor_url, vendor_email,
-        ),
-        ("instrument", "egui") => generate_instrument_egui_template(
-            &pascal_name, &snake_name, &description_escaped, &vst3_id,
-            vendor_name, &vendor_id, vendor_url, vendor_email,
-        ),
-        ("instrument", _) => generate_instrument_native_template(
-            &pascal_name, &snake_name, &description_escaped, &vst3_id,
-            vendor_name, &vendor_id, vendor_url, vendor_email,
-        ),
-        ("effect", "webview") => generate_effect_webview_template(
-            &pascal_name, &snake_name, &description_escaped, &vst3_id,
-            vendor_name, &vendor_id, vendor_url, vendor_email,
-        ),
-        ("effect", "egui") => generate_effect_egui_template(
-            &pascal_name, &snake_name, &description_escaped, &vst3_id,
-            vendor_name, &vendor_id, vendor_url, vendor_email,
-        ),
-        _ => generate_effect_native_template(
-            &pascal_name, &snake_name, &description_escaped, &vst3_id,
-            vendor_name, &vendor_id, vendor_url, vendor_email,
-        ),
-    };
+    let lib_rs = generate_lib_rs(
+        TemplateSpec::from_names(&input.template, &input.ui_framework),
+        &PluginIdentity {
+            pascal_name: &pascal_name,
+            snake_name: &snake_name,
+            description: &description_escaped,
+            vst3_id: &vst3_id,
+            vendor_name: &vendor.name,
+            vendor_id: &vendor.id,
+            vendor_url: &vendor.url,
+            vendor_email: &vendor.email,
+        },
+    );
 
     fs::write(project_path.join("src/lib.rs"), lib_rs)
         .map_err(|e| format!("Failed to write lib.rs: {}", e))?;
@@ -431,6 +567,7 @@ strip = "symbols"
         created_at: now.clone(),
         updated_at: now,
         path: project_path.to_string_lossy().to_string(),
+        thumbnail: None,
     };
 
     let metadata_json = serde_json::to_string_pretty(&metadata)
@@ -512,6 +649,7 @@ pub async fn get_project(name: String) -> Result<ProjectMeta, String> {
     Ok(meta)
 }
 
+/// Move a project (and its output folder, output/{name}/) to the workspace trash
 #[tauri::command]
 pub async fn delete_project(name: String) -> Result<(), String> {
     let project_path = get_projects_path().join(&name);
@@ -520,18 +658,124 @@ pub async fn delete_project(name: String) -> Result<(), String> {
         return Err(format!("Project '{}' not found", name));
     }
 
-    // Delete the project source folder
-    fs::remove_dir_all(&project_path)
-        .map_err(|e| format!("Failed to delete project: {}", e))?;
+    let trashed = trash_project(&get_trash_path(), &project_path, &get_output_path().join(&name), &name)?;
+    log::info!("Moved project '{}' to the trash as {}", name, trashed.id);
+    Ok(())
+}
+
+/// Deleted projects that can still be restored, most recently deleted first
+#[tauri::command]
+pub async fn list_trashed_projects() -> Result<Vec<TrashedProject>, String> {
+    Ok(read_trash(&get_trash_path()))
+}
+
+/// Move a trashed project (and its output folder) back into the workspace
+#[tauri::command]
+pub async fn restore_project(id: String) -> Result<ProjectMeta, String> {
+    let name = restore_from_trash(&get_trash_path(), &get_projects_path(), &get_output_path(), &id)?;
+    log::info!("Restored project '{}' from the trash", name);
+    get_project(name).await
+}
+
+/// Permanently delete everything in the trash, returning how many projects were removed
+#[tauri::command]
+pub async fn empty_trash() -> Result<usize, String> {
+    let trash_dir = get_trash_path();
+    let trashed = read_trash(&trash_dir);
+    for entry in &trashed {
+        fs::remove_dir_all(trash_dir.join(&entry.id))
+            .map_err(|e| format!("Failed to delete '{}' from the trash: {}", entry.name, e))?;
+    }
+    Ok(trashed.len())
+}
+
+/// Move `project_path` (and `output_folder`, if it exists) into a new timestamped trash entry
+fn trash_project(trash_dir: &Path, project_path: &Path, output_folder: &Path, name: &str) -> Result<TrashedProject, String> {
+    let now = chrono::Utc::now();
+    let id = format!("{}-{}", now.format("%Y%m%d-%H%M%S-%3f"), name);
+    let entry_dir = trash_dir.join(&id);
+    fs::create_dir_all(&entry_dir).map_err(|e| format!("Failed to create trash folder: {}", e))?;
+
+    // Keep the display name for the trash list - the metadata moves with the project
+    let display_name = fs::read_to_string(project_path.join(".vstworkshop/metadata.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectMeta>(&content).ok())
+        .map(|meta| meta.name)
+        .unwrap_or_else(|| name.to_string());
+
+    if let Err(e) = fs::rename(project_path, entry_dir.join("project")) {
+        let _ = fs::remove_dir(&entry_dir);
+        return Err(format!("Failed to move project to the trash: {}", e));
+    }
 
-    // Also clean up the output folder for this project (output/{name}/)
-    let output_folder = get_output_path().join(&name);
+    // Don't fail if the output folder can't be moved - the project is already in the trash
+    let mut has_output = false;
     if output_folder.exists() {
-        // Don't fail if output cleanup fails - project is already deleted
-        let _ = fs::remove_dir_all(&output_folder);
+        match fs::rename(output_folder, entry_dir.join("output")) {
+            Ok(()) => has_output = true,
+            Err(e) => log::warn!("Failed to move output folder to the trash: {}", e),
+        }
     }
 
-    Ok(())
+    let trashed = TrashedProject {
+        id,
+        name: name.to_string(),
+        display_name,
+        trashed_at: now.to_rfc3339(),
+        has_output,
+    };
+    let json = serde_json::to_string_pretty(&trashed)
+        .map_err(|e| format!("Failed to serialize trash entry: {}", e))?;
+    fs::write(entry_dir.join("trashed.json"), json)
+        .map_err(|e| format!("Failed to write trash entry: {}", e))?;
+
+    Ok(trashed)
+}
+
+/// Entries in the trash, newest first (folders without a readable trashed.json are skipped)
+fn read_trash(trash_dir: &Path) -> Vec<TrashedProject> {
+    let Ok(entries) = fs::read_dir(trash_dir) else {
+        return Vec::new();
+    };
+
+    let mut trashed: Vec<TrashedProject> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join("trashed.json")).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    trashed.sort_by(|a, b| b.trashed_at.cmp(&a.trashed_at));
+    trashed
+}
+
+/// Move trash entry `id` back to `projects_dir` (and its output to `output_dir`), returning
+/// the project's folder name
+fn restore_from_trash(trash_dir: &Path, projects_dir: &Path, output_dir: &Path, id: &str) -> Result<String, String> {
+    let Some(trashed) = read_trash(trash_dir).into_iter().find(|t| t.id == id) else {
+        return Err(format!("'{}' is not in the trash", id));
+    };
+    let entry_dir = trash_dir.join(&trashed.id);
+
+    let project_path = projects_dir.join(&trashed.name);
+    if project_path.exists() {
+        return Err(format!("A project named '{}' already exists", trashed.name));
+    }
+    fs::create_dir_all(projects_dir).map_err(|e| format!("Failed to create projects dir: {}", e))?;
+    fs::rename(entry_dir.join("project"), &project_path)
+        .map_err(|e| format!("Failed to restore project: {}", e))?;
+
+    // A newer build may have recreated the output folder - keep that one
+    let trashed_output = entry_dir.join("output");
+    let output_folder = output_dir.join(&trashed.name);
+    if trashed_output.exists() && !output_folder.exists() {
+        let _ = fs::create_dir_all(output_dir);
+        if let Err(e) = fs::rename(&trashed_output, &output_folder) {
+            log::warn!("Failed to restore output folder: {}", e);
+        }
+    }
+
+    // The project is back - leftovers in the entry aren't worth failing over
+    let _ = fs::remove_dir_all(&entry_dir);
+    Ok(trashed.name)
 }
 
 #[tauri::command]
@@ -572,6 +816,35 @@ pub async fn update_project(
     Ok(meta)
 }
 
+/// Save a PNG thumbnail of the plugin UI into the project and record it in the metadata
+pub fn save_project_thumbnail(project_path: &str, png: &[u8]) -> Result<ProjectMeta, String> {
+    let path = PathBuf::from(project_path);
+    let metadata_path = path.join(".vstworkshop/metadata.json");
+
+    if !metadata_path.exists() {
+        return Err("Project metadata not found".to_string());
+    }
+
+    let thumbnail_path = path.join(".vstworkshop/thumbnail.png");
+    fs::write(&thumbnail_path, png)
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    let content = fs::read_to_string(&metadata_path)
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+    let mut meta: ProjectMeta = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse metadata: {}", e))?;
+
+    // Not an edit to the project itself, so updated_at is left alone
+    meta.thumbnail = Some(thumbnail_path.to_string_lossy().to_string());
+
+    let metadata_json = serde_json::to_string_pretty(&meta)
+        .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+    fs::write(&metadata_path, metadata_json)
+        .map_err(|e| format!("Failed to write metadata: {}", e))?;
+
+    Ok(meta)
+}
+
 #[tauri::command]
 pub async fn open_project_folder(path: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
@@ -601,1087 +874,8 @@ pub async fn get_workspace_path_string() -> String {
     get_workspace_path().to_string_lossy().to_string()
 }
 
-/// Generate a native effect plugin template (no custom UI)
-fn generate_effect_native_template(
-    pascal_name: &str,
-    snake_name: &str,
-    description: &str,
-    vst3_id: &str,
-    vendor_name: &str,
-    vendor_id: &str,
-    vendor_url: &str,
-    vendor_email: &str,
-) -> String {
-    format!(
-        r#"use nih_plug::prelude::*;
-use std::sync::Arc;
-
-/// {description}
-struct {pascal_name} {{
-    params: Arc<{pascal_name}Params>,
-}}
-
-#[derive(Params)]
-struct {pascal_name}Params {{
-    #[id = "gain"]
-    pub gain: FloatParam,
-}}
-
-impl Default for {pascal_name} {{
-    fn default() -> Self {{
-        Self {{
-            params: Arc::new({pascal_name}Params::default()),
-        }}
-    }}
-}}
-
-impl Default for {pascal_name}Params {{
-    fn default() -> Self {{
-        Self {{
-            gain: FloatParam::new(
-                "Gain",
-                util::db_to_gain(0.0),
-                FloatRange::Skewed {{
-                    min: util::db_to_gain(-30.0),
-                    max: util::db_to_gain(30.0),
-                    factor: FloatRange::gain_skew_factor(-30.0, 30.0),
-                }},
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit(" dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
-        }}
-    }}
-}}
-
-impl Plugin for {pascal_name} {{
-    const NAME: &'static str = "{pascal_name}";
-    const VENDOR: &'static str = "{vendor_name}";
-    const URL: &'static str = "{vendor_url}";
-    const EMAIL: &'static str = "{vendor_email}";
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
-        main_input_channels: NonZeroU32::new(2),
-        main_output_channels: NonZeroU32::new(2),
-        ..AudioIOLayout::const_default()
-    }}];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::None;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
-
-    type SysExMessage = ();
-    type BackgroundTask = ();
-
-    fn params(&self) -> Arc<dyn Params> {{
-        self.params.clone()
-    }}
-
-    fn process(
-        &mut self,
-        buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        _context: &mut impl ProcessContext<Self>,
-    ) -> ProcessStatus {{
-        for channel_samples in buffer.iter_samples() {{
-            let gain = self.params.gain.smoothed.next();
-            for sample in channel_samples {{
-                *sample *= gain;
-                // Protect against NaN/Inf (can crash DAWs)
-                if !sample.is_finite() {{
-                    *sample = 0.0;
-                }}
-            }}
-        }}
-        ProcessStatus::Normal
-    }}
-}}
-
-impl ClapPlugin for {pascal_name} {{
-    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::AudioEffect, ClapFeature::Stereo];
-}}
-
-impl Vst3Plugin for {pascal_name} {{
-    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[Vst3SubCategory::Fx];
-}}
-
-nih_export_clap!({pascal_name});
-nih_export_vst3!({pascal_name});
-"#,
-        pascal_name = pascal_name,
-        snake_name = snake_name,
-        description = description,
-        vst3_id = vst3_id,
-        vendor_name = vendor_name,
-        vendor_id = vendor_id,
-        vendor_url = vendor_url,
-        vendor_email = vendor_email
-    )
-}
-
-/// Generate a native instrument plugin template (no custom UI)
-fn generate_instrument_native_template(
-    pascal_name: &str,
-    snake_name: &str,
-    description: &str,
-    vst3_id: &str,
-    vendor_name: &str,
-    vendor_id: &str,
-    vendor_url: &str,
-    vendor_email: &str,
-) -> String {
-    format!(
-        r#"use nih_plug::prelude::*;
-use std::sync::Arc;
-
-/// {description}
-struct {pascal_name} {{
-    params: Arc<{pascal_name}Params>,
-    sample_rate: f32,
-    /// Current phase of the oscillator (0.0 to 1.0)
-    phase: f32,
-    /// Current note frequency (0 if no note playing)
-    note_freq: f32,
-    /// Current note velocity (0.0 to 1.0)
-    velocity: f32,
-}}
-
-#[derive(Params)]
-struct {pascal_name}Params {{
-    #[id = "gain"]
-    pub gain: FloatParam,
-}}
-
-impl Default for {pascal_name} {{
-    fn default() -> Self {{
-        Self {{
-            params: Arc::new({pascal_name}Params::default()),
-            sample_rate: 44100.0,
-            phase: 0.0,
-            note_freq: 0.0,
-            velocity: 0.0,
-        }}
-    }}
-}}
-
-impl Default for {pascal_name}Params {{
-    fn default() -> Self {{
-        Self {{
-            gain: FloatParam::new(
-                "Gain",
-                util::db_to_gain(-6.0),
-                FloatRange::Skewed {{
-                    min: util::db_to_gain(-30.0),
-                    max: util::db_to_gain(6.0),
-                    factor: FloatRange::gain_skew_factor(-30.0, 6.0),
-                }},
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit(" dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
-        }}
-    }}
-}}
-
-impl {pascal_name} {{
-    /// Convert MIDI note number to frequency in Hz
-    fn midi_note_to_freq(note: u8) -> f32 {{
-        440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
-    }}
-}}
-
-impl Plugin for {pascal_name} {{
-    const NAME: &'static str = "{pascal_name}";
-    const VENDOR: &'static str = "{vendor_name}";
-    const URL: &'static str = "{vendor_url}";
-    const EMAIL: &'static str = "{vendor_email}";
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-
-    // Instrument: no audio input, stereo output
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
-        main_input_channels: None,
-        main_output_channels: NonZeroU32::new(2),
-        ..AudioIOLayout::const_default()
-    }}];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::Basic;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
-
-    type SysExMessage = ();
-    type BackgroundTask = ();
-
-    fn params(&self) -> Arc<dyn Params> {{
-        self.params.clone()
-    }}
-
-    fn initialize(
-        &mut self,
-        _audio_io_layout: &AudioIOLayout,
-        buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
-    ) -> bool {{
-        self.sample_rate = buffer_config.sample_rate;
-        true
-    }}
-
-    fn process(
-        &mut self,
-        buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        context: &mut impl ProcessContext<Self>,
-    ) -> ProcessStatus {{
-        // Process MIDI events
-        while let Some(event) = context.next_event() {{
-            match event {{
-                NoteEvent::NoteOn {{ note, velocity, .. }} => {{
-                    self.note_freq = Self::midi_note_to_freq(note);
-                    self.velocity = velocity;
-                }}
-                NoteEvent::NoteOff {{ note, .. }} => {{
-                    // Only stop if it's the same note
-                    if Self::midi_note_to_freq(note) == self.note_freq {{
-                        self.note_freq = 0.0;
-                        self.velocity = 0.0;
-                    }}
-                }}
-                _ => {{}}
-            }}
-        }}
-
-        // Generate audio
-        let gain = self.params.gain.smoothed.next();
-        let phase_delta = self.note_freq / self.sample_rate;
-
-        for channel_samples in buffer.iter_samples() {{
-            // Simple sine wave oscillator
-            let sample = if self.note_freq > 0.0 {{
-                let sine = (self.phase * std::f32::consts::TAU).sin();
-                self.phase = (self.phase + phase_delta) % 1.0;
-                sine * self.velocity * gain
-            }} else {{
-                0.0
-            }};
-
-            for output_sample in channel_samples {{
-                // Protect against NaN/Inf (can crash DAWs)
-                *output_sample = if sample.is_finite() {{ sample }} else {{ 0.0 }};
-            }}
-        }}
-
-        ProcessStatus::Normal
-    }}
-}}
-
-impl ClapPlugin for {pascal_name} {{
-    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::Instrument, ClapFeature::Synthesizer, ClapFeature::Stereo];
-}}
-
-impl Vst3Plugin for {pascal_name} {{
-    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[Vst3SubCategory::Synth, Vst3SubCategory::Instrument];
-}}
-
-nih_export_clap!({pascal_name});
-nih_export_vst3!({pascal_name});
-"#,
-        pascal_name = pascal_name,
-        snake_name = snake_name,
-        description = description,
-        vst3_id = vst3_id,
-        vendor_name = vendor_name,
-        vendor_id = vendor_id,
-        vendor_url = vendor_url,
-        vendor_email = vendor_email
-    )
-}
-
-/// Generate an effect plugin template with WebView UI
-fn generate_effect_webview_template(
-    pascal_name: &str,
-    snake_name: &str,
-    description: &str,
-    vst3_id: &str,
-    vendor_name: &str,
-    vendor_id: &str,
-    vendor_url: &str,
-    vendor_email: &str,
-) -> String {
-    format!(
-        r#"use nih_plug::prelude::*;
-use nih_plug_webview::{{WebViewEditor, HTMLSource}};
-use serde::Deserialize;
-use serde_json::json;
-use std::sync::Arc;
-use std::sync::atomic::{{AtomicBool, Ordering}};
-
-/// Messages from the WebView UI
-#[derive(Deserialize)]
-#[serde(tag = "type")]
-enum UIMessage {{
-    Init,
-    SetGain {{ value: f32 }},
-}}
-
-/// {description}
-struct {pascal_name} {{
-    params: Arc<{pascal_name}Params>,
-}}
-
-#[derive(Params)]
-struct {pascal_name}Params {{
-    #[id = "gain"]
-    pub gain: FloatParam,
-    /// Flag to notify UI when gain changes from host automation
-    #[persist = "gain-dirty"]
-    gain_changed: Arc<AtomicBool>,
-}}
-
-impl Default for {pascal_name} {{
-    fn default() -> Self {{
-        Self {{
-            params: Arc::new({pascal_name}Params::default()),
-        }}
-    }}
-}}
-
-impl Default for {pascal_name}Params {{
-    fn default() -> Self {{
-        let gain_changed = Arc::new(AtomicBool::new(false));
-        let gain_changed_clone = gain_changed.clone();
-
-        Self {{
-            gain: FloatParam::new(
-                "Gain",
-                util::db_to_gain(0.0),
-                FloatRange::Skewed {{
-                    min: util::db_to_gain(-30.0),
-                    max: util::db_to_gain(30.0),
-                    factor: FloatRange::gain_skew_factor(-30.0, 30.0),
-                }},
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit(" dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db())
-            .with_callback(Arc::new(move |_| {{
-                gain_changed_clone.store(true, Ordering::Relaxed);
-            }})),
-            gain_changed,
-        }}
-    }}
-}}
-
-impl Plugin for {pascal_name} {{
-    const NAME: &'static str = "{pascal_name}";
-    const VENDOR: &'static str = "{vendor_name}";
-    const URL: &'static str = "{vendor_url}";
-    const EMAIL: &'static str = "{vendor_email}";
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
-        main_input_channels: NonZeroU32::new(2),
-        main_output_channels: NonZeroU32::new(2),
-        ..AudioIOLayout::const_default()
-    }}];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::None;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
-
-    type SysExMessage = ();
-    type BackgroundTask = ();
-
-    fn params(&self) -> Arc<dyn Params> {{
-        self.params.clone()
-    }}
-
-    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {{
-        let params = self.params.clone();
-        let gain_changed = self.params.gain_changed.clone();
-
-        let editor = WebViewEditor::new(HTMLSource::String(include_str!("ui.html")), (400, 300))
-            .with_background_color((26, 26, 46, 255))
-            .with_developer_mode(true)
-            .with_event_loop(move |ctx, setter, _window| {{
-                // Handle messages from WebView
-                while let Ok(msg) = ctx.next_event() {{
-                    if let Ok(ui_msg) = serde_json::from_value::<UIMessage>(msg) {{
-                        match ui_msg {{
-                            UIMessage::Init => {{
-                                // Send initial state to UI
-                                ctx.send_json(json!({{
-                                    "type": "param_change",
-                                    "param": "gain",
-                                    "value": params.gain.unmodulated_normalized_value(),
-                                    "text": params.gain.to_string()
-                                }}));
-                            }}
-                            UIMessage::SetGain {{ value }} => {{
-                                setter.begin_set_parameter(&params.gain);
-                                setter.set_parameter_normalized(&params.gain, value);
-                                setter.end_set_parameter(&params.gain);
-                            }}
-                        }}
-                    }}
-                }}
-
-                // Sync UI when parameter changes from host automation
-                if gain_changed.swap(false, Ordering::Relaxed) {{
-                    ctx.send_json(json!({{
-                        "type": "param_change",
-                        "param": "gain",
-                        "value": params.gain.unmodulated_normalized_value(),
-                        "text": params.gain.to_string()
-                    }}));
-                }}
-            }});
-
-        Some(Box::new(editor))
-    }}
-
-    fn process(
-        &mut self,
-        buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        _context: &mut impl ProcessContext<Self>,
-    ) -> ProcessStatus {{
-        for channel_samples in buffer.iter_samples() {{
-            let gain = self.params.gain.smoothed.next();
-            for sample in channel_samples {{
-                *sample *= gain;
-                // Protect against NaN/Inf (can crash DAWs)
-                if !sample.is_finite() {{
-                    *sample = 0.0;
-                }}
-            }}
-        }}
-        ProcessStatus::Normal
-    }}
-}}
-
-impl ClapPlugin for {pascal_name} {{
-    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::AudioEffect, ClapFeature::Stereo];
-}}
-
-impl Vst3Plugin for {pascal_name} {{
-    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[Vst3SubCategory::Fx];
-}}
-
-nih_export_clap!({pascal_name});
-nih_export_vst3!({pascal_name});
-"#,
-        pascal_name = pascal_name,
-        snake_name = snake_name,
-        description = description,
-        vst3_id = vst3_id,
-        vendor_name = vendor_name,
-        vendor_id = vendor_id,
-        vendor_url = vendor_url,
-        vendor_email = vendor_email
-    )
-}
-
-/// Generate an effect plugin template with egui UI
-fn generate_effect_egui_template(
-    pascal_name: &str,
-    snake_name: &str,
-    description: &str,
-    vst3_id: &str,
-    vendor_name: &str,
-    vendor_id: &str,
-    vendor_url: &str,
-    vendor_email: &str,
-) -> String {
-    format!(
-        r#"use nih_plug::prelude::*;
-use nih_plug_egui::{{create_egui_editor, egui, widgets, EguiState}};
-use std::sync::Arc;
-
-/// {description}
-struct {pascal_name} {{
-    params: Arc<{pascal_name}Params>,
-}}
-
-#[derive(Params)]
-struct {pascal_name}Params {{
-    #[persist = "editor-state"]
-    editor_state: Arc<EguiState>,
-
-    #[id = "gain"]
-    pub gain: FloatParam,
-}}
-
-impl Default for {pascal_name} {{
-    fn default() -> Self {{
-        Self {{
-            params: Arc::new({pascal_name}Params::default()),
-        }}
-    }}
-}}
-
-impl Default for {pascal_name}Params {{
-    fn default() -> Self {{
-        Self {{
-            editor_state: EguiState::from_size(400, 300),
-            gain: FloatParam::new(
-                "Gain",
-                util::db_to_gain(0.0),
-                FloatRange::Skewed {{
-                    min: util::db_to_gain(-30.0),
-                    max: util::db_to_gain(30.0),
-                    factor: FloatRange::gain_skew_factor(-30.0, 30.0),
-                }},
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit(" dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
-        }}
-    }}
-}}
-
-impl Plugin for {pascal_name} {{
-    const NAME: &'static str = "{pascal_name}";
-    const VENDOR: &'static str = "{vendor_name}";
-    const URL: &'static str = "{vendor_url}";
-    const EMAIL: &'static str = "{vendor_email}";
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
-        main_input_channels: NonZeroU32::new(2),
-        main_output_channels: NonZeroU32::new(2),
-        ..AudioIOLayout::const_default()
-    }}];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::None;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
-
-    type SysExMessage = ();
-    type BackgroundTask = ();
-
-    fn params(&self) -> Arc<dyn Params> {{
-        self.params.clone()
-    }}
-
-    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {{
-        let params = self.params.clone();
-        create_egui_editor(
-            self.params.editor_state.clone(),
-            (),
-            |_, _| {{}},
-            move |egui_ctx, setter, _state| {{
-                egui::CentralPanel::default().show(egui_ctx, |ui| {{
-                    ui.heading("{pascal_name}");
-                    ui.add_space(10.0);
-
-                    ui.label("Gain");
-                    ui.add(widgets::ParamSlider::for_param(&params.gain, setter));
-                }});
-            }},
-        )
-    }}
-
-    fn process(
-        &mut self,
-        buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        _context: &mut impl ProcessContext<Self>,
-    ) -> ProcessStatus {{
-        for channel_samples in buffer.iter_samples() {{
-            let gain = self.params.gain.smoothed.next();
-            for sample in channel_samples {{
-                *sample *= gain;
-                // Protect against NaN/Inf (can crash DAWs)
-                if !sample.is_finite() {{
-                    *sample = 0.0;
-                }}
-            }}
-        }}
-        ProcessStatus::Normal
-    }}
-}}
-
-impl ClapPlugin for {pascal_name} {{
-    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::AudioEffect, ClapFeature::Stereo];
-}}
-
-impl Vst3Plugin for {pascal_name} {{
-    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[Vst3SubCategory::Fx];
-}}
-
-nih_export_clap!({pascal_name});
-nih_export_vst3!({pascal_name});
-"#,
-        pascal_name = pascal_name,
-        snake_name = snake_name,
-        description = description,
-        vst3_id = vst3_id,
-        vendor_name = vendor_name,
-        vendor_id = vendor_id,
-        vendor_url = vendor_url,
-        vendor_email = vendor_email
-    )
-}
-
-/// Generate an instrument plugin template with WebView UI
-fn generate_instrument_webview_template(
-    pascal_name: &str,
-    snake_name: &str,
-    description: &str,
-    vst3_id: &str,
-    vendor_name: &str,
-    vendor_id: &str,
-    vendor_url: &str,
-    vendor_email: &str,
-) -> String {
-    format!(
-        r#"use nih_plug::prelude::*;
-use nih_plug_webview::{{WebViewEditor, HTMLSource}};
-use serde::Deserialize;
-use serde_json::json;
-use std::sync::Arc;
-use std::sync::atomic::{{AtomicBool, Ordering}};
-
-/// Messages from the WebView UI
-#[derive(Deserialize)]
-#[serde(tag = "type")]
-enum UIMessage {{
-    Init,
-    SetGain {{ value: f32 }},
-}}
-
-/// {description}
-struct {pascal_name} {{
-    params: Arc<{pascal_name}Params>,
-    sample_rate: f32,
-    phase: f32,
-    note_freq: f32,
-    velocity: f32,
-}}
-
-#[derive(Params)]
-struct {pascal_name}Params {{
-    #[id = "gain"]
-    pub gain: FloatParam,
-    /// Flag to notify UI when gain changes from host automation
-    #[persist = "gain-dirty"]
-    gain_changed: Arc<AtomicBool>,
-}}
-
-impl Default for {pascal_name} {{
-    fn default() -> Self {{
-        Self {{
-            params: Arc::new({pascal_name}Params::default()),
-            sample_rate: 44100.0,
-            phase: 0.0,
-            note_freq: 0.0,
-            velocity: 0.0,
-        }}
-    }}
-}}
-
-impl Default for {pascal_name}Params {{
-    fn default() -> Self {{
-        let gain_changed = Arc::new(AtomicBool::new(false));
-        let gain_changed_clone = gain_changed.clone();
-
-        Self {{
-            gain: FloatParam::new(
-                "Gain",
-                util::db_to_gain(-6.0),
-                FloatRange::Skewed {{
-                    min: util::db_to_gain(-30.0),
-                    max: util::db_to_gain(6.0),
-                    factor: FloatRange::gain_skew_factor(-30.0, 6.0),
-                }},
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit(" dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db())
-            .with_callback(Arc::new(move |_| {{
-                gain_changed_clone.store(true, Ordering::Relaxed);
-            }})),
-            gain_changed,
-        }}
-    }}
-}}
-
-impl {pascal_name} {{
-    fn midi_note_to_freq(note: u8) -> f32 {{
-        440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
-    }}
-}}
-
-impl Plugin for {pascal_name} {{
-    const NAME: &'static str = "{pascal_name}";
-    const VENDOR: &'static str = "{vendor_name}";
-    const URL: &'static str = "{vendor_url}";
-    const EMAIL: &'static str = "{vendor_email}";
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
-        main_input_channels: None,
-        main_output_channels: NonZeroU32::new(2),
-        ..AudioIOLayout::const_default()
-    }}];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::Basic;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
-
-    type SysExMessage = ();
-    type BackgroundTask = ();
-
-    fn params(&self) -> Arc<dyn Params> {{
-        self.params.clone()
-    }}
-
-    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {{
-        let params = self.params.clone();
-        let gain_changed = self.params.gain_changed.clone();
-
-        let editor = WebViewEditor::new(HTMLSource::String(include_str!("ui.html")), (400, 300))
-            .with_background_color((26, 26, 46, 255))
-            .with_developer_mode(true)
-            .with_event_loop(move |ctx, setter, _window| {{
-                // Handle messages from WebView
-                while let Ok(msg) = ctx.next_event() {{
-                    if let Ok(ui_msg) = serde_json::from_value::<UIMessage>(msg) {{
-                        match ui_msg {{
-                            UIMessage::Init => {{
-                                // Send initial state to UI
-                                ctx.send_json(json!({{
-                                    "type": "param_change",
-                                    "param": "gain",
-                                    "value": params.gain.unmodulated_normalized_value(),
-                                    "text": params.gain.to_string()
-                                }}));
-                            }}
-                            UIMessage::SetGain {{ value }} => {{
-                                setter.begin_set_parameter(&params.gain);
-                                setter.set_parameter_normalized(&params.gain, value);
-                                setter.end_set_parameter(&params.gain);
-                            }}
-                        }}
-                    }}
-                }}
-
-                // Sync UI when parameter changes from host automation
-                if gain_changed.swap(false, Ordering::Relaxed) {{
-                    ctx.send_json(json!({{
-                        "type": "param_change",
-                        "param": "gain",
-                        "value": params.gain.unmodulated_normalized_value(),
-                        "text": params.gain.to_string()
-                    }}));
-                }}
-            }});
-
-        Some(Box::new(editor))
-    }}
-
-    fn initialize(
-        &mut self,
-        _audio_io_layout: &AudioIOLayout,
-        buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
-    ) -> bool {{
-        self.sample_rate = buffer_config.sample_rate;
-        true
-    }}
-
-    fn process(
-        &mut self,
-        buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        context: &mut impl ProcessContext<Self>,
-    ) -> ProcessStatus {{
-        // Process MIDI events
-        while let Some(event) = context.next_event() {{
-            match event {{
-                NoteEvent::NoteOn {{ note, velocity, .. }} => {{
-                    self.note_freq = Self::midi_note_to_freq(note);
-                    self.velocity = velocity;
-                }}
-                NoteEvent::NoteOff {{ note, .. }} => {{
-                    if Self::midi_note_to_freq(note) == self.note_freq {{
-                        self.note_freq = 0.0;
-                        self.velocity = 0.0;
-                    }}
-                }}
-                _ => {{}}
-            }}
-        }}
-
-        // Generate audio
-        let gain = self.params.gain.smoothed.next();
-        let phase_delta = self.note_freq / self.sample_rate;
-
-        for channel_samples in buffer.iter_samples() {{
-            let sample = if self.note_freq > 0.0 {{
-                let sine = (self.phase * std::f32::consts::TAU).sin();
-                self.phase = (self.phase + phase_delta) % 1.0;
-                sine * self.velocity * gain
-            }} else {{
-                0.0
-            }};
-
-            for output_sample in channel_samples {{
-                // Protect against NaN/Inf (can crash DAWs)
-                *output_sample = if sample.is_finite() {{ sample }} else {{ 0.0 }};
-            }}
-        }}
-
-        ProcessStatus::Normal
-    }}
-}}
-
-impl ClapPlugin for {pascal_name} {{
-    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::Instrument, ClapFeature::Synthesizer, ClapFeature::Stereo];
-}}
-
-impl Vst3Plugin for {pascal_name} {{
-    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[Vst3SubCategory::Synth, Vst3SubCategory::Instrument];
-}}
-
-nih_export_clap!({pascal_name});
-nih_export_vst3!({pascal_name});
-"#,
-        pascal_name = pascal_name,
-        snake_name = snake_name,
-        description = description,
-        vst3_id = vst3_id,
-        vendor_name = vendor_name,
-        vendor_id = vendor_id,
-        vendor_url = vendor_url,
-        vendor_email = vendor_email
-    )
-}
-
-/// Generate an instrument plugin template with egui UI
-fn generate_instrument_egui_template(
-    pascal_name: &str,
-    snake_name: &str,
-    description: &str,
-    vst3_id: &str,
-    vendor_name: &str,
-    vendor_id: &str,
-    vendor_url: &str,
-    vendor_email: &str,
-) -> String {
-    format!(
-        r#"use nih_plug::prelude::*;
-use nih_plug_egui::{{create_egui_editor, egui, widgets, EguiState}};
-use std::sync::Arc;
-
-/// {description}
-struct {pascal_name} {{
-    params: Arc<{pascal_name}Params>,
-    sample_rate: f32,
-    phase: f32,
-    note_freq: f32,
-    velocity: f32,
-}}
-
-#[derive(Params)]
-struct {pascal_name}Params {{
-    #[persist = "editor-state"]
-    editor_state: Arc<EguiState>,
-
-    #[id = "gain"]
-    pub gain: FloatParam,
-}}
-
-impl Default for {pascal_name} {{
-    fn default() -> Self {{
-        Self {{
-            params: Arc::new({pascal_name}Params::default()),
-            sample_rate: 44100.0,
-            phase: 0.0,
-            note_freq: 0.0,
-            velocity: 0.0,
-        }}
-    }}
-}}
-
-impl Default for {pascal_name}Params {{
-    fn default() -> Self {{
-        Self {{
-            editor_state: EguiState::from_size(400, 300),
-            gain: FloatParam::new(
-                "Gain",
-                util::db_to_gain(-6.0),
-                FloatRange::Skewed {{
-                    min: util::db_to_gain(-30.0),
-                    max: util::db_to_gain(6.0),
-                    factor: FloatRange::gain_skew_factor(-30.0, 6.0),
-                }},
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit(" dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
-        }}
-    }}
-}}
-
-impl {pascal_name} {{
-    fn midi_note_to_freq(note: u8) -> f32 {{
-        440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
-    }}
-}}
-
-impl Plugin for {pascal_name} {{
-    const NAME: &'static str = "{pascal_name}";
-    const VENDOR: &'static str = "{vendor_name}";
-    const URL: &'static str = "{vendor_url}";
-    const EMAIL: &'static str = "{vendor_email}";
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {{
-        main_input_channels: None,
-        main_output_channels: NonZeroU32::new(2),
-        ..AudioIOLayout::const_default()
-    }}];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::Basic;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
-
-    type SysExMessage = ();
-    type BackgroundTask = ();
-
-    fn params(&self) -> Arc<dyn Params> {{
-        self.params.clone()
-    }}
-
-    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {{
-        let params = self.params.clone();
-        create_egui_editor(
-            self.params.editor_state.clone(),
-            (),
-            |_, _| {{}},
-            move |egui_ctx, setter, _state| {{
-                egui::CentralPanel::default().show(egui_ctx, |ui| {{
-                    ui.heading("{pascal_name}");
-                    ui.add_space(10.0);
-
-                    ui.label("Gain");
-                    ui.add(widgets::ParamSlider::for_param(&params.gain, setter));
-                }});
-            }},
-        )
-    }}
-
-    fn initialize(
-        &mut self,
-        _audio_io_layout: &AudioIOLayout,
-        buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
-    ) -> bool {{
-        self.sample_rate = buffer_config.sample_rate;
-        true
-    }}
-
-    fn process(
-        &mut self,
-        buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        context: &mut impl ProcessContext<Self>,
-    ) -> ProcessStatus {{
-        // Process MIDI events
-        while let Some(event) = context.next_event() {{
-            match event {{
-                NoteEvent::NoteOn {{ note, velocity, .. }} => {{
-                    self.note_freq = Self::midi_note_to_freq(note);
-                    self.velocity = velocity;
-                }}
-                NoteEvent::NoteOff {{ note, .. }} => {{
-                    if Self::midi_note_to_freq(note) == self.note_freq {{
-                        self.note_freq = 0.0;
-                        self.velocity = 0.0;
-                    }}
-                }}
-                _ => {{}}
-            }}
-        }}
-
-        // Generate audio
-        let gain = self.params.gain.smoothed.next();
-        let phase_delta = self.note_freq / self.sample_rate;
-
-        for channel_samples in buffer.iter_samples() {{
-            let sample = if self.note_freq > 0.0 {{
-                let sine = (self.phase * std::f32::consts::TAU).sin();
-                self.phase = (self.phase + phase_delta) % 1.0;
-                sine * self.velocity * gain
-            }} else {{
-                0.0
-            }};
-
-            for output_sample in channel_samples {{
-                // Protect against NaN/Inf (can crash DAWs)
-                *output_sample = if sample.is_finite() {{ sample }} else {{ 0.0 }};
-            }}
-        }}
-
-        ProcessStatus::Normal
-    }}
-}}
-
-impl ClapPlugin for {pascal_name} {{
-    const CLAP_ID: &'static str = "com.{vendor_id}.{snake_name}";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("{description}");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::Instrument, ClapFeature::Synthesizer, ClapFeature::Stereo];
-}}
-
-impl Vst3Plugin for {pascal_name} {{
-    const VST3_CLASS_ID: [u8; 16] = *b"{vst3_id}";
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[Vst3SubCategory::Synth, Vst3SubCategory::Instrument];
-}}
-
-nih_export_clap!({pascal_name});
-nih_export_vst3!({pascal_name});
-"#,
-        pascal_name = pascal_name,
-        snake_name = snake_name,
-        description = description,
-        vst3_id = vst3_id,
-        vendor_name = vendor_name,
-        vendor_id = vendor_id,
-        vendor_url = vendor_url,
-        vendor_email = vendor_email
-    )
-}
-
 /// Generate the HTML file for WebView UI
-fn generate_webview_ui_html(pascal_name: &str) -> String {
+pub fn generate_webview_ui_html(pascal_name: &str) -> String {
     format!(
         r##"<!DOCTYPE html>
 <html lang="en">
@@ -1768,6 +962,26 @@ fn generate_webview_ui_html(pascal_name: &str) -> String {
             }}
         }}
 
+        // Ask the host to resize the editor window (e.g. compact/expanded layouts).
+        // 'Resize' is a reserved message type: the plugin clamps the size and replies
+        // with {{ type: 'resized', width, height }}.
+        function requestResize(width, height) {{
+            sendToPlugin({{ type: 'Resize', width: Math.round(width), height: Math.round(height) }});
+        }}
+
+        // Many DAWs swallow keystrokes meant for plugin windows. 'SetKeyboardFocus' is a
+        // reserved message type: while a text field has focus the plugin captures all keys.
+        function isTextInput(el) {{
+            return el && (el.tagName === 'TEXTAREA' || el.isContentEditable ||
+                (el.tagName === 'INPUT' && !['range', 'checkbox', 'radio', 'button'].includes(el.type)));
+        }}
+        document.addEventListener('focusin', (e) => {{
+            if (isTextInput(e.target)) sendToPlugin({{ type: 'SetKeyboardFocus', focused: true }});
+        }});
+        document.addEventListener('focusout', (e) => {{
+            if (isTextInput(e.target)) sendToPlugin({{ type: 'SetKeyboardFocus', focused: false }});
+        }});
+
         // Gain control - uses normalized value (0-1)
         const gainSlider = document.getElementById('gain');
         const gainValue = document.getElementById('gain-value');
@@ -1782,14 +996,24 @@ fn generate_webview_ui_html(pascal_name: &str) -> String {
             sendToPlugin({{ type: 'SetGain', value: normalized }});
         }});
 
-        // Handle messages from the plugin
-        window.onPluginMessage = function(msg) {{
-            if (msg.type === 'param_change' && msg.param === 'gain') {{
+        // Apply one parameter update: {{ param, value, text }}
+        function applyParamChange(update) {{
+            if (update.param === 'gain') {{
                 updatingFromPlugin = true;
-                gainSlider.value = msg.value;
-                gainValue.textContent = msg.text;
+                gainSlider.value = update.value;
+                gainValue.textContent = update.text;
                 updatingFromPlugin = false;
             }}
+        }}
+
+        // Handle messages from the plugin
+        // Host-side changes arrive batched: {{ type: 'param_batch', updates: [{{ param, value, text }}, ...] }}
+        window.onPluginMessage = function(msg) {{
+            if (msg.type === 'param_change') {{
+                applyParamChange(msg);
+            }} else if (msg.type === 'param_batch') {{
+                msg.updates.forEach(applyParamChange);
+            }}
         }};
 
         // Request initial state when loaded
@@ -1803,3 +1027,99 @@ fn generate_webview_ui_html(pascal_name: &str) -> String {
         pascal_name = pascal_name
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clap_id_for(vendor_name: &str) -> String {
+        let vendor = validate_vendor(vendor_name, "", "").unwrap();
+        let lib_rs = generate_lib_rs(
+            TemplateSpec::from_names("effect", "native"),
+            &PluginIdentity {
+                pascal_name: "MyPlugin",
+                snake_name: "my_plugin",
+                description: "",
+                vst3_id: &generate_vst3_id("my_plugin"),
+                vendor_name: &vendor.name,
+                vendor_id: &vendor.id,
+                vendor_url: &vendor.url,
+                vendor_email: &vendor.email,
+            },
+        );
+        let line = lib_rs.lines().find(|l| l.contains("CLAP_ID")).unwrap();
+        line.split('"').nth(1).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_trash_and_restore_project() {
+        let root = std::env::temp_dir().join(format!("freqlab_trash_test_{}", std::process::id()));
+        let (projects, output, trash) = (root.join("projects"), root.join("output"), root.join(".trash"));
+        fs::create_dir_all(projects.join("my_plugin/src")).unwrap();
+        fs::write(projects.join("my_plugin/src/lib.rs"), "// plugin").unwrap();
+        fs::create_dir_all(output.join("my_plugin")).unwrap();
+
+        let trashed =
+            trash_project(&trash, &projects.join("my_plugin"), &output.join("my_plugin"), "my_plugin").unwrap();
+        assert!(!projects.join("my_plugin").exists());
+        assert!(!output.join("my_plugin").exists());
+        assert!(trashed.has_output);
+        // No metadata - the folder name is shown instead
+        assert_eq!(trashed.display_name, "my_plugin");
+        assert_eq!(read_trash(&trash), vec![trashed.clone()]);
+
+        // Restoring refuses to overwrite a project created since
+        fs::create_dir_all(projects.join("my_plugin")).unwrap();
+        assert!(restore_from_trash(&trash, &projects, &output, &trashed.id).is_err());
+        fs::remove_dir(projects.join("my_plugin")).unwrap();
+
+        assert!(restore_from_trash(&trash, &projects, &output, "../projects").is_err());
+        assert_eq!(restore_from_trash(&trash, &projects, &output, &trashed.id).unwrap(), "my_plugin");
+        let restored = fs::read_to_string(projects.join("my_plugin/src/lib.rs"));
+        let output_restored = output.join("my_plugin").is_dir();
+        let trash_left = read_trash(&trash);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(restored.unwrap(), "// plugin");
+        assert!(output_restored);
+        assert!(trash_left.is_empty());
+    }
+
+    #[test]
+    fn test_vendor_with_symbols_gives_reverse_dns_clap_id() {
+        let clap_id = clap_id_for("Böse & Co.");
+        assert_eq!(clap_id, "com.bseco.my_plugin");
+        for label in clap_id.split('.') {
+            assert!(!label.is_empty());
+            assert!(label.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'));
+        }
+    }
+
+    #[test]
+    fn test_vendor_without_letters_is_rejected() {
+        assert!(validate_vendor("&&& ...", "", "").is_err());
+        assert!(validate_vendor("Acme\nAudio", "", "").is_err());
+    }
+
+    #[test]
+    fn test_vendor_name_is_escaped() {
+        let vendor = validate_vendor(r#"The "Best" \ Audio"#, "", "").unwrap();
+        assert_eq!(vendor.name, r#"The \"Best\" \\ Audio"#);
+    }
+
+    #[test]
+    fn test_vendor_url_and_email_shape() {
+        assert!(validate_vendor("Acme", "https://acme.audio/plugins", "dev@acme.audio").is_ok());
+        assert!(validate_vendor("Acme", " http://localhost ", " dev@acme.audio ").is_ok());
+        // Optional fields can be left empty
+        assert!(validate_vendor("Acme", "", "").is_ok());
+
+        for url in ["acme.audio", "https://", "https://acme.audio/\"x", "https://acme .audio"] {
+            assert!(validate_vendor("Acme", url, "").is_err(), "accepted url {}", url);
+        }
+        let bad_emails = ["dev", "@acme.audio", "dev@acme", "dev@@acme.audio", "dev@acme.\naudio", "d\"ev@acme.audio"];
+        for email in bad_emails {
+            assert!(validate_vendor("Acme", "", email).is_err(), "accepted email {}", email);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
@@ -14,6 +14,9 @@ pub struct DawPublishTarget {
     pub daw: String,
     pub vst3_path: String,
     pub clap_path: String,
+    /// Ignore the paths above and install into the OS plugin folders instead
+    #[serde(default)]
+    pub use_system_paths: bool,
 }
 
 #[derive(Serialize)]
@@ -28,6 +31,16 @@ pub struct CopiedFile {
     pub format: String,
     pub daw: String,
     pub path: String,
+    /// Where the previously installed bundle was moved to, if one was replaced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
+}
+
+/// Plugin install folders for the current OS, preferred first
+#[derive(Serialize)]
+pub struct SystemPluginDirs {
+    pub clap: Vec<String>,
+    pub vst3: Vec<String>,
 }
 
 /// Expand ~ to home directory
@@ -83,6 +96,109 @@ fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result
     Ok(())
 }
 
+/// Remove a bundle, which is a directory (VST3, macOS CLAP) or a single file (Windows/Linux CLAP)
+fn remove_bundle(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Standard plugin folders for a format ("clap" or "vst3") on this OS, preferred first
+///
+/// The user-level folder comes first where the OS has one, since it doesn't need admin rights.
+pub fn system_plugin_dirs(format: &str) -> Vec<PathBuf> {
+    let folder = if format == "clap" { "CLAP" } else { "VST3" };
+    let mut dirs = Vec::new();
+
+    #[cfg(target_os = "macos")]
+    {
+        dirs.push(expand_tilde(&format!("~/Library/Audio/Plug-Ins/{}", folder)));
+        dirs.push(PathBuf::from(format!("/Library/Audio/Plug-Ins/{}", folder)));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(common) = std::env::var("COMMONPROGRAMFILES") {
+            dirs.push(PathBuf::from(common).join(folder));
+        }
+        if let Ok(local) = std::env::var("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Programs").join("Common").join(folder));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let lower = folder.to_lowercase();
+        dirs.push(expand_tilde(&format!("~/.{}", lower)));
+        dirs.push(PathBuf::from(format!("/usr/lib/{}", lower)));
+    }
+
+    dirs
+}
+
+/// Copy a bundle into a plugin folder, creating it if needed
+///
+/// An existing bundle with the same name is moved to `<name>.bak` first (replacing any
+/// older backup). Returns (installed path, backup path).
+fn install_bundle(bundle: &Path, dest_dir: &Path) -> Result<(PathBuf, Option<PathBuf>), String> {
+    let file_name = bundle
+        .file_name()
+        .ok_or_else(|| format!("Invalid bundle path: {:?}", bundle))?;
+    let dest = dest_dir.join(file_name);
+
+    std::fs::create_dir_all(dest_dir)
+        .map_err(|e| format!("Failed to create {}: {}", dest_dir.display(), e))?;
+
+    let backup = if dest.exists() {
+        let backup = dest_dir.join(format!("{}.bak", file_name.to_string_lossy()));
+        if backup.exists() {
+            remove_bundle(&backup)
+                .map_err(|e| format!("Failed to remove old backup {}: {}", backup.display(), e))?;
+        }
+        log_message("DEBUG", "publish", &format!("Backing up existing {:?} to {:?}", dest, backup));
+        std::fs::rename(&dest, &backup)
+            .map_err(|e| format!("Failed to back up existing {}: {}", dest.display(), e))?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    log_message("DEBUG", "publish", &format!("Copying {:?} to {:?}", bundle, dest));
+    let copy_result = if bundle.is_dir() {
+        copy_dir_all(bundle, &dest)
+    } else {
+        std::fs::copy(bundle, &dest).map(|_| ())
+    };
+    if let Err(e) = copy_result {
+        // Put the previous version back so a failed publish doesn't uninstall the plugin
+        if let Some(ref backup) = backup {
+            let _ = remove_bundle(&dest);
+            let _ = std::fs::rename(backup, &dest);
+        }
+        return Err(format!("Failed to copy to {}: {}", dest.display(), e));
+    }
+
+    // Clear macOS quarantine attribute so Gatekeeper doesn't block the plugin
+    let _ = clear_quarantine(&dest);
+    Ok((dest, backup))
+}
+
+/// Get the standard plugin folders for this OS (what `use_system_paths` installs into)
+#[tauri::command]
+pub fn get_system_plugin_dirs() -> SystemPluginDirs {
+    let to_strings = |dirs: Vec<PathBuf>| {
+        dirs.iter()
+            .map(|d| d.to_string_lossy().to_string())
+            .collect()
+    };
+    SystemPluginDirs {
+        clap: to_strings(system_plugin_dirs("clap")),
+        vst3: to_strings(system_plugin_dirs("vst3")),
+    }
+}
+
 /// Publish plugin to selected DAW folders
 #[tauri::command]
 pub async fn publish_to_daw(
@@ -140,103 +256,58 @@ pub async fn publish_to_daw(
         ));
     }
 
-    log_message("DEBUG", "publish", &format!("Targets: {:?}", targets.iter().map(|t| (&t.daw, &t.vst3_path, &t.clap_path)).collect::<Vec<_>>()));
+    log_message("DEBUG", "publish", &format!("Targets: {:?}", targets.iter().map(|t| (&t.daw, &t.vst3_path, &t.clap_path, t.use_system_paths)).collect::<Vec<_>>()));
 
     for target in targets {
-        log_message("INFO", "publish", &format!("Processing target: {} (vst3: '{}', clap: '{}')", target.daw, target.vst3_path, target.clap_path));
-
-        // Copy VST3 if available and path is specified
-        if has_vst3 && !target.vst3_path.is_empty() {
-            let dest_dir = expand_tilde(&target.vst3_path);
-            let dest = dest_dir.join(format!("{}.vst3", snake_name));
-            log_message("DEBUG", "publish", &format!("VST3 dest_dir: {:?}, dest: {:?}", dest_dir, dest));
-
-            // Remove existing bundle if present
-            if dest.exists() {
-                log_message("DEBUG", "publish", &format!("Removing existing VST3 at {:?}", dest));
-                if let Err(e) = std::fs::remove_dir_all(&dest) {
-                    log_message("ERROR", "publish", &format!("Failed to remove existing VST3: {}", e));
-                    errors.push(format!("Failed to remove existing VST3 for {}: {}", target.daw, e));
-                    continue;
-                }
-            }
+        log_message("INFO", "publish", &format!("Processing target: {} (vst3: '{}', clap: '{}', system: {})", target.daw, target.vst3_path, target.clap_path, target.use_system_paths));
+
+        let formats = [
+            ("VST3", "vst3", has_vst3, &vst3_bundle, &target.vst3_path),
+            ("CLAP", "clap", has_clap, &clap_bundle, &target.clap_path),
+        ];
 
-            // Create parent directory if needed
-            if let Err(e) = std::fs::create_dir_all(&dest_dir) {
-                log_message("ERROR", "publish", &format!("Failed to create VST3 dir: {}", e));
-                errors.push(format!("Failed to create VST3 directory for {}: {}", target.daw, e));
+        for (label, format, available, bundle, path) in formats {
+            if !available {
+                log_message("DEBUG", "publish", &format!("Skipping {}: not built", label));
                 continue;
             }
 
-            // Copy the bundle
-            log_message("DEBUG", "publish", &format!("Copying VST3 from {:?} to {:?}", vst3_bundle, dest));
-            if let Err(e) = copy_dir_all(&vst3_bundle, &dest) {
-                log_message("ERROR", "publish", &format!("VST3 copy failed: {}", e));
-                errors.push(format!("Failed to copy VST3 to {}: {}", target.daw, e));
+            // Candidate folders: the OS plugin folders (first one that works wins) or the DAW path
+            let dest_dirs = if target.use_system_paths {
+                system_plugin_dirs(format)
+            } else if !path.is_empty() {
+                vec![expand_tilde(path)]
             } else {
-                // Verify the copy actually worked
-                let copy_verified = dest.exists();
-                log_message("INFO", "publish", &format!("VST3 copy succeeded! Verified exists: {}", copy_verified));
-                if !copy_verified {
-                    log_message("WARN", "publish", "dest.exists() returned false after copy!");
-                }
-                // Clear macOS quarantine attribute so Gatekeeper doesn't block the plugin
-                let _ = clear_quarantine(&dest);
-                copied.push(CopiedFile {
-                    format: "VST3".to_string(),
-                    daw: target.daw.clone(),
-                    path: dest.to_string_lossy().to_string(),
-                });
-            }
-        } else {
-            log_message("DEBUG", "publish", &format!("Skipping VST3: has_vst3={}, path_empty={}", has_vst3, target.vst3_path.is_empty()));
-        }
-
-        // Copy CLAP if available and path is specified
-        if has_clap && !target.clap_path.is_empty() {
-            let dest_dir = expand_tilde(&target.clap_path);
-            let dest = dest_dir.join(format!("{}.clap", snake_name));
-            log_message("DEBUG", "publish", &format!("CLAP dest_dir: {:?}, dest: {:?}", dest_dir, dest));
-
-            // Remove existing bundle if present
-            if dest.exists() {
-                log_message("DEBUG", "publish", &format!("Removing existing CLAP at {:?}", dest));
-                if let Err(e) = std::fs::remove_dir_all(&dest) {
-                    log_message("ERROR", "publish", &format!("Failed to remove existing CLAP: {}", e));
-                    errors.push(format!("Failed to remove existing CLAP for {}: {}", target.daw, e));
-                    continue;
-                }
-            }
-
-            // Create parent directory if needed
-            if let Err(e) = std::fs::create_dir_all(&dest_dir) {
-                log_message("ERROR", "publish", &format!("Failed to create CLAP dir: {}", e));
-                errors.push(format!("Failed to create CLAP directory for {}: {}", target.daw, e));
+                log_message("DEBUG", "publish", &format!("Skipping {}: no path for {}", label, target.daw));
                 continue;
+            };
+
+            let mut last_error = format!("No {} plugin folder known for this OS", label);
+            let mut installed = false;
+            for dest_dir in dest_dirs {
+                match install_bundle(bundle, &dest_dir) {
+                    Ok((dest, backup)) => {
+                        log_message("INFO", "publish", &format!("{} installed to {:?} (verified exists: {})", label, dest, dest.exists()));
+                        copied.push(CopiedFile {
+                            format: label.to_string(),
+                            daw: target.daw.clone(),
+                            path: dest.to_string_lossy().to_string(),
+                            backup_path: backup.map(|b| b.to_string_lossy().to_string()),
+                        });
+                        installed = true;
+                        break;
+                    }
+                    Err(e) => {
+                        log_message("WARN", "publish", &format!("{} install to {:?} failed: {}", label, dest_dir, e));
+                        last_error = e;
+                    }
+                }
             }
 
-            // Copy the bundle
-            log_message("DEBUG", "publish", &format!("Copying CLAP from {:?} to {:?}", clap_bundle, dest));
-            if let Err(e) = copy_dir_all(&clap_bundle, &dest) {
-                log_message("ERROR", "publish", &format!("CLAP copy failed: {}", e));
-                errors.push(format!("Failed to copy CLAP to {}: {}", target.daw, e));
-            } else {
-                // Verify the copy actually worked
-                let copy_verified = dest.exists();
-                log_message("INFO", "publish", &format!("CLAP copy succeeded! Verified exists: {}", copy_verified));
-                if !copy_verified {
-                    log_message("WARN", "publish", "dest.exists() returned false after copy!");
-                }
-                // Clear macOS quarantine attribute so Gatekeeper doesn't block the plugin
-                let _ = clear_quarantine(&dest);
-                copied.push(CopiedFile {
-                    format: "CLAP".to_string(),
-                    daw: target.daw.clone(),
-                    path: dest.to_string_lossy().to_string(),
-                });
+            if !installed {
+                log_message("ERROR", "publish", &format!("{} publish failed for {}: {}", label, target.daw, last_error));
+                errors.push(format!("Failed to install {} for {}: {}", label, target.daw, last_error));
             }
-        } else {
-            log_message("DEBUG", "publish", &format!("Skipping CLAP: has_clap={}, path_empty={}", has_clap, target.clap_path.is_empty()));
         }
     }
 
@@ -0,0 +1,205 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Stdio;
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use super::build::to_package_name;
+use super::prerequisites::{is_child_pid_tracked, register_child_pid, unregister_child_pid};
+use super::projects::{ensure_workspace, get_projects_path, get_workspace_path};
+
+/// Events emitted on "standalone-stream" while a standalone plugin runs
+#[derive(Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum StandaloneEvent {
+    #[serde(rename = "output")]
+    Output { pid: u32, line: String },
+    #[serde(rename = "exit")]
+    Exit { pid: u32, success: bool },
+}
+
+/// Find the plugin struct name from the `nih_export_clap!(Name);` line in lib.rs
+fn find_plugin_struct(lib_rs: &str) -> Option<String> {
+    let start = lib_rs.find("nih_export_clap!(")? + "nih_export_clap!(".len();
+    let end = lib_rs[start..].find(')')? + start;
+    let name = lib_rs[start..end].trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Add a standalone binary target to a project if it doesn't have one yet
+///
+/// The plugin crate is built as an rlib too, exposes `run_standalone()` behind a
+/// `standalone` feature (which enables nih-plug's standalone backend), and gets a
+/// tiny `src/main.rs` that calls it. Plugin builds are unaffected since the feature
+/// and binary are opt-in.
+fn ensure_standalone_target(project_path: &Path, package_name: &str) -> Result<(), String> {
+    let cargo_path = project_path.join("Cargo.toml");
+    let mut cargo_toml = fs::read_to_string(&cargo_path)
+        .map_err(|e| format!("Failed to read Cargo.toml: {}", e))?;
+
+    if !cargo_toml.contains("standalone = [") {
+        if cargo_toml.contains("crate-type = [\"cdylib\"]") {
+            cargo_toml = cargo_toml.replace("crate-type = [\"cdylib\"]", "crate-type = [\"cdylib\", \"lib\"]");
+        }
+        if cargo_toml.contains("\n[features]\n") {
+            cargo_toml = cargo_toml.replace("\n[features]\n", "\n[features]\nstandalone = [\"nih_plug/standalone\"]\n");
+        } else {
+            cargo_toml.push_str("\n[features]\nstandalone = [\"nih_plug/standalone\"]\n");
+        }
+        cargo_toml.push_str(&format!(
+            r#"
+[[bin]]
+name = "{package_name}_standalone"
+path = "src/main.rs"
+required-features = ["standalone"]
+"#
+        ));
+        fs::write(&cargo_path, cargo_toml)
+            .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
+    }
+
+    let lib_path = project_path.join("src/lib.rs");
+    let mut lib_rs = fs::read_to_string(&lib_path)
+        .map_err(|e| format!("Failed to read lib.rs: {}", e))?;
+
+    if !lib_rs.contains("fn run_standalone") {
+        let plugin_struct = find_plugin_struct(&lib_rs)
+            .ok_or("Could not find nih_export_clap! in lib.rs")?;
+        lib_rs.push_str(&format!(
+            r#"
+/// Entry point for the standalone runner (built with the `standalone` feature)
+#[cfg(feature = "standalone")]
+pub fn run_standalone() -> bool {{
+    nih_plug::nih_export_standalone::<{plugin_struct}>()
+}}
+"#
+        ));
+        fs::write(&lib_path, lib_rs)
+            .map_err(|e| format!("Failed to write lib.rs: {}", e))?;
+    }
+
+    let main_path = project_path.join("src/main.rs");
+    if !main_path.exists() {
+        let main_rs = format!(
+            r#"fn main() {{
+    {package_name}::run_standalone();
+}}
+"#
+        );
+        fs::write(&main_path, main_rs)
+            .map_err(|e| format!("Failed to write main.rs: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Build and launch a project's plugin as a nih-plug standalone app on the default audio device
+/// Output is streamed on "standalone-stream". Returns the child PID for stop_standalone.
+#[tauri::command]
+pub async fn launch_standalone(project_name: String, window: tauri::Window) -> Result<u32, String> {
+    ensure_workspace()?;
+
+    let project_path = get_projects_path().join(&project_name);
+    if !project_path.exists() {
+        return Err(format!("Project not found: {}", project_name));
+    }
+
+    let package_name = to_package_name(&project_name);
+    ensure_standalone_target(&project_path, &package_name)?;
+
+    let bin_name = format!("{}_standalone", package_name);
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(get_workspace_path())
+        .args([
+            "run",
+            "--release",
+            "--package",
+            &package_name,
+            "--bin",
+            &bin_name,
+            "--features",
+            "standalone",
+        ])
+        .env("PATH", super::get_extended_path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Own process group so stopping cargo also stops the compiled binary
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to spawn cargo: {}", e))?;
+
+    let pid = child.id().ok_or("Standalone process exited immediately")?;
+
+    // Track the child PID for cleanup on app exit
+    register_child_pid(pid);
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+
+    let stdout_window = window.clone();
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            let _ = stdout_window.emit("standalone-stream", StandaloneEvent::Output { pid, line });
+        }
+    });
+
+    let stderr_window = window.clone();
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            let _ = stderr_window.emit("standalone-stream", StandaloneEvent::Output { pid, line });
+        }
+    });
+
+    // Wait in the background so the command returns as soon as the process is running
+    tokio::spawn(async move {
+        let success = child.wait().await.map(|s| s.success()).unwrap_or(false);
+        unregister_child_pid(pid);
+        let _ = window.emit("standalone-stream", StandaloneEvent::Exit { pid, success });
+    });
+
+    Ok(pid)
+}
+
+/// Stop a standalone plugin started with launch_standalone
+#[tauri::command]
+pub async fn stop_standalone(pid: u32) -> Result<(), String> {
+    // Only signal processes we launched
+    if !is_child_pid_tracked(pid) {
+        return Err("No standalone process with that PID".to_string());
+    }
+
+    // cargo was spawned as a process group leader - signal the whole group
+    #[cfg(unix)]
+    {
+        use std::process::Command as StdCommand;
+        let _ = StdCommand::new("kill")
+            .args(["-TERM", "--", &format!("-{}", pid)])
+            .output();
+    }
+
+    // /T kills the whole process tree
+    #[cfg(windows)]
+    {
+        use std::process::Command as StdCommand;
+        let _ = StdCommand::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output();
+    }
+
+    unregister_child_pid(pid);
+    Ok(())
+}
@@ -5,7 +5,7 @@ use tauri::{Manager, RunEvent};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize file logging
+    // Initialize file logging (also the backend for `log::` macros)
     commands::logging::init_logging();
 
     let app = tauri::Builder::default()
@@ -19,19 +19,13 @@ pub fn run() {
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
 
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
-
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::prerequisites::check_prerequisites,
             commands::prerequisites::check_disk_space,
+            commands::prerequisites::check_plugin_dirs,
+            commands::prerequisites::check_rust_target,
             commands::prerequisites::install_xcode,
             commands::prerequisites::install_rust,
             commands::prerequisites::install_claude_cli,
@@ -43,15 +37,31 @@ pub fn run() {
             commands::projects::list_projects,
             commands::projects::get_project,
             commands::projects::delete_project,
+            commands::projects::list_trashed_projects,
+            commands::projects::restore_project,
+            commands::projects::empty_trash,
             commands::projects::update_project,
             commands::projects::open_project_folder,
             commands::projects::open_in_editor,
             commands::projects::get_workspace_path_string,
+            commands::doctor::diagnose_project,
             commands::claude::send_to_claude,
+            commands::claude::dump_last_prompt,
+            commands::claude::set_project_instructions,
+            commands::claude::get_project_instructions,
             commands::claude::test_claude_cli,
             commands::claude::interrupt_claude,
             commands::build::build_project,
+            commands::build::cancel_build,
+            commands::build::validate_project,
+            commands::build::collect_build_errors,
+            commands::build::clean_build_cache,
+            commands::build::check_build_disk_space,
+            commands::build::get_target_dir_size,
+            commands::build::warmup_build_cache,
             commands::build::open_output_folder,
+            commands::standalone::launch_standalone,
+            commands::standalone::stop_standalone,
             commands::git::revert_to_commit,
             commands::chat::save_chat_history,
             commands::chat::load_chat_history,
@@ -59,12 +69,16 @@ pub fn run() {
             commands::chat::update_active_version,
             commands::chat::get_current_version,
             commands::publish::publish_to_daw,
+            commands::publish::get_system_plugin_dirs,
             commands::publish::check_available_formats,
             commands::publish::package_plugins,
             commands::logging::get_log_file_path,
             commands::logging::read_log_file,
+            commands::logging::export_log_text,
             commands::logging::clear_log_file,
             commands::logging::get_log_file_size,
+            commands::logging::set_log_level,
+            commands::logging::get_log_level,
             commands::files::store_chat_attachments,
             commands::share::export_project,
             commands::share::import_project,
@@ -72,21 +86,37 @@ pub fn run() {
             // Preview/Audio commands
             commands::preview::init_audio_engine,
             commands::preview::shutdown_audio_engine,
+            commands::preview::audio_self_test,
+            commands::preview::get_audio_engine_status,
             commands::preview::get_audio_devices,
             commands::preview::get_audio_sample_rate,
             commands::preview::get_system_sample_rate,
             commands::preview::set_audio_config,
+            commands::preview::get_supported_buffer_sizes,
             commands::preview::preview_play,
             commands::preview::preview_stop,
             commands::preview::preview_pause,
             commands::preview::preview_set_signal,
+            commands::preview::preview_trigger_burst,
             commands::preview::preview_set_frequency,
             commands::preview::preview_set_amplitude,
             commands::preview::preview_set_gate,
+            commands::preview::preview_set_gate_sync,
             commands::preview::preview_load_sample,
             commands::preview::preview_set_looping,
+            commands::preview::preview_set_loop_region,
+            commands::preview::preview_clear_loop_region,
+            commands::preview::preview_set_playback_speed,
+            commands::preview::preview_get_signal,
+            commands::preview::preview_get_input_source,
+            commands::preview::preview_get_looping,
             commands::preview::preview_get_state,
             commands::preview::preview_get_levels,
+            commands::preview::preview_get_spectrum_bands,
+            commands::preview::preview_set_spectrum_resolution,
+            commands::preview::preview_capture_spectrum,
+            commands::preview::preview_get_spectrum_snapshot,
+            commands::preview::preview_clear_spectrum_snapshot,
             commands::preview::get_demo_samples,
             commands::preview::start_level_meter,
             commands::preview::stop_level_meter,
@@ -96,40 +126,100 @@ pub fn run() {
             commands::preview::plugin_get_state,
             commands::preview::plugin_has_plugin,
             commands::preview::plugin_has_editor,
+            commands::preview::plugin_get_note_names,
+            commands::preview::plugin_list_ports,
+            commands::preview::plugin_set_input_mapping,
+            commands::preview::plugin_set_bypass,
+            commands::preview::plugin_set_mix,
+            commands::preview::plugin_get_input_mapping,
+            commands::preview::plugin_dump_params_json,
             commands::preview::plugin_scan_directory,
             commands::preview::get_project_plugin_path,
             commands::preview::plugin_load_for_project,
             commands::preview::plugin_open_editor,
             commands::preview::plugin_close_editor,
             commands::preview::plugin_is_editor_open,
+            commands::preview::plugin_capture_thumbnail,
             commands::preview::enable_performance_monitoring,
             commands::preview::is_performance_monitoring_enabled,
             commands::preview::plugin_idle,
             commands::preview::plugin_reload,
+            // Preview session commands
+            commands::preview::save_preview_session,
+            commands::preview::load_preview_session,
             // Live input commands
             commands::preview::get_input_devices,
             commands::preview::preview_set_live_input,
             commands::preview::preview_set_live_paused,
+            commands::preview::preview_set_input_channels,
+            commands::preview::preview_set_input_monitor,
+            commands::preview::preview_get_input_monitor,
+            commands::preview::preview_set_input_latency,
+            commands::preview::preview_get_input_latency,
             commands::preview::preview_is_live_paused,
             commands::preview::preview_get_input_levels,
             // Master volume commands
             commands::preview::preview_set_master_volume,
             commands::preview::preview_get_master_volume,
+            commands::preview::preview_set_output_channels,
+            commands::preview::preview_get_output_channels,
+            commands::preview::preview_get_position,
+            commands::preview::preview_set_dc_block,
+            commands::preview::preview_set_dither,
+            commands::preview::preview_set_goniometer_scale,
+            commands::preview::preview_set_correlation_smoothing,
+            commands::preview::preview_measure_response,
+            commands::preview::preview_render_sample,
+            commands::preview::preview_set_limiter,
+            commands::preview::preview_set_metering_weighting,
+            commands::preview::preview_get_loudness,
+            commands::preview::preview_reset_loudness,
+            commands::preview::preview_get_headroom_stats,
+            commands::preview::preview_reset_headroom_stats,
+            commands::preview::preview_reset_analysis,
+            commands::preview::preview_set_monitor_mode,
+            commands::preview::preview_get_monitor_mode,
+            commands::preview::preview_set_monitor_width,
+            commands::preview::preview_get_monitor_width,
+            commands::preview::preview_set_gain_match,
+            commands::preview::preview_get_gain_match,
+            commands::preview::preview_set_auto_level,
+            commands::preview::preview_get_auto_level,
+            // Parameter automation commands
+            commands::preview::preview_set_parameter_value,
+            commands::preview::start_param_recording,
+            commands::preview::stop_param_recording,
+            commands::preview::play_param_automation,
+            commands::preview::stop_param_automation,
+            commands::preview::get_param_automation,
+            commands::preview::set_param_automation,
             // MIDI commands (for instrument plugins)
             commands::preview::midi_batch,
             commands::preview::midi_note_on,
             commands::preview::midi_note_off,
+            commands::preview::midi_note_off_vel,
+            commands::preview::midi_set_transpose,
+            commands::preview::midi_get_transpose,
+            commands::preview::midi_set_default_velocity,
+            commands::preview::midi_panic,
+            commands::preview::midi_cc,
+            commands::preview::midi_pitch_bend,
             commands::preview::midi_all_notes_off,
+            commands::preview::midi_get_active_notes,
             commands::preview::set_plugin_is_instrument,
             // Pattern playback commands
             commands::preview::pattern_list,
             commands::preview::pattern_list_by_category,
+            commands::preview::save_user_pattern,
+            commands::preview::delete_user_pattern,
             commands::preview::pattern_play,
             commands::preview::pattern_stop,
             commands::preview::pattern_set_bpm,
             commands::preview::pattern_set_octave_shift,
+            commands::preview::pattern_set_swing,
             commands::preview::pattern_set_looping,
             commands::preview::pattern_is_playing,
+            commands::preview::sidechain_ghost_kick,
             // MIDI file commands
             commands::preview::midi_file_load,
             commands::preview::midi_file_get_info,
@@ -137,14 +227,20 @@ pub fn run() {
             commands::preview::midi_file_play,
             commands::preview::midi_file_stop,
             commands::preview::midi_file_set_tempo_automation,
+            commands::preview::midi_file_set_track_mute,
+            commands::preview::midi_file_set_track_solo,
+            commands::preview::midi_file_use_embedded_tempo,
             commands::preview::midi_file_get_position,
             commands::preview::midi_file_seek,
+            commands::preview::midi_file_set_loop,
             // Live MIDI device commands
             commands::preview::midi_device_list,
             commands::preview::midi_device_connect,
             commands::preview::midi_device_disconnect,
             commands::preview::midi_device_is_connected,
             commands::preview::midi_device_get_connected,
+            commands::preview::midi_device_set_channel_filter,
+            commands::preview::midi_device_set_channel_remap,
             commands::preview::midi_device_get_last_note,
         ])
         .build(tauri::generate_context!())
@@ -153,6 +249,8 @@ pub fn run() {
     app.run(|app_handle, event| {
         match event {
             RunEvent::Exit => {
+                // Keep the project plugin's state for the next session
+                commands::preview::save_project_plugin_state();
                 // Clean up any spawned child processes when the app exits
                 commands::cleanup_child_processes();
             }
@@ -13,7 +13,7 @@ import { GuidedTour } from './components/Tour';
 import { LicenseAcceptanceModal } from './components/License';
 import { applyTheme } from './components/Settings/ThemePicker';
 import { CURRENT_LICENSE_VERSION } from './constants/license';
-import { onPluginCrashed } from './api/preview';
+import { onPluginCrashed, onPluginError } from './api/preview';
 import type { PrerequisiteStatus } from './types';
 
 function App() {
@@ -21,6 +21,7 @@ function App() {
   const acceptedLicenseVersion = useSettingsStore((state) => state.acceptedLicenseVersion);
   const theme = useSettingsStore((state) => state.theme);
   const customColors = useSettingsStore((state) => state.customColors);
+  const warmBuildCacheOnStartup = useSettingsStore((state) => state.warmBuildCacheOnStartup);
   const loadProjects = useProjectStore((state) => state.loadProjects);
   const { addToast } = useToastStore();
   const { setStatus, setUpdateInfo, setLastChecked } = useUpdateStore();
@@ -58,6 +59,14 @@ function App() {
     }
   }, [setupComplete, loadProjects]);
 
+  // Precompile shared plugin dependencies in the background so the first build is fast
+  useEffect(() => {
+    if (!setupComplete || !warmBuildCacheOnStartup) return;
+    invoke('warmup_build_cache').catch((err) => {
+      console.error('Build cache warm-up failed:', err);
+    });
+  }, [setupComplete, warmBuildCacheOnStartup]);
+
   // Silent prerequisites check on startup
   useEffect(() => {
     if (!setupComplete || hasCheckedPrereqs) return;
@@ -90,7 +99,7 @@ function App() {
     checkPrereqs();
   }, [setupComplete, hasCheckedPrereqs, addToast]);
 
-  // Global plugin crash listener - always active so crashes are caught even when PreviewPanel is closed
+  // Global plugin crash/error listeners - always active so failures are caught even when PreviewPanel is closed
   useEffect(() => {
     if (!setupComplete) return;
 
@@ -113,8 +122,28 @@ function App() {
       });
     });
 
+    const unlistenErrorPromise = onPluginError((message) => {
+      const { addToast, clearErrorToasts } = useToastStore.getState();
+      const { queueMessage } = useChatStore.getState();
+
+      addToast({
+        type: 'error',
+        message: 'Plugin reported a processing error and was bypassed',
+        action: {
+          label: 'Fix It',
+          onClick: () => {
+            queueMessage(
+              `The plugin returned an error from its audio processing callback:\n\n\`\`\`\n${message}\n\`\`\`\n\nThis happens when \`process()\` in \`src/lib.rs\` returns \`ProcessStatus::Error\`. Please review the code paths that return an error and handle those cases without failing the whole buffer.`
+            );
+            clearErrorToasts();
+          },
+        },
+      });
+    });
+
     return () => {
       unlistenPromise.then((unlisten) => unlisten());
+      unlistenErrorPromise.then((unlisten) => unlisten());
     };
   }, [setupComplete]);
 
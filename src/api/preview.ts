@@ -8,7 +8,9 @@ import type { PluginPerformance } from '../types';
 
 export interface AudioDeviceInfo {
   name: string;
-  isDefault: boolean;
+  is_default: boolean;
+  /** Total channel count of the device (null if it couldn't be queried) */
+  channels: number | null;
 }
 
 export interface DemoSample {
@@ -47,6 +49,57 @@ export async function shutdownAudioEngine(): Promise<void> {
   await invoke('shutdown_audio_engine');
 }
 
+export type EngineStatus = 'running' | 'device_lost' | 'no_device' | 'stopped';
+
+/**
+ * Get the audio engine status (running, device lost, no device, or stopped).
+ * 'no_device' means no output could be opened and the engine is running silently.
+ */
+export async function getAudioEngineStatus(): Promise<EngineStatus> {
+  return await invoke('get_audio_engine_status');
+}
+
+export interface AudioSelfTestReport {
+  device_name: string | null;
+  sample_rate: number | null;
+  buffer_size: number | null;
+  /** Whether the engine had to be started by the test */
+  initialized_engine: boolean;
+  /** Whether the test tone showed up on the output meters */
+  levels_registered: boolean;
+  peak_left_db: number;
+  peak_right_db: number;
+  stream_error: string | null;
+  status: EngineStatus;
+}
+
+/**
+ * Check the output path end to end: plays a 1kHz sine for 200ms and reports whether it reached
+ * the output meters. Starts the engine if needed; the previous source and transport are restored.
+ */
+export async function audioSelfTest(): Promise<AudioSelfTestReport> {
+  return await invoke('audio_self_test');
+}
+
+/**
+ * Subscribe to output device loss events
+ * Emitted once when the audio interface disappears; the engine retries on the default device
+ */
+export function onDeviceLost(callback: (message: string) => void): Promise<UnlistenFn> {
+  return listen<string>('device-lost', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Subscribe to device recovery events (engine rebuilt on the default device)
+ */
+export function onDeviceRecovered(callback: () => void): Promise<UnlistenFn> {
+  return listen('device-recovered', () => {
+    callback();
+  });
+}
+
 /**
  * Get list of available audio output devices
  */
@@ -54,6 +107,19 @@ export async function getAudioDevices(): Promise<AudioDeviceInfo[]> {
   return await invoke('get_audio_devices');
 }
 
+export interface SupportedBufferSizes {
+  min: number | null;
+  max: number | null;
+  options: number[];
+}
+
+/**
+ * Get the buffer sizes supported by an output device (default device if null)
+ */
+export async function getSupportedBufferSizes(deviceName?: string | null): Promise<SupportedBufferSizes> {
+  return await invoke('get_supported_buffer_sizes', { deviceName: deviceName || null });
+}
+
 /**
  * Start audio playback
  */
@@ -86,9 +152,18 @@ export async function previewSetSignal(
   amplitude?: number,
   gatePattern?: GatePattern,
   gateRate?: number,
-  gateDuty?: number
+  gateDuty?: number,
+  burstCycles?: number
 ): Promise<void> {
-  await invoke('preview_set_signal', { signalType, frequency, amplitude, gatePattern, gateRate, gateDuty });
+  await invoke('preview_set_signal', { signalType, frequency, amplitude, gatePattern, gateRate, gateDuty, burstCycles });
+}
+
+/**
+ * Fire the one-shot burst ('burst' signal type): a full-scale single-sample impulse,
+ * or a tone burst when burstCycles was given to previewSetSignal
+ */
+export async function previewTriggerBurst(): Promise<void> {
+  await invoke('preview_trigger_burst');
 }
 
 /**
@@ -102,6 +177,17 @@ export async function previewSetGate(
   await invoke('preview_set_gate', { pattern, rate, duty });
 }
 
+/**
+ * Sync the gate to the transport tempo on a note division (follows the pattern player's BPM
+ * and position); null returns to the free-running gate set with previewSetGate
+ */
+export async function previewSetGateSync(
+  division: 'quarter' | 'eighth' | 'sixteenth' | null,
+  duty?: number
+): Promise<void> {
+  await invoke('preview_set_gate_sync', { division, duty });
+}
+
 /**
  * Set the signal frequency
  */
@@ -116,11 +202,24 @@ export async function previewSetAmplitude(amplitude: number): Promise<void> {
   await invoke('preview_set_amplitude', { amplitude });
 }
 
+export interface SampleInfo {
+  name: string;
+  path: string;
+  sample_rate: number;
+  channels: number;
+  duration_secs: number;
+  /** Length in frames (estimated from the container while streaming) */
+  num_samples: number;
+  /** Long files are decoded while playing instead of loaded into memory */
+  streaming: boolean;
+}
+
 /**
  * Load a sample file as the input source
+ * Returns the loaded sample's info, or null if nothing could be loaded
  */
-export async function previewLoadSample(path: string): Promise<void> {
-  await invoke('preview_load_sample', { path });
+export async function previewLoadSample(path: string): Promise<SampleInfo | null> {
+  return await invoke('preview_load_sample', { path });
 }
 
 /**
@@ -130,6 +229,50 @@ export async function previewSetLooping(looping: boolean): Promise<void> {
   await invoke('preview_set_looping', { looping });
 }
 
+/**
+ * Loop only part of the loaded sample (seconds, clamped to its length) while looping is on
+ * Not available for streamed (long) samples
+ */
+export async function previewSetLoopRegion(startSecs: number, endSecs: number): Promise<void> {
+  await invoke('preview_set_loop_region', { startSecs, endSecs });
+}
+
+/**
+ * Loop the whole sample again
+ */
+export async function previewClearLoopRegion(): Promise<void> {
+  await invoke('preview_clear_loop_region');
+}
+
+/**
+ * Speed sample playback up or slow it down (0.25 - 4.0, pitch follows)
+ * Returns the speed that was applied after clamping
+ */
+export async function previewSetPlaybackSpeed(ratio: number): Promise<number> {
+  return await invoke('preview_set_playback_speed', { ratio });
+}
+
+/**
+ * Get the signal generator settings (kept even while another input source is active)
+ */
+export async function previewGetSignal(): Promise<PreviewSignalConfig> {
+  return await invoke('preview_get_signal');
+}
+
+/**
+ * Get the active input source
+ */
+export async function previewGetInputSource(): Promise<PreviewInputSource> {
+  return await invoke('preview_get_input_source');
+}
+
+/**
+ * Get looping mode
+ */
+export async function previewGetLooping(): Promise<boolean> {
+  return await invoke('preview_get_looping');
+}
+
 /**
  * Get current preview state
  */
@@ -144,6 +287,52 @@ export async function previewGetLevels(): Promise<[number, number]> {
   return await invoke('preview_get_levels');
 }
 
+export interface SpectrumBands {
+  magnitudes: number[];
+  input_magnitudes: number[];
+  frequencies: number[];     // Center frequency of each band in Hz
+  edges: [number, number][]; // [low, high] range of each band in Hz
+  sample_rate: number;
+  fft_size: number;
+  bin_hz: number;
+}
+
+/**
+ * Get spectrum magnitudes together with each band's frequency and edges
+ */
+export async function previewGetSpectrumBands(): Promise<SpectrumBands> {
+  return await invoke('preview_get_spectrum_bands');
+}
+
+/**
+ * Set the spectrum analyzer's FFT size (power of 2, 256 - 16384)
+ * Larger sizes resolve low frequencies better but respond more slowly
+ */
+export async function previewSetSpectrumResolution(size: number): Promise<void> {
+  return await invoke('preview_set_spectrum_resolution', { size });
+}
+
+/**
+ * Freeze the current output spectrum for before/after comparison
+ */
+export async function previewCaptureSpectrum(): Promise<number[]> {
+  return await invoke('preview_capture_spectrum');
+}
+
+/**
+ * Get the frozen spectrum snapshot (null if nothing has been captured)
+ */
+export async function previewGetSpectrumSnapshot(): Promise<number[] | null> {
+  return await invoke('preview_get_spectrum_snapshot');
+}
+
+/**
+ * Discard the frozen spectrum snapshot
+ */
+export async function previewClearSpectrumSnapshot(): Promise<void> {
+  await invoke('preview_clear_spectrum_snapshot');
+}
+
 /**
  * Get list of available demo samples
  */
@@ -188,6 +377,12 @@ export interface MeteringData {
   left_db: number;
   /** Right channel output level in dB (-60 to 0) */
   right_db: number;
+  /** Frequency weighting applied to the weighted level */
+  weighting: MeteringWeighting;
+  /** Weighted level of the mono output mix (0.0 - 1.0); unweighted when weighting is 'none' */
+  weighted_level: number;
+  /** Weighted level in dB (-60 to 0) */
+  weighted_db: number;
   /** Left channel input level (0.0 - 1.0) - for live input metering */
   input_left: number;
   /** Right channel input level (0.0 - 1.0) - for live input metering */
@@ -220,12 +415,18 @@ export interface MeteringData {
   clipping_left: boolean;
   /** Right channel clipping indicator */
   clipping_right: boolean;
+  /** Safety limiter activity since the last reset */
+  headroom: HeadroomStats;
   /** Stereo imaging positions: [angle, radius] pairs for particle display - post-FX output */
   stereo_positions: [number, number][];
+  /** Per-position flag marking the newest points (drawn brighter) - post-FX output */
+  stereo_recent: boolean[];
   /** Stereo correlation coefficient (-1.0 to +1.0) - post-FX output */
   stereo_correlation: number;
   /** INPUT stereo imaging positions: [angle, radius] pairs for particle display - pre-FX */
   stereo_positions_input: [number, number][];
+  /** Per-position recent flag - pre-FX */
+  stereo_recent_input: boolean[];
   /** INPUT stereo correlation coefficient (-1.0 to +1.0) - pre-FX */
   stereo_correlation_input: number;
   /** Plugin performance metrics (only present when monitoring is enabled) */
@@ -265,6 +466,7 @@ export interface PluginStateActive {
   name: string;
   path: string;
   has_editor: boolean;
+  is_instrument: boolean;  // Detected at load time from the plugin's features/audio ports
 }
 
 export interface PluginStateError {
@@ -290,7 +492,7 @@ export interface PluginInfo {
 }
 
 /**
- * Load a CLAP plugin from a .clap bundle path
+ * Load a plugin from a .clap or .vst3 bundle path
  */
 export async function pluginLoad(path: string): Promise<void> {
   await invoke('plugin_load', { path });
@@ -324,8 +526,96 @@ export async function pluginHasEditor(): Promise<boolean> {
   return await invoke('plugin_has_editor');
 }
 
+/** A parameter of the loaded plugin */
+export interface PluginParam {
+  id: number;
+  name: string;
+  /** Group path the plugin files the parameter under, may be empty */
+  module: string;
+  min_value: number;
+  max_value: number;
+  default_value: number;
+  /** Current plain value (null if the plugin couldn't report it) */
+  value: number | null;
+  /** Current value as formatted by the plugin (e.g. "-6.0 dB") */
+  display_value: string | null;
+  /** Set flags by name, e.g. 'stepped', 'automatable', 'hidden' */
+  flags: string[];
+}
+
+/**
+ * Dump the loaded plugin's parameters with ranges, current values and flags
+ * (empty when no plugin is loaded)
+ */
+export async function pluginDumpParamsJson(): Promise<PluginParam[]> {
+  return await invoke('plugin_dump_params_json');
+}
+
+/**
+ * Get the loaded plugin's note names (drum maps, key switches) as [key, name] pairs
+ */
+export async function pluginGetNoteNames(): Promise<[number, string][]> {
+  return await invoke('plugin_get_note_names');
+}
+
+/**
+ * Subscribe to note name changes reported by the plugin (re-fetch with pluginGetNoteNames)
+ */
+export function onPluginNoteNamesChanged(callback: () => void): Promise<UnlistenFn> {
+  return listen('plugin-note-names-changed', () => {
+    callback();
+  });
+}
+
+/** An audio port declared by the loaded plugin */
+export interface AudioPortInfo {
+  name: string;
+  is_input: boolean;
+  /** The main port in its direction (the one the stereo bus is connected to) */
+  is_main: boolean;
+  channel_count: number;
+}
+
+/** How the stereo input is fed to the plugin's main input */
+export type InputMapping = 'stereo_passthrough' | 'mono_sum' | 'left_only';
+
+/**
+ * List the audio ports the loaded plugin declares
+ */
+export async function pluginListPorts(): Promise<AudioPortInfo[]> {
+  return await invoke('plugin_list_ports');
+}
+
+/**
+ * Set how the stereo input is mapped onto the plugin's main input (kept across plugin loads)
+ */
+export async function pluginSetInputMapping(mode: InputMapping): Promise<void> {
+  return await invoke('plugin_set_input_mapping', { mode });
+}
+
+/**
+ * Bypass the hosted plugin so the input passes through unprocessed (crossfaded, kept across loads)
+ */
+export async function pluginSetBypass(bypass: boolean): Promise<void> {
+  return await invoke('plugin_set_bypass', { bypass });
+}
+
+/**
+ * Blend the plugin output with the dry input (0.0 = fully dry, 1.0 = fully wet)
+ */
+export async function pluginSetMix(mix: number): Promise<void> {
+  return await invoke('plugin_set_mix', { mix });
+}
+
 /**
- * Scan a directory for .clap plugin bundles
+ * Get the current input mapping
+ */
+export async function pluginGetInputMapping(): Promise<InputMapping> {
+  return await invoke('plugin_get_input_mapping');
+}
+
+/**
+ * Scan a directory for .clap and .vst3 plugin bundles
  */
 export async function pluginScanDirectory(path: string): Promise<PluginInfo[]> {
   return await invoke('plugin_scan_directory', { path });
@@ -377,6 +667,16 @@ export function onPluginCrashed(callback: (message: string) => void): Promise<Un
   });
 }
 
+/**
+ * Subscribe to plugin processing error events
+ * Emitted once when a plugin returns CLAP_PROCESS_ERROR; it's bypassed (dry signal) until reloaded
+ */
+export function onPluginError(callback: (message: string) => void): Promise<UnlistenFn> {
+  return listen<string>('plugin-error', (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Get the .clap plugin path for a project (based on current version)
  */
@@ -444,9 +744,20 @@ export async function pluginIdle(): Promise<void> {
 /**
  * Reload the current plugin (for hot reload)
  * If projectName and version are provided, reload from that project's output folder
+ * Plugin state carries over to the new build unless preserveState is false
+ */
+export async function pluginReload(projectName?: string, version?: number, preserveState?: boolean): Promise<void> {
+  await invoke('plugin_reload', { projectName, version, preserveState });
+}
+
+/**
+ * Subscribe to failures restoring plugin state after a hot reload
+ * (usually the new build's parameter layout changed)
  */
-export async function pluginReload(projectName?: string, version?: number): Promise<void> {
-  await invoke('plugin_reload', { projectName, version });
+export function onPluginStateRestoreFailed(callback: (error: string) => void): Promise<UnlistenFn> {
+  return listen<string>('plugin-state-restore-failed', (event) => {
+    callback(event.payload);
+  });
 }
 
 /**
@@ -458,6 +769,60 @@ export function onPluginReloading(callback: (path: string) => void): Promise<Unl
   });
 }
 
+// =============================================================================
+// Preview Session API
+// =============================================================================
+
+export type PreviewInputSource =
+  | { type: 'signal'; config: PreviewSignalConfig }
+  | { type: 'sample'; path: string }
+  | { type: 'live'; device: string | null; chunk_size: number | null }
+  | { type: 'none' };
+
+export interface PreviewSignalConfig {
+  signal_type: string;
+  frequency: number;
+  amplitude: number;
+  sweep_start: number;
+  sweep_end: number;
+  sweep_duration: number;
+  gate_pattern: GatePattern;
+  gate_rate: number;
+  gate_duty: number;
+  /** Musical gate divisions follow the transport tempo instead of gate_rate */
+  gate_sync?: boolean;
+  burst?: BurstShape;
+}
+
+export type BurstShape = { shape: 'impulse' } | { shape: 'tone_burst'; cycles: number };
+
+export interface PreviewSession {
+  input_source: PreviewInputSource;
+  signal: PreviewSignalConfig;
+  master_volume: number;
+  looping: boolean;
+  is_instrument: boolean;
+  plugin_path: string | null;
+  /** Plugin state as base64 */
+  plugin_state: string | null;
+  automation: ParamAutomation | null;
+  saved_at: string;
+}
+
+/**
+ * Save the current preview session for a project
+ */
+export async function savePreviewSession(projectPath: string): Promise<void> {
+  await invoke('save_preview_session', { projectPath });
+}
+
+/**
+ * Restore a project's saved preview session (null if none saved)
+ */
+export async function loadPreviewSession(projectPath: string): Promise<PreviewSession | null> {
+  return await invoke('load_preview_session', { projectPath });
+}
+
 // =============================================================================
 // Live Input API
 // =============================================================================
@@ -465,6 +830,7 @@ export function onPluginReloading(callback: (path: string) => void): Promise<Unl
 export interface InputDeviceInfo {
   name: string;
   is_default: boolean;
+  channels: number | null;  // Null if the device's config couldn't be queried
 }
 
 /**
@@ -483,6 +849,51 @@ export async function previewSetLiveInput(deviceName?: string | null, chunkSize?
   await invoke('preview_set_live_input', { deviceName: deviceName || null, chunkSize });
 }
 
+/**
+ * Choose which physical input channels (0-based) feed the left/right inputs
+ * Use the same index twice to hear a single mono input on both sides
+ */
+export async function previewSetInputChannels(leftIdx: number, rightIdx: number): Promise<void> {
+  await invoke('preview_set_input_channels', { leftIdx, rightIdx });
+}
+
+export interface LiveInputLatency {
+  resampling: boolean;  // Input device rate differs from the output rate
+  chunk_size: number;
+  added_latency_ms: number;  // 0 when not resampling
+  input_sample_rate: number | null;
+  output_sample_rate: number;
+}
+
+/**
+ * Change the live input resampler chunk size without restarting capture
+ */
+export async function previewSetInputLatency(chunkSize: number): Promise<LiveInputLatency> {
+  return await invoke('preview_set_input_latency', { chunkSize });
+}
+
+/**
+ * Get whether live input is resampled and the latency that adds
+ */
+export async function previewGetInputLatency(): Promise<LiveInputLatency> {
+  return await invoke('preview_get_input_latency');
+}
+
+/**
+ * Set whether live input is heard (passed to the plugin and output)
+ * Capture and input meters keep running while monitoring is off
+ */
+export async function previewSetInputMonitor(enabled: boolean): Promise<void> {
+  await invoke('preview_set_input_monitor', { enabled });
+}
+
+/**
+ * Get whether live input is heard
+ */
+export async function previewGetInputMonitor(): Promise<boolean> {
+  return await invoke('preview_get_input_monitor');
+}
+
 /**
  * Set the live input paused state
  */
@@ -518,6 +929,346 @@ export async function previewGetMasterVolume(): Promise<number> {
   return await invoke('preview_get_master_volume');
 }
 
+/** Device channels the stereo preview is written to (0-based) */
+export interface OutputChannels {
+  left: number;
+  right: number;
+  device_channels: number;
+}
+
+/**
+ * Route the stereo output to a pair of the device's channels (0-based), e.g. 2/3 for outputs 3-4
+ */
+export async function previewSetOutputChannels(leftIdx: number, rightIdx: number): Promise<OutputChannels> {
+  return await invoke('preview_set_output_channels', { leftIdx, rightIdx });
+}
+
+/**
+ * Get the device channels the stereo output is written to
+ */
+export async function previewGetOutputChannels(): Promise<OutputChannels> {
+  return await invoke('preview_get_output_channels');
+}
+
+export interface PlaybackPosition {
+  /** Frames rendered since the engine started (free-running) */
+  sample_clock: number;
+  /** Frames rendered while playing since the last stop (pause holds it) */
+  play_clock: number;
+  position_secs: number;
+  sample_rate: number;
+  is_playing: boolean;
+}
+
+/**
+ * Get the engine's sample clock and transport position, for syncing UI animations to the audio
+ */
+export async function previewGetPosition(): Promise<PlaybackPosition> {
+  return await invoke('preview_get_position');
+}
+
+/**
+ * Enable/disable the DC blocker on the final output (off by default)
+ */
+export async function previewSetDcBlock(enabled: boolean): Promise<void> {
+  await invoke('preview_set_dc_block', { enabled });
+}
+
+/**
+ * Configure output dither: quantize to 16 or 24 bits with TPDF noise, optionally noise-shaped
+ */
+export async function previewSetDither(enabled: boolean, bits: 16 | 24, shaped: boolean): Promise<void> {
+  await invoke('preview_set_dither', { enabled, bits, shaped });
+}
+
+export type LimiterMode = 'hard' | 'soft';
+
+/**
+ * Configure the safety limiter: ceiling in dBFS (-24 to +6) and hard clamp or soft (tanh) curve
+ */
+export async function previewSetLimiter(ceilingDb: number, mode: LimiterMode): Promise<void> {
+  await invoke('preview_set_limiter', { ceilingDb, mode });
+}
+
+export type MeteringWeighting = 'none' | 'a' | 'k';
+
+/**
+ * Set the frequency weighting of the weighted output level
+ * ('a' = A-weighting, 'k' = K-weighting per BS.1770)
+ */
+export async function previewSetMeteringWeighting(mode: MeteringWeighting): Promise<void> {
+  await invoke('preview_set_metering_weighting', { mode });
+}
+
+/** Loudness readings in LUFS (null until enough audio has been measured) */
+export interface LoudnessReading {
+  /** Latest 400 ms */
+  momentary: number | null;
+  /** Latest 3 s */
+  short_term: number | null;
+  /** Gated loudness since the last reset (BS.1770) */
+  integrated: number | null;
+}
+
+/**
+ * Get momentary, short-term and integrated loudness of the output
+ */
+export async function previewGetLoudness(): Promise<LoudnessReading> {
+  return await invoke('preview_get_loudness');
+}
+
+/**
+ * Restart integrated loudness measurement
+ */
+export async function previewResetLoudness(): Promise<void> {
+  await invoke('preview_reset_loudness');
+}
+
+export interface HeadroomStats {
+  /** Output samples clamped by the safety limiter (including NaN/Inf) */
+  clamped_samples: number;
+  /** Largest absolute sample value seen before limiting */
+  max_peak: number;
+  /** max_peak in dB (positive = over 0dBFS) */
+  max_peak_db: number;
+}
+
+/**
+ * Get safety limiter activity since the last reset
+ */
+export async function previewGetHeadroomStats(): Promise<HeadroomStats> {
+  return await invoke('preview_get_headroom_stats');
+}
+
+/**
+ * Reset the safety limiter counters
+ */
+export async function previewResetHeadroomStats(): Promise<void> {
+  await invoke('preview_reset_headroom_stats');
+}
+
+/**
+ * Clear all meters and analysis displays (levels, spectrum, waveform, goniometer, peak holds,
+ * headroom stats). Happens automatically on plugin load/unload and input source changes.
+ */
+export async function previewResetAnalysis(): Promise<void> {
+  await invoke('preview_reset_analysis');
+}
+
+export interface FrequencyResponse {
+  sample_rate: number;
+  /** Plugin latency compensated when aligning the impulse response */
+  latency_samples: number;
+  /** Log-spaced frequencies in Hz */
+  frequencies: number[];
+  magnitude_db: number[];
+  /** Phase in degrees (-180 to 180) */
+  phase_degrees: number[];
+}
+
+/**
+ * Measure the loaded plugin's magnitude and phase response with a sine sweep (takes a few seconds)
+ */
+export async function previewMeasureResponse(): Promise<FrequencyResponse> {
+  return await invoke('preview_measure_response');
+}
+
+export interface RenderResult {
+  output_path: string;
+  sample_rate: number;
+  /** Length in frames (the same as the source sample) */
+  frames: number;
+  duration_secs: number;
+  /** Plugin latency compensated in the render */
+  latency_samples: number;
+}
+
+export interface RenderProgress {
+  frames_done: number;
+  /** Source frames plus the latency tail */
+  total_frames: number;
+  /** 0.0 - 1.0 */
+  progress: number;
+}
+
+/**
+ * Render the loaded sample through the loaded plugin to a 32-bit float WAV, faster than realtime
+ * Uses a separate instance of the plugin, so playback isn't interrupted
+ */
+export async function previewRenderSample(outputPath: string): Promise<RenderResult> {
+  return await invoke('preview_render_sample', { outputPath });
+}
+
+/**
+ * Subscribe to offline render progress (reported once per percent)
+ */
+export function onRenderProgress(callback: (progress: RenderProgress) => void): Promise<UnlistenFn> {
+  return listen<RenderProgress>('render-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Configure the goniometer: number of history points shown (1-2048) and radius gain (0.25-16)
+ */
+export async function previewSetGoniometerScale(historyLength: number, gain: number): Promise<void> {
+  await invoke('preview_set_goniometer_scale', { historyLength, gain });
+}
+
+/**
+ * Set the correlation meter smoothing time constant in ms (0-5000, 0 = no smoothing)
+ */
+export async function previewSetCorrelationSmoothing(ms: number): Promise<void> {
+  await invoke('preview_set_correlation_smoothing', { ms });
+}
+
+/**
+ * Output monitoring with an effect plugin loaded:
+ * - normal: regular monitoring path
+ * - wet: plugin output only
+ * - dry: input delayed by the plugin latency (bypass)
+ * - difference: plugin output minus the aligned dry input (null test residual)
+ */
+export type MonitorMode = 'normal' | 'wet' | 'dry' | 'difference';
+
+/**
+ * Choose what's sent to the output when an effect plugin is loaded
+ */
+export async function previewSetMonitorMode(mode: MonitorMode): Promise<void> {
+  await invoke('preview_set_monitor_mode', { mode });
+}
+
+/**
+ * Get the current monitor mode
+ */
+export async function previewGetMonitorMode(): Promise<MonitorMode> {
+  return await invoke('preview_get_monitor_mode');
+}
+
+/**
+ * Set the monitor stereo width applied after the plugin (0 = mono, 1 = unchanged, 2 = widest)
+ * Out-of-range values are clamped; resolves to the width in effect
+ */
+export async function previewSetMonitorWidth(width: number): Promise<number> {
+  return await invoke('preview_set_monitor_width', { width });
+}
+
+/**
+ * Get the monitor stereo width
+ */
+export async function previewGetMonitorWidth(): Promise<number> {
+  return await invoke('preview_get_monitor_width');
+}
+
+export interface GainMatchInfo {
+  enabled: boolean;
+  compensation_db: number;
+}
+
+/**
+ * Enable/disable loudness matching of the plugin output against the dry input
+ */
+export async function previewSetGainMatch(enabled: boolean): Promise<void> {
+  await invoke('preview_set_gain_match', { enabled });
+}
+
+/**
+ * Get gain match state and the compensation currently applied (dB)
+ */
+export async function previewGetGainMatch(): Promise<GainMatchInfo> {
+  return await invoke('preview_get_gain_match');
+}
+
+export interface AutoLevelInfo {
+  enabled: boolean;
+  time_constant_secs: number;
+  makeup_db: number;
+}
+
+/**
+ * Enable/disable auto level - a slow makeup gain holding the plugin output at the input level.
+ * Optionally sets how slowly it reacts (0.5-10 seconds). Gain match is skipped while it's on.
+ */
+export async function previewSetAutoLevel(enabled: boolean, timeConstantSecs?: number): Promise<void> {
+  await invoke('preview_set_auto_level', { enabled, timeConstantSecs: timeConstantSecs ?? null });
+}
+
+/**
+ * Get auto level state and the makeup gain currently applied (dB)
+ */
+export async function previewGetAutoLevel(): Promise<AutoLevelInfo> {
+  return await invoke('preview_get_auto_level');
+}
+
+// =============================================================================
+// Parameter Automation API
+// =============================================================================
+
+export interface AutomationPoint {
+  /** Sample position relative to the start of the recording */
+  sample: number;
+  value: number;
+}
+
+export interface AutomationLane {
+  param_id: number;
+  points: AutomationPoint[];
+}
+
+export interface ParamAutomation {
+  sample_rate: number;
+  lanes: AutomationLane[];
+}
+
+/**
+ * Set a plugin parameter by CLAP id (plain value); recorded while recording is active
+ */
+export async function previewSetParameterValue(paramId: number, value: number): Promise<void> {
+  await invoke('preview_set_parameter_value', { paramId, value });
+}
+
+/**
+ * Start recording parameter changes against the engine sample clock
+ */
+export async function startParamRecording(): Promise<void> {
+  await invoke('start_param_recording');
+}
+
+/**
+ * Stop recording and return the recorded lanes (null if not recording)
+ */
+export async function stopParamRecording(): Promise<ParamAutomation | null> {
+  return await invoke('stop_param_recording');
+}
+
+/**
+ * Play back the recorded parameter automation from the start
+ */
+export async function playParamAutomation(): Promise<void> {
+  await invoke('play_param_automation');
+}
+
+/**
+ * Stop parameter automation playback
+ */
+export async function stopParamAutomation(): Promise<void> {
+  await invoke('stop_param_automation');
+}
+
+/**
+ * Get the automation available for playback
+ */
+export async function getParamAutomation(): Promise<ParamAutomation | null> {
+  return await invoke('get_param_automation');
+}
+
+/**
+ * Replace the automation used for playback (null clears it)
+ */
+export async function setParamAutomation(automation: ParamAutomation | null): Promise<void> {
+  await invoke('set_param_automation', { automation });
+}
+
 // =============================================================================
 // MIDI API (for instrument plugins)
 // =============================================================================
@@ -563,9 +1314,9 @@ function scheduleMidiFlush(): void {
  * Send a MIDI note on event to the loaded plugin
  * Events are batched and sent together to reduce IPC overhead
  * @param note - MIDI note number (0-127, 60 = middle C)
- * @param velocity - Note velocity (0-127)
+ * @param velocity - Note velocity (0-127), defaults to the velocity set with midiSetDefaultVelocity
  */
-export function midiNoteOn(note: number, velocity: number): void {
+export function midiNoteOn(note: number, velocity?: number): void {
   midiEventQueue.push({ type: 'on', note, velocity });
   scheduleMidiFlush();
 }
@@ -574,12 +1325,32 @@ export function midiNoteOn(note: number, velocity: number): void {
  * Send a MIDI note off event to the loaded plugin
  * Events are batched and sent together to reduce IPC overhead
  * @param note - MIDI note number (0-127)
+ * @param velocity - Release velocity (0-127), defaults to 0
  */
-export function midiNoteOff(note: number): void {
-  midiEventQueue.push({ type: 'off', note });
+export function midiNoteOff(note: number, velocity?: number): void {
+  midiEventQueue.push({ type: 'off', note, velocity });
   scheduleMidiFlush();
 }
 
+/**
+ * Send a MIDI control change to the loaded plugin
+ * @param controller - Controller number (0-127, e.g. 1 = mod wheel, 64 = sustain)
+ * @param value - Controller value (0-127)
+ * @param channel - MIDI channel (0-15), defaults to 0
+ */
+export async function midiCc(controller: number, value: number, channel?: number): Promise<void> {
+  await invoke('midi_cc', { controller, value, channel });
+}
+
+/**
+ * Send a MIDI pitch bend to the loaded plugin
+ * @param value - 14-bit bend (0-16383, 8192 = center)
+ * @param channel - MIDI channel (0-15), defaults to 0
+ */
+export async function midiPitchBend(value: number, channel?: number): Promise<void> {
+  await invoke('midi_pitch_bend', { value, channel });
+}
+
 /**
  * Send all notes off to the loaded plugin (panic button)
  */
@@ -591,6 +1362,66 @@ export async function midiAllNotesOff(): Promise<void> {
   await invoke('midi_all_notes_off');
 }
 
+/**
+ * Set the keyboard transpose in semitones (-48 to +48), applied to midiNoteOn/midiNoteOff
+ * Held notes are released. Independent of the pattern player's octave shift.
+ */
+export async function midiSetTranspose(semitones: number): Promise<void> {
+  if (midiFlushPromise) {
+    await midiFlushPromise;
+  }
+  await invoke('midi_set_transpose', { semitones });
+}
+
+/**
+ * Get the keyboard transpose in semitones
+ */
+export async function midiGetTranspose(): Promise<number> {
+  return await invoke('midi_get_transpose');
+}
+
+/**
+ * Set the velocity used when midiNoteOn is called without one (1-127)
+ */
+export async function midiSetDefaultVelocity(velocity: number): Promise<void> {
+  await invoke('midi_set_default_velocity', { velocity });
+}
+
+/**
+ * Panic: send all notes off and reset the keyboard transpose
+ */
+export async function midiPanic(): Promise<void> {
+  if (midiFlushPromise) {
+    await midiFlushPromise;
+  }
+  await invoke('midi_panic');
+}
+
+export type MidiNoteSource = 'command' | 'device';
+
+export interface MidiNoteActivity {
+  note: number;
+  /** 0 when the note was released */
+  velocity: number;
+  source: MidiNoteSource;
+}
+
+/**
+ * Get the notes currently held from any source (sorted)
+ */
+export async function midiGetActiveNotes(): Promise<number[]> {
+  return await invoke('midi_get_active_notes');
+}
+
+/**
+ * Subscribe to note on/off activity from both the on-screen keyboard and MIDI devices
+ */
+export function onMidiNote(callback: (activity: MidiNoteActivity) => void): Promise<UnlistenFn> {
+  return listen<MidiNoteActivity>('midi-note', (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Set whether the loaded plugin is an instrument (vs effect)
  * Instrument plugins are processed even when not "playing" for MIDI input
@@ -610,6 +1441,24 @@ export interface PatternInfo {
   name: string;
   category: PatternCategory;
   length_beats: number;
+  /** True for patterns saved with saveUserPattern */
+  is_user: boolean;
+}
+
+/** A note in a user pattern (times in beats) */
+export interface PatternNoteDef {
+  start: number;
+  /** Must be positive */
+  duration: number;
+  note: number;
+  velocity: number;
+}
+
+/** Note data for a user pattern */
+export interface PatternDef {
+  notes: PatternNoteDef[];
+  /** Loop length in beats (defaults to the last note's end, rounded up to a whole beat) */
+  length_beats?: number;
 }
 
 /**
@@ -626,6 +1475,21 @@ export async function patternListByCategory(category: PatternCategory): Promise<
   return await invoke('pattern_list_by_category', { category });
 }
 
+/**
+ * Save a user pattern (overwrites one with the same name)
+ * User patterns are listed and played just like built-ins
+ */
+export async function saveUserPattern(name: string, category: PatternCategory, def: PatternDef): Promise<PatternInfo> {
+  return await invoke('save_user_pattern', { name, category, def });
+}
+
+/**
+ * Delete a user pattern by name or ID
+ */
+export async function deleteUserPattern(name: string): Promise<void> {
+  await invoke('delete_user_pattern', { name });
+}
+
 /**
  * Start playing a pattern
  * @param patternId - ID of the pattern to play
@@ -663,6 +1527,14 @@ export async function patternSetOctaveShift(shift: number): Promise<void> {
   await invoke('pattern_set_octave_shift', { shift });
 }
 
+/**
+ * Set pattern swing (0-100%): every second 16th is delayed by up to half a 16th.
+ * Takes effect at the next 16th-note pair.
+ */
+export async function patternSetSwing(amount: number): Promise<void> {
+  await invoke('pattern_set_swing', { amount });
+}
+
 /**
  * Set pattern looping
  */
@@ -677,6 +1549,23 @@ export async function patternIsPlaying(): Promise<boolean> {
   return await invoke('pattern_is_playing');
 }
 
+export type GhostKickPattern = 'quarter' | 'half' | 'eighth' | 'offbeat';
+
+export interface GhostKickInfo {
+  enabled: boolean;
+  pattern: GhostKickPattern;
+  bpm: number;
+  has_sidechain: boolean;  // Whether the loaded plugin has a sidechain input
+}
+
+/**
+ * Feed a synthetic kick into the effect plugin's sidechain input on each pattern hit
+ * (tempo follows the pattern BPM, see patternSetBpm)
+ */
+export async function sidechainGhostKick(enabled: boolean, pattern: GhostKickPattern): Promise<GhostKickInfo> {
+  return await invoke('sidechain_ghost_kick', { enabled, pattern });
+}
+
 // =============================================================================
 // MIDI File API
 // =============================================================================
@@ -728,19 +1617,20 @@ export async function midiFileUnload(): Promise<void> {
 }
 
 /**
- * Play a track from the loaded MIDI file
- * @param trackIndex - Index into the tracks array (not original MIDI track number)
+ * Play a track from the loaded MIDI file, or every track when trackIndex is null
+ * Track mutes and solos apply either way.
+ * @param trackIndex - Index into the tracks array (not original MIDI track number), or null for all tracks
  * @param bpm - Tempo override (or null to use file's default BPM)
  * @param octaveShift - Octave shift (-2 to +2)
  * @param looping - Whether to loop the track
- * @param useTempoAutomation - Whether to follow the file's tempo automation
+ * @param useTempoAutomation - Whether to follow the file's embedded tempo map (default true)
  */
 export async function midiFilePlay(
-  trackIndex: number,
+  trackIndex: number | null,
   bpm: number | null,
   octaveShift: number,
   looping: boolean,
-  useTempoAutomation: boolean
+  useTempoAutomation?: boolean
 ): Promise<void> {
   await invoke('midi_file_play', { trackIndex, bpm, octaveShift, looping, useTempoAutomation });
 }
@@ -760,13 +1650,42 @@ export async function midiFileSetTempoAutomation(enabled: boolean): Promise<void
   await invoke('midi_file_set_tempo_automation', { enabled });
 }
 
+/**
+ * Mute or unmute a track of the loaded MIDI file (takes effect immediately)
+ * @param track - Index into the tracks array
+ */
+export async function midiFileSetTrackMute(track: number, muted: boolean): Promise<void> {
+  await invoke('midi_file_set_track_mute', { track, muted });
+}
+
+/**
+ * Solo or unsolo a track of the loaded MIDI file. While any track is soloed,
+ * only soloed tracks play (solo overrides mute).
+ * @param track - Index into the tracks array
+ */
+export async function midiFileSetTrackSolo(track: number, solo: boolean): Promise<void> {
+  await invoke('midi_file_set_track_solo', { track, solo });
+}
+
+/**
+ * Toggle between the file's embedded tempo map (true) and the manual BPM override (false)
+ */
+export async function midiFileUseEmbeddedTempo(enabled: boolean): Promise<void> {
+  await invoke('midi_file_use_embedded_tempo', { enabled });
+}
+
 /**
  * Playback position info
  */
 export interface PlaybackPositionInfo {
   position: number;
   duration: number;
+  /** Position in seconds (follows the tempo map when embedded tempo is on) */
+  position_seconds: number;
+  duration_seconds: number;
   is_playing: boolean;
+  /** Active loop region [start, end] in beats */
+  loop_region: [number, number] | null;
 }
 
 /**
@@ -777,10 +1696,18 @@ export async function midiFileGetPosition(): Promise<PlaybackPositionInfo> {
 }
 
 /**
- * Seek to a position in beats
+ * Seek to a position in beats (clamped into the loop region while it's enabled)
  */
 export async function midiFileSeek(positionBeats: number): Promise<void> {
-  await invoke('midi_file_seek', { position_beats: positionBeats });
+  await invoke('midi_file_seek', { positionBeats });
+}
+
+/**
+ * Loop a region of the MIDI file (in beats)
+ * Notes are released at the loop boundary so nothing hangs across the wrap
+ */
+export async function midiFileSetLoop(startBeats: number, endBeats: number, enabled: boolean): Promise<void> {
+  await invoke('midi_file_set_loop', { startBeats, endBeats, enabled });
 }
 
 // =============================================================================
@@ -800,7 +1727,7 @@ export async function midiDeviceList(): Promise<MidiDeviceInfo[]> {
 }
 
 /**
- * Connect to a MIDI input device by index
+ * Connect to a MIDI input device by index (devices already connected stay connected)
  * @param deviceIndex - Index from midiDeviceList()
  * @returns Name of the connected device
  */
@@ -809,26 +1736,55 @@ export async function midiDeviceConnect(deviceIndex: number): Promise<string> {
 }
 
 /**
- * Disconnect from the current MIDI input device
+ * Disconnect a MIDI input device
+ * @param deviceName - Device to disconnect; all devices are disconnected if omitted
  */
-export async function midiDeviceDisconnect(): Promise<void> {
-  await invoke('midi_device_disconnect');
+export async function midiDeviceDisconnect(deviceName?: string): Promise<void> {
+  await invoke('midi_device_disconnect', { deviceName });
 }
 
 /**
- * Check if connected to a MIDI input device
+ * Check if any MIDI input device is connected
  */
 export async function midiDeviceIsConnected(): Promise<boolean> {
   return await invoke('midi_device_is_connected');
 }
 
 /**
- * Get the name of the connected MIDI device (if any)
+ * Get the names of the connected MIDI devices
  */
-export async function midiDeviceGetConnected(): Promise<string | null> {
+export async function midiDeviceGetConnected(): Promise<string[]> {
   return await invoke('midi_device_get_connected');
 }
 
+/**
+ * Subscribe to MIDI device unplug events (payload is the device name)
+ */
+export function onMidiDeviceDisconnected(callback: (deviceName: string) => void): Promise<UnlistenFn> {
+  return listen<string>('midi-device-disconnected', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Only accept the given incoming channels from a connected device
+ * @param device - Connected device name
+ * @param mask - One bit per channel (bit 0 = channel 1); 0xFFFF passes every channel
+ */
+export async function midiDeviceSetChannelFilter(device: string, mask: number): Promise<void> {
+  await invoke('midi_device_set_channel_filter', { device, mask });
+}
+
+/**
+ * Remap one of a connected device's channels
+ * @param device - Connected device name
+ * @param from - Incoming channel (0-15)
+ * @param to - Channel sent to the plugin (0-15); use `from` to restore pass-through
+ */
+export async function midiDeviceSetChannelRemap(device: string, from: number, to: number): Promise<void> {
+  await invoke('midi_device_set_channel_remap', { device, from, to });
+}
+
 /**
  * Get the last received MIDI note (for activity indicator)
  */
@@ -34,10 +34,29 @@ interface StoredAttachment {
 }
 
 interface ClaudeStreamEvent {
-  type: 'start' | 'text' | 'error' | 'done';
+  type: 'start' | 'text' | 'error' | 'done' | 'tool';
   project_path: string;
   content?: string;
   message?: string;
+  // Tool activity (type === 'tool')
+  tool?: string;
+  summary?: string;
+  target?: string | null;
+}
+
+interface ToolActivity {
+  tool: string;
+  summary: string;
+  target: string | null;
+}
+
+/** Short label for a tool target - file names instead of full paths, commands truncated */
+function formatToolTarget(activity: ToolActivity): string | null {
+  if (!activity.target) return null;
+  if (activity.tool === 'Bash') {
+    return activity.target.length > 40 ? `${activity.target.substring(0, 40)}...` : activity.target;
+  }
+  return activity.target.split(/[\\/]/).pop() || activity.target;
 }
 
 interface ChatPanelProps {
@@ -71,6 +90,7 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
     return 0;
   });
   const [isHistoryLoaded, setIsHistoryLoaded] = useState(false);
+  const [toolActivity, setToolActivity] = useState<ToolActivity | null>(null);
   const messagesEndRef = useRef<HTMLDivElement>(null);
   const messagesContainerRef = useRef<HTMLDivElement>(null);
   const versionBadgeRef = useRef<HTMLSpanElement>(null);
@@ -504,6 +524,7 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
     setClaudeBusy(project.path);
     clearStreamingContent(project.path);
     streamingContentRef.current = '';
+    setToolActivity(null);
 
     // Save user message immediately (don't rely on effect in case of unmount)
     try {
@@ -546,6 +567,9 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
         streamingContentRef.current += `\nError: ${data.message}`;
         setStreamingContent(project.path, streamingContentRef.current);
         addLine(`[ERROR] ${data.message}`);
+      } else if (data.type === 'tool' && data.tool && data.summary) {
+        // Text line for the same tool use was already logged - this only drives the activity indicator
+        setToolActivity({ tool: data.tool, summary: data.summary, target: data.target ?? null });
       } else if (data.type === 'start') {
         addLine('[Started working...]');
       }
@@ -569,6 +593,8 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
         model: aiSettings.model,
         customInstructions: aiSettings.customInstructions,
         agentVerbosity: aiSettings.agentVerbosity,
+        maxTurns: aiSettings.maxTurns,
+        allowedTools: aiSettings.allowedTools,
       });
 
       // Calculate next version number if this response has a commit (files were changed)
@@ -621,8 +647,12 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
       // Check if this was a user-initiated interrupt
       const errorStr = String(err);
       const wasInterrupted = errorStr.includes('Session interrupted');
+      const wasRemoved = errorStr.includes('Project removed');
 
-      if (wasInterrupted) {
+      if (wasRemoved) {
+        // Project was deleted mid-turn - nothing to save (it would recreate the project folder)
+        usePreviewStore.getState().setPendingBuildVersion(null);
+      } else if (wasInterrupted) {
         // Clear any pending build to prevent auto-build from triggering on interrupt
         usePreviewStore.getState().setPendingBuildVersion(null);
 
@@ -692,6 +722,7 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
       clearClaudeBusy(project.path);
       clearStreamingContent(project.path);
       streamingContentRef.current = '';
+      setToolActivity(null);
       addLine('');
       addLine('[Done]');
 
@@ -1035,7 +1066,16 @@ export function ChatPanel({ project, onVersionChange }: ChatPanelProps) {
                         <div className="w-1.5 h-4 rounded-full bg-accent animate-[pulse_1s_ease-in-out_infinite]" style={{ animationDelay: '300ms' }} />
                         <div className="w-1.5 h-5 rounded-full bg-accent animate-[pulse_1s_ease-in-out_infinite]" style={{ animationDelay: '450ms' }} />
                       </div>
-                      <span className="text-sm transition-all duration-300">{THINKING_PHRASES[thinkingPhraseIndex]}</span>
+                      {toolActivity ? (
+                        <span className="text-sm transition-all duration-300" title={toolActivity.target ?? undefined}>
+                          {toolActivity.summary}
+                          {formatToolTarget(toolActivity) && (
+                            <span className="ml-1 font-mono text-xs text-text-secondary">{formatToolTarget(toolActivity)}</span>
+                          )}
+                        </span>
+                      ) : (
+                        <span className="text-sm transition-all duration-300">{THINKING_PHRASES[thinkingPhraseIndex]}</span>
+                      )}
                       <span className="text-xs text-text-muted tabular-nums">
                         {elapsedSeconds < 60
                           ? `${elapsedSeconds}s`
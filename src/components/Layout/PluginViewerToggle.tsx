@@ -99,6 +99,14 @@ export function PluginViewerToggle() {
         if (state.status === 'active') {
           if (!webviewNeedsFreshBuild || activeProject.uiFramework !== 'webview') {
             const hasEditor = await previewApi.pluginHasEditor();
+            if (hasEditor && !activeProject.thumbnail) {
+              // First launch: capture the UI for the project list (editor is opened off-screen)
+              try {
+                await useProjectStore.getState().captureThumbnail(activeProject.path);
+              } catch (err) {
+                console.warn('[PluginViewerToggle] Failed to capture thumbnail:', err);
+              }
+            }
             if (hasEditor) {
               console.log('[PluginViewerToggle] Opening editor...');
               await previewApi.pluginOpenEditor();
@@ -9,6 +9,7 @@ import { usePreviewStore } from '../../stores/previewStore'
 import { useProjectStore } from '../../stores/projectStore'
 import { useTourStore } from '../../stores/tourStore'
 import { useTipsStore } from '../../stores/tipsStore'
+import { useSettingsStore } from '../../stores/settingsStore'
 import { Tip } from '../Common/Tip'
 import { registerTourRef, unregisterTourRef } from '../../utils/tourRefs'
 import type { ProjectMeta } from '../../types'
@@ -25,8 +26,23 @@ interface BuildResult {
     success: boolean
     output_path?: string
     error?: string
+    duration_secs?: number | null
 }
 
+interface BuildErrorReport {
+    diagnostics: unknown[]
+    prompt: string | null
+}
+
+interface LastPromptDump {
+    path: string
+    content: string
+    message: string
+}
+
+// Consecutive failed builds auto-fix will send to Claude before handing back to the user
+const MAX_AUTO_FIX_ATTEMPTS = 3
+
 interface ProjectActionBarProps {
     project: ProjectMeta
     hasBuild: boolean
@@ -63,6 +79,8 @@ export function ProjectActionBar({
         version: number
     } | null>(null)
     const quickActionsRef = useRef<HTMLDivElement>(null)
+    // Auto-fix attempts since the last successful build, per project
+    const autoFixAttemptsRef = useRef<Record<string, number>>({})
 
     // Tour refs
     const buildButtonRef = useRef<HTMLButtonElement>(null)
@@ -165,6 +183,28 @@ export function ProjectActionBar({
         }
     }, [showQuickActions])
 
+    // Send the build's compile errors (src/ only) to Claude as a follow-up in the project's session
+    // Falls back to the tail of the raw build output if the errors can't be collected
+    const sendBuildErrorsToAgent = useCallback(async (buildError: string) => {
+        let prompt: string | null = null
+        try {
+            const report = await invoke<BuildErrorReport>('collect_build_errors', {
+                projectPath: project.path
+            })
+            prompt = report.prompt
+        } catch (err) {
+            console.error('Failed to collect build errors:', err)
+        }
+        if (!prompt) {
+            const relevantError = buildError.split('\n').slice(-30).join('\n')
+            prompt = `The build failed with this error:\n\n\`\`\`\n${relevantError}\n\`\`\`\n\nPlease fix this issue.`
+        }
+        queueMessage(prompt)
+        setLastBuildError(null)
+        // Clear any error toasts (including the "Build failed" toast with Fix It action)
+        clearErrorToasts()
+    }, [project.path, queueMessage, clearErrorToasts])
+
     const handleBuild = useCallback(async () => {
         if (buildDisabled) return
 
@@ -207,8 +247,13 @@ export function ProjectActionBar({
 
             if (result.success) {
                 addLine('')
-                addLine('Build successful!')
+                addLine(
+                    result.duration_secs != null
+                        ? `Build successful! (${result.duration_secs.toFixed(1)}s)`
+                        : 'Build successful!'
+                )
                 setLastBuildError(null)
+                delete autoFixAttemptsRef.current[project.path]
                 // Clear failed build for this project on success
                 if (lastFailedBuild?.projectPath === project.path) {
                     setLastFailedBuild(null)
@@ -229,21 +274,31 @@ export function ProjectActionBar({
                 setLastBuildError(errorMsg)
                 // Track this version as failed to prevent auto-build loops (scoped by project)
                 setLastFailedBuild({ projectPath: project.path, version })
-                addToast({
-                    type: 'error',
-                    message: 'Build failed. Check output for details.',
-                    action: {
-                        label: 'Fix It',
-                        onClick: () => {
-                            const errorLines = errorMsg.split('\n')
-                            const relevantError = errorLines.slice(-30).join('\n')
-                            queueMessage(
-                                `The build failed with this error:\n\n\`\`\`\n${relevantError}\n\`\`\`\n\nPlease fix this issue.`
-                            )
-                            setLastBuildError(null)
+
+                // Auto-fix: hand the errors straight to Claude (not for cancelled builds, and
+                // only a few times in a row so a fix that keeps failing doesn't loop forever)
+                const autoFixAttempts = autoFixAttemptsRef.current[project.path] ?? 0
+                const autoFix =
+                    useSettingsStore.getState().aiSettings.autoFixBuildErrors &&
+                    result.duration_secs != null &&
+                    autoFixAttempts < MAX_AUTO_FIX_ATTEMPTS
+                if (autoFix) {
+                    autoFixAttemptsRef.current[project.path] = autoFixAttempts + 1
+                    addLine('')
+                    addLine('> Sending build errors to Claude (auto-fix)...')
+                    sendBuildErrorsToAgent(errorMsg)
+                } else {
+                    addToast({
+                        type: 'error',
+                        message: 'Build failed. Check output for details.',
+                        action: {
+                            label: 'Fix It',
+                            onClick: () => {
+                                sendBuildErrorsToAgent(errorMsg)
+                            }
                         }
-                    }
-                })
+                    })
+                }
             }
         } catch (err) {
             addLine(`[ERROR] ${err}`)
@@ -267,20 +322,40 @@ export function ProjectActionBar({
         setBuildingPath,
         clearBuildingIfMatch,
         addToast,
-        queueMessage,
+        sendBuildErrorsToAgent,
         onBuildComplete,
         lastFailedBuild
     ])
 
     const handleFixError = useCallback(() => {
         if (!lastBuildError) return
-        const errorLines = lastBuildError.split('\n')
-        const relevantError = errorLines.slice(-30).join('\n')
-        queueMessage(`The build failed with this error:\n\n\`\`\`\n${relevantError}\n\`\`\`\n\nPlease fix this issue.`)
-        setLastBuildError(null)
-        // Clear any error toasts (including the "Build failed" toast with Fix It action)
-        clearErrorToasts()
-    }, [lastBuildError, queueMessage, clearErrorToasts])
+        sendBuildErrorsToAgent(lastBuildError)
+    }, [lastBuildError, sendBuildErrorsToAgent])
+
+    // Print the args and system prompt of the last Claude request to the output panel
+    const handleShowLastPrompt = useCallback(async () => {
+        try {
+            const dump = await invoke<LastPromptDump>('dump_last_prompt', { projectPath: project.path })
+            clear()
+            addLine(`> Last Claude request (${dump.path})`)
+            addLine('')
+            dump.content.split('\n').forEach((line) => addLine(line))
+        } catch (err) {
+            addToast({ type: 'error', message: String(err) })
+        }
+    }, [project.path, clear, addLine, addToast])
+
+    // Re-send the last message to Claude (in the current session)
+    const handleReplayLastPrompt = useCallback(async () => {
+        try {
+            const dump = await invoke<LastPromptDump>('dump_last_prompt', { projectPath: project.path })
+            if (dump.message.trim()) {
+                queueMessage(dump.message)
+            }
+        } catch (err) {
+            addToast({ type: 'error', message: String(err) })
+        }
+    }, [project.path, queueMessage, addToast])
 
     const handleQuickAction = useCallback((action: () => void) => {
         setShowQuickActions(false)
@@ -580,6 +655,45 @@ export function ProjectActionBar({
                             </svg>
                             Edit Project
                         </button>
+                        <button
+                            onClick={() => handleQuickAction(handleShowLastPrompt)}
+                            className="w-full px-3 py-2 text-left text-sm text-text-secondary hover:text-text-primary hover:bg-bg-tertiary transition-colors flex items-center gap-2"
+                        >
+                            <svg
+                                className="w-4 h-4"
+                                fill="none"
+                                viewBox="0 0 24 24"
+                                stroke="currentColor"
+                                strokeWidth={1.5}
+                            >
+                                <path
+                                    strokeLinecap="round"
+                                    strokeLinejoin="round"
+                                    d="M19.5 14.25v-2.625a3.375 3.375 0 00-3.375-3.375h-1.5A1.125 1.125 0 0113.5 7.125v-1.5a3.375 3.375 0 00-3.375-3.375H8.25m0 12.75h7.5m-7.5 3H12M10.5 2.25H5.625c-.621 0-1.125.504-1.125 1.125v17.25c0 .621.504 1.125 1.125 1.125h12.75c.621 0 1.125-.504 1.125-1.125V11.25a9 9 0 00-9-9z"
+                                />
+                            </svg>
+                            Show Last Prompt
+                        </button>
+                        <button
+                            onClick={() => handleQuickAction(handleReplayLastPrompt)}
+                            disabled={thisProjectClaudeBusy}
+                            className="w-full px-3 py-2 text-left text-sm text-text-secondary hover:text-text-primary hover:bg-bg-tertiary disabled:opacity-50 disabled:cursor-not-allowed transition-colors flex items-center gap-2"
+                        >
+                            <svg
+                                className="w-4 h-4"
+                                fill="none"
+                                viewBox="0 0 24 24"
+                                stroke="currentColor"
+                                strokeWidth={1.5}
+                            >
+                                <path
+                                    strokeLinecap="round"
+                                    strokeLinejoin="round"
+                                    d="M16.023 9.348h4.992v-.001M2.985 19.644v-4.992m0 0h4.992m-4.993 0l3.181 3.183a8.25 8.25 0 0013.803-3.7M4.031 9.865a8.25 8.25 0 0113.803-3.7l3.181 3.182m0-4.991v4.99"
+                                />
+                            </svg>
+                            Replay Last Prompt
+                        </button>
                         <div className="border-t border-border my-1" />
                         <button
                             onClick={() => handleQuickAction(onDeleteClick)}
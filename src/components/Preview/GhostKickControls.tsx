@@ -0,0 +1,121 @@
+import { memo, useState, useEffect, useCallback, useRef } from 'react';
+import { sidechainGhostKick, patternSetBpm } from '../../api/preview';
+import type { GhostKickPattern } from '../../api/preview';
+
+interface GhostKickControlsProps {
+  pluginLoaded: boolean;
+}
+
+const KICK_PATTERNS: { value: GhostKickPattern; label: string }[] = [
+  { value: 'quarter', label: '1/4' },
+  { value: 'half', label: '1/2' },
+  { value: 'eighth', label: '1/8' },
+  { value: 'offbeat', label: 'Offbeat' },
+];
+
+// Synthetic kick on the plugin's sidechain input, for auditioning ducking
+export const GhostKickControls = memo(function GhostKickControls({ pluginLoaded }: GhostKickControlsProps) {
+  const [enabled, setEnabled] = useState(false);
+  const [pattern, setPattern] = useState<GhostKickPattern>('quarter');
+  const [bpm, setBpm] = useState(120);
+  const [hasSidechain, setHasSidechain] = useState(true);
+
+  const enabledRef = useRef(enabled);
+  enabledRef.current = enabled;
+
+  // Turn the kick off when the controls go away
+  useEffect(() => {
+    return () => {
+      if (enabledRef.current) {
+        sidechainGhostKick(false, 'quarter').catch(err => {
+          console.error('Failed to disable ghost kick on unmount:', err);
+        });
+      }
+    };
+  }, []);
+
+  const applyKick = useCallback(async (nextEnabled: boolean, nextPattern: GhostKickPattern) => {
+    try {
+      const info = await sidechainGhostKick(nextEnabled, nextPattern);
+      setEnabled(info.enabled);
+      setPattern(info.pattern);
+      setBpm(info.bpm);
+      setHasSidechain(info.has_sidechain);
+    } catch (err) {
+      console.error('Failed to set ghost kick:', err);
+    }
+  }, []);
+
+  const handleBpmChange = useCallback(async (newBpm: number) => {
+    setBpm(newBpm);
+    try {
+      // The kick follows the pattern player's tempo
+      await patternSetBpm(newBpm);
+    } catch (err) {
+      console.error('Failed to set BPM:', err);
+    }
+  }, []);
+
+  if (!pluginLoaded) return null;
+
+  return (
+    <div className="space-y-2 pt-2 border-t border-border">
+      <div className="flex items-center justify-between">
+        <span className="text-xs text-text-muted">Sidechain Ghost Kick</span>
+        <button
+          onClick={() => applyKick(!enabled, pattern)}
+          className={`text-xs px-2 py-0.5 rounded transition-colors ${
+            enabled
+              ? 'bg-accent/20 text-accent'
+              : 'bg-bg-tertiary text-text-muted hover:text-text-primary'
+          }`}
+          title="Send a kick drum to the plugin's sidechain input"
+        >
+          {enabled ? 'On' : 'Off'}
+        </button>
+      </div>
+
+      {enabled && (
+        <>
+          <div className="flex flex-wrap gap-1">
+            {KICK_PATTERNS.map((opt) => (
+              <button
+                key={opt.value}
+                onClick={() => applyKick(true, opt.value)}
+                className={`px-2.5 py-1 rounded text-xs font-medium transition-colors ${
+                  pattern === opt.value
+                    ? 'bg-accent text-white'
+                    : 'bg-bg-primary text-text-secondary hover:text-text-primary hover:bg-bg-tertiary'
+                }`}
+              >
+                {opt.label}
+              </button>
+            ))}
+          </div>
+
+          <div className="space-y-1.5">
+            <div className="flex items-center justify-between">
+              <span className="text-xs text-text-muted">Tempo (BPM)</span>
+              <span className="text-xs font-medium text-accent">{bpm} BPM</span>
+            </div>
+            <input
+              type="range"
+              min={40}
+              max={240}
+              step={1}
+              value={bpm}
+              onChange={(e) => handleBpmChange(Number(e.target.value))}
+              className="w-full h-2 bg-bg-tertiary rounded-lg appearance-none cursor-pointer accent-accent"
+            />
+          </div>
+
+          {!hasSidechain && (
+            <p className="text-xs text-warning">
+              This plugin has no sidechain input, so the kick has nothing to drive.
+            </p>
+          )}
+        </>
+      )}
+    </div>
+  );
+});
@@ -42,6 +42,15 @@ export const LiveInputControls = memo(function LiveInputControls({
 
   const { addToast } = useToastStore();
 
+  // Resampler status for the running capture (null until capture starts)
+  const [inputLatency, setInputLatency] = useState<previewApi.LiveInputLatency | null>(null);
+
+  // Whether captured input is heard - off keeps metering without monitoring
+  const [inputMonitor, setInputMonitor] = useState(true);
+
+  // Physical input channels feeding L/R (only offered for interfaces with more than 2)
+  const [inputChannels, setInputChannels] = useState({ left: 0, right: 1 });
+
   // Animation state for input levels
   const [animatedLevels, setAnimatedLevels] = useState({ left: 0, right: 0 });
   const [displayDb, setDisplayDb] = useState({ left: -60, right: -60 });
@@ -113,6 +122,7 @@ export const LiveInputControls = memo(function LiveInputControls({
         const mapped: AudioDevice[] = devices.map(d => ({
           name: d.name,
           is_default: d.is_default,
+          channels: d.channels,
         }));
         setAvailableInputDevices(mapped);
 
@@ -133,6 +143,25 @@ export const LiveInputControls = memo(function LiveInputControls({
     loadDevices();
   }, [setAvailableInputDevices, addToast]);
 
+  // Sync the monitor switch with the engine (it outlives this panel)
+  useEffect(() => {
+    if (!usePreviewStore.getState().engineInitialized) return;
+    previewApi.previewGetInputMonitor()
+      .then(setInputMonitor)
+      .catch((err) => console.error('Failed to get input monitor state:', err));
+  }, []);
+
+  const handleInputMonitorToggle = useCallback(async () => {
+    const next = !inputMonitor;
+    try {
+      await previewApi.previewSetInputMonitor(next);
+      setInputMonitor(next);
+    } catch (err) {
+      console.error('Failed to set input monitor:', err);
+      addToast({ type: 'error', message: 'Failed to change input monitoring' });
+    }
+  }, [inputMonitor, addToast]);
+
   // Handle device selection
   const handleDeviceChange = useCallback(async (deviceName: string | undefined) => {
     const currentInputSource = usePreviewStore.getState().inputSource;
@@ -141,6 +170,14 @@ export const LiveInputControls = memo(function LiveInputControls({
       liveDeviceId: deviceName,
     });
 
+    // Channel numbers are per device - start the new one on its first pair
+    setInputChannels({ left: 0, right: 1 });
+    try {
+      await previewApi.previewSetInputChannels(0, 1);
+    } catch (err) {
+      console.error('Failed to reset input channels:', err);
+    }
+
     const state = usePreviewStore.getState();
     if (state.engineInitialized && state.isPlaying) {
       try {
@@ -191,6 +228,18 @@ export const LiveInputControls = memo(function LiveInputControls({
     }
   }, [setLivePaused, onPlay, addToast]);
 
+  // Handle L/R input channel selection
+  const handleInputChannelChange = useCallback(async (side: 'left' | 'right', channel: number) => {
+    const next = { ...inputChannels, [side]: channel };
+    try {
+      await previewApi.previewSetInputChannels(next.left, next.right);
+      setInputChannels(next);
+    } catch (err) {
+      console.error('Failed to set input channels:', err);
+      addToast({ type: 'error', message: `Failed to set input channels: ${err}` });
+    }
+  }, [inputChannels, addToast]);
+
   // Handle chunk size change
   const handleChunkSizeChange = useCallback(async (chunkSize: number) => {
     const currentInputSource = usePreviewStore.getState().inputSource;
@@ -202,7 +251,8 @@ export const LiveInputControls = memo(function LiveInputControls({
     const state = usePreviewStore.getState();
     if (state.engineInitialized && state.isPlaying && !state.isLivePaused) {
       try {
-        await previewApi.previewSetLiveInput(currentInputSource.liveDeviceId || null, chunkSize);
+        // Swaps the resampler in place - capture keeps running
+        setInputLatency(await previewApi.previewSetInputLatency(chunkSize));
       } catch (err) {
         console.error('Failed to update chunk size:', err);
         addToast({ type: 'error', message: 'Failed to update latency setting' });
@@ -210,6 +260,15 @@ export const LiveInputControls = memo(function LiveInputControls({
     }
   }, [setInputSource, addToast]);
 
+  // Refresh resampler status whenever capture (re)starts
+  useEffect(() => {
+    if (!isOpen || !isPlaying || isLivePaused) return;
+    if (!usePreviewStore.getState().engineInitialized) return;
+    previewApi.previewGetInputLatency()
+      .then(setInputLatency)
+      .catch((err) => console.error('Failed to get input latency:', err));
+  }, [isOpen, isPlaying, isLivePaused, inputSource.liveDeviceId]);
+
   // Compute meter values with useMemo
   const meterValues = useMemo(() => {
     const animLeftDb = animatedLevels.left > 0 ? Math.max(-60, 20 * Math.log10(animatedLevels.left)) : -60;
@@ -224,6 +283,10 @@ export const LiveInputControls = memo(function LiveInputControls({
 
   const selectedDevice = inputSource.liveDeviceId;
   const defaultDevice = availableInputDevices.find(d => d.is_default);
+  const activeDevice = selectedDevice
+    ? availableInputDevices.find(d => d.name === selectedDevice)
+    : defaultDevice;
+  const channelCount = activeDevice?.channels ?? 2;
 
   return (
     <div className="space-y-4">
@@ -263,6 +326,31 @@ export const LiveInputControls = memo(function LiveInputControls({
         )}
       </div>
 
+      {/* Channel Selector (multi-channel interfaces) */}
+      {channelCount > 2 && (
+        <div className="space-y-2">
+          <label className="text-xs text-text-secondary font-medium">Input Channels</label>
+          <div className="grid grid-cols-2 gap-2">
+            {(['left', 'right'] as const).map((side) => (
+              <div key={side} className="flex items-center gap-2">
+                <span className="text-xs text-text-muted w-3 font-mono">{side === 'left' ? 'L' : 'R'}</span>
+                <select
+                  value={inputChannels[side]}
+                  onChange={(e) => handleInputChannelChange(side, Number(e.target.value))}
+                  className="flex-1 bg-bg-primary text-text-primary text-sm rounded-md border border-border px-2 py-1 focus:outline-none focus:ring-1 focus:ring-accent"
+                >
+                  {Array.from({ length: channelCount }, (_, i) => (
+                    <option key={i} value={i}>
+                      Input {i + 1}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       {/* Input Level Meters */}
       <div className="space-y-2">
         <div className="flex items-center gap-1.5">
@@ -357,6 +445,13 @@ export const LiveInputControls = memo(function LiveInputControls({
             );
           })}
         </div>
+        {inputLatency && (
+          <p className="text-[10px] text-text-muted">
+            {inputLatency.resampling
+              ? `Resampling ${inputLatency.input_sample_rate ?? '?'} Hz → ${inputLatency.output_sample_rate} Hz adds ~${inputLatency.added_latency_ms.toFixed(1)} ms`
+              : 'Input and output rates match - no resampling latency'}
+          </p>
+        )}
       </div>
 
       {/* Monitoring Toggle */}
@@ -387,6 +482,26 @@ export const LiveInputControls = memo(function LiveInputControls({
           )}
         </button>
 
+        {/* Monitor mute - keeps capturing for the meters */}
+        <button
+          onClick={handleInputMonitorToggle}
+          title={inputMonitor ? 'Mute input (meters keep running)' : 'Unmute input'}
+          className={`flex items-center gap-1.5 px-2 py-2 rounded-md text-xs font-medium transition-colors ${
+            inputMonitor
+              ? 'bg-bg-tertiary text-text-secondary hover:bg-bg-elevated hover:text-text-primary'
+              : 'bg-yellow-500/15 text-yellow-500'
+          }`}
+        >
+          <svg className="w-4 h-4" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
+            {inputMonitor ? (
+              <path strokeLinecap="round" strokeLinejoin="round" d="M15.536 8.464a5 5 0 010 7.072M12 6v12l-4.5-4.5H4v-3h3.5L12 6z" />
+            ) : (
+              <path strokeLinecap="round" strokeLinejoin="round" d="M12 6v12l-4.5-4.5H4v-3h3.5L12 6zm5 3l4 4m0-4l-4 4" />
+            )}
+          </svg>
+          <span>{inputMonitor ? 'Monitor' : 'Muted'}</span>
+        </button>
+
         {/* Headphone warning */}
         <div className="flex items-center gap-1.5 text-text-muted" title="Use headphones to avoid feedback">
           <svg className="w-4 h-4" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={1.5}>
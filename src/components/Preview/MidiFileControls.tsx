@@ -33,7 +33,7 @@ export function MidiFileControls({ pluginLoaded }: MidiFileControlsProps) {
   const [bpm, setBpm] = useState(120);
   const [originalBpm, setOriginalBpm] = useState(120);
   const [octaveShift, setOctaveShift] = useState(0);
-  const [useTempoAutomation, setUseTempoAutomation] = useState(false);
+  const [useTempoAutomation, setUseTempoAutomation] = useState(true);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [playbackPosition, setPlaybackPosition] = useState(0);
@@ -7,6 +7,7 @@ import {
   midiDeviceGetConnected,
   midiDeviceGetLastNote,
   midiAllNotesOff,
+  onMidiDeviceDisconnected,
 } from '../../api/preview';
 import { Tooltip } from '../Common/Tooltip';
 
@@ -25,7 +26,7 @@ function noteToName(note: number): string {
 export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
   const [devices, setDevices] = useState<MidiDeviceInfo[]>([]);
   const [selectedDeviceIndex, setSelectedDeviceIndex] = useState<number | null>(null);
-  const [connectedDeviceName, setConnectedDeviceName] = useState<string | null>(null);
+  const [connectedDevices, setConnectedDevices] = useState<string[]>([]);
   const [isConnecting, setIsConnecting] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [lastNote, setLastNote] = useState<number | null>(null);
@@ -61,16 +62,7 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
       const connected = await midiDeviceGetConnected();
       if (!isMountedRef.current) return;
 
-      if (connected) {
-        setConnectedDeviceName(connected);
-        // Find the device index
-        const idx = deviceList.findIndex(d => d.name === connected);
-        if (idx >= 0) {
-          setSelectedDeviceIndex(idx);
-        }
-      } else {
-        setConnectedDeviceName(null);
-      }
+      setConnectedDevices(connected);
     } catch (err) {
       console.error('Failed to load MIDI devices:', err);
       if (isMountedRef.current) {
@@ -94,6 +86,21 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
     };
   }, []); // Empty deps - only run on mount/unmount
 
+  // Drop devices the backend reports as unplugged
+  useEffect(() => {
+    const unlisten = onMidiDeviceDisconnected((deviceName) => {
+      if (!isMountedRef.current) return;
+      setConnectedDevices(prev => prev.filter(name => name !== deviceName));
+      setError(`MIDI device disconnected: ${deviceName}`);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  const isConnected = connectedDevices.length > 0;
+
   // Poll for activity when connected (with guards against overlapping calls)
   useEffect(() => {
     // Cleanup any existing interval first
@@ -102,7 +109,8 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
       pollIntervalRef.current = null;
     }
 
-    if (!connectedDeviceName) {
+    if (!isConnected) {
+      setLastNote(null);
       return;
     }
 
@@ -132,7 +140,7 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
         pollIntervalRef.current = null;
       }
     };
-  }, [connectedDeviceName]);
+  }, [isConnected]);
 
   // Note: We intentionally do NOT disconnect on unmount
   // This allows the MIDI connection to persist when switching tabs
@@ -140,14 +148,14 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
 
   // Disconnect if plugin is unloaded
   useEffect(() => {
-    if (!pluginLoaded && connectedDeviceName) {
+    if (!pluginLoaded && isConnected) {
       midiDeviceDisconnect().catch(err => {
-        console.error('Failed to disconnect MIDI device when plugin unloaded:', err);
+        console.error('Failed to disconnect MIDI devices when plugin unloaded:', err);
       });
-      setConnectedDeviceName(null);
+      setConnectedDevices([]);
       setLastNote(null);
     }
-  }, [pluginLoaded, connectedDeviceName]);
+  }, [pluginLoaded, isConnected]);
 
   const handleConnect = useCallback(async () => {
     if (selectedDeviceIndex === null) return;
@@ -158,8 +166,8 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
     try {
       const deviceName = await midiDeviceConnect(selectedDeviceIndex);
       if (isMountedRef.current) {
-        setConnectedDeviceName(deviceName);
-        setLastNote(null);
+        setConnectedDevices(prev => (prev.includes(deviceName) ? prev : [...prev, deviceName]));
+        setSelectedDeviceIndex(null);
       }
     } catch (err) {
       console.error('Failed to connect to MIDI device:', err);
@@ -173,12 +181,11 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
     }
   }, [selectedDeviceIndex]);
 
-  const handleDisconnect = useCallback(async () => {
+  const handleDisconnect = useCallback(async (deviceName: string) => {
     try {
-      await midiDeviceDisconnect();
+      await midiDeviceDisconnect(deviceName);
       if (isMountedRef.current) {
-        setConnectedDeviceName(null);
-        setLastNote(null);
+        setConnectedDevices(prev => prev.filter(name => name !== deviceName));
       }
     } catch (err) {
       console.error('Failed to disconnect MIDI device:', err);
@@ -197,7 +204,8 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
     loadDevices(true); // Force refresh to re-enumerate devices
   }, [loadDevices]);
 
-  const isConnected = !!connectedDeviceName;
+  // Devices that can still be added
+  const availableDevices = devices.filter(d => !connectedDevices.includes(d.name));
 
   return (
     <div className="space-y-4">
@@ -225,15 +233,19 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
           <div className="px-3 py-2 bg-bg-tertiary rounded-lg text-sm text-text-muted">
             No MIDI devices found
           </div>
+        ) : availableDevices.length === 0 ? (
+          <div className="px-3 py-2 bg-bg-tertiary rounded-lg text-sm text-text-muted">
+            All devices connected
+          </div>
         ) : (
           <select
             value={selectedDeviceIndex ?? ''}
             onChange={(e) => setSelectedDeviceIndex(e.target.value ? Number(e.target.value) : null)}
-            disabled={isConnected || isConnecting || !pluginLoaded}
+            disabled={isConnecting || !pluginLoaded}
             className="w-full px-3 py-2 bg-bg-tertiary text-text-primary rounded-lg text-sm border border-border focus:border-accent focus:outline-none disabled:opacity-50 disabled:cursor-not-allowed"
           >
             <option value="">Select a device...</option>
-            {devices.map((device) => (
+            {availableDevices.map((device) => (
               <option key={device.index} value={device.index}>
                 {device.name}
               </option>
@@ -242,24 +254,27 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
         )}
       </div>
 
-      {/* Connection Status & Controls */}
-      <div className="flex items-center gap-2">
-        {isConnected ? (
-          <>
-            <div className="flex-1 flex items-center gap-2">
-              <span className="w-2 h-2 rounded-full bg-green-500 animate-pulse" />
-              <span className="text-sm text-text-primary truncate" title={connectedDeviceName}>
-                {connectedDeviceName}
-              </span>
-            </div>
-            <button
-              onClick={handleDisconnect}
-              className="px-3 py-1.5 text-xs font-medium bg-bg-tertiary text-text-secondary hover:text-error hover:bg-error/10 rounded transition-colors"
-            >
-              Disconnect
-            </button>
-          </>
-        ) : (
+      {/* Connected Devices */}
+      {connectedDevices.map((deviceName) => (
+        <div key={deviceName} className="flex items-center gap-2">
+          <div className="flex-1 flex items-center gap-2 min-w-0">
+            <span className="w-2 h-2 rounded-full bg-green-500 animate-pulse" />
+            <span className="text-sm text-text-primary truncate" title={deviceName}>
+              {deviceName}
+            </span>
+          </div>
+          <button
+            onClick={() => handleDisconnect(deviceName)}
+            className="px-3 py-1.5 text-xs font-medium bg-bg-tertiary text-text-secondary hover:text-error hover:bg-error/10 rounded transition-colors"
+          >
+            Disconnect
+          </button>
+        </div>
+      ))}
+
+      {/* Connect Controls */}
+      {availableDevices.length > 0 && (
+        <div className="flex items-center gap-2">
           <Tooltip
             content={
               !pluginLoaded
@@ -282,11 +297,11 @@ export function MidiLiveControls({ pluginLoaded }: MidiLiveControlsProps) {
                 }
               `}
             >
-              {isConnecting ? 'Connecting...' : 'Connect'}
+              {isConnecting ? 'Connecting...' : isConnected ? 'Add Device' : 'Connect'}
             </button>
           </Tooltip>
-        )}
-      </div>
+        </div>
+      )}
 
       {error && (
         <p className="text-xs text-error">{error}</p>
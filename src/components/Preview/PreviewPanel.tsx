@@ -11,6 +11,7 @@ import { LiveInputControls } from './LiveInputControls';
 import { InstrumentControls } from './InstrumentControls';
 import { SampleInputControls } from './SampleInputControls';
 import { SignalInputControls } from './SignalInputControls';
+import { GhostKickControls } from './GhostKickControls';
 import { OutputSection } from './OutputSection';
 import PerformanceMonitor from './PerformanceMonitor';
 import { TransportBar } from './TransportBar';
@@ -114,6 +115,8 @@ export function PreviewPanel() {
   const activeProject = useProjectStore((s) => s.activeProject);
   const { audioSettings, markAudioSettingsApplied } = useSettingsStore();
   const [engineError, setEngineError] = useState<string | null>(null);
+  // Engine runs on a silent null output when no audio device could be opened
+  const [noAudioDevice, setNoAudioDevice] = useState(false);
   // Collapsible section state
   const [collapsedSections, setCollapsedSections] = useState<Record<string, boolean>>({
     input: false,
@@ -204,6 +207,18 @@ export function PreviewPanel() {
         setEngineInitialized(true);
         setEngineError(null);
         markAudioSettingsApplied();
+        // Route to the saved output pair (the engine starts on the first pair)
+        const [leftChannel, rightChannel] = audioSettings.outputChannels;
+        if (leftChannel !== 0 || rightChannel !== 1) {
+          previewApi.previewSetOutputChannels(leftChannel, rightChannel).catch(err => {
+            console.warn('Saved output channels not available on this device:', err);
+          });
+        }
+        previewApi.getAudioEngineStatus().then(status => {
+          if (!isCancelled) setNoAudioDevice(status === 'no_device');
+        }).catch(err => {
+          console.error('Failed to get audio engine status:', err);
+        });
 
         // Run independent operations in parallel for faster startup
         const [meteringUnlisten, samples, pluginState, pluginListeners] = await Promise.all([
@@ -267,6 +282,8 @@ export function PreviewPanel() {
               setLoadedPlugin({ status: 'unloaded' });
               setPluginLoading(false);
             }),
+            // Engine moved from the silent fallback (or a lost device) onto a real device
+            previewApi.onDeviceRecovered(() => setNoAudioDevice(false)),
           ]),
         ]);
 
@@ -759,6 +776,9 @@ export function PreviewPanel() {
                         isOpen={isOpen}
                       />
                     )}
+
+                    {/* Sidechain ghost kick (for sidechain compressors/gates) */}
+                    <GhostKickControls pluginLoaded={loadedPlugin.status === 'active'} />
                   </>
                 )}
 
@@ -791,6 +811,13 @@ export function PreviewPanel() {
                 </div>
               )}
 
+              {/* No audio device - everything works, but nothing is audible */}
+              {noAudioDevice && !engineError && (
+                <div className="p-3 rounded-lg bg-warning/10 border border-warning/30 text-warning text-sm">
+                  No audio device available - running silently. Plugins, meters and MIDI still work.
+                </div>
+              )}
+
               {/* Monitoring Section (Level Meters, Spectrum, Waveform, Stereo) */}
               <div className="border-b border-border pb-2">
                 {renderSectionHeader('output', 'Monitoring',
@@ -6,7 +6,7 @@ import * as previewApi from '../../api/preview';
 import { registerTourRef, unregisterTourRef } from '../../utils/tourRefs';
 
 // Supported audio file extensions
-const AUDIO_EXTENSIONS = ['wav', 'mp3', 'flac', 'ogg', 'aac', 'm4a'];
+const AUDIO_EXTENSIONS = ['wav', 'aif', 'aiff', 'aifc', 'caf', 'mp3', 'flac', 'ogg', 'aac', 'm4a'];
 
 function isAudioFile(path: string): boolean {
   const ext = path.split('.').pop()?.toLowerCase() || '';
@@ -7,6 +7,7 @@ const SIGNAL_OPTIONS: { value: SignalType; label: string }[] = [
   { value: 'sine', label: 'Sine Wave' },
   { value: 'white_noise', label: 'White Noise' },
   { value: 'pink_noise', label: 'Pink Noise' },
+  { value: 'brown_noise', label: 'Brown Noise' },
   { value: 'impulse', label: 'Impulse' },
   { value: 'sweep', label: 'Frequency Sweep' },
   { value: 'square', label: 'Square Wave' },
@@ -1,5 +1,6 @@
 import { memo, useState, useEffect, useRef } from 'react';
 import { usePreviewStore } from '../../stores/previewStore';
+import { previewGetSpectrumBands } from '../../api/preview';
 
 interface SpectrumAnalyzerProps {
   isOpen: boolean;
@@ -58,22 +59,24 @@ const BOTTOM_MARGIN = 14;     // Space for frequency labels at bottom
 const SVG_WIDTH = LEFT_MARGIN + SPECTRUM_WIDTH + RIGHT_MARGIN;
 const SVG_HEIGHT = TOP_MARGIN + SPECTRUM_HEIGHT + BOTTOM_MARGIN;
 
-// Frequency labels with mathematically accurate logarithmic positions
-// Formula: pos = ln(freq/10) / ln(20000/10) = ln(freq/10) / ln(2000)
-// The spectrum backend uses the same log spacing for 32 bands from 10Hz-20kHz
-const FREQ_LABELS = [
-  { freq: '10', pos: 0 },
-  { freq: '20', pos: 9.1 },
-  { freq: '50', pos: 21.2 },
-  { freq: '100', pos: 30.3 },
-  { freq: '200', pos: 39.4 },
-  { freq: '500', pos: 51.5 },
-  { freq: '1k', pos: 60.6 },
-  { freq: '2k', pos: 69.7 },
-  { freq: '5k', pos: 81.8 },
-  { freq: '10k', pos: 90.9 },
-  { freq: '20k', pos: 100 },
-];
+// Frequency axis label values (positions are computed from the band range)
+const FREQ_LABEL_VALUES = [10, 20, 50, 100, 200, 500, 1000, 2000, 5000, 10000, 20000];
+
+// Fallback band range used until the backend reports its actual band frequencies
+const DEFAULT_MIN_FREQ = 10;
+const DEFAULT_MAX_FREQ = 20000;
+
+// Frequency labels with logarithmic positions (0-100) across the band range
+// Formula: pos = ln(freq/minFreq) / ln(maxFreq/minFreq)
+function getFreqLabels(minFreq: number, maxFreq: number): { freq: string; pos: number }[] {
+  const logRange = Math.log(maxFreq / minFreq);
+  return FREQ_LABEL_VALUES
+    .filter(f => f >= minFreq - 0.5 && f <= maxFreq + 0.5)
+    .map(f => ({
+      freq: f >= 1000 ? `${f / 1000}k` : `${f}`,
+      pos: Math.max(0, Math.min(100, (Math.log(f / minFreq) / logRange) * 100)),
+    }));
+}
 
 export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
   isOpen,
@@ -93,6 +96,9 @@ export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
   const [range, setRange] = useState<RangeOption>(60);
   const [showRangeMenu, setShowRangeMenu] = useState(false);
 
+  // Band center frequencies reported by the backend (null until fetched)
+  const [bandFrequencies, setBandFrequencies] = useState<number[] | null>(null);
+
   // Animation state
   const [spectrumState, setSpectrumState] = useState({
     spectrum: new Array(32).fill(0) as number[],
@@ -118,6 +124,25 @@ export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
     }
   }, [showPeaks]);
 
+  // Fetch the analyzer's band layout when the spectrum is turned on
+  // (depends on the engine sample rate, so refetch each time)
+  useEffect(() => {
+    if (!isOpen || !showSpectrum) return;
+    let cancelled = false;
+    previewGetSpectrumBands()
+      .then((bands) => {
+        if (!cancelled && bands.frequencies.length >= 2) {
+          setBandFrequencies(bands.frequencies);
+        }
+      })
+      .catch(() => {
+        // Engine not running yet - keep the default 10Hz-20kHz layout
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [isOpen, showSpectrum]);
+
   // Animation loop - runs at 60fps when visible and spectrum is on
   useEffect(() => {
     if (!isOpen || !isVisible || !showSpectrum) return;
@@ -216,6 +241,9 @@ export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
 
   const currentTiltOption = TILT_OPTIONS.find(o => o.value === tilt) || TILT_OPTIONS[2];
   const currentRangeConfig = RANGE_OPTIONS.find(o => o.value === range) || RANGE_OPTIONS[3];
+  const freqLabels = bandFrequencies
+    ? getFreqLabels(bandFrequencies[0], bandFrequencies[bandFrequencies.length - 1])
+    : getFreqLabels(DEFAULT_MIN_FREQ, DEFAULT_MAX_FREQ);
 
   return (
     <div className="mt-3">
@@ -393,7 +421,7 @@ export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
             })}
 
             {/* Vertical grid lines at frequency positions */}
-            {FREQ_LABELS.filter(f => f.pos > 0 && f.pos < 100).map(({ freq, pos }) => {
+            {freqLabels.filter(f => f.pos > 0 && f.pos < 100).map(({ freq, pos }) => {
               const x = LEFT_MARGIN + (pos / 100) * SPECTRUM_WIDTH;
               return (
                 <line
@@ -412,13 +440,13 @@ export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
             {/* Spectrum curves clipped to chart area */}
             <g clipPath="url(#spectrumClip)">
               {/* Input spectrum curve (pre-FX) - rendered behind output */}
-              {showPrePost && renderSpectrumCurve(spectrumState.spectrumInput, 'input', tilt, currentRangeConfig)}
+              {showPrePost && renderSpectrumCurve(spectrumState.spectrumInput, 'input', tilt, currentRangeConfig, bandFrequencies)}
 
               {/* Output spectrum curve (post-FX) - rendered on top */}
-              {renderSpectrumCurve(spectrumState.spectrum, 'output', tilt, currentRangeConfig)}
+              {renderSpectrumCurve(spectrumState.spectrum, 'output', tilt, currentRangeConfig, bandFrequencies)}
 
               {/* Peak hold markers */}
-              {showPeaks && renderPeakMarkers(spectrumState.peakSpectrum, tilt, currentRangeConfig)}
+              {showPeaks && renderPeakMarkers(spectrumState.peakSpectrum, tilt, currentRangeConfig, bandFrequencies)}
             </g>
 
             {/* Right border of spectrum area */}
@@ -461,7 +489,7 @@ export const SpectrumAnalyzer = memo(function SpectrumAnalyzer({
             })}
 
             {/* Frequency labels at bottom - positioned to align with grid lines */}
-            {FREQ_LABELS.map(({ freq, pos }) => {
+            {freqLabels.map(({ freq, pos }) => {
               const x = LEFT_MARGIN + (pos / 100) * SPECTRUM_WIDTH;
               const textAnchor = pos === 0 ? 'start' : pos === 100 ? 'end' : 'middle';
               const xOffset = pos === 0 ? 2 : pos === 100 ? -2 : 0;
@@ -508,11 +536,15 @@ function dbToY(db: number, rangeConfig: RangeConfig): number {
 
 type SpectrumType = 'input' | 'output';
 
-// Calculate frequency for a given band index (logarithmically spaced from 10Hz to 20kHz)
-// This matches the backend's band_frequencies calculation
-function getBandFrequency(bandIndex: number, numBands: number): number {
-  const minFreq = 10;
-  const maxFreq = 20000;
+// Calculate frequency for a given band index
+// Uses the backend's band frequencies when available, otherwise assumes
+// log spacing from 10Hz to 20kHz
+function getBandFrequency(bandIndex: number, numBands: number, bandFrequencies: number[] | null): number {
+  if (bandFrequencies && bandFrequencies.length === numBands) {
+    return bandFrequencies[bandIndex];
+  }
+  const minFreq = DEFAULT_MIN_FREQ;
+  const maxFreq = DEFAULT_MAX_FREQ;
   const logMin = Math.log(minFreq);
   const logMax = Math.log(maxFreq);
   const t = bandIndex / (numBands - 1);
@@ -531,7 +563,7 @@ function getTiltCompensation(freq: number, slopeDbPerOctave: number): number {
 // Renders spectrum curve within the spectrum area
 // The curve uses the same coordinate system as the grid (LEFT_MARGIN offset, SPECTRUM_WIDTH, SPECTRUM_HEIGHT)
 // Accounts for TOP_MARGIN offset
-function renderSpectrumCurve(animatedSpectrum: number[], type: SpectrumType, tilt: TiltOption, rangeConfig: RangeConfig) {
+function renderSpectrumCurve(animatedSpectrum: number[], type: SpectrumType, tilt: TiltOption, rangeConfig: RangeConfig, bandFrequencies: number[] | null) {
   // Safety check for empty or too-small spectrum
   if (!animatedSpectrum || animatedSpectrum.length < 2) {
     return null;
@@ -551,7 +583,7 @@ function renderSpectrumCurve(animatedSpectrum: number[], type: SpectrumType, til
     // Apply tilt compensation based on frequency
     // This tilts the spectrum around 1kHz by the specified dB/octave
     // Higher frequencies get boosted, lower frequencies get cut
-    const freq = getBandFrequency(i, numBands);
+    const freq = getBandFrequency(i, numBands, bandFrequencies);
     db += getTiltCompensation(freq, tilt);
 
     // Normalize dB to 0-1 range for Y positioning
@@ -610,7 +642,7 @@ function renderSpectrumCurve(animatedSpectrum: number[], type: SpectrumType, til
 }
 
 // Renders peak hold markers as small horizontal lines at each band's peak
-function renderPeakMarkers(peakSpectrum: number[], tilt: TiltOption, rangeConfig: RangeConfig) {
+function renderPeakMarkers(peakSpectrum: number[], tilt: TiltOption, rangeConfig: RangeConfig, bandFrequencies: number[] | null) {
   if (!peakSpectrum || peakSpectrum.length < 2) {
     return null;
   }
@@ -627,7 +659,7 @@ function renderPeakMarkers(peakSpectrum: number[], tilt: TiltOption, rangeConfig
     let db = safeMag > 0 ? 20 * Math.log10(safeMag) : rangeConfig.dbMin;
 
     // Apply tilt compensation
-    const freq = getBandFrequency(i, numBands);
+    const freq = getBandFrequency(i, numBands, bandFrequencies);
     db += getTiltCompensation(freq, tilt);
 
     // Skip if below visible range
@@ -21,6 +21,7 @@ const SIGNAL_NAMES: Record<SignalType, string> = {
   sine: 'Sine',
   white_noise: 'White Noise',
   pink_noise: 'Pink Noise',
+  brown_noise: 'Brown Noise',
   impulse: 'Impulse',
   sweep: 'Sweep',
   square: 'Square',
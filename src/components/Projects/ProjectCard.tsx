@@ -1,5 +1,6 @@
 import { useState, useRef, memo, useMemo } from 'react'
 import { createPortal } from 'react-dom'
+import { convertFileSrc } from '@tauri-apps/api/core'
 import type { ProjectMeta } from '../../types'
 import { Modal } from '../Common/Modal'
 import { Spinner } from '../Common/Spinner'
@@ -28,6 +29,7 @@ export const ProjectCard = memo(function ProjectCard({
     const [showDeleteConfirm, setShowDeleteConfirm] = useState(false)
     const [isDeleting, setIsDeleting] = useState(false)
     const [showTooltip, setShowTooltip] = useState(false)
+    const [thumbnailError, setThumbnailError] = useState(false)
     const [tooltipPosition, setTooltipPosition] = useState({ top: 0, left: 0 })
     const cardRef = useRef<HTMLDivElement>(null)
 
@@ -104,6 +106,17 @@ export const ProjectCard = memo(function ProjectCard({
         if (isBusy) {
             return <Spinner size="sm" className={getIconColor()} />
         }
+        // Captured plugin UI, when there is one
+        if (project.thumbnail && !thumbnailError) {
+            return (
+                <img
+                    src={convertFileSrc(project.thumbnail)}
+                    alt=""
+                    className="w-full h-full object-cover rounded-md"
+                    onError={() => setThumbnailError(true)}
+                />
+            )
+        }
         return project.template === 'instrument' ? (
             <svg
                 className={`w-4 h-4 ${getIconColor()}`}
@@ -190,7 +203,7 @@ export const ProjectCard = memo(function ProjectCard({
                 >
                     {/* Icon */}
                     <div
-                        className={`rounded-md flex items-center justify-center flex-shrink-0 transition-all duration-200 ${
+                        className={`rounded-md flex items-center justify-center flex-shrink-0 overflow-hidden transition-all duration-200 ${
                             collapsed ? 'w-full h-full' : 'w-8 h-8'
                         } ${getIconBg()}`}
                     >
@@ -22,12 +22,14 @@ interface DawPublishTarget {
   daw: string;
   vst3_path: string;
   clap_path: string;
+  use_system_paths?: boolean;
 }
 
 interface CopiedFile {
   format: string;
   daw: string;
   path: string;
+  backup_path?: string;
 }
 
 interface PublishResult {
@@ -1,4 +1,5 @@
 import { useSettingsStore } from '../../stores/settingsStore';
+import { DEFAULT_AGENT_TOOLS } from '../../types';
 import type { ClaudeModel, AgentVerbosity } from '../../types';
 
 interface ModelOption {
@@ -19,6 +20,9 @@ const modelOptions: ModelOption[] = [
   { id: 'opus', label: 'Opus (Recommended)', description: 'Senior audio engineer' },
 ];
 
+// Tools shown in the allowlist (defaults plus a few extras the backend accepts)
+const agentToolOptions = [...DEFAULT_AGENT_TOOLS, 'MultiEdit', 'TodoWrite'];
+
 const verbosityOptions: VerbosityOption[] = [
   { id: 'direct', label: 'Direct', description: 'Minimal questions, just implement' },
   { id: 'balanced', label: 'Balanced', description: 'A few key questions, then implement' },
@@ -26,7 +30,24 @@ const verbosityOptions: VerbosityOption[] = [
 ];
 
 export function AISettings() {
-  const { aiSettings, setChatStyle, setModel, setCustomInstructions, setAgentVerbosity } = useSettingsStore();
+  const {
+    aiSettings,
+    setChatStyle,
+    setModel,
+    setCustomInstructions,
+    setAgentVerbosity,
+    setAutoFixBuildErrors,
+    setMaxTurns,
+    setAllowedTools,
+  } = useSettingsStore();
+
+  const toggleTool = (tool: string) => {
+    const allowed = aiSettings.allowedTools.includes(tool)
+      ? aiSettings.allowedTools.filter((t) => t !== tool)
+      : [...aiSettings.allowedTools, tool];
+    // Keep at least one tool - the backend rejects an empty allowlist
+    if (allowed.length > 0) setAllowedTools(allowed);
+  };
 
   return (
     <div className="space-y-5">
@@ -119,6 +140,80 @@ export function AISettings() {
         </div>
       </div>
 
+      {/* Auto-fix build errors - toggle */}
+      <div className="flex items-center justify-between">
+        <div>
+          <label className="block text-sm font-medium text-text-primary">Auto-Fix Build Errors</label>
+          <p className="text-xs text-text-muted">
+            {aiSettings.autoFixBuildErrors
+              ? 'Compile errors are sent to Claude automatically when a build fails'
+              : 'Use "Fix It" to send compile errors to Claude'}
+          </p>
+        </div>
+        <div className="flex rounded-lg border border-border overflow-hidden">
+          {[false, true].map((enabled, index) => (
+            <button
+              key={String(enabled)}
+              onClick={() => setAutoFixBuildErrors(enabled)}
+              className={`px-3 py-1.5 text-sm transition-colors ${
+                index > 0 ? 'border-l border-border' : ''
+              } ${
+                aiSettings.autoFixBuildErrors === enabled
+                  ? 'bg-accent text-white'
+                  : 'bg-bg-primary text-text-secondary hover:text-text-primary'
+              }`}
+            >
+              {enabled ? 'On' : 'Off'}
+            </button>
+          ))}
+        </div>
+      </div>
+
+      {/* Max Turns - number input */}
+      <div className="flex items-center justify-between">
+        <div>
+          <label className="block text-sm font-medium text-text-primary">Max Turns</label>
+          <p className="text-xs text-text-muted">How many steps Claude may take per message (1-500)</p>
+        </div>
+        <input
+          type="number"
+          min={1}
+          max={500}
+          value={aiSettings.maxTurns}
+          onChange={(e) => {
+            const turns = parseInt(e.target.value, 10);
+            if (!isNaN(turns)) setMaxTurns(Math.min(500, Math.max(1, turns)));
+          }}
+          className="w-20 px-2 py-1.5 rounded-lg border border-border bg-bg-primary text-text-primary text-sm text-right focus:outline-none focus:ring-2 focus:ring-accent/50"
+        />
+      </div>
+
+      {/* Allowed Tools - toggle chips */}
+      <div className="space-y-2">
+        <div>
+          <label className="block text-sm font-medium text-text-primary">Allowed Tools</label>
+          <p className="text-xs text-text-muted">Tools Claude may use (e.g. turn off Bash to keep it from running commands)</p>
+        </div>
+        <div className="flex flex-wrap gap-1.5">
+          {agentToolOptions.map((tool) => {
+            const allowed = aiSettings.allowedTools.includes(tool);
+            return (
+              <button
+                key={tool}
+                onClick={() => toggleTool(tool)}
+                className={`px-2.5 py-1 text-xs rounded-md border transition-colors ${
+                  allowed
+                    ? 'bg-accent/15 border-accent/40 text-accent'
+                    : 'bg-bg-primary border-border text-text-muted hover:text-text-primary'
+                }`}
+              >
+                {tool}
+              </button>
+            );
+          })}
+        </div>
+      </div>
+
       {/* Custom Instructions - textarea */}
       <div className="space-y-2">
         <label className="block text-sm font-medium text-text-primary">
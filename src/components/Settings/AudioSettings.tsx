@@ -2,10 +2,13 @@ import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { relaunch } from '@tauri-apps/plugin-process';
 import { useSettingsStore } from '../../stores/settingsStore';
+import { audioSelfTest, previewGetOutputChannels, previewSetOutputChannels } from '../../api/preview';
+import type { AudioSelfTestReport } from '../../api/preview';
 
 interface AudioDevice {
   name: string;
   is_default: boolean;
+  channels: number | null;
 }
 
 const SAMPLE_RATES = [
@@ -28,6 +31,12 @@ export function AudioSettings() {
   const [devices, setDevices] = useState<AudioDevice[]>([]);
   const [loading, setLoading] = useState(false);
   const [systemSampleRate, setSystemSampleRate] = useState<number | null>(null);
+  const [selfTesting, setSelfTesting] = useState(false);
+  const [selfTestReport, setSelfTestReport] = useState<AudioSelfTestReport | null>(null);
+  const [selfTestError, setSelfTestError] = useState<string | null>(null);
+  // Channel count of the device the engine is running on (null = engine not running)
+  const [engineChannels, setEngineChannels] = useState<number | null>(null);
+  const [outputChannelsError, setOutputChannelsError] = useState<string | null>(null);
 
   // Check if settings have changed from what's currently applied
   const hasChanges = appliedAudioSettings !== null && (
@@ -40,6 +49,9 @@ export function AudioSettings() {
   useEffect(() => {
     loadDevices();
     loadSystemSampleRate();
+    previewGetOutputChannels()
+      .then(channels => setEngineChannels(channels.device_channels))
+      .catch(() => setEngineChannels(null));
   }, []);
 
   const loadDevices = async () => {
@@ -63,8 +75,33 @@ export function AudioSettings() {
     }
   };
 
+  // Output routing applies live - no restart needed
+  const handleOutputChannelsChange = async (left: number) => {
+    const pair: [number, number] = [left, left + 1];
+    updateAudioSetting('outputChannels', pair);
+    setOutputChannelsError(null);
+    try {
+      await previewSetOutputChannels(pair[0], pair[1]);
+    } catch (err) {
+      setOutputChannelsError(String(err));
+    }
+  };
+
   const isDev = import.meta.env.DEV;
 
+  const handleSelfTest = async () => {
+    setSelfTesting(true);
+    setSelfTestError(null);
+    try {
+      setSelfTestReport(await audioSelfTest());
+    } catch (err) {
+      setSelfTestReport(null);
+      setSelfTestError(String(err));
+    } finally {
+      setSelfTesting(false);
+    }
+  };
+
   const handleRestart = async () => {
     if (isDev) {
       // In dev mode, relaunch doesn't work well - just show instructions
@@ -105,6 +142,28 @@ export function AudioSettings() {
         </p>
       </div>
 
+      {/* Output Channels (multi-output interfaces only) */}
+      {engineChannels !== null && engineChannels > 2 && (
+        <div className="space-y-2">
+          <label className="block text-sm font-medium text-text-secondary">Output Channels</label>
+          <select
+            value={audioSettings.outputChannels[0]}
+            onChange={(e) => handleOutputChannelsChange(Number(e.target.value))}
+            className="w-full px-3 py-2 bg-bg-primary border border-border rounded-lg text-text-primary text-sm focus:outline-none focus:ring-2 focus:ring-accent/50"
+          >
+            {Array.from({ length: Math.floor(engineChannels / 2) }, (_, i) => (
+              <option key={i} value={i * 2}>
+                Outputs {i * 2 + 1}-{i * 2 + 2}
+              </option>
+            ))}
+          </select>
+          {outputChannelsError && <p className="text-xs text-error">{outputChannelsError}</p>}
+          <p className="text-xs text-text-muted">
+            Which pair of the interface's {engineChannels} outputs the preview plays through.
+          </p>
+        </div>
+      )}
+
       {/* Sample Rate */}
       <div className="space-y-2">
         <label className="block text-sm font-medium text-text-secondary">Sample Rate</label>
@@ -160,6 +219,41 @@ export function AudioSettings() {
         </p>
       </div>
 
+      {/* Output Test */}
+      <div className="space-y-2">
+        <label className="block text-sm font-medium text-text-secondary">Output Test</label>
+        <div className="flex items-center gap-3">
+          <button
+            onClick={handleSelfTest}
+            disabled={selfTesting}
+            className="px-4 py-2 text-sm font-medium text-accent bg-accent/10 hover:bg-accent/20 rounded-lg border border-accent/30 transition-colors disabled:opacity-50"
+          >
+            {selfTesting ? 'Testing...' : 'Test Audio Output'}
+          </button>
+          {selfTestReport && (selfTestReport.status === 'no_device' ? (
+            <span className="text-sm text-warning">No audio device - output is silent</span>
+          ) : (
+            <span className={`text-sm ${selfTestReport.levels_registered ? 'text-green-500' : 'text-error'}`}>
+              {selfTestReport.levels_registered ? 'Output is working' : 'No signal reached the output'}
+            </span>
+          ))}
+        </div>
+        {selfTestReport && (
+          <p className="text-xs text-text-muted">
+            {selfTestReport.device_name ?? 'No device'}
+            {selfTestReport.sample_rate && ` · ${selfTestReport.sample_rate / 1000} kHz`}
+            {selfTestReport.buffer_size && ` · ${selfTestReport.buffer_size} samples`}
+            {` · peak ${Math.max(selfTestReport.peak_left_db, selfTestReport.peak_right_db).toFixed(1)} dB`}
+            {selfTestReport.status !== 'running' && ` · engine ${selfTestReport.status.replace('_', ' ')}`}
+            {selfTestReport.stream_error && ` · stream error: ${selfTestReport.stream_error}`}
+          </p>
+        )}
+        {selfTestError && <p className="text-xs text-error">{selfTestError}</p>}
+        <p className="text-xs text-text-muted">
+          Plays a short 1 kHz tone (you should hear a brief beep) and checks it reaches the output.
+        </p>
+      </div>
+
       {/* Restart Notice */}
       <div className="pt-4 border-t border-border">
         {hasChanges ? (
@@ -1,6 +1,7 @@
 import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { useSettingsStore } from '../../stores/settingsStore';
-import type { DawPaths } from '../../types';
+import type { CheckResult, DawPaths, PluginFormatDirs } from '../../types';
 
 interface DawConfig {
   key: keyof DawPaths;
@@ -18,6 +19,26 @@ const daws: DawConfig[] = [
 export function DawPathsSettings() {
   const { dawPaths, updateDawPath } = useSettingsStore();
   const [expandedDaws, setExpandedDaws] = useState<Set<keyof DawPaths>>(new Set());
+  const [checking, setChecking] = useState(false);
+  const [pluginDirs, setPluginDirs] = useState<PluginFormatDirs[] | null>(null);
+  const [rustTarget, setRustTarget] = useState<CheckResult | null>(null);
+
+  // Confirm plugins can actually be installed before "nothing shows up in my DAW"
+  const handleCheckInstall = async () => {
+    setChecking(true);
+    try {
+      const [dirs, target] = await Promise.all([
+        invoke<PluginFormatDirs[]>('check_plugin_dirs'),
+        invoke<CheckResult>('check_rust_target'),
+      ]);
+      setPluginDirs(dirs);
+      setRustTarget(target);
+    } catch (err) {
+      console.error('Failed to check install folders:', err);
+    } finally {
+      setChecking(false);
+    }
+  };
 
   const toggleDaw = (key: keyof DawPaths) => {
     setExpandedDaws((prev) => {
@@ -113,6 +134,56 @@ export function DawPathsSettings() {
         })}
       </div>
 
+      {/* Install check */}
+      <div className="border border-border rounded-lg p-4 space-y-3">
+        <div className="flex items-center justify-between">
+          <div>
+            <h4 className="font-medium text-text-primary">Install Check</h4>
+            <p className="text-xs text-text-muted">
+              Verify the system plugin folders are writable and Rust can build for this machine.
+            </p>
+          </div>
+          <button
+            onClick={handleCheckInstall}
+            disabled={checking}
+            className="px-3 py-1.5 text-sm font-medium text-accent bg-accent/10 hover:bg-accent/20 rounded-lg border border-accent/30 transition-colors disabled:opacity-50 flex-shrink-0 ml-4"
+          >
+            {checking ? 'Checking...' : 'Run Check'}
+          </button>
+        </div>
+
+        {pluginDirs?.map((formatDirs) => (
+          <div key={formatDirs.format} className="text-sm">
+            <div className="flex items-center gap-2">
+              <span className={formatDirs.usable ? 'text-success' : 'text-error'}>{formatDirs.usable ? '✓' : '✗'}</span>
+              <span className="font-medium text-text-primary">{formatDirs.format.toUpperCase()}</span>
+            </div>
+            <ul className="ml-5 mt-1 space-y-0.5 text-xs text-text-muted">
+              {formatDirs.dirs.map((dir) => (
+                <li key={dir.path}>
+                  <span className="font-mono">{dir.path}</span>
+                  {dir.message ? ` - ${dir.message}` : ' - OK'}
+                </li>
+              ))}
+            </ul>
+            {formatDirs.fix && <p className="ml-5 mt-1 text-xs text-warning font-mono select-text">{formatDirs.fix}</p>}
+          </div>
+        ))}
+
+        {rustTarget && (
+          <div className="text-sm">
+            <div className="flex items-center gap-2">
+              <span className={rustTarget.status === 'installed' ? 'text-success' : 'text-error'}>
+                {rustTarget.status === 'installed' ? '✓' : '✗'}
+              </span>
+              <span className="font-medium text-text-primary">Rust target</span>
+              {rustTarget.version && <span className="text-xs font-mono text-text-muted">{rustTarget.version}</span>}
+            </div>
+            {rustTarget.message && <p className="ml-5 mt-1 text-xs text-text-muted font-mono select-text">{rustTarget.message}</p>}
+          </div>
+        )}
+      </div>
+
       <div className="bg-bg-tertiary rounded-lg p-4">
         <div className="flex items-start gap-3">
           <svg className="w-5 h-5 text-accent flex-shrink-0 mt-0.5" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
@@ -1,9 +1,33 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { sendNotification, isPermissionGranted, requestPermission } from '@tauri-apps/plugin-notification';
 import { useTipsStore } from '../../stores/tipsStore';
 import { useSettingsStore } from '../../stores/settingsStore';
 
+type WarmupEvent =
+  | { type: 'start'; total: number }
+  | { type: 'step'; index: number; package: string }
+  | { type: 'output'; line: string }
+  | { type: 'done'; success: boolean; duration_secs: number; error: string | null };
+
+interface BuildDiskSpace {
+  free_bytes: number;
+  total_bytes: number;
+  status: 'ok' | 'low' | 'critical';
+  min_build_free_bytes: number;
+}
+
+interface LogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+}
+
+/** Most recent entries shown in the viewer */
+const LOG_VIEW_LIMIT = 2000;
+
 function formatBytes(bytes: number): string {
   if (bytes === 0) return '0 B';
   const k = 1024;
@@ -18,6 +42,62 @@ export function DevSettings() {
   const [logPath, setLogPath] = useState<string>('');
   const [logSize, setLogSize] = useState<number>(0);
   const [logContent, setLogContent] = useState<string | null>(null);
+  const [logLevel, setLogLevel] = useState<string>('trace');
+  const [logModule, setLogModule] = useState<string>('');
+  const [cacheStatus, setCacheStatus] = useState<string | null>(null);
+  const [cleaningCache, setCleaningCache] = useState(false);
+  const [warmupProgress, setWarmupProgress] = useState<string | null>(null);
+  const [warmingUp, setWarmingUp] = useState(false);
+  const [cacheSize, setCacheSize] = useState<number | null>(null);
+  const [diskSpace, setDiskSpace] = useState<BuildDiskSpace | null>(null);
+  const warmBuildCacheOnStartup = useSettingsStore((state) => state.warmBuildCacheOnStartup);
+  const setWarmBuildCacheOnStartup = useSettingsStore((state) => state.setWarmBuildCacheOnStartup);
+
+  // Follow warm-up progress, including one started in the background on app launch
+  useEffect(() => {
+    let total = 0;
+    const unlisten = listen<WarmupEvent>('warmup-progress', (event) => {
+      const payload = event.payload;
+      switch (payload.type) {
+        case 'start':
+          total = payload.total;
+          setWarmingUp(true);
+          break;
+        case 'step':
+          setWarmupProgress(`Compiling dependencies (${payload.index}/${total})...`);
+          break;
+        case 'done':
+          setWarmingUp(false);
+          setWarmupProgress(
+            payload.success
+              ? `Build cache is warm (${payload.duration_secs.toFixed(1)}s).`
+              : 'Warm-up failed. Check the log for details.'
+          );
+          break;
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Build cache size and free space, so the user can clean up before builds start failing
+  const loadCacheInfo = async () => {
+    try {
+      const [size, space] = await Promise.all([
+        invoke<number>('get_target_dir_size'),
+        invoke<BuildDiskSpace>('check_build_disk_space'),
+      ]);
+      setCacheSize(size);
+      setDiskSpace(space);
+    } catch (err) {
+      console.error('Failed to load build cache info:', err);
+    }
+  };
+
+  useEffect(() => {
+    loadCacheInfo();
+  }, []);
 
   // Load log file info on mount
   useEffect(() => {
@@ -41,10 +121,16 @@ export function DevSettings() {
     window.location.reload();
   };
 
-  const handleViewLog = async () => {
+  const handleViewLog = async (level = logLevel, module = logModule) => {
     try {
-      const content = await invoke<string>('read_log_file');
-      setLogContent(content);
+      const entries = await invoke<LogEntry[]>('read_log_file', {
+        filterLevel: level,
+        moduleFilter: module || null,
+        limit: LOG_VIEW_LIMIT,
+      });
+      setLogContent(
+        entries.map((e) => `[${e.timestamp}] [${e.level}] [${e.target}] ${e.message}`).join('\n')
+      );
     } catch (err) {
       setLogContent(`Error reading log: ${err}`);
     }
@@ -62,8 +148,39 @@ export function DevSettings() {
   };
 
   const handleCopyLog = async () => {
-    if (logContent) {
-      await navigator.clipboard.writeText(logContent);
+    try {
+      // Full plain-text export (not just the entries shown in the viewer)
+      const text = await invoke<string>('export_log_text', {
+        filterLevel: logLevel,
+        moduleFilter: logModule || null,
+      });
+      await navigator.clipboard.writeText(text);
+    } catch (err) {
+      console.error('Failed to export log:', err);
+    }
+  };
+
+  const handleCleanBuildCache = async () => {
+    setCleaningCache(true);
+    try {
+      const freed = await invoke<number>('clean_build_cache');
+      setCacheStatus(`Freed ${formatBytes(freed)}. The next build will recompile all dependencies.`);
+    } catch (err) {
+      setCacheStatus(String(err));
+    } finally {
+      setCleaningCache(false);
+      loadCacheInfo();
+    }
+  };
+
+  const handleWarmupBuildCache = async () => {
+    setWarmingUp(true);
+    try {
+      await invoke<number>('warmup_build_cache');
+    } catch (err) {
+      setWarmupProgress(String(err));
+    } finally {
+      setWarmingUp(false);
     }
   };
 
@@ -97,7 +214,7 @@ export function DevSettings() {
 
           <div className="flex gap-2 pt-2">
             <button
-              onClick={handleViewLog}
+              onClick={() => handleViewLog()}
               className="px-3 py-1.5 text-sm font-medium text-accent bg-accent/10 hover:bg-accent/20 rounded-lg border border-accent/30 transition-colors"
             >
               View Log
@@ -133,7 +250,31 @@ export function DevSettings() {
         {logContent !== null && (
           <div className="mt-4">
             <div className="flex items-center justify-between mb-2">
-              <span className="text-sm font-medium text-text-secondary">Log Contents</span>
+              <div className="flex items-center gap-2">
+                <span className="text-sm font-medium text-text-secondary">Log Contents</span>
+                <select
+                  value={logLevel}
+                  onChange={(e) => {
+                    setLogLevel(e.target.value);
+                    handleViewLog(e.target.value, logModule);
+                  }}
+                  className="px-1.5 py-0.5 text-xs bg-bg-tertiary border border-border rounded text-text-secondary"
+                >
+                  <option value="trace">All</option>
+                  <option value="info">Info+</option>
+                  <option value="warn">Warnings+</option>
+                  <option value="error">Errors</option>
+                </select>
+                <input
+                  value={logModule}
+                  onChange={(e) => setLogModule(e.target.value)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter') handleViewLog();
+                  }}
+                  placeholder="Module (e.g. freqlab::audio::plugin)"
+                  className="px-1.5 py-0.5 text-xs bg-bg-tertiary border border-border rounded text-text-secondary font-mono w-56"
+                />
+              </div>
               <div className="flex gap-2">
                 <button
                   onClick={handleCopyLog}
@@ -156,6 +297,72 @@ export function DevSettings() {
         )}
       </div>
 
+      {/* Build Cache Section */}
+      <div className="border border-border rounded-lg p-4">
+        <h4 className="font-medium text-text-primary mb-2">Build Cache</h4>
+        <p className="text-sm text-text-muted mb-4">
+          Compiled dependencies are shared by all projects so rebuilds are fast. Clean the cache if
+          builds behave strangely and you want a completely fresh build.
+        </p>
+        {(cacheSize !== null || diskSpace) && (
+          <div className="flex items-center justify-between text-sm mb-4">
+            <span className="text-text-muted">
+              {cacheSize !== null && `Cache size: ${formatBytes(cacheSize)}`}
+            </span>
+            {diskSpace && (
+              <span className={diskSpace.status === 'ok' ? 'text-text-muted' : 'text-error'}>
+                {formatBytes(diskSpace.free_bytes)} free
+              </span>
+            )}
+          </div>
+        )}
+        {diskSpace && diskSpace.status !== 'ok' && (
+          <p className="text-xs text-error mb-4">
+            {diskSpace.status === 'critical'
+              ? `Builds are blocked until at least ${formatBytes(diskSpace.min_build_free_bytes)} is free. Clean the build cache or free up disk space.`
+              : 'Disk space is low and builds may fail. Consider cleaning the build cache.'}
+          </p>
+        )}
+        <label className="flex items-center justify-between cursor-pointer p-3 mb-4 rounded-lg bg-bg-tertiary hover:bg-bg-elevated transition-colors">
+          <div>
+            <span className="text-sm font-medium text-text-primary">Warm up on startup</span>
+            <p className="text-xs text-text-muted">
+              Precompile nih-plug and the UI libraries in the background when freqlab starts. The first
+              warm-up takes a few minutes of CPU time, but new projects then build in seconds instead of
+              minutes. Later warm-ups finish almost instantly.
+            </p>
+          </div>
+          <div className="relative flex-shrink-0 ml-4">
+            <input
+              type="checkbox"
+              checked={warmBuildCacheOnStartup}
+              onChange={(e) => setWarmBuildCacheOnStartup(e.target.checked)}
+              className="sr-only peer"
+            />
+            <div className="w-9 h-5 bg-bg-primary border border-border rounded-full peer-checked:bg-accent peer-checked:border-accent transition-colors" />
+            <div className="absolute left-0.5 top-0.5 w-4 h-4 bg-text-muted rounded-full peer-checked:translate-x-4 peer-checked:bg-white transition-all" />
+          </div>
+        </label>
+        <div className="flex gap-2">
+          <button
+            onClick={handleWarmupBuildCache}
+            disabled={warmingUp || cleaningCache}
+            className="px-4 py-2 text-sm font-medium text-accent bg-accent/10 hover:bg-accent/20 rounded-lg border border-accent/30 transition-colors disabled:opacity-50"
+          >
+            {warmingUp ? 'Warming Up...' : 'Warm Up Now'}
+          </button>
+          <button
+            onClick={handleCleanBuildCache}
+            disabled={cleaningCache || warmingUp}
+            className="px-4 py-2 text-sm font-medium text-accent bg-accent/10 hover:bg-accent/20 rounded-lg border border-accent/30 transition-colors disabled:opacity-50"
+          >
+            {cleaningCache ? 'Cleaning...' : 'Clean Build Cache'}
+          </button>
+        </div>
+        {warmupProgress && <p className="text-xs text-text-muted mt-2">{warmupProgress}</p>}
+        {cacheStatus && <p className="text-xs text-text-muted mt-2">{cacheStatus}</p>}
+      </div>
+
       {/* Reset Tips Section */}
       <div className="border border-border rounded-lg p-4">
         <h4 className="font-medium text-text-primary mb-2">Reset Tips</h4>
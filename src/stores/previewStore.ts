@@ -3,7 +3,7 @@ import type { PluginState } from '../api/preview';
 import type { PluginPerformance } from '../types';
 
 export type InputSourceType = 'sample' | 'signal' | 'custom' | 'live';
-export type SignalType = 'sine' | 'white_noise' | 'pink_noise' | 'impulse' | 'sweep' | 'square';
+export type SignalType = 'sine' | 'white_noise' | 'pink_noise' | 'brown_noise' | 'impulse' | 'sweep' | 'square';
 export type GatePattern = 'continuous' | 'pulse' | 'quarter' | 'eighth' | 'sixteenth';
 export type { PluginState } from '../api/preview';
 
@@ -30,6 +30,7 @@ export interface InputSource {
 export interface AudioDevice {
   name: string;
   is_default: boolean;
+  channels?: number | null;
 }
 
 export interface PluginParameter {
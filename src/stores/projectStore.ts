@@ -1,9 +1,25 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { invoke } from '@tauri-apps/api/core';
-import type { ProjectMeta, CreateProjectInput } from '../types';
+import type { ProjectMeta, CreateProjectInput, TrashedProject, ProjectDiagnosis } from '../types';
 import { useDraftStore } from './draftStore';
 
+interface CompilerDiagnostic {
+  level: string;
+  message: string;
+  code: string | null;
+  file: string | null;
+  line: number | null;
+  column: number | null;
+  rendered: string | null;
+}
+
+interface ValidationResult {
+  success: boolean;
+  diagnostics: CompilerDiagnostic[];
+  error: string | null;
+}
+
 interface ProjectState {
   projects: ProjectMeta[];
   activeProject: ProjectMeta | null;
@@ -18,7 +34,12 @@ interface ProjectState {
   createProject: (input: CreateProjectInput) => Promise<ProjectMeta>;
   selectProject: (project: ProjectMeta | null) => void;
   deleteProject: (folderName: string, projectPath: string) => Promise<void>;
+  listTrashedProjects: () => Promise<TrashedProject[]>;
+  restoreProject: (id: string) => Promise<ProjectMeta>;
+  emptyTrash: () => Promise<number>;
+  diagnoseProject: (projectPath: string, autoFix?: boolean) => Promise<ProjectDiagnosis>;
   updateProject: (projectPath: string, name: string, description: string) => Promise<void>;
+  captureThumbnail: (projectPath: string) => Promise<void>;
   setAutoBuild: (projectPath: string, enabled: boolean) => void;
   isAutoBuildEnabled: (projectPath: string) => boolean;
 }
@@ -51,6 +72,15 @@ export const useProjectStore = create<ProjectState>()(
             activeProject: project,
             loading: false,
           }));
+          // Check the generated code compiles so an outdated template is reported now,
+          // not on the first build (runs in the background - the first check can be slow)
+          invoke<ValidationResult>('validate_project', { projectPath: project.path })
+            .then((result) => {
+              if (!result.success && result.error) {
+                set({ error: result.error });
+              }
+            })
+            .catch((err) => console.error('Project validation failed:', err));
           return project;
         } catch (err) {
           set({ error: String(err), loading: false });
@@ -65,7 +95,7 @@ export const useProjectStore = create<ProjectState>()(
       deleteProject: async (folderName: string, projectPath: string) => {
         set({ loading: true, error: null });
         try {
-          // Backend uses folder name to find the project directory
+          // Backend uses folder name to find the project directory (it's moved to the trash)
           await invoke('delete_project', { name: folderName });
           // Filter local state by path (more reliable than name since name can be edited)
           set((state) => ({
@@ -83,6 +113,26 @@ export const useProjectStore = create<ProjectState>()(
         }
       },
 
+      listTrashedProjects: async () => {
+        return await invoke<TrashedProject[]>('list_trashed_projects');
+      },
+
+      restoreProject: async (id: string) => {
+        const project = await invoke<ProjectMeta>('restore_project', { id });
+        set((state) => ({
+          projects: [project, ...state.projects.filter((p) => p.path !== project.path)],
+        }));
+        return project;
+      },
+
+      emptyTrash: async () => {
+        return await invoke<number>('empty_trash');
+      },
+
+      diagnoseProject: async (projectPath: string, autoFix = false) => {
+        return await invoke<ProjectDiagnosis>('diagnose_project', { projectPath, autoFix });
+      },
+
       updateProject: async (projectPath: string, name: string, description: string) => {
         try {
           const updated = await invoke<ProjectMeta>('update_project', {
@@ -101,6 +151,17 @@ export const useProjectStore = create<ProjectState>()(
         }
       },
 
+      captureThumbnail: async (projectPath: string) => {
+        // Captures the loaded plugin's editor (opened off-screen if needed)
+        const updated = await invoke<ProjectMeta>('plugin_capture_thumbnail', { projectPath });
+        set((state) => ({
+          projects: state.projects.map((p) =>
+            p.path === projectPath ? updated : p
+          ),
+          activeProject: state.activeProject?.path === projectPath ? updated : state.activeProject,
+        }));
+      },
+
       setAutoBuild: (projectPath: string, enabled: boolean) => {
         set((state) => ({
           autoBuildPaths: enabled
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
+import { DEFAULT_AGENT_TOOLS } from '../types';
 import type { AppConfig, DawPaths, CustomThemeColors, AudioSettings, AISettings, ChatStyle, ClaudeModel, AgentVerbosity } from '../types';
 
 const defaultDawPaths: DawPaths = {
@@ -24,6 +25,7 @@ const defaultAudioSettings: AudioSettings = {
   outputDevice: null,  // Use system default
   sampleRate: 48000,   // 48kHz - industry standard
   bufferSize: 512,
+  outputChannels: [0, 1],  // First stereo pair
 };
 
 const defaultAISettings: AISettings = {
@@ -31,6 +33,9 @@ const defaultAISettings: AISettings = {
   model: 'opus',
   customInstructions: '',
   agentVerbosity: 'balanced',
+  autoFixBuildErrors: false,
+  maxTurns: 50,
+  allowedTools: DEFAULT_AGENT_TOOLS,
 };
 
 interface SettingsState extends AppConfig {
@@ -49,6 +54,9 @@ interface SettingsState extends AppConfig {
   setModel: (model: ClaudeModel) => void;
   setCustomInstructions: (instructions: string) => void;
   setAgentVerbosity: (verbosity: AgentVerbosity) => void;
+  setAutoFixBuildErrors: (enabled: boolean) => void;
+  setMaxTurns: (turns: number) => void;
+  setAllowedTools: (tools: string[]) => void;
   // License versioning (tracks which license version user has accepted)
   acceptedLicenseVersion: number;
   setAcceptedLicenseVersion: (version: number) => void;
@@ -64,6 +72,9 @@ interface SettingsState extends AppConfig {
   setDawPaths: (paths: DawPaths) => void;
   updateDawPath: (daw: keyof DawPaths, format: 'vst3' | 'clap', path: string) => void;
   setShowNotifications: (show: boolean) => void;
+  // Precompile shared plugin dependencies in the background on startup
+  warmBuildCacheOnStartup: boolean;
+  setWarmBuildCacheOnStartup: (enabled: boolean) => void;
 }
 
 export const useSettingsStore = create<SettingsState>()(
@@ -90,6 +101,7 @@ export const useSettingsStore = create<SettingsState>()(
       aiSettings: defaultAISettings,
       // License versioning (0 = never accepted, 1 = GPL-3.0, 2 = PolyForm Shield)
       acceptedLicenseVersion: 0,
+      warmBuildCacheOnStartup: true,
 
       // Audio settings setters
       setAudioSettings: (settings) => set({ audioSettings: settings }),
@@ -123,6 +135,18 @@ export const useSettingsStore = create<SettingsState>()(
         set((state) => ({
           aiSettings: { ...state.aiSettings, agentVerbosity: verbosity },
         })),
+      setAutoFixBuildErrors: (enabled) =>
+        set((state) => ({
+          aiSettings: { ...state.aiSettings, autoFixBuildErrors: enabled },
+        })),
+      setMaxTurns: (turns) =>
+        set((state) => ({
+          aiSettings: { ...state.aiSettings, maxTurns: turns },
+        })),
+      setAllowedTools: (tools) =>
+        set((state) => ({
+          aiSettings: { ...state.aiSettings, allowedTools: tools },
+        })),
 
       // License versioning setter
       setAcceptedLicenseVersion: (version) => set({ acceptedLicenseVersion: version }),
@@ -153,6 +177,7 @@ export const useSettingsStore = create<SettingsState>()(
           },
         })),
       setShowNotifications: (show) => set({ showNotifications: show }),
+      setWarmBuildCacheOnStartup: (enabled) => set({ warmBuildCacheOnStartup: enabled }),
     }),
     {
       name: 'freqlab-settings',
@@ -168,7 +193,7 @@ export const useSettingsStore = create<SettingsState>()(
             typeof persisted.acceptedLicenseVersion === 'number' && !isNaN(persisted.acceptedLicenseVersion)
               ? persisted.acceptedLicenseVersion
               : 0,
-          // Deep merge aiSettings to pick up new fields (model, customInstructions)
+          // Deep merge aiSettings to pick up new fields (model, customInstructions, maxTurns, allowedTools)
           aiSettings: {
             ...currentState.aiSettings,
             ...(persisted.aiSettings || {}),
@@ -13,6 +13,21 @@ export interface PrerequisiteStatus {
   claude_auth: CheckResult;
 }
 
+export interface PluginDirStatus {
+  path: string;
+  exists: boolean;
+  // For a missing folder: whether it can be created
+  writable: boolean;
+  message: string | null;
+}
+
+export interface PluginFormatDirs {
+  format: 'clap' | 'vst3';
+  usable: boolean;
+  dirs: PluginDirStatus[];
+  fix: string | null;
+}
+
 export interface DiskSpaceBreakdown {
   xcode_gb: number;
   rust_gb: number;
@@ -59,6 +74,7 @@ export interface AudioSettings {
   outputDevice: string | null;  // null = system default
   sampleRate: number;
   bufferSize: number;
+  outputChannels: [number, number];  // [left, right] device channel indices (0-based)
 }
 
 // Plugin performance metrics (only present when monitoring is enabled)
@@ -83,8 +99,19 @@ export interface AISettings {
   model: ClaudeModel;
   customInstructions: string;
   agentVerbosity: AgentVerbosity;
+  // Send compile errors to Claude automatically when a build fails
+  autoFixBuildErrors: boolean;
+  // Agent turn limit per message
+  maxTurns: number;
+  // Tools the agent may use (validated by the backend)
+  allowedTools: string[];
 }
 
+// Tools the agent may use by default
+export const DEFAULT_AGENT_TOOLS = [
+  'Edit', 'Write', 'Read', 'Bash', 'Grep', 'Glob', 'WebSearch', 'WebFetch', 'Skill',
+];
+
 export interface AppConfig {
   workspacePath: string;
   outputPath: string;
@@ -112,6 +139,43 @@ export interface ProjectMeta {
   created_at: string;
   updated_at: string;
   path: string;
+  thumbnail?: string | null;  // Path to a PNG capture of the plugin UI
+}
+
+/** A problem found by the project doctor (diagnose_project) */
+export interface ProjectIssue {
+  kind:
+    | 'metadata_missing'
+    | 'metadata_invalid'
+    | 'lib_rs_missing'
+    | 'ui_html_missing'
+    | 'cargo_toml_missing'
+    | 'workspace_manifest_outdated'
+    | 'not_workspace_member'
+    | 'git_missing'
+    | 'vstworkshop_not_ignored'
+    | 'nih_plug_rev_mismatch';
+  severity: 'error' | 'warning';
+  message: string;
+  suggestion: string;
+  auto_fixable: boolean;
+  fixed: boolean;  // Repaired by this run (autoFix)
+  fix_error: string | null;
+}
+
+export interface ProjectDiagnosis {
+  project_path: string;
+  issues: ProjectIssue[];
+  healthy: boolean;  // No issues left unfixed
+}
+
+/** A deleted project in the workspace trash (restorable until the trash is emptied) */
+export interface TrashedProject {
+  id: string;
+  name: string;  // Folder name it's restored to
+  displayName: string;
+  trashedAt: string;
+  hasOutput: boolean;  // Its output folder was trashed with it
 }
 
 export type PluginTemplate = 'effect' | 'instrument';
@@ -164,6 +228,7 @@ export interface ChatMessage {
   version?: number;  // Version number for commits (1, 2, 3...) - only set if files were changed
   reverted: boolean;
   attachments?: FileAttachment[];  // Files attached to this message
+  interrupted?: boolean;  // Recovered from the stream log after the app exited mid-turn
 }
 
 export interface ChatState {